@@ -0,0 +1,290 @@
+//! Types and utilities for entries.
+
+use std::{
+	collections::BTreeMap,
+	fmt::{Display, Write},
+};
+
+use crate::names::{write_names, Name};
+
+/// English names of the months, which the predefined `jan`–`dec` macros expand to.
+pub const MONTHS: [&str; 12] = [
+	"January",
+	"February",
+	"March",
+	"April",
+	"May",
+	"June",
+	"July",
+	"August",
+	"September",
+	"October",
+	"November",
+	"December",
+];
+
+/// A BibTeX entry.
+///
+/// The fields most tools need are typed; all others are in [fields](Self::fields).
+/// All values are TeX, as written in the file.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Entry {
+	/// The type of the entry, e.g. `article`.
+	pub entry_type: EntryType,
+
+	/// The citation key of the entry.
+	pub key: String,
+
+	/// Authors of the work.
+	pub author: Vec<Name>,
+
+	/// Editors of the work.
+	pub editor: Vec<Name>,
+
+	/// Title of the work.
+	pub title: Option<String>,
+
+	/// Year of publication.
+	///
+	/// This is usually a number, but BibTeX allows any text.
+	pub year: Option<String>,
+
+	/// Month of publication.
+	///
+	/// Months written with the `jan`–`dec` macros are the month's English name.
+	pub month: Option<String>,
+
+	/// DOI of the work, without a resolver.
+	pub doi: Option<String>,
+
+	/// URL of the work.
+	pub url: Option<String>,
+
+	/// Fields not defined above, by lowercase name.
+	pub fields: BTreeMap<String, String>,
+}
+
+impl Entry {
+	/// Get any field of the entry by its lowercase name.
+	///
+	/// Typed fields are included, except for names.
+	pub fn get(&self, field: &str) -> Option<&str> {
+		match field {
+			"title" => self.title.as_deref(),
+			"year" => self.year.as_deref(),
+			"month" => self.month.as_deref(),
+			"doi" => self.doi.as_deref(),
+			"url" => self.url.as_deref(),
+			_ => self.fields.get(field).map(String::as_str),
+		}
+		.filter(|value| !value.is_empty())
+	}
+
+	/// Set any field of the entry by its lowercase name.
+	///
+	/// Names in `author` and `editor` are parsed.
+	pub fn set(&mut self, field: &str, value: impl Into<String>) {
+		let value = value.into();
+		match field {
+			"author" => self.author = Name::parse_list(&value),
+			"editor" => self.editor = Name::parse_list(&value),
+			"title" => self.title = Some(value),
+			"year" => self.year = Some(value),
+			"month" => self.month = Some(value),
+			"doi" => self.doi = Some(value),
+			"url" => self.url = Some(value),
+			_ => {
+				self.fields.insert(field.into(), value);
+			}
+		}
+	}
+
+	/// The year as a number, if it is one.
+	pub fn year_number(&self) -> Option<i64> {
+		self.year.as_deref()?.trim().parse().ok()
+	}
+
+	/// The month as a number from 1 to 12.
+	///
+	/// This understands numbers, and English names and abbreviations.
+	pub fn month_number(&self) -> Option<u8> {
+		let month = self.month.as_deref()?.trim().trim_end_matches('.');
+		if let Ok(n) = month.parse::<u8>() {
+			return (1..=12).contains(&n).then(|| n);
+		}
+		if month.len() < 3 {
+			return None;
+		}
+
+		MONTHS
+			.iter()
+			.position(|name| {
+				name.len() >= month.len() && name[..month.len()].eq_ignore_ascii_case(month)
+			})
+			.map(|n| n as u8 + 1)
+	}
+}
+
+impl Display for Entry {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "@{}{{{},", self.entry_type, self.key)?;
+
+		let field = |f: &mut std::fmt::Formatter<'_>, name: &str, value: &str| {
+			writeln!(f, "  {name} = {{{value}}},")
+		};
+		if !self.author.is_empty() {
+			field(f, "author", &write_names(&self.author))?;
+		}
+		if !self.editor.is_empty() {
+			field(f, "editor", &write_names(&self.editor))?;
+		}
+		if let Some(title) = &self.title {
+			field(f, "title", title)?;
+		}
+		for (name, value) in &self.fields {
+			field(f, name, value)?;
+		}
+		if let Some(year) = &self.year {
+			field(f, "year", year)?;
+		}
+		match (&self.month, self.month_number()) {
+			(Some(month), Some(n)) if month == MONTHS[n as usize - 1] => {
+				let mut abbr = MONTHS[n as usize - 1][..3].to_string();
+				abbr.make_ascii_lowercase();
+				writeln!(f, "  month = {abbr},")?;
+			}
+			(Some(month), _) => field(f, "month", month)?,
+			(None, _) => {}
+		}
+		if let Some(doi) = &self.doi {
+			field(f, "doi", doi)?;
+		}
+		if let Some(url) = &self.url {
+			field(f, "url", url)?;
+		}
+
+		f.write_char('}')?;
+		writeln!(f)
+	}
+}
+
+/// The type of an entry.
+///
+/// This has the types of BibTeX's standard styles, and the most common of the
+/// types added by biblatex. Others are kept as they were written, lowercased.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum EntryType {
+	/// An article from a journal or magazine.
+	Article,
+
+	/// A book with an explicit publisher.
+	Book,
+
+	/// A printed and bound work without a named publisher.
+	Booklet,
+
+	/// A part of a book, e.g. a chapter or range of pages.
+	InBook,
+
+	/// A part of a book with its own title.
+	InCollection,
+
+	/// An article in the proceedings of a conference.
+	///
+	/// `@conference` is read as this.
+	InProceedings,
+
+	/// Technical documentation.
+	Manual,
+
+	/// A Master's thesis.
+	MastersThesis,
+
+	/// Anything that doesn't fit the other types.
+	Misc,
+
+	/// A PhD thesis.
+	PhdThesis,
+
+	/// The proceedings of a conference.
+	Proceedings,
+
+	/// A report published by an institution.
+	TechReport,
+
+	/// A document with an author and title, but not formally published.
+	Unpublished,
+
+	/// An online resource (biblatex).
+	Online,
+
+	/// Software (biblatex).
+	Software,
+
+	/// A data set (biblatex).
+	Dataset,
+
+	/// Any other type.
+	Other(String),
+}
+
+impl Default for EntryType {
+	fn default() -> Self {
+		Self::Misc
+	}
+}
+
+impl EntryType {
+	/// The type as written in BibTeX, lowercase.
+	pub fn as_str(&self) -> &str {
+		match self {
+			Self::Article => "article",
+			Self::Book => "book",
+			Self::Booklet => "booklet",
+			Self::InBook => "inbook",
+			Self::InCollection => "incollection",
+			Self::InProceedings => "inproceedings",
+			Self::Manual => "manual",
+			Self::MastersThesis => "mastersthesis",
+			Self::Misc => "misc",
+			Self::PhdThesis => "phdthesis",
+			Self::Proceedings => "proceedings",
+			Self::TechReport => "techreport",
+			Self::Unpublished => "unpublished",
+			Self::Online => "online",
+			Self::Software => "software",
+			Self::Dataset => "dataset",
+			Self::Other(other) => other,
+		}
+	}
+}
+
+impl From<&str> for EntryType {
+	fn from(s: &str) -> Self {
+		match s.to_ascii_lowercase().as_str() {
+			"article" => Self::Article,
+			"book" => Self::Book,
+			"booklet" => Self::Booklet,
+			"inbook" => Self::InBook,
+			"incollection" => Self::InCollection,
+			"inproceedings" | "conference" => Self::InProceedings,
+			"manual" => Self::Manual,
+			"mastersthesis" => Self::MastersThesis,
+			"misc" => Self::Misc,
+			"phdthesis" => Self::PhdThesis,
+			"proceedings" => Self::Proceedings,
+			"techreport" => Self::TechReport,
+			"unpublished" => Self::Unpublished,
+			"online" | "electronic" | "www" => Self::Online,
+			"software" => Self::Software,
+			"dataset" => Self::Dataset,
+			other => Self::Other(other.into()),
+		}
+	}
+}
+
+impl Display for EntryType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
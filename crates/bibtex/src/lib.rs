@@ -0,0 +1,65 @@
+//! BibTeX types, parsing, and writing.
+//!
+//! This reads and writes `.bib` files as used by BibTeX and biblatex, keeping
+//! every field of every entry: fields which aren't typed on [Entry] are kept
+//! as they were written, so files survive being read and written back.
+//!
+//! `@string` macros are expanded when parsing, including the predefined month
+//! macros like `jan`. `@comment` and `@preamble` blocks, and any text outside
+//! of entries, are skipped.
+//!
+//! Values are kept as TeX, as they were written. Use the [tex] module to
+//! convert them to and from plain text.
+//!
+//! ```
+//! let entries = citeworks_bibtex::from_str(r#"
+//! @article{druskat2017,
+//!   author = {Druskat, Stephan and {The CFF team}},
+//!   title = {Citation File Format},
+//!   journal = {Journal of {Open Source} Software},
+//!   year = 2017,
+//!   month = dec,
+//! }
+//! "#).unwrap();
+//!
+//! assert_eq!(entries[0].key, "druskat2017");
+//! assert_eq!(entries[0].author[0].last, "Druskat");
+//! assert_eq!(entries[0].month_number(), Some(12));
+//! assert_eq!(entries[0].get("journal"), Some("Journal of {Open Source} Software"));
+//! ```
+#![warn(clippy::unwrap_used, missing_docs)]
+#![deny(rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use std::io::Write;
+
+pub use entries::{Entry, EntryType};
+pub use error::{Error, Result};
+
+pub mod entries;
+mod error;
+pub mod names;
+mod parser;
+pub mod tex;
+
+/// Parse the entries of a BibTeX file.
+pub fn from_str(s: &str) -> Result<Vec<Entry>> {
+	parser::parse(s)
+}
+
+/// Write entries as BibTeX.
+pub fn to_string(entries: &[Entry]) -> String {
+	entries
+		.iter()
+		.map(ToString::to_string)
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+/// Write entries as BibTeX into the IO stream.
+pub fn to_writer<W>(mut writer: W, entries: &[Entry]) -> std::io::Result<()>
+where
+	W: Write,
+{
+	writer.write_all(to_string(entries).as_bytes())
+}
@@ -0,0 +1,213 @@
+//! Types and utilities for names.
+//!
+//! BibTeX lists names separated by `and`, each in one of three forms:
+//!
+//! - `First von Last`
+//! - `von Last, First`
+//! - `von Last, Jr, First`
+//!
+//! The `von` part is made of the words starting with a lowercase letter.
+//! Anything in braces is kept together, which is how names of organisations are
+//! written: `{Dark Side Software}`.
+
+/// A name in a list of authors or editors.
+///
+/// All parts are TeX, as written in the file.
+#[derive(Debug, Default, Clone, Hash, Eq, PartialEq)]
+pub struct Name {
+	/// Given names.
+	pub first: Option<String>,
+
+	/// Particles before the family name, e.g. `van der`.
+	pub von: Option<String>,
+
+	/// Family name, or the whole name of an organisation.
+	pub last: String,
+
+	/// Suffixes, e.g. `Jr.`.
+	pub jr: Option<String>,
+}
+
+impl Name {
+	/// Parse a list of names separated by `and`.
+	pub fn parse_list(names: &str) -> Vec<Self> {
+		split_words(names)
+			.split(|word| word.eq_ignore_ascii_case("and"))
+			.filter(|words| !words.is_empty())
+			.map(|words| Self::parse(&words.join(" ")))
+			.collect()
+	}
+
+	/// Parse a single name.
+	pub fn parse(name: &str) -> Self {
+		let parts: Vec<Vec<&str>> = split_top_level(name, ',')
+			.into_iter()
+			.map(split_words)
+			.collect();
+
+		let (before, jr, first) = match parts.as_slice() {
+			[] => return Self::default(),
+			[words] => {
+				// First von Last: von starts at the first lowercase word, and
+				// the last word is always part of Last.
+				let (rest, last) = words.split_at(words.len().saturating_sub(1));
+				let von_start = rest.iter().position(|word| is_lowercase(word));
+				let first = &rest[..von_start.unwrap_or(rest.len())];
+				let mut name = Self::from_von_last(&[&rest[first.len()..], last].concat());
+				name.first = join(first);
+				return name;
+			}
+			[before, first] => (before, None, join(first)),
+			[before, jr, first, ..] => (before, join(jr), join(first)),
+		};
+
+		let mut name = Self::from_von_last(before);
+		name.jr = jr;
+		name.first = first;
+		name
+	}
+
+	/// Split `von Last` words: von ends at the last lowercase word, but Last
+	/// always has at least one word.
+	fn from_von_last(words: &[&str]) -> Self {
+		let (rest, last) = words.split_at(words.len().saturating_sub(1));
+		let von_end = rest
+			.iter()
+			.rposition(|word| is_lowercase(word))
+			.map_or(0, |n| n + 1);
+		Self {
+			von: join(&rest[..von_end]),
+			last: [&rest[von_end..], last].concat().join(" "),
+			..Default::default()
+		}
+	}
+
+	/// The name of an organisation, if this is one.
+	///
+	/// These are names made of a single group in braces, like
+	/// `{Dark Side Software}`. The braces are removed.
+	pub fn literal(&self) -> Option<&str> {
+		if self.first.is_some() || self.von.is_some() || self.jr.is_some() {
+			return None;
+		}
+
+		let inner = self.last.strip_prefix('{')?.strip_suffix('}')?;
+		let mut depth = 0_usize;
+		for c in inner.chars() {
+			match c {
+				'{' => depth += 1,
+				'}' if depth == 0 => return None,
+				'}' => depth -= 1,
+				_ => {}
+			}
+		}
+		Some(inner)
+	}
+
+	/// Whether this is the `others` of `and others`, which means "et al.".
+	pub fn is_others(&self) -> bool {
+		self.first.is_none() && self.von.is_none() && self.jr.is_none() && self.last == "others"
+	}
+}
+
+impl std::fmt::Display for Name {
+	/// Writes the name as `von Last, Jr, First`, leaving out empty parts.
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if let Some(von) = &self.von {
+			write!(f, "{von} ")?;
+		}
+		f.write_str(&self.last)?;
+		match (&self.jr, &self.first) {
+			(Some(jr), first) => write!(f, ", {jr}, {}", first.as_deref().unwrap_or_default()),
+			(None, Some(first)) => write!(f, ", {first}"),
+			(None, None) => Ok(()),
+		}
+	}
+}
+
+/// Write a list of names separated by `and`.
+pub(crate) fn write_names(names: &[Name]) -> String {
+	names
+		.iter()
+		.map(ToString::to_string)
+		.collect::<Vec<_>>()
+		.join(" and ")
+}
+
+fn join(words: &[&str]) -> Option<String> {
+	(!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Split on a character outside of braces, trimming the pieces.
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+	let mut pieces = Vec::new();
+	let mut depth = 0_usize;
+	let mut start = 0;
+	for (i, c) in s.char_indices() {
+		match c {
+			'{' => depth += 1,
+			'}' => depth = depth.saturating_sub(1),
+			c if c == sep && depth == 0 => {
+				pieces.push(s[start..i].trim());
+				start = i + c.len_utf8();
+			}
+			_ => {}
+		}
+	}
+	pieces.push(s[start..].trim());
+	pieces
+}
+
+/// Split into words on whitespace and `~` outside of braces.
+fn split_words(s: &str) -> Vec<&str> {
+	let mut words = Vec::new();
+	let mut depth = 0_usize;
+	let mut start = None;
+	for (i, c) in s.char_indices() {
+		match c {
+			'{' => depth += 1,
+			'}' => depth = depth.saturating_sub(1),
+			c if depth == 0 && (c.is_whitespace() || c == '~') => {
+				if let Some(start) = start.take() {
+					words.push(&s[start..i]);
+				}
+				continue;
+			}
+			_ => {}
+		}
+		start.get_or_insert(i);
+	}
+	if let Some(start) = start {
+		words.push(&s[start..]);
+	}
+	words
+}
+
+/// Whether a word starts with a lowercase letter, as BibTeX decides it.
+///
+/// Letters in braces don't count, except for accented letters like `{\"u}ber`,
+/// which count as the letter after the accent command.
+fn is_lowercase(word: &str) -> bool {
+	let mut chars = word.chars().peekable();
+	let mut depth = 0_usize;
+	while let Some(c) = chars.next() {
+		match c {
+			'{' if depth == 0 && chars.peek() == Some(&'\\') => {
+				chars.next();
+				// Skip a non-letter command like \" but not a letter command
+				// like \aa, which is itself the letter.
+				if chars.peek().map_or(false, |c| !c.is_alphabetic()) {
+					chars.next();
+				}
+				return chars
+					.find(|c| c.is_alphabetic())
+					.map_or(false, char::is_lowercase);
+			}
+			'{' => depth += 1,
+			'}' => depth = depth.saturating_sub(1),
+			c if depth == 0 && c.is_alphabetic() => return c.is_lowercase(),
+			_ => {}
+		}
+	}
+	false
+}
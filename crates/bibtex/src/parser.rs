@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use crate::{entries::MONTHS, Entry, EntryType, Error, Result};
+
+pub(crate) fn parse(source: &str) -> Result<Vec<Entry>> {
+	let mut parser = Parser {
+		source,
+		pos: 0,
+		strings: HashMap::new(),
+	};
+
+	let mut entries = Vec::new();
+	while let Some(at) = parser.rest().find('@') {
+		parser.pos += at + 1;
+		if let Some(entry) = parser.block()? {
+			entries.push(entry);
+		}
+	}
+	Ok(entries)
+}
+
+struct Parser<'s> {
+	source: &'s str,
+	pos: usize,
+	strings: HashMap<String, String>,
+}
+
+impl<'s> Parser<'s> {
+	fn rest(&self) -> &'s str {
+		&self.source[self.pos..]
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.rest().chars().next()
+	}
+
+	fn error(&self, message: impl Into<String>) -> Error {
+		Error::at(self.source, self.pos, message)
+	}
+
+	fn skip_whitespace(&mut self) {
+		let rest = self.rest();
+		self.pos += rest.len() - rest.trim_start().len();
+	}
+
+	fn expect(&mut self, c: char) -> Result<()> {
+		self.skip_whitespace();
+		if self.peek() == Some(c) {
+			self.pos += 1;
+			Ok(())
+		} else {
+			Err(self.error(format!("expected `{c}`")))
+		}
+	}
+
+	/// Identifiers: entry types, field names, and macro names.
+	fn identifier(&mut self) -> &'s str {
+		self.skip_whitespace();
+		let rest = self.rest();
+		let len = rest
+			.find(|c: char| c.is_whitespace() || "{}(),=#\"%'".contains(c))
+			.unwrap_or(rest.len());
+		self.pos += len;
+		&rest[..len]
+	}
+
+	/// Everything after an `@`, up to the end of the block.
+	fn block(&mut self) -> Result<Option<Entry>> {
+		let kind = self.identifier().to_ascii_lowercase();
+		if kind.is_empty() {
+			return Err(self.error("expected an entry type after `@`"));
+		}
+
+		self.skip_whitespace();
+		let close = match self.peek() {
+			Some('{') => '}',
+			Some('(') => ')',
+			_ if kind == "comment" => return Ok(None),
+			_ => return Err(self.error("expected `{` or `(`")),
+		};
+		self.pos += 1;
+
+		match kind.as_str() {
+			"comment" if close == '}' => {
+				self.pos -= 1;
+				self.braced()?;
+				Ok(None)
+			}
+			"comment" => {
+				let end = self.rest().find(close).map_or(self.rest().len(), |n| n + 1);
+				self.pos += end;
+				Ok(None)
+			}
+			"preamble" => {
+				self.value()?;
+				self.expect(close)?;
+				Ok(None)
+			}
+			"string" => {
+				let name = self.identifier().to_ascii_lowercase();
+				self.expect('=')?;
+				let value = self.value()?;
+				self.strings.insert(name, value);
+				self.expect(close)?;
+				Ok(None)
+			}
+			_ => self.entry(EntryType::from(kind.as_str()), close).map(Some),
+		}
+	}
+
+	fn entry(&mut self, entry_type: EntryType, close: char) -> Result<Entry> {
+		self.skip_whitespace();
+		let rest = self.rest();
+		let len = rest
+			.find(|c: char| c == ',' || c == close || c.is_whitespace())
+			.unwrap_or(rest.len());
+		let mut entry = Entry {
+			entry_type,
+			key: rest[..len].to_string(),
+			..Default::default()
+		};
+		self.pos += len;
+
+		let mut seen = Vec::new();
+		loop {
+			self.skip_whitespace();
+			match self.peek() {
+				Some(',') => self.pos += 1,
+				Some(c) if c == close => {
+					self.pos += 1;
+					return Ok(entry);
+				}
+				Some(_) => return Err(self.error("expected `,` between fields")),
+				None => return Err(self.error("unterminated entry")),
+			}
+
+			self.skip_whitespace();
+			if self.peek() == Some(close) {
+				continue;
+			}
+
+			let name = self.identifier().to_ascii_lowercase();
+			if name.is_empty() {
+				return Err(self.error("expected a field name"));
+			}
+			self.expect('=')?;
+			let value = self.value()?;
+
+			// BibTeX keeps the first of repeated fields.
+			if !seen.contains(&name) {
+				entry.set(&name, value);
+				seen.push(name);
+			}
+		}
+	}
+
+	/// A value: pieces joined by `#`, with whitespace collapsed.
+	fn value(&mut self) -> Result<String> {
+		let mut value = String::new();
+		loop {
+			self.skip_whitespace();
+			match self.peek() {
+				Some('{') => value.push_str(self.braced()?),
+				Some('"') => value.push_str(self.quoted()?),
+				Some(c) if c.is_ascii_digit() => {
+					let rest = self.rest();
+					let len = rest
+						.find(|c: char| !c.is_ascii_digit())
+						.unwrap_or(rest.len());
+					self.pos += len;
+					value.push_str(&rest[..len]);
+				}
+				Some(_) => {
+					let start = self.pos;
+					let name = self.identifier().to_ascii_lowercase();
+					match self.macro_value(&name) {
+						Some(expansion) => value.push_str(&expansion),
+						None if name.is_empty() => return Err(self.error("expected a value")),
+						None => {
+							return Err(Error::at(
+								self.source,
+								start,
+								format!("undefined string `{name}`"),
+							))
+						}
+					}
+				}
+				None => return Err(self.error("expected a value")),
+			}
+
+			self.skip_whitespace();
+			if self.peek() == Some('#') {
+				self.pos += 1;
+			} else {
+				return Ok(value.split_whitespace().collect::<Vec<_>>().join(" "));
+			}
+		}
+	}
+
+	fn macro_value(&self, name: &str) -> Option<String> {
+		if let Some(value) = self.strings.get(name) {
+			return Some(value.clone());
+		}
+		MONTHS
+			.iter()
+			.find(|month| month[..3].eq_ignore_ascii_case(name) && name.len() == 3)
+			.map(|month| month.to_string())
+	}
+
+	/// The contents of a balanced brace group, starting at its `{`.
+	fn braced(&mut self) -> Result<&'s str> {
+		let start = self.pos;
+		let mut depth = 0_usize;
+		for (i, c) in self.rest().char_indices() {
+			match c {
+				'{' => depth += 1,
+				'}' => {
+					depth -= 1;
+					if depth == 0 {
+						self.pos += i + 1;
+						return Ok(&self.source[start + 1..self.pos - 1]);
+					}
+				}
+				_ => {}
+			}
+		}
+		Err(Error::at(self.source, start, "unbalanced `{`"))
+	}
+
+	/// The contents of a quoted value, starting at its `"`.
+	///
+	/// Quotes inside braces don't end the value.
+	fn quoted(&mut self) -> Result<&'s str> {
+		let start = self.pos;
+		let mut depth = 0_usize;
+		for (i, c) in self.rest().char_indices().skip(1) {
+			match c {
+				'{' => depth += 1,
+				'}' => depth = depth.saturating_sub(1),
+				'"' if depth == 0 => {
+					self.pos += i + 1;
+					return Ok(&self.source[start + 1..self.pos - 1]);
+				}
+				_ => {}
+			}
+		}
+		Err(Error::at(self.source, start, "unterminated `\"`"))
+	}
+}
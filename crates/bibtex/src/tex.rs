@@ -0,0 +1,184 @@
+//! Conversion of TeX values to and from plain text.
+//!
+//! BibTeX values are TeX: braces protect the case of words from styles,
+//! accents are written as commands like `\"o`, and some characters must be
+//! escaped. [to_text] turns such values into Unicode plain text, understanding
+//! the commands commonly found in bibliographies, and [escape] does the reverse
+//! for plain text, keeping non-ASCII characters as they are (as biber and
+//! bibtexu read UTF-8).
+//!
+//! ```
+//! use citeworks_bibtex::tex::{escape, to_text};
+//!
+//! assert_eq!(to_text(r#"Schr{\"o}dinger's {C}at --- \emph{a} \& b"#), "Schrödinger's Cat — a & b");
+//! assert_eq!(escape("50% & more"), r"50\% \& more");
+//! ```
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Convert a TeX value to plain text.
+///
+/// Braces are removed, accent and symbol commands are replaced by their
+/// characters, `--` and `---` become dashes, and `~` a space. Other commands,
+/// like `\emph`, are removed, keeping their arguments.
+pub fn to_text(tex: &str) -> String {
+	let mut out = String::with_capacity(tex.len());
+	let mut chars = tex.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'{' | '}' => {}
+			'~' => out.push(' '),
+			'-' if chars.peek() == Some(&'-') => {
+				chars.next();
+				if chars.peek() == Some(&'-') {
+					chars.next();
+					out.push('—');
+				} else {
+					out.push('–');
+				}
+			}
+			'\\' => command(&mut chars, &mut out),
+			c if c.is_whitespace() => {
+				if !out.ends_with(' ') {
+					out.push(' ');
+				}
+			}
+			c => out.push(c),
+		}
+	}
+	out.nfc().collect::<String>().trim().to_string()
+}
+
+fn command(chars: &mut std::iter::Peekable<std::str::Chars<'_>>, out: &mut String) {
+	let first = match chars.next() {
+		Some(c) => c,
+		None => return,
+	};
+
+	if !first.is_ascii_alphabetic() {
+		match first {
+			'\\' => out.push(' '),
+			'\'' | '`' | '^' | '"' | '~' | '=' | '.' => accent(chars, first, out),
+			c => out.push(c),
+		}
+		return;
+	}
+
+	let mut name = String::from(first);
+	while let Some(c) = chars.peek().copied().filter(char::is_ascii_alphabetic) {
+		name.push(c);
+		chars.next();
+	}
+	// The space after a command name only ends the name.
+	if chars.peek() == Some(&' ') {
+		chars.next();
+	}
+
+	match name.as_str() {
+		"c" | "v" | "u" | "H" | "k" | "r" | "d" | "b" => {
+			accent(chars, name.as_bytes()[0] as char, out)
+		}
+		"ss" => out.push('ß'),
+		"o" => out.push('ø'),
+		"O" => out.push('Ø'),
+		"ae" => out.push('æ'),
+		"AE" => out.push('Æ'),
+		"oe" => out.push('œ'),
+		"OE" => out.push('Œ'),
+		"aa" => out.push('å'),
+		"AA" => out.push('Å'),
+		"l" => out.push('ł'),
+		"L" => out.push('Ł'),
+		"i" => out.push('ı'),
+		"j" => out.push('ȷ'),
+		"textbackslash" => out.push('\\'),
+		"textasciitilde" => out.push('~'),
+		"textasciicircum" => out.push('^'),
+		"textbraceleft" => out.push('{'),
+		"textbraceright" => out.push('}'),
+		"textendash" => out.push('–'),
+		"textemdash" => out.push('—'),
+		"textquotesingle" => out.push('\''),
+		"textquotedbl" => out.push('"'),
+		"textregistered" => out.push('®'),
+		"texttrademark" => out.push('™'),
+		"copyright" | "textcopyright" => out.push('©'),
+		"S" => out.push('§'),
+		"P" => out.push('¶'),
+		"dag" => out.push('†'),
+		"ddag" => out.push('‡'),
+		"pounds" => out.push('£'),
+		"euro" => out.push('€'),
+		"dots" | "ldots" | "textellipsis" => out.push('…'),
+		"LaTeX" => out.push_str("LaTeX"),
+		"TeX" => out.push_str("TeX"),
+		_ => {}
+	}
+}
+
+/// Apply an accent to the next letter, which may be in braces, as a combining
+/// character. The text is composed to NFC at the end.
+fn accent(chars: &mut std::iter::Peekable<std::str::Chars<'_>>, accent: char, out: &mut String) {
+	let combining = match accent {
+		'`' => '\u{300}',
+		'\'' => '\u{301}',
+		'^' => '\u{302}',
+		'~' => '\u{303}',
+		'=' => '\u{304}',
+		'u' => '\u{306}',
+		'.' => '\u{307}',
+		'"' => '\u{308}',
+		'r' => '\u{30A}',
+		'H' => '\u{30B}',
+		'v' => '\u{30C}',
+		'd' => '\u{323}',
+		'c' => '\u{327}',
+		'k' => '\u{328}',
+		'b' => '\u{331}',
+		_ => return,
+	};
+
+	let braced = chars.peek() == Some(&'{');
+	if braced {
+		chars.next();
+	}
+	let letter = match chars.next() {
+		// Dotless i and j, as in \'{\i}.
+		Some('\\') => match chars.next() {
+			Some('i') => 'i',
+			Some('j') => 'j',
+			Some(c) => c,
+			None => return,
+		},
+		Some(c) => c,
+		None => return,
+	};
+	out.push(letter);
+	out.push(combining);
+	if braced && chars.peek() == Some(&'}') {
+		chars.next();
+	}
+}
+
+/// Escape plain text for use in a BibTeX value.
+///
+/// Braces are written as commands, as BibTeX requires braces in values to be
+/// balanced even when escaped.
+pub fn escape(text: &str) -> String {
+	let mut out = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'\\' => out.push_str("\\textbackslash{}"),
+			'~' => out.push_str("\\textasciitilde{}"),
+			'^' => out.push_str("\\textasciicircum{}"),
+			'{' => out.push_str("\\textbraceleft{}"),
+			'}' => out.push_str("\\textbraceright{}"),
+			'&' | '%' | '$' | '#' | '_' => {
+				out.push('\\');
+				out.push(c);
+			}
+			c => out.push(c),
+		}
+	}
+	out
+}
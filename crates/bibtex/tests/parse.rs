@@ -0,0 +1,100 @@
+use citeworks_bibtex::{from_str, names::Name, tex::to_text, EntryType};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn entries_strings_and_comments() {
+	let entries = from_str(
+		r#"
+This text is ignored.
+
+@string{ joss = "Journal of Open Source Software" }
+@comment{ Exported by hand }
+@preamble{ "\newcommand{\noop}[1]{}" }
+
+@Article{druskat2017,
+  Author = "Druskat, Stephan and van der Berg, Jr., Jan",
+  title = {The {Citation File Format}: {"}quotes{"}},
+  journal = joss # { (JOSS)},
+  year = 2017,
+  month = dec,
+  pages = {1--10},
+  note = {kept},
+  note = {dropped},
+}
+
+@conference(smith2020,
+  author = {Jean de la Fontaine and {Dark Side Software} and others},
+  booktitle = {Proceedings
+               of things},
+)
+"#,
+	)
+	.unwrap();
+
+	assert_eq!(entries.len(), 2);
+	let article = &entries[0];
+	assert_eq!(article.entry_type, EntryType::Article);
+	assert_eq!(article.key, "druskat2017");
+	assert_eq!(
+		article.author,
+		vec![
+			Name {
+				first: Some("Stephan".into()),
+				last: "Druskat".into(),
+				..Default::default()
+			},
+			Name {
+				first: Some("Jan".into()),
+				von: Some("van der".into()),
+				last: "Berg".into(),
+				jr: Some("Jr.".into()),
+			},
+		]
+	);
+	assert_eq!(
+		article.title.as_deref(),
+		Some(r#"The {Citation File Format}: {"}quotes{"}"#)
+	);
+	assert_eq!(
+		article.get("journal"),
+		Some("Journal of Open Source Software (JOSS)")
+	);
+	assert_eq!(article.year_number(), Some(2017));
+	assert_eq!(article.month.as_deref(), Some("December"));
+	assert_eq!(article.get("note"), Some("kept"));
+
+	let paper = &entries[1];
+	assert_eq!(paper.entry_type, EntryType::InProceedings);
+	assert_eq!(paper.author[0].first.as_deref(), Some("Jean"));
+	assert_eq!(paper.author[0].von.as_deref(), Some("de la"));
+	assert_eq!(paper.author[0].last, "Fontaine");
+	assert_eq!(paper.author[1].literal(), Some("Dark Side Software"));
+	assert!(paper.author[2].is_others());
+	assert_eq!(paper.get("booktitle"), Some("Proceedings of things"));
+}
+
+#[test]
+fn errors() {
+	let err = from_str("@misc{a,\n  title = {unbalanced,\n").unwrap_err();
+	assert_eq!((err.line, err.column), (2, 11));
+
+	let err = from_str("@misc{a, publisher = acm}").unwrap_err();
+	assert_eq!(err.message, "undefined string `acm`");
+	assert_eq!(
+		err.to_string(),
+		"undefined string `acm` at line 1 column 22"
+	);
+}
+
+#[test]
+fn tex_to_text() {
+	assert_eq!(
+		to_text(r#"{\'E}cole {\c{c}}a \v{S}koda \'{\i} \AA{}ngstr{\"o}m \ss"#),
+		"École ça Škoda í Ångström ß"
+	);
+	assert_eq!(
+		to_text("pages 1--10,~\\textit{et al.}"),
+		"pages 1–10, et al."
+	);
+}
@@ -0,0 +1,62 @@
+use citeworks_bibtex::{from_str, names::Name, to_string, Entry, EntryType};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn write_entries() {
+	let mut entry = Entry {
+		entry_type: EntryType::Software,
+		key: "opaquity".into(),
+		author: vec![Name {
+			last: "{Dark Side Software}".into(),
+			..Default::default()
+		}],
+		title: Some("Opaquity".into()),
+		year: Some("2022".into()),
+		month: Some("August".into()),
+		doi: Some("10.5281/zenodo.1234".into()),
+		..Default::default()
+	};
+	entry.set("version", "1.2.3");
+
+	assert_eq!(
+		to_string(&[entry.clone(), entry]),
+		"@software{opaquity,
+  author = {{Dark Side Software}},
+  title = {Opaquity},
+  version = {1.2.3},
+  year = {2022},
+  month = aug,
+  doi = {10.5281/zenodo.1234},
+}
+
+@software{opaquity,
+  author = {{Dark Side Software}},
+  title = {Opaquity},
+  version = {1.2.3},
+  year = {2022},
+  month = aug,
+  doi = {10.5281/zenodo.1234},
+}
+"
+	);
+}
+
+#[test]
+fn roundtrip() {
+	let source = r#"
+@book{knuth1984,
+  author = {Knuth, Donald E. and van Rossum, Jr., Guido},
+  editor = {Ludwig van Beethoven},
+  title = {The {\TeX}book},
+  publisher = {Addison-Wesley},
+  series = {Computers and Typesetting},
+  volume = {A},
+  year = {1984},
+  month = {Spring},
+}
+"#;
+	let entries = from_str(source).unwrap();
+	assert_eq!(entries[0].editor[0].von.as_deref(), Some("van"));
+	assert_eq!(from_str(&to_string(&entries)).unwrap(), entries);
+}
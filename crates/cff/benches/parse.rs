@@ -0,0 +1,50 @@
+use std::env;
+
+use citeworks_cff::{
+	from_slice,
+	test_util::{load_corpus, synthetic_cff, CorpusFile},
+	to_vec,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+fn parse_files(c: &mut Criterion, group_name: &str, files: &[CorpusFile]) {
+	let mut group = c.benchmark_group(group_name);
+	for file in files {
+		group.throughput(Throughput::Bytes(file.contents.len() as u64));
+		group.bench_with_input(
+			BenchmarkId::from_parameter(&file.name),
+			&file.contents,
+			|b, contents| b.iter(|| from_slice(contents).unwrap()),
+		);
+	}
+	group.finish();
+}
+
+fn small(c: &mut Criterion) {
+	let files = load_corpus("tests/pass").unwrap();
+	parse_files(c, "parse/small", &files);
+}
+
+fn large(c: &mut Criterion) {
+	let cff = synthetic_cff(1000);
+	let yaml = to_vec(&cff).unwrap();
+
+	let mut group = c.benchmark_group("large");
+	group.throughput(Throughput::Bytes(yaml.len() as u64));
+	group.sample_size(20);
+	group.bench_function("parse/1k-references", |b| {
+		b.iter(|| from_slice(&yaml).unwrap())
+	});
+	group.bench_function("write/1k-references", |b| b.iter(|| to_vec(&cff).unwrap()));
+	group.finish();
+}
+
+fn user(c: &mut Criterion) {
+	if let Some(dir) = env::var_os("CITEWORKS_BENCH_CORPUS") {
+		let files = load_corpus(dir).unwrap();
+		parse_files(c, "parse/user", &files);
+	}
+}
+
+criterion_group!(benches, small, large, user);
+criterion_main!(benches);
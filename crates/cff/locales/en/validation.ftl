@@ -0,0 +1,46 @@
+# Validation messages, by rule name.
+#
+# Messages with details from the document have them as variables. The details
+# of `orcid-format`, `doi-format`, `isbn-format`, `issn-format`, `ror-format`,
+# and `zenodo-orcid` are in English.
+
+message-empty = message should not be empty
+title-empty = title should not be empty
+authors-empty = there should be at least one author
+month-range = month should be between 1 and 12, got: { $month }
+orcid-format = { $error }
+doi-format = { $error }
+isbn-format = { $error }
+issn-format = { $error }
+unknown-type = type "{ $type }" isn't one of CFF 1.2.0's
+preferred-citation-self = preferred citation is the work itself: it's for another work to cite instead
+conference-paper-conference = conference paper should have a conference
+conference-paper-collection-title = conference paper should have the title of the proceedings
+patent-states-or-number = patent should have the states it's granted in, or a number
+thesis-institution = thesis should have an institution
+thesis-type = thesis should have a thesis type
+article-journal = article should have a journal
+book-publisher = book should have a publisher
+report-institution = report should have an institution
+website-url = website should have a URL
+ror-format = { $error }
+commit-format = commit should be a 40-character hash, or start with "Revision:", got: { $commit }
+version-blank = version should not be blank
+abstract-url = abstract should describe the work, not only link to a description
+repository-field = { $url } belongs in { $field }
+embargo-not-dataset = embargo fields only apply to datasets
+embargo-before-release = available date { $available } is before release date { $released }
+embargo-mismatch = embargo period ends on { $end }, but available date is { $available }
+contact-not-author = contact is not one of the authors
+license-format = license isn't a valid SPDX expression, got: "{ $license }"
+github-preferred-citation = GitHub shows the preferred citation, not the version or DOI of the work
+github-identifier-kind = GitHub only shows DOI identifiers
+github-preferred-citation-type = GitHub shows this type of reference as a generic @misc entry
+github-preferred-citation-authors = GitHub shows the authors of the preferred citation, and it has none
+github-preferred-citation-year = GitHub shows the year of the preferred citation, and it has none
+github-family-names = GitHub cites people by family name, and this person has none
+zenodo-creator-name = Zenodo needs a family name for each person
+zenodo-orcid = { $error }
+zenodo-license = Zenodo records have a single SPDX license, got: { $expression }
+zenodo-abstract = Zenodo uses the abstract as the description of the record
+zenodo-doi = Zenodo DOIs are either for one release, or for all of them as the concept DOI: check that this is the one you mean
@@ -0,0 +1,326 @@
+//! Building documents and references programmatically.
+//!
+//! Struct literals make it easy to forget required fields, like the title or
+//! authors. The builders check them when building instead:
+//!
+//! ```
+//! use citeworks_cff::{
+//!     builder::BuildError,
+//!     names::{Name, PersonName},
+//!     references::{RefType, Reference},
+//!     Cff,
+//! };
+//!
+//! let jane = Name::Person(PersonName {
+//!     family_names: Some("Roe".into()),
+//!     given_names: Some("Jane".into()),
+//!     ..Default::default()
+//! });
+//!
+//! let paper = Reference::builder(RefType::Article)
+//!     .title("A study")
+//!     .author(jane.clone())
+//!     .journal("Journal of Studies")
+//!     .build()
+//!     .unwrap();
+//!
+//! let cff = Cff::builder()
+//!     .title("Example")
+//!     .author(jane)
+//!     .version("1.0.0")
+//!     .preferred_citation(paper)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(cff.title, "Example");
+//!
+//! assert_eq!(
+//!     Cff::builder().title("Example").build(),
+//!     Err(BuildError::Empty("authors".into()))
+//! );
+//! ```
+
+use std::fmt::Display;
+
+use serde_yaml::Value;
+use url::Url;
+
+use crate::{
+	funding::Funding,
+	identifiers::{Identifier, Isbn, Issn},
+	names::{EntityName, Name},
+	references::{PublicationStatus, RefType, Reference},
+	Cff, Date, Duration, License, WorkType,
+};
+
+/// The error when building a document or reference fails.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BuildError {
+	/// A required field isn't set, or is blank.
+	Missing(String),
+
+	/// A list which must have at least one entry is empty.
+	Empty(String),
+}
+
+impl Display for BuildError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Missing(field) => write!(f, "{field} is required"),
+			Self::Empty(field) => write!(f, "{field} needs at least one entry"),
+		}
+	}
+}
+
+impl std::error::Error for BuildError {}
+
+macro_rules! set {
+	($inner:ident; $($field:ident: $type:ty),* $(,)?) => {
+		$(
+			#[doc = concat!("Set `", stringify!($field), "`.")]
+			pub fn $field(mut self, value: impl Into<$type>) -> Self {
+				self.$inner.$field = Some(value.into());
+				self
+			}
+		)*
+	};
+}
+
+macro_rules! push {
+	($inner:ident; $($setter:ident => $field:ident: $type:ty),* $(,)?) => {
+		$(
+			#[doc = concat!("Add to `", stringify!($field), "`.")]
+			pub fn $setter(mut self, value: impl Into<$type>) -> Self {
+				self.$inner.$field.push(value.into());
+				self
+			}
+		)*
+	};
+}
+
+/// Builder for a [Cff] document.
+///
+/// Setters for lists add an entry each time they're called; others replace
+/// the value. The message starts as the default message of [Cff].
+#[derive(Debug, Clone, Default)]
+pub struct CffBuilder {
+	cff: Cff,
+}
+
+impl CffBuilder {
+	/// Start building a document.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the title, which is required.
+	pub fn title(mut self, title: impl Into<String>) -> Self {
+		self.cff.title = title.into();
+		self
+	}
+
+	/// Set the message, which is required.
+	pub fn message(mut self, message: impl Into<String>) -> Self {
+		self.cff.message = message.into();
+		self
+	}
+
+	set!(cff;
+		work_type: WorkType,
+		version: String,
+		commit: String,
+		date_released: Date,
+		available_date: Date,
+		embargo_period: Duration,
+		abstract_text: String,
+		url: Url,
+		repository: Url,
+		repository_artifact: Url,
+		repository_code: Url,
+		license: License,
+		license_url: Url,
+		doi: String,
+		preferred_citation: Reference,
+	);
+
+	push!(cff;
+		author => authors: Name,
+		contact => contact: Name,
+		keyword => keywords: String,
+		identifier => identifiers: Identifier,
+		funding => funding: Funding,
+		reference => references: Reference,
+	);
+
+	/// Set a field which isn't part of the specification.
+	pub fn extra(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+		self.cff.extra.insert(key.into(), value.into());
+		self
+	}
+
+	/// Build the document.
+	///
+	/// This fails if the title or message are blank, if there are no authors,
+	/// or if the preferred citation or a reference is missing its own required
+	/// fields.
+	pub fn build(self) -> Result<Cff, BuildError> {
+		let cff = self.cff;
+		require(&cff.title, "title")?;
+		require(&cff.message, "message")?;
+		if cff.authors.is_empty() {
+			return Err(BuildError::Empty("authors".into()));
+		}
+
+		if let Some(reference) = &cff.preferred_citation {
+			check_reference(reference, "preferred-citation.")?;
+		}
+		for (n, reference) in cff.references.iter().enumerate() {
+			check_reference(reference, &format!("references[{n}]."))?;
+		}
+
+		Ok(cff)
+	}
+}
+
+/// Builder for a [Reference].
+///
+/// Setters for lists add an entry each time they're called; others replace
+/// the value.
+#[derive(Debug, Clone)]
+pub struct ReferenceBuilder {
+	reference: Reference,
+}
+
+impl ReferenceBuilder {
+	/// Start building a reference to a work of the given type.
+	pub fn new(work_type: RefType) -> Self {
+		Self {
+			reference: Reference {
+				work_type,
+				..Default::default()
+			},
+		}
+	}
+
+	set!(reference;
+		title: String,
+		abbreviation: String,
+		abstract_text: String,
+		collection_doi: String,
+		collection_title: String,
+		collection_type: String,
+		commit: String,
+		conference: EntityName,
+		copyright: String,
+		data_type: String,
+		database_provider: EntityName,
+		database: String,
+		date_accessed: Date,
+		date_downloaded: Date,
+		date_published: Date,
+		date_released: Date,
+		department: String,
+		doi: String,
+		edition: String,
+		start: u64,
+		end: u64,
+		entry: String,
+		filename: String,
+		format: String,
+		institution: EntityName,
+		isbn: Isbn,
+		issn: Issn,
+		issue: String,
+		issue_date: String,
+		issue_title: String,
+		journal: String,
+		license: License,
+		license_url: Url,
+		loc_end: u64,
+		loc_start: u64,
+		location: EntityName,
+		medium: String,
+		month: u8,
+		nihmsid: String,
+		notes: String,
+		number: String,
+		number_volumes: u64,
+		pages: u64,
+		pmcid: String,
+		publisher: EntityName,
+		repository: Url,
+		repository_artifact: Url,
+		repository_code: Url,
+		scope: String,
+		section: String,
+		status: PublicationStatus,
+		term: String,
+		thesis_type: String,
+		url: Url,
+		version: String,
+		volume: u64,
+		volume_title: String,
+		year: u64,
+		year_original: i64,
+	);
+
+	push!(reference;
+		author => authors: Name,
+		contact => contact: Name,
+		editor => editors: Name,
+		series_editor => editors_series: Name,
+		identifier => identifiers: Identifier,
+		keyword => keywords: String,
+		language => languages: String,
+		patent_state => patent_states: String,
+		recipient => recipients: Name,
+		sender => senders: Name,
+		translator => translators: Name,
+	);
+
+	/// Set a field which isn't part of the specification.
+	pub fn extra(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+		self.reference.extra.insert(key.into(), value.into());
+		self
+	}
+
+	/// Build the reference.
+	///
+	/// This fails if the title is blank or there are no authors.
+	pub fn build(self) -> Result<Reference, BuildError> {
+		check_reference(&self.reference, "")?;
+		Ok(self.reference)
+	}
+}
+
+fn require(value: &str, field: &str) -> Result<(), BuildError> {
+	if value.trim().is_empty() {
+		Err(BuildError::Missing(field.into()))
+	} else {
+		Ok(())
+	}
+}
+
+fn check_reference(reference: &Reference, prefix: &str) -> Result<(), BuildError> {
+	require(
+		reference.title.as_deref().unwrap_or_default(),
+		&format!("{prefix}title"),
+	)?;
+	if reference.authors.is_empty() {
+		return Err(BuildError::Empty(format!("{prefix}authors")));
+	}
+	Ok(())
+}
+
+impl Cff {
+	/// Start building a document, see [CffBuilder].
+	pub fn builder() -> CffBuilder {
+		CffBuilder::new()
+	}
+}
+
+impl Reference {
+	/// Start building a reference, see [ReferenceBuilder].
+	pub fn builder(work_type: RefType) -> ReferenceBuilder {
+		ReferenceBuilder::new(work_type)
+	}
+}
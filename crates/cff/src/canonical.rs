@@ -0,0 +1,240 @@
+//! Writing documents in a canonical layout, for minimal diffs.
+
+use serde_yaml::{Mapping, Value};
+
+use crate::{references::Reference, Cff, Result};
+
+/// Keys of the document, in the order of the specification's guide.
+const DOCUMENT_KEYS: &[&str] = &[
+	"cff-version",
+	"message",
+	"title",
+	"abstract",
+	"type",
+	"authors",
+	"contact",
+	"version",
+	"commit",
+	"date-released",
+	"available-date",
+	"embargo-period",
+	"doi",
+	"identifiers",
+	"url",
+	"repository",
+	"repository-code",
+	"repository-artifact",
+	"license",
+	"license-url",
+	"keywords",
+	"funding",
+	"preferred-citation",
+	"references",
+];
+
+/// Keys of references, with the work first, then where and when it appeared.
+const REFERENCE_KEYS: &[&str] = &[
+	"type",
+	"title",
+	"abbreviation",
+	"abstract",
+	"authors",
+	"editors",
+	"editors-series",
+	"translators",
+	"recipients",
+	"senders",
+	"contact",
+	"conference",
+	"journal",
+	"collection-title",
+	"collection-type",
+	"collection-doi",
+	"volume",
+	"volume-title",
+	"issue",
+	"issue-title",
+	"issue-date",
+	"number",
+	"pages",
+	"start",
+	"end",
+	"section",
+	"edition",
+	"publisher",
+	"institution",
+	"department",
+	"database",
+	"database-provider",
+	"location",
+	"year",
+	"year-original",
+	"month",
+	"date-published",
+	"date-released",
+	"date-accessed",
+	"date-downloaded",
+	"doi",
+	"identifiers",
+	"isbn",
+	"issn",
+	"url",
+	"repository",
+	"repository-code",
+	"repository-artifact",
+	"license",
+	"license-url",
+	"version",
+	"commit",
+	"keywords",
+	"languages",
+	"notes",
+];
+
+/// Keys of persons and entities.
+const NAME_KEYS: &[&str] = &[
+	"family-names",
+	"given-names",
+	"name-particle",
+	"name-suffix",
+	"name",
+	"alias",
+	"affiliation",
+	"orcid",
+	"email",
+	"website",
+	"tel",
+	"fax",
+	"address",
+	"city",
+	"region",
+	"post-code",
+	"country",
+	"location",
+	"date-start",
+	"date-end",
+];
+
+/// Keys of identifiers.
+const IDENTIFIER_KEYS: &[&str] = &["type", "value", "description"];
+
+/// Keys of references holding lists of names.
+const NAME_LIST_KEYS: &[&str] = &[
+	"authors",
+	"contact",
+	"editors",
+	"editors-series",
+	"recipients",
+	"senders",
+	"translators",
+];
+
+/// Keys of references holding a single entity.
+const ENTITY_KEYS: &[&str] = &[
+	"conference",
+	"database-provider",
+	"institution",
+	"location",
+	"publisher",
+];
+
+/// What a mapping is, to know the order of its keys.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+	Document,
+	Reference,
+	Name,
+	Identifier,
+
+	/// Anything else, like funding or extra fields, in alphabetical order
+	Other,
+}
+
+impl Kind {
+	fn keys(self) -> &'static [&'static str] {
+		match self {
+			Self::Document => DOCUMENT_KEYS,
+			Self::Reference => REFERENCE_KEYS,
+			Self::Name => NAME_KEYS,
+			Self::Identifier => IDENTIFIER_KEYS,
+			Self::Other => &[],
+		}
+	}
+
+	/// The kind of the value at a key of this kind of mapping.
+	fn child(self, key: &str) -> Self {
+		match (self, key) {
+			(Self::Document, "preferred-citation" | "references") => Self::Reference,
+			(Self::Document | Self::Reference, "identifiers") => Self::Identifier,
+			(Self::Document | Self::Reference, key) if NAME_LIST_KEYS.contains(&key) => Self::Name,
+			(Self::Reference, key) if ENTITY_KEYS.contains(&key) => Self::Name,
+			_ => Self::Other,
+		}
+	}
+}
+
+/// The document as YAML, with keys in canonical order and sorted references.
+pub(crate) fn to_value(cff: &Cff) -> Result<Value> {
+	let mut cff = cff.clone();
+	cff.references.sort_by_cached_key(reference_key);
+	Ok(order_keys(serde_yaml::to_value(&cff)?, Kind::Document))
+}
+
+/// A reference as YAML, with keys in canonical order.
+pub(crate) fn reference_to_value(reference: &Reference) -> Result<Value> {
+	Ok(order_keys(
+		serde_yaml::to_value(reference)?,
+		Kind::Reference,
+	))
+}
+
+/// Sort key of references: by first author, then year, then title.
+fn reference_key(reference: &Reference) -> (Vec<String>, Option<i64>, String) {
+	let year = reference
+		.year
+		.map(|year| year as i64)
+		.or_else(|| reference.date_published.map(|date| date.year))
+		.or_else(|| reference.date_released.map(|date| date.year));
+	(
+		reference
+			.authors
+			.first()
+			.map(|author| author.sort_key())
+			.unwrap_or_default(),
+		year,
+		reference
+			.title
+			.as_deref()
+			.unwrap_or_default()
+			.to_lowercase(),
+	)
+}
+
+fn order_keys(value: Value, kind: Kind) -> Value {
+	match value {
+		Value::Mapping(mapping) => {
+			let keys = kind.keys();
+			let mut entries: Vec<(Value, Value)> = mapping
+				.into_iter()
+				.map(|(key, value)| {
+					let child = key.as_str().map_or(Kind::Other, |key| kind.child(key));
+					let value = order_keys(value, child);
+					(key, value)
+				})
+				.collect();
+			entries.sort_by_cached_key(|(key, _)| {
+				let key = key.as_str().unwrap_or_default();
+				let position = keys.iter().position(|known| *known == key);
+				(position.unwrap_or(keys.len()), key.to_string())
+			});
+			Value::Mapping(entries.into_iter().collect::<Mapping>())
+		}
+		Value::Sequence(values) => Value::Sequence(
+			values
+				.into_iter()
+				.map(|value| order_keys(value, kind))
+				.collect(),
+		),
+		value => value,
+	}
+}
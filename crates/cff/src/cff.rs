@@ -1,9 +1,13 @@
+use std::collections::BTreeMap;
+
 use semver::Version;
 use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
 use url::Url;
 
 use crate::{
-	date::Date, identifiers::Identifier, license::License, names::Name, references::Reference,
+	date::Date, duration::Duration, funding::Funding, identifiers::Identifier, license::License,
+	names::Name, references::Reference,
 };
 
 /// The top-level CFF document.
@@ -61,6 +65,20 @@ pub struct Cff {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub date_released: Option<Date>,
 
+	/// The date from which a dataset is available, e.g. after an embargo.
+	///
+	/// This is an extension: it is not part of CFF 1.2.0, and only applies
+	/// when `work_type` is [`WorkType::Dataset`].
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub available_date: Option<Date>,
+
+	/// The length of the embargo on a dataset, from its release date.
+	///
+	/// This is an extension: it is not part of CFF 1.2.0, and only applies
+	/// when `work_type` is [`WorkType::Dataset`]. See [`Cff::embargo_end`].
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub embargo_period: Option<Duration>,
+
 	/// A description of the work.
 	#[serde(default, skip_serializing_if = "Option::is_none", rename = "abstract")]
 	pub abstract_text: Option<String>,
@@ -123,6 +141,12 @@ pub struct Cff {
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub identifiers: Vec<Identifier>,
 
+	/// Sources of funding for the work.
+	///
+	/// This is an extension: it is not part of CFF 1.2.0.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub funding: Vec<Funding>,
+
 	/// A reference to another work that should be cited instead of the work.
 	///
 	/// Note that the principles of [software citation] and [data citation]
@@ -144,6 +168,13 @@ pub struct Cff {
 	/// the software or dataset.
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub references: Vec<Reference>,
+
+	/// Fields not defined above.
+	///
+	/// These are kept so documents using fields from newer versions of CFF, or
+	/// extensions, aren't damaged by reading and writing them back.
+	#[serde(flatten)]
+	pub extra: BTreeMap<String, Value>,
 }
 
 impl Default for Cff {
@@ -156,6 +187,8 @@ impl Default for Cff {
 			version: Default::default(),
 			commit: Default::default(),
 			date_released: Default::default(),
+			available_date: Default::default(),
+			embargo_period: Default::default(),
 			abstract_text: Default::default(),
 			keywords: Default::default(),
 			url: Default::default(),
@@ -168,19 +201,44 @@ impl Default for Cff {
 			contact: Default::default(),
 			doi: Default::default(),
 			identifiers: Default::default(),
+			funding: Default::default(),
 			preferred_citation: Default::default(),
 			references: Default::default(),
+			extra: Default::default(),
 		}
 	}
 }
 
+impl Cff {
+	/// The date the embargo on the work ends, if there is one.
+	///
+	/// This is the `available_date` if set, otherwise the `date_released` plus
+	/// the `embargo_period` if both are set.
+	pub fn embargo_end(&self) -> Option<Date> {
+		self.available_date.or_else(|| {
+			self.date_released
+				.zip(self.embargo_period)
+				.and_then(|(released, period)| released.add(period))
+		})
+	}
+}
+
 /// Types of works recognised by CFF.
-#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(
+	rename_all = "kebab-case",
+	expecting = "expected a work type, `software` or `dataset`"
+)]
 pub enum WorkType {
 	/// A software project.
 	Software,
 
 	/// A dataset.
 	Dataset,
+
+	/// A type this crate doesn't know, e.g. from a newer version of CFF.
+	///
+	/// It's kept as written, so it's written back the same.
+	#[serde(untagged)]
+	Other(String),
 }
@@ -1,14 +1,17 @@
 use std::{
 	fmt::{Debug, Display},
 	str::FromStr,
+	time::{SystemTime, UNIX_EPOCH},
 };
 
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::Duration;
+
 /// A date.
 ///
 /// In CFF this is a string in `YYYY-MM-DD` format.
-#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Date {
 	/// Year, in the Gregorian calendar
 	pub year: i64,
@@ -20,6 +23,25 @@ pub struct Date {
 	pub day: u8,
 }
 
+impl Date {
+	/// Today's date, in UTC.
+	pub fn today() -> Self {
+		let days = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |since| since.as_secs() / 86400);
+		Self {
+			year: 1970,
+			month: 1,
+			day: 1,
+		}
+		.add(Duration {
+			days: u32::try_from(days).unwrap_or_default(),
+			..Default::default()
+		})
+		.expect("days since 1970 fit in a date")
+	}
+}
+
 impl Display for Date {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		let Self { year, month, day } = self;
@@ -81,3 +103,52 @@ impl<'de> Deserialize<'de> for Date {
 		Date::from_str(&s).map_err(D::Error::custom)
 	}
 }
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for Date {
+	fn from(date: chrono::NaiveDate) -> Self {
+		use chrono::Datelike;
+		Self {
+			year: date.year().into(),
+			month: date.month() as u8,
+			day: date.day() as u8,
+		}
+	}
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Date> for chrono::NaiveDate {
+	type Error = String;
+
+	fn try_from(date: Date) -> Result<Self, Self::Error> {
+		i32::try_from(date.year)
+			.ok()
+			.and_then(|year| {
+				chrono::NaiveDate::from_ymd_opt(year, date.month.into(), date.day.into())
+			})
+			.ok_or_else(|| format!("not a valid date for chrono: {date}"))
+	}
+}
+
+#[cfg(feature = "time")]
+impl From<time::Date> for Date {
+	fn from(date: time::Date) -> Self {
+		Self {
+			year: date.year().into(),
+			month: date.month().into(),
+			day: date.day(),
+		}
+	}
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Date> for time::Date {
+	type Error = String;
+
+	fn try_from(date: Date) -> Result<Self, Self::Error> {
+		let year = i32::try_from(date.year).map_err(|err| format!("{date}: {err}"))?;
+		let month = time::Month::try_from(date.month).map_err(|err| format!("{date}: {err}"))?;
+		time::Date::from_calendar_date(year, month, date.day)
+			.map_err(|err| format!("{date}: {err}"))
+	}
+}
@@ -0,0 +1,189 @@
+use std::{
+	fmt::{Debug, Display},
+	str::FromStr,
+};
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Date;
+
+/// A calendar duration.
+///
+/// This is an [ISO 8601 duration][iso] restricted to its date components,
+/// e.g. `P6M` (six months) or `P1Y2W` (a year and two weeks).
+///
+/// [iso]: https://en.wikipedia.org/wiki/ISO_8601#Durations
+#[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Duration {
+	/// Number of years
+	pub years: u32,
+
+	/// Number of months
+	pub months: u32,
+
+	/// Number of weeks
+	pub weeks: u32,
+
+	/// Number of days
+	pub days: u32,
+}
+
+impl Duration {
+	/// Returns true if the duration is zero.
+	pub fn is_zero(&self) -> bool {
+		*self == Self::default()
+	}
+}
+
+impl Display for Duration {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let Self {
+			years,
+			months,
+			weeks,
+			days,
+		} = self;
+
+		if self.is_zero() {
+			return write!(f, "P0D");
+		}
+
+		write!(f, "P")?;
+		for (n, unit) in [(years, 'Y'), (months, 'M'), (weeks, 'W'), (days, 'D')] {
+			if *n > 0 {
+				write!(f, "{n}{unit}")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for Duration {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let err = || -> String { format!("expected an ISO 8601 duration like P6M, got: {s:?}") };
+
+		let rest = s.strip_prefix('P').ok_or_else(err)?;
+		if rest.is_empty() {
+			return Err(err());
+		}
+		if rest.contains('T') {
+			return Err(format!(
+				"only date components are supported in durations, got: {s:?}"
+			));
+		}
+
+		let mut duration = Self::default();
+		let mut number = String::new();
+		let mut last_unit = 0;
+		for c in rest.chars() {
+			if c.is_ascii_digit() {
+				number.push(c);
+				continue;
+			}
+
+			let (order, field) = match c {
+				'Y' => (1, &mut duration.years),
+				'M' => (2, &mut duration.months),
+				'W' => (3, &mut duration.weeks),
+				'D' => (4, &mut duration.days),
+				_ => return Err(err()),
+			};
+
+			if order <= last_unit || number.is_empty() {
+				return Err(err());
+			}
+
+			*field = number.parse().map_err(|_| err())?;
+			number.clear();
+			last_unit = order;
+		}
+
+		if number.is_empty() {
+			Ok(duration)
+		} else {
+			Err(err())
+		}
+	}
+}
+
+impl Serialize for Duration {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let s = self.to_string();
+		s.serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for Duration {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let s = String::deserialize(deserializer)?;
+		Duration::from_str(&s).map_err(D::Error::custom)
+	}
+}
+
+impl Date {
+	/// Add a [Duration] to this date.
+	///
+	/// Years and months are added first, clamping the day to the end of the
+	/// resulting month (so January 31st plus one month is February 28th or
+	/// 29th), then weeks and days are added.
+	///
+	/// Returns `None` if the resulting year is out of range.
+	pub fn add(&self, duration: Duration) -> Option<Self> {
+		let months = self
+			.year
+			.checked_mul(12)?
+			.checked_add(i64::from(self.month) - 1)?
+			.checked_add(i64::from(duration.years) * 12 + i64::from(duration.months))?;
+		let mut date = Self {
+			year: months.div_euclid(12),
+			month: months.rem_euclid(12) as u8 + 1,
+			day: self.day,
+		};
+		date.day = date.day.min(days_in_month(date.year, date.month));
+
+		// Whole Gregorian cycles of 400 years have the same number of days.
+		let mut days = u64::from(duration.weeks) * 7 + u64::from(duration.days);
+		date.year = date
+			.year
+			.checked_add(i64::try_from(days / DAYS_IN_400_YEARS).ok()? * 400)?;
+		days %= DAYS_IN_400_YEARS;
+
+		while days > 0 {
+			let left_in_month = u64::from(days_in_month(date.year, date.month) - date.day);
+			if days <= left_in_month {
+				date.day += days as u8;
+				break;
+			}
+
+			days -= left_in_month + 1;
+			date.day = 1;
+			if date.month == 12 {
+				date.month = 1;
+				date.year = date.year.checked_add(1)?;
+			} else {
+				date.month += 1;
+			}
+		}
+
+		Some(date)
+	}
+}
+
+const DAYS_IN_400_YEARS: u64 = 146_097;
+
+fn days_in_month(year: i64, month: u8) -> u8 {
+	match month {
+		2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
@@ -0,0 +1,322 @@
+//! Editing the references of CFF files, keeping the rest as written.
+//!
+//! Writing a document with [crate::to_string] loses its comments, quoting
+//! styles, and order of keys. These functions edit the text of the document
+//! instead, only touching its `references`, so the rest of the file stays
+//! byte-identical:
+//!
+//! ```
+//! use citeworks_cff::{edit, names::Name, references::{RefType, Reference}};
+//!
+//! let yaml = "\
+//! ## Please keep this file up to date!
+//! cff-version: 1.2.0
+//! message: 'Please cite this software.'
+//! title: Example
+//! authors:
+//!   - name: Acme  # the company
+//! references:
+//!   - type: book
+//!     title: Old Book
+//!     authors: [{ name: anonymous }]
+//! ";
+//!
+//! let reference = Reference {
+//!     work_type: RefType::Book,
+//!     title: Some("New Book".into()),
+//!     authors: vec![Name::Anonymous],
+//!     ..Default::default()
+//! };
+//! let edited = edit::insert_references(yaml, &[reference]).unwrap();
+//!
+//! assert!(edited.starts_with(yaml));
+//! assert!(edited.ends_with("\n  - type: book\n    title: New Book\n    authors:\n    - name: anonymous\n"));
+//! ```
+//!
+//! The edited text is read back, and an error is returned if it doesn't have
+//! the references expected.
+
+use std::ops::Range;
+
+use serde::de::Error as _;
+use yaml_rust2::{
+	parser::{Event, MarkedEventReceiver, Parser},
+	scanner::Marker,
+};
+
+use crate::{canonical, references::Reference, Error, Result};
+
+/// Append references to the `references` of a document.
+///
+/// If the document has a block sequence of references, the new ones are
+/// written after them, at the same indentation. Otherwise the `references` are
+/// written anew, or added at the end of the document.
+pub fn insert_references(yaml: &str, references: &[Reference]) -> Result<String> {
+	let mut expected = crate::from_str(yaml)?.references;
+	if references.is_empty() {
+		return Ok(yaml.into());
+	}
+	expected.extend_from_slice(references);
+
+	let layout = Layout::read(yaml)?;
+	let text = match &layout.references {
+		Some(Section {
+			range,
+			items: Some(indent),
+		}) => splice(yaml, range.end..range.end, &render(references, *indent)?),
+		Some(Section { range, items: None }) => {
+			splice(yaml, range.clone(), &section(&expected, layout.column)?)
+		}
+		None => splice(
+			yaml,
+			layout.end..layout.end,
+			&section(&expected, layout.column)?,
+		),
+	};
+
+	check(&text, &expected)?;
+	Ok(text)
+}
+
+/// Replace the `references` of a document.
+///
+/// The block sequence of references is kept at its indentation, if there's
+/// one. With no references, the `references` key is removed.
+pub fn replace_references(yaml: &str, references: &[Reference]) -> Result<String> {
+	crate::from_str(yaml)?;
+
+	let layout = Layout::read(yaml)?;
+	let text = match &layout.references {
+		Some(Section { range, .. }) if references.is_empty() => splice(yaml, range.clone(), ""),
+		Some(Section { range, items }) => {
+			let mut text = section(&[], layout.column)?;
+			text.push_str(&render(references, items.unwrap_or(layout.column))?);
+			splice(yaml, range.clone(), &text)
+		}
+		None if references.is_empty() => yaml.into(),
+		None => splice(
+			yaml,
+			layout.end..layout.end,
+			&section(references, layout.column)?,
+		),
+	};
+
+	check(&text, references)?;
+	Ok(text)
+}
+
+/// The `references` key with the references, or only the key if there are none.
+fn section(references: &[Reference], column: usize) -> Result<String> {
+	let mut text = format!("{:column$}references:\n", "");
+	if !references.is_empty() {
+		text.push_str(&render(references, column)?);
+	}
+	Ok(text)
+}
+
+/// References as a block sequence, indented, with keys in canonical order.
+fn render(references: &[Reference], indent: usize) -> Result<String> {
+	let values = references
+		.iter()
+		.map(canonical::reference_to_value)
+		.collect::<Result<Vec<_>>>()?;
+	let yaml = serde_yaml::to_string(&values)?;
+	Ok(yaml
+		.split_inclusive('\n')
+		.map(|line| {
+			if line.trim().is_empty() {
+				line.into()
+			} else {
+				format!("{:indent$}{line}", "")
+			}
+		})
+		.collect())
+}
+
+/// Replace a range of the text, starting on a new line.
+fn splice(yaml: &str, range: Range<usize>, with: &str) -> String {
+	let mut text = String::with_capacity(yaml.len() + with.len() + 1);
+	text.push_str(&yaml[..range.start]);
+	if !text.is_empty() && !text.ends_with('\n') && !with.is_empty() {
+		text.push('\n');
+	}
+	text.push_str(with);
+	text.push_str(&yaml[range.end..]);
+	text
+}
+
+fn check(text: &str, expected: &[Reference]) -> Result<()> {
+	if crate::from_str(text)?.references == expected {
+		Ok(())
+	} else {
+		Err(Error::custom(
+			"the edited document doesn't read back with the expected references",
+		))
+	}
+}
+
+/// Where things are in the text of a document, as byte offsets.
+#[derive(Debug)]
+struct Layout {
+	references: Option<Section>,
+
+	/// End of the last line of the last top-level key's value
+	end: usize,
+
+	/// Column of the top-level keys
+	column: usize,
+}
+
+#[derive(Debug)]
+struct Section {
+	/// The lines of the `references` key and its value
+	range: Range<usize>,
+
+	/// Indentation of the items, if the value is a block sequence
+	items: Option<usize>,
+}
+
+impl Layout {
+	fn read(yaml: &str) -> Result<Self> {
+		let mut keys = TopLevelKeys::default();
+		Parser::new_from_str(yaml)
+			.load(&mut keys, false)
+			.map_err(|err| Error::custom(err.to_string()))?;
+
+		let offset = |marker: Marker| {
+			yaml.char_indices()
+				.nth(marker.index())
+				.map_or(yaml.len(), |(offset, _)| offset)
+		};
+		if let Some((_, first)) = keys.keys.first() {
+			if yaml[..offset(*first)].trim_end().ends_with('{') {
+				return Err(Error::custom(
+					"documents in flow style, within braces, can't be edited in place",
+				));
+			}
+		}
+		let doc_end = keys.end.map_or(yaml.len(), offset);
+		let starts: Vec<usize> = keys
+			.keys
+			.iter()
+			.map(|(_, marker)| line_start(yaml, offset(*marker)))
+			.collect();
+		let range = |n: usize| {
+			let start = starts[n];
+			let end = starts.get(n + 1).copied().unwrap_or(doc_end);
+			start..content_end(yaml, start, end)
+		};
+
+		let references = keys
+			.keys
+			.iter()
+			.position(|(key, _)| key == "references")
+			.map(|n| {
+				let range = range(n);
+				let key = offset(keys.keys[n].1);
+				Section {
+					items: block_items(&yaml[key..range.end]),
+					range,
+				}
+			});
+
+		Ok(Self {
+			references,
+			end: match keys.keys.len() {
+				0 => doc_end,
+				len => range(len - 1).end,
+			},
+			column: keys.keys.first().map_or(0, |(_, marker)| marker.col()),
+		})
+	}
+}
+
+fn line_start(yaml: &str, offset: usize) -> usize {
+	yaml[..offset].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+/// End of the last line in the range which isn't blank or a comment at the
+/// start of a line, which are more likely to be about the next key.
+fn content_end(yaml: &str, start: usize, end: usize) -> usize {
+	let mut content_end = start;
+	let mut offset = start;
+	for line in yaml[start..end].split_inclusive('\n') {
+		offset += line.len();
+		let trimmed = line.trim_end();
+		if !(trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "...") {
+			content_end = offset;
+		}
+	}
+	content_end
+}
+
+/// Indentation of the items of the value, if it's a block sequence.
+///
+/// This is given the text from the key to the end of its value.
+fn block_items(text: &str) -> Option<usize> {
+	let mut lines = text.lines();
+	let (_, value) = lines.next()?.split_once(':')?;
+	let value = value.trim();
+	if !(value.is_empty() || value.starts_with('#')) {
+		return None;
+	}
+
+	let item = lines.find(|line| {
+		let line = line.trim();
+		!(line.is_empty() || line.starts_with('#'))
+	})?;
+	let trimmed = item.trim_start();
+	if trimmed == "-" || trimmed.starts_with("- ") {
+		Some(item.len() - trimmed.len())
+	} else {
+		None
+	}
+}
+
+/// The keys of the top-level mapping of the first document, with their positions.
+#[derive(Debug, Default)]
+struct TopLevelKeys {
+	keys: Vec<(String, Marker)>,
+	end: Option<Marker>,
+	depth: usize,
+	expect_key: bool,
+}
+
+impl TopLevelKeys {
+	/// Move on from a complete node in the top-level mapping.
+	fn advance(&mut self) {
+		if self.depth == 1 {
+			self.expect_key = !self.expect_key;
+		}
+	}
+}
+
+impl MarkedEventReceiver for TopLevelKeys {
+	fn on_event(&mut self, event: Event, mark: Marker) {
+		if self.end.is_some() {
+			return;
+		}
+
+		match event {
+			Event::Scalar(value, ..) => {
+				if self.depth == 1 && self.expect_key {
+					self.keys.push((value, mark));
+				}
+				self.advance();
+			}
+			Event::Alias(_) => self.advance(),
+			Event::MappingStart(..) | Event::SequenceStart(..) => {
+				if self.depth == 0 {
+					self.expect_key = true;
+				}
+				self.depth += 1;
+			}
+			Event::MappingEnd | Event::SequenceEnd => {
+				self.depth -= 1;
+				self.advance();
+			}
+			Event::DocumentEnd | Event::StreamEnd => self.end = Some(mark),
+			_ => {}
+		}
+	}
+}
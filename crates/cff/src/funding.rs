@@ -0,0 +1,78 @@
+//! Types and utilities for funding e.g. grants.
+//!
+//! Funding is an extension: it is not part of CFF 1.2.0. Where strict
+//! conformance is needed, funding can be expressed as references of type
+//! [`RefType::Grant`] instead, see [`Funding::to_reference`].
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+	names::{EntityName, Name},
+	references::{RefType, Reference},
+	Cff,
+};
+
+/// A source of funding for the work, e.g. a grant.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Funding {
+	/// The funder, e.g. a research council or foundation.
+	pub funder: EntityName,
+
+	/// The number or code of the award, as given by the funder.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub award_number: Option<String>,
+
+	/// The title of the award or funded project.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub award_title: Option<String>,
+
+	/// The URL of the award, e.g. on the funder's website.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub award_uri: Option<Url>,
+}
+
+impl Funding {
+	/// Express this funding as a reference of type [`RefType::Grant`].
+	///
+	/// The funder becomes the single author, the award title the title, the
+	/// award number the number, and the award URI the URL.
+	pub fn to_reference(&self) -> Reference {
+		Reference {
+			work_type: RefType::Grant,
+			authors: vec![Name::Entity(self.funder.clone())],
+			title: self.award_title.clone(),
+			number: self.award_number.clone(),
+			url: self.award_uri.clone(),
+			..Default::default()
+		}
+	}
+
+	/// Read funding from a reference of type [`RefType::Grant`].
+	///
+	/// This is the inverse of [`Funding::to_reference`]. Returns `None` if the
+	/// reference isn't a grant, or if its first author isn't an entity.
+	pub fn from_reference(reference: &Reference) -> Option<Self> {
+		if reference.work_type != RefType::Grant {
+			return None;
+		}
+
+		let funder = reference.authors.first()?.as_entity()?.clone();
+		Some(Self {
+			funder,
+			award_number: reference.number.clone(),
+			award_title: reference.title.clone(),
+			award_uri: reference.url.clone(),
+		})
+	}
+}
+
+impl Cff {
+	/// All funding of the work expressed as grant references.
+	///
+	/// See [`Funding::to_reference`].
+	pub fn grant_references(&self) -> Vec<Reference> {
+		self.funding.iter().map(Funding::to_reference).collect()
+	}
+}
@@ -0,0 +1,146 @@
+//! Translations of validation messages.
+//!
+//! Messages are [Fluent] catalogs, with one message per [Rule], named after the
+//! rule, and the [args](ValidationIssue::args) of issues as variables. English
+//! is built in, and other catalogs can be loaded at runtime. Messages missing
+//! from a catalog are left in English.
+//!
+//! ```
+//! use citeworks_cff::{
+//!     i18n::Catalog,
+//!     references::{RefType, Reference},
+//! };
+//!
+//! let catalog = Catalog::new("fr", "
+//! thesis-institution = une thèse devrait avoir un établissement
+//! ").unwrap();
+//!
+//! let thesis = Reference {
+//!     work_type: RefType::Thesis,
+//!     ..Default::default()
+//! };
+//! let issues = thesis.validate();
+//! assert_eq!(catalog.message(&issues[0]), "une thèse devrait avoir un établissement");
+//! assert_eq!(catalog.message(&issues[1]), "thesis should have a thesis type");
+//! ```
+//!
+//! To add a built-in language, translate `locales/en/validation.ftl` into
+//! `locales/<language>/validation.ftl`, and add it to the list in this module.
+//!
+//! [Fluent]: https://projectfluent.org
+
+use std::fmt;
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+use crate::validation::{Rule, ValidationIssue};
+
+/// Built-in catalogs, by language.
+const CATALOGS: &[(&str, &str)] = &[("en", include_str!("../locales/en/validation.ftl"))];
+
+/// Validation messages in one language.
+pub struct Catalog {
+	locale: LanguageIdentifier,
+	bundle: FluentBundle<FluentResource>,
+}
+
+impl fmt::Debug for Catalog {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Catalog")
+			.field("locale", &self.locale.to_string())
+			.finish_non_exhaustive()
+	}
+}
+
+impl Catalog {
+	/// The built-in English messages.
+	pub fn english() -> Self {
+		Self::new("en", CATALOGS[0].1).expect("the built-in catalog is valid")
+	}
+
+	/// Load a catalog of Fluent messages for a locale, e.g. `fr` or `pt-BR`.
+	pub fn new(locale: &str, ftl: &str) -> Result<Self, String> {
+		let locale: LanguageIdentifier = locale
+			.parse()
+			.map_err(|err| format!("invalid locale {locale:?}: {err}"))?;
+		let resource = FluentResource::try_new(ftl.into()).map_err(|(_, errors)| {
+			format!(
+				"invalid catalog: {}",
+				errors
+					.iter()
+					.map(ToString::to_string)
+					.collect::<Vec<_>>()
+					.join(", ")
+			)
+		})?;
+
+		let mut bundle = FluentBundle::new(vec![locale.clone()]);
+		// Unicode isolation marks around variables show up in terminals.
+		bundle.set_use_isolating(false);
+		bundle
+			.add_resource(resource)
+			.map_err(|errors| format!("invalid catalog: {errors:?}"))?;
+		Ok(Self { locale, bundle })
+	}
+
+	/// The built-in catalog for a locale, or English if there isn't one.
+	///
+	/// POSIX locales, as in `LANG`, are accepted, e.g. `de_CH.UTF-8`.
+	pub fn for_locale(locale: &str) -> Self {
+		let locale = locale
+			.split('.')
+			.next()
+			.unwrap_or_default()
+			.replace('_', "-");
+		let language = locale
+			.parse::<LanguageIdentifier>()
+			.map(|id| id.language.to_string())
+			.unwrap_or_default();
+		CATALOGS
+			.iter()
+			.find(|(name, _)| *name == language)
+			.and_then(|(name, ftl)| Self::new(name, ftl).ok())
+			.unwrap_or_else(Self::english)
+	}
+
+	/// The locale of the catalog.
+	pub fn locale(&self) -> String {
+		self.locale.to_string()
+	}
+
+	/// Whether the catalog has the message of a rule.
+	pub fn contains(&self, rule: Rule) -> bool {
+		self.bundle.has_message(rule.name())
+	}
+
+	/// The message of an issue, translated.
+	///
+	/// If the catalog doesn't have the message, or it can't be formatted, the
+	/// English message of the issue is returned.
+	pub fn message(&self, issue: &ValidationIssue) -> String {
+		let pattern = match self
+			.bundle
+			.get_message(issue.rule.name())
+			.and_then(|message| message.value())
+		{
+			Some(pattern) => pattern,
+			None => return issue.message.clone(),
+		};
+
+		let mut args = FluentArgs::new();
+		for (name, value) in &issue.args {
+			args.set(name.as_str(), value.as_str());
+		}
+
+		let mut errors = Vec::new();
+		let message = self
+			.bundle
+			.format_pattern(pattern, Some(&args), &mut errors);
+		if errors.is_empty() {
+			message.into_owned()
+		} else {
+			issue.message.clone()
+		}
+	}
+}
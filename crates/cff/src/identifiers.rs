@@ -1,8 +1,25 @@
-//! Types and utilities for identifiers e.g. DOIs.
+//! Types and utilities for identifiers e.g. DOIs, ISBNs, and ISSNs.
+//!
+//! ```
+//! use citeworks_cff::identifiers::{Identifier, IdentifierKind};
+//!
+//! let id = Identifier::Swh {
+//!     value: "swh:1:dir:bc286860f423ea7ced246ba7458eef4b4541cf2d".into(),
+//!     description: Some("Source code archive".into()),
+//! };
+//!
+//! assert_eq!(id.kind(), IdentifierKind::Swh);
+//! assert_eq!(id.value(), "swh:1:dir:bc286860f423ea7ced246ba7458eef4b4541cf2d");
+//! assert_eq!(id.description(), Some("Source code archive"));
+//! ```
 
-use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use url::Url;
 
+use crate::{references::Reference, Cff};
+
 /// An identifier for a work.
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "kebab-case")]
@@ -57,3 +74,320 @@ pub enum Identifier {
 		description: Option<String>,
 	},
 }
+
+/// The kind of an [Identifier], without its value.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdentifierKind {
+	/// DOI
+	Doi,
+
+	/// URL
+	Url,
+
+	/// Software Heritage identifier
+	Swh,
+
+	/// Some other identifier.
+	Other,
+}
+
+impl Identifier {
+	/// The kind of identifier this is.
+	pub fn kind(&self) -> IdentifierKind {
+		match self {
+			Self::Doi { .. } => IdentifierKind::Doi,
+			Self::Url { .. } => IdentifierKind::Url,
+			Self::Swh { .. } => IdentifierKind::Swh,
+			Self::Other { .. } => IdentifierKind::Other,
+		}
+	}
+
+	/// The value of the identifier, as a string.
+	pub fn value(&self) -> &str {
+		match self {
+			Self::Doi { value, .. } | Self::Swh { value, .. } | Self::Other { value, .. } => value,
+			Self::Url { value, .. } => value.as_str(),
+		}
+	}
+
+	/// The description of the identifier, if any.
+	pub fn description(&self) -> Option<&str> {
+		match self {
+			Self::Doi { description, .. }
+			| Self::Url { description, .. }
+			| Self::Swh { description, .. }
+			| Self::Other { description, .. } => description.as_deref(),
+		}
+	}
+}
+
+impl Cff {
+	/// Find the first identifier of the given kind.
+	///
+	/// Note that this only looks in `identifiers`, not at the `doi` field.
+	pub fn identifier_of_kind(&self, kind: IdentifierKind) -> Option<&Identifier> {
+		self.identifiers_of_kind(kind).next()
+	}
+
+	/// Iterate over all identifiers of the given kind.
+	pub fn identifiers_of_kind(
+		&self,
+		kind: IdentifierKind,
+	) -> impl Iterator<Item = &Identifier> + '_ {
+		self.identifiers.iter().filter(move |id| id.kind() == kind)
+	}
+}
+
+impl Reference {
+	/// Find the first identifier of the given kind.
+	pub fn identifier_of_kind(&self, kind: IdentifierKind) -> Option<&Identifier> {
+		self.identifiers_of_kind(kind).next()
+	}
+
+	/// Iterate over all identifiers of the given kind.
+	pub fn identifiers_of_kind(
+		&self,
+		kind: IdentifierKind,
+	) -> impl Iterator<Item = &Identifier> + '_ {
+		self.identifiers.iter().filter(move |id| id.kind() == kind)
+	}
+}
+
+/// Check that a DOI is bare and has a well-formed prefix.
+///
+/// That is, `10.` followed by a registrant code of four to nine digits,
+/// optionally with dot-separated subdivisions, then `/` and a suffix. The
+/// suffix can be anything but empty.
+pub fn validate_doi(doi: &str) -> Result<(), String> {
+	let (prefix, suffix) = doi.split_once('/').ok_or_else(|| {
+		format!("DOI should be a prefix and a suffix separated by /, got: {doi:?}")
+	})?;
+	let registrant = prefix
+		.strip_prefix("10.")
+		.ok_or_else(|| format!("DOI should start with 10., got: {doi:?}"))?;
+
+	let mut parts = registrant.split('.');
+	let code = parts.next().unwrap_or_default();
+	let well_formed = (4..=9).contains(&code.len())
+		&& code.bytes().all(|c| c.is_ascii_digit())
+		&& parts.all(|part| !part.is_empty() && part.bytes().all(|c| c.is_ascii_digit()));
+	if !well_formed {
+		return Err(format!(
+			"DOI registrant code should be four to nine digits, got: {prefix:?}"
+		));
+	}
+
+	if suffix.is_empty() {
+		return Err(format!("DOI should have a suffix, got: {doi:?}"));
+	}
+
+	Ok(())
+}
+
+macro_rules! standard_number {
+	($(#[doc = $doc:literal])+ $name:ident, $validate:ident) => {
+		$(#[doc = $doc])+
+		#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+		pub struct $name(String);
+
+		impl $name {
+			/// The value, as written.
+			pub fn as_str(&self) -> &str {
+				&self.0
+			}
+
+			/// Check the value, see [$validate].
+			pub fn validate(&self) -> Result<(), String> {
+				$validate(&self.0).map(drop)
+			}
+
+			/// Whether the value is well-formed, with a correct check digit.
+			pub fn is_valid(&self) -> bool {
+				self.validate().is_ok()
+			}
+
+			/// The value in its normal form, or `None` if it's not valid.
+			pub fn normalized(&self) -> Option<String> {
+				$validate(&self.0).ok()
+			}
+		}
+
+		impl FromStr for $name {
+			type Err = String;
+
+			/// Parse and normalize a value, failing if it's not valid.
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				$validate(s).map(Self)
+			}
+		}
+
+		/// Wrap a value without checking it, e.g. a historical malformed one.
+		impl From<String> for $name {
+			fn from(value: String) -> Self {
+				Self(value)
+			}
+		}
+
+		/// Wrap a value without checking it, e.g. a historical malformed one.
+		impl From<&str> for $name {
+			fn from(value: &str) -> Self {
+				Self(value.into())
+			}
+		}
+
+		impl From<$name> for String {
+			fn from(value: $name) -> Self {
+				value.0
+			}
+		}
+
+		impl Display for $name {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				f.write_str(&self.0)
+			}
+		}
+
+		impl Serialize for $name {
+			fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.serialize_str(&self.0)
+			}
+		}
+
+		impl<'de> Deserialize<'de> for $name {
+			fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				String::deserialize(deserializer).map(Self)
+			}
+		}
+	};
+}
+
+standard_number! {
+	/// An [ISBN], for a book.
+	///
+	/// Values read from documents are kept as written, even if they're not
+	/// valid, so that files with historical malformed ISBNs can still be read
+	/// and written back unchanged; they're reported by validation instead. To
+	/// check a value when making one, parse it:
+	///
+	/// ```
+	/// use citeworks_cff::identifiers::Isbn;
+	///
+	/// let isbn: Isbn = "978-0-306-40615-7".parse().unwrap();
+	/// assert_eq!(isbn.as_str(), "9780306406157");
+	///
+	/// assert!("978-0-306-40615-8".parse::<Isbn>().is_err());
+	///
+	/// let old = Isbn::from("0-306-4061");
+	/// assert!(!old.is_valid());
+	/// ```
+	///
+	/// [ISBN]: https://en.wikipedia.org/wiki/International_Standard_Book_Number
+	Isbn, validate_isbn
+}
+
+standard_number! {
+	/// An [ISSN], for a periodical.
+	///
+	/// As with [Isbn], values read from documents are kept as written:
+	///
+	/// ```
+	/// use citeworks_cff::identifiers::Issn;
+	///
+	/// let issn: Issn = "0378 5955".parse().unwrap();
+	/// assert_eq!(issn.as_str(), "0378-5955");
+	/// assert_eq!(Issn::from("03785955").normalized().as_deref(), Some("0378-5955"));
+	/// ```
+	///
+	/// [ISSN]: https://en.wikipedia.org/wiki/International_Standard_Serial_Number
+	Issn, validate_issn
+}
+
+/// The digits of a standard number, without a label like `ISBN-13:`, hyphens,
+/// or spaces, and with `x` check digits uppercased.
+fn compact(value: &str, label: &str) -> String {
+	let mut value = value.trim();
+	if let Some(rest) = value
+		.get(..label.len())
+		.filter(|start| start.eq_ignore_ascii_case(label))
+		.map(|_| &value[label.len()..])
+	{
+		value = rest
+			.strip_prefix("-10")
+			.or_else(|| rest.strip_prefix("-13"))
+			.unwrap_or(rest)
+			.trim_start()
+			.trim_start_matches(':');
+	}
+
+	value
+		.chars()
+		.filter(|c| *c != '-' && !c.is_whitespace())
+		.map(|c| c.to_ascii_uppercase())
+		.collect()
+}
+
+/// Check that an ISBN is well-formed, and return it in its compact form.
+///
+/// ISBNs are 10 digits, the last of which may be `X`, or 13 digits starting
+/// with 978 or 979, with a correct check digit. Hyphens, spaces, and an `ISBN`
+/// label are ignored, and left out of the compact form.
+pub fn validate_isbn(isbn: &str) -> Result<String, String> {
+	let digits = compact(isbn, "ISBN");
+
+	let valid = match digits.len() {
+		10 => {
+			let sum = digits.chars().enumerate().try_fold(0, |sum, (i, c)| {
+				let value = match c {
+					'X' if i == 9 => 10,
+					c => c.to_digit(10)?,
+				};
+				Some(sum + value * (10 - i as u32))
+			});
+			sum.map(|sum| sum % 11 == 0)
+		}
+		13 if digits.starts_with("978") || digits.starts_with("979") => {
+			let sum = digits.chars().enumerate().try_fold(0, |sum, (i, c)| {
+				Some(sum + c.to_digit(10)? * if i % 2 == 0 { 1 } else { 3 })
+			});
+			sum.map(|sum| sum % 10 == 0)
+		}
+		_ => {
+			return Err(format!(
+				"ISBN should be 10 digits, or 13 digits starting with 978 or 979, got: {isbn:?}"
+			))
+		}
+	};
+
+	match valid {
+		Some(true) => Ok(digits),
+		Some(false) => Err(format!("ISBN check digit is wrong, got: {isbn:?}")),
+		None => Err(format!("ISBN should only have digits, got: {isbn:?}")),
+	}
+}
+
+/// Check that an ISSN is well-formed, and return it in its normal form.
+///
+/// ISSNs are 8 digits, the last of which may be `X`, with a correct check
+/// digit. The normal form has a hyphen in the middle, as in `0378-5955`.
+/// Spaces and an `ISSN` label are ignored.
+pub fn validate_issn(issn: &str) -> Result<String, String> {
+	let digits = compact(issn, "ISSN");
+	if digits.len() != 8 {
+		return Err(format!("ISSN should be 8 digits, got: {issn:?}"));
+	}
+
+	let sum = digits.chars().enumerate().try_fold(0, |sum, (i, c)| {
+		let value = match c {
+			'X' if i == 7 => 10,
+			c => c.to_digit(10)?,
+		};
+		Some(sum + value * (8 - i as u32))
+	});
+
+	match sum {
+		Some(sum) if sum % 11 == 0 => Ok(format!("{}-{}", &digits[..4], &digits[4..])),
+		Some(_) => Err(format!("ISSN check digit is wrong, got: {issn:?}")),
+		None => Err(format!("ISSN should only have digits, got: {issn:?}")),
+	}
+}
@@ -0,0 +1,280 @@
+//! Revalidating documents as they're edited, for editor integrations.
+//!
+//! An editor validating a CITATION.cff as it's typed in reparses it on every
+//! change, but doesn't need to run every rule again: a change to a reference
+//! only changes the issues of that reference. [IncrementalValidator] keeps the
+//! issues of the document and of each reference apart, and given the path of
+//! what an edit changed, e.g. `references[3].title`, only revalidates that.
+//!
+//! Documents are read leniently, like [from_str_lenient](crate::from_str_lenient),
+//! so a value that can't be read yet, like a half-typed date, is a parse error
+//! but doesn't stop the rest from being validated. Issues and parse errors can
+//! be placed in the text with [IncrementalValidator::position], and what's
+//! under the cursor found with [IncrementalValidator::path_at].
+//!
+//! ```
+//! use citeworks_cff::{incremental::IncrementalValidator, validation::Rule};
+//!
+//! let text = "
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! authors:
+//!   - name: Research Software Lab
+//! references:
+//!   - type: thesis
+//!     title: A thesis
+//!     authors: []
+//! ";
+//! let mut validator = IncrementalValidator::new(text, Default::default());
+//! assert_eq!(validator.issues()[0].path, "references[0].institution");
+//!
+//! // The editor inserts the institution after the authors of the thesis.
+//! let path = validator.path_at(text.find("authors: []").unwrap());
+//! assert_eq!(path, "references[0].authors");
+//! let edited = text.replace(
+//!     "    authors: []\n",
+//!     "    authors: []\n    institution:\n      name: Victoria University of Wellington\n",
+//! );
+//!
+//! validator.edit(&edited, &path);
+//! let issues = validator.issues();
+//! assert_eq!(issues.len(), 1);
+//! assert_eq!(issues[0].rule, Rule::ThesisType);
+//!
+//! // The thesis type is missing, so it's placed at the reference.
+//! let position = validator.position(&issues[0].path);
+//! assert_eq!((position.line, position.column), (8, 5));
+//! ```
+
+use crate::{
+	from_str_lenient,
+	positions::{positions, Positions},
+	validation::{
+		validate_document, validate_nth_reference, ValidationIssue, ValidationOptions, Validator,
+	},
+	Cff, ParseError,
+};
+
+/// Where something is in the text of a document.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Position {
+	/// Line, starting at 1.
+	pub line: usize,
+
+	/// Column, starting at 1.
+	pub column: usize,
+
+	/// Byte offset in the text.
+	pub offset: usize,
+
+	/// Length in bytes of the key of the value, or 0 if it's not at its key.
+	pub len: usize,
+}
+
+/// Validates a document, then revalidates what's changed as it's edited.
+///
+/// After each edit, [issues](Self::issues) are the same as validating the
+/// whole document with [Cff::validate_with], provided the edit's path covers
+/// everything that changed.
+#[derive(Debug)]
+pub struct IncrementalValidator {
+	options: ValidationOptions,
+	text: String,
+	positions: Option<Positions>,
+	cff: Option<Cff>,
+	parse_errors: Vec<ParseError>,
+	document: Vec<ValidationIssue>,
+	references: Vec<Vec<ValidationIssue>>,
+}
+
+impl IncrementalValidator {
+	/// Read and validate a document.
+	pub fn new(text: &str, options: ValidationOptions) -> Self {
+		let mut validator = Self {
+			options,
+			text: String::new(),
+			positions: None,
+			cff: None,
+			parse_errors: Vec::new(),
+			document: Vec::new(),
+			references: Vec::new(),
+		};
+		validator.edit(text, "");
+		validator
+	}
+
+	/// Read the edited text of the document, and revalidate what changed.
+	///
+	/// The path is of the value the edit was in, as in issues, e.g.
+	/// `references[3].title`, or empty to revalidate everything. Edits within
+	/// a reference only revalidate that reference, unless references were
+	/// added or removed. Other edits revalidate everything but the references.
+	pub fn edit(&mut self, text: &str, path: &str) {
+		let previous = self.cff.take();
+		let had_reference_errors = self.parse_errors.iter().any(in_references);
+
+		self.text = text.into();
+		self.positions = positions(text).ok();
+		let cff = match from_str_lenient(text) {
+			Ok((cff, errors)) => {
+				self.parse_errors = errors;
+				cff
+			}
+			Err(err) => {
+				// Nothing can be validated until the document can be read.
+				self.parse_errors = vec![err];
+				self.document.clear();
+				self.references.clear();
+				return;
+			}
+		};
+
+		// References left out because they couldn't be read shift the
+		// references after them, so they're all revalidated.
+		let same_references = previous.as_ref().map_or(false, |previous| {
+			previous.references.len() == cff.references.len()
+		}) && !had_reference_errors
+			&& !self.parse_errors.iter().any(in_references);
+
+		match reference_index(path) {
+			Some(n) if same_references && n < cff.references.len() => {
+				self.references[n] = self.validate(|v| {
+					validate_nth_reference(v, n, &cff.references[n]);
+				});
+			}
+			_ if same_references && !path.is_empty() && !path.starts_with("references") => {
+				self.document = self.validate(|v| validate_document(v, &cff));
+			}
+			_ => {
+				self.document = self.validate(|v| validate_document(v, &cff));
+				self.references = cff
+					.references
+					.iter()
+					.enumerate()
+					.map(|(n, reference)| {
+						self.validate(|v| validate_nth_reference(v, n, reference))
+					})
+					.collect();
+			}
+		}
+		self.cff = Some(cff);
+	}
+
+	fn validate(&self, f: impl FnOnce(&mut Validator<'_>)) -> Vec<ValidationIssue> {
+		let mut validator = Validator::new(&self.options);
+		f(&mut validator);
+		validator.finish()
+	}
+
+	/// The text of the document, as of the last edit.
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+
+	/// The document as read, or `None` if it can't be read at all.
+	pub fn cff(&self) -> Option<&Cff> {
+		self.cff.as_ref()
+	}
+
+	/// Values that couldn't be read, or why the document couldn't be.
+	pub fn parse_errors(&self) -> &[ParseError] {
+		&self.parse_errors
+	}
+
+	/// The issues of the document, in the order [Cff::validate_with] has them.
+	pub fn issues(&self) -> Vec<&ValidationIssue> {
+		self.document
+			.iter()
+			.chain(self.references.iter().flatten())
+			.collect()
+	}
+
+	/// Where a value is in the text, e.g. of an issue's path.
+	///
+	/// Values that aren't in the text, like a missing field, are placed at
+	/// their closest parent that is, or the start of the document.
+	pub fn position(&self, path: &str) -> Position {
+		let keys = keys(path);
+		let marker = self
+			.positions
+			.as_ref()
+			.and_then(|positions| positions.find(&keys));
+		let marker = match marker {
+			Some(marker) => marker,
+			None => {
+				return Position {
+					line: 1,
+					column: 1,
+					offset: 0,
+					len: 0,
+				}
+			}
+		};
+
+		let offset = self
+			.text
+			.char_indices()
+			.nth(marker.index())
+			.map_or(self.text.len(), |(offset, _)| offset);
+		let len = keys
+			.last()
+			.filter(|key| self.text[offset..].starts_with(key.as_str()))
+			.map_or(0, String::len);
+		Position {
+			line: marker.line(),
+			column: marker.col() + 1,
+			offset,
+			len,
+		}
+	}
+
+	/// Path of the innermost value at or before a byte offset in the text.
+	///
+	/// This is what an edit at the offset is in, e.g. to give to
+	/// [edit](Self::edit). It's empty before the first value, or if the text
+	/// isn't YAML.
+	pub fn path_at(&self, offset: usize) -> String {
+		let index = self.text[..offset.min(self.text.len())].chars().count();
+		self.positions
+			.as_ref()
+			.and_then(|positions| positions.path_at(index))
+			.map(display)
+			.unwrap_or_default()
+	}
+}
+
+fn in_references(err: &ParseError) -> bool {
+	err.path.starts_with("references")
+}
+
+/// Index of the reference a path is in, e.g. 3 for `references[3].title`.
+fn reference_index(path: &str) -> Option<usize> {
+	let rest = path.strip_prefix("references[")?;
+	let (index, _) = rest.split_once(']')?;
+	index.parse().ok()
+}
+
+/// Keys of a path, e.g. `references`, `3`, `title` for `references[3].title`.
+fn keys(path: &str) -> Vec<String> {
+	path.split(['.', '['])
+		.filter(|key| !key.is_empty())
+		.map(|key| key.trim_end_matches(']').to_string())
+		.collect()
+}
+
+/// Path of keys as written in issues, e.g. `references[3].title`.
+fn display(keys: &[String]) -> String {
+	let mut path = String::new();
+	for key in keys {
+		if key.bytes().all(|b| b.is_ascii_digit()) {
+			path.push_str(&format!("[{key}]"));
+		} else {
+			if !path.is_empty() {
+				path.push('.');
+			}
+			path.push_str(key);
+		}
+	}
+	path
+}
@@ -0,0 +1,161 @@
+//! Utilities for keywords e.g. normalisation and controlled vocabularies.
+//!
+//! ```
+//! use citeworks_cff::keywords::{normalize, Vocabulary};
+//!
+//! let keywords = normalize(["Machine  Learning ", "machine learning", "ML", "Rust"]);
+//! assert_eq!(keywords, vec!["machine learning", "ml", "rust"]);
+//!
+//! let vocabulary = Vocabulary::from_yaml_str(r#"
+//! Machine learning: [ML, statistical learning]
+//! Programming languages:
+//! "#).unwrap();
+//!
+//! let mapping = vocabulary.map(&keywords);
+//! assert_eq!(mapping.mapped, vec!["Machine learning"]);
+//! assert_eq!(mapping.unmapped, vec!["rust"]);
+//! ```
+
+use std::{
+	collections::{BTreeMap, HashSet},
+	io::Read,
+};
+
+use crate::Cff;
+
+/// Normalise a single keyword.
+///
+/// This trims it, collapses inner whitespace to single spaces, and lowercases
+/// it. The result may be empty.
+pub fn normalize_one(keyword: &str) -> String {
+	keyword
+		.split_whitespace()
+		.collect::<Vec<_>>()
+		.join(" ")
+		.to_lowercase()
+}
+
+/// Normalise a list of keywords.
+///
+/// Each keyword is normalised with [normalize_one], then empty keywords and
+/// duplicates are removed. The order of first appearance is preserved.
+pub fn normalize<I, S>(keywords: I) -> Vec<String>
+where
+	I: IntoIterator<Item = S>,
+	S: AsRef<str>,
+{
+	let mut seen = HashSet::new();
+	keywords
+		.into_iter()
+		.map(|k| normalize_one(k.as_ref()))
+		.filter(|k| !k.is_empty() && seen.insert(k.clone()))
+		.collect()
+}
+
+/// A controlled vocabulary (or thesaurus) of preferred terms.
+///
+/// Each preferred term may have synonyms. Keywords are matched against both
+/// the preferred terms and their synonyms, after normalisation.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Vocabulary {
+	/// Normalised term or synonym → preferred term.
+	lookup: BTreeMap<String, String>,
+}
+
+/// The result of mapping keywords against a [Vocabulary].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct VocabularyMapping {
+	/// The preferred terms that keywords mapped to, deduplicated.
+	pub mapped: Vec<String>,
+
+	/// The (normalised) keywords that didn't match any term.
+	pub unmapped: Vec<String>,
+}
+
+impl Vocabulary {
+	/// Create an empty vocabulary.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Add a preferred term along with its synonyms.
+	///
+	/// If a synonym was already present, it now points to this term instead.
+	pub fn insert<I, S>(&mut self, preferred: &str, synonyms: I)
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		let preferred = preferred.trim().to_string();
+		self.lookup
+			.insert(normalize_one(&preferred), preferred.clone());
+		for synonym in synonyms {
+			self.lookup
+				.insert(normalize_one(synonym.as_ref()), preferred.clone());
+		}
+	}
+
+	/// Read a vocabulary from a YAML string.
+	///
+	/// The YAML must be a mapping of preferred terms to lists of synonyms. A
+	/// term with no synonyms may have an empty list or no value at all.
+	pub fn from_yaml_str(s: &str) -> serde_yaml::Result<Self> {
+		Ok(Self::from_terms(serde_yaml::from_str(s)?))
+	}
+
+	/// Read a vocabulary from a YAML stream.
+	///
+	/// See [Vocabulary::from_yaml_str] for the format.
+	pub fn from_yaml_reader<R: Read>(rdr: R) -> serde_yaml::Result<Self> {
+		Ok(Self::from_terms(serde_yaml::from_reader(rdr)?))
+	}
+
+	fn from_terms(terms: BTreeMap<String, Option<Vec<String>>>) -> Self {
+		let mut vocabulary = Self::new();
+		for (preferred, synonyms) in terms {
+			vocabulary.insert(&preferred, synonyms.unwrap_or_default());
+		}
+		vocabulary
+	}
+
+	/// Look up the preferred term for a keyword.
+	pub fn lookup(&self, keyword: &str) -> Option<&str> {
+		self.lookup.get(&normalize_one(keyword)).map(|s| s.as_str())
+	}
+
+	/// Map keywords to preferred terms.
+	pub fn map<I, S>(&self, keywords: I) -> VocabularyMapping
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		let mut mapping = VocabularyMapping::default();
+		for keyword in normalize(keywords) {
+			match self.lookup(&keyword) {
+				Some(term) => {
+					if !mapping.mapped.iter().any(|m| m == term) {
+						mapping.mapped.push(term.to_string());
+					}
+				}
+				None => mapping.unmapped.push(keyword),
+			}
+		}
+		mapping
+	}
+}
+
+impl Cff {
+	/// Normalise the keywords of the work and of all its references in place.
+	///
+	/// See [normalize] for details.
+	pub fn normalize_keywords(&mut self) {
+		self.keywords = normalize(&self.keywords);
+		for reference in self
+			.preferred_citation
+			.iter_mut()
+			.chain(self.references.iter_mut())
+		{
+			reference.keywords = normalize(&reference.keywords);
+		}
+	}
+}
@@ -2,7 +2,8 @@
 //!
 //! This targets CFF 1.2.0 but may not support the entire specification.
 //!
-//! The top level API mimics [serde_yaml]'s:
+//! The top level API mimics [serde_yaml]'s, with `from_str`, `from_slice`, and
+//! `from_reader` to parse, and `to_string`, `to_vec`, and `to_writer` to write:
 //!
 //! ```
 //! let cff = citeworks_cff::from_str(r#"
@@ -40,6 +41,12 @@
 
 use std::io::{Read, Write};
 
+/// The error when deserializing or serializing CFF fails.
+///
+/// This is [serde_yaml]'s error, which has the location of parse errors.
+pub use serde_yaml::Error;
+
+/// Alias for a `Result` with the error type [Error].
 pub use serde_yaml::Result;
 
 #[doc(inline)]
@@ -47,14 +54,42 @@ pub use cff::{Cff, WorkType};
 #[doc(inline)]
 pub use date::Date;
 #[doc(inline)]
+pub use duration::Duration;
+#[doc(inline)]
 pub use license::License;
+#[doc(inline)]
+pub use located::{from_str_lenient, from_str_located, ParseError};
+#[doc(inline)]
+pub use partial_dates::PartialDate;
 
+pub mod builder;
+mod canonical;
 mod cff;
 mod date;
+mod duration;
+pub mod edit;
+pub mod funding;
+#[cfg(feature = "i18n")]
+pub mod i18n;
 pub mod identifiers;
+pub mod incremental;
+pub mod keywords;
 mod license;
+mod located;
 pub mod names;
+pub mod partial_dates;
+mod positions;
+pub mod redact;
 pub mod references;
+pub mod repositories;
+#[cfg(feature = "schema")]
+pub mod schema;
+pub mod signature;
+pub mod template;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+pub mod unicode;
+pub mod validation;
 
 /// Deserialize CFF from an IO stream of YAML.
 pub fn from_reader<R>(rdr: R) -> Result<Cff>
@@ -91,3 +126,21 @@ where
 {
 	serde_yaml::to_writer(writer, value)
 }
+
+/// Serialize the given CFF as a String of YAML, in a canonical layout.
+///
+/// Keys are written in the order of the specification's guide, from
+/// `cff-version` and `message` to `preferred-citation` and `references`, with
+/// unknown keys last in alphabetical order; references are sorted by first
+/// author, year, and title. Files generated this way only change where the
+/// metadata does, so they make minimal diffs.
+pub fn to_string_canonical(value: &Cff) -> Result<String> {
+	serde_yaml::to_string(&canonical::to_value(value)?)
+}
+
+/// Serialize the given CFF as a YAML byte vector, in a canonical layout.
+///
+/// See [to_string_canonical].
+pub fn to_vec_canonical(value: &Cff) -> Result<Vec<u8>> {
+	to_string_canonical(value).map(|v| v.into_bytes())
+}
@@ -1,4 +1,8 @@
-use std::hash::Hash;
+use std::{
+	fmt::{self, Display},
+	hash::Hash,
+	str::FromStr,
+};
 
 use serde::{Deserialize, Serialize};
 use spdx::Expression;
@@ -13,45 +17,135 @@ use spdx::Expression;
 /// as to keep the format of the original document. However, the
 /// [`License::to_expression`] method does this for convenience.
 ///
+/// Values which aren't valid SPDX expressions, like `Proprietary` or legacy
+/// identifiers, are kept as written in the `Unparsed` variants, so documents
+/// with them can still be read, edited, and written back. Validation raises
+/// them as [LicenseFormat](crate::validation::Rule::LicenseFormat) errors.
+///
 /// Note that `Hash`, `PartialEq`, and `Eq` are implemented in term of the
 /// original strings for the expression. That is, the list of `Apache-2.0` and
 /// `MIT` may not be equal or hash to the same as `Apache-2.0 OR MIT`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged, try_from = "ExprInternal", into = "ExprInternal")]
+#[serde(untagged, from = "ExprInternal", into = "ExprInternal")]
 pub enum License {
 	/// A single SPDX license expression.
 	Single(Box<Expression>),
 
 	/// A set of SPDX license expressions (interpreted as joined by `OR`).
 	AnyOf(Vec<Expression>),
+
+	/// A single license which isn't a valid SPDX expression, as written.
+	Unparsed(String),
+
+	/// A set of licenses of which at least one isn't a valid SPDX expression,
+	/// all as written.
+	UnparsedAnyOf(Vec<String>),
 }
 
 impl License {
 	/// Get a single SPDX expression for this License value.
+	///
+	/// # Panics
+	///
+	/// Panics for the `Unparsed` variants; use [`License::try_to_expression`]
+	/// for licenses which may not be valid SPDX expressions.
 	pub fn to_expression(&self) -> Expression {
+		self.try_to_expression()
+			.expect("license is not a valid SPDX expression")
+	}
+
+	/// Get a single SPDX expression for this License value, if it has one.
+	///
+	/// Returns `None` for the `Unparsed` variants.
+	pub fn try_to_expression(&self) -> Option<Expression> {
 		match self {
-			Self::Single(exp) => *exp.clone(),
-			Self::AnyOf(exps) => Expression::parse(
-				&exps
-					.iter()
-					.map(|exp| format!("({exp})"))
-					.collect::<Vec<_>>()
-					.join(" OR "),
-			)
-			.expect("if the original expressions parsed, this one will too"),
+			Self::Single(exp) => Some(*exp.clone()),
+			Self::AnyOf(exps) => Some(
+				Expression::parse(
+					&exps
+						.iter()
+						.map(|exp| format!("({exp})"))
+						.collect::<Vec<_>>()
+						.join(" OR "),
+				)
+				.expect("if the original expressions parsed, this one will too"),
+			),
+			Self::Unparsed(_) | Self::UnparsedAnyOf(_) => None,
+		}
+	}
+
+	/// Whether this is made of valid SPDX expressions.
+	pub fn is_spdx(&self) -> bool {
+		matches!(self, Self::Single(_) | Self::AnyOf(_))
+	}
+
+	/// The expressions or licenses of this value, as strings: one for a single
+	/// license, or one for each of a set.
+	pub fn members(&self) -> Vec<String> {
+		match self {
+			Self::Single(exp) => vec![exp.to_string()],
+			Self::AnyOf(exps) => exps.iter().map(ToString::to_string).collect(),
+			Self::Unparsed(text) => vec![text.clone()],
+			Self::UnparsedAnyOf(texts) => texts.clone(),
+		}
+	}
+
+	/// The members which aren't valid SPDX expressions, with their index in
+	/// a set, or `None` for a single license.
+	pub(crate) fn invalid_members(&self) -> Vec<(Option<usize>, String)> {
+		let invalid = |text: &&String| Expression::parse(text).is_err();
+		match self {
+			Self::Single(_) | Self::AnyOf(_) => Vec::new(),
+			Self::Unparsed(text) => Some(text)
+				.filter(invalid)
+				.map(|text| (None, text.clone()))
+				.into_iter()
+				.collect(),
+			Self::UnparsedAnyOf(texts) => texts
+				.iter()
+				.enumerate()
+				.filter(|(_, text)| invalid(text))
+				.map(|(n, text)| (Some(n), text.clone()))
+				.collect(),
 		}
 	}
 }
 
+/// The SPDX expression, with sets joined by `OR`, or the unparsed licenses as
+/// written, likewise joined.
+impl Display for License {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.try_to_expression() {
+			Some(exp) => write!(f, "{exp}"),
+			None => write!(f, "{}", self.members().join(" OR ")),
+		}
+	}
+}
+
+impl FromStr for License {
+	type Err = String;
+
+	/// Parse a single SPDX license expression.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Expression::parse(s)
+			.map(|exp| Self::Single(Box::new(exp)))
+			.map_err(|err| err.to_string())
+	}
+}
+
 impl Hash for License {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-		self.to_expression().to_string().hash(state)
+		self.to_string().hash(state)
 	}
 }
 
 impl PartialEq for License {
 	fn eq(&self, other: &Self) -> bool {
-		self.to_expression().eq(&other.to_expression())
+		match (self.try_to_expression(), other.try_to_expression()) {
+			(Some(this), Some(other)) => this.eq(&other),
+			(None, None) => self.members() == other.members(),
+			_ => false,
+		}
 	}
 }
 
@@ -64,21 +158,22 @@ enum ExprInternal {
 	AnyOf(Vec<String>),
 }
 
-impl TryFrom<ExprInternal> for License {
-	type Error = spdx::ParseError;
-
-	fn try_from(value: ExprInternal) -> Result<Self, Self::Error> {
+impl From<ExprInternal> for License {
+	fn from(value: ExprInternal) -> Self {
 		match value {
-			ExprInternal::Single(expr) => {
-				let expr = Expression::parse(&expr)?;
-				Ok(Self::Single(Box::new(expr)))
-			}
+			ExprInternal::Single(expr) => match Expression::parse(&expr) {
+				Ok(exp) => Self::Single(Box::new(exp)),
+				Err(_) => Self::Unparsed(expr),
+			},
 			ExprInternal::AnyOf(exprs) => {
-				let mut exps = Vec::with_capacity(exprs.len());
-				for exp in exprs {
-					exps.push(Expression::parse(&exp)?);
+				match exprs
+					.iter()
+					.map(|exp| Expression::parse(exp))
+					.collect::<Result<Vec<_>, _>>()
+				{
+					Ok(exps) => Self::AnyOf(exps),
+					Err(_) => Self::UnparsedAnyOf(exprs),
 				}
-				Ok(Self::AnyOf(exps))
 			}
 		}
 	}
@@ -89,6 +184,8 @@ impl From<License> for ExprInternal {
 		match license {
 			License::Single(exp) => Self::Single(exp.to_string()),
 			License::AnyOf(exps) => Self::AnyOf(exps.into_iter().map(|e| e.to_string()).collect()),
+			License::Unparsed(text) => Self::Single(text),
+			License::UnparsedAnyOf(texts) => Self::AnyOf(texts),
 		}
 	}
 }
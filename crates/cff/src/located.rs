@@ -0,0 +1,388 @@
+//! Parse errors with the position of the offending key, and lenient parsing.
+
+use std::{collections::HashMap, fmt::Display};
+
+use serde_yaml::{Location, Value};
+
+use crate::{
+	positions::{positions, Positions},
+	Cff,
+};
+
+/// The error when parsing CFF fails, with where in the document it failed.
+///
+/// [serde_yaml]'s errors have a location, but it's often the start of the
+/// document for errors which aren't about the YAML itself, like a malformed
+/// date. This instead points at the key whose value couldn't be read.
+///
+/// With the `diagnostics` feature, this is a [miette::Diagnostic], with the
+/// document as source code and a label at the key.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+	/// Path to the value that couldn't be read, e.g. `references[0].type`.
+	///
+	/// This is empty for errors about the document as a whole, like a missing
+	/// field, and may only be a parent of where invalid YAML is.
+	pub path: String,
+
+	/// Description of the error.
+	pub message: String,
+
+	/// Line of the error, starting at 1.
+	pub line: usize,
+
+	/// Column of the error, starting at 1.
+	pub column: usize,
+
+	/// Byte offset of the error in the document.
+	pub offset: usize,
+
+	/// Length in bytes of what the error is about, e.g. the key, or 0.
+	pub len: usize,
+
+	source: String,
+}
+
+impl Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if !self.path.is_empty() {
+			write!(f, "{}: ", self.path)?;
+		}
+		write!(
+			f,
+			"{} at line {} column {}",
+			self.message, self.line, self.column
+		)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(feature = "diagnostics")]
+impl miette::Diagnostic for ParseError {
+	fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+		Some(Box::new("citeworks_cff::parse"))
+	}
+
+	fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+		Some(&self.source)
+	}
+
+	fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+		Some(Box::new(std::iter::once(miette::LabeledSpan::new(
+			Some(self.message.clone()),
+			self.offset,
+			self.len,
+		))))
+	}
+}
+
+/// Deserialize CFF from a string of YAML, with positions in errors.
+///
+/// This is [from_str](crate::from_str), but errors say where the value that
+/// couldn't be read is:
+///
+/// ```
+/// let err = citeworks_cff::from_str_located("
+/// cff-version: 1.2.0
+/// message: Please cite this software.
+/// title: Example
+/// authors:
+///   - name: Acme
+/// date-released: 20 August 2022
+/// ").unwrap_err();
+///
+/// assert_eq!(err.path, "date-released");
+/// assert_eq!((err.line, err.column), (7, 1));
+/// assert_eq!(
+///     err.to_string(),
+///     r#"date-released: expected YYYY-MM-DD, got: "20 August 2022" at line 7 column 1"#
+/// );
+/// ```
+pub fn from_str_located(s: &str) -> Result<Cff, ParseError> {
+	let err = match serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(s)) {
+		Ok(cff) => return Ok(cff),
+		Err(err) => err,
+	};
+
+	let path = segments(err.path());
+	let inner = err.into_inner();
+	let location = inner.location();
+
+	// Errors about the YAML itself, or the document as a whole, are best
+	// placed by serde_yaml; others at their key.
+	let positions = if path.is_empty() {
+		None
+	} else {
+		positions(s).ok()
+	};
+	Err(located(s, positions.as_ref(), &path, &inner, location))
+}
+
+/// Deserialize CFF from a string of YAML, skipping values that can't be read.
+///
+/// Instead of stopping at the first error, this leaves out values which can't
+/// be read, like a malformed date or URL, and returns the document without
+/// them, with an error for each, in the order they're in the document. Entries
+/// of lists, like authors or references, are left out as a whole.
+///
+/// Required fields which are missing or left out are empty in the document:
+/// the title and message are empty strings, and the authors an empty list.
+///
+/// ```
+/// let (cff, errors) = citeworks_cff::from_str_lenient("
+/// cff-version: 1.2.0
+/// message: Please cite this software.
+/// title: Example
+/// authors:
+///   - name: Acme
+/// date-released: 20 August 2022
+/// repository: not a url
+/// url: https://example.com
+/// ").unwrap();
+///
+/// assert_eq!(cff.url.unwrap().as_str(), "https://example.com/");
+/// assert_eq!(cff.date_released, None);
+/// assert_eq!(
+///     errors.iter().map(|err| err.path.as_str()).collect::<Vec<_>>(),
+///     ["date-released", "repository"]
+/// );
+/// ```
+///
+/// This still fails if the document isn't YAML, or isn't a mapping.
+pub fn from_str_lenient(s: &str) -> Result<(Cff, Vec<ParseError>), ParseError> {
+	let mut value: Value = match serde_yaml::from_str(s) {
+		Ok(value) => value,
+		// The located parse fails the same way, and says where.
+		Err(_) => return from_str_located(s).map(|cff| (cff, Vec::new())),
+	};
+
+	let positions = positions(s).ok();
+	let mut removed = Removed::default();
+	let mut errors = Vec::new();
+
+	loop {
+		let err = match serde_path_to_error::deserialize::<_, Cff>(value.clone()) {
+			Ok(cff) => {
+				errors.sort_by_key(|error: &ParseError| error.offset);
+				return Ok((cff, errors));
+			}
+			Err(err) => err,
+		};
+
+		let path = segments(err.path());
+		let inner = err.into_inner();
+		let original = removed.original(&path);
+		let error = located(s, positions.as_ref(), &original, &inner, None);
+
+		let missing = error
+			.message
+			.strip_prefix("missing field `")
+			.and_then(|rest| rest.strip_suffix('`'))
+			.map(String::from);
+
+		// A missing field that was left out already had its error.
+		let consequence = missing.as_ref().map_or(false, |field| {
+			let mut field_path = original.clone();
+			field_path.push(Segment::Key(field.clone()));
+			removed.paths.contains(&field_path)
+		});
+
+		match (&missing, path.is_empty()) {
+			(Some(field), true) => match required_default(field) {
+				Some(default) => match &mut value {
+					Value::Mapping(map) => {
+						map.insert(Value::String(field.clone()), default);
+					}
+					_ => return Err(error),
+				},
+				None => return Err(error),
+			},
+			(_, true) => return Err(error),
+			(_, false) => {
+				if !remove(&mut value, &path) {
+					return Err(error);
+				}
+				removed.record(&original);
+			}
+		}
+
+		if !consequence {
+			errors.push(error);
+		}
+	}
+}
+
+/// A step in the path to a value.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum Segment {
+	Index(usize),
+	Key(String),
+}
+
+fn segments(path: &serde_path_to_error::Path) -> Vec<Segment> {
+	path.iter()
+		.filter_map(|segment| match segment {
+			serde_path_to_error::Segment::Seq { index } => Some(Segment::Index(*index)),
+			serde_path_to_error::Segment::Map { key } => Some(Segment::Key(key.clone())),
+			_ => None,
+		})
+		.collect()
+}
+
+/// Path as written in errors, e.g. `references[0].type`.
+fn display(path: &[Segment]) -> String {
+	let mut display = String::new();
+	for segment in path {
+		match segment {
+			Segment::Index(index) => display.push_str(&format!("[{index}]")),
+			Segment::Key(key) => {
+				if !display.is_empty() {
+					display.push('.');
+				}
+				display.push_str(key);
+			}
+		}
+	}
+	display
+}
+
+fn located(
+	s: &str,
+	positions: Option<&Positions>,
+	path: &[Segment],
+	err: &serde_yaml::Error,
+	location: Option<Location>,
+) -> ParseError {
+	let display_path = display(path);
+	let mut message = err.to_string();
+
+	// serde_yaml's messages have its own path and location, which are
+	// replaced with ours.
+	if let Some(location) = &location {
+		let suffix = format!(" at line {} column {}", location.line(), location.column());
+		message = message.replacen(&suffix, "", 1);
+	}
+	if let Some((prefix, rest)) = message.split_once(": ") {
+		if !prefix.is_empty() && display_path.starts_with(prefix) {
+			message = rest.into();
+		}
+	}
+
+	let keys: Vec<String> = path
+		.iter()
+		.map(|segment| match segment {
+			Segment::Index(index) => index.to_string(),
+			Segment::Key(key) => key.clone(),
+		})
+		.collect();
+	let marker = positions.and_then(|positions| positions.find(&keys));
+	let (line, column, offset) = match (marker, location) {
+		(Some(marker), _) => (
+			marker.line(),
+			marker.col() + 1,
+			s.char_indices()
+				.nth(marker.index())
+				.map_or(s.len(), |(offset, _)| offset),
+		),
+		(None, Some(location)) => (location.line(), location.column(), location.index()),
+		(None, None) => (1, 1, 0),
+	};
+
+	let len = match path.last() {
+		Some(Segment::Key(key)) if marker.is_some() && s[offset..].starts_with(key.as_str()) => {
+			key.len()
+		}
+		_ => 0,
+	};
+
+	ParseError {
+		path: display_path,
+		message,
+		line,
+		column,
+		offset,
+		len,
+		source: s.into(),
+	}
+}
+
+/// Empty values for the required fields of a document.
+fn required_default(field: &str) -> Option<Value> {
+	match field {
+		"cff-version" => Some(Value::String("1.2.0".into())),
+		"message" | "title" => Some(Value::String(String::new())),
+		"authors" => Some(Value::Sequence(Vec::new())),
+		_ => None,
+	}
+}
+
+/// Remove the value at the path, returning whether there was one.
+fn remove(value: &mut Value, path: &[Segment]) -> bool {
+	let (last, parents) = match path.split_last() {
+		Some(split) => split,
+		None => return false,
+	};
+
+	let mut value = value;
+	for segment in parents {
+		value = match (segment, value) {
+			(Segment::Index(index), Value::Sequence(seq)) => match seq.get_mut(*index) {
+				Some(value) => value,
+				None => return false,
+			},
+			(Segment::Key(key), Value::Mapping(map)) => match map.get_mut(key.as_str()) {
+				Some(value) => value,
+				None => return false,
+			},
+			_ => return false,
+		};
+	}
+
+	match (last, value) {
+		(Segment::Index(index), Value::Sequence(seq)) if *index < seq.len() => {
+			seq.remove(*index);
+			true
+		}
+		(Segment::Key(key), Value::Mapping(map)) => map.remove(key.as_str()).is_some(),
+		_ => false,
+	}
+}
+
+/// Values left out so far, to find where later errors are in the document.
+///
+/// Leaving out entries of lists moves the entries after them, so paths in
+/// errors need to be moved back.
+#[derive(Debug, Default)]
+struct Removed {
+	paths: Vec<Vec<Segment>>,
+	indices: HashMap<Vec<Segment>, Vec<usize>>,
+}
+
+impl Removed {
+	fn record(&mut self, original: &[Segment]) {
+		if let Some((Segment::Index(index), parent)) = original.split_last() {
+			self.indices
+				.entry(parent.to_vec())
+				.or_default()
+				.push(*index);
+		}
+		self.paths.push(original.to_vec());
+	}
+
+	/// The path in the document of a path in what's left of it.
+	fn original(&self, path: &[Segment]) -> Vec<Segment> {
+		let mut original = Vec::with_capacity(path.len());
+		for segment in path {
+			let segment = match (segment, self.indices.get(&original)) {
+				(Segment::Index(index), Some(removed)) => {
+					let mut kept = (0..).filter(|i| !removed.contains(i));
+					Segment::Index(kept.nth(*index).unwrap_or(*index))
+				}
+				(segment, _) => segment.clone(),
+			};
+			original.push(segment);
+		}
+		original
+	}
+}
@@ -54,6 +54,95 @@ impl Name {
 			None
 		}
 	}
+
+	/// The key names are sorted by alphabetically.
+	///
+	/// For people, this is their family names, then given names, then particle,
+	/// lowercased; for entities, their name, lowercased. Anonymous is empty.
+	pub fn sort_key(&self) -> Vec<String> {
+		let part = |part: &Option<String>| part.as_deref().unwrap_or_default().to_lowercase();
+		match self {
+			Self::Person(p) => vec![
+				part(&p.family_names),
+				part(&p.given_names),
+				part(&p.name_particle),
+			],
+			Self::Entity(e) => vec![part(&e.name)],
+			Self::Anonymous => Vec::new(),
+		}
+	}
+
+	/// Whether two names are of the same person or entity.
+	///
+	/// Names with the same ORCID or email are the same; otherwise people are
+	/// the same if their family and given names are, and entities if their
+	/// names are, ignoring case. Anonymous is never the same as anyone.
+	pub fn is_same(&self, other: &Self) -> bool {
+		fn same<T: PartialEq>(a: Option<T>, b: Option<T>) -> bool {
+			matches!((a, b), (Some(a), Some(b)) if a == b)
+		}
+		fn same_text(a: &Option<String>, b: &Option<String>) -> bool {
+			same(
+				a.as_deref().map(str::to_lowercase),
+				b.as_deref().map(str::to_lowercase),
+			)
+		}
+
+		fn meta(name: &Name) -> Option<&NameMeta> {
+			match name {
+				Name::Person(p) => Some(&p.meta),
+				Name::Entity(e) => Some(&e.meta),
+				Name::Anonymous => None,
+			}
+		}
+		if let (Some(a), Some(b)) = (meta(self), meta(other)) {
+			if same(a.orcid.as_ref(), b.orcid.as_ref()) || same_text(&a.email, &b.email) {
+				return true;
+			}
+		}
+
+		match (self, other) {
+			(Self::Person(a), Self::Person(b)) => {
+				same_text(&a.family_names, &b.family_names)
+					&& a.given_names.as_deref().map(str::to_lowercase)
+						== b.given_names.as_deref().map(str::to_lowercase)
+			}
+			(Self::Entity(a), Self::Entity(b)) => same_text(&a.name, &b.name),
+			_ => false,
+		}
+	}
+}
+
+/// Sort names alphabetically, in place.
+///
+/// See [Name::sort_key]. The sort is stable, so names with the same key stay in
+/// the order they were in, and anonymous names come first.
+pub fn sort_alphabetically(names: &mut [Name]) {
+	names.sort_by_cached_key(Name::sort_key);
+}
+
+/// Names in alphabetical order, with their index in the original order.
+///
+/// In CFF, the order of authors is the order of contribution, which is often
+/// meaningful. This lists names alphabetically without losing that: the index
+/// of each name is its position in the original list.
+///
+/// ```
+/// use citeworks_cff::names::{alphabetical, Name, PersonName};
+///
+/// let person = |family: &str| Name::Person(PersonName {
+///     family_names: Some(family.into()),
+///     ..Default::default()
+/// });
+/// let authors = vec![person("Roe"), person("Doe")];
+///
+/// let sorted: Vec<usize> = alphabetical(&authors).into_iter().map(|(n, _)| n).collect();
+/// assert_eq!(sorted, vec![1, 0]);
+/// ```
+pub fn alphabetical(names: &[Name]) -> Vec<(usize, &Name)> {
+	let mut sorted: Vec<(usize, &Name)> = names.iter().enumerate().collect();
+	sorted.sort_by_cached_key(|(_, name)| name.sort_key());
+	sorted
 }
 
 impl Serialize for Name {
@@ -182,6 +271,9 @@ pub struct EntityName {
 pub struct NameMeta {
 	/// [ORCID] identifier.
 	///
+	/// It should be the full URL, e.g. `https://orcid.org/0000-0002-1825-0097`,
+	/// see [validate_orcid].
+	///
 	/// [ORCID]: https://orcid.org
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub orcid: Option<Url>,
@@ -229,4 +321,113 @@ pub struct NameMeta {
 	/// Website.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub website: Option<Url>,
+
+	/// [ROR] identifier.
+	///
+	/// For an entity, this identifies the entity itself; for a person, it
+	/// identifies their affiliation. It should be the full URL, e.g.
+	/// `https://ror.org/027m9bs27`, see [validate_ror].
+	///
+	/// This is an extension: it is not part of CFF 1.2.0.
+	///
+	/// [ROR]: https://ror.org
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub ror: Option<Url>,
+}
+
+/// Check that a URL is a well-formed [ORCID] identifier.
+///
+/// That is, `https://orcid.org/` followed by four groups of four digits
+/// separated by dashes, where the last digit may be `X`, and is a checksum,
+/// which is verified.
+///
+/// [ORCID]: https://support.orcid.org/hc/en-us/articles/360006897674
+pub fn validate_orcid(url: &Url) -> Result<(), String> {
+	let id = validate_orcid_shape(url)?;
+	let digits: Vec<u8> = id.bytes().filter(|c| *c != b'-').collect();
+
+	let mut total: u32 = 0;
+	for c in &digits[..15] {
+		total = (total + u32::from(c - b'0')) * 2;
+	}
+	let expected = match (12 - total % 11) % 11 {
+		10 => b'X',
+		n => b'0' + n as u8,
+	};
+	if digits[15] == expected {
+		Ok(())
+	} else {
+		Err(format!(
+			"ORCID checksum should be {}, got: {}",
+			expected as char, digits[15] as char
+		))
+	}
+}
+
+/// Check the shape of an ORCID URL, as the specification does, and return the
+/// identifier part.
+///
+/// Unlike [validate_orcid], this doesn't verify the checksum.
+pub(crate) fn validate_orcid_shape(url: &Url) -> Result<&str, String> {
+	if url.scheme() != "https" || url.host_str() != Some("orcid.org") {
+		return Err(format!(
+			"ORCID should be an https://orcid.org/ URL, got: {url}"
+		));
+	}
+
+	let id = url.path().trim_start_matches('/');
+	let well_formed = id.len() == 19
+		&& id.bytes().enumerate().all(|(n, c)| match n {
+			4 | 9 | 14 => c == b'-',
+			18 => c.is_ascii_digit() || c == b'X',
+			_ => c.is_ascii_digit(),
+		});
+	if well_formed {
+		Ok(id)
+	} else {
+		Err(format!(
+			"ORCID should be four groups of four digits, got: {id:?}"
+		))
+	}
+}
+
+const ROR_ALPHABET: &[u8] = b"0123456789abcdefghjkmnpqrstvwxyz";
+
+/// Check that a URL is a well-formed [ROR] identifier.
+///
+/// That is, `https://ror.org/` followed by a leading `0`, six characters of
+/// Crockford base32, and a two-digit checksum, which is verified.
+///
+/// [ROR]: https://ror.org/about/faqs/#what-is-a-ror-identifier
+pub fn validate_ror(url: &Url) -> Result<(), String> {
+	if url.host_str() != Some("ror.org") {
+		return Err(format!("ROR should be on the ror.org host, got: {url}"));
+	}
+
+	let id = url.path().trim_start_matches('/');
+	let (digits, checksum) = match (id.len(), id.starts_with('0')) {
+		(9, true) => id.split_at(7),
+		_ => {
+			return Err(format!(
+				"ROR should be a 0 followed by 8 characters, got: {id:?}"
+			))
+		}
+	};
+
+	let mut number: u64 = 0;
+	for c in digits.bytes() {
+		let value = ROR_ALPHABET
+			.iter()
+			.position(|a| *a == c.to_ascii_lowercase())
+			.ok_or_else(|| format!("ROR contains invalid character {:?}", c as char))?;
+		number = number * 32 + value as u64;
+	}
+
+	let expected = 98 - ((number * 100) % 97);
+	match checksum.parse::<u64>() {
+		Ok(actual) if actual == expected => Ok(()),
+		_ => Err(format!(
+			"ROR checksum should be {expected:02}, got: {checksum:?}"
+		)),
+	}
 }
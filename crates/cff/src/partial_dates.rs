@@ -0,0 +1,323 @@
+//! Dates with only a year, or a year and month.
+//!
+//! CFF dates are full `YYYY-MM-DD` dates, but many projects only know the
+//! year they released something. [from_str] reads documents with dates like
+//! `2020` or `2020-05`, completing them to the first day they can be, so the
+//! document can be used as usual, and records which were partial and how
+//! precise they were in [PartialDates]:
+//!
+//! ```
+//! use citeworks_cff::partial_dates::{self, Precision, Strictness};
+//!
+//! let (cff, partial) = partial_dates::from_str("
+//! cff-version: 1.2.0
+//! message: Please cite this software.
+//! title: Example
+//! authors:
+//!   - name: Acme
+//! date-released: 2020
+//! ").unwrap();
+//!
+//! assert_eq!(cff.date_released.unwrap().to_string(), "2020-01-01");
+//! assert_eq!(partial.get("date-released").unwrap().precision(), Precision::Year);
+//!
+//! // Strict output is valid CFF, with the completed dates.
+//! let strict = partial_dates::to_string(&cff, &partial, Strictness::Strict).unwrap();
+//! assert!(strict.contains("date-released: 2020-01-01"));
+//!
+//! // Lenient output has the dates as they were read, if they're unchanged.
+//! let lenient = partial_dates::to_string(&cff, &partial, Strictness::Lenient).unwrap();
+//! assert!(lenient.contains("date-released: '2020'"));
+//! ```
+
+use std::{
+	collections::BTreeMap,
+	fmt::{self, Display},
+	str::FromStr,
+};
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use serde_yaml::Value;
+
+use crate::{Cff, Date};
+
+/// Keys of dates in documents, at any depth.
+const DATE_KEYS: &[&str] = &[
+	"available-date",
+	"date-accessed",
+	"date-downloaded",
+	"date-end",
+	"date-published",
+	"date-released",
+	"date-start",
+];
+
+/// A date which may only have a year, or a year and month.
+///
+/// In text this is `YYYY`, `YYYY-MM`, or `YYYY-MM-DD`.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct PartialDate {
+	/// Year, in the Gregorian calendar
+	pub year: i64,
+
+	/// Month, starting from 1
+	pub month: Option<u8>,
+
+	/// Day of the month, starting from 1, only with a month
+	pub day: Option<u8>,
+}
+
+/// How precise a [PartialDate] is.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Precision {
+	/// Only the year is known
+	Year,
+
+	/// The year and month are known
+	Month,
+
+	/// The full date is known
+	Day,
+}
+
+impl PartialDate {
+	/// How precise the date is.
+	pub fn precision(&self) -> Precision {
+		match (self.month, self.day) {
+			(Some(_), Some(_)) => Precision::Day,
+			(Some(_), None) => Precision::Month,
+			(None, _) => Precision::Year,
+		}
+	}
+
+	/// The full date, if it's known.
+	pub fn to_date(&self) -> Option<Date> {
+		match (self.month, self.day) {
+			(Some(month), Some(day)) => Some(Date {
+				year: self.year,
+				month,
+				day,
+			}),
+			_ => None,
+		}
+	}
+
+	/// The first day the date can be, e.g. 2020-05-01 for `2020-05`.
+	pub fn complete(&self) -> Date {
+		let month = self.month.unwrap_or(1);
+		Date {
+			year: self.year,
+			month,
+			day: self.month.and(self.day).unwrap_or(1),
+		}
+	}
+}
+
+impl From<Date> for PartialDate {
+	fn from(date: Date) -> Self {
+		Self {
+			year: date.year,
+			month: Some(date.month),
+			day: Some(date.day),
+		}
+	}
+}
+
+impl Display for PartialDate {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:04}", self.year)?;
+		if let Some(month) = self.month {
+			write!(f, "-{month:02}")?;
+			if let Some(day) = self.day {
+				write!(f, "-{day:02}")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for PartialDate {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let err = || -> String { format!("expected YYYY, YYYY-MM, or YYYY-MM-DD, got: {s:?}") };
+		let number = |part: &str, len: usize| -> Result<u8, String> {
+			if part.len() == len && part.bytes().all(|b| b.is_ascii_digit()) {
+				part.parse().map_err(|_| err())
+			} else {
+				Err(err())
+			}
+		};
+
+		let mut parts = s.splitn(3, '-');
+		let year = parts.next().unwrap_or_default();
+		if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+			return Err(err());
+		}
+		let month = parts.next().map(|month| number(month, 2)).transpose()?;
+		let day = parts.next().map(|day| number(day, 2)).transpose()?;
+
+		match (month, day) {
+			(Some(month), _) if month == 0 || month > 12 => {
+				Err(format!("month should be in range 1-12, got: {month}"))
+			}
+			(_, Some(day)) if day == 0 || day > 31 => {
+				Err(format!("day should be in range 1-31, got: {day}"))
+			}
+			_ => Ok(Self {
+				year: year.parse().map_err(|_| err())?,
+				month,
+				day,
+			}),
+		}
+	}
+}
+
+impl Serialize for PartialDate {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		self.to_string().serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for PartialDate {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		// A year alone is a number in YAML.
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Text {
+			Year(u16),
+			String(String),
+		}
+
+		match Text::deserialize(deserializer)? {
+			Text::Year(year) => format!("{year:04}").parse(),
+			Text::String(s) => s.parse(),
+		}
+		.map_err(D::Error::custom)
+	}
+}
+
+/// The dates of a document which were partial, by path, e.g.
+/// `references[0].date-published`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PartialDates {
+	dates: BTreeMap<String, PartialDate>,
+}
+
+impl PartialDates {
+	/// The partial date at a path, if the date there was partial.
+	pub fn get(&self, path: &str) -> Option<PartialDate> {
+		self.dates.get(path).copied()
+	}
+
+	/// The partial dates and their paths.
+	pub fn iter(&self) -> impl Iterator<Item = (&str, PartialDate)> {
+		self.dates.iter().map(|(path, date)| (path.as_str(), *date))
+	}
+
+	/// Whether all dates were full dates.
+	pub fn is_empty(&self) -> bool {
+		self.dates.is_empty()
+	}
+}
+
+/// Whether to write partial dates as they were read.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Strictness {
+	/// Write completed dates, as CFF requires.
+	Strict,
+
+	/// Write partial dates as they were read, unless they were changed. This
+	/// isn't valid CFF.
+	Lenient,
+}
+
+/// Deserialize CFF from a string of YAML, accepting partial dates.
+///
+/// Partial dates are completed to the first day they can be, see
+/// [PartialDate::complete], and returned with their paths.
+pub fn from_str(s: &str) -> crate::Result<(Cff, PartialDates)> {
+	let mut value: Value = serde_yaml::from_str(s)?;
+	let mut partial = PartialDates::default();
+	complete_dates(&mut value, &mut String::new(), &mut partial);
+	let cff = serde_yaml::from_value(value)?;
+	Ok((cff, partial))
+}
+
+/// Serialize CFF as a String of YAML, with partial dates if lenient.
+pub fn to_string(
+	cff: &Cff,
+	partial: &PartialDates,
+	strictness: Strictness,
+) -> crate::Result<String> {
+	let mut value = serde_yaml::to_value(cff)?;
+	if strictness == Strictness::Lenient {
+		restore_dates(&mut value, &mut String::new(), partial);
+	}
+	serde_yaml::to_string(&value)
+}
+
+fn complete_dates(value: &mut Value, path: &mut String, partial: &mut PartialDates) {
+	visit(value, path, &mut |path, key, value| {
+		if !DATE_KEYS.contains(&key) {
+			return;
+		}
+		let text = match value {
+			Value::String(s) => s.clone(),
+			Value::Number(n) => n.to_string(),
+			_ => return,
+		};
+		if let Ok(date) = text.parse::<PartialDate>() {
+			if date.precision() != Precision::Day {
+				*value = Value::String(date.complete().to_string());
+				partial.dates.insert(path.into(), date);
+			}
+		}
+	});
+}
+
+fn restore_dates(value: &mut Value, path: &mut String, partial: &PartialDates) {
+	visit(value, path, &mut |path, _, value| {
+		if let Some(date) = partial.get(path) {
+			if value.as_str() == Some(date.complete().to_string().as_str()) {
+				*value = Value::String(date.to_string());
+			}
+		}
+	});
+}
+
+/// Call `f` with the path, key, and value of every entry of every mapping.
+fn visit(value: &mut Value, path: &mut String, f: &mut dyn FnMut(&str, &str, &mut Value)) {
+	let len = path.len();
+	match value {
+		Value::Mapping(map) => {
+			for (key, value) in map.iter_mut() {
+				let key = match key.as_str() {
+					Some(key) => key,
+					None => continue,
+				};
+				if !path.is_empty() {
+					path.push('.');
+				}
+				path.push_str(key);
+				f(path, key, value);
+				visit(value, path, f);
+				path.truncate(len);
+			}
+		}
+		Value::Sequence(seq) => {
+			for (index, value) in seq.iter_mut().enumerate() {
+				path.push_str(&format!("[{index}]"));
+				visit(value, path, f);
+				path.truncate(len);
+			}
+		}
+		_ => {}
+	}
+}
@@ -0,0 +1,153 @@
+//! Positions of values in YAML documents, for error messages and editors.
+
+use std::collections::HashMap;
+
+use yaml_rust2::{
+	parser::{Event, MarkedEventReceiver, Parser},
+	scanner::Marker,
+};
+
+/// Read the positions of the values in a YAML document.
+pub(crate) fn positions(yaml: &str) -> Result<Positions, String> {
+	let mut positions = Positions::default();
+	Parser::new_from_str(yaml)
+		.load(&mut positions, false)
+		.map_err(|err| err.to_string())?;
+	Ok(positions)
+}
+
+/// Positions of the values in a YAML document, by path.
+///
+/// The values of mappings are at their key, which is where editors put the
+/// cursor for a field.
+#[derive(Debug, Default)]
+pub(crate) struct Positions {
+	frames: Vec<Frame>,
+	markers: HashMap<Vec<String>, Marker>,
+}
+
+#[derive(Debug)]
+enum Frame {
+	/// A mapping, expecting a key or the value of the key. The path is `None`
+	/// within complex keys, which have no place in JSON.
+	Mapping {
+		path: Option<Vec<String>>,
+		key: Option<String>,
+		expect_key: bool,
+	},
+	Sequence {
+		path: Option<Vec<String>>,
+		index: usize,
+	},
+}
+
+impl Positions {
+	/// Position of the value at the path, or of the closest parent that has one.
+	pub(crate) fn find(&self, path: &[String]) -> Option<Marker> {
+		(0..=path.len())
+			.rev()
+			.find_map(|len| self.markers.get(&path[..len]).copied())
+	}
+
+	/// Path of the innermost value starting at or before a character index.
+	pub(crate) fn path_at(&self, index: usize) -> Option<&[String]> {
+		self.markers
+			.iter()
+			.filter(|(_, marker)| marker.index() <= index)
+			.max_by_key(|(path, marker)| (marker.index(), path.len()))
+			.map(|(path, _)| path.as_slice())
+	}
+
+	/// Path of the node starting now, with the scalar if it's one.
+	fn path(&self, scalar: Option<&str>) -> Option<Vec<String>> {
+		let (path, last) = match self.frames.last() {
+			None => return Some(Vec::new()),
+			Some(Frame::Mapping {
+				path,
+				key,
+				expect_key,
+			}) => (path, if *expect_key { scalar } else { key.as_deref() }),
+			Some(Frame::Sequence { path, index }) => {
+				return path.as_ref().map(|path| {
+					let mut path = path.clone();
+					path.push(index.to_string());
+					path
+				})
+			}
+		};
+
+		let (path, last) = (path.as_ref()?, last?);
+		let mut path = path.clone();
+		path.push(last.into());
+		Some(path)
+	}
+
+	/// Move on from a complete node, with the scalar if it was one.
+	fn advance(&mut self, scalar: Option<String>) {
+		match self.frames.last_mut() {
+			Some(Frame::Mapping {
+				key, expect_key, ..
+			}) => {
+				if *expect_key {
+					*key = scalar;
+				}
+				*expect_key = !*expect_key;
+			}
+			Some(Frame::Sequence { index, .. }) => *index += 1,
+			None => {}
+		}
+	}
+
+	fn record(&mut self, path: &Option<Vec<String>>, mark: Marker) {
+		if let Some(path) = path {
+			self.markers.entry(path.clone()).or_insert(mark);
+		}
+	}
+}
+
+impl MarkedEventReceiver for Positions {
+	fn on_event(&mut self, event: Event, mark: Marker) {
+		match event {
+			Event::Scalar(value, ..) => {
+				// Block mappings start at their first key, not at the mapping
+				// event, which is after it.
+				if let Some(Frame::Mapping {
+					path,
+					expect_key: true,
+					..
+				}) = self.frames.last()
+				{
+					let path = path.clone();
+					self.record(&path, mark);
+				}
+
+				let path = self.path(Some(&value));
+				self.record(&path, mark);
+				self.advance(Some(value));
+			}
+			Event::Alias(_) => {
+				let path = self.path(None);
+				self.record(&path, mark);
+				self.advance(None);
+			}
+			Event::MappingStart(..) => {
+				let path = self.path(None);
+				self.frames.push(Frame::Mapping {
+					path,
+					key: None,
+					expect_key: true,
+				});
+			}
+			Event::SequenceStart(..) => {
+				let path = self.path(None);
+				self.record(&path, mark);
+				self.frames.push(Frame::Sequence { path, index: 0 });
+			}
+			Event::MappingEnd | Event::SequenceEnd => {
+				self.frames.pop();
+				self.advance(None);
+			}
+			_ => {}
+		}
+	}
+}
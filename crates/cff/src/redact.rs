@@ -0,0 +1,95 @@
+//! Removing personal contact details before publishing.
+//!
+//! Some institutions don't allow publishing people's emails, phone numbers, or
+//! addresses in repository metadata. These remove them from the names of
+//! people throughout a document, keeping names, ORCIDs, and affiliations.
+//! Entities (organisations, conferences…) are left alone, as their contact
+//! details are not personal.
+//!
+//! ```
+//! let mut cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! authors:
+//!   - family-names: Doe
+//!     given-names: Jane
+//!     email: jane@example.com
+//!     tel: +64 4 123 4567
+//! "#).unwrap();
+//!
+//! cff.redact_contact_details();
+//! let jane = cff.authors[0].as_person().unwrap();
+//! assert_eq!(jane.meta.email, None);
+//! assert_eq!(jane.meta.tel, None);
+//! assert_eq!(jane.family_names.as_deref(), Some("Doe"));
+//! ```
+
+use crate::{
+	names::{Name, NameMeta},
+	references::Reference,
+	Cff,
+};
+
+impl NameMeta {
+	/// Remove the email, phone and fax numbers, and postal address.
+	///
+	/// The city, region, and country are kept.
+	pub fn redact_contact_details(&mut self) {
+		self.email = None;
+		self.tel = None;
+		self.fax = None;
+		self.address = None;
+		self.post_code = None;
+	}
+}
+
+impl Name {
+	/// Remove the contact details of a person.
+	///
+	/// See [NameMeta::redact_contact_details]. Entities are left alone.
+	pub fn redact_contact_details(&mut self) {
+		if let Self::Person(person) = self {
+			person.meta.redact_contact_details();
+		}
+	}
+}
+
+impl Reference {
+	/// Remove the contact details of all people named in the reference.
+	pub fn redact_contact_details(&mut self) {
+		for name in [
+			&mut self.authors,
+			&mut self.contact,
+			&mut self.editors,
+			&mut self.editors_series,
+			&mut self.recipients,
+			&mut self.senders,
+			&mut self.translators,
+		]
+		.into_iter()
+		.flatten()
+		{
+			name.redact_contact_details();
+		}
+	}
+}
+
+impl Cff {
+	/// Remove the contact details of all people named in the document.
+	///
+	/// This includes its authors and contacts, and the people named in its
+	/// preferred citation and references.
+	pub fn redact_contact_details(&mut self) {
+		for name in self.authors.iter_mut().chain(self.contact.iter_mut()) {
+			name.redact_contact_details();
+		}
+		for reference in self
+			.preferred_citation
+			.iter_mut()
+			.chain(self.references.iter_mut())
+		{
+			reference.redact_contact_details();
+		}
+	}
+}
@@ -1,10 +1,13 @@
 //! Types and utilities for references to this or other works.
 
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_yaml::Value;
 use url::Url;
 
 use crate::{
-	identifiers::Identifier,
+	identifiers::{Identifier, Isbn, Issn},
 	names::{EntityName, Name},
 	Date, License,
 };
@@ -150,24 +153,29 @@ pub struct Reference {
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub institution: Option<EntityName>,
 
-	/// The [ISBN] of the work.
-	///
-	/// The value is not validated.
+	/// The ISBN of the work.
 	///
-	/// [ISBN]: https://en.wikipedia.org/wiki/International_Standard_Book_Number
+	/// Malformed values are read as they are, and raise an issue when
+	/// validating. See [Isbn].
 	#[serde(default, skip_serializing_if = "Option::is_none")]
-	pub isbn: Option<String>,
+	pub isbn: Option<Isbn>,
 
-	/// The [ISSN] of the work.
-	///
-	/// The value is not validated.
+	/// The ISSN of the work.
 	///
-	/// [ISSN]: https://en.wikipedia.org/wiki/International_Standard_Serial_Number
+	/// Malformed values are read as they are, and raise an issue when
+	/// validating. See [Issn].
 	#[serde(default, skip_serializing_if = "Option::is_none")]
-	pub issn: Option<String>,
+	pub issn: Option<Issn>,
 
 	/// The issue of a periodical in which a work appeared.
-	#[serde(default, skip_serializing_if = "Option::is_none")]
+	///
+	/// Issues are strings in CFF, but numbers are accepted too, and kept as
+	/// strings. See [Reference::issue_number].
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "string_or_number"
+	)]
 	pub issue: Option<String>,
 
 	/// The publication date of the issue of a periodical in which a work appeared.
@@ -349,6 +357,44 @@ pub struct Reference {
 	/// The year of the original publication.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub year_original: Option<i64>,
+
+	/// Fields not defined above.
+	///
+	/// See [Cff::extra](crate::Cff::extra).
+	#[serde(flatten)]
+	pub extra: BTreeMap<String, Value>,
+}
+
+impl Reference {
+	/// The issue as a number, if it is one.
+	///
+	/// Leading zeros and surrounding whitespace are ignored, so `10`, `"10"`,
+	/// and `"010"` are all issue 10.
+	pub fn issue_number(&self) -> Option<u64> {
+		self.issue.as_deref()?.trim().parse().ok()
+	}
+}
+
+/// Deserialize an optional string that may be written as a number.
+fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum StringOrNumber {
+		String(String),
+		Unsigned(u64),
+		Signed(i64),
+	}
+
+	Ok(
+		Option::<StringOrNumber>::deserialize(deserializer)?.map(|value| match value {
+			StringOrNumber::String(s) => s,
+			StringOrNumber::Unsigned(n) => n.to_string(),
+			StringOrNumber::Signed(n) => n.to_string(),
+		}),
+	)
 }
 
 /// Publication statuses.
@@ -365,8 +411,14 @@ pub enum PublicationStatus {
 }
 
 /// Types of referenced works.
-#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
+///
+/// Types this crate doesn't know, e.g. from a newer version of CFF, are kept as
+/// written in [RefType::Other].
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(
+	rename_all = "kebab-case",
+	expecting = "expected a reference type, like `article`"
+)]
 #[allow(missing_docs)]
 pub enum RefType {
 	Art,
@@ -416,6 +468,8 @@ pub enum RefType {
 	Unpublished,
 	Video,
 	Website,
+	#[serde(untagged)]
+	Other(String),
 }
 
 impl Default for RefType {
@@ -0,0 +1,176 @@
+//! Classifying repository URLs.
+//!
+//! CFF has three fields for where a work is: `repository-code` for its source
+//! code, `repository-artifact` for built forms like packages and container
+//! images, and `repository` for anything else, like an archive of a dataset.
+//! This tells which a URL belongs in from its host.
+//!
+//! ```
+//! use citeworks_cff::repositories::{classify_repository, RepositoryKind};
+//! use url::Url;
+//!
+//! let url = Url::parse("https://crates.io/crates/citeworks-cff").unwrap();
+//! let kind = classify_repository(&url);
+//! assert_eq!(kind, Some(RepositoryKind::Artifact));
+//! assert_eq!(kind.unwrap().field(), "repository-artifact");
+//!
+//! let url = Url::parse("https://example.com/").unwrap();
+//! assert_eq!(classify_repository(&url), None);
+//! ```
+
+use std::fmt::Display;
+
+use url::Url;
+
+use crate::{references::Reference, Cff};
+
+/// What kind of repository a URL is for.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum RepositoryKind {
+	/// Source code, e.g. on GitHub. Goes in `repository-code`.
+	Code,
+
+	/// Packages, binaries, or images, e.g. on crates.io. Goes in
+	/// `repository-artifact`.
+	Artifact,
+
+	/// Archives of a work, e.g. on Zenodo. Goes in `repository`.
+	Archive,
+}
+
+impl RepositoryKind {
+	/// The CFF field for URLs of this kind.
+	pub fn field(self) -> &'static str {
+		match self {
+			Self::Code => "repository-code",
+			Self::Artifact => "repository-artifact",
+			Self::Archive => "repository",
+		}
+	}
+}
+
+impl Display for RepositoryKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Code => "code",
+				Self::Artifact => "artifact",
+				Self::Archive => "archive",
+			}
+		)
+	}
+}
+
+const CODE_HOSTS: &[&str] = &[
+	"bitbucket.org",
+	"codeberg.org",
+	"gitea.com",
+	"github.com",
+	"gitlab.com",
+	"git.sr.ht",
+	"salsa.debian.org",
+];
+
+const ARTIFACT_HOSTS: &[&str] = &[
+	"anaconda.org",
+	"bioconductor.org",
+	"central.sonatype.com",
+	"cran.r-project.org",
+	"crates.io",
+	"ghcr.io",
+	"hackage.haskell.org",
+	"hub.docker.com",
+	"npmjs.com",
+	"nuget.org",
+	"packagist.org",
+	"pypi.org",
+	"quay.io",
+	"repo1.maven.org",
+	"rubygems.org",
+];
+
+const ARCHIVE_HOSTS: &[&str] = &[
+	"archive.softwareheritage.org",
+	"datadryad.org",
+	"figshare.com",
+	"hal.science",
+	"osf.io",
+	"zenodo.org",
+];
+
+/// Classify a URL by its host, or `None` if it's not a known host.
+///
+/// Hosts match with their subdomains, e.g. `www.npmjs.com`, and self-hosted
+/// GitLab instances are recognised by a `gitlab.` subdomain. Releases on code
+/// hosts, like `https://github.com/owner/repo/releases`, are artifacts.
+pub fn classify_repository(url: &Url) -> Option<RepositoryKind> {
+	let host = url.host_str()?.to_ascii_lowercase();
+	let on = |hosts: &[&str]| {
+		hosts
+			.iter()
+			.any(|known| host == *known || host.ends_with(&format!(".{known}")))
+	};
+
+	if on(CODE_HOSTS) || host.starts_with("gitlab.") {
+		let releases = url.path_segments().map_or(false, |mut segments| {
+			segments.any(|segment| segment == "releases")
+		});
+		Some(if releases {
+			RepositoryKind::Artifact
+		} else {
+			RepositoryKind::Code
+		})
+	} else if on(ARTIFACT_HOSTS) {
+		Some(RepositoryKind::Artifact)
+	} else if on(ARCHIVE_HOSTS) || host.contains(".dataverse.") || host.starts_with("dataverse.") {
+		Some(RepositoryKind::Archive)
+	} else {
+		None
+	}
+}
+
+/// Repository URLs which are in a field for another kind of repository.
+///
+/// Returns the field each is in, its URL, and its kind.
+pub(crate) fn misplaced<'u>(
+	repository: Option<&'u Url>,
+	repository_code: Option<&'u Url>,
+	repository_artifact: Option<&'u Url>,
+) -> Vec<(&'static str, &'u Url, RepositoryKind)> {
+	[
+		(RepositoryKind::Archive, repository),
+		(RepositoryKind::Code, repository_code),
+		(RepositoryKind::Artifact, repository_artifact),
+	]
+	.into_iter()
+	.filter_map(|(field, url)| {
+		let url = url?;
+		let kind = classify_repository(url)?;
+		(kind != field).then(|| (field.field(), url, kind))
+	})
+	.collect()
+}
+
+impl Cff {
+	/// Set the repository field for this kind of repository.
+	pub fn set_repository(&mut self, kind: RepositoryKind, url: Url) {
+		match kind {
+			RepositoryKind::Code => self.repository_code = Some(url),
+			RepositoryKind::Artifact => self.repository_artifact = Some(url),
+			RepositoryKind::Archive => self.repository = Some(url),
+		}
+	}
+}
+
+impl Reference {
+	/// Set the repository field for this kind of repository.
+	pub fn set_repository(&mut self, kind: RepositoryKind, url: Url) {
+		match kind {
+			RepositoryKind::Code => self.repository_code = Some(url),
+			RepositoryKind::Artifact => self.repository_artifact = Some(url),
+			RepositoryKind::Archive => self.repository = Some(url),
+		}
+	}
+}
@@ -0,0 +1,98 @@
+//! Validation against the CFF JSON Schema.
+//!
+//! The [types](crate::Cff) of this crate accept some documents which the
+//! [official schema][schema] doesn't, like unknown fields, and the other way
+//! around. This checks a document against the schema itself, as the reference
+//! tooling does, and gives the position in the YAML of each violation.
+//!
+//! The schema for CFF 1.2.0 is embedded as [SCHEMA].
+//!
+//! ```
+//! use citeworks_cff::schema::validate_against_schema;
+//!
+//! let violations = validate_against_schema("
+//! cff-version: 1.2.0
+//! message: Please cite this software.
+//! title: Example
+//! authors:
+//!   - given-names: Jane
+//!     surname: Roe
+//! ").unwrap();
+//!
+//! assert_eq!(violations.len(), 1);
+//! assert_eq!(violations[0].path, "/authors/0");
+//! assert_eq!((violations[0].line, violations[0].column), (6, 5));
+//! ```
+//!
+//! [schema]: https://github.com/citation-file-format/citation-file-format/blob/main/schema.json
+
+use std::fmt::Display;
+
+use jsonschema::{error::ValidationErrorKind, Draft, JSONSchema};
+use serde_json::Value;
+
+use crate::positions::positions;
+
+/// The CFF 1.2.0 JSON Schema.
+pub const SCHEMA: &str = include_str!("../schema/1.2.0.json");
+
+/// A place where a document doesn't conform to the schema.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SchemaViolation {
+	/// JSON Pointer to the value in the document, e.g. `/authors/0`.
+	pub path: String,
+
+	/// Description of the violation, from the validator.
+	pub message: String,
+
+	/// Line of the value in the YAML, starting at 1.
+	pub line: usize,
+
+	/// Column of the value in the YAML, starting at 1.
+	pub column: usize,
+}
+
+impl Display for SchemaViolation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}: {}", self.line, self.column, self.message)
+	}
+}
+
+/// Validate a CFF document, as YAML, against the [SCHEMA].
+///
+/// Returns the violations, in the order the validator finds them, which is
+/// empty for a valid document. Fails if the YAML can't be parsed.
+pub fn validate_against_schema(yaml: &str) -> Result<Vec<SchemaViolation>, String> {
+	let document: Value = serde_yaml::from_str(yaml).map_err(|err| err.to_string())?;
+
+	let schema: Value = serde_json::from_str(SCHEMA).map_err(|err| err.to_string())?;
+	let schema = JSONSchema::options()
+		.with_draft(Draft::Draft7)
+		.compile(&schema)
+		.map_err(|err| err.to_string())?;
+
+	let positions = positions(yaml)?;
+
+	let violations = match schema.validate(&document) {
+		Ok(()) => Vec::new(),
+		Err(errors) => errors
+			.map(|error| {
+				let mut path = error.instance_path.clone().into_vec();
+				// Unexpected keys are reported on their mapping, but are
+				// easier to find at the key itself.
+				if let ValidationErrorKind::AdditionalProperties { unexpected } = &error.kind {
+					path.extend(unexpected.first().cloned());
+				}
+				let marker = positions.find(&path);
+				SchemaViolation {
+					path: error.instance_path.to_string(),
+					message: error.to_string(),
+					line: marker.map_or(1, |marker| marker.line()),
+					column: marker.map_or(1, |marker| marker.col() + 1),
+				}
+			})
+			.collect(),
+	};
+
+	Ok(violations)
+}
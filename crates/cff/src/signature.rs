@@ -0,0 +1,79 @@
+//! Canonical text of documents, to sign citation metadata.
+//!
+//! A signature over a CITATION.cff file breaks as soon as a mirror reformats
+//! it, reorders its keys, or changes how an accented name is encoded, even if
+//! the metadata is the same. So signatures are made over the canonical text of
+//! the document instead: its YAML as written by this crate, with all text in
+//! NFC form and the keys of every mapping sorted. Comments, quoting, and
+//! formatting don't count, but every field does, including ones this crate
+//! doesn't know.
+//!
+//! This doesn't sign anything itself: the canonical text is what to give to a
+//! signing tool like `ssh-keygen -Y sign` or `minisign`, in the [NAMESPACE] if
+//! the tool has namespaces.
+//!
+//! ```
+//! let written = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! authors:
+//!   - family-names: Doe
+//!     given-names: Jane
+//! "#).unwrap();
+//!
+//! let mirrored = citeworks_cff::from_str(r#"
+//! ## Mirrored from the source repository
+//! title: "My Research Software"
+//! authors: [{ given-names: Jane, family-names: Doe }]
+//! message: Please cite this software using these metadata.
+//! cff-version: "1.2.0"
+//! "#).unwrap();
+//!
+//! assert_eq!(
+//!     written.canonical_string().unwrap(),
+//!     mirrored.canonical_string().unwrap(),
+//! );
+//! ```
+
+use serde_yaml::{Mapping, Value};
+
+use crate::{Cff, Result};
+
+/// Namespace of signatures over citation metadata.
+///
+/// Tools like `ssh-keygen -Y` sign within a namespace, so a signature made for
+/// one purpose can't be passed off as one for another.
+pub const NAMESPACE: &str = "citation-file-format";
+
+impl Cff {
+	/// The canonical text of the document, to sign or verify.
+	///
+	/// Two documents with the same metadata have the same canonical text, however
+	/// they're formatted. This text isn't meant to be written as the file.
+	pub fn canonical_string(&self) -> Result<String> {
+		let mut cff = self.clone();
+		cff.normalize_unicode();
+		let value = serde_yaml::to_value(&cff)?;
+		serde_yaml::to_string(&sort_keys(value))
+	}
+}
+
+fn sort_keys(value: Value) -> Value {
+	match value {
+		Value::Mapping(mapping) => {
+			let mut entries: Vec<(Value, Value)> = mapping
+				.into_iter()
+				.map(|(key, value)| (key, sort_keys(value)))
+				.collect();
+			entries.sort_by(|(a, _), (b, _)| a.as_str().cmp(&b.as_str()));
+			Value::Mapping(entries.into_iter().collect::<Mapping>())
+		}
+		Value::Sequence(values) => Value::Sequence(values.into_iter().map(sort_keys).collect()),
+		Value::Tagged(mut tagged) => {
+			tagged.value = sort_keys(tagged.value);
+			Value::Tagged(tagged)
+		}
+		value => value,
+	}
+}
@@ -0,0 +1,141 @@
+//! Generating CFF documents from templates.
+//!
+//! Organisations that want all their projects cited the same way can keep a
+//! template CFF document, with placeholders like `{{version}}` or `{{date}}`
+//! for the parts that change, and fill them in from a context when releasing.
+//!
+//! Placeholders are a name between double braces, optionally with spaces
+//! inside the braces: `{{version}}` and `{{ version }}` are the same. Values
+//! are inserted into the text of the template before it's parsed, so they can
+//! be anywhere, including in keys, but also aren't escaped: quote placeholders
+//! in the template where values could contain YAML syntax.
+//!
+//! ```
+//! use std::collections::BTreeMap;
+//! use citeworks_cff::template::Template;
+//!
+//! let template = Template::new(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: Opaquity
+//! authors:
+//!   - name: Dark Side Software
+//! version: "{{version}}"
+//! date-released: {{ date }}
+//! commit: {{git_commit}}
+//! "#);
+//! assert_eq!(template.placeholders(), vec!["version", "date", "git_commit"]);
+//!
+//! let context = BTreeMap::from([
+//!     ("version".to_string(), "1.2.3".to_string()),
+//!     ("date".to_string(), "2022-08-30".to_string()),
+//!     ("git_commit".to_string(), "8c4e6cd".to_string()),
+//! ]);
+//! let cff = template.instantiate(&context).unwrap();
+//! assert_eq!(cff.version.as_deref(), Some("1.2.3"));
+//! assert_eq!(cff.date_released.unwrap().to_string(), "2022-08-30");
+//! assert_eq!(cff.commit.as_deref(), Some("8c4e6cd"));
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::{from_str, Cff};
+
+/// A CFF document with placeholders.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Template {
+	source: String,
+}
+
+/// A piece of a template.
+enum Part<'t> {
+	Text(&'t str),
+	Placeholder(&'t str),
+}
+
+impl Template {
+	/// A template from its source text.
+	pub fn new(source: impl Into<String>) -> Self {
+		Self {
+			source: source.into(),
+		}
+	}
+
+	/// The source text of the template.
+	pub fn source(&self) -> &str {
+		&self.source
+	}
+
+	/// The names of the placeholders in the template, in order of first use.
+	pub fn placeholders(&self) -> Vec<&str> {
+		let mut names = Vec::new();
+		for part in self.parts() {
+			if let Part::Placeholder(name) = part {
+				if !names.contains(&name) {
+					names.push(name);
+				}
+			}
+		}
+		names
+	}
+
+	/// Fill in the placeholders of the template with values from the context.
+	///
+	/// Fails if the context doesn't have a value for a placeholder.
+	pub fn render(&self, context: &BTreeMap<String, String>) -> Result<String, String> {
+		let mut missing = Vec::new();
+		let mut text = String::with_capacity(self.source.len());
+		for part in self.parts() {
+			match part {
+				Part::Text(t) => text.push_str(t),
+				Part::Placeholder(name) => match context.get(name) {
+					Some(value) => text.push_str(value),
+					None if missing.contains(&name) => {}
+					None => missing.push(name),
+				},
+			}
+		}
+
+		if missing.is_empty() {
+			Ok(text)
+		} else {
+			Err(format!("no value for {}", missing.join(", ")))
+		}
+	}
+
+	/// Fill in the placeholders of the template, and parse the result.
+	pub fn instantiate(&self, context: &BTreeMap<String, String>) -> Result<Cff, String> {
+		let text = self.render(context)?;
+		from_str(&text).map_err(|err| format!("filled-in template doesn't parse: {err}"))
+	}
+
+	fn parts(&self) -> Vec<Part<'_>> {
+		let mut parts = Vec::new();
+		let mut rest = self.source.as_str();
+		while let Some(start) = rest.find("{{") {
+			let end = match rest[start + 2..].find("}}") {
+				Some(end) => start + 2 + end,
+				None => break,
+			};
+
+			let name = rest[start + 2..end].trim();
+			if is_name(name) {
+				parts.push(Part::Text(&rest[..start]));
+				parts.push(Part::Placeholder(name));
+			} else {
+				// Not a placeholder, e.g. YAML flow mappings like `{{a: b}: c}`.
+				parts.push(Part::Text(&rest[..end + 2]));
+			}
+			rest = &rest[end + 2..];
+		}
+		parts.push(Part::Text(rest));
+		parts
+	}
+}
+
+/// Placeholder names are made of ASCII letters, digits, `_`, and `-`.
+fn is_name(s: &str) -> bool {
+	!s.is_empty()
+		&& s.bytes()
+			.all(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'-')
+}
@@ -0,0 +1,406 @@
+//! Property-testing support for code that converts to and from CFF.
+//!
+//! This is only available with the `test-util` feature. It provides [proptest]
+//! strategies that generate valid CFF values, and round-trip assertion helpers.
+//!
+//! Downstream crates can use these to check that their own conversions (e.g.
+//! to and from BibTeX) preserve data:
+//!
+//! ```
+//! use citeworks_cff::test_util::{arb_reference, assert_roundtrip};
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(reference in arb_reference())| {
+//!     assert_roundtrip(
+//!         &reference,
+//!         |r| serde_yaml::to_string(r),
+//!         |s| serde_yaml::from_str(&s),
+//!     );
+//! });
+//! ```
+//!
+//! The generators produce documents that conform to the specification, not
+//! every value the types can represent: for example, entities always have a
+//! name, and dates are always within the four-digit year range.
+//!
+//! There's also a corpus loader and a synthetic document builder, which the
+//! crate's benchmarks use and which can be pointed at your own files.
+
+use std::{fmt::Debug, fs, io, path::Path};
+
+use proptest::{collection::vec, option, prelude::*};
+use spdx::Expression;
+use url::Url;
+
+use crate::{
+	identifiers::Identifier,
+	names::{EntityName, Name, NameMeta, PersonName},
+	references::{PublicationStatus, RefType, Reference},
+	Cff, Date, License, WorkType,
+};
+
+/// Assert that a value survives a conversion there and back unchanged.
+///
+/// `there` converts the value to some other representation, and `back`
+/// converts that representation to the original type again. Panics with both
+/// values if they differ, or if either conversion fails.
+pub fn assert_roundtrip<T, U, E1, E2>(
+	value: &T,
+	there: impl FnOnce(&T) -> Result<U, E1>,
+	back: impl FnOnce(U) -> Result<T, E2>,
+) where
+	T: Debug + PartialEq,
+	E1: Debug,
+	E2: Debug,
+{
+	let there = there(value).expect("conversion there failed");
+	let back = back(there).expect("conversion back failed");
+	assert_eq!(value, &back, "value did not survive the round-trip");
+}
+
+/// Assert that a [Cff] document survives serialisation to YAML and back.
+pub fn assert_yaml_roundtrip(cff: &Cff) {
+	assert_roundtrip(cff, crate::to_string, |s| crate::from_str(&s));
+}
+
+/// Strategy for short, printable, non-empty strings.
+pub fn arb_text() -> impl Strategy<Value = String> {
+	"[A-Za-z0-9][A-Za-z0-9 .,'&()-]{0,30}".prop_map(|s| s.trim_end().to_string())
+}
+
+/// Strategy for URLs.
+pub fn arb_url() -> impl Strategy<Value = Url> {
+	("[a-z]{1,12}", "[a-z0-9]{0,12}").prop_map(|(host, path)| {
+		Url::parse(&format!("https://{host}.example/{path}")).expect("generated URL is valid")
+	})
+}
+
+/// Strategy for [Date]s.
+pub fn arb_date() -> impl Strategy<Value = Date> {
+	(1000_i64..=9999, 1_u8..=12, 1_u8..=28).prop_map(|(year, month, day)| Date { year, month, day })
+}
+
+/// Strategy for DOI strings.
+pub fn arb_doi() -> impl Strategy<Value = String> {
+	("[0-9]{4,5}", "[a-z0-9.]{1,16}")
+		.prop_map(|(registrant, suffix)| format!("10.{registrant}/{suffix}"))
+}
+
+/// Strategy for [License]s, picked from a small set of SPDX identifiers.
+pub fn arb_license() -> impl Strategy<Value = License> {
+	let id = prop::sample::select(vec![
+		"Apache-2.0",
+		"MIT",
+		"GPL-3.0-or-later",
+		"BSD-3-Clause",
+		"CC-BY-4.0",
+		"MPL-2.0",
+	]);
+	prop_oneof![
+		id.clone().prop_map(|id| License::Single(Box::new(
+			Expression::parse(id).expect("known SPDX identifier")
+		))),
+		vec(id, 2..4).prop_map(|ids| License::AnyOf(
+			ids.into_iter()
+				.map(|id| Expression::parse(id).expect("known SPDX identifier"))
+				.collect()
+		)),
+	]
+}
+
+/// Strategy for [NameMeta].
+pub fn arb_name_meta() -> impl Strategy<Value = NameMeta> {
+	(
+		option::of(arb_url()),
+		option::of(arb_text()),
+		option::of(arb_text()),
+		option::of("[a-z]{1,8}@[a-z]{1,8}\\.example"),
+		option::of(arb_url()),
+	)
+		.prop_map(|(orcid, city, country, email, website)| NameMeta {
+			orcid,
+			city,
+			country,
+			email,
+			website,
+			..Default::default()
+		})
+}
+
+/// Strategy for [PersonName]s.
+pub fn arb_person() -> impl Strategy<Value = PersonName> {
+	(
+		option::of(arb_text()),
+		option::of(arb_text()),
+		option::of("(von|van|de|da)"),
+		option::of("(Jr\\.|Sr\\.|III)"),
+		option::of(arb_text()),
+		arb_name_meta(),
+	)
+		.prop_map(
+			|(family_names, given_names, name_particle, name_suffix, affiliation, meta)| {
+				PersonName {
+					family_names,
+					given_names,
+					name_particle,
+					name_suffix,
+					affiliation,
+					meta,
+				}
+			},
+		)
+}
+
+/// Strategy for [EntityName]s.
+///
+/// Entities always have a name, as otherwise they're indistinguishable from
+/// persons once serialised.
+pub fn arb_entity() -> impl Strategy<Value = EntityName> {
+	(
+		arb_text().prop_filter("anonymous is a special name", |n| n != "anonymous"),
+		option::of(arb_date()),
+		option::of(arb_date()),
+		arb_name_meta(),
+	)
+		.prop_map(|(name, date_start, date_end, meta)| EntityName {
+			name: Some(name),
+			date_start,
+			date_end,
+			meta,
+		})
+}
+
+/// Strategy for [Name]s of any kind.
+pub fn arb_name() -> impl Strategy<Value = Name> {
+	prop_oneof![
+		4 => arb_person().prop_map(Name::Person),
+		2 => arb_entity().prop_map(Name::Entity),
+		1 => Just(Name::Anonymous),
+	]
+}
+
+/// Strategy for [Identifier]s.
+pub fn arb_identifier() -> impl Strategy<Value = Identifier> {
+	let description = || option::of(arb_text());
+	prop_oneof![
+		(arb_doi(), description())
+			.prop_map(|(value, description)| Identifier::Doi { value, description }),
+		(arb_url(), description())
+			.prop_map(|(value, description)| Identifier::Url { value, description }),
+		("swh:1:dir:[0-9a-f]{40}", description())
+			.prop_map(|(value, description)| Identifier::Swh { value, description }),
+		("arXiv:[0-9]{4}\\.[0-9]{5}", description())
+			.prop_map(|(value, description)| Identifier::Other { value, description }),
+	]
+}
+
+/// Strategy for [RefType]s.
+pub fn arb_ref_type() -> impl Strategy<Value = RefType> {
+	prop::sample::select(vec![
+		RefType::Article,
+		RefType::Book,
+		RefType::ConferencePaper,
+		RefType::Data,
+		RefType::Generic,
+		RefType::Patent,
+		RefType::Report,
+		RefType::Software,
+		RefType::Thesis,
+		RefType::Website,
+	])
+}
+
+/// Strategy for [PublicationStatus]es.
+pub fn arb_status() -> impl Strategy<Value = PublicationStatus> {
+	prop::sample::select(vec![
+		PublicationStatus::Abstract,
+		PublicationStatus::AdvanceOnline,
+		PublicationStatus::InPreparation,
+		PublicationStatus::InPress,
+		PublicationStatus::Preprint,
+		PublicationStatus::Submitted,
+	])
+}
+
+/// Strategy for [Reference]s.
+///
+/// This covers a representative subset of fields rather than all of them.
+pub fn arb_reference() -> impl Strategy<Value = Reference> {
+	(
+		(
+			arb_ref_type(),
+			vec(arb_name(), 1..4),
+			option::of(arb_text()),
+			option::of(arb_text()),
+			option::of(arb_doi()),
+			vec(arb_identifier(), 0..3),
+		),
+		(
+			option::of(arb_text()),
+			option::of(1_u64..2000),
+			option::of(1_u64..2000),
+			vec(arb_text(), 0..4),
+			option::of(arb_license()),
+			option::of(arb_url()),
+		),
+		(
+			option::of(1_u64..200),
+			option::of(1000_u64..3000),
+			option::of(1_u8..=12),
+			option::of(arb_date()),
+			option::of(arb_status()),
+			vec(arb_name(), 0..3),
+		),
+	)
+		.prop_map(
+			|(
+				(work_type, authors, title, abstract_text, doi, identifiers),
+				(journal, start, end, keywords, license, url),
+				(volume, year, month, date_published, status, editors),
+			)| Reference {
+				work_type,
+				authors,
+				title,
+				abstract_text,
+				doi,
+				identifiers,
+				journal,
+				start,
+				end,
+				keywords,
+				license,
+				url,
+				volume,
+				year,
+				month,
+				date_published,
+				status,
+				editors,
+				..Default::default()
+			},
+		)
+}
+
+/// Strategy for whole [Cff] documents.
+pub fn arb_cff() -> impl Strategy<Value = Cff> {
+	(
+		(
+			arb_text(),
+			arb_text(),
+			option::of(prop_oneof![
+				Just(WorkType::Software),
+				Just(WorkType::Dataset)
+			]),
+			option::of("[0-9]{1,2}\\.[0-9]{1,2}\\.[0-9]{1,2}"),
+			option::of("[0-9a-f]{40}"),
+			option::of(arb_date()),
+		),
+		(
+			vec(arb_name(), 1..4),
+			vec(arb_name(), 0..2),
+			option::of(arb_doi()),
+			vec(arb_identifier(), 0..3),
+			option::of(arb_license()),
+			vec(arb_text(), 0..4),
+		),
+		(
+			option::of(arb_url()),
+			option::of(arb_url()),
+			option::of(arb_reference()),
+			vec(arb_reference(), 0..3),
+		),
+	)
+		.prop_map(
+			|(
+				(message, title, work_type, version, commit, date_released),
+				(authors, contact, doi, identifiers, license, keywords),
+				(url, repository_code, preferred_citation, references),
+			)| Cff {
+				message,
+				title,
+				work_type,
+				version,
+				commit,
+				date_released,
+				authors,
+				contact,
+				doi,
+				identifiers,
+				license,
+				keywords,
+				url,
+				repository_code,
+				preferred_citation,
+				references,
+				..Default::default()
+			},
+		)
+}
+
+/// A named file of CFF text, for benchmarks and corpus tests.
+#[derive(Debug, Clone)]
+pub struct CorpusFile {
+	/// The file name, without directories.
+	pub name: String,
+
+	/// The raw contents of the file.
+	pub contents: Vec<u8>,
+}
+
+/// Load all `.cff` files from a directory (non-recursively), sorted by name.
+pub fn load_corpus(dir: impl AsRef<Path>) -> io::Result<Vec<CorpusFile>> {
+	let mut files = Vec::new();
+	for entry in fs::read_dir(dir)? {
+		let path = entry?.path();
+		if path.extension().map_or(false, |ext| ext == "cff") {
+			files.push(CorpusFile {
+				name: path
+					.file_name()
+					.map_or_else(String::new, |n| n.to_string_lossy().into_owned()),
+				contents: fs::read(&path)?,
+			});
+		}
+	}
+
+	files.sort_by(|a, b| a.name.cmp(&b.name));
+	Ok(files)
+}
+
+/// Build a deterministic [Cff] document with the given number of references.
+///
+/// This is useful to benchmark or test behaviour on large bibliographies
+/// without checking large files into a repository.
+pub fn synthetic_cff(references: usize) -> Cff {
+	Cff {
+		title: "Synthetic corpus".into(),
+		authors: vec![Name::Person(PersonName {
+			family_names: Some("Doe".into()),
+			given_names: Some("Jane".into()),
+			..Default::default()
+		})],
+		references: (0..references)
+			.map(|n| Reference {
+				work_type: RefType::Article,
+				authors: (0..(n % 5 + 1))
+					.map(|a| {
+						Name::Person(PersonName {
+							family_names: Some(format!("Author{a}")),
+							given_names: Some(format!("Given{n}")),
+							..Default::default()
+						})
+					})
+					.collect(),
+				title: Some(format!("On the synthesis of reference number {n}")),
+				journal: Some("Journal of Synthetic Results".into()),
+				doi: Some(format!("10.5555/synthetic.{n}")),
+				year: Some(1950 + (n % 70) as u64),
+				volume: Some((n % 40) as u64 + 1),
+				start: Some((n % 300) as u64 + 1),
+				end: Some((n % 300) as u64 + 12),
+				keywords: vec!["synthetic".into(), format!("batch-{}", n / 100)],
+				..Default::default()
+			})
+			.collect(),
+		..Default::default()
+	}
+}
@@ -0,0 +1,228 @@
+//! Unicode normalisation and detection of encoding damage.
+//!
+//! Metadata that went through old exports is often damaged: UTF-8 text read
+//! as Latin-1 or Windows-1252 turns "Müller" into "MÃ¼ller" (mojibake), and
+//! characters a tool couldn't encode turn into "M??ller". Text from different
+//! sources also mixes composed and decomposed forms of accented characters,
+//! which look the same but don't compare equal.
+//!
+//! [Cff::normalize_unicode] puts all text in NFC form, and [Cff::mojibake]
+//! finds text that looks damaged, for a human to review. Where the damage can
+//! be undone, a repair is suggested.
+//!
+//! ```
+//! let mut cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: Analyse de donnÃ©es
+//! authors:
+//!   - family-names: M??ller
+//! "#).unwrap();
+//!
+//! let suspects = cff.mojibake();
+//! assert_eq!(suspects.len(), 2);
+//! assert_eq!(suspects[0].path, "title");
+//! assert_eq!(suspects[0].suggestion.as_deref(), Some("Analyse de données"));
+//! assert_eq!(suspects[1].path, "authors[0].family-names");
+//! assert_eq!(suspects[1].suggestion, None);
+//! ```
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_yaml::Value;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{references::Reference, Cff};
+
+/// Text that looks damaged.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Suspect {
+	/// Where the text is, e.g. `references[2].authors[0].family-names`.
+	pub path: String,
+
+	/// The text.
+	pub value: String,
+
+	/// What the text probably was, if it can be worked out.
+	pub suggestion: Option<String>,
+}
+
+/// Whether text looks damaged by encoding problems.
+pub fn is_mojibake(text: &str) -> bool {
+	text.contains('\u{FFFD}') || has_lost_characters(text) || repair_mojibake(text).is_some()
+}
+
+/// Undo UTF-8 text having been read as Windows-1252 or Latin-1.
+///
+/// Returns `None` if the text doesn't look like it was, e.g. "MÃ¼ller" is
+/// repaired to "Müller", but "Müller" is left alone.
+pub fn repair_mojibake(text: &str) -> Option<String> {
+	let mut repaired = String::with_capacity(text.len());
+	let mut changed = false;
+	for (n, word) in text.split(' ').enumerate() {
+		if n > 0 {
+			repaired.push(' ');
+		}
+		match repair_word(word) {
+			Some(word) => {
+				repaired.push_str(&word);
+				changed = true;
+			}
+			None => repaired.push_str(word),
+		}
+	}
+	if changed {
+		Some(repaired)
+	} else {
+		None
+	}
+}
+
+fn repair_word(word: &str) -> Option<String> {
+	if word.is_ascii() {
+		return None;
+	}
+
+	let bytes = word.chars().map(cp1252_byte).collect::<Option<Vec<u8>>>()?;
+	match String::from_utf8(bytes) {
+		Ok(repaired) if repaired != word => Some(repaired),
+		_ => None,
+	}
+}
+
+/// The Windows-1252 byte of a character, if it has one.
+fn cp1252_byte(c: char) -> Option<u8> {
+	let byte = match c {
+		'€' => 0x80,
+		'‚' => 0x82,
+		'ƒ' => 0x83,
+		'„' => 0x84,
+		'…' => 0x85,
+		'†' => 0x86,
+		'‡' => 0x87,
+		'ˆ' => 0x88,
+		'‰' => 0x89,
+		'Š' => 0x8A,
+		'‹' => 0x8B,
+		'Œ' => 0x8C,
+		'Ž' => 0x8E,
+		'‘' => 0x91,
+		'’' => 0x92,
+		'“' => 0x93,
+		'”' => 0x94,
+		'•' => 0x95,
+		'–' => 0x96,
+		'—' => 0x97,
+		'˜' => 0x98,
+		'™' => 0x99,
+		'š' => 0x9A,
+		'›' => 0x9B,
+		'œ' => 0x9C,
+		'ž' => 0x9E,
+		'Ÿ' => 0x9F,
+		c => return u8::try_from(u32::from(c)).ok(),
+	};
+	Some(byte)
+}
+
+/// Question marks within words, where characters were lost.
+///
+/// URLs are skipped, as their query strings look just like that.
+fn has_lost_characters(text: &str) -> bool {
+	if text.contains("://") {
+		return false;
+	}
+
+	let chars: Vec<char> = text.chars().collect();
+	chars
+		.windows(3)
+		.any(|w| w[0].is_alphabetic() && w[1] == '?' && (w[2].is_alphabetic() || w[2] == '?'))
+}
+
+impl Cff {
+	/// Put all text of the document in Unicode Normalization Form C.
+	pub fn normalize_unicode(&mut self) {
+		normalize(self);
+	}
+
+	/// Find text in the document that looks damaged by encoding problems.
+	pub fn mojibake(&self) -> Vec<Suspect> {
+		suspects(self)
+	}
+}
+
+impl Reference {
+	/// Put all text of the reference in Unicode Normalization Form C.
+	pub fn normalize_unicode(&mut self) {
+		normalize(self);
+	}
+
+	/// Find text in the reference that looks damaged by encoding problems.
+	pub fn mojibake(&self) -> Vec<Suspect> {
+		suspects(self)
+	}
+}
+
+fn normalize<T: Serialize + DeserializeOwned>(value: &mut T) {
+	let mut yaml = match serde_yaml::to_value(&*value) {
+		Ok(yaml) => yaml,
+		Err(_) => return,
+	};
+	map_strings(&mut yaml, &|s| s.nfc().collect());
+	if let Ok(normalized) = serde_yaml::from_value(yaml) {
+		*value = normalized;
+	}
+}
+
+fn map_strings(value: &mut Value, f: &impl Fn(&str) -> String) {
+	match value {
+		Value::String(s) => *s = f(s),
+		Value::Sequence(seq) => {
+			for value in seq {
+				map_strings(value, f);
+			}
+		}
+		Value::Mapping(map) => {
+			for (_, value) in map.iter_mut() {
+				map_strings(value, f);
+			}
+		}
+		Value::Tagged(tagged) => map_strings(&mut tagged.value, f),
+		Value::Null | Value::Bool(_) | Value::Number(_) => {}
+	}
+}
+
+fn suspects<T: Serialize>(value: &T) -> Vec<Suspect> {
+	let mut suspects = Vec::new();
+	if let Ok(yaml) = serde_yaml::to_value(value) {
+		find_suspects(&yaml, String::new(), &mut suspects);
+	}
+	suspects
+}
+
+fn find_suspects(value: &Value, path: String, suspects: &mut Vec<Suspect>) {
+	match value {
+		Value::String(s) if is_mojibake(s) => suspects.push(Suspect {
+			path,
+			value: s.clone(),
+			suggestion: repair_mojibake(s),
+		}),
+		Value::Sequence(seq) => {
+			for (n, value) in seq.iter().enumerate() {
+				find_suspects(value, format!("{path}[{n}]"), suspects);
+			}
+		}
+		Value::Mapping(map) => {
+			for (key, value) in map {
+				let key = key.as_str().unwrap_or_default();
+				let path = if path.is_empty() {
+					key.to_string()
+				} else {
+					format!("{path}.{key}")
+				};
+				find_suspects(value, path, suspects);
+			}
+		}
+		Value::Tagged(tagged) => find_suspects(&tagged.value, path, suspects),
+		_ => {}
+	}
+}
@@ -0,0 +1,1186 @@
+//! Validation of CFF documents beyond what the types enforce.
+//!
+//! Deserialisation only checks that a document has the right shape. The
+//! specification has further requirements, like a non-empty title, or the
+//! format of DOIs and ORCIDs, and conventions, like which fields a reference
+//! of a given type should have, which are checked here. Each check is a [Rule] with
+//! a stable code and name, like `CFF014` and `thesis-institution`, and rules can
+//! be suppressed or given another severity with [ValidationOptions], which can
+//! also be read from a [config file](ValidationOptions::from_config).
+//!
+//! Some rules aren't about the specification, but about what a particular tool
+//! supports. They're grouped in [Ruleset]s, which are only checked when enabled,
+//! e.g. [Ruleset::Github] for GitHub's "Cite this repository" box, or
+//! [Ruleset::Zenodo] for Zenodo's archiving of GitHub releases.
+//!
+//! ```
+//! use citeworks_cff::{
+//!     references::{RefType, Reference},
+//!     validation::{Rule, ValidationOptions},
+//! };
+//!
+//! let thesis = Reference {
+//!     work_type: RefType::Thesis,
+//!     thesis_type: Some("PhD".into()),
+//!     ..Default::default()
+//! };
+//!
+//! let issues = thesis.validate();
+//! assert_eq!(issues.len(), 1);
+//! assert_eq!(issues[0].rule, Rule::ThesisInstitution);
+//!
+//! let options = ValidationOptions::default().suppress(Rule::ThesisInstitution);
+//! assert!(thesis.validate_with(&options).is_empty());
+//! ```
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fmt::Display,
+	str::FromStr,
+};
+
+use spdx::LicenseItem;
+use url::Url;
+
+use crate::{
+	identifiers::{validate_doi, Identifier, IdentifierKind},
+	names::{validate_orcid, validate_orcid_shape, validate_ror, Name},
+	references::{RefType, Reference},
+	repositories::misplaced,
+	Cff, License, WorkType,
+};
+
+/// How serious a validation issue is.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Severity {
+	/// Informational, e.g. a suggestion.
+	Info,
+
+	/// The document is valid but likely incomplete or incorrect.
+	Warning,
+
+	/// The document violates the specification.
+	Error,
+}
+
+impl FromStr for Severity {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"info" => Ok(Self::Info),
+			"warning" => Ok(Self::Warning),
+			"error" => Ok(Self::Error),
+			_ => Err(format!("unknown severity: {s:?}")),
+		}
+	}
+}
+
+impl Display for Severity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Info => "info",
+				Self::Warning => "warning",
+				Self::Error => "error",
+			}
+		)
+	}
+}
+
+macro_rules! rules {
+	(@ruleset) => { None };
+	(@ruleset $ruleset:ident) => { Some(Ruleset::$ruleset) };
+	($(
+		$(#[doc = $doc:literal])+
+		$variant:ident = $code:literal, $name:literal, $severity:ident $(in $ruleset:ident)?;
+	)+) => {
+		/// A validation rule.
+		///
+		/// Each rule has a stable code, like `CFF012`, and a stable kebab-case
+		/// name, either of which is used to suppress it or change its severity.
+		/// Codes are never reused, even if a rule is removed.
+		#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+		#[non_exhaustive]
+		pub enum Rule {
+			$(
+				$(#[doc = $doc])+
+				$variant,
+			)+
+		}
+
+		impl Rule {
+			/// All known rules.
+			pub const ALL: &'static [Rule] = &[$(Self::$variant),+];
+
+			/// The stable code of the rule, like `CFF012`.
+			pub fn code(self) -> &'static str {
+				match self {
+					$(Self::$variant => $code,)+
+				}
+			}
+
+			/// The stable name of the rule.
+			pub fn name(self) -> &'static str {
+				match self {
+					$(Self::$variant => $name,)+
+				}
+			}
+
+			/// What the rule checks, from its documentation, as plain text.
+			pub fn description(self) -> String {
+				plain_doc(match self {
+					$(Self::$variant => concat!($($doc),+),)+
+				})
+			}
+
+			/// The severity issues raised by this rule have by default.
+			pub fn default_severity(self) -> Severity {
+				match self {
+					$(Self::$variant => Severity::$severity,)+
+				}
+			}
+
+			/// The ruleset this rule is part of, if it's only checked on demand.
+			pub fn ruleset(self) -> Option<Ruleset> {
+				match self {
+					$(Self::$variant => rules!(@ruleset $($ruleset)?),)+
+				}
+			}
+		}
+	};
+}
+
+rules! {
+	/// The message of a document shouldn't be empty.
+	MessageEmpty = "CFF001", "message-empty", Error;
+
+	/// The title of a document shouldn't be empty.
+	TitleEmpty = "CFF002", "title-empty", Error;
+
+	/// A document should have at least one author.
+	AuthorsEmpty = "CFF003", "authors-empty", Error;
+
+	/// A month should be between 1 and 12.
+	MonthRange = "CFF004", "month-range", Error;
+
+	/// An ORCID should be an `https://orcid.org/` URL with four groups of four
+	/// digits. See [validate_orcid] to also check the checksum.
+	OrcidFormat = "CFF005", "orcid-format", Error;
+
+	/// A DOI should be bare, with a well-formed prefix. See [validate_doi].
+	DoiFormat = "CFF006", "doi-format", Error;
+
+	/// An ISBN should be 10 or 13 digits, with a correct check digit. See
+	/// [Isbn](crate::identifiers::Isbn).
+	IsbnFormat = "CFF007", "isbn-format", Warning;
+
+	/// An ISSN should be 8 digits, with a correct check digit. See
+	/// [Issn](crate::identifiers::Issn).
+	IssnFormat = "CFF008", "issn-format", Warning;
+
+	/// A type should be one of CFF 1.2.0's. Others are kept as written, but
+	/// tools may not know them.
+	UnknownType = "CFF009", "unknown-type", Warning;
+
+	/// The preferred citation is for another work to cite instead, such as a
+	/// paper about the software, not the work itself.
+	PreferredCitationSelf = "CFF010", "preferred-citation-self", Warning;
+
+	/// A conference paper should name the conference it was presented at.
+	ConferencePaperConference = "CFF011", "conference-paper-conference", Warning;
+
+	/// A conference paper should have the title of the proceedings.
+	ConferencePaperCollectionTitle = "CFF012", "conference-paper-collection-title", Warning;
+
+	/// A patent should have the states it's granted in, or its number.
+	PatentStatesOrNumber = "CFF013", "patent-states-or-number", Warning;
+
+	/// A thesis should have the institution it was produced at.
+	ThesisInstitution = "CFF014", "thesis-institution", Warning;
+
+	/// A thesis should have its type, e.g. "PhD".
+	ThesisType = "CFF015", "thesis-type", Warning;
+
+	/// An article should have the journal it was published in.
+	ArticleJournal = "CFF016", "article-journal", Warning;
+
+	/// A book should have its publisher.
+	BookPublisher = "CFF017", "book-publisher", Warning;
+
+	/// A report should have the institution that produced it.
+	ReportInstitution = "CFF018", "report-institution", Warning;
+
+	/// A website should have its URL.
+	WebsiteUrl = "CFF019", "website-url", Warning;
+
+	/// A ROR identifier should be well-formed.
+	RorFormat = "CFF020", "ror-format", Error;
+
+	/// A commit should be a full 40-character commit hash, like Git's, or a
+	/// revision like `Revision: 8612` for other version control systems.
+	CommitFormat = "CFF021", "commit-format", Warning;
+
+	/// A version shouldn't be blank, i.e. only spaces.
+	VersionBlank = "CFF022", "version-blank", Error;
+
+	/// An abstract should describe the work, not only link to a description.
+	AbstractUrl = "CFF023", "abstract-url", Warning;
+
+	/// Repository URLs should be in the field for their kind of repository,
+	/// e.g. crates.io in `repository-artifact`. See
+	/// [classify_repository](crate::repositories::classify_repository).
+	RepositoryField = "CFF024", "repository-field", Warning;
+
+	/// Embargo fields only apply to datasets.
+	EmbargoNotDataset = "CFF025", "embargo-not-dataset", Warning;
+
+	/// A dataset shouldn't become available before it's released.
+	EmbargoBeforeRelease = "CFF026", "embargo-before-release", Error;
+
+	/// The available date and embargo period of a dataset should agree.
+	EmbargoMismatch = "CFF027", "embargo-mismatch", Warning;
+
+	/// Contacts should also be authors.
+	///
+	/// This is a convention in some communities rather than a requirement of
+	/// the specification. See [Name::is_same] for how names are compared.
+	ContactNotAuthor = "CFF028", "contact-not-author", Info;
+
+	/// A license should be a valid SPDX expression. Others are kept as
+	/// written, see [License](crate::License).
+	LicenseFormat = "CFF040", "license-format", Error;
+
+	/// GitHub shows the preferred citation instead of the work, so the work's
+	/// own version and DOI aren't shown.
+	GithubPreferredCitation = "CFF029", "github-preferred-citation", Info in Github;
+
+	/// GitHub only shows DOI identifiers.
+	GithubIdentifierKind = "CFF030", "github-identifier-kind", Info in Github;
+
+	/// GitHub shows preferred citations of types without a BibTeX equivalent as
+	/// generic `@misc` entries.
+	GithubPreferredCitationType = "CFF031", "github-preferred-citation-type", Warning in Github;
+
+	/// GitHub shows the authors of the preferred citation, not of the work, so
+	/// it needs its own.
+	GithubPreferredCitationAuthors = "CFF032", "github-preferred-citation-authors", Warning in Github;
+
+	/// GitHub shows the year of the preferred citation, from `year` or
+	/// `date-published`.
+	GithubPreferredCitationYear = "CFF033", "github-preferred-citation-year", Warning in Github;
+
+	/// GitHub cites people by family name and initials, so people without a
+	/// family name are shown as just initials.
+	GithubFamilyNames = "CFF034", "github-family-names", Warning in Github;
+
+	/// Zenodo needs a family name for each person who is a creator.
+	ZenodoCreatorName = "CFF035", "zenodo-creator-name", Error in Zenodo;
+
+	/// Zenodo rejects ORCIDs that aren't well-formed, including their checksum.
+	/// See [validate_orcid].
+	ZenodoOrcid = "CFF036", "zenodo-orcid", Error in Zenodo;
+
+	/// Zenodo records have a single license, from the SPDX list, without
+	/// exceptions or `+`.
+	ZenodoLicense = "CFF037", "zenodo-license", Error in Zenodo;
+
+	/// Zenodo uses the abstract as the description of the record.
+	ZenodoAbstract = "CFF038", "zenodo-abstract", Info in Zenodo;
+
+	/// Zenodo mints a DOI for each release, and a concept DOI for all of them
+	/// which always resolves to the latest version. Both look the same, so
+	/// this is a reminder to check which one the work's DOI is.
+	ZenodoDoi = "CFF039", "zenodo-doi", Info in Zenodo;
+}
+
+/// Documentation as plain text, with links replaced by their text.
+fn plain_doc(doc: &str) -> String {
+	let mut text = String::with_capacity(doc.len());
+	let mut rest = doc.trim();
+	while let Some(start) = rest.find('[') {
+		let end = match rest[start..].find(']') {
+			Some(end) => start + end,
+			None => break,
+		};
+		text.push_str(&rest[..start]);
+		text.push_str(&rest[start + 1..end]);
+		rest = &rest[end + 1..];
+		if rest.starts_with('(') {
+			if let Some(close) = rest.find(')') {
+				rest = &rest[close + 1..];
+			}
+		}
+	}
+	text.push_str(rest);
+	text
+}
+
+/// Groups of rules about what a tool supports, which are checked on demand.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum Ruleset {
+	/// What GitHub's "Cite this repository" box shows, and what it ignores.
+	Github,
+
+	/// What Zenodo needs to make a record of a GitHub release.
+	Zenodo,
+}
+
+impl Display for Ruleset {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Github => "github",
+				Self::Zenodo => "zenodo",
+			}
+		)
+	}
+}
+
+impl FromStr for Ruleset {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"github" => Ok(Self::Github),
+			"zenodo" => Ok(Self::Zenodo),
+			_ => Err(format!("unknown ruleset: {s:?}")),
+		}
+	}
+}
+
+impl Display for Rule {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.name())
+	}
+}
+
+/// Parses a rule from its name or its code.
+impl FromStr for Rule {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::ALL
+			.iter()
+			.copied()
+			.find(|rule| rule.name() == s || rule.code().eq_ignore_ascii_case(s))
+			.ok_or_else(|| format!("unknown rule: {s:?}"))
+	}
+}
+
+/// A problem found while validating.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ValidationIssue {
+	/// The rule that raised the issue.
+	pub rule: Rule,
+
+	/// How serious the issue is.
+	pub severity: Severity,
+
+	/// Path to the field the issue is about, e.g. `references[2].conference`.
+	pub path: String,
+
+	/// Human-readable description of the issue, in English.
+	pub message: String,
+
+	/// Values in the message, by name, to translate it with.
+	///
+	/// See the [i18n](crate::i18n) module, with the `i18n` feature.
+	pub args: BTreeMap<String, String>,
+}
+
+impl Display for ValidationIssue {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{}: {}: {} [{}]",
+			self.severity, self.path, self.message, self.rule
+		)
+	}
+}
+
+/// Options for validation.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationOptions {
+	/// Rules that won't raise issues.
+	pub suppressed: BTreeSet<Rule>,
+
+	/// Rulesets to check as well as the specification's rules.
+	pub rulesets: BTreeSet<Ruleset>,
+
+	/// Severities of issues raised by rules, instead of their default.
+	pub severities: BTreeMap<Rule, Severity>,
+}
+
+impl ValidationOptions {
+	/// Read options from a config file.
+	///
+	/// The file is YAML, like CFF, with the severity of rules, by name or code,
+	/// or `off` to suppress them, and rulesets to check:
+	///
+	/// ```
+	/// use citeworks_cff::validation::{Rule, Ruleset, Severity, ValidationOptions};
+	///
+	/// let options = ValidationOptions::from_config("
+	/// rules:
+	///   commit-format: error
+	///   contact-not-author: off
+	///   CFF014: warning
+	/// rulesets: [zenodo]
+	/// ").unwrap();
+	///
+	/// assert_eq!(options.severities[&Rule::CommitFormat], Severity::Error);
+	/// assert!(options.suppressed.contains(&Rule::ContactNotAuthor));
+	/// assert_eq!(options.severities[&Rule::ThesisInstitution], Severity::Warning);
+	/// assert!(options.rulesets.contains(&Ruleset::Zenodo));
+	/// ```
+	///
+	/// Fails on unknown rules, rulesets, or severities, so that typos aren't
+	/// silently ignored.
+	pub fn from_config(yaml: &str) -> Result<Self, String> {
+		#[derive(serde::Deserialize)]
+		#[serde(deny_unknown_fields)]
+		struct Config {
+			#[serde(default)]
+			rules: BTreeMap<String, String>,
+			#[serde(default)]
+			rulesets: Vec<String>,
+		}
+
+		// An empty file is an empty config, not a YAML null.
+		if yaml.trim().is_empty() {
+			return Ok(Self::default());
+		}
+
+		let config: Config = serde_yaml::from_str(yaml).map_err(|err| err.to_string())?;
+		let mut options = Self::default();
+		for (rule, level) in config.rules {
+			let rule = Rule::from_str(&rule)?;
+			match level.as_str() {
+				"off" => options = options.suppress(rule),
+				level => options = options.severity(rule, Severity::from_str(level)?),
+			}
+		}
+		for ruleset in config.rulesets {
+			options = options.ruleset(Ruleset::from_str(&ruleset)?);
+		}
+
+		Ok(options)
+	}
+
+	/// Suppress a rule.
+	///
+	/// This replaces any severity given to the rule before.
+	#[doc(alias = "allow")]
+	#[must_use]
+	pub fn suppress(mut self, rule: Rule) -> Self {
+		self.severities.remove(&rule);
+		self.suppressed.insert(rule);
+		self
+	}
+
+	/// Raise issues for a rule with this severity instead of its default.
+	///
+	/// This turns the rule back on if it was suppressed before.
+	#[must_use]
+	pub fn severity(mut self, rule: Rule, severity: Severity) -> Self {
+		self.suppressed.remove(&rule);
+		self.severities.insert(rule, severity);
+		self
+	}
+
+	/// Raise issues for a rule as errors, e.g. to enforce a convention.
+	///
+	/// Rules in a ruleset are still only checked if the ruleset is.
+	#[must_use]
+	pub fn deny(self, rule: Rule) -> Self {
+		self.severity(rule, Severity::Error)
+	}
+
+	/// Add options from `other` to these, e.g. from the command line to those
+	/// from a config file. Rules suppressed or given a severity in `other` take
+	/// precedence.
+	#[must_use]
+	pub fn merge(mut self, other: Self) -> Self {
+		for rule in other.suppressed {
+			self = self.suppress(rule);
+		}
+		for (rule, severity) in other.severities {
+			self = self.severity(rule, severity);
+		}
+		self.rulesets.extend(other.rulesets);
+		self
+	}
+
+	/// Check a ruleset.
+	#[must_use]
+	pub fn ruleset(mut self, ruleset: Ruleset) -> Self {
+		self.rulesets.insert(ruleset);
+		self
+	}
+}
+
+/// Collects issues while validating, applying the options.
+#[derive(Debug)]
+pub(crate) struct Validator<'opts> {
+	options: &'opts ValidationOptions,
+	prefix: String,
+	issues: Vec<ValidationIssue>,
+}
+
+impl<'opts> Validator<'opts> {
+	pub(crate) fn new(options: &'opts ValidationOptions) -> Self {
+		Self {
+			options,
+			prefix: String::new(),
+			issues: Vec::new(),
+		}
+	}
+
+	/// Run `f` with all paths prefixed by `prefix`.
+	pub(crate) fn nested(&mut self, prefix: &str, f: impl FnOnce(&mut Self)) {
+		let outer = self.prefix.clone();
+		self.prefix = join_path(&outer, prefix);
+		f(self);
+		self.prefix = outer;
+	}
+
+	/// Raise an issue unless the rule is suppressed.
+	pub(crate) fn raise(&mut self, rule: Rule, field: &str, message: impl Into<String>) {
+		self.raise_with(rule, field, message, &[]);
+	}
+
+	/// Raise an issue unless the rule is suppressed, with values for translations.
+	pub(crate) fn raise_with(
+		&mut self,
+		rule: Rule,
+		field: &str,
+		message: impl Into<String>,
+		args: &[(&str, String)],
+	) {
+		if self.options.suppressed.contains(&rule) {
+			return;
+		}
+		if let Some(ruleset) = rule.ruleset() {
+			if !self.options.rulesets.contains(&ruleset) {
+				return;
+			}
+		}
+
+		self.issues.push(ValidationIssue {
+			rule,
+			severity: self
+				.options
+				.severities
+				.get(&rule)
+				.copied()
+				.unwrap_or_else(|| rule.default_severity()),
+			path: join_path(&self.prefix, field),
+			message: message.into(),
+			args: args
+				.iter()
+				.map(|(name, value)| (name.to_string(), value.clone()))
+				.collect(),
+		});
+	}
+
+	/// Raise an issue if `missing` is true.
+	pub(crate) fn require(&mut self, missing: bool, rule: Rule, field: &str, message: &str) {
+		if missing {
+			self.raise(rule, field, message);
+		}
+	}
+
+	pub(crate) fn finish(self) -> Vec<ValidationIssue> {
+		self.issues
+	}
+}
+
+fn join_path(prefix: &str, field: &str) -> String {
+	match (
+		prefix.is_empty(),
+		field.is_empty() || field.starts_with('['),
+	) {
+		(true, _) => field.into(),
+		(false, true) => format!("{prefix}{field}"),
+		(false, false) => format!("{prefix}.{field}"),
+	}
+}
+
+impl Cff {
+	/// Validate this document with the default options.
+	pub fn validate(&self) -> Vec<ValidationIssue> {
+		self.validate_with(&ValidationOptions::default())
+	}
+
+	/// Validate this document.
+	///
+	/// This includes the preferred citation and all references.
+	pub fn validate_with(&self, options: &ValidationOptions) -> Vec<ValidationIssue> {
+		let mut validator = Validator::new(options);
+		validate_cff(&mut validator, self);
+		validator.finish()
+	}
+}
+
+impl Reference {
+	/// Validate this reference with the default options.
+	pub fn validate(&self) -> Vec<ValidationIssue> {
+		self.validate_with(&ValidationOptions::default())
+	}
+
+	/// Validate this reference.
+	pub fn validate_with(&self, options: &ValidationOptions) -> Vec<ValidationIssue> {
+		let mut validator = Validator::new(options);
+		validate_reference(&mut validator, self);
+		validator.finish()
+	}
+}
+
+pub(crate) fn validate_cff(v: &mut Validator<'_>, cff: &Cff) {
+	validate_document(v, cff);
+	for (n, reference) in cff.references.iter().enumerate() {
+		validate_nth_reference(v, n, reference);
+	}
+}
+
+/// Validate all of a document but its references.
+pub(crate) fn validate_document(v: &mut Validator<'_>, cff: &Cff) {
+	v.require(
+		cff.message.trim().is_empty(),
+		Rule::MessageEmpty,
+		"message",
+		"message should not be empty",
+	);
+	v.require(
+		cff.title.trim().is_empty(),
+		Rule::TitleEmpty,
+		"title",
+		"title should not be empty",
+	);
+	v.require(
+		cff.authors.is_empty(),
+		Rule::AuthorsEmpty,
+		"authors",
+		"there should be at least one author",
+	);
+	validate_dois(v, cff.doi.as_deref(), &cff.identifiers);
+	validate_conventions(
+		v,
+		cff.commit.as_deref(),
+		cff.version.as_deref(),
+		cff.abstract_text.as_deref(),
+	);
+	validate_repositories(
+		v,
+		cff.repository.as_ref(),
+		cff.repository_code.as_ref(),
+		cff.repository_artifact.as_ref(),
+	);
+	if let Some(WorkType::Other(work_type)) = &cff.work_type {
+		validate_unknown_type(v, work_type);
+	}
+	validate_preferred_self(v, cff);
+	validate_license(v, cff.license.as_ref());
+	validate_names(v, "authors", &cff.authors);
+	validate_names(v, "contact", &cff.contact);
+	validate_contacts(v, &cff.authors, &cff.contact);
+	validate_embargo(v, cff);
+	validate_github(v, cff);
+	validate_zenodo(v, cff);
+
+	if let Some(reference) = &cff.preferred_citation {
+		v.nested("preferred-citation", |v| validate_reference(v, reference));
+	}
+}
+
+/// Validate one of the references of a document.
+pub(crate) fn validate_nth_reference(v: &mut Validator<'_>, n: usize, reference: &Reference) {
+	v.nested(&format!("references[{n}]"), |v| {
+		validate_reference(v, reference)
+	});
+}
+
+fn validate_license(v: &mut Validator<'_>, license: Option<&License>) {
+	for (n, text) in license.map(License::invalid_members).unwrap_or_default() {
+		let path = match n {
+			Some(n) => format!("license[{n}]"),
+			None => "license".into(),
+		};
+		v.raise_with(
+			Rule::LicenseFormat,
+			&path,
+			format!("license isn't a valid SPDX expression, got: {text:?}"),
+			&[("license", text)],
+		);
+	}
+}
+
+fn validate_dois(v: &mut Validator<'_>, doi: Option<&str>, identifiers: &[Identifier]) {
+	if let Some(doi) = doi {
+		validate_doi_field(v, "doi", doi);
+	}
+
+	for (n, id) in identifiers.iter().enumerate() {
+		if let Identifier::Doi { value, .. } = id {
+			validate_doi_field(v, &format!("identifiers[{n}].value"), value);
+		}
+	}
+}
+
+fn validate_doi_field(v: &mut Validator<'_>, field: &str, doi: &str) {
+	if let Err(err) = validate_doi(doi) {
+		v.raise_with(
+			Rule::DoiFormat,
+			field,
+			err.clone(),
+			&[("error", err), ("doi", doi.into())],
+		);
+	}
+}
+
+fn validate_conventions(
+	v: &mut Validator<'_>,
+	commit: Option<&str>,
+	version: Option<&str>,
+	abstract_text: Option<&str>,
+) {
+	if let Some(commit) = commit {
+		let hash = commit.len() == 40 && commit.bytes().all(|b| b.is_ascii_hexdigit());
+		if !hash && !commit.starts_with("Revision:") {
+			v.raise_with(
+				Rule::CommitFormat,
+				"commit",
+				format!(
+					"commit should be a 40-character hash, or start with \"Revision:\", got: {commit}"
+				),
+				&[("commit", commit.into())],
+			);
+		}
+	}
+
+	if let Some(version) = version {
+		v.require(
+			version.trim().is_empty(),
+			Rule::VersionBlank,
+			"version",
+			"version should not be blank",
+		);
+	}
+
+	if let Some(abstract_text) = abstract_text {
+		let text = abstract_text.trim();
+		let url = !text.contains(char::is_whitespace)
+			&& url::Url::parse(text).map_or(false, |url| matches!(url.scheme(), "http" | "https"));
+		v.require(
+			url,
+			Rule::AbstractUrl,
+			"abstract",
+			"abstract should describe the work, not only link to a description",
+		);
+	}
+}
+
+fn validate_repositories(
+	v: &mut Validator<'_>,
+	repository: Option<&Url>,
+	repository_code: Option<&Url>,
+	repository_artifact: Option<&Url>,
+) {
+	for (field, url, kind) in misplaced(repository, repository_code, repository_artifact) {
+		v.raise_with(
+			Rule::RepositoryField,
+			field,
+			format!("{url} belongs in {}", kind.field()),
+			&[("url", url.to_string()), ("field", kind.field().into())],
+		);
+	}
+}
+
+fn validate_preferred_self(v: &mut Validator<'_>, cff: &Cff) {
+	let preferred = match &cff.preferred_citation {
+		Some(preferred) => preferred,
+		None => return,
+	};
+
+	let same_doi = match (&cff.doi, &preferred.doi) {
+		(Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+		_ => false,
+	};
+	let same_software = matches!(
+		preferred.work_type,
+		RefType::Software
+			| RefType::SoftwareCode
+			| RefType::SoftwareContainer
+			| RefType::SoftwareExecutable
+			| RefType::SoftwareVirtualMachine
+	) && preferred.title.as_deref().map(str::trim) == Some(cff.title.trim());
+
+	if same_doi || same_software {
+		v.raise(
+			Rule::PreferredCitationSelf,
+			"preferred-citation",
+			"preferred citation is the work itself: it's for another work to cite instead",
+		);
+	}
+}
+
+fn validate_unknown_type(v: &mut Validator<'_>, work_type: &str) {
+	v.raise_with(
+		Rule::UnknownType,
+		"type",
+		format!("type {work_type:?} isn't one of CFF 1.2.0's"),
+		&[("type", work_type.into())],
+	);
+}
+
+fn validate_embargo(v: &mut Validator<'_>, cff: &Cff) {
+	let field = match (cff.available_date, cff.embargo_period) {
+		(None, None) => return,
+		(Some(_), _) => "available-date",
+		(None, Some(_)) => "embargo-period",
+	};
+
+	if cff.work_type != Some(WorkType::Dataset) {
+		v.raise(
+			Rule::EmbargoNotDataset,
+			field,
+			"embargo fields only apply to datasets",
+		);
+	}
+
+	if let (Some(available), Some(released)) = (cff.available_date, cff.date_released) {
+		if available < released {
+			v.raise_with(
+				Rule::EmbargoBeforeRelease,
+				"available-date",
+				format!("available date {available} is before release date {released}"),
+				&[
+					("available", available.to_string()),
+					("released", released.to_string()),
+				],
+			);
+		}
+
+		if let Some(period) = cff.embargo_period {
+			let end = released.add(period);
+			if end != Some(available) {
+				let end =
+					end.map_or_else(|| "a date out of range".to_string(), |end| end.to_string());
+				v.raise_with(
+					Rule::EmbargoMismatch,
+					"embargo-period",
+					format!("embargo period ends on {end}, but available date is {available}"),
+					&[
+						("end", end.to_string()),
+						("available", available.to_string()),
+					],
+				);
+			}
+		}
+	}
+}
+
+fn validate_github(v: &mut Validator<'_>, cff: &Cff) {
+	for (n, id) in cff.identifiers.iter().enumerate() {
+		if id.kind() != IdentifierKind::Doi {
+			v.raise(
+				Rule::GithubIdentifierKind,
+				&format!("identifiers[{n}]"),
+				"GitHub only shows DOI identifiers",
+			);
+		}
+	}
+
+	let preferred = match &cff.preferred_citation {
+		Some(preferred) => preferred,
+		None => {
+			validate_family_names(v, "authors", &cff.authors);
+			return;
+		}
+	};
+
+	if cff.version.is_some() || cff.doi.is_some() {
+		v.raise(
+			Rule::GithubPreferredCitation,
+			"preferred-citation",
+			"GitHub shows the preferred citation, not the version or DOI of the work",
+		);
+	}
+
+	v.nested("preferred-citation", |v| {
+		if !matches!(
+			preferred.work_type,
+			RefType::Article
+				| RefType::Book
+				| RefType::ConferencePaper
+				| RefType::Generic
+				| RefType::Manual
+				| RefType::Pamphlet
+				| RefType::Proceedings
+				| RefType::Report
+				| RefType::Software
+				| RefType::Thesis
+				| RefType::Unpublished
+		) {
+			v.raise(
+				Rule::GithubPreferredCitationType,
+				"type",
+				"GitHub shows this type of reference as a generic @misc entry",
+			);
+		}
+
+		v.require(
+			preferred.authors.is_empty(),
+			Rule::GithubPreferredCitationAuthors,
+			"authors",
+			"GitHub shows the authors of the preferred citation, and it has none",
+		);
+		v.require(
+			preferred.year.is_none() && preferred.date_published.is_none(),
+			Rule::GithubPreferredCitationYear,
+			"year",
+			"GitHub shows the year of the preferred citation, and it has none",
+		);
+		validate_family_names(v, "authors", &preferred.authors);
+	});
+}
+
+fn validate_zenodo(v: &mut Validator<'_>, cff: &Cff) {
+	for (n, author) in cff.authors.iter().enumerate() {
+		let person = match author {
+			Name::Person(person) => person,
+			_ => continue,
+		};
+		v.require(
+			person.family_names.is_none(),
+			Rule::ZenodoCreatorName,
+			&format!("authors[{n}].family-names"),
+			"Zenodo needs a family name for each person",
+		);
+		// Malformed ORCIDs are already raised by OrcidFormat.
+		let orcid = person
+			.meta
+			.orcid
+			.as_ref()
+			.filter(|orcid| validate_orcid_shape(orcid).is_ok());
+		if let Some(Err(err)) = orcid.map(validate_orcid) {
+			v.raise_with(
+				Rule::ZenodoOrcid,
+				&format!("authors[{n}].orcid"),
+				err.clone(),
+				&[("error", err)],
+			);
+		}
+	}
+
+	// Licenses which aren't SPDX expressions are already raised by LicenseFormat.
+	if let Some(expression) = cff.license.as_ref().and_then(License::try_to_expression) {
+		let mut requirements = expression.requirements();
+		let single = match (requirements.next(), requirements.next()) {
+			(Some(only), None) => {
+				only.req.exception.is_none()
+					&& matches!(
+						only.req.license,
+						LicenseItem::Spdx {
+							or_later: false,
+							..
+						}
+					)
+			}
+			_ => false,
+		};
+		if !single {
+			v.raise_with(
+				Rule::ZenodoLicense,
+				"license",
+				format!("Zenodo records have a single SPDX license, got: {expression}"),
+				&[("expression", expression.to_string())],
+			);
+		}
+	}
+
+	v.require(
+		cff.abstract_text.is_none(),
+		Rule::ZenodoAbstract,
+		"abstract",
+		"Zenodo uses the abstract as the description of the record",
+	);
+
+	if let Some(doi) = &cff.doi {
+		if doi.starts_with("10.5281/zenodo.") {
+			v.raise(
+				Rule::ZenodoDoi,
+				"doi",
+				"Zenodo DOIs are either for one release, or for all of them as the concept DOI: check that this is the one you mean",
+			);
+		}
+	}
+}
+
+fn validate_family_names(v: &mut Validator<'_>, field: &str, names: &[Name]) {
+	for (n, name) in names.iter().enumerate() {
+		if let Name::Person(person) = name {
+			v.require(
+				person.family_names.is_none(),
+				Rule::GithubFamilyNames,
+				&format!("{field}[{n}].family-names"),
+				"GitHub cites people by family name, and this person has none",
+			);
+		}
+	}
+}
+
+fn validate_contacts(v: &mut Validator<'_>, authors: &[Name], contacts: &[Name]) {
+	for (n, contact) in contacts.iter().enumerate() {
+		if !contact.is_anonymous() && !authors.iter().any(|author| author.is_same(contact)) {
+			v.raise(
+				Rule::ContactNotAuthor,
+				&format!("contact[{n}]"),
+				"contact is not one of the authors",
+			);
+		}
+	}
+}
+
+pub(crate) fn validate_names(v: &mut Validator<'_>, field: &str, names: &[Name]) {
+	for (n, name) in names.iter().enumerate() {
+		let meta = match name {
+			Name::Person(person) => &person.meta,
+			Name::Entity(entity) => &entity.meta,
+			Name::Anonymous => continue,
+		};
+
+		if let Some(Err(err)) = meta.orcid.as_ref().map(validate_orcid_shape) {
+			v.raise_with(
+				Rule::OrcidFormat,
+				&format!("{field}[{n}].orcid"),
+				err.clone(),
+				&[("error", err)],
+			);
+		}
+
+		if let Some(Err(err)) = meta.ror.as_ref().map(validate_ror) {
+			v.raise_with(
+				Rule::RorFormat,
+				&format!("{field}[{n}].ror"),
+				err.clone(),
+				&[("error", err)],
+			);
+		}
+	}
+}
+
+pub(crate) fn validate_reference(v: &mut Validator<'_>, reference: &Reference) {
+	if let RefType::Other(work_type) = &reference.work_type {
+		validate_unknown_type(v, work_type);
+	}
+	validate_dois(v, reference.doi.as_deref(), &reference.identifiers);
+	validate_conventions(
+		v,
+		reference.commit.as_deref(),
+		reference.version.as_deref(),
+		reference.abstract_text.as_deref(),
+	);
+	validate_repositories(
+		v,
+		reference.repository.as_ref(),
+		reference.repository_code.as_ref(),
+		reference.repository_artifact.as_ref(),
+	);
+	if let Some(doi) = &reference.collection_doi {
+		validate_doi_field(v, "collection-doi", doi);
+	}
+	validate_license(v, reference.license.as_ref());
+	if let Some(Err(err)) = reference.isbn.as_ref().map(|isbn| isbn.validate()) {
+		v.raise_with(Rule::IsbnFormat, "isbn", err.clone(), &[("error", err)]);
+	}
+	if let Some(Err(err)) = reference.issn.as_ref().map(|issn| issn.validate()) {
+		v.raise_with(Rule::IssnFormat, "issn", err.clone(), &[("error", err)]);
+	}
+	if let Some(month) = reference.month {
+		if !(1..=12).contains(&month) {
+			v.raise_with(
+				Rule::MonthRange,
+				"month",
+				format!("month should be between 1 and 12, got: {month}"),
+				&[("month", month.to_string())],
+			);
+		}
+	}
+	validate_names(v, "authors", &reference.authors);
+	validate_names(v, "contact", &reference.contact);
+	validate_contacts(v, &reference.authors, &reference.contact);
+	validate_names(v, "editors", &reference.editors);
+	validate_names(v, "editors-series", &reference.editors_series);
+	validate_names(v, "recipients", &reference.recipients);
+	validate_names(v, "senders", &reference.senders);
+	validate_names(v, "translators", &reference.translators);
+
+	match reference.work_type {
+		RefType::ConferencePaper => {
+			v.require(
+				reference.conference.is_none(),
+				Rule::ConferencePaperConference,
+				"conference",
+				"conference paper should have a conference",
+			);
+			v.require(
+				reference.collection_title.is_none(),
+				Rule::ConferencePaperCollectionTitle,
+				"collection-title",
+				"conference paper should have the title of the proceedings",
+			);
+		}
+		RefType::Patent => v.require(
+			reference.patent_states.is_empty() && reference.number.is_none(),
+			Rule::PatentStatesOrNumber,
+			"patent-states",
+			"patent should have the states it's granted in, or a number",
+		),
+		RefType::Thesis => {
+			v.require(
+				reference.institution.is_none(),
+				Rule::ThesisInstitution,
+				"institution",
+				"thesis should have an institution",
+			);
+			v.require(
+				reference.thesis_type.is_none(),
+				Rule::ThesisType,
+				"thesis-type",
+				"thesis should have a thesis type",
+			);
+		}
+		RefType::Article => v.require(
+			reference.journal.is_none(),
+			Rule::ArticleJournal,
+			"journal",
+			"article should have a journal",
+		),
+		RefType::Book => v.require(
+			reference.publisher.is_none(),
+			Rule::BookPublisher,
+			"publisher",
+			"book should have a publisher",
+		),
+		RefType::Report => v.require(
+			reference.institution.is_none(),
+			Rule::ReportInstitution,
+			"institution",
+			"report should have an institution",
+		),
+		RefType::Website => v.require(
+			reference.url.is_none(),
+			Rule::WebsiteUrl,
+			"url",
+			"website should have a URL",
+		),
+		_ => {}
+	}
+}
@@ -0,0 +1,102 @@
+use citeworks_cff::{
+	builder::BuildError,
+	names::{EntityName, Name},
+	references::{RefType, Reference},
+	Cff, Date,
+};
+
+use pretty_assertions::assert_eq;
+
+fn acme() -> Name {
+	Name::Entity(EntityName {
+		name: Some("Acme".into()),
+		..Default::default()
+	})
+}
+
+#[test]
+fn builds_document() {
+	let cff = Cff::builder()
+		.title("Example")
+		.message("Cite this.")
+		.author(acme())
+		.keyword("citation")
+		.keyword("metadata")
+		.date_released(Date {
+			year: 2022,
+			month: 8,
+			day: 20,
+		})
+		.extra("x-custom", "value")
+		.build()
+		.unwrap();
+
+	assert_eq!(cff.message, "Cite this.");
+	assert_eq!(cff.keywords, vec!["citation", "metadata"]);
+	assert_eq!(cff.extra.len(), 1);
+	assert_eq!(
+		citeworks_cff::from_str(&citeworks_cff::to_string(&cff).unwrap()).unwrap(),
+		cff
+	);
+}
+
+#[test]
+fn required_fields() {
+	assert_eq!(
+		Cff::builder().author(acme()).build(),
+		Err(BuildError::Missing("title".into()))
+	);
+	assert_eq!(
+		Cff::builder()
+			.title("Example")
+			.message("  ")
+			.author(acme())
+			.build(),
+		Err(BuildError::Missing("message".into()))
+	);
+	assert_eq!(
+		Cff::builder()
+			.title("Example")
+			.build()
+			.unwrap_err()
+			.to_string(),
+		"authors needs at least one entry"
+	);
+}
+
+#[test]
+fn references() {
+	let reference = Reference::builder(RefType::Book)
+		.title("A book")
+		.author(acme())
+		.publisher(EntityName {
+			name: Some("Publisher".into()),
+			..Default::default()
+		})
+		.year(2020_u64)
+		.build()
+		.unwrap();
+	assert_eq!(reference.work_type, RefType::Book);
+	assert_eq!(reference.year, Some(2020));
+
+	assert_eq!(
+		Reference::builder(RefType::Book).title("A book").build(),
+		Err(BuildError::Empty("authors".into()))
+	);
+
+	let untitled = Reference {
+		authors: vec![acme()],
+		..Default::default()
+	};
+	assert_eq!(
+		Cff::builder()
+			.title("Example")
+			.author(acme())
+			.reference(reference)
+			.reference(untitled)
+			.build()
+			.unwrap_err()
+			.to_string(),
+		"references[1].title is required"
+	);
+}
@@ -0,0 +1,115 @@
+use citeworks_cff::{from_str, to_string_canonical};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn keys_in_specification_order() {
+	let cff = from_str(
+		"
+references:
+  - title: Book
+    type: book
+    year: 2001
+    authors:
+      - given-names: Jane
+        family-names: Doe
+license: MIT
+version: 1.0.0
+authors:
+  - name: Acme
+    email: hello@acme.test
+title: Example
+message: Please cite this software.
+cff-version: 1.2.0
+x-custom: extra
+",
+	)
+	.unwrap();
+
+	assert_eq!(
+		to_string_canonical(&cff).unwrap(),
+		"cff-version: 1.2.0
+message: Please cite this software.
+title: Example
+authors:
+- name: Acme
+  email: hello@acme.test
+version: 1.0.0
+license: MIT
+references:
+- type: book
+  title: Book
+  authors:
+  - family-names: Doe
+    given-names: Jane
+  year: 2001
+x-custom: extra
+"
+	);
+}
+
+#[test]
+fn references_sorted() {
+	let cff = from_str(
+		"
+cff-version: 1.2.0
+message: Please cite this software.
+title: Example
+authors:
+  - name: Acme
+references:
+  - type: article
+    title: Later
+    year: 2010
+    authors:
+      - family-names: Doe
+  - type: article
+    title: Zebras
+    authors:
+      - family-names: roe
+  - type: article
+    title: Earlier
+    year: 2001
+    authors:
+      - family-names: Doe
+",
+	)
+	.unwrap();
+
+	let canonical = from_str(&to_string_canonical(&cff).unwrap()).unwrap();
+	let titles: Vec<_> = canonical
+		.references
+		.iter()
+		.map(|reference| reference.title.as_deref().unwrap())
+		.collect();
+	assert_eq!(titles, vec!["Earlier", "Later", "Zebras"]);
+}
+
+#[test]
+fn stable_across_orderings() {
+	let one = from_str(
+		"
+cff-version: 1.2.0
+message: Cite me.
+title: Example
+authors:
+  - name: Acme
+references:
+  - type: book
+    title: B
+    authors:
+      - name: Beta
+  - type: book
+    title: A
+    authors:
+      - name: Alpha
+",
+	)
+	.unwrap();
+	let mut two = one.clone();
+	two.references.reverse();
+
+	assert_eq!(
+		to_string_canonical(&one).unwrap(),
+		to_string_canonical(&two).unwrap()
+	);
+}
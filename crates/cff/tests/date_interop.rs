@@ -0,0 +1,32 @@
+use citeworks_cff::Date;
+
+use pretty_assertions::assert_eq;
+
+fn date(s: &str) -> Date {
+	s.parse().unwrap()
+}
+
+#[test]
+fn today() {
+	let today = Date::today();
+	assert!(today > date("2022-01-01"));
+	assert_eq!(date(&today.to_string()), today);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono() {
+	let naive = chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+	assert_eq!(Date::from(naive), date("2024-02-29"));
+	assert_eq!(chrono::NaiveDate::try_from(date("2024-02-29")), Ok(naive));
+	assert!(chrono::NaiveDate::try_from(date("2023-02-29")).is_err());
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn time() {
+	let calendar = time::Date::from_calendar_date(2024, time::Month::February, 29).unwrap();
+	assert_eq!(Date::from(calendar), date("2024-02-29"));
+	assert_eq!(time::Date::try_from(date("2024-02-29")), Ok(calendar));
+	assert!(time::Date::try_from(date("2023-04-31")).is_err());
+}
@@ -0,0 +1,139 @@
+use citeworks_cff::{
+	edit::{insert_references, replace_references},
+	from_str,
+	names::Name,
+	references::{RefType, Reference},
+};
+use pretty_assertions::assert_eq;
+
+fn book(title: &str) -> Reference {
+	Reference {
+		work_type: RefType::Book,
+		title: Some(title.into()),
+		authors: vec![Name::Anonymous],
+		..Default::default()
+	}
+}
+
+const HEAD: &str = "\
+# Metadata for citing this software
+cff-version: \"1.2.0\"
+message: 'Please cite this software.'
+title: Example   # working title
+authors:
+  - family-names: Doe
+    given-names: Jane
+";
+
+#[test]
+fn insert_after_existing() {
+	let yaml = format!(
+		"{HEAD}references:
+- type: article  # first
+  title: Old
+  authors: [{{ name: anonymous }}]
+
+# Keep the license last
+license: MIT
+"
+	);
+
+	let edited = insert_references(&yaml, &[book("New")]).unwrap();
+	assert_eq!(
+		edited,
+		format!(
+			"{HEAD}references:
+- type: article  # first
+  title: Old
+  authors: [{{ name: anonymous }}]
+- type: book
+  title: New
+  authors:
+  - name: anonymous
+
+# Keep the license last
+license: MIT
+"
+		)
+	);
+}
+
+#[test]
+fn insert_without_references() {
+	let yaml = format!("{HEAD}# the end\n");
+
+	let edited = insert_references(&yaml, &[book("New")]).unwrap();
+	assert_eq!(
+		edited,
+		format!(
+			"{HEAD}references:
+- type: book
+  title: New
+  authors:
+  - name: anonymous
+# the end
+"
+		)
+	);
+}
+
+#[test]
+fn insert_into_flow_sequence() {
+	let yaml = format!("{HEAD}references: []\nlicense: MIT\n");
+
+	let edited = insert_references(&yaml, &[book("New")]).unwrap();
+	assert!(edited.starts_with(HEAD));
+	assert!(edited.ends_with("  - name: anonymous\nlicense: MIT\n"));
+	assert_eq!(from_str(&edited).unwrap().references, vec![book("New")]);
+}
+
+#[test]
+fn replace_keeps_indentation() {
+	let yaml = format!(
+		"{HEAD}references:
+  - type: article
+    title: Old
+    authors: [{{ name: anonymous }}]
+license: MIT # for now
+"
+	);
+
+	let edited = replace_references(&yaml, &[book("New"), book("Newer")]).unwrap();
+	assert_eq!(
+		edited,
+		format!(
+			"{HEAD}references:
+  - type: book
+    title: New
+    authors:
+    - name: anonymous
+  - type: book
+    title: Newer
+    authors:
+    - name: anonymous
+license: MIT # for now
+"
+		)
+	);
+}
+
+#[test]
+fn replace_with_nothing() {
+	let yaml = format!(
+		"{HEAD}references:
+  - type: article
+    title: Old
+    authors: [{{ name: anonymous }}]
+license: MIT
+"
+	);
+
+	let edited = replace_references(&yaml, &[]).unwrap();
+	assert_eq!(edited, format!("{HEAD}license: MIT\n"));
+}
+
+#[test]
+fn flow_document() {
+	let yaml = "{cff-version: 1.2.0, message: Cite, title: Example, authors: [{name: Acme}]}";
+	assert!(insert_references(yaml, &[book("New")]).is_err());
+}
@@ -0,0 +1,128 @@
+use citeworks_cff::{names::Name, validation::Rule, Cff, Date, Duration, WorkType};
+
+use pretty_assertions::assert_eq;
+
+fn date(s: &str) -> Date {
+	s.parse().unwrap()
+}
+
+fn rules(cff: &Cff) -> Vec<Rule> {
+	cff.validate().into_iter().map(|i| i.rule).collect()
+}
+
+#[test]
+fn parse_durations() {
+	let d: Duration = "P1Y6M2W3D".parse().unwrap();
+	assert_eq!(
+		d,
+		Duration {
+			years: 1,
+			months: 6,
+			weeks: 2,
+			days: 3
+		}
+	);
+	assert_eq!(d.to_string(), "P1Y6M2W3D");
+	assert_eq!("P6M".parse::<Duration>().unwrap().to_string(), "P6M");
+
+	for bad in ["", "P", "6M", "P6", "PM", "P6M1Y", "PT6H", "P1X"] {
+		assert!(bad.parse::<Duration>().is_err(), "{bad:?} should not parse");
+	}
+}
+
+#[test]
+fn add_durations() {
+	let d = |s: &str| s.parse::<Duration>().unwrap();
+	assert_eq!(date("2020-01-31").add(d("P1M")), Some(date("2020-02-29")));
+	assert_eq!(date("2021-01-31").add(d("P1M")), Some(date("2021-02-28")));
+	assert_eq!(date("2021-11-15").add(d("P6M")), Some(date("2022-05-15")));
+	assert_eq!(date("2021-12-25").add(d("P2W")), Some(date("2022-01-08")));
+	assert_eq!(date("2020-02-28").add(d("P1Y1D")), Some(date("2021-03-01")));
+	assert_eq!(date("2020-01-01").add(d("P366D")), Some(date("2021-01-01")));
+	assert_eq!(
+		date("2020-03-01").add(d("P146097D")),
+		Some(date("2420-03-01"))
+	);
+}
+
+#[test]
+fn add_out_of_range() {
+	let far = Date {
+		year: i64::MAX / 12,
+		month: 12,
+		day: 31,
+	};
+	assert_eq!(far.add("P1Y".parse().unwrap()), None);
+	assert_eq!(far.add("P1D".parse().unwrap()), None);
+
+	let big = Duration {
+		years: u32::MAX,
+		months: u32::MAX,
+		weeks: u32::MAX,
+		days: u32::MAX,
+	};
+	assert!(date("2020-01-01").add(big).is_some());
+}
+
+#[test]
+fn embargo_end() {
+	let mut cff = Cff {
+		work_type: Some(WorkType::Dataset),
+		date_released: Some(date("2022-03-01")),
+		embargo_period: Some("P6M".parse().unwrap()),
+		..Default::default()
+	};
+	assert_eq!(cff.embargo_end(), Some(date("2022-09-01")));
+
+	cff.available_date = Some(date("2022-10-01"));
+	assert_eq!(cff.embargo_end(), Some(date("2022-10-01")));
+}
+
+#[test]
+fn parse_and_write_fields() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this dataset using these metadata.
+title: Embargoed data
+type: dataset
+authors:
+  - name: The Lab
+date-released: 2022-03-01
+available-date: 2022-09-01
+embargo-period: P6M
+"#,
+	)
+	.unwrap();
+	assert_eq!(cff.available_date, Some(date("2022-09-01")));
+	assert_eq!(rules(&cff), vec![]);
+
+	let yaml = citeworks_cff::to_string(&cff).unwrap();
+	assert!(yaml.contains("available-date: 2022-09-01"));
+	assert!(yaml.contains("embargo-period: P6M"));
+}
+
+#[test]
+fn validation() {
+	let mut cff = Cff {
+		message: "Please cite this dataset using these metadata.".into(),
+		title: "Embargoed data".into(),
+		authors: vec![Name::Anonymous],
+		date_released: Some(date("2022-03-01")),
+		available_date: Some(date("2022-01-01")),
+		embargo_period: Some("P6M".parse().unwrap()),
+		..Default::default()
+	};
+	assert_eq!(
+		rules(&cff),
+		vec![
+			Rule::EmbargoNotDataset,
+			Rule::EmbargoBeforeRelease,
+			Rule::EmbargoMismatch
+		]
+	);
+
+	cff.work_type = Some(WorkType::Dataset);
+	cff.available_date = Some(date("2022-09-01"));
+	assert_eq!(rules(&cff), vec![]);
+}
@@ -0,0 +1,59 @@
+use citeworks_cff::{
+	funding::Funding,
+	names::{EntityName, Name},
+	references::{RefType, Reference},
+};
+
+use pretty_assertions::assert_eq;
+use url::Url;
+
+fn funding() -> Funding {
+	Funding {
+		funder: EntityName {
+			name: Some("European Research Council".into()),
+			..Default::default()
+		},
+		award_number: Some("101012345".into()),
+		award_title: Some("Citations for everyone".into()),
+		award_uri: Some(Url::parse("https://cordis.europa.eu/project/id/101012345").unwrap()),
+	}
+}
+
+#[test]
+fn parse() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: Funded
+authors:
+  - name: The Lab
+funding:
+  - funder:
+      name: European Research Council
+    award-number: "101012345"
+    award-title: Citations for everyone
+    award-uri: https://cordis.europa.eu/project/id/101012345
+"#,
+	)
+	.unwrap();
+	assert_eq!(cff.funding, vec![funding()]);
+}
+
+#[test]
+fn grant_reference() {
+	let reference = funding().to_reference();
+	assert_eq!(
+		reference,
+		Reference {
+			work_type: RefType::Grant,
+			authors: vec![Name::Entity(funding().funder)],
+			title: Some("Citations for everyone".into()),
+			number: Some("101012345".into()),
+			url: Some(Url::parse("https://cordis.europa.eu/project/id/101012345").unwrap()),
+			..Default::default()
+		}
+	);
+	assert_eq!(Funding::from_reference(&reference), Some(funding()));
+	assert_eq!(Funding::from_reference(&Reference::default()), None);
+}
@@ -0,0 +1,78 @@
+use citeworks_cff::{
+	i18n::Catalog,
+	validation::{Rule, ValidationIssue},
+};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn english_has_all_rules() {
+	let catalog = Catalog::english();
+	for rule in Rule::ALL {
+		assert!(catalog.contains(*rule), "missing message for {rule}");
+	}
+}
+
+#[test]
+fn english_matches_source() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this dataset using these metadata.
+title: My Dataset
+type: dataset
+date-released: 2022-01-01
+available-date: 2021-12-01
+authors:
+  - name: The project
+"#,
+	)
+	.unwrap();
+
+	let issues = cff.validate();
+	assert_eq!(issues[0].rule, Rule::EmbargoBeforeRelease);
+	let catalog = Catalog::english();
+	for issue in &issues {
+		assert_eq!(catalog.message(issue), issue.message);
+	}
+}
+
+#[test]
+fn variables_and_fallback() {
+	let catalog = Catalog::new(
+		"fr-CA",
+		"embargo-mismatch = la période d'embargo se termine le { $end }, pas le { $available }",
+	)
+	.unwrap();
+	assert_eq!(catalog.locale(), "fr-CA");
+
+	let issue = |rule, args: &[(&str, &str)]| ValidationIssue {
+		rule,
+		severity: rule.default_severity(),
+		path: String::new(),
+		message: "in English".into(),
+		args: args
+			.iter()
+			.map(|(name, value)| (name.to_string(), value.to_string()))
+			.collect(),
+	};
+	assert_eq!(
+		catalog.message(&issue(
+			Rule::EmbargoMismatch,
+			&[("end", "2022-03-01"), ("available", "2022-04-01")]
+		)),
+		"la période d'embargo se termine le 2022-03-01, pas le 2022-04-01"
+	);
+	assert_eq!(
+		catalog.message(&issue(Rule::EmbargoMismatch, &[])),
+		"in English"
+	);
+	assert_eq!(catalog.message(&issue(Rule::ThesisType, &[])), "in English");
+}
+
+#[test]
+fn built_in_locales() {
+	assert_eq!(Catalog::for_locale("en_NZ.UTF-8").locale(), "en");
+	assert_eq!(Catalog::for_locale("tlh").locale(), "en");
+	assert!(Catalog::new("fr", "this isn't fluent {").is_err());
+}
@@ -0,0 +1,102 @@
+use citeworks_cff::{
+	identifiers::{validate_isbn, validate_issn, Identifier, IdentifierKind, Isbn, Issn},
+	references::Reference,
+	Cff,
+};
+
+use pretty_assertions::assert_eq;
+use url::Url;
+
+#[test]
+fn accessors() {
+	let url = Identifier::Url {
+		value: Url::parse("https://example.com/x").unwrap(),
+		description: None,
+	};
+	assert_eq!(url.kind(), IdentifierKind::Url);
+	assert_eq!(url.value(), "https://example.com/x");
+	assert_eq!(url.description(), None);
+}
+
+#[test]
+fn lookup_by_kind() {
+	let cff = Cff {
+		identifiers: vec![
+			Identifier::Other {
+				value: "arXiv:2103.06681".into(),
+				description: None,
+			},
+			Identifier::Doi {
+				value: "10.5281/zenodo.1003149".into(),
+				description: Some("The concept DOI".into()),
+			},
+			Identifier::Doi {
+				value: "10.5281/zenodo.1003150".into(),
+				description: Some("The versioned DOI".into()),
+			},
+		],
+		..Default::default()
+	};
+
+	assert_eq!(
+		cff.identifier_of_kind(IdentifierKind::Doi)
+			.map(|id| id.value()),
+		Some("10.5281/zenodo.1003149")
+	);
+	assert_eq!(cff.identifiers_of_kind(IdentifierKind::Doi).count(), 2);
+	assert_eq!(cff.identifier_of_kind(IdentifierKind::Swh), None);
+}
+
+#[test]
+fn isbn() {
+	assert_eq!(validate_isbn("0-306-40615-2").unwrap(), "0306406152");
+	assert_eq!(
+		validate_isbn("ISBN-13: 978 0 306 40615 7").unwrap(),
+		"9780306406157"
+	);
+	assert_eq!(validate_isbn("isbn 0-8044-2957-x").unwrap(), "080442957X");
+	assert_eq!(
+		validate_isbn("0-306-40615-3").unwrap_err(),
+		r#"ISBN check digit is wrong, got: "0-306-40615-3""#
+	);
+	assert!(validate_isbn("123-0-306-40615-7").is_err());
+	assert!(validate_isbn("03064061X2").is_err());
+}
+
+#[test]
+fn issn() {
+	assert_eq!(validate_issn("03785955").unwrap(), "0378-5955");
+	assert_eq!(validate_issn("ISSN 2434-561x").unwrap(), "2434-561X");
+	assert_eq!(
+		validate_issn("0378-5954").unwrap_err(),
+		r#"ISSN check digit is wrong, got: "0378-5954""#
+	);
+	assert!(validate_issn("0378-595").is_err());
+}
+
+#[test]
+fn malformed_kept_as_written() {
+	let reference: Reference = serde_yaml::from_str(
+		"
+type: book
+title: An old book
+authors:
+  - name: Acme
+isbn: 0-306-4061
+issn: 0378 5955
+",
+	)
+	.unwrap();
+
+	assert_eq!(reference.isbn, Some(Isbn::from("0-306-4061")));
+	assert!(!reference.isbn.as_ref().unwrap().is_valid());
+	assert_eq!(
+		reference.issn.as_ref().unwrap().normalized().as_deref(),
+		Some("0378-5955")
+	);
+	assert_eq!(reference.issn, Some(Issn::from("0378 5955")));
+
+	let yaml = serde_yaml::to_string(&reference).unwrap();
+	assert!(yaml.contains("isbn: 0-306-4061\n"));
+	assert!(yaml.contains("issn: 0378 5955\n"));
+}
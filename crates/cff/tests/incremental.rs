@@ -0,0 +1,104 @@
+use citeworks_cff::{incremental::IncrementalValidator, validation::ValidationIssue};
+use pretty_assertions::assert_eq;
+
+const DOCUMENT: &str = r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My Research Software
+authors:
+  - name: Research Software Lab
+commit: not-a-commit
+references:
+  - type: thesis
+    title: A thesis
+    authors: []
+  - type: article
+    title: An article
+    authors: []
+    doi: 10.1234/article
+"#;
+
+fn from_scratch(text: &str) -> Vec<ValidationIssue> {
+	citeworks_cff::from_str(text)
+		.unwrap()
+		.validate_with(&Default::default())
+}
+
+fn incremental(validator: &IncrementalValidator) -> Vec<ValidationIssue> {
+	validator.issues().into_iter().cloned().collect()
+}
+
+#[test]
+fn same_issues_as_from_scratch() {
+	let validator = IncrementalValidator::new(DOCUMENT, Default::default());
+	assert!(!validator.issues().is_empty());
+	assert_eq!(incremental(&validator), from_scratch(DOCUMENT));
+}
+
+#[test]
+fn edit_in_reference() {
+	let mut validator = IncrementalValidator::new(DOCUMENT, Default::default());
+	let edited = DOCUMENT.replace("doi: 10.1234/article", "doi: not a doi");
+	let path = validator.path_at(DOCUMENT.find("10.1234").unwrap());
+	assert_eq!(path, "references[1].doi");
+
+	validator.edit(&edited, &path);
+	assert_eq!(incremental(&validator), from_scratch(&edited));
+	assert!(validator
+		.issues()
+		.iter()
+		.any(|issue| issue.path == "references[1].doi"));
+}
+
+#[test]
+fn edit_in_document() {
+	let mut validator = IncrementalValidator::new(DOCUMENT, Default::default());
+	let edited = DOCUMENT.replace("commit: not-a-commit\n", "");
+
+	validator.edit(&edited, "commit");
+	assert_eq!(incremental(&validator), from_scratch(&edited));
+	assert!(validator
+		.issues()
+		.iter()
+		.all(|issue| issue.path != "commit"));
+}
+
+#[test]
+fn reference_removed() {
+	let mut validator = IncrementalValidator::new(DOCUMENT, Default::default());
+	let edited = DOCUMENT.replace(
+		"  - type: thesis\n    title: A thesis\n    authors: []\n",
+		"",
+	);
+
+	validator.edit(&edited, "references[0]");
+	assert_eq!(incremental(&validator), from_scratch(&edited));
+}
+
+#[test]
+fn unreadable_then_fixed() {
+	let mut validator = IncrementalValidator::new(DOCUMENT, Default::default());
+	let broken = DOCUMENT.replace("references:", "references: [");
+
+	validator.edit(&broken, "references");
+	assert!(validator.cff().is_none());
+	assert_eq!(validator.parse_errors().len(), 1);
+	assert!(validator.issues().is_empty());
+
+	validator.edit(DOCUMENT, "references");
+	assert!(validator.parse_errors().is_empty());
+	assert_eq!(incremental(&validator), from_scratch(DOCUMENT));
+}
+
+#[test]
+fn positions_of_paths() {
+	let validator = IncrementalValidator::new(DOCUMENT, Default::default());
+
+	let position = validator.position("references[1].doi");
+	assert_eq!((position.line, position.column), (15, 5));
+	assert_eq!(&DOCUMENT[position.offset..][..position.len], "doi");
+
+	// Missing fields are placed at the start of the document's fields.
+	let position = validator.position("nowhere");
+	assert_eq!((position.line, position.column, position.len), (2, 1, 0));
+}
@@ -0,0 +1,65 @@
+use citeworks_cff::{
+	keywords::{normalize, Vocabulary},
+	references::Reference,
+	Cff,
+};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn normalize_trims_folds_and_dedupes() {
+	assert_eq!(
+		normalize([
+			" Ruby",
+			"CREDIT",
+			"ruby ",
+			"",
+			"  ",
+			"citation\tfile  format"
+		]),
+		vec!["ruby", "credit", "citation file format"]
+	);
+}
+
+#[test]
+fn normalize_cff_in_place() {
+	let mut cff = Cff {
+		keywords: vec!["Rust".into(), "rust".into()],
+		references: vec![Reference {
+			keywords: vec!["A".into(), " a".into(), "B".into()],
+			..Default::default()
+		}],
+		..Default::default()
+	};
+	cff.normalize_keywords();
+	assert_eq!(cff.keywords, vec!["rust"]);
+	assert_eq!(cff.references[0].keywords, vec!["a", "b"]);
+}
+
+#[test]
+fn vocabulary_mapping() {
+	let vocabulary = Vocabulary::from_yaml_str(
+		r#"
+Citation analysis:
+  - bibliometrics
+  - Citation Metrics
+Research software: [RSE]
+Open science:
+"#,
+	)
+	.unwrap();
+
+	assert_eq!(
+		vocabulary.lookup("BIBLIOMETRICS"),
+		Some("Citation analysis")
+	);
+	assert_eq!(vocabulary.lookup("open  science"), Some("Open science"));
+	assert_eq!(vocabulary.lookup("rust"), None);
+
+	let mapping = vocabulary.map(["citation metrics", "Bibliometrics", "rse", "Rust"]);
+	assert_eq!(
+		mapping.mapped,
+		vec!["Citation analysis", "Research software"]
+	);
+	assert_eq!(mapping.unmapped, vec!["rust"]);
+}
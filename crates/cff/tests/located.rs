@@ -0,0 +1,127 @@
+use citeworks_cff::{from_str_lenient, from_str_located};
+
+use pretty_assertions::assert_eq;
+
+const HEAD: &str = "cff-version: 1.2.0\nmessage: Please cite this software.\ntitle: Example\n";
+
+#[test]
+fn parses() {
+	let cff = from_str_located(&format!("{HEAD}authors:\n  - name: Acme\n")).unwrap();
+	assert_eq!(cff.title, "Example");
+}
+
+#[test]
+fn nested_key() {
+	let text = format!(
+		"{HEAD}authors:\n  - name: Acme\nreferences:\n  - title: Other\n    authors: []\n    type: [nope]\n"
+	);
+	let err = from_str_located(&text).unwrap_err();
+
+	assert_eq!(err.path, "references[0].type");
+	assert_eq!(err.message, "expected a reference type, like `article`");
+	assert_eq!((err.line, err.column), (9, 5));
+	assert_eq!(&text[err.offset..err.offset + err.len], "type");
+}
+
+#[test]
+fn missing_field() {
+	let err = from_str_located("cff-version: 1.2.0\nmessage: Hi\nauthors: []\n").unwrap_err();
+
+	assert_eq!(err.path, "");
+	assert_eq!(err.message, "missing field `title`");
+	assert_eq!((err.line, err.column), (1, 1));
+}
+
+#[test]
+fn invalid_yaml() {
+	let err =
+		from_str_located(&format!("{HEAD}authors:\n  - name: Acme\n  name: Other\n")).unwrap_err();
+
+	assert_eq!((err.line, err.column), (6, 3));
+	assert!(err
+		.message
+		.starts_with("did not find expected '-' indicator, "));
+}
+
+#[test]
+fn diagnostic() {
+	use miette::Diagnostic;
+
+	let text = format!("{HEAD}authors: []\ndate-released: 2022-13-45\n");
+	let err = from_str_located(&text).unwrap_err();
+	let labels: Vec<_> = err.labels().unwrap().collect();
+
+	assert_eq!(labels.len(), 1);
+	assert_eq!(labels[0].offset(), text.find("date-released").unwrap());
+	assert_eq!(labels[0].len(), "date-released".len());
+	assert!(err.source_code().is_some());
+}
+
+#[test]
+fn lenient_collects_errors() {
+	let text = format!(
+		"{HEAD}authors:
+  - name: Acme
+  - name: Other
+    orcid: [1]
+  - name: Third
+date-released: 20 August 2022
+repository-code: not a url
+references:
+  - title: Bad
+    authors: []
+    type: [nope]
+  - title: Good
+    authors: []
+    type: software
+  - title: Also bad
+    authors: []
+    type: software
+    date-published: soon
+"
+	);
+	let (cff, errors) = from_str_lenient(&text).unwrap();
+
+	assert_eq!(cff.authors.len(), 2);
+	assert_eq!(cff.date_released, None);
+	assert_eq!(cff.repository_code, None);
+	assert_eq!(cff.references.len(), 2);
+	assert_eq!(cff.references[0].title.as_deref(), Some("Good"));
+
+	let found: Vec<_> = errors
+		.iter()
+		.map(|err| (err.path.as_str(), err.line))
+		.collect();
+	assert_eq!(
+		found,
+		vec![
+			("authors[1]", 6),
+			("date-released", 9),
+			("repository-code", 10),
+			("references[0].type", 14),
+			("references[2].date-published", 21),
+		]
+	);
+}
+
+#[test]
+fn lenient_required_fields() {
+	let (cff, errors) = from_str_lenient("cff-version: 1.2.0\ntitle: [not, text]\n").unwrap();
+
+	assert_eq!(cff.title, "");
+	assert_eq!(cff.message, "");
+	assert!(cff.authors.is_empty());
+	assert_eq!(
+		errors
+			.iter()
+			.map(|err| err.path.as_str())
+			.collect::<Vec<_>>(),
+		["", "", "title"]
+	);
+	assert_eq!(errors[0].message, "missing field `message`");
+}
+
+#[test]
+fn lenient_invalid_yaml() {
+	assert!(from_str_lenient("title: [").is_err());
+}
@@ -0,0 +1,59 @@
+use citeworks_cff::names::{alphabetical, sort_alphabetically, EntityName, Name, PersonName};
+
+use pretty_assertions::assert_eq;
+
+fn person(family: &str, given: &str) -> Name {
+	Name::Person(PersonName {
+		family_names: Some(family.into()),
+		given_names: Some(given.into()),
+		..Default::default()
+	})
+}
+
+fn entity(name: &str) -> Name {
+	Name::Entity(EntityName {
+		name: Some(name.into()),
+		..Default::default()
+	})
+}
+
+#[test]
+fn sorts_alphabetically() {
+	let authors = vec![
+		person("Roe", "Richard"),
+		entity("Research Software Lab"),
+		person("doe", "Jane"),
+		Name::Anonymous,
+		person("Doe", "Adam"),
+	];
+
+	assert_eq!(
+		alphabetical(&authors)
+			.into_iter()
+			.map(|(n, _)| n)
+			.collect::<Vec<_>>(),
+		vec![3, 4, 2, 1, 0]
+	);
+
+	let mut sorted = authors.clone();
+	sort_alphabetically(&mut sorted);
+	assert_eq!(
+		sorted,
+		vec![
+			Name::Anonymous,
+			person("Doe", "Adam"),
+			person("doe", "Jane"),
+			entity("Research Software Lab"),
+			person("Roe", "Richard"),
+		]
+	);
+}
+
+#[test]
+fn same_names() {
+	assert!(person("Doe", "Jane").is_same(&person("DOE", "jane")));
+	assert!(!person("Doe", "Jane").is_same(&person("Doe", "John")));
+	assert!(entity("ACME").is_same(&entity("Acme")));
+	assert!(!entity("Doe").is_same(&person("Doe", "Jane")));
+	assert!(!Name::Anonymous.is_same(&Name::Anonymous));
+}
@@ -0,0 +1,86 @@
+use citeworks_cff::{
+	partial_dates::{self, Precision, Strictness},
+	Date, PartialDate,
+};
+
+use pretty_assertions::assert_eq;
+
+const DOC: &str = "cff-version: 1.2.0
+message: Please cite this software.
+title: Example
+authors:
+  - name: Acme
+    date-start: 2019-03
+date-released: 2020
+references:
+  - type: book
+    title: Things
+    authors:
+      - name: Acme
+    date-published: '2018-11'
+    date-accessed: 2022-01-02
+";
+
+#[test]
+fn parse_partial_dates() {
+	let date: PartialDate = "2020-05".parse().unwrap();
+	assert_eq!(date.precision(), Precision::Month);
+	assert_eq!(date.to_date(), None);
+	assert_eq!(date.complete().to_string(), "2020-05-01");
+	assert_eq!(date.to_string(), "2020-05");
+
+	let full: PartialDate = "2020-05-17".parse().unwrap();
+	assert_eq!(full.precision(), Precision::Day);
+	assert_eq!(full.to_date(), Some("2020-05-17".parse::<Date>().unwrap()));
+	assert_eq!(PartialDate::from(full.complete()), full);
+
+	assert_eq!(
+		"1999".parse::<PartialDate>().unwrap().precision(),
+		Precision::Year
+	);
+	assert!("2020-13".parse::<PartialDate>().is_err());
+	assert!("2020-5".parse::<PartialDate>().is_err());
+	assert!("20".parse::<PartialDate>().is_err());
+}
+
+#[test]
+fn read_partial_dates() {
+	let (cff, partial) = partial_dates::from_str(DOC).unwrap();
+	assert_eq!(cff.date_released.unwrap().to_string(), "2020-01-01");
+	assert_eq!(
+		cff.references[0].date_published.unwrap().to_string(),
+		"2018-11-01"
+	);
+
+	assert_eq!(
+		partial
+			.iter()
+			.map(|(path, date)| (path.to_string(), date.to_string()))
+			.collect::<Vec<_>>(),
+		vec![
+			("authors[0].date-start".to_string(), "2019-03".to_string()),
+			("date-released".into(), "2020".into()),
+			("references[0].date-published".into(), "2018-11".into()),
+		]
+	);
+	assert_eq!(partial.get("references[0].date-accessed"), None);
+
+	// The strict parser still rejects them.
+	assert!(citeworks_cff::from_str(DOC).is_err());
+}
+
+#[test]
+fn write_partial_dates() {
+	let (mut cff, partial) = partial_dates::from_str(DOC).unwrap();
+	cff.date_released = Some("2021-06-30".parse().unwrap());
+
+	let strict = partial_dates::to_string(&cff, &partial, Strictness::Strict).unwrap();
+	assert!(strict.contains("date-published: 2018-11-01"));
+	assert!(citeworks_cff::from_str(&strict).is_ok());
+
+	let lenient = partial_dates::to_string(&cff, &partial, Strictness::Lenient).unwrap();
+	assert!(lenient.contains("date-published: 2018-11"));
+	assert!(lenient.contains("date-start: 2019-03"));
+	// Changed dates are written as they are now.
+	assert!(lenient.contains("date-released: 2021-06-30"));
+}
@@ -57,7 +57,9 @@ fn short() {
 				month: 7,
 				day: 22
 			}),
-			license: Some(License::Single(Expression::parse("Apache-2.0").unwrap())),
+			license: Some(License::Single(Box::new(
+				Expression::parse("Apache-2.0").unwrap()
+			))),
 			repository_artifact: Some(Url::parse("https://rubygems.org/gems/cff").unwrap()),
 			..Cff::default()
 		}
@@ -219,7 +221,6 @@ fn conference_paper() {
 						post_code: Some("12345".into()),
 						..Default::default()
 					},
-					..Default::default()
 				}),
 				start: Some(42),
 				end: Some(45),
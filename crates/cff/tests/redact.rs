@@ -0,0 +1,61 @@
+use citeworks_cff::names::{EntityName, Name, NameMeta};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn redacts_people_throughout() {
+	let mut cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My Research Software
+authors:
+  - family-names: Doe
+    given-names: Jane
+    orcid: https://orcid.org/0000-0003-4925-7248
+    city: Wellington
+    address: 1 Example Street
+    post-code: "6011"
+contact:
+  - name: Research Software Lab
+    email: lab@example.com
+references:
+  - type: software
+    title: Dependency
+    authors:
+      - family-names: Roe
+        fax: +64 4 765 4321
+    translators:
+      - family-names: Moe
+        email: moe@example.com
+"#,
+	)
+	.unwrap();
+
+	cff.redact_contact_details();
+
+	let jane = cff.authors[0].as_person().unwrap();
+	assert_eq!(jane.meta.address, None);
+	assert_eq!(jane.meta.post_code, None);
+	assert_eq!(jane.meta.city.as_deref(), Some("Wellington"));
+	assert!(jane.meta.orcid.is_some());
+
+	assert_eq!(
+		cff.contact,
+		vec![Name::Entity(EntityName {
+			name: Some("Research Software Lab".into()),
+			meta: NameMeta {
+				email: Some("lab@example.com".into()),
+				..Default::default()
+			},
+			..Default::default()
+		})]
+	);
+
+	let reference = &cff.references[0];
+	assert_eq!(reference.authors[0].as_person().unwrap().meta.fax, None);
+	assert_eq!(
+		reference.translators[0].as_person().unwrap().meta.email,
+		None
+	);
+}
@@ -0,0 +1,60 @@
+use citeworks_cff::{
+	references::{RefType, Reference},
+	WorkType,
+};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn issue_number_or_string() {
+	let yaml = |issue: &str| -> Reference {
+		serde_yaml::from_str(&format!("type: article\nauthors: []\nissue: {issue}\n")).unwrap()
+	};
+	let json: Reference =
+		serde_json::from_str(r#"{"type": "article", "authors": [], "issue": 10}"#).unwrap();
+
+	assert_eq!(json.issue.as_deref(), Some("10"));
+	assert_eq!(yaml("10"), json);
+	assert_eq!(yaml("'10'"), json);
+	assert_eq!(yaml("'010'").issue_number(), Some(10));
+	assert_eq!(yaml("3-4").issue_number(), None);
+	assert_eq!(Reference::default().issue_number(), None);
+
+	let written = citeworks_cff::to_string(&citeworks_cff::Cff {
+		references: vec![json],
+		..Default::default()
+	})
+	.unwrap();
+	assert!(written.contains("issue: '10'"), "{written}");
+}
+
+#[test]
+fn unknown_types_round_trip() {
+	let text = r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My Research Software
+type: model
+authors:
+  - name: Research Software Lab
+references:
+  - type: software
+    title: Dependency
+    authors: []
+  - type: notebook
+    title: Analysis
+    authors: []
+"#;
+	let cff = citeworks_cff::from_str(text).unwrap();
+	assert_eq!(cff.work_type, Some(WorkType::Other("model".into())));
+	assert_eq!(cff.references[0].work_type, RefType::Software);
+	assert_eq!(
+		cff.references[1].work_type,
+		RefType::Other("notebook".into())
+	);
+
+	let written = citeworks_cff::to_string(&cff).unwrap();
+	assert!(written.contains("type: model\n"), "{written}");
+	assert!(written.contains("- type: notebook\n"), "{written}");
+	assert_eq!(citeworks_cff::from_str(&written).unwrap(), cff);
+}
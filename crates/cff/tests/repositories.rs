@@ -0,0 +1,93 @@
+use citeworks_cff::{
+	references::Reference,
+	repositories::{classify_repository, RepositoryKind},
+	validation::Rule,
+};
+
+use pretty_assertions::assert_eq;
+use url::Url;
+
+fn classify(url: &str) -> Option<RepositoryKind> {
+	classify_repository(&Url::parse(url).unwrap())
+}
+
+#[test]
+fn hosts() {
+	assert_eq!(
+		classify("https://github.com/passcod/citeworks"),
+		Some(RepositoryKind::Code)
+	);
+	assert_eq!(
+		classify("https://gitlab.inria.fr/team/project"),
+		Some(RepositoryKind::Code)
+	);
+	assert_eq!(
+		classify("https://codeberg.org/owner/repo"),
+		Some(RepositoryKind::Code)
+	);
+	assert_eq!(
+		classify("https://github.com/passcod/citeworks/releases/tag/v1.0.0"),
+		Some(RepositoryKind::Artifact)
+	);
+	assert_eq!(
+		classify("https://pypi.org/project/cffconvert/"),
+		Some(RepositoryKind::Artifact)
+	);
+	assert_eq!(
+		classify("https://www.npmjs.com/package/citeworks"),
+		Some(RepositoryKind::Artifact)
+	);
+	assert_eq!(
+		classify("https://hub.docker.com/r/owner/image"),
+		Some(RepositoryKind::Artifact)
+	);
+	assert_eq!(
+		classify("https://zenodo.org/record/1234"),
+		Some(RepositoryKind::Archive)
+	);
+	assert_eq!(
+		classify("https://dataverse.harvard.edu/dataset.xhtml"),
+		Some(RepositoryKind::Archive)
+	);
+	assert_eq!(classify("https://passcod.github.io/citeworks"), None);
+	assert_eq!(classify("https://notgithub.com/owner/repo"), None);
+}
+
+#[test]
+fn misplaced_repositories() {
+	let mut reference = Reference {
+		repository_code: Some(Url::parse("https://crates.io/crates/citeworks-cff").unwrap()),
+		repository: Some(Url::parse("https://github.com/passcod/citeworks").unwrap()),
+		..Default::default()
+	};
+
+	let issues = reference.validate();
+	let found: Vec<_> = issues
+		.iter()
+		.map(|issue| {
+			(
+				issue.rule,
+				issue.path.as_str(),
+				issue.args["field"].as_str(),
+			)
+		})
+		.collect();
+	assert_eq!(
+		found,
+		vec![
+			(Rule::RepositoryField, "repository", "repository-code"),
+			(
+				Rule::RepositoryField,
+				"repository-code",
+				"repository-artifact"
+			),
+		]
+	);
+
+	let url = reference.repository.take().unwrap();
+	reference.set_repository(RepositoryKind::Code, url);
+	reference.repository_artifact = None;
+	let url = Url::parse("https://crates.io/crates/citeworks-cff").unwrap();
+	reference.set_repository(classify_repository(&url).unwrap(), url);
+	assert_eq!(reference.validate(), vec![]);
+}
@@ -0,0 +1,68 @@
+use citeworks_cff::test_util::{
+	arb_cff, arb_name, arb_reference, assert_roundtrip, assert_yaml_roundtrip,
+};
+use proptest::prelude::*;
+
+proptest! {
+	#[test]
+	fn cff_yaml(cff in arb_cff()) {
+		assert_yaml_roundtrip(&cff);
+	}
+
+	#[test]
+	fn cff_str(cff in arb_cff()) {
+		assert_roundtrip(&cff, citeworks_cff::to_string, |s| citeworks_cff::from_str(&s));
+	}
+
+	#[test]
+	fn cff_slice(cff in arb_cff()) {
+		assert_roundtrip(&cff, citeworks_cff::to_vec, |v| citeworks_cff::from_slice(&v));
+	}
+
+	#[test]
+	fn reference_yaml(reference in arb_reference()) {
+		assert_roundtrip(&reference, serde_yaml::to_string, |s| serde_yaml::from_str(&s));
+	}
+
+	#[test]
+	fn name_yaml(name in arb_name()) {
+		assert_roundtrip(&name, serde_yaml::to_string, |s| serde_yaml::from_str(&s));
+	}
+}
+
+#[test]
+fn parse_error_location() {
+	let err: citeworks_cff::Error =
+		citeworks_cff::from_str("cff-version: 1.2.0\ntitle: [\n").unwrap_err();
+	assert_eq!(err.location().map(|l| l.line()), Some(2));
+}
+
+#[test]
+fn unknown_fields_are_kept() {
+	let text = "cff-version: 1.3.0
+message: Please cite this software using these metadata.
+title: Opaquity
+authors:
+  - name: Dark Side Software
+contributors:
+  - name: Light Side Software
+references:
+  - type: software
+    title: Opacity
+    authors:
+      - name: Grey Side Software
+    x-archived: true
+";
+	let mut cff = citeworks_cff::from_str(text).unwrap();
+	assert!(cff.extra.contains_key("contributors"));
+	assert_eq!(
+		cff.references[0].extra.get("x-archived"),
+		Some(&serde_yaml::Value::Bool(true))
+	);
+
+	cff.version = Some("1.0.0".into());
+	let written = citeworks_cff::to_string(&cff).unwrap();
+	assert!(written.contains("contributors:"), "{written}");
+	assert!(written.contains("x-archived: true"), "{written}");
+	assert_eq!(citeworks_cff::from_str(&written).unwrap(), cff);
+}
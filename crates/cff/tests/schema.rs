@@ -0,0 +1,89 @@
+use std::fs::read_to_string;
+
+use citeworks_cff::schema::validate_against_schema;
+
+fn validate_file(name: &str) -> Vec<String> {
+	let yaml = read_to_string(format!("tests/pass/{name}.cff")).unwrap();
+	validate_against_schema(&yaml)
+		.unwrap()
+		.into_iter()
+		.map(|violation| format!("{}: {}", violation.path, violation))
+		.collect()
+}
+
+#[test]
+fn passing_files() {
+	for name in [
+		"binary",
+		"closed-source",
+		"conference-paper",
+		"container",
+		"mardyn",
+		"minimal",
+		"short",
+		"simple",
+	] {
+		assert_eq!(validate_file(name), Vec::<String>::new(), "{name}");
+	}
+}
+
+#[test]
+fn positions() {
+	let violations = validate_against_schema(
+		r#"cff-version: 1.2.0
+message: Please cite this software.
+title: Example
+authors:
+  - family-names: Roe
+    given-names: Jane
+date-released: 20 August 2022
+references:
+  - type: software
+    title: Dependency
+    authors:
+      - name: Acme
+    doi: not a doi
+"#,
+	)
+	.unwrap();
+
+	let mut found: Vec<_> = violations
+		.iter()
+		.map(|violation| (violation.path.as_str(), violation.line, violation.column))
+		.collect();
+	found.sort();
+	found.dedup();
+	assert_eq!(
+		found,
+		vec![("/date-released", 7, 1), ("/references/0/doi", 13, 5)]
+	);
+}
+
+#[test]
+fn missing_fields() {
+	let violations = validate_against_schema("cff-version: 1.2.0\ntitle: Example\n").unwrap();
+
+	assert_eq!(violations.len(), 2);
+	for violation in violations {
+		assert_eq!(violation.path, "");
+		assert_eq!((violation.line, violation.column), (1, 1));
+		assert!(violation.message.contains("is a required property"));
+	}
+}
+
+#[test]
+fn invalid_yaml() {
+	assert!(validate_against_schema("title: [").is_err());
+}
+
+#[test]
+fn unexpected_key() {
+	let violations = validate_against_schema(
+		"cff-version: 1.2.0\nmessage: Hi\ntitle: Example\nauthors:\n  - name: Acme\nhomepage: https://example.com\n",
+	)
+	.unwrap();
+
+	assert_eq!(violations.len(), 1);
+	assert_eq!(violations[0].path, "");
+	assert_eq!((violations[0].line, violations[0].column), (6, 1));
+}
@@ -0,0 +1,66 @@
+use pretty_assertions::{assert_eq, assert_ne};
+
+const SIGNED: &str = r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: Analyse de données
+authors:
+  - family-names: Doe
+    given-names: Jane
+x-extension:
+  second: 2
+  first: 1
+"#;
+
+#[test]
+fn canonical_text_ignores_encoding_and_key_order() {
+	let signed = citeworks_cff::from_str(SIGNED).unwrap();
+	let mirrored = citeworks_cff::from_str(
+		"
+x-extension: { first: 1, second: 2 }
+authors:
+  - given-names: Jane
+    family-names: Doe
+title: \"Analyse de donne\u{301}es\"
+message: 'Please cite this software using these metadata.'
+cff-version: 1.2.0
+",
+	)
+	.unwrap();
+
+	assert_eq!(
+		signed.canonical_string().unwrap(),
+		mirrored.canonical_string().unwrap()
+	);
+}
+
+#[test]
+fn canonical_text_sorts_keys() {
+	let canonical = citeworks_cff::from_str(SIGNED)
+		.unwrap()
+		.canonical_string()
+		.unwrap();
+
+	let keys: Vec<&str> = canonical
+		.lines()
+		.filter(|line| !line.starts_with(' ') && !line.starts_with('-'))
+		.filter_map(|line| line.split_once(':').map(|(key, _)| key))
+		.collect();
+	assert_eq!(
+		keys,
+		["authors", "cff-version", "message", "title", "x-extension"]
+	);
+	assert!(canonical.contains("  first: 1\n  second: 2\n"));
+}
+
+#[test]
+fn canonical_text_changes_with_metadata() {
+	let signed = citeworks_cff::from_str(SIGNED).unwrap();
+	let mut tampered = signed.clone();
+	tampered.title = "Analyse de données (fork)".into();
+
+	assert_ne!(
+		signed.canonical_string().unwrap(),
+		tampered.canonical_string().unwrap()
+	);
+}
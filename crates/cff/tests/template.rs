@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+
+use citeworks_cff::template::Template;
+
+use pretty_assertions::assert_eq;
+
+fn context(vars: &[(&str, &str)]) -> BTreeMap<String, String> {
+	vars.iter()
+		.map(|(name, value)| (name.to_string(), value.to_string()))
+		.collect()
+}
+
+#[test]
+fn placeholders() {
+	let template = Template::new("a: {{ a }}\nb: {{b}} {{a}}\nc: {{not a placeholder}}\n");
+	assert_eq!(template.placeholders(), vec!["a", "b"]);
+	assert_eq!(
+		template.render(&context(&[("a", "1"), ("b", "2")])),
+		Ok("a: 1\nb: 2 1\nc: {{not a placeholder}}\n".into())
+	);
+}
+
+#[test]
+fn missing_values() {
+	let template = Template::new("{{version}} {{date}} {{version}}");
+	assert_eq!(
+		template.render(&context(&[])),
+		Err("no value for version, date".into())
+	);
+}
+
+#[test]
+fn unterminated() {
+	let template = Template::new("title: {{title");
+	assert!(template.placeholders().is_empty());
+	assert_eq!(template.render(&context(&[])), Ok("title: {{title".into()));
+}
+
+#[test]
+fn instantiate() {
+	let template = Template::new(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: "{{title}}"
+authors:
+  - name: The Research Software project
+date-released: {{date}}
+"#,
+	);
+
+	let cff = template
+		.instantiate(&context(&[
+			("title", "My Research Software"),
+			("date", "2022-08-30"),
+		]))
+		.unwrap();
+	assert_eq!(cff.title, "My Research Software");
+
+	let err = template
+		.instantiate(&context(&[
+			("title", "My Research Software"),
+			("date", "soon"),
+		]))
+		.unwrap_err();
+	assert!(err.starts_with("filled-in template doesn't parse"), "{err}");
+}
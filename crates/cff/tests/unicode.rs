@@ -0,0 +1,43 @@
+use citeworks_cff::{
+	references::Reference,
+	unicode::{is_mojibake, repair_mojibake, Suspect},
+};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn repairs() {
+	assert_eq!(repair_mojibake("MÃ¼ller").as_deref(), Some("Müller"));
+	assert_eq!(repair_mojibake("Itâ€™s done").as_deref(), Some("It’s done"));
+	assert_eq!(repair_mojibake("Müller"), None);
+	assert_eq!(repair_mojibake("Ørsted and Łukasiewicz"), None);
+}
+
+#[test]
+fn detects() {
+	assert!(is_mojibake("Jos\u{FFFD} Garc\u{FFFD}a"));
+	assert!(is_mojibake("Jos? Garc??a"));
+	assert!(!is_mojibake("Is it? Yes."));
+	assert!(!is_mojibake("https://example.com/index.php?id=1"));
+}
+
+#[test]
+fn reference() {
+	let mut reference = Reference {
+		title: Some("Cafe\u{301} culture".into()),
+		abstract_text: Some("DonnÃ©es".into()),
+		..Default::default()
+	};
+
+	reference.normalize_unicode();
+	assert_eq!(reference.title.as_deref(), Some("Caf\u{e9} culture"));
+
+	assert_eq!(
+		reference.mojibake(),
+		vec![Suspect {
+			path: "abstract".into(),
+			value: "DonnÃ©es".into(),
+			suggestion: Some("Données".into()),
+		}]
+	);
+}
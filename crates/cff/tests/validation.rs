@@ -0,0 +1,554 @@
+use citeworks_cff::{
+	names::{EntityName, Name, NameMeta, PersonName},
+	references::{RefType, Reference},
+	validation::{Rule, Ruleset, Severity, ValidationOptions},
+};
+
+use pretty_assertions::assert_eq;
+use url::Url;
+
+fn rules(reference: &Reference) -> Vec<Rule> {
+	reference.validate().into_iter().map(|i| i.rule).collect()
+}
+
+#[test]
+fn conference_paper() {
+	let mut paper = Reference {
+		work_type: RefType::ConferencePaper,
+		..Default::default()
+	};
+	assert_eq!(
+		rules(&paper),
+		vec![
+			Rule::ConferencePaperConference,
+			Rule::ConferencePaperCollectionTitle
+		]
+	);
+
+	paper.conference = Some(EntityName {
+		name: Some("1st Conference on Wishful Thinking".into()),
+		..Default::default()
+	});
+	paper.collection_title = Some("Proceedings".into());
+	assert_eq!(rules(&paper), vec![]);
+}
+
+#[test]
+fn patent() {
+	let mut patent = Reference {
+		work_type: RefType::Patent,
+		..Default::default()
+	};
+	let issues = patent.validate();
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].rule, Rule::PatentStatesOrNumber);
+	assert_eq!(issues[0].severity, Severity::Warning);
+	assert_eq!(issues[0].path, "patent-states");
+
+	patent.number = Some("US 1234".into());
+	assert_eq!(rules(&patent), vec![]);
+}
+
+#[test]
+fn thesis() {
+	let thesis = Reference {
+		work_type: RefType::Thesis,
+		..Default::default()
+	};
+	assert_eq!(
+		rules(&thesis),
+		vec![Rule::ThesisInstitution, Rule::ThesisType]
+	);
+}
+
+#[test]
+fn generic_has_no_type_rules() {
+	assert_eq!(rules(&Reference::default()), vec![]);
+}
+
+#[test]
+fn suppress() {
+	let thesis = Reference {
+		work_type: RefType::Thesis,
+		..Default::default()
+	};
+	let options = ValidationOptions::default().suppress(Rule::ThesisType);
+	let issues = thesis.validate_with(&options);
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].rule, Rule::ThesisInstitution);
+}
+
+#[test]
+fn rule_names_roundtrip() {
+	for rule in Rule::ALL {
+		assert_eq!(rule.name().parse::<Rule>(), Ok(*rule));
+	}
+	assert!("not-a-rule".parse::<Rule>().is_err());
+}
+
+#[test]
+fn ror() {
+	let with_ror = |ror: &str| Reference {
+		authors: vec![Name::Entity(EntityName {
+			name: Some("The University of Manchester".into()),
+			meta: NameMeta {
+				ror: Some(Url::parse(ror).unwrap()),
+				..Default::default()
+			},
+			..Default::default()
+		})],
+		..Default::default()
+	};
+
+	assert_eq!(rules(&with_ror("https://ror.org/027m9bs27")), vec![]);
+
+	let issues = with_ror("https://ror.org/027m9bs28").validate();
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].rule, Rule::RorFormat);
+	assert_eq!(issues[0].severity, Severity::Error);
+	assert_eq!(issues[0].path, "authors[0].ror");
+
+	assert_eq!(
+		rules(&with_ror("https://example.com/027m9bs27")),
+		vec![Rule::RorFormat]
+	);
+	assert_eq!(
+		rules(&with_ror("https://ror.org/127m9bs27")),
+		vec![Rule::RorFormat]
+	);
+	assert_eq!(
+		rules(&with_ror("https://ror.org/0i7m9bs27")),
+		vec![Rule::RorFormat]
+	);
+}
+
+#[test]
+fn contact_not_author() {
+	let person = |family: &str, email: Option<&str>| {
+		Name::Person(PersonName {
+			family_names: Some(family.into()),
+			meta: NameMeta {
+				email: email.map(Into::into),
+				..Default::default()
+			},
+			..Default::default()
+		})
+	};
+	let reference = Reference {
+		authors: vec![person("Doe", None), person("Roe", Some("roe@example.com"))],
+		contact: vec![
+			person("doe", None),
+			person("Roe-Smith", Some("roe@example.com")),
+			person("Moe", None),
+		],
+		..Default::default()
+	};
+
+	let issues = reference.validate();
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].rule, Rule::ContactNotAuthor);
+	assert_eq!(issues[0].severity, Severity::Info);
+	assert_eq!(issues[0].path, "contact[2]");
+}
+
+#[test]
+fn github_ruleset() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My Research Software
+version: 1.0.0
+identifiers:
+  - type: swh
+    value: swh:1:dir:bc286860f423ea7ced246ba7458eef4b4541cf2d
+authors:
+  - given-names: Jane
+preferred-citation:
+  type: slides
+  title: A talk about the software
+  authors:
+    - family-names: Doe
+      given-names: Jane
+"#,
+	)
+	.unwrap();
+	assert_eq!(cff.validate(), vec![]);
+
+	let options = ValidationOptions::default().ruleset(Ruleset::Github);
+	let issues: Vec<_> = cff
+		.validate_with(&options)
+		.into_iter()
+		.map(|issue| (issue.rule, issue.path))
+		.collect();
+	assert_eq!(
+		issues,
+		vec![
+			(Rule::GithubIdentifierKind, "identifiers[0]".into()),
+			(Rule::GithubPreferredCitation, "preferred-citation".into()),
+			(
+				Rule::GithubPreferredCitationType,
+				"preferred-citation.type".into()
+			),
+			(
+				Rule::GithubPreferredCitationYear,
+				"preferred-citation.year".into()
+			),
+		]
+	);
+	assert_eq!(Rule::GithubFamilyNames.ruleset(), Some(Ruleset::Github));
+	assert_eq!(Rule::ThesisType.ruleset(), None);
+}
+
+#[test]
+fn zenodo_ruleset() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My Research Software
+doi: 10.5281/zenodo.1234
+license: MIT OR Apache-2.0
+authors:
+  - given-names: Jane
+  - family-names: Carberry
+    given-names: Josiah
+    orcid: https://orcid.org/0000-0002-1825-0097
+  - family-names: Roe
+    given-names: Richard
+    orcid: https://orcid.org/0000-0002-1825-0098
+"#,
+	)
+	.unwrap();
+	assert_eq!(cff.validate(), vec![]);
+
+	let options = ValidationOptions::default().ruleset(Ruleset::Zenodo);
+	let issues: Vec<_> = cff
+		.validate_with(&options)
+		.into_iter()
+		.map(|issue| (issue.rule, issue.path))
+		.collect();
+	assert_eq!(
+		issues,
+		vec![
+			(Rule::ZenodoCreatorName, "authors[0].family-names".into()),
+			(Rule::ZenodoOrcid, "authors[2].orcid".into()),
+			(Rule::ZenodoLicense, "license".into()),
+			(Rule::ZenodoAbstract, "abstract".into()),
+			(Rule::ZenodoDoi, "doi".into()),
+		]
+	);
+
+	// Concept and version DOIs look the same, so this is only a reminder.
+	let reminder = cff
+		.validate_with(&options)
+		.into_iter()
+		.find(|issue| issue.rule == Rule::ZenodoDoi)
+		.unwrap();
+	assert_eq!(reminder.severity, Severity::Info);
+	assert_eq!(
+		reminder.message,
+		"Zenodo DOIs are either for one release, or for all of them as the concept DOI: check that this is the one you mean"
+	);
+}
+
+#[test]
+fn document_invariants() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: " "
+title: ""
+authors: []
+doi: https://doi.org/10.5281/zenodo.1234
+"#,
+	)
+	.unwrap();
+	let issues = cff.validate();
+	assert_eq!(
+		issues
+			.iter()
+			.map(|i| (i.rule, i.path.as_str()))
+			.collect::<Vec<_>>(),
+		vec![
+			(Rule::MessageEmpty, "message"),
+			(Rule::TitleEmpty, "title"),
+			(Rule::AuthorsEmpty, "authors"),
+			(Rule::DoiFormat, "doi"),
+		]
+	);
+	assert!(issues.iter().all(|i| i.severity == Severity::Error));
+}
+
+#[test]
+fn month_and_dois() {
+	let mut reference = Reference {
+		month: Some(13),
+		doi: Some("10.123/short".into()),
+		collection_doi: Some("10.1234/".into()),
+		..Default::default()
+	};
+	assert_eq!(
+		rules(&reference),
+		vec![Rule::DoiFormat, Rule::DoiFormat, Rule::MonthRange]
+	);
+
+	reference.month = Some(12);
+	reference.doi = Some("10.1000.10/xyz(2022)".into());
+	reference.collection_doi = Some("10.21105/joss.01234".into());
+	assert_eq!(rules(&reference), vec![]);
+}
+
+#[test]
+fn orcid_format() {
+	let with_orcid = |orcid: &str| Reference {
+		authors: vec![Name::Person(PersonName {
+			family_names: Some("Carberry".into()),
+			meta: NameMeta {
+				orcid: Some(Url::parse(orcid).unwrap()),
+				..Default::default()
+			},
+			..Default::default()
+		})],
+		..Default::default()
+	};
+
+	assert_eq!(
+		rules(&with_orcid("https://orcid.org/0000-0002-1825-0097")),
+		vec![]
+	);
+	// The checksum is only checked by the Zenodo ruleset.
+	assert_eq!(
+		rules(&with_orcid("https://orcid.org/0000-0002-1825-0098")),
+		vec![]
+	);
+	assert_eq!(
+		rules(&with_orcid("http://orcid.org/0000-0002-1825-0097")),
+		vec![Rule::OrcidFormat]
+	);
+	assert_eq!(
+		rules(&with_orcid("https://orcid.org/0000-0002-1825")),
+		vec![Rule::OrcidFormat]
+	);
+}
+
+#[test]
+fn preferred_citation_self() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My Research Software
+authors:
+  - name: The Research Software project
+preferred-citation:
+  type: software
+  title: My Research Software
+  authors:
+    - name: The Research Software project
+"#,
+	)
+	.unwrap();
+	let issues = cff.validate();
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].rule, Rule::PreferredCitationSelf);
+	assert_eq!(issues[0].severity, Severity::Warning);
+	assert_eq!(issues[0].path, "preferred-citation");
+}
+
+#[test]
+fn conventions() {
+	let mut reference = Reference {
+		commit: Some("1ff847d".into()),
+		version: Some("  ".into()),
+		abstract_text: Some("https://example.com/about".into()),
+		..Default::default()
+	};
+	assert_eq!(
+		rules(&reference),
+		vec![Rule::CommitFormat, Rule::VersionBlank, Rule::AbstractUrl]
+	);
+
+	reference.commit = Some("1ff847d81f29c45a3a1a5ce73d38e45c2f319bba".into());
+	reference.version = Some("1.0.0".into());
+	reference.abstract_text = Some("A tool, see https://example.com/about".into());
+	assert_eq!(rules(&reference), vec![]);
+
+	reference.commit = Some("Revision: 8612".into());
+	assert_eq!(rules(&reference), vec![]);
+}
+
+#[test]
+fn config() {
+	let reference = Reference {
+		commit: Some("main".into()),
+		version: Some("".into()),
+		..Default::default()
+	};
+	let options = ValidationOptions::from_config(
+		"
+rules:
+  commit-format: error
+  version-blank: off
+",
+	)
+	.unwrap();
+
+	let issues = reference.validate_with(&options);
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].rule, Rule::CommitFormat);
+	assert_eq!(issues[0].severity, Severity::Error);
+
+	assert!(ValidationOptions::from_config("").is_ok());
+	assert_eq!(
+		ValidationOptions::from_config("rules:\n  commit-fromat: error\n").unwrap_err(),
+		"unknown rule: \"commit-fromat\""
+	);
+	assert_eq!(
+		ValidationOptions::from_config("rules:\n  commit-format: fatal\n").unwrap_err(),
+		"unknown severity: \"fatal\""
+	);
+}
+
+#[test]
+fn config_overrides() {
+	let reference = Reference {
+		commit: Some("main".into()),
+		..Default::default()
+	};
+	let off = ValidationOptions::from_config("rules:\n  commit-format: off\n").unwrap();
+	let error = ValidationOptions::from_config("rules:\n  commit-format: error\n").unwrap();
+
+	let issues = reference.validate_with(&off.clone().deny(Rule::CommitFormat));
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].severity, Severity::Error);
+	assert_eq!(
+		reference.validate_with(&error.clone().suppress(Rule::CommitFormat)),
+		vec![]
+	);
+
+	let issues = reference.validate_with(&off.clone().merge(error.clone()));
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].severity, Severity::Error);
+	assert_eq!(reference.validate_with(&error.merge(off)), vec![]);
+}
+
+#[test]
+fn isbn_and_issn() {
+	let mut book = Reference {
+		work_type: RefType::Generic,
+		isbn: Some("978-0-306-40615-8".into()),
+		issn: Some("0378-5955".parse().unwrap()),
+		..Default::default()
+	};
+	let issues = book.validate();
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].rule, Rule::IsbnFormat);
+	assert_eq!(issues[0].severity, Severity::Warning);
+	assert_eq!(issues[0].path, "isbn");
+
+	book.isbn = Some("978-0-306-40615-7".parse().unwrap());
+	book.issn = Some("0378-5954".into());
+	assert_eq!(rules(&book), vec![Rule::IssnFormat]);
+}
+
+#[test]
+fn unknown_type() {
+	let mut notebook = Reference {
+		work_type: RefType::Other("notebook".into()),
+		..Default::default()
+	};
+	let issues = notebook.validate();
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].rule, Rule::UnknownType);
+	assert_eq!(issues[0].severity, Severity::Warning);
+	assert_eq!(issues[0].path, "type");
+
+	notebook.work_type = RefType::Software;
+	assert_eq!(rules(&notebook), vec![]);
+}
+
+#[test]
+fn rule_codes() {
+	let codes: std::collections::BTreeSet<&str> =
+		Rule::ALL.iter().map(|rule| rule.code()).collect();
+	assert_eq!(codes.len(), Rule::ALL.len());
+	for rule in Rule::ALL {
+		assert!(rule.code().starts_with("CFF"), "{}", rule.code());
+		assert_eq!(rule.code().len(), 6, "{}", rule.code());
+		assert!(!rule.description().is_empty(), "{rule}");
+	}
+
+	assert_eq!(Rule::MessageEmpty.code(), "CFF001");
+	assert_eq!(Rule::ThesisInstitution.code(), "CFF014");
+	assert_eq!("CFF014".parse(), Ok(Rule::ThesisInstitution));
+	assert_eq!("cff014".parse(), Ok(Rule::ThesisInstitution));
+	assert_eq!("thesis-institution".parse(), Ok(Rule::ThesisInstitution));
+	assert_eq!(
+		Rule::IssnFormat.description(),
+		"An ISSN should be 8 digits, with a correct check digit. See Issn."
+	);
+}
+
+#[test]
+fn deny() {
+	let reference = Reference {
+		commit: Some("main".into()),
+		..Default::default()
+	};
+	let issues = reference.validate_with(&ValidationOptions::default().deny(Rule::CommitFormat));
+	assert_eq!(issues.len(), 1);
+	assert_eq!(issues[0].severity, Severity::Error);
+}
+
+#[test]
+fn unparsed_license() {
+	let cff = citeworks_cff::from_str(
+		"cff-version: 1.2.0
+message: Cite this.
+title: Example
+authors:
+  - name: Acme
+license: Proprietary
+references:
+  - type: software
+    title: Dependency
+    authors:
+      - name: Acme
+    license:
+      - MIT
+      - GPL-2.0+ with classpath
+",
+	)
+	.unwrap();
+	assert_eq!(
+		cff.license,
+		Some(citeworks_cff::License::Unparsed("Proprietary".into()))
+	);
+	assert!(!cff.license.as_ref().unwrap().is_spdx());
+	assert_eq!(cff.license.as_ref().unwrap().try_to_expression(), None);
+
+	let issues: Vec<_> = cff
+		.validate_with(&ValidationOptions::default().ruleset(Ruleset::Zenodo))
+		.into_iter()
+		.map(|issue| (issue.rule, issue.path, issue.severity))
+		.collect();
+	assert_eq!(
+		issues,
+		vec![
+			(Rule::LicenseFormat, "license".into(), Severity::Error),
+			// Not ZenodoLicense, as the license isn't an SPDX expression at all.
+			(Rule::ZenodoAbstract, "abstract".into(), Severity::Info),
+			(
+				Rule::LicenseFormat,
+				"references[0].license[1]".into(),
+				Severity::Error
+			),
+		]
+	);
+
+	let written = citeworks_cff::to_string(&cff).unwrap();
+	assert!(written.contains("license: Proprietary"));
+	assert!(written.contains("- GPL-2.0+ with classpath"));
+	assert_eq!(citeworks_cff::from_str(&written).unwrap(), cff);
+}
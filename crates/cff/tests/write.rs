@@ -24,14 +24,12 @@ fn yaml_cff(cff: Cff) -> Value {
 fn order_map(map: Mapping) -> Mapping {
 	let mut elements: Vec<(Value, Value)> = map.into_iter().collect();
 	elements.sort_by_key(|(k, _)| k.as_str().unwrap().to_string());
-	Mapping::from_iter(elements.into_iter())
+	Mapping::from_iter(elements)
 }
 
 fn order_all_maps(val: Value) -> Value {
 	match val {
-		Value::Sequence(seq) => {
-			Value::Sequence(seq.into_iter().map(|v| order_all_maps(v)).collect())
-		}
+		Value::Sequence(seq) => Value::Sequence(seq.into_iter().map(order_all_maps).collect()),
 		Value::Mapping(map) => {
 			let map = order_map(map);
 			Value::Mapping(Mapping::from_iter(
@@ -83,7 +81,9 @@ fn short() {
 				month: 7,
 				day: 22
 			}),
-			license: Some(License::Single(Expression::parse("Apache-2.0").unwrap())),
+			license: Some(License::Single(Box::new(
+				Expression::parse("Apache-2.0").unwrap()
+			))),
 			repository_artifact: Some(Url::parse("https://rubygems.org/gems/cff").unwrap()),
 			..Cff::default()
 		})
@@ -245,7 +245,6 @@ fn conference_paper() {
 						post_code: Some("12345".into()),
 						..Default::default()
 					},
-					..Default::default()
 				}),
 				start: Some(42),
 				end: Some(45),
@@ -0,0 +1,65 @@
+use std::{
+	fs::{read_to_string, File},
+	io::{stdin, stdout, Write},
+	path::PathBuf,
+};
+
+use citeworks_cff::{from_reader, from_str_located, Cff};
+use citeworks_codemeta::CodeMeta;
+use citeworks_convert::codemeta::update_codemeta;
+use clap::Parser;
+use miette::{IntoDiagnostic, NamedSource, Report, Result, WrapErr};
+
+/// Convert a CFF file to CodeMeta
+///
+/// Prints the codemeta.json, or with --update, changes only what's converted
+/// in an existing one, keeping its other properties.
+#[derive(Debug, Parser)]
+#[clap(author, version)]
+struct Args {
+	/// CFF file, or - to read STDIN
+	#[clap(default_value = "CITATION.cff")]
+	input: PathBuf,
+
+	/// Update this CodeMeta file instead of printing
+	#[clap(long, value_name = "FILE")]
+	update: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+	let args = Args::parse();
+
+	let cff: Cff = if args.input.to_str() == Some("-") {
+		from_reader(stdin()).into_diagnostic()?
+	} else {
+		let text = read_to_string(&args.input)
+			.into_diagnostic()
+			.wrap_err_with(|| format!("opening {}", args.input.display()))?;
+		from_str_located(&text).map_err(|err| {
+			Report::new(err).with_source_code(NamedSource::new(
+				args.input.display().to_string(),
+				text.clone(),
+			))
+		})?
+	};
+
+	let mut codemeta = match &args.update {
+		Some(target) => {
+			let file = File::open(target)
+				.into_diagnostic()
+				.wrap_err_with(|| format!("opening {}", target.display()))?;
+			citeworks_codemeta::from_reader(file)
+				.into_diagnostic()
+				.wrap_err_with(|| format!("parsing {}", target.display()))?
+		}
+		None => CodeMeta::default(),
+	};
+	update_codemeta(&mut codemeta, &cff);
+
+	let mut out: Box<dyn Write> = match &args.update {
+		Some(target) => Box::new(File::create(target).into_diagnostic()?),
+		None => Box::new(stdout()),
+	};
+	citeworks_codemeta::to_writer_pretty(&mut out, &codemeta).into_diagnostic()?;
+	writeln!(out).into_diagnostic()
+}
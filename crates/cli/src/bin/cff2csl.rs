@@ -0,0 +1,139 @@
+use std::{
+	fs::{read_to_string, File},
+	io::{stdin, stdout, BufWriter},
+	path::PathBuf,
+};
+
+use citeworks_cff::{from_reader, from_str_located, Cff};
+use citeworks_convert::{
+	csl::{references_to_items_with, work_to_item_with},
+	keys::{KeyGenerator, KeyPattern},
+	options::{ConvertOptions, Preset},
+	report::{AuditLog, PrintWarnings, WarningSink},
+	stash::restore,
+};
+use clap::Parser;
+use miette::{miette, IntoDiagnostic, NamedSource, Report, Result, WrapErr};
+
+/// Convert a CFF file to CSL-JSON
+///
+/// By default, all of the preferred citation, the work itself, and its
+/// references are converted, in that order. Use --work, --preferred-citation,
+/// and --references to select some of them.
+#[derive(Debug, Parser)]
+#[clap(author, version)]
+struct Args {
+	/// CFF file, or - to read STDIN
+	#[clap(default_value = "CITATION.cff")]
+	input: PathBuf,
+
+	/// Convert the work itself
+	#[clap(long)]
+	work: bool,
+
+	/// Convert the preferred citation, if there is one
+	#[clap(long)]
+	preferred_citation: bool,
+
+	/// Convert the references
+	#[clap(long)]
+	references: bool,
+
+	/// Pattern for the keys of the items
+	///
+	/// See `citeworks pandoc-filter --help` for the fields.
+	#[clap(long, value_name = "PATTERN", default_value = "[auth:lower][year]")]
+	key_pattern: KeyPattern,
+
+	/// Preset of conversion options
+	///
+	/// One of `zotero`, `crossref`, or `strict-spec`.
+	#[clap(long, value_name = "PRESET")]
+	preset: Option<Preset>,
+
+	/// Write how each field was converted to a file, as JSON lines
+	///
+	/// Each line has the `record` key and an `action`: `mapped` from a CFF
+	/// field to a CSL variable, `dropped`, `guessed`, or `warning`. See the
+	/// citeworks-convert documentation of `AuditLog` for the details.
+	#[clap(long, value_name = "FILE")]
+	audit_log: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+	let mut args = Args::parse();
+	if !(args.work || args.preferred_citation || args.references) {
+		args.work = true;
+		args.preferred_citation = true;
+		args.references = true;
+	}
+
+	let cff: Cff = if args.input.to_str() == Some("-") {
+		from_reader(stdin()).into_diagnostic()?
+	} else {
+		let text = read_to_string(&args.input)
+			.into_diagnostic()
+			.wrap_err_with(|| format!("opening {}", args.input.display()))?;
+		from_str_located(&text).map_err(|err| {
+			Report::new(err).with_source_code(NamedSource::new(
+				args.input.display().to_string(),
+				text.clone(),
+			))
+		})?
+	};
+
+	let mut options = args
+		.preset
+		.map_or_else(Default::default, ConvertOptions::preset);
+	// The preferred citation is selected here, so the work shouldn't report it
+	// as dropped.
+	options.preferred_citation = true;
+
+	let mut keys = KeyGenerator::new(args.key_pattern);
+	let mut audit_log = match &args.audit_log {
+		Some(path) => {
+			let file = File::create(path)
+				.into_diagnostic()
+				.wrap_err_with(|| format!("creating {}", path.display()))?;
+			Some(AuditLog::new(BufWriter::new(file)).forward(PrintWarnings::stderr()))
+		}
+		None => None,
+	};
+	let mut printer = PrintWarnings::stderr();
+	let warnings: &mut dyn WarningSink = match &mut audit_log {
+		Some(log) => log,
+		None => &mut printer,
+	};
+	let mut items = Vec::new();
+	if args.preferred_citation {
+		if let Some(preferred) = &cff.preferred_citation {
+			items.extend(references_to_items_with(
+				std::slice::from_ref(preferred),
+				&mut keys,
+				warnings,
+			));
+		}
+	}
+	if args.work {
+		items.push(work_to_item_with(&cff, &options, &mut keys, warnings));
+	}
+	if args.references {
+		items.extend(references_to_items_with(
+			&cff.references,
+			&mut keys,
+			warnings,
+		));
+	}
+	if let (Some(log), Some(path)) = (&mut audit_log, &args.audit_log) {
+		log.finish()
+			.into_diagnostic()
+			.wrap_err_with(|| format!("writing {}", path.display()))?;
+	}
+	for item in &mut items {
+		restore(item).map_err(|err| miette!("{}", err))?;
+	}
+
+	citeworks_csl::to_writer_pretty(stdout(), &items).into_diagnostic()?;
+	println!();
+	Ok(())
+}
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use citeworks_convert::badge::{cite_badge, doi_badge, BadgeFormat};
+use clap::Parser;
+use miette::{miette, Result};
+
+use crate::read_cff;
+
+/// Print README badges for the DOI of a work
+#[derive(Debug, Parser)]
+pub struct BadgeArgs {
+	/// CFF file to read
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// Markup to output: markdown or html
+	#[clap(long, default_value = "markdown")]
+	format: BadgeFormat,
+
+	/// Also print a "Cite this" badge
+	#[clap(long)]
+	cite: bool,
+
+	/// Where the "Cite this" badge links to, instead of the CITATION.cff in
+	/// the repository-code
+	#[clap(long, value_name = "URL", requires = "cite")]
+	cite_link: Option<String>,
+}
+
+pub fn run(args: BadgeArgs) -> Result<()> {
+	let cff = read_cff(&args.path)?;
+
+	let doi = doi_badge(&cff).ok_or_else(|| {
+		miette!(
+			"no DOI in {}: add a `doi` or a DOI identifier",
+			args.path.display()
+		)
+	})?;
+	println!("{}", doi.render(args.format));
+
+	if args.cite {
+		let cite = cite_badge(&cff, args.cite_link.as_deref()).ok_or_else(|| {
+			miette!(
+				"nowhere to link the cite badge to: add a `repository-code` or pass --cite-link"
+			)
+		})?;
+		println!("{}", cite.render(args.format));
+	}
+
+	Ok(())
+}
@@ -0,0 +1,77 @@
+use std::{io::stdout, path::PathBuf, str::FromStr};
+
+use citeworks_convert::{
+	aggregate::aggregate_citations_with,
+	bibtex::item_to_entry,
+	keys::{KeyGenerator, KeyPattern},
+	report::PrintWarnings,
+};
+use clap::Parser;
+use miette::{IntoDiagnostic, Result};
+
+use crate::read_cff;
+
+/// Print a bibliography of how many CFF files want to be cited
+///
+/// Each file contributes its `preferred-citation`, or the work itself if it
+/// doesn't have one. Works with the same DOI, or the same title and year, are
+/// listed once. The bibliography is CSL-JSON, or BibTeX with --format bibtex.
+#[derive(Debug, Parser)]
+pub struct BibliographyArgs {
+	/// CFF files to read
+	#[clap(required = true)]
+	paths: Vec<PathBuf>,
+
+	/// Pattern for the keys of the items
+	///
+	/// See `citeworks pandoc-filter --help` for the fields.
+	#[clap(long, value_name = "PATTERN", default_value = "[auth:lower][year]")]
+	key_pattern: KeyPattern,
+
+	/// Output format: csl-json or bibtex
+	#[clap(long, default_value = "csl-json")]
+	format: BibliographyFormat,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum BibliographyFormat {
+	CslJson,
+	Bibtex,
+}
+
+impl FromStr for BibliographyFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"csl-json" | "csl" => Ok(Self::CslJson),
+			"bibtex" | "bib" => Ok(Self::Bibtex),
+			_ => Err(format!("unknown bibliography format: {s:?}")),
+		}
+	}
+}
+
+pub fn run(args: BibliographyArgs) -> Result<()> {
+	let cffs = args
+		.paths
+		.iter()
+		.map(|path| read_cff(path))
+		.collect::<Result<Vec<_>>>()?;
+
+	let items = aggregate_citations_with(
+		&cffs,
+		&mut KeyGenerator::new(args.key_pattern),
+		&mut PrintWarnings::stderr(),
+	);
+	match args.format {
+		BibliographyFormat::CslJson => {
+			citeworks_csl::to_writer_pretty(stdout(), &items).into_diagnostic()?;
+			println!();
+		}
+		BibliographyFormat::Bibtex => {
+			let entries: Vec<_> = items.iter().map(item_to_entry).collect();
+			citeworks_bibtex::to_writer(stdout(), &entries).into_diagnostic()?;
+		}
+	}
+	Ok(())
+}
@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+
+use citeworks_cff::{references::Reference, Cff};
+use citeworks_fetch::{crossref::Crossref, USER_AGENT};
+use clap::Parser;
+use miette::{bail, Result};
+use serde_json::json;
+use url::Url;
+
+use crate::{
+	output::{print_json, OutputFormat},
+	read_cff,
+};
+
+/// Check that the links in a CFF file work, and that cited DOIs aren't retracted
+#[derive(Debug, Parser)]
+pub struct CheckLinksArgs {
+	/// CFF file to read
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// Don't check URLs, only look for retracted works
+	#[clap(long)]
+	skip_urls: bool,
+
+	/// Don't look for retracted works, only check URLs
+	#[clap(long, conflicts_with = "skip-urls")]
+	skip_retractions: bool,
+
+	/// Email address to send to Crossref, to use its "polite" pool
+	#[clap(long, value_name = "EMAIL")]
+	mailto: Option<String>,
+
+	/// Output format: text or json
+	#[clap(long, default_value = "text")]
+	format: OutputFormat,
+}
+
+/// A broken link or retracted work.
+struct Problem {
+	what: String,
+	kind: &'static str,
+	target: String,
+	message: String,
+}
+
+pub fn run(args: CheckLinksArgs) -> Result<()> {
+	let cff = read_cff(&args.path)?;
+
+	let mut problems = Vec::new();
+	if !args.skip_urls {
+		let agent = ureq::AgentBuilder::new().user_agent(USER_AGENT).build();
+		for (what, url) in urls(&cff) {
+			// Some servers don't do HEAD, so only fall back to GET on error.
+			let result = match agent.head(url.as_str()).call() {
+				Ok(response) => Ok(response),
+				Err(_) => agent.get(url.as_str()).call(),
+			};
+			if let Err(err) = result {
+				problems.push(Problem {
+					what,
+					kind: "broken-url",
+					target: url.to_string(),
+					message: format!("{url} is broken: {err}"),
+				});
+			}
+		}
+	}
+
+	if !args.skip_retractions {
+		let mut crossref = Crossref::new();
+		if let Some(mailto) = args.mailto {
+			crossref = crossref.mailto(mailto);
+		}
+
+		for (what, doi) in dois(&cff) {
+			match crossref.retraction(doi) {
+				Ok(Some(notice)) => {
+					let source = notice
+						.source
+						.map(|source| format!(", per {source}"))
+						.unwrap_or_default();
+					problems.push(Problem {
+						what,
+						kind: "retracted",
+						target: doi.into(),
+						message: format!(
+							"{doi} is retracted (notice {}: {}{source})",
+							notice.doi, notice.kind
+						),
+					});
+				}
+				Ok(None) => {}
+				Err(err) => problems.push(Problem {
+					what,
+					kind: "unchecked",
+					target: doi.into(),
+					message: format!("could not check {doi} for retraction: {err}"),
+				}),
+			}
+		}
+	}
+
+	match args.format {
+		OutputFormat::Text => {
+			for problem in &problems {
+				println!("{}: {}", problem.what, problem.message);
+			}
+		}
+		OutputFormat::Json => print_json(
+			"check-links",
+			json!({
+				"path": args.path,
+				"problems": problems.iter().map(|problem| json!({
+					"what": problem.what,
+					"kind": problem.kind,
+					"target": problem.target,
+					"message": problem.message,
+				})).collect::<Vec<_>>(),
+			}),
+		),
+	}
+
+	if !problems.is_empty() {
+		bail!(
+			"found {} problem(s) in {}",
+			problems.len(),
+			args.path.display()
+		);
+	}
+
+	Ok(())
+}
+
+fn describe(n: usize, reference: &Reference) -> String {
+	match &reference.title {
+		Some(title) => format!("reference {} ({title})", n + 1),
+		None => format!("reference {}", n + 1),
+	}
+}
+
+fn urls(cff: &Cff) -> Vec<(String, &Url)> {
+	let mut urls = Vec::new();
+	for (what, url) in [
+		("url", &cff.url),
+		("repository", &cff.repository),
+		("repository-artifact", &cff.repository_artifact),
+		("repository-code", &cff.repository_code),
+		("license-url", &cff.license_url),
+	] {
+		if let Some(url) = url {
+			urls.push((what.to_string(), url));
+		}
+	}
+
+	for (n, reference) in cff.references.iter().enumerate() {
+		for url in [&reference.url, &reference.repository_code]
+			.into_iter()
+			.flatten()
+		{
+			urls.push((describe(n, reference), url));
+		}
+	}
+
+	urls
+}
+
+fn dois(cff: &Cff) -> Vec<(String, &str)> {
+	let mut dois = Vec::new();
+	if let Some(doi) = &cff.doi {
+		dois.push(("doi".to_string(), doi.as_str()));
+	}
+	if let Some(doi) = cff.preferred_citation.as_ref().and_then(|p| p.doi.as_ref()) {
+		dois.push(("preferred-citation".to_string(), doi.as_str()));
+	}
+	for (n, reference) in cff.references.iter().enumerate() {
+		if let Some(doi) = &reference.doi {
+			dois.push((describe(n, reference), doi.as_str()));
+		}
+	}
+	dois
+}
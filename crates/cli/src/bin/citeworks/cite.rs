@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+
+use citeworks_convert::{
+	keys::{KeyGenerator, KeyPattern},
+	options::ConvertOptions,
+	render::{
+		author_date_with, bibliography_order, entry_with, to_plain, And, RenderOptions, Terms,
+	},
+};
+use clap::Parser;
+use miette::{bail, Result};
+
+use crate::read_library;
+
+/// Print bibliography entries or citations for the items of CFF or CSL-JSON files
+///
+/// Entries are printed in bibliography order. How lists of authors are
+/// shortened with "et al." and joined can be set separately for citations
+/// and for bibliography entries, like the et-al options of CSL styles.
+#[derive(Debug, Parser)]
+pub struct CiteArgs {
+	/// CFF or CSL-JSON files
+	#[clap(required = true)]
+	library: Vec<PathBuf>,
+
+	/// Only print the items with these keys, in this order
+	#[clap(long = "key", value_name = "KEY")]
+	keys: Vec<String>,
+
+	/// Print author-date citations, like `Doe et al. 2017`, instead of entries
+	#[clap(long)]
+	citations: bool,
+
+	/// Pattern for the keys of CFF references and items without ids
+	///
+	/// See `citeworks pandoc-filter --help` for the fields.
+	#[clap(long, value_name = "PATTERN", default_value = "[auth:lower][year]")]
+	key_pattern: KeyPattern,
+
+	/// Shorten author lists of citations from this many authors, or never if 0
+	#[clap(long, value_name = "N", default_value = "3")]
+	et_al_min: usize,
+
+	/// How many authors shortened lists of citations keep before "et al."
+	#[clap(long, value_name = "N", default_value = "1")]
+	et_al_use_first: usize,
+
+	/// What goes before the last author of citations: text, symbol, or none
+	///
+	/// Text is the "and" term of the locale, and symbol is "&".
+	#[clap(long, value_name = "AND", default_value = "text")]
+	and: And,
+
+	/// Shorten author lists of entries from this many authors, or never if 0
+	#[clap(long, value_name = "N", default_value = "0")]
+	bibliography_et_al_min: usize,
+
+	/// How many authors shortened lists of entries keep before "et al."
+	#[clap(long, value_name = "N", default_value = "1")]
+	bibliography_et_al_use_first: usize,
+
+	/// What goes before the last author of entries: text, symbol, or none
+	#[clap(long, value_name = "AND", default_value = "symbol")]
+	bibliography_and: And,
+
+	/// Language of the terms "and" and "et al.", e.g. `de` for "und" and "u. a."
+	///
+	/// Known languages are en, de, es, fr, it, nl, and pt.
+	#[clap(long, value_name = "LANG", default_value = "en")]
+	locale: String,
+}
+
+impl CiteArgs {
+	fn options(&self) -> Result<RenderOptions> {
+		let terms = match Terms::locale(&self.locale) {
+			Some(terms) => terms,
+			None => bail!("no terms for locale {:?}", self.locale),
+		};
+
+		let mut options = RenderOptions {
+			terms,
+			..Default::default()
+		};
+		options.citation.et_al_min = Some(self.et_al_min).filter(|min| *min > 0);
+		options.citation.et_al_use_first = self.et_al_use_first;
+		options.citation.and = self.and;
+		options.bibliography.et_al_min = Some(self.bibliography_et_al_min).filter(|min| *min > 0);
+		options.bibliography.et_al_use_first = self.bibliography_et_al_use_first;
+		options.bibliography.and = self.bibliography_and;
+		Ok(options)
+	}
+}
+
+pub fn run(args: CiteArgs) -> Result<()> {
+	let options = args.options()?;
+
+	let mut keys = KeyGenerator::new(args.key_pattern.clone());
+	let mut library = Vec::new();
+	for path in &args.library {
+		library.extend(read_library(path, &ConvertOptions::default(), &mut keys)?);
+	}
+
+	let items = if args.keys.is_empty() {
+		library.sort_by(bibliography_order);
+		library
+	} else {
+		let mut items = Vec::with_capacity(args.keys.len());
+		for key in &args.keys {
+			match library.iter().find(|item| &item.id == key) {
+				Some(item) => items.push(item.clone()),
+				None => bail!("no item with key {:?}", key),
+			}
+		}
+		items
+	};
+
+	for item in &items {
+		if args.citations {
+			println!("{}", author_date_with(item, &options));
+		} else {
+			println!("{}", to_plain(&entry_with(item, &options)));
+		}
+	}
+	Ok(())
+}
@@ -0,0 +1,62 @@
+use std::{
+	fs::read_to_string,
+	path::{Path, PathBuf},
+};
+
+use citeworks_cff::validation::{Rule, ValidationOptions};
+use clap::Args;
+use miette::{miette, IntoDiagnostic, Result, WrapErr};
+
+/// Config file read by default, in the current directory.
+pub const DEFAULT_CONFIG: &str = ".citeworks.yml";
+
+/// Read validation options from a config file.
+///
+/// Without a path, the default config file is read if it exists. A path that
+/// was given must exist.
+pub fn validation_options(path: Option<&Path>) -> Result<ValidationOptions> {
+	let path = match path {
+		Some(path) => path.to_path_buf(),
+		None => {
+			let path = PathBuf::from(DEFAULT_CONFIG);
+			if !path.exists() {
+				return Ok(ValidationOptions::default());
+			}
+			path
+		}
+	};
+
+	let yaml = read_to_string(&path)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("reading {}", path.display()))?;
+	ValidationOptions::from_config(&yaml)
+		.map_err(|err| miette!("{}", err))
+		.wrap_err_with(|| format!("parsing {}", path.display()))
+}
+
+/// Flags to tune rules, over the config file.
+#[derive(Debug, Args)]
+pub struct RuleArgs {
+	/// Don't raise issues for this rule, by code or name (can be given multiple
+	/// times)
+	#[clap(long, alias = "suppress", value_name = "RULE")]
+	allow: Vec<Rule>,
+
+	/// Raise issues for this rule as errors, by code or name (can be given
+	/// multiple times)
+	#[clap(long, value_name = "RULE")]
+	deny: Vec<Rule>,
+}
+
+impl RuleArgs {
+	/// Apply the flags to options, e.g. from the config file.
+	pub fn apply(&self, options: ValidationOptions) -> ValidationOptions {
+		let options = self
+			.allow
+			.iter()
+			.fold(options, |options, rule| options.suppress(*rule));
+		self.deny
+			.iter()
+			.fold(options, |options, rule| options.deny(*rule))
+	}
+}
@@ -0,0 +1,322 @@
+use std::{
+	fs::{read_dir, read_to_string},
+	io::Read,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+use citeworks_cff::{
+	from_str,
+	validation::{Ruleset, Severity, ValidationIssue, ValidationOptions},
+	Cff,
+};
+use citeworks_convert::table::{write_row, Delimiter};
+use citeworks_fetch::USER_AGENT;
+use clap::Parser;
+use miette::{IntoDiagnostic, Result, WrapErr};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+	config::{validation_options, RuleArgs},
+	output::print_json,
+};
+
+/// Find, validate, and report on the CITATION.cff files of many repositories
+#[derive(Debug, Parser)]
+pub struct CrawlArgs {
+	/// Directories to search, or URLs of GitHub or GitLab repositories
+	///
+	/// Defaults to the current directory if no sources are given here or with
+	/// --list.
+	sources: Vec<String>,
+
+	/// File with more sources, one per line
+	#[clap(long, value_name = "FILE")]
+	list: Option<PathBuf>,
+
+	/// Report format: json or csv
+	#[clap(long, default_value = "json")]
+	format: ReportFormat,
+
+	#[clap(flatten)]
+	rules: RuleArgs,
+
+	/// Also check this ruleset, e.g. github (can be given multiple times)
+	#[clap(long, value_name = "RULESET")]
+	ruleset: Vec<Ruleset>,
+
+	/// Config file with the severity of rules, defaults to .citeworks.yml
+	#[clap(long, value_name = "FILE")]
+	config: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ReportFormat {
+	Json,
+	Csv,
+}
+
+impl FromStr for ReportFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"json" => Ok(Self::Json),
+			"csv" => Ok(Self::Csv),
+			_ => Err(format!("unknown report format: {s:?}")),
+		}
+	}
+}
+
+/// A CITATION.cff file that was found, or that was looked for and failed.
+struct Found {
+	source: String,
+	cff: std::result::Result<Cff, String>,
+}
+
+/// What was found out about one file.
+struct Row {
+	source: String,
+	cff: Option<Cff>,
+	error: Option<String>,
+	issues: Vec<ValidationIssue>,
+}
+
+impl Row {
+	fn count(&self, severity: Severity) -> usize {
+		self.issues
+			.iter()
+			.filter(|issue| issue.severity == severity)
+			.count()
+	}
+}
+
+pub fn run(args: CrawlArgs) -> Result<()> {
+	let mut sources = args.sources;
+	if let Some(list) = &args.list {
+		let list = read_to_string(list)
+			.into_diagnostic()
+			.wrap_err_with(|| format!("reading {}", list.display()))?;
+		sources.extend(
+			list.lines()
+				.map(str::trim)
+				.filter(|line| !line.is_empty() && !line.starts_with('#'))
+				.map(String::from),
+		);
+	}
+	if sources.is_empty() {
+		sources.push(".".into());
+	}
+
+	let agent = ureq::AgentBuilder::new().user_agent(USER_AGENT).build();
+	let mut found = Vec::new();
+	for source in sources {
+		match Url::parse(&source) {
+			Ok(url) if matches!(url.scheme(), "http" | "https") => {
+				found.push(Found {
+					cff: fetch(&agent, &url),
+					source,
+				});
+			}
+			_ => {
+				let dir = PathBuf::from(&source);
+				if !dir.is_dir() {
+					found.push(Found {
+						source,
+						cff: Err("not a directory or repository URL".into()),
+					});
+					continue;
+				}
+				walk(&dir, &mut found);
+			}
+		}
+	}
+
+	let options =
+		args.rules.apply(
+			validation_options(args.config.as_deref())?.merge(ValidationOptions {
+				rulesets: args.ruleset.into_iter().collect(),
+				..Default::default()
+			}),
+		);
+	let rows: Vec<Row> = found
+		.into_iter()
+		.map(|found| match found.cff {
+			Ok(cff) => Row {
+				source: found.source,
+				issues: cff.validate_with(&options),
+				cff: Some(cff),
+				error: None,
+			},
+			Err(err) => Row {
+				source: found.source,
+				cff: None,
+				error: Some(err),
+				issues: Vec::new(),
+			},
+		})
+		.collect();
+
+	match args.format {
+		ReportFormat::Json => print_json("crawl", json_report(&rows)),
+		ReportFormat::Csv => print!("{}", csv_report(&rows)),
+	}
+
+	Ok(())
+}
+
+/// Recursively find CITATION.cff files, skipping hidden and build directories.
+///
+/// Paths which can't be read are recorded with their error, and skipped.
+fn walk(dir: &Path, found: &mut Vec<Found>) {
+	let unreadable = |path: &Path, err: std::io::Error| Found {
+		source: path.display().to_string(),
+		cff: Err(err.to_string()),
+	};
+
+	let mut entries = Vec::new();
+	match read_dir(dir) {
+		Ok(iter) => {
+			for entry in iter {
+				match entry {
+					Ok(entry) => entries.push(entry),
+					Err(err) => found.push(unreadable(dir, err)),
+				}
+			}
+		}
+		Err(err) => return found.push(unreadable(dir, err)),
+	}
+	entries.sort_by_key(|entry| entry.file_name());
+
+	for entry in entries {
+		let path = entry.path();
+		let name = entry.file_name();
+		let name = name.to_string_lossy();
+		let file_type = match entry.file_type() {
+			Ok(file_type) => file_type,
+			Err(err) => {
+				found.push(unreadable(&path, err));
+				continue;
+			}
+		};
+		if file_type.is_dir() {
+			if !name.starts_with('.') && name != "target" && name != "node_modules" {
+				walk(&path, found);
+			}
+		} else if name == "CITATION.cff" {
+			found.push(Found {
+				source: path.display().to_string(),
+				cff: read_to_string(&path)
+					.map_err(|err| err.to_string())
+					.and_then(|text| from_str(&text).map_err(|err| err.to_string())),
+			});
+		}
+	}
+}
+
+/// Fetch the CITATION.cff at the root of a repository's default branch.
+fn fetch(agent: &ureq::Agent, repo: &Url) -> std::result::Result<Cff, String> {
+	let url = raw_citation_url(repo).ok_or_else(|| {
+		format!(
+			"don't know how to get files from {}",
+			repo.host_str().unwrap_or_default()
+		)
+	})?;
+
+	let mut text = String::new();
+	match agent.get(url.as_str()).call() {
+		Ok(response) => response
+			.into_reader()
+			.read_to_string(&mut text)
+			.map_err(|err| err.to_string())?,
+		Err(ureq::Error::Status(404, _)) => return Err("no CITATION.cff".into()),
+		Err(err) => return Err(err.to_string()),
+	};
+	from_str(&text).map_err(|err| err.to_string())
+}
+
+fn raw_citation_url(repo: &Url) -> Option<Url> {
+	let host = repo.host_str()?;
+	let path = repo.path().trim_matches('/').trim_end_matches(".git");
+	if path.is_empty() {
+		return None;
+	}
+
+	let raw = if host == "github.com" {
+		format!("https://raw.githubusercontent.com/{path}/HEAD/CITATION.cff")
+	} else if host.contains("gitlab") {
+		format!("https://{host}/{path}/-/raw/HEAD/CITATION.cff")
+	} else {
+		return None;
+	};
+	Url::parse(&raw).ok()
+}
+
+fn json_report(rows: &[Row]) -> Value {
+	let files: Vec<Value> = rows
+		.iter()
+		.map(|row| {
+			json!({
+				"source": row.source,
+				"title": row.cff.as_ref().map(|cff| &cff.title),
+				"version": row.cff.as_ref().and_then(|cff| cff.version.as_ref()),
+				"doi": row.cff.as_ref().and_then(|cff| cff.doi.as_ref()),
+				"error": row.error,
+				"issues": row.issues.iter().map(|issue| json!({
+					"rule": issue.rule.name(),
+					"code": issue.rule.code(),
+					"severity": issue.severity.to_string(),
+					"path": issue.path,
+					"message": issue.message,
+				})).collect::<Vec<_>>(),
+			})
+		})
+		.collect();
+
+	let total = |severity| rows.iter().map(|row| row.count(severity)).sum::<usize>();
+	json!({
+		"summary": {
+			"files": rows.len(),
+			"unreadable": rows.iter().filter(|row| row.error.is_some()).count(),
+			"invalid": rows.iter().filter(|row| row.count(Severity::Error) > 0).count(),
+			"errors": total(Severity::Error),
+			"warnings": total(Severity::Warning),
+			"infos": total(Severity::Info),
+		},
+		"files": files,
+	})
+}
+
+/// One line per file, with counts of issues and the rules they're for.
+fn csv_report(rows: &[Row]) -> String {
+	let mut csv = String::from("source,title,version,doi,error,errors,warnings,infos,rules\n");
+	for row in rows {
+		let mut rules: Vec<&str> = row.issues.iter().map(|issue| issue.rule.name()).collect();
+		rules.sort_unstable();
+		rules.dedup();
+		let fields = [
+			row.source.clone(),
+			row.cff
+				.as_ref()
+				.map(|cff| cff.title.clone())
+				.unwrap_or_default(),
+			row.cff
+				.as_ref()
+				.and_then(|cff| cff.version.clone())
+				.unwrap_or_default(),
+			row.cff
+				.as_ref()
+				.and_then(|cff| cff.doi.clone())
+				.unwrap_or_default(),
+			row.error.clone().unwrap_or_default(),
+			row.count(Severity::Error).to_string(),
+			row.count(Severity::Warning).to_string(),
+			row.count(Severity::Info).to_string(),
+			rules.join(" "),
+		];
+		let fields: Vec<&str> = fields.iter().map(String::as_str).collect();
+		write_row(&mut csv, &fields, Delimiter::Csv, true);
+	}
+	csv
+}
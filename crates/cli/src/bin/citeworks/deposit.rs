@@ -0,0 +1,104 @@
+use std::{fs::read_to_string, path::Path, path::PathBuf, str::FromStr};
+
+use citeworks_cff::Cff;
+use citeworks_convert::{
+	changelog::{entry_for_version, latest_entry, truncate},
+	datacite::{cff_to_datacite_json, cff_to_datacite_xml, DataCiteOptions},
+};
+use clap::Parser;
+use miette::{miette, IntoDiagnostic, Result, WrapErr};
+
+/// Print DataCite metadata to deposit a release with, e.g. on Zenodo
+///
+/// With --changelog, the notes of the release are taken from the changelog and
+/// added after the abstract, as Zenodo shows notes. That's the entry for the
+/// file's version, or the latest entry if there's none for it.
+#[derive(Debug, Parser)]
+pub struct DepositArgs {
+	/// CFF file to read
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// Publisher, e.g. Zenodo
+	#[clap(long, value_name = "NAME")]
+	publisher: Option<String>,
+
+	/// Markdown changelog to take the release notes from
+	#[clap(long, value_name = "FILE")]
+	changelog: Option<PathBuf>,
+
+	/// Most characters of release notes to keep
+	#[clap(long, value_name = "N", default_value = "1000")]
+	notes_length: usize,
+
+	/// Output format: json or xml
+	#[clap(long, default_value = "json")]
+	format: DepositFormat,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DepositFormat {
+	Json,
+	Xml,
+}
+
+impl FromStr for DepositFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"json" => Ok(Self::Json),
+			"xml" => Ok(Self::Xml),
+			_ => Err(format!("unknown deposit format: {s:?}")),
+		}
+	}
+}
+
+pub fn run(args: DepositArgs) -> Result<()> {
+	let cff = super::read_cff(&args.path)?;
+	let release_notes = match &args.changelog {
+		Some(path) => Some(release_notes(&cff, path, args.notes_length)?),
+		None => None,
+	};
+
+	print!(
+		"{}",
+		deposit(
+			&cff,
+			DataCiteOptions {
+				publisher: args.publisher,
+				release_notes,
+			},
+			args.format
+		)
+	);
+	Ok(())
+}
+
+/// The DataCite metadata of a document, ending in a newline.
+pub fn deposit(cff: &Cff, options: DataCiteOptions, format: DepositFormat) -> String {
+	match format {
+		DepositFormat::Json => format!("{:#}\n", cff_to_datacite_json(cff, &options)),
+		DepositFormat::Xml => {
+			let mut xml = cff_to_datacite_xml(cff, &options);
+			if !xml.ends_with('\n') {
+				xml.push('\n');
+			}
+			xml
+		}
+	}
+}
+
+/// The notes of a document's release from a changelog, truncated.
+pub fn release_notes(cff: &Cff, changelog: &Path, max_chars: usize) -> Result<String> {
+	let text = read_to_string(changelog)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("reading {}", changelog.display()))?;
+	let entry = cff
+		.version
+		.as_deref()
+		.and_then(|version| entry_for_version(&text, version))
+		.or_else(|| latest_entry(&text))
+		.ok_or_else(|| miette!("{} has no releases", changelog.display()))?;
+	Ok(truncate(&entry.notes, max_chars))
+}
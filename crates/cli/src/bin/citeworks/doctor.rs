@@ -0,0 +1,535 @@
+use std::{
+	cmp::Reverse,
+	collections::BTreeMap,
+	env::var,
+	fs::read_to_string,
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+use citeworks_cff::{
+	from_str_located,
+	i18n::Catalog,
+	names::{EntityName, Name, NameMeta},
+	schema::validate_against_schema,
+	validation::{Rule, Ruleset, Severity},
+	Cff,
+};
+use citeworks_convert::csl::bare_doi;
+use clap::Parser;
+use miette::{bail, IntoDiagnostic, Result, WrapErr};
+use serde_json::json;
+
+use crate::{
+	config::{validation_options, RuleArgs},
+	output::{print_json, OutputFormat},
+};
+
+/// Diagnose problems with a CFF file, and suggest fixes
+///
+/// This runs all validation rules, including the GitHub ruleset, checks the
+/// file against the CFF 1.2.0 JSON Schema, looks for text damaged by encoding
+/// problems, and checks for things that break GitHub's "Cite this repository"
+/// box.
+/// Problems are listed most serious first.
+#[derive(Debug, Parser)]
+pub struct DoctorArgs {
+	/// CFF file to read
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// Also check this ruleset, e.g. zenodo (can be given multiple times)
+	#[clap(long, value_name = "RULESET")]
+	ruleset: Vec<Ruleset>,
+
+	/// Check the file as staged in the git index, if it has staged changes
+	///
+	/// This is what the hook installed by `citeworks hook install` runs.
+	#[clap(long)]
+	staged: bool,
+
+	/// Config file with the severity of rules, defaults to .citeworks.yml
+	#[clap(long, value_name = "FILE")]
+	config: Option<PathBuf>,
+
+	#[clap(flatten)]
+	rules: RuleArgs,
+
+	/// Output format: text or json
+	#[clap(long, default_value = "text")]
+	format: OutputFormat,
+
+	/// Language of validation messages, e.g. en or fr_FR
+	///
+	/// Defaults to the LANG environment variable. Messages are in English for
+	/// languages without a catalog.
+	#[clap(long, value_name = "LOCALE")]
+	locale: Option<String>,
+}
+
+/// A problem, and what to do about it.
+struct Finding {
+	severity: Severity,
+	rule: Option<Rule>,
+	path: String,
+	message: String,
+	suggestion: Option<String>,
+}
+
+impl Finding {
+	fn new(severity: Severity, path: impl Into<String>, message: impl Into<String>) -> Self {
+		Self {
+			severity,
+			rule: None,
+			path: path.into(),
+			message: message.into(),
+			suggestion: None,
+		}
+	}
+
+	fn suggest(mut self, suggestion: impl Into<String>) -> Self {
+		self.suggestion = Some(suggestion.into());
+		self
+	}
+}
+
+pub fn run(args: DoctorArgs) -> Result<()> {
+	let text = if args.staged {
+		match staged(&args.path)? {
+			Some(text) => text,
+			None => return Ok(()),
+		}
+	} else {
+		read_to_string(&args.path)
+			.into_diagnostic()
+			.wrap_err_with(|| format!("reading {}", args.path.display()))?
+	};
+
+	let config = args
+		.rules
+		.apply(validation_options(args.config.as_deref())?);
+	let mut findings = location(&args.path);
+	if text.starts_with('\u{feff}') {
+		findings.push(
+			Finding::new(
+				Severity::Warning,
+				"",
+				"file starts with a byte order mark, which some parsers choke on",
+			)
+			.suggest("save the file as UTF-8 without BOM"),
+		);
+	}
+
+	let text = text.trim_start_matches('\u{feff}');
+	match from_str_located(text) {
+		Ok(cff) => {
+			let rejected = rejected_keys(&cff);
+			findings.extend(github(&cff, &rejected));
+			findings.extend(schema(text, &rejected));
+			let mut options = config.ruleset(Ruleset::Github);
+			options.rulesets.extend(args.ruleset.iter().copied());
+			let locale = args
+				.locale
+				.clone()
+				.or_else(|| var("LANG").ok())
+				.unwrap_or_default();
+			let catalog = Catalog::for_locale(&locale);
+			findings.extend(cff.validate_with(&options).into_iter().map(|issue| Finding {
+				severity: issue.severity,
+				rule: Some(issue.rule),
+				message: catalog.message(&issue),
+				suggestion: suggestion(issue.rule, &issue.args),
+				path: issue.path,
+			}));
+			findings.extend(cff.mojibake().into_iter().map(|suspect| {
+				let finding = Finding::new(
+					Severity::Warning,
+					suspect.path,
+					format!("{:?} looks damaged by an encoding problem", suspect.value),
+				);
+				match suspect.suggestion {
+					Some(repaired) => finding.suggest(format!("replace with {repaired:?}")),
+					None => finding.suggest("retype the missing characters"),
+				}
+			}));
+		}
+		Err(err) => findings.push(
+			Finding::new(Severity::Error, "", format!("file doesn't parse: {err}"))
+				.suggest("fix the syntax, e.g. at https://citation-file-format.github.io/cff-initializer-javascript/"),
+		),
+	}
+
+	// Stable, so findings of the same severity stay in document order.
+	findings.sort_by_key(|finding| Reverse(finding.severity));
+	let count = |severity| findings.iter().filter(|f| f.severity == severity).count();
+	let (errors, warnings, infos) = (
+		count(Severity::Error),
+		count(Severity::Warning),
+		count(Severity::Info),
+	);
+
+	match args.format {
+		OutputFormat::Text => {
+			for finding in &findings {
+				print_finding(finding);
+			}
+			if findings.is_empty() {
+				println!("{} looks good", args.path.display());
+			}
+		}
+		OutputFormat::Json => print_json(
+			"doctor",
+			json!({
+				"path": args.path,
+				"findings": findings.iter().map(|finding| json!({
+					"severity": finding.severity.to_string(),
+					"rule": finding.rule.map(Rule::name),
+					"code": finding.rule.map(Rule::code),
+					"path": finding.path,
+					"message": finding.message,
+					"suggestion": finding.suggestion,
+				})).collect::<Vec<_>>(),
+				"summary": {
+					"errors": errors,
+					"warnings": warnings,
+					"infos": infos,
+				},
+			}),
+		),
+	}
+
+	if errors > 0 {
+		bail!(
+			"found {} error(s), {} warning(s), and {} info(s) in {}",
+			errors,
+			warnings,
+			infos,
+			args.path.display()
+		);
+	}
+
+	Ok(())
+}
+
+/// What to do about some validation issues.
+fn suggestion(rule: Rule, args: &BTreeMap<String, String>) -> Option<String> {
+	match rule {
+		Rule::AuthorsEmpty => Some("add an author, or `- name: anonymous`".into()),
+		Rule::RepositoryField => Some(format!("move it to {}", args.get("field")?)),
+		Rule::DoiFormat => {
+			let doi = args.get("doi")?;
+			let bare = bare_doi(doi);
+			(bare != doi).then(|| format!("use {bare}"))
+		}
+		_ => None,
+	}
+}
+
+fn print_finding(finding: &Finding) {
+	let rule = finding
+		.rule
+		.map(|rule| format!(" [{} {rule}]", rule.code()))
+		.unwrap_or_default();
+	if finding.path.is_empty() {
+		println!("{}: {}{rule}", finding.severity, finding.message);
+	} else {
+		println!(
+			"{}: {}: {}{rule}",
+			finding.severity, finding.path, finding.message
+		);
+	}
+	if let Some(suggestion) = &finding.suggestion {
+		println!("  suggestion: {suggestion}");
+	}
+}
+
+/// The contents of a file in the git index, if it has staged changes.
+fn staged(path: &Path) -> Result<Option<String>> {
+	let status = Command::new("git")
+		.args(["diff", "--cached", "--quiet", "--"])
+		.arg(path)
+		.status()
+		.into_diagnostic()
+		.wrap_err("running git")?;
+	match status.code() {
+		Some(0) => return Ok(None),
+		Some(1) => {}
+		_ => bail!("git diff --cached failed: {}", status),
+	}
+
+	let output = Command::new("git")
+		.arg("show")
+		.arg(format!(":./{}", path.display()))
+		.output()
+		.into_diagnostic()
+		.wrap_err("running git")?;
+	if !output.status.success() {
+		// Staged for deletion.
+		return Ok(None);
+	}
+	String::from_utf8(output.stdout)
+		.map(Some)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("reading staged {}", path.display()))
+}
+
+/// GitHub only reads CITATION.cff at the root of a repository.
+fn location(path: &Path) -> Vec<Finding> {
+	let mut findings = Vec::new();
+	if path.file_name().map_or(true, |name| name != "CITATION.cff") {
+		findings.push(
+			Finding::new(
+				Severity::Warning,
+				"",
+				"GitHub only looks for files named CITATION.cff",
+			)
+			.suggest("rename the file to CITATION.cff"),
+		);
+	}
+
+	let dir = match path
+		.canonicalize()
+		.ok()
+		.and_then(|p| p.parent().map(Path::to_path_buf))
+	{
+		Some(dir) => dir,
+		None => return findings,
+	};
+	if let Some(root) = dir.ancestors().find(|dir| dir.join(".git").exists()) {
+		if root != dir {
+			findings.push(
+				Finding::new(
+					Severity::Warning,
+					"",
+					"GitHub only reads the CITATION.cff at the root of the repository",
+				)
+				.suggest(format!("move the file to {}", root.display())),
+			);
+		}
+	}
+	findings
+}
+
+/// Things GitHub's parser rejects, which make the "Cite this repository" box
+/// show an error instead.
+fn github(cff: &Cff, rejected: &[RejectedKey]) -> Vec<Finding> {
+	let mut findings = Vec::new();
+
+	if cff.cff_version.to_string() != "1.2.0" {
+		findings.push(
+			Finding::new(
+				Severity::Warning,
+				"cff-version",
+				format!(
+					"GitHub validates against CFF 1.2.0, not {}",
+					cff.cff_version
+				),
+			)
+			.suggest("set cff-version: 1.2.0"),
+		);
+	}
+
+	for key in rejected {
+		findings.push(if key.extension {
+			Finding::new(
+				Severity::Error,
+				&key.path,
+				"extension key: GitHub rejects files with keys that aren't in the schema",
+			)
+			.suggest("remove it from the CITATION.cff GitHub reads")
+		} else {
+			Finding::new(
+				Severity::Error,
+				&key.path,
+				"unknown key: GitHub rejects files with keys that aren't in the schema",
+			)
+			.suggest("check the spelling against the CFF 1.2.0 schema, or remove it")
+		});
+	}
+
+	findings
+}
+
+/// A key which isn't in the CFF 1.2.0 schema.
+struct RejectedKey {
+	path: String,
+
+	/// Whether this is an extension this library reads, like `funding` or
+	/// `ror`, rather than an unknown key.
+	extension: bool,
+}
+
+impl RejectedKey {
+	fn unknown(path: String) -> Self {
+		Self {
+			path,
+			extension: false,
+		}
+	}
+
+	fn extension(path: impl Into<String>) -> Self {
+		Self {
+			path: path.into(),
+			extension: true,
+		}
+	}
+}
+
+/// Keys which aren't in the CFF 1.2.0 schema, in document order.
+fn rejected_keys(cff: &Cff) -> Vec<RejectedKey> {
+	let mut keys: Vec<RejectedKey> = cff
+		.extra
+		.keys()
+		.cloned()
+		.map(RejectedKey::unknown)
+		.collect();
+	if cff.available_date.is_some() {
+		keys.push(RejectedKey::extension("available-date"));
+	}
+	if cff.embargo_period.is_some() {
+		keys.push(RejectedKey::extension("embargo-period"));
+	}
+	if !cff.funding.is_empty() {
+		keys.push(RejectedKey::extension("funding"));
+	}
+	name_keys(&mut keys, "authors", &cff.authors);
+	name_keys(&mut keys, "contact", &cff.contact);
+
+	let references = cff
+		.preferred_citation
+		.iter()
+		.map(|reference| ("preferred-citation".to_string(), reference))
+		.chain(
+			cff.references
+				.iter()
+				.enumerate()
+				.map(|(n, reference)| (format!("references[{n}]"), reference)),
+		);
+	for (path, reference) in references {
+		keys.extend(
+			reference
+				.extra
+				.keys()
+				.map(|key| RejectedKey::unknown(format!("{path}.{key}"))),
+		);
+		for (field, names) in [
+			("authors", &reference.authors),
+			("contact", &reference.contact),
+			("editors", &reference.editors),
+			("editors-series", &reference.editors_series),
+			("recipients", &reference.recipients),
+			("senders", &reference.senders),
+			("translators", &reference.translators),
+		] {
+			name_keys(&mut keys, &format!("{path}.{field}"), names);
+		}
+		for (field, entity) in [
+			("conference", &reference.conference),
+			("database-provider", &reference.database_provider),
+			("institution", &reference.institution),
+			("location", &reference.location),
+			("publisher", &reference.publisher),
+		] {
+			if let Some(EntityName { meta, .. }) = entity {
+				meta_keys(&mut keys, &format!("{path}.{field}"), meta);
+			}
+		}
+	}
+
+	keys
+}
+
+fn name_keys(keys: &mut Vec<RejectedKey>, path: &str, names: &[Name]) {
+	for (n, name) in names.iter().enumerate() {
+		let meta = match name {
+			Name::Person(person) => &person.meta,
+			Name::Entity(entity) => &entity.meta,
+			Name::Anonymous => continue,
+		};
+		meta_keys(keys, &format!("{path}[{n}]"), meta);
+	}
+}
+
+fn meta_keys(keys: &mut Vec<RejectedKey>, path: &str, meta: &NameMeta) {
+	if meta.ror.is_some() {
+		keys.push(RejectedKey::extension(format!("{path}.ror")));
+	}
+}
+
+/// Violations of the CFF 1.2.0 schema.
+///
+/// Keys already reported by [github] are left out, as the schema reports them
+/// again on their parent.
+fn schema(text: &str, rejected: &[RejectedKey]) -> Vec<Finding> {
+	let violations = match validate_against_schema(text) {
+		Ok(violations) => violations,
+		Err(err) => {
+			return vec![Finding::new(
+				Severity::Error,
+				"",
+				format!("couldn't check against the schema: {err}"),
+			)]
+		}
+	};
+
+	violations
+		.into_iter()
+		.map(|violation| (dotted(&violation.path), violation))
+		.filter(|(path, violation)| !reported(path, &violation.message, rejected))
+		.map(|(path, violation)| {
+			Finding::new(
+				Severity::Error,
+				path,
+				format!(
+					"doesn't match the CFF 1.2.0 schema (line {}, column {}): {}",
+					violation.line, violation.column, violation.message
+				),
+			)
+			.suggest("run `citeworks validate` to check against the schema")
+		})
+		.collect()
+}
+
+/// Whether a violation is about keys which were already reported.
+///
+/// The schema reports unexpected keys as additional properties of their
+/// parent, or for names, as the name matching none of the kinds of name.
+fn reported(path: &str, message: &str, rejected: &[RejectedKey]) -> bool {
+	let child = |key: &str| {
+		let key = if path.is_empty() {
+			key.to_string()
+		} else {
+			format!("{path}.{key}")
+		};
+		rejected.iter().any(|rejected| rejected.path == key)
+	};
+
+	if let Some(rest) = message.strip_prefix("Additional properties are not allowed (") {
+		rest.split('\'').skip(1).step_by(2).all(child)
+	} else if message
+		.ends_with("is not valid under any of the schemas listed in the 'anyOf' keyword")
+	{
+		let prefix = format!("{path}.");
+		rejected.iter().any(
+			|rejected| matches!(rejected.path.strip_prefix(&prefix), Some(key) if !key.contains(['.', '['])),
+		)
+	} else {
+		false
+	}
+}
+
+/// A JSON Pointer like `/references/0/authors` as `references[0].authors`.
+fn dotted(pointer: &str) -> String {
+	let mut path = String::new();
+	for segment in pointer.split('/').skip(1) {
+		if segment.parse::<usize>().is_ok() {
+			path.push_str(&format!("[{segment}]"));
+		} else {
+			if !path.is_empty() {
+				path.push('.');
+			}
+			path.push_str(&segment.replace("~1", "/").replace("~0", "~"));
+		}
+	}
+	path
+}
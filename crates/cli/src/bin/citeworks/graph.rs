@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+use citeworks_convert::graph::{Graph, GraphFormat};
+use clap::Parser;
+use miette::Result;
+
+use crate::read_cff;
+
+/// Print a graph of a work, its preferred citation, and its references
+#[derive(Debug, Parser)]
+pub struct GraphArgs {
+	/// CFF file to read
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// Output format: dot (for Graphviz) or json
+	#[clap(long, default_value = "dot")]
+	format: GraphFormat,
+}
+
+pub fn run(args: GraphArgs) -> Result<()> {
+	let cff = read_cff(&args.path)?;
+	print!("{}", Graph::from_cff(&cff).render(args.format));
+	if args.format == GraphFormat::Json {
+		println!();
+	}
+	Ok(())
+}
@@ -0,0 +1,98 @@
+use std::{
+	fs::{create_dir_all, read_to_string, write},
+	path::PathBuf,
+	process::Command,
+};
+
+use clap::{Parser, Subcommand};
+use miette::{bail, miette, IntoDiagnostic, Result, WrapErr};
+
+/// Marks hooks written by citeworks, which it may overwrite.
+const MARKER: &str = "# Installed by citeworks";
+
+/// Manage the git hook that checks CITATION.cff before commits
+#[derive(Debug, Parser)]
+pub struct HookArgs {
+	#[clap(subcommand)]
+	command: HookCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum HookCommand {
+	Install(InstallArgs),
+}
+
+/// Install a pre-commit hook running `citeworks doctor --staged`
+///
+/// The commit is refused if the staged CFF file has errors.
+#[derive(Debug, Parser)]
+struct InstallArgs {
+	/// CFF file to check
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// Replace an existing pre-commit hook not installed by citeworks
+	#[clap(long)]
+	force: bool,
+}
+
+pub fn run(args: HookArgs) -> Result<()> {
+	match args.command {
+		HookCommand::Install(args) => install(args),
+	}
+}
+
+fn install(args: InstallArgs) -> Result<()> {
+	// This follows core.hooksPath if it's set.
+	let hooks = PathBuf::from(git(&["rev-parse", "--git-path", "hooks"])?);
+	let hook = hooks.join("pre-commit");
+
+	// Hooks run at the root of the repository.
+	let path = PathBuf::from(git(&["rev-parse", "--show-prefix"])?).join(&args.path);
+
+	if let Ok(existing) = read_to_string(&hook) {
+		if !existing.contains(MARKER) && !args.force {
+			return Err(miette!(
+				"{} already exists: add `citeworks doctor --staged` to it, or use --force to replace it",
+				hook.display()
+			));
+		}
+	}
+
+	let path = path.display().to_string().replace('\'', "'\\''");
+	create_dir_all(&hooks)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("creating {}", hooks.display()))?;
+	write(
+		&hook,
+		format!("#!/bin/sh\n{MARKER}: checks {path} before committing.\nexec citeworks doctor --staged '{path}'\n"),
+	)
+	.into_diagnostic()
+	.wrap_err_with(|| format!("writing {}", hook.display()))?;
+
+	#[cfg(unix)]
+	{
+		use std::{fs::set_permissions, os::unix::fs::PermissionsExt};
+		set_permissions(&hook, PermissionsExt::from_mode(0o755))
+			.into_diagnostic()
+			.wrap_err_with(|| format!("making {} executable", hook.display()))?;
+	}
+
+	println!("installed {}", hook.display());
+	Ok(())
+}
+
+fn git(args: &[&str]) -> Result<String> {
+	let output = Command::new("git")
+		.args(args)
+		.output()
+		.into_diagnostic()
+		.wrap_err("running git")?;
+	if !output.status.success() {
+		bail!(
+			"not in a git repository: {}",
+			String::from_utf8_lossy(&output.stderr).trim()
+		);
+	}
+	Ok(String::from_utf8_lossy(&output.stdout).trim().into())
+}
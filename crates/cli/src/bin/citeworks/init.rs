@@ -0,0 +1,279 @@
+use std::{
+	collections::BTreeMap,
+	env::current_dir,
+	fs::{read_to_string, write},
+	path::PathBuf,
+	process::Command,
+};
+
+use citeworks_cff::{
+	names::{EntityName, Name},
+	template::Template,
+	Cff, Date,
+};
+use citeworks_convert::{
+	cargo::{cargo_to_cff, update_cff_from_cargo},
+	datacite::DataCiteOptions,
+};
+use citeworks_fetch::crates_io::CratesIo;
+use clap::Parser;
+use miette::{miette, IntoDiagnostic, Result, WrapErr};
+
+use crate::deposit::{deposit, release_notes, DepositFormat};
+
+/// Used when no template is given.
+const DEFAULT_TEMPLATE: &str = r#"cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: "{{title}}"
+authors:
+  - name: "{{author}}"
+version: "{{version}}"
+date-released: {{date}}
+commit: {{git_commit}}
+"#;
+
+/// Write a CITATION.cff file from a template
+///
+/// Templates are CFF files with placeholders like {{version}}, which are filled
+/// in from the repository and --set values. These are provided:
+///
+/// - title: the name of the current directory
+/// - author: the git user.name
+/// - version: the latest git tag, without a leading v
+/// - date: today
+/// - git_commit: the current git commit
+///
+/// Without a template, a minimal file using all of them is written.
+///
+/// With --from-cargo, the file is instead made from the package metadata in
+/// Cargo.toml, or if it already exists, its version is updated, and its
+/// date-released and commit set to today and the current commit if the version
+/// changed. With --deposit, DataCite metadata for depositing the release is also
+/// written, with release notes from --changelog, as the `deposit` command does.
+///
+/// With --from-crates-io, the file is made from the metadata of a crate published
+/// on crates.io, with its owners as authors.
+#[derive(Debug, Parser)]
+pub struct InitArgs {
+	/// File to write, or - for stdout
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// Template CFF file
+	#[clap(long, value_name = "FILE", conflicts_with_all = &["from-cargo", "from-crates-io"])]
+	template: Option<PathBuf>,
+
+	/// Set a placeholder, e.g. `version=1.2.3` (can be given multiple times)
+	#[clap(
+		long,
+		value_name = "NAME=VALUE",
+		parse(try_from_str = parse_var),
+		conflicts_with_all = &["from-cargo", "from-crates-io"]
+	)]
+	set: Vec<(String, String)>,
+
+	/// Make the file from Cargo package metadata, or update its version
+	#[clap(long, conflicts_with = "from-crates-io")]
+	from_cargo: bool,
+
+	/// Make the file from the metadata of a crate published on crates.io
+	#[clap(long, value_name = "CRATE")]
+	from_crates_io: Option<String>,
+
+	/// Cargo.toml to read with --from-cargo
+	#[clap(long, value_name = "PATH", default_value = "Cargo.toml")]
+	manifest_path: PathBuf,
+
+	/// With --from-cargo, also write DataCite metadata to this .json or .xml file
+	#[clap(long, value_name = "FILE", requires = "from-cargo")]
+	deposit: Option<PathBuf>,
+
+	/// Changelog to take the release notes of the deposit from
+	#[clap(long, value_name = "FILE", requires = "deposit")]
+	changelog: Option<PathBuf>,
+
+	/// Most characters of release notes to keep in the deposit
+	#[clap(long, value_name = "N", default_value = "1000")]
+	notes_length: usize,
+
+	/// Replace the file if it exists
+	#[clap(long)]
+	force: bool,
+}
+
+fn parse_var(var: &str) -> Result<(String, String), String> {
+	let (name, value) = var
+		.split_once('=')
+		.ok_or_else(|| format!("expected NAME=VALUE, got {var:?}"))?;
+	Ok((name.trim().into(), value.into()))
+}
+
+pub fn run(args: InitArgs) -> Result<()> {
+	if args.from_cargo {
+		return from_cargo(args);
+	}
+	if let Some(name) = &args.from_crates_io {
+		return from_crates_io(&args, name);
+	}
+
+	let template = match &args.template {
+		Some(path) => Template::new(
+			read_to_string(path)
+				.into_diagnostic()
+				.wrap_err_with(|| format!("reading {}", path.display()))?,
+		),
+		None => Template::new(DEFAULT_TEMPLATE),
+	};
+
+	let mut context = BTreeMap::new();
+	for name in template.placeholders() {
+		if let Some(value) = provided(name) {
+			context.insert(name.to_string(), value);
+		}
+	}
+	context.extend(args.set.iter().cloned());
+
+	let text = template
+		.render(&context)
+		.map_err(|err| miette!("{}: set it with --set NAME=VALUE", err))?;
+	citeworks_cff::from_str(&text)
+		.into_diagnostic()
+		.wrap_err("the filled-in template isn't valid CFF")?;
+
+	output(&args, text)
+}
+
+fn from_cargo(args: InitArgs) -> Result<()> {
+	let manifest = read_to_string(&args.manifest_path)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("reading {}", args.manifest_path.display()))?;
+
+	let stdout = args.path.to_str() == Some("-");
+	if !stdout && args.path.exists() && !args.force {
+		let mut cff = super::read_cff(&args.path)?;
+		if update_cff_from_cargo(&mut cff, &manifest, Date::today())
+			.map_err(|err| miette!("{}", err))?
+		{
+			if cff.commit.is_some() {
+				cff.commit = provided("git_commit");
+			}
+			let text = citeworks_cff::to_string_canonical(&cff).into_diagnostic()?;
+			write(&args.path, text)
+				.into_diagnostic()
+				.wrap_err_with(|| format!("writing {}", args.path.display()))?;
+		}
+		return write_deposit(&args, &cff);
+	}
+
+	let mut cff = cargo_to_cff(&manifest, Date::today()).map_err(|err| miette!("{}", err))?;
+	if cff.authors.is_empty() {
+		let author = provided("author").ok_or_else(|| {
+			miette!("the package has no authors, and there's no git user.name to use instead")
+		})?;
+		cff.authors.push(Name::Entity(EntityName {
+			name: Some(author),
+			..Default::default()
+		}));
+	}
+	if cff.commit.is_none() {
+		cff.commit = provided("git_commit");
+	}
+
+	let text = citeworks_cff::to_string_canonical(&cff).into_diagnostic()?;
+	output(&args, text)?;
+	write_deposit(&args, &cff)
+}
+
+/// Write the deposit metadata of a document, if --deposit was given.
+fn write_deposit(args: &InitArgs, cff: &Cff) -> Result<()> {
+	let path = match &args.deposit {
+		Some(path) => path,
+		None => return Ok(()),
+	};
+
+	let release_notes = match &args.changelog {
+		Some(changelog) => Some(release_notes(cff, changelog, args.notes_length)?),
+		None => None,
+	};
+	let format = if path.extension().map_or(false, |ext| ext == "xml") {
+		DepositFormat::Xml
+	} else {
+		DepositFormat::Json
+	};
+
+	let text = deposit(
+		cff,
+		DataCiteOptions {
+			release_notes,
+			..Default::default()
+		},
+		format,
+	);
+	write(path, text)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("writing {}", path.display()))
+}
+
+fn from_crates_io(args: &InitArgs, name: &str) -> Result<()> {
+	let mut cff = CratesIo::new()
+		.starter_cff(name)
+		.map_err(|err| miette!("{}", err))
+		.wrap_err_with(|| format!("looking up {name} on crates.io"))?
+		.ok_or_else(|| miette!("there's no crate named {name} on crates.io"))?;
+
+	if cff.authors.is_empty() {
+		let author = provided("author").ok_or_else(|| {
+			miette!("the crate has no owners, and there's no git user.name to use instead")
+		})?;
+		cff.authors.push(Name::Entity(EntityName {
+			name: Some(author),
+			..Default::default()
+		}));
+	}
+
+	let text = citeworks_cff::to_string_canonical(&cff).into_diagnostic()?;
+	output(args, text)
+}
+
+fn output(args: &InitArgs, text: String) -> Result<()> {
+	if args.path.to_str() == Some("-") {
+		print!("{text}");
+		return Ok(());
+	}
+
+	if args.path.exists() && !args.force {
+		return Err(miette!(
+			"{} already exists: use --force to replace it",
+			args.path.display()
+		));
+	}
+	write(&args.path, text)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("writing {}", args.path.display()))
+}
+
+/// Values of the placeholders that are provided by default.
+pub(crate) fn provided(name: &str) -> Option<String> {
+	match name {
+		"title" => current_dir()
+			.ok()?
+			.file_name()
+			.map(|name| name.to_string_lossy().into_owned()),
+		"author" => git(&["config", "user.name"]),
+		"version" => git(&["describe", "--tags", "--abbrev=0"])
+			.map(|tag| tag.trim_start_matches('v').to_string()),
+		"date" => Some(Date::today().to_string()),
+		"git_commit" => git(&["rev-parse", "HEAD"]),
+		_ => None,
+	}
+}
+
+fn git(args: &[&str]) -> Option<String> {
+	let output = Command::new("git").args(args).output().ok()?;
+	if !output.status.success() {
+		return None;
+	}
+	let out = String::from_utf8(output.stdout).ok()?;
+	let out = out.trim();
+	(!out.is_empty()).then(|| out.to_string())
+}
@@ -0,0 +1,130 @@
+use std::{
+	fs::{read_to_string, File},
+	path::Path,
+};
+
+use citeworks_cff::{from_str_located, Cff};
+use citeworks_convert::{
+	csl::cff_to_items_with,
+	keys::{fill_missing_keys, KeyGenerator},
+	options::ConvertOptions,
+	report::IgnoreWarnings,
+	stash::restore,
+};
+use citeworks_csl::Item;
+use clap::{Parser, Subcommand};
+use miette::{miette, IntoDiagnostic, NamedSource, Report, Result, WrapErr};
+
+mod badge;
+mod bibliography;
+mod check_links;
+mod cite;
+mod config;
+mod crawl;
+mod deposit;
+mod doctor;
+mod graph;
+mod hook;
+mod init;
+mod output;
+mod pandoc_filter;
+mod rules;
+mod sign;
+mod table;
+mod validate;
+mod verify_doi;
+mod verify_signature;
+mod workspace;
+
+#[derive(Debug, Parser)]
+#[clap(author, about, version)]
+struct Args {
+	#[clap(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	Badge(badge::BadgeArgs),
+	Bibliography(bibliography::BibliographyArgs),
+	CheckLinks(check_links::CheckLinksArgs),
+	Cite(cite::CiteArgs),
+	Crawl(crawl::CrawlArgs),
+	Deposit(deposit::DepositArgs),
+	Doctor(doctor::DoctorArgs),
+	Graph(graph::GraphArgs),
+	Hook(hook::HookArgs),
+	Init(init::InitArgs),
+	PandocFilter(pandoc_filter::PandocFilterArgs),
+	Rules(rules::RulesArgs),
+	Sign(sign::SignArgs),
+	Table(table::TableArgs),
+	Validate(validate::ValidateArgs),
+	VerifyDoi(verify_doi::VerifyDoiArgs),
+	VerifySignature(verify_signature::VerifySignatureArgs),
+	Workspace(workspace::WorkspaceArgs),
+}
+
+fn main() -> Result<()> {
+	let args = Args::parse();
+
+	match args.command {
+		Command::Badge(args) => badge::run(args),
+		Command::Bibliography(args) => bibliography::run(args),
+		Command::CheckLinks(args) => check_links::run(args),
+		Command::Cite(args) => cite::run(args),
+		Command::Crawl(args) => crawl::run(args),
+		Command::Deposit(args) => deposit::run(args),
+		Command::Doctor(args) => doctor::run(args),
+		Command::Graph(args) => graph::run(args),
+		Command::Hook(args) => hook::run(args),
+		Command::Init(args) => init::run(args),
+		Command::PandocFilter(args) => pandoc_filter::run(args),
+		Command::Rules(args) => rules::run(args),
+		Command::Sign(args) => sign::run(args),
+		Command::Table(args) => table::run(args),
+		Command::Validate(args) => validate::run(args),
+		Command::VerifyDoi(args) => verify_doi::run(args),
+		Command::VerifySignature(args) => verify_signature::run(args),
+		Command::Workspace(args) => workspace::run(args),
+	}
+}
+
+/// Read a CFF file, with parse errors shown at the offending key.
+fn read_cff(path: &Path) -> Result<Cff> {
+	let text = read_to_string(path)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("opening {}", path.display()))?;
+	from_str_located(&text).map_err(|err| {
+		Report::new(err)
+			.with_source_code(NamedSource::new(path.display().to_string(), text.clone()))
+	})
+}
+
+/// Read a CFF file as CSL items, or a CSL-JSON file.
+///
+/// Items without ids are given keys from the generator, and CSL variables
+/// stashed by `csl2cff --lossless` are restored.
+fn read_library(
+	path: &Path,
+	options: &ConvertOptions,
+	keys: &mut KeyGenerator,
+) -> Result<Vec<Item>> {
+	if path.extension().map_or(false, |ext| ext == "cff") {
+		// What's lost doesn't matter for citing, so the report isn't shown.
+		let mut items = cff_to_items_with(&read_cff(path)?, options, keys, &mut IgnoreWarnings);
+		for item in &mut items {
+			restore(item).map_err(|err| miette!("reading {}: {}", path.display(), err))?;
+		}
+		return Ok(items);
+	}
+
+	let file = File::open(path)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("opening {}", path.display()))?;
+	let mut items = citeworks_csl::from_reader(file)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("parsing {}", path.display()))?;
+	fill_missing_keys(&mut items, keys);
+	Ok(items)
+}
@@ -0,0 +1,44 @@
+use std::str::FromStr;
+
+use serde_json::Value;
+
+/// Version of the JSON output of commands, incremented on breaking changes.
+///
+/// The schemas are documented in the README.
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// How commands print their results.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OutputFormat {
+	/// For humans.
+	Text,
+
+	/// For other tools.
+	Json,
+}
+
+impl FromStr for OutputFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"text" => Ok(Self::Text),
+			"json" => Ok(Self::Json),
+			_ => Err(format!("unknown output format: {s:?}")),
+		}
+	}
+}
+
+/// Add the schema version and command name to a command's JSON output.
+pub fn envelope(command: &str, mut output: Value) -> Value {
+	if let Value::Object(map) = &mut output {
+		map.insert("schema".into(), SCHEMA_VERSION.into());
+		map.insert("command".into(), command.into());
+	}
+	output
+}
+
+/// Print a command's JSON output, with the schema version and command name.
+pub fn print_json(command: &str, output: Value) {
+	println!("{:#}", envelope(command, output));
+}
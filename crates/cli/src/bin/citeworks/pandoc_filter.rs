@@ -0,0 +1,79 @@
+use std::{
+	io::{stdin, stdout},
+	path::PathBuf,
+};
+
+use citeworks_convert::{
+	keys::{KeyGenerator, KeyPattern},
+	options::ConvertOptions,
+	pandoc::{bibliography_paths, filter},
+};
+use clap::Parser;
+use miette::{IntoDiagnostic, Result, WrapErr};
+
+use crate::read_library;
+
+/// Resolve citations in a pandoc JSON document and add a bibliography
+///
+/// Reads the document from STDIN and writes the result to STDOUT:
+///
+///     pandoc -t json doc.md | citeworks pandoc-filter | pandoc -f json -o doc.html
+#[derive(Debug, Parser)]
+#[clap(verbatim_doc_comment)]
+pub struct PandocFilterArgs {
+	/// CFF or CSL-JSON files to resolve citations against
+	///
+	/// Defaults to the files in the `bibliography` metadata of the document.
+	#[clap(long, value_name = "FILE")]
+	library: Vec<PathBuf>,
+
+	/// Pattern for the keys of CFF references and items without ids
+	///
+	/// Fields are `[auth]`, `[authors]`, `[authEtAl]`, `[year]`, `[shortyear]`,
+	/// `[titleword]`, and `[shorttitle]`, optionally followed by `:lower` or
+	/// `:upper`.
+	#[clap(long, value_name = "PATTERN", default_value = "[auth:lower][year]")]
+	key_pattern: KeyPattern,
+
+	/// Cite the `preferred-citation` of CFF files instead of the work itself
+	///
+	/// The preferred citation gets the key, and the work is the next item.
+	#[clap(long)]
+	preferred_citation: bool,
+
+	/// Output format, as passed by pandoc to filters (ignored)
+	#[clap(hide = true)]
+	format: Option<String>,
+}
+
+pub fn run(args: PandocFilterArgs) -> Result<()> {
+	let mut doc: serde_json::Value = serde_json::from_reader(stdin())
+		.into_diagnostic()
+		.wrap_err("reading pandoc JSON from STDIN")?;
+
+	let paths = if args.library.is_empty() {
+		bibliography_paths(&doc)
+			.into_iter()
+			.map(PathBuf::from)
+			.collect()
+	} else {
+		args.library
+	};
+
+	let options = ConvertOptions {
+		preferred_citation: args.preferred_citation,
+		..Default::default()
+	};
+	let mut keys = KeyGenerator::new(args.key_pattern);
+	let mut library = Vec::new();
+	for path in paths {
+		library.extend(read_library(&path, &options, &mut keys)?);
+	}
+
+	let report = filter(&mut doc, &library);
+	for key in report.unresolved {
+		eprintln!("WARNING: citation not found: @{key}");
+	}
+
+	serde_json::to_writer(stdout(), &doc).into_diagnostic()
+}
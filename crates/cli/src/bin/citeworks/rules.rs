@@ -0,0 +1,49 @@
+use citeworks_cff::validation::Rule;
+use clap::Parser;
+use serde_json::json;
+
+use crate::output::{print_json, OutputFormat};
+
+/// List the validation rules, with their codes and default severities
+///
+/// Rules are given by code or name to --allow and --deny, and in the config
+/// file. Rules in a ruleset are only checked when it is, e.g. with --ruleset.
+#[derive(Debug, Parser)]
+pub struct RulesArgs {
+	/// Output format: text or json
+	#[clap(long, default_value = "text")]
+	format: OutputFormat,
+}
+
+pub fn run(args: RulesArgs) -> miette::Result<()> {
+	match args.format {
+		OutputFormat::Text => {
+			for rule in Rule::ALL {
+				let ruleset = rule
+					.ruleset()
+					.map(|ruleset| format!(", in {ruleset}"))
+					.unwrap_or_default();
+				println!(
+					"{} {} ({}{ruleset})",
+					rule.code(),
+					rule.name(),
+					rule.default_severity()
+				);
+				println!("  {}", rule.description());
+			}
+		}
+		OutputFormat::Json => print_json(
+			"rules",
+			json!({
+				"rules": Rule::ALL.iter().map(|rule| json!({
+					"code": rule.code(),
+					"name": rule.name(),
+					"severity": rule.default_severity().to_string(),
+					"ruleset": rule.ruleset().map(|ruleset| ruleset.to_string()),
+					"description": rule.description(),
+				})).collect::<Vec<_>>(),
+			}),
+		),
+	}
+	Ok(())
+}
@@ -0,0 +1,181 @@
+use std::{
+	fs::{read, write, OpenOptions},
+	io::Write,
+	path::{Path, PathBuf},
+	process::{Command, Output, Stdio},
+	str::FromStr,
+};
+
+use citeworks_cff::{signature::NAMESPACE, Cff};
+use clap::Parser;
+use miette::{bail, IntoDiagnostic, Result, WrapErr};
+use tempfile::TempDir;
+
+/// Sign a CFF file with ssh-keygen or minisign, in a detached signature
+///
+/// The signature is over the canonical text of the file, which stays the same
+/// if a mirror reformats it, so it can be checked with `verify-signature`.
+/// SSH signatures are made in the `citation-file-format` namespace.
+///
+/// The signature is written next to the file, as CITATION.cff.sig for SSH or
+/// CITATION.cff.minisig for minisign.
+#[derive(Debug, Parser)]
+pub struct SignArgs {
+	/// CFF file to sign
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// Private key to sign with
+	#[clap(long, value_name = "FILE")]
+	key: PathBuf,
+
+	/// Signing tool: ssh or minisign
+	#[clap(long, default_value = "ssh")]
+	tool: SignatureTool,
+
+	/// Where to write the signature
+	#[clap(long, value_name = "FILE")]
+	output: Option<PathBuf>,
+}
+
+/// Which tool makes and checks signatures.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SignatureTool {
+	/// `ssh-keygen -Y`
+	Ssh,
+
+	/// `minisign`
+	Minisign,
+}
+
+impl SignatureTool {
+	/// Where the tool's signature of a file goes by default.
+	pub fn signature_path(self, path: &Path) -> PathBuf {
+		let mut name = path.as_os_str().to_owned();
+		name.push(match self {
+			Self::Ssh => ".sig",
+			Self::Minisign => ".minisig",
+		});
+		name.into()
+	}
+}
+
+impl FromStr for SignatureTool {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"ssh" => Ok(Self::Ssh),
+			"minisign" => Ok(Self::Minisign),
+			_ => Err(format!("unknown signature tool: {s:?}")),
+		}
+	}
+}
+
+pub fn run(args: SignArgs) -> Result<()> {
+	let cff = super::read_cff(&args.path)?;
+	let signature = sign(&cff, &args.key, args.tool)?;
+
+	let output = args
+		.output
+		.unwrap_or_else(|| args.tool.signature_path(&args.path));
+	write(&output, signature)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("writing {}", output.display()))?;
+	println!("signed {} in {}", args.path.display(), output.display());
+	Ok(())
+}
+
+/// A detached signature of a document's canonical text.
+pub fn sign(cff: &Cff, key: &Path, tool: SignatureTool) -> Result<Vec<u8>> {
+	let text = cff.canonical_string().into_diagnostic()?;
+	match tool {
+		SignatureTool::Ssh => {
+			let output = run_tool(
+				Command::new("ssh-keygen")
+					.args(["-Y", "sign", "-n", NAMESPACE, "-f"])
+					.arg(key),
+				Some(&text),
+			)?;
+			if !output.status.success() {
+				bail!("ssh-keygen couldn't sign with {}", key.display());
+			}
+			Ok(output.stdout)
+		}
+		SignatureTool::Minisign => {
+			// minisign only signs files, and writes the signature to another.
+			let dir = temp_dir()?;
+			let message = write_message(&dir, &text)?;
+			let signature = dir.path().join("CITATION.cff.minisig");
+			let output = run_tool(
+				Command::new("minisign")
+					.arg("-S")
+					.arg("-s")
+					.arg(key)
+					.arg("-m")
+					.arg(&message)
+					.arg("-x")
+					.arg(&signature),
+				None,
+			)?;
+			if !output.status.success() {
+				bail!("minisign couldn't sign with {}", key.display());
+			}
+			read(&signature)
+				.into_diagnostic()
+				.wrap_err("reading minisign's signature")
+		}
+	}
+}
+
+/// Run a signing tool, and wait for it to exit.
+///
+/// The tool gets the input on its standard input, or without any, the terminal.
+/// Its standard error is shown as it runs, as it may ask for a passphrase.
+pub fn run_tool(command: &mut Command, input: Option<&str>) -> Result<Output> {
+	let program = command.get_program().to_string_lossy().into_owned();
+	let mut child = command
+		.stdin(if input.is_some() {
+			Stdio::piped()
+		} else {
+			Stdio::inherit()
+		})
+		.stdout(Stdio::piped())
+		.stderr(Stdio::inherit())
+		.spawn()
+		.into_diagnostic()
+		.wrap_err_with(|| format!("running {program}"))?;
+	if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+		stdin
+			.write_all(input.as_bytes())
+			.into_diagnostic()
+			.wrap_err_with(|| format!("writing to {program}"))?;
+	}
+
+	child
+		.wait_with_output()
+		.into_diagnostic()
+		.wrap_err_with(|| format!("running {program}"))
+}
+
+/// A private temporary directory, only readable by us and removed when dropped.
+pub fn temp_dir() -> Result<TempDir> {
+	tempfile::Builder::new()
+		.prefix("citeworks-")
+		.tempdir()
+		.into_diagnostic()
+		.wrap_err("creating a temporary directory")
+}
+
+/// Write the text to sign or verify in a new file of the temporary directory.
+pub fn write_message(dir: &TempDir, text: &str) -> Result<PathBuf> {
+	let path = dir.path().join("CITATION.cff");
+	OpenOptions::new()
+		.write(true)
+		.create_new(true)
+		.open(&path)
+		.and_then(|mut file| file.write_all(text.as_bytes()))
+		.into_diagnostic()
+		.wrap_err_with(|| format!("writing {}", path.display()))?;
+	Ok(path)
+}
@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+
+use citeworks_convert::{
+	keys::{KeyGenerator, KeyPattern},
+	options::ConvertOptions,
+	table::{items_to_table, Column, Delimiter, TableOptions},
+};
+use clap::Parser;
+#[cfg(feature = "xlsx")]
+use miette::IntoDiagnostic;
+use miette::Result;
+
+use crate::read_library;
+
+/// Print the items of CFF or CSL-JSON files as a CSV or TSV table
+///
+/// Each item is a row, in the order of the files. Columns are CSL variables,
+/// like `title` or `DOI`, or `year`, `entry` for the bibliography entry, or
+/// `citation` for the author-date citation.
+#[derive(Debug, Parser)]
+pub struct TableArgs {
+	/// CFF or CSL-JSON files
+	#[clap(required = true)]
+	library: Vec<PathBuf>,
+
+	/// Columns, separated by commas
+	///
+	/// Defaults to id, type, author, year, title, container-title, publisher,
+	/// DOI, and URL.
+	#[clap(long, value_name = "COLUMNS", value_delimiter = ',')]
+	columns: Vec<Column>,
+
+	/// Output format: csv or tsv
+	#[clap(long, default_value = "csv")]
+	format: Delimiter,
+
+	/// Don't start with a row of column names
+	#[clap(long)]
+	no_header: bool,
+
+	/// Keep cells starting with =, +, -, or @ as they are
+	///
+	/// By default they're prefixed with a ', so spreadsheets don't run them as
+	/// formulas.
+	#[clap(long)]
+	allow_formulas: bool,
+
+	/// Pattern for the keys of CFF references and items without ids
+	///
+	/// See `citeworks pandoc-filter --help` for the fields.
+	#[clap(long, value_name = "PATTERN", default_value = "[auth:lower][year]")]
+	key_pattern: KeyPattern,
+
+	/// Write an XLSX workbook to this file instead, with typed cells and linked DOIs
+	#[cfg(feature = "xlsx")]
+	#[clap(long, value_name = "FILE")]
+	xlsx: Option<PathBuf>,
+}
+
+pub fn run(args: TableArgs) -> Result<()> {
+	let mut keys = KeyGenerator::new(args.key_pattern.clone());
+	let mut items = Vec::new();
+	for path in &args.library {
+		items.extend(read_library(path, &ConvertOptions::default(), &mut keys)?);
+	}
+
+	let mut options = TableOptions {
+		delimiter: args.format,
+		header: !args.no_header,
+		escape_formulas: !args.allow_formulas,
+		..Default::default()
+	};
+	if !args.columns.is_empty() {
+		options.columns = args.columns;
+	}
+
+	#[cfg(feature = "xlsx")]
+	if let Some(path) = args.xlsx {
+		let xlsx = citeworks_convert::table::items_to_xlsx(&items, &options)
+			.map_err(|err| miette::miette!("writing XLSX: {}", err))?;
+		return std::fs::write(path, xlsx).into_diagnostic();
+	}
+
+	print!("{}", items_to_table(&items, &options));
+	Ok(())
+}
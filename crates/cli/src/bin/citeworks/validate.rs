@@ -0,0 +1,63 @@
+use std::{fs::read_to_string, path::PathBuf};
+
+use citeworks_cff::schema::validate_against_schema;
+use clap::Parser;
+use miette::{bail, miette, IntoDiagnostic, Result, WrapErr};
+use serde_json::json;
+
+use crate::output::{print_json, OutputFormat};
+
+/// Check a CFF file against the CFF 1.2.0 JSON Schema
+///
+/// This is the check the reference tooling (cffconvert) does, which is stricter
+/// than what this library reads: e.g. unknown keys are errors. Violations are
+/// reported with their line and column in the file.
+#[derive(Debug, Parser)]
+pub struct ValidateArgs {
+	/// CFF file to check
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// Output format: text or json
+	#[clap(long, default_value = "text")]
+	format: OutputFormat,
+}
+
+pub fn run(args: ValidateArgs) -> Result<()> {
+	let yaml = read_to_string(&args.path)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("opening {}", args.path.display()))?;
+	let violations = validate_against_schema(&yaml)
+		.map_err(|err| miette!("{}", err))
+		.wrap_err_with(|| format!("parsing {}", args.path.display()))?;
+
+	match args.format {
+		OutputFormat::Text => {
+			for violation in &violations {
+				println!("{}:{}", args.path.display(), violation);
+			}
+		}
+		OutputFormat::Json => print_json(
+			"validate",
+			json!({
+				"path": args.path,
+				"violations": violations.iter().map(|violation| json!({
+					"path": violation.path,
+					"line": violation.line,
+					"column": violation.column,
+					"message": violation.message,
+				})).collect::<Vec<_>>(),
+			}),
+		),
+	}
+
+	if !violations.is_empty() {
+		bail!(
+			"{} doesn't conform to the CFF schema: {} violation(s)",
+			args.path.display(),
+			violations.len()
+		);
+	}
+
+	Ok(())
+}
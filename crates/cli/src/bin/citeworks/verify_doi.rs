@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+use citeworks_cff::identifiers::IdentifierKind;
+use citeworks_fetch::doi::{drift, Resolver};
+use clap::Parser;
+use miette::{bail, miette, IntoDiagnostic, Result, WrapErr};
+use serde_json::json;
+
+use crate::{
+	output::{print_json, OutputFormat},
+	read_cff,
+};
+
+/// Check that a CFF file agrees with the metadata registered for its DOI
+///
+/// The metadata is fetched from doi.org, so this works for Zenodo (DataCite)
+/// and Crossref DOIs alike. Titles, authors, and versions are compared.
+#[derive(Debug, Parser)]
+pub struct VerifyDoiArgs {
+	/// CFF file to read
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// DOI to check against, instead of the one in the file
+	#[clap(long, value_name = "DOI")]
+	doi: Option<String>,
+
+	/// Output format: text or json
+	#[clap(long, default_value = "text")]
+	format: OutputFormat,
+}
+
+pub fn run(args: VerifyDoiArgs) -> Result<()> {
+	let cff = read_cff(&args.path)?;
+	let doi = args
+		.doi
+		.clone()
+		.or_else(|| cff.doi.clone())
+		.or_else(|| {
+			cff.identifier_of_kind(IdentifierKind::Doi)
+				.map(|id| id.value().to_string())
+		})
+		.ok_or_else(|| miette!("{} has no DOI: use --doi", args.path.display()))?;
+
+	let registered = Resolver::new()
+		.csl(&doi)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("fetching the metadata of {doi}"))?
+		.ok_or_else(|| miette!("{} isn't a registered DOI", doi))?;
+	let drifts = drift(&cff, &registered);
+
+	match args.format {
+		OutputFormat::Text => {
+			for drift in &drifts {
+				println!(
+					"{}: {:?} in {}, but {:?} registered for {doi}",
+					drift.field,
+					drift.cff,
+					args.path.display(),
+					drift.registered
+				);
+			}
+		}
+		OutputFormat::Json => print_json(
+			"verify-doi",
+			json!({
+				"path": args.path,
+				"doi": doi,
+				"drift": drifts.iter().map(|drift| json!({
+					"field": drift.field,
+					"cff": drift.cff,
+					"registered": drift.registered,
+				})).collect::<Vec<_>>(),
+			}),
+		),
+	}
+
+	if !drifts.is_empty() {
+		bail!(
+			"{} disagrees with {} in {} field(s)",
+			args.path.display(),
+			doi,
+			drifts.len()
+		);
+	}
+
+	Ok(())
+}
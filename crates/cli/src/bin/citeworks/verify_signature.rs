@@ -0,0 +1,154 @@
+use std::{
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+use citeworks_cff::{signature::NAMESPACE, Cff};
+use clap::Parser;
+use miette::{bail, IntoDiagnostic, Result};
+
+use crate::sign::{run_tool, temp_dir, write_message, SignatureTool};
+
+/// Check the detached signature of a CFF file, as made by `sign`
+///
+/// SSH signatures are checked against an allowed signers file (see the
+/// ALLOWED SIGNERS section of ssh-keygen's manual), as the given identity, or
+/// any identity allowed to have made it. Minisign signatures are checked
+/// against a public key.
+///
+/// Only the metadata counts, so a file which was reformatted or had its
+/// comments changed after it was signed still verifies.
+#[derive(Debug, Parser)]
+pub struct VerifySignatureArgs {
+	/// CFF file to check
+	#[clap(default_value = "CITATION.cff")]
+	path: PathBuf,
+
+	/// Signature to check, defaults to the file's path with .sig or .minisig
+	#[clap(long, value_name = "FILE")]
+	signature: Option<PathBuf>,
+
+	/// SSH allowed signers file
+	#[clap(long, value_name = "FILE", required_unless_present = "public-key")]
+	allowed_signers: Option<PathBuf>,
+
+	/// Identity to check an SSH signature as, e.g. an email
+	#[clap(long, value_name = "ID", requires = "allowed-signers")]
+	identity: Option<String>,
+
+	/// Minisign public key
+	#[clap(long, value_name = "FILE", conflicts_with = "allowed-signers")]
+	public_key: Option<PathBuf>,
+}
+
+/// Who can have signed a document.
+#[derive(Debug, Clone)]
+pub enum Signers<'a> {
+	/// Signers in an SSH allowed signers file, maybe only one identity.
+	Ssh {
+		allowed_signers: &'a Path,
+		identity: Option<&'a str>,
+	},
+
+	/// The owner of a minisign public key.
+	Minisign { public_key: &'a Path },
+}
+
+pub fn run(args: VerifySignatureArgs) -> Result<()> {
+	let signers = match (&args.allowed_signers, &args.public_key) {
+		(Some(allowed_signers), _) => Signers::Ssh {
+			allowed_signers,
+			identity: args.identity.as_deref(),
+		},
+		(None, Some(public_key)) => Signers::Minisign { public_key },
+		(None, None) => bail!("--allowed-signers or --public-key is required"),
+	};
+	let tool = match signers {
+		Signers::Ssh { .. } => SignatureTool::Ssh,
+		Signers::Minisign { .. } => SignatureTool::Minisign,
+	};
+	let signature = args
+		.signature
+		.unwrap_or_else(|| tool.signature_path(&args.path));
+	if !signature.exists() {
+		bail!(
+			"{} has no signature at {}",
+			args.path.display(),
+			signature.display()
+		);
+	}
+
+	let cff = super::read_cff(&args.path)?;
+	if !verify(&cff, &signature, &signers)? {
+		bail!(
+			"{} doesn't match its signature at {}: its metadata may have been changed since it was signed",
+			args.path.display(),
+			signature.display()
+		);
+	}
+
+	println!("{} matches its signature", args.path.display());
+	Ok(())
+}
+
+/// Whether a detached signature is of a document's canonical text, by a signer.
+pub fn verify(cff: &Cff, signature: &Path, signers: &Signers<'_>) -> Result<bool> {
+	let text = cff.canonical_string().into_diagnostic()?;
+	match signers {
+		Signers::Ssh {
+			allowed_signers,
+			identity,
+		} => {
+			let identities = match identity {
+				Some(identity) => vec![identity.to_string()],
+				None => {
+					let output = run_tool(
+						Command::new("ssh-keygen")
+							.args(["-Y", "find-principals", "-f"])
+							.arg(allowed_signers)
+							.arg("-s")
+							.arg(signature),
+						Some(""),
+					)?;
+					String::from_utf8_lossy(&output.stdout)
+						.lines()
+						.map(String::from)
+						.collect()
+				}
+			};
+
+			for identity in identities {
+				let output = run_tool(
+					Command::new("ssh-keygen")
+						.args(["-Y", "verify", "-n", NAMESPACE, "-f"])
+						.arg(allowed_signers)
+						.arg("-I")
+						.arg(&identity)
+						.arg("-s")
+						.arg(signature),
+					Some(&text),
+				)?;
+				if output.status.success() {
+					return Ok(true);
+				}
+			}
+			Ok(false)
+		}
+		Signers::Minisign { public_key } => {
+			let dir = temp_dir()?;
+			let message = write_message(&dir, &text)?;
+			let output = run_tool(
+				Command::new("minisign")
+					.arg("-V")
+					.arg("-p")
+					.arg(public_key)
+					.arg("-m")
+					.arg(&message)
+					.arg("-x")
+					.arg(signature),
+				Some(""),
+			)?;
+			Ok(output.status.success())
+		}
+	}
+}
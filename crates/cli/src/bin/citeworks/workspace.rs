@@ -0,0 +1,362 @@
+use std::{
+	fs::{read_dir, read_to_string, write},
+	path::{Path, PathBuf},
+};
+
+use citeworks_cff::{
+	names::{EntityName, Name},
+	validation::{Severity, ValidationIssue},
+	Cff, Date,
+};
+use citeworks_convert::{
+	cargo::{cargo_to_cff, inherit_workspace, update_cff_from_cargo},
+	npm::{npm_to_cff, update_cff_from_npm},
+	workspace::{set_package_references, Workspace, WorkspaceKind},
+};
+use clap::Parser;
+use miette::{bail, miette, IntoDiagnostic, Result, WrapErr};
+use serde_json::json;
+
+use crate::{
+	config::{validation_options, RuleArgs},
+	init::provided,
+	output::{print_json, OutputFormat},
+};
+
+/// Write and check the CITATION.cff files of a Cargo or npm workspace
+///
+/// Each package of the workspace gets a CITATION.cff next to its manifest, made
+/// from its metadata as `init --from-cargo` does, or if it already exists, with
+/// its version updated. The workspace's own CITATION.cff cites every package as
+/// a software reference. If it doesn't exist, it's made from the root package,
+/// or named after the directory in a virtual workspace.
+///
+/// All the files are then validated, and the command fails if any has errors.
+#[derive(Debug, Parser)]
+pub struct WorkspaceArgs {
+	/// Root directory of the workspace
+	#[clap(default_value = ".")]
+	dir: PathBuf,
+
+	/// Don't write anything, and fail if a file is missing or out of date
+	#[clap(long)]
+	check: bool,
+
+	/// Config file with the severity of rules, defaults to .citeworks.yml
+	#[clap(long, value_name = "FILE")]
+	config: Option<PathBuf>,
+
+	#[clap(flatten)]
+	rules: RuleArgs,
+
+	/// Output format: text or json
+	#[clap(long, default_value = "text")]
+	format: OutputFormat,
+}
+
+/// Whether a file agrees with its package.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Status {
+	Missing,
+	Outdated,
+	Current,
+}
+
+impl Status {
+	fn name(self) -> &'static str {
+		match self {
+			Self::Missing => "missing",
+			Self::Outdated => "outdated",
+			Self::Current => "current",
+		}
+	}
+
+	/// What happened to the file, or what would with --check.
+	fn describe(self, check: bool) -> &'static str {
+		match (self, check) {
+			(Self::Missing, false) => "created",
+			(Self::Outdated, false) => "updated",
+			(Self::Current, false) => "unchanged",
+			(Self::Missing, true) => "missing",
+			(Self::Outdated, true) => "out of date",
+			(Self::Current, true) => "up to date",
+		}
+	}
+}
+
+struct File {
+	path: PathBuf,
+	status: Status,
+	cff: Cff,
+	issues: Vec<ValidationIssue>,
+}
+
+pub fn run(args: WorkspaceArgs) -> Result<()> {
+	let (workspace, root_manifest) = detect(&args.dir)?;
+	let kind = workspace.kind;
+	let options = args
+		.rules
+		.apply(validation_options(args.config.as_deref())?);
+	let released = Date::today();
+
+	let mut files = Vec::new();
+	for member in members(&args.dir, &workspace)? {
+		let dir = args.dir.join(&member);
+		let mut manifest = read(&dir.join(kind.manifest()))?;
+		if kind == WorkspaceKind::Cargo {
+			manifest = inherit_workspace(&manifest, &root_manifest)
+				.map_err(|err| miette!("{}", err))
+				.wrap_err_with(|| format!("reading {}", dir.join(kind.manifest()).display()))?;
+		}
+
+		let path = dir.join("CITATION.cff");
+		let (cff, status) = if path.exists() {
+			let mut cff = super::read_cff(&path)?;
+			let bumped = match kind {
+				WorkspaceKind::Cargo => update_cff_from_cargo(&mut cff, &manifest, released),
+				WorkspaceKind::Npm => update_cff_from_npm(&mut cff, &manifest, released),
+			}
+			.map_err(|err| miette!("{}", err))?;
+			(
+				cff,
+				if bumped {
+					Status::Outdated
+				} else {
+					Status::Current
+				},
+			)
+		} else {
+			let mut cff = package_cff(kind, &manifest, released)
+				.wrap_err_with(|| format!("reading {}", dir.join(kind.manifest()).display()))?;
+			fill_authors(&mut cff, &path)?;
+			(cff, Status::Missing)
+		};
+
+		files.push(File {
+			path,
+			status,
+			cff,
+			issues: Vec::new(),
+		});
+	}
+
+	let packages: Vec<Cff> = files.iter().map(|file| file.cff.clone()).collect();
+	let path = args.dir.join("CITATION.cff");
+	let (mut root, mut status) = if path.exists() {
+		(super::read_cff(&path)?, Status::Current)
+	} else {
+		let mut cff = root_cff(&args.dir, kind, &root_manifest, released)?;
+		fill_authors(&mut cff, &path)?;
+		(cff, Status::Missing)
+	};
+	if set_package_references(&mut root, &packages) && status == Status::Current {
+		status = Status::Outdated;
+	}
+	files.push(File {
+		path,
+		status,
+		cff: root,
+		issues: Vec::new(),
+	});
+
+	for file in &mut files {
+		file.issues = file.cff.validate_with(&options);
+		if !args.check && file.status != Status::Current {
+			let text = citeworks_cff::to_string_canonical(&file.cff).into_diagnostic()?;
+			write(&file.path, text)
+				.into_diagnostic()
+				.wrap_err_with(|| format!("writing {}", file.path.display()))?;
+		}
+	}
+
+	match args.format {
+		OutputFormat::Text => {
+			for file in &files {
+				println!(
+					"{} {}",
+					file.status.describe(args.check),
+					file.path.display()
+				);
+				for issue in &file.issues {
+					println!("  {issue}");
+				}
+			}
+		}
+		OutputFormat::Json => print_json(
+			"workspace",
+			json!({
+				"dir": args.dir,
+				"kind": match kind {
+					WorkspaceKind::Cargo => "cargo",
+					WorkspaceKind::Npm => "npm",
+				},
+				"files": files.iter().map(|file| json!({
+					"path": file.path,
+					"title": file.cff.title,
+					"version": file.cff.version,
+					"status": file.status.name(),
+					"written": !args.check && file.status != Status::Current,
+					"issues": file.issues.iter().map(|issue| json!({
+						"rule": issue.rule.to_string(),
+						"code": issue.rule.code(),
+						"severity": issue.severity.to_string(),
+						"path": issue.path,
+						"message": issue.message,
+					})).collect::<Vec<_>>(),
+				})).collect::<Vec<_>>(),
+			}),
+		),
+	}
+
+	let stale = files
+		.iter()
+		.filter(|file| file.status != Status::Current)
+		.count();
+	if args.check && stale > 0 {
+		bail!(
+			"{} CITATION.cff file(s) missing or out of date: run `citeworks workspace` to update them",
+			stale
+		);
+	}
+
+	let errors = files
+		.iter()
+		.flat_map(|file| &file.issues)
+		.filter(|issue| issue.severity == Severity::Error)
+		.count();
+	if errors > 0 {
+		bail!("{} error(s) in the workspace's CITATION.cff files", errors);
+	}
+
+	Ok(())
+}
+
+fn read(path: &Path) -> Result<String> {
+	read_to_string(path)
+		.into_diagnostic()
+		.wrap_err_with(|| format!("reading {}", path.display()))
+}
+
+/// Find the workspace in a directory, and read its manifest.
+fn detect(dir: &Path) -> Result<(Workspace, String)> {
+	for kind in [WorkspaceKind::Cargo, WorkspaceKind::Npm] {
+		let path = dir.join(kind.manifest());
+		if !path.exists() {
+			continue;
+		}
+
+		let manifest = read(&path)?;
+		let workspace = match kind {
+			WorkspaceKind::Cargo => Workspace::from_cargo(&manifest),
+			WorkspaceKind::Npm => Workspace::from_npm(&manifest),
+		}
+		.map_err(|err| miette!("{}", err))
+		.wrap_err_with(|| format!("reading {}", path.display()))?;
+		if let Some(workspace) = workspace {
+			return Ok((workspace, manifest));
+		}
+	}
+
+	bail!(
+		"{} isn't a Cargo or npm workspace: there's no Cargo.toml with a [workspace], or package.json with workspaces",
+		dir.display()
+	)
+}
+
+/// Directories of the packages of a workspace, relative to it, in order.
+///
+/// Hidden directories, and `target` and `node_modules`, aren't searched.
+fn members(root: &Path, workspace: &Workspace) -> Result<Vec<String>> {
+	fn walk(
+		root: &Path,
+		relative: &str,
+		workspace: &Workspace,
+		found: &mut Vec<String>,
+	) -> Result<()> {
+		let dir = root.join(relative);
+		let entries = read_dir(&dir)
+			.into_diagnostic()
+			.wrap_err_with(|| format!("reading {}", dir.display()))?;
+		for entry in entries {
+			let entry = entry.into_diagnostic()?;
+			let name = entry.file_name().to_string_lossy().into_owned();
+			if name.starts_with('.')
+				|| name == "target"
+				|| name == "node_modules"
+				|| !entry.file_type().map_or(false, |kind| kind.is_dir())
+			{
+				continue;
+			}
+
+			let path = if relative.is_empty() {
+				name
+			} else {
+				format!("{relative}/{name}")
+			};
+			if workspace.includes(&path)
+				&& root.join(&path).join(workspace.kind.manifest()).exists()
+			{
+				found.push(path.clone());
+			}
+			walk(root, &path, workspace, found)?;
+		}
+		Ok(())
+	}
+
+	let mut found = Vec::new();
+	walk(root, "", workspace, &mut found)?;
+	found.sort();
+	Ok(found)
+}
+
+fn package_cff(kind: WorkspaceKind, manifest: &str, released: Date) -> Result<Cff> {
+	match kind {
+		WorkspaceKind::Cargo => cargo_to_cff(manifest, released),
+		WorkspaceKind::Npm => npm_to_cff(manifest, released),
+	}
+	.map_err(|err| miette!("{}", err))
+}
+
+/// The workspace's own document: of its root package, if it has one.
+fn root_cff(dir: &Path, kind: WorkspaceKind, manifest: &str, released: Date) -> Result<Cff> {
+	let root_package = match kind {
+		WorkspaceKind::Cargo => inherit_workspace(manifest, manifest)
+			.and_then(|manifest| cargo_to_cff(&manifest, released)),
+		WorkspaceKind::Npm => npm_to_cff(manifest, released),
+	};
+	if let Ok(cff) = root_package {
+		return Ok(cff);
+	}
+
+	let title = dir
+		.canonicalize()
+		.ok()
+		.and_then(|dir| {
+			dir.file_name()
+				.map(|name| name.to_string_lossy().into_owned())
+		})
+		.ok_or_else(|| miette!("can't name the workspace after {}", dir.display()))?;
+	Ok(Cff {
+		title,
+		..Default::default()
+	})
+}
+
+/// Use the git user as the author of a document without authors.
+fn fill_authors(cff: &mut Cff, path: &Path) -> Result<()> {
+	if !cff.authors.is_empty() {
+		return Ok(());
+	}
+
+	let author = provided("author").ok_or_else(|| {
+		miette!(
+			"{} would have no authors, and there's no git user.name to use instead",
+			path.display()
+		)
+	})?;
+	cff.authors.push(Name::Entity(EntityName {
+		name: Some(author),
+		..Default::default()
+	}));
+	Ok(())
+}
@@ -0,0 +1,62 @@
+use std::{
+	fs::{read_to_string, File},
+	io::{stdin, stdout},
+	path::PathBuf,
+};
+
+use citeworks_cff::{from_str_located, to_writer, Cff};
+use citeworks_codemeta::CodeMeta;
+use citeworks_convert::codemeta::update_cff;
+use clap::Parser;
+use miette::{miette, IntoDiagnostic, NamedSource, Report, Result, WrapErr};
+
+/// Convert a CodeMeta file to CFF
+///
+/// Prints the CITATION.cff, or with --update, changes only what's converted in
+/// an existing one, keeping its other fields, like references.
+#[derive(Debug, Parser)]
+#[clap(author, version)]
+struct Args {
+	/// CodeMeta file, or - to read STDIN
+	#[clap(default_value = "codemeta.json")]
+	input: PathBuf,
+
+	/// Update this CFF file instead of printing
+	#[clap(long, value_name = "FILE")]
+	update: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+	let args = Args::parse();
+
+	let codemeta: CodeMeta = if args.input.to_str() == Some("-") {
+		citeworks_codemeta::from_reader(stdin()).into_diagnostic()?
+	} else {
+		let file = File::open(&args.input)
+			.into_diagnostic()
+			.wrap_err_with(|| format!("opening {}", args.input.display()))?;
+		citeworks_codemeta::from_reader(file)
+			.into_diagnostic()
+			.wrap_err_with(|| format!("parsing {}", args.input.display()))?
+	};
+
+	let mut cff = match &args.update {
+		Some(target) => {
+			let text = read_to_string(target)
+				.into_diagnostic()
+				.wrap_err_with(|| format!("opening {}", target.display()))?;
+			from_str_located(&text).map_err(|err| {
+				Report::new(err)
+					.with_source_code(NamedSource::new(target.display().to_string(), text.clone()))
+			})?
+		}
+		None => Cff::default(),
+	};
+	update_cff(&mut cff, &codemeta).map_err(|err| miette!("{}", err))?;
+
+	match &args.update {
+		Some(target) => to_writer(File::create(target).into_diagnostic()?, &cff),
+		None => to_writer(stdout(), &cff),
+	}
+	.into_diagnostic()
+}
@@ -1,34 +1,58 @@
 use std::{
-	fs::File,
+	fs::{read_to_string, write, File},
+	io::{stdin, BufWriter, Read},
 	path::{Path, PathBuf},
 	str::FromStr,
 };
 
 use citeworks_cff::{
-	from_reader as cff_from_reader,
-	identifiers::Identifier,
-	names::{EntityName, Name as CffName, PersonName},
-	references::{RefType, Reference},
-	to_writer, Cff, Date as CffDate,
+	edit, from_str_located,
+	references::{PublicationStatus, RefType, Reference},
+	to_writer, Cff,
 };
-use citeworks_csl::{
-	dates::{Date as CslDate, DateParts as CslDateParts},
-	from_reader as csl_from_reader,
-	items::ItemType,
-	names::Name as CslName,
-	ordinaries::OrdinaryValue,
-	Item,
+use citeworks_convert::{
+	cff::item_to_reference_with,
+	csl::parse_status,
+	keys::{KeyGenerator, KeyPattern},
+	options::{BadUrls, ConvertOptions, Preset},
+	report::{AuditLog, ConversionReport, PrintWarnings, RecordReport, WarningSink},
+	ris::records_to_items,
+	stash::stash,
+	table::{table_to_items, Column, Delimiter, ImportOptions, RowError},
+	zotero::rdf_to_items,
 };
+use citeworks_csl::{dates::Hemisphere, from_str as csl_from_str, items::ItemType, Item};
 use clap::Parser;
-use miette::{IntoDiagnostic, Result};
-use url::Url;
+use miette::{miette, IntoDiagnostic, NamedSource, Report, Result};
+
+// Shared with the citeworks binary, for the same JSON envelope.
+#[allow(dead_code)]
+#[path = "citeworks/output.rs"]
+mod output;
 
 #[derive(Debug, Parser)]
 #[clap(author, about, version)]
 struct Args {
-	/// CSL-JSON file or - to read STDIN
+	/// CSL-JSON, RIS, CSV, TSV, or Zotero RDF file, or - to read STDIN
 	input: PathBuf,
 
+	/// Format of the input: csl-json, ris, csv, tsv, or zotero-rdf
+	///
+	/// Defaults to the extension of the file, and to csl-json otherwise.
+	/// Records without an ID are given keys like `doe2017`.
+	///
+	/// Tables have a header row naming the CSL variable of each column, or
+	/// `year`; see --map-column for other headers. Rows which can't be read
+	/// are reported as warnings and skipped.
+	#[clap(long, value_name = "FORMAT")]
+	from: Option<InputFormat>,
+
+	/// Read a column of a CSV or TSV table as a CSL variable, e.g. `Authors=author`
+	///
+	/// Can be given multiple times.
+	#[clap(long, value_name = "HEADER=COLUMN", parse(try_from_str = parse_column_mapping))]
+	map_column: Vec<(String, Column)>,
+
 	/// Append bibliography from CSL to references section of target CFF file
 	#[clap(long, value_name = "TARGET")]
 	insert: Option<PathBuf>,
@@ -36,267 +60,384 @@ struct Args {
 	/// Replace references section of target CFF file with CSL bibliography
 	#[clap(long, value_name = "TARGET")]
 	replace: Option<PathBuf>,
-}
 
-fn main() -> Result<()> {
-	let args = Args::parse();
+	/// With --insert or --replace, only rewrite the references of the target
+	///
+	/// The rest of the file is kept as it is, with its comments, quoting, and
+	/// order of keys.
+	#[clap(long, conflicts_with = "redact")]
+	keep_format: bool,
 
-	let csl = if args.input.to_str() == Some("-") {
-		let stdin = std::io::stdin();
-		csl_from_reader(stdin).into_diagnostic()?
-	} else {
-		let file = File::open(args.input).into_diagnostic()?;
-		csl_from_reader(file).into_diagnostic()?
-	};
+	/// Stash CSL variables that can't be converted in the notes of references
+	///
+	/// They're restored when the CFF file is read back as CSL by citeworks.
+	#[clap(long)]
+	lossless: bool,
 
-	let mut refs = Vec::with_capacity(csl.len());
-	for item in csl {
-		refs.push(convert_ref(item)?);
-	}
+	/// Normalise text to Unicode NFC, and warn about text that looks damaged
+	#[clap(long)]
+	normalize_unicode: bool,
 
-	if let Some(target) = args.replace {
-		let mut cff = read_cff(&target)?;
-		cff.references = refs;
-		write_cff(&target, &cff)?;
-	} else if let Some(target) = args.insert {
-		let mut cff = read_cff(&target)?;
-		cff.references.extend(refs);
-		write_cff(&target, &cff)?;
-	} else {
-		print_references(refs)?;
-	}
+	/// Remove people's emails, phone numbers, and addresses from the output
+	///
+	/// With --insert or --replace, this applies to the whole target file.
+	#[clap(long)]
+	redact: bool,
 
-	Ok(())
-}
+	/// Preset of conversion options for where the CSL comes from
+	///
+	/// One of `zotero`, `crossref`, or `strict-spec`. Options below override
+	/// the preset's.
+	#[clap(long, value_name = "PRESET")]
+	preset: Option<Preset>,
 
-fn read_cff(file: &Path) -> Result<Cff> {
-	let file = File::open(file).into_diagnostic()?;
-	cff_from_reader(file).into_diagnostic()
-}
+	/// Characters separating keywords in CSL `category` and `keyword` values
+	///
+	/// Keywords are trimmed, and empty ones are skipped. Pass an empty string
+	/// to keep each value as a single keyword. Defaults to `;,`.
+	#[clap(long, value_name = "CHARS")]
+	keyword_separators: Option<String>,
 
-fn write_cff(target: &Path, cff: &Cff) -> Result<()> {
-	let file = File::create(target).into_diagnostic()?;
-	to_writer(file, cff).into_diagnostic()
-}
+	/// Publication status for references with a CSL `status` that isn't recognised
+	///
+	/// One of abstract, advance-online, in-preparation, in-press, preprint, or
+	/// submitted. Otherwise, unrecognised statuses are dropped.
+	#[clap(long, value_name = "STATUS", parse(try_from_str = parse_status_arg))]
+	status_fallback: Option<PublicationStatus>,
 
-fn print_references(refs: Vec<Reference>) -> Result<()> {
-	let stdout = std::io::stdout();
-	serde_yaml::to_writer(
-		stdout,
-		&serde_yaml::Value::Sequence(
-			refs.into_iter()
-				.map(|r| serde_yaml::to_value(r).unwrap())
-				.collect(),
-		),
-	)
-	.into_diagnostic()
+	/// What to do with CSL `URL`s that can't be parsed
+	///
+	/// `drop` them (the default); `keep` them as identifiers; `repair` them by
+	/// adding a missing scheme and encoding spaces, dropping them if that
+	/// fails; or fail with an error (`strict`).
+	#[clap(long, value_name = "HANDLING")]
+	bad_urls: Option<BadUrls>,
+
+	/// Remove `https://doi.org/` and `doi:` prefixes from DOIs
+	#[clap(long)]
+	normalise_dois: bool,
+
+	/// Convert a CSL type to a CFF type, e.g. `manuscript=unpublished`
+	///
+	/// Types outside CSL, like `preprint` from some exports, can be mapped too;
+	/// otherwise they're converted to `generic`. Can be given multiple times.
+	#[clap(long, value_name = "CSL=CFF", parse(try_from_str = parse_type_mapping))]
+	map_type: Vec<(ItemType, RefType)>,
+
+	/// Hemisphere of seasons in CSL dates: northern (the default) or southern
+	///
+	/// Dates with only a year and a season get the first month of the season.
+	#[clap(long, value_name = "HEMISPHERE")]
+	hemisphere: Option<Hemisphere>,
+
+	/// How to print warnings about what wasn't converted exactly: text or json
+	///
+	/// Warnings are printed to stderr. As JSON, they're printed once all items
+	/// are converted, as a `records` array, each with the `id` of the item, and
+	/// `dropped`, `guessed`, and `warnings` arrays, with the `schema` version
+	/// and `command` like the JSON output of citeworks.
+	#[clap(long, value_name = "FORMAT", default_value = "text")]
+	warnings: WarningsFormat,
+
+	/// Fail if anything wasn't converted exactly, after writing the output
+	#[clap(long)]
+	deny_warnings: bool,
+
+	/// Write how each field was converted to a file, as JSON lines
+	///
+	/// Each line has the `record` id and an `action`: `mapped` from a CSL
+	/// variable to a CFF field, `dropped`, `guessed`, or `warning`. See the
+	/// citeworks-convert documentation of `AuditLog` for the details.
+	#[clap(long, value_name = "FILE")]
+	audit_log: Option<PathBuf>,
 }
 
-fn convert_ref(item: Item) -> Result<Reference> {
-	Ok(Reference {
-		work_type: convert_type(item.item_type),
-		authors: convert_authors(item.author.into_iter().chain(item.contributor.into_iter())),
-		abbreviation: ov_string(item.title_short),
-		abstract_text: ov_string(item.abstract_text),
-		collection_title: ov_string(item.container_title),
-		copyright: ov_string(item.rights).or_else(|| ov_string(item.license)),
-		database: ov_string(item.source),
-		date_accessed: convert_date(item.accessed),
-		date_published: convert_date(item.published),
-		doi: ov_string(item.doi),
-		start: page_start(ov_string(item.page.clone())),
-		end: page_end(ov_string(item.page.clone())),
-		identifiers: extra_idents(ov_string(item.eissn), ov_string(item.issnl)),
-		issn: ov_string(item.issn),
-		issue: ov_string(item.issue),
-		issue_date: convert_date(item.issued).map(|d| d.to_string()),
-		journal: ov_string(item.journal_abbrevation),
-		keywords: ov_string(item.category).map_or_else(Vec::new, |c| vec![c]),
-		languages: ov_string(item.language).map_or_else(Vec::new, |c| vec![c]),
-		notes: ov_string(item.note),
-		title: ov_string(item.title),
-		url: ov_string(item.url).and_then(|u| match Url::parse(&u) {
-			Ok(url) => Some(url),
-			Err(err) => {
-				eprintln!("WARNING: could not parse URL {u:?}\n{err}");
-				None
-			}
-		}),
-		volume: ov_string(item.volume).and_then(|v| match u64::from_str(&v) {
-			Ok(vol) => Some(vol),
-			Err(err) => {
-				eprintln!("WARNING: could not parse volume {v:?} as number\n{err}");
-				None
-			}
-		}),
-		..Default::default()
-	})
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum InputFormat {
+	CslJson,
+	Ris,
+	Table(Delimiter),
+	ZoteroRdf,
 }
 
-fn convert_type(item_type: ItemType) -> RefType {
-	match item_type {
-		ItemType::Article => RefType::Article,
-		ItemType::ArticleJournal => RefType::Article,
-		ItemType::ArticleMagazine => RefType::MagazineArticle,
-		ItemType::ArticleNewspaper => RefType::NewspaperArticle,
-		ItemType::Bill => RefType::Bill,
-		ItemType::Book => RefType::Book,
-		ItemType::Broadcast => RefType::Generic,
-		ItemType::Chapter => RefType::Book,
-		ItemType::Classic => RefType::Generic,
-		ItemType::Collection => RefType::Generic,
-		ItemType::Dataset => RefType::Data,
-		ItemType::Document => RefType::Generic,
-		ItemType::Entry => RefType::Generic,
-		ItemType::EntryDictionary => RefType::Dictionary,
-		ItemType::EntryEncyclopedia => RefType::Encyclopedia,
-		ItemType::Figure => RefType::Generic,
-		ItemType::Graphic => RefType::Generic,
-		ItemType::Hearing => RefType::Hearing,
-		ItemType::Interview => RefType::Generic,
-		ItemType::LegalCase => RefType::LegalCase,
-		ItemType::Legislation => RefType::GovernmentDocument,
-		ItemType::Manuscript => RefType::Generic,
-		ItemType::Map => RefType::Map,
-		ItemType::MotionPicture => RefType::Video,
-		ItemType::MusicalScore => RefType::Music,
-		ItemType::Pamphlet => RefType::Pamphlet,
-		ItemType::PaperConference => RefType::ConferencePaper,
-		ItemType::Patent => RefType::Patent,
-		ItemType::Performance => RefType::Generic,
-		ItemType::Periodical => RefType::Generic,
-		ItemType::PersonalCommunication => RefType::PersonalCommunication,
-		ItemType::Post => RefType::Blog,
-		ItemType::PostWeblog => RefType::Blog,
-		ItemType::Regulation => RefType::Statute,
-		ItemType::Report => RefType::Report,
-		ItemType::Review => RefType::Generic,
-		ItemType::ReviewBook => RefType::Generic,
-		ItemType::Software => RefType::Software,
-		ItemType::Song => RefType::Music,
-		ItemType::Speech => RefType::SoundRecording,
-		ItemType::Standard => RefType::Standard,
-		ItemType::Thesis => RefType::Thesis,
-		ItemType::Treaty => RefType::GovernmentDocument,
-		ItemType::Webpage => RefType::Website,
-		ItemType::Gazette => RefType::Generic,
-		ItemType::Video => RefType::Video,
-		ItemType::LegalCommentary => RefType::Generic,
+impl FromStr for InputFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"csl-json" | "csl" => Ok(Self::CslJson),
+			"ris" => Ok(Self::Ris),
+			"csv" => Ok(Self::Table(Delimiter::Csv)),
+			"tsv" => Ok(Self::Table(Delimiter::Tsv)),
+			"zotero-rdf" | "rdf" => Ok(Self::ZoteroRdf),
+			_ => Err(format!("unknown input format: {s:?}")),
+		}
 	}
 }
 
-fn convert_authors(csl: impl Iterator<Item = CslName>) -> Vec<CffName> {
-	let mut authors: Vec<_> = csl.map(convert_name).collect();
-	if authors.is_empty() {
-		authors.push(CffName::Anonymous);
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum WarningsFormat {
+	Text,
+	Json,
+}
+
+impl FromStr for WarningsFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"text" => Ok(Self::Text),
+			"json" => Ok(Self::Json),
+			_ => Err(format!("unknown warnings format: {s:?}")),
+		}
 	}
-	authors
 }
 
-fn convert_name(csl_name: CslName) -> CffName {
-	if csl_name.family.is_some() || csl_name.given.is_some() {
-		CffName::Person(PersonName {
-			family_names: csl_name.family,
-			given_names: csl_name.given,
-			name_particle: csl_name.non_dropping_particle,
-			name_suffix: csl_name.suffix,
-			..Default::default()
-		})
-	} else if csl_name.literal.is_some() {
-		CffName::Entity(EntityName {
-			name: csl_name.literal,
-			..Default::default()
-		})
-	} else {
-		eprintln!("WARNING: a name could not be converted, using debug repr");
-		CffName::Entity(EntityName {
-			name: Some(format!("{:?}", csl_name)),
-			..Default::default()
-		})
+impl Args {
+	fn options(&self) -> ConvertOptions {
+		let mut options = self
+			.preset
+			.map_or_else(Default::default, ConvertOptions::preset);
+		if let Some(separators) = &self.keyword_separators {
+			options.keyword_separators = separators.clone();
+		}
+		if let Some(fallback) = self.status_fallback {
+			options.status_fallback = Some(fallback);
+		}
+		if let Some(bad_urls) = self.bad_urls {
+			options.bad_urls = bad_urls;
+		}
+		if self.normalise_dois {
+			options.normalise_dois = true;
+		}
+		if let Some(hemisphere) = self.hemisphere {
+			options.hemisphere = hemisphere;
+		}
+		options.type_mappings.extend(self.map_type.iter().cloned());
+		options
 	}
 }
 
-fn ov_string(ov: Option<OrdinaryValue>) -> Option<String> {
-	ov.map(|v| v.to_string())
+fn parse_status_arg(status: &str) -> Result<PublicationStatus, String> {
+	parse_status(status).ok_or_else(|| format!("unknown publication status: {status}"))
 }
 
-fn warn_partial_dates(date: CslDateParts) {
-	if date.month.is_none() {
-		eprintln!("WARNING: date {date:?} has no month, will default to january");
+fn parse_type_mapping(mapping: &str) -> Result<(ItemType, RefType), String> {
+	let (csl, cff) = mapping
+		.split_once('=')
+		.ok_or_else(|| format!("expected CSL=CFF, got {mapping:?}"))?;
+	// Types outside CSL can be mapped, as some tools write them, but not to
+	// types outside CFF.
+	let csl = serde_json::from_value(serde_json::Value::String(csl.trim().into()))
+		.map_err(|_| format!("unknown CSL type: {csl}"))?;
+	match serde_json::from_value(serde_json::Value::String(cff.trim().into())) {
+		Ok(RefType::Other(_)) | Err(_) => Err(format!("unknown CFF type: {cff}")),
+		Ok(cff) => Ok((csl, cff)),
 	}
+}
 
-	if date.day.is_none() {
-		eprintln!("WARNING: date {date:?} has no day, will default to the 1st");
-	}
+fn parse_column_mapping(mapping: &str) -> Result<(String, Column), String> {
+	let (header, column) = mapping
+		.split_once('=')
+		.ok_or_else(|| format!("expected HEADER=COLUMN, got {mapping:?}"))?;
+	Ok((header.trim().into(), column.parse()?))
 }
 
-fn convert_date(date: Option<CslDate>) -> Option<CffDate> {
-	match date {
-		Some(CslDate::Single { date, .. }) => {
-			warn_partial_dates(date);
-			Some(CffDate {
-				year: date.year,
-				month: date.month.unwrap_or(1),
-				day: date.day.unwrap_or(1),
-			})
+fn main() -> Result<()> {
+	let args = Args::parse();
+
+	let text = if args.input.to_str() == Some("-") {
+		let mut text = String::new();
+		stdin().read_to_string(&mut text).into_diagnostic()?;
+		text
+	} else {
+		read_to_string(&args.input).into_diagnostic()?
+	};
+	let from =
+		args.from.unwrap_or_else(
+			|| match args.input.extension().and_then(|ext| ext.to_str()) {
+				Some(ext) if ext.eq_ignore_ascii_case("ris") => InputFormat::Ris,
+				Some(ext) if ext.eq_ignore_ascii_case("csv") => InputFormat::Table(Delimiter::Csv),
+				Some(ext) if ext.eq_ignore_ascii_case("tsv") => InputFormat::Table(Delimiter::Tsv),
+				Some(ext) if ext.eq_ignore_ascii_case("rdf") => InputFormat::ZoteroRdf,
+				_ => InputFormat::CslJson,
+			},
+		);
+	let mut row_errors = Vec::new();
+	let csl = match from {
+		InputFormat::CslJson => csl_from_str(&text).into_diagnostic()?,
+		InputFormat::Ris => {
+			let records = citeworks_ris::from_str(&text).into_diagnostic()?;
+			records_to_items(&records, &mut KeyGenerator::new(KeyPattern::default()))
+		}
+		InputFormat::Table(delimiter) => {
+			let options = ImportOptions {
+				delimiter,
+				mapping: args.map_column.iter().cloned().collect(),
+				..Default::default()
+			};
+			let (items, errors) = table_to_items(
+				&text,
+				&options,
+				&mut KeyGenerator::new(KeyPattern::default()),
+			)
+			.map_err(|err| miette!("reading {}: {}", args.input.display(), err))?;
+			row_errors = errors;
+			items
+		}
+		InputFormat::ZoteroRdf => {
+			rdf_to_items(&text, &mut KeyGenerator::new(KeyPattern::default()))
+				.map_err(|err| miette!("reading {}: {}", args.input.display(), err))?
 		}
-		Some(CslDate::Range { start, .. }) => {
-			warn_partial_dates(start);
-			Some(CffDate {
-				year: start.year,
-				month: start.month.unwrap_or(1),
-				day: start.day.unwrap_or(1),
-			})
+	};
+
+	let mut report = ConversionReport::default();
+	let mut printer = PrintWarnings::stderr();
+	let mut warnings = |record: RecordReport| {
+		if args.warnings == WarningsFormat::Text {
+			printer.report(record.clone());
 		}
-		Some(other) => {
-			eprintln!("WARNING: could not convert date {other:?}, do it manually");
-			None
+		report.push(record);
+	};
+	let mut refs = match &args.audit_log {
+		Some(path) => {
+			let file = File::create(path)
+				.into_diagnostic()
+				.map_err(|err| err.wrap_err(format!("creating {}", path.display())))?;
+			let mut log = AuditLog::new(BufWriter::new(file)).forward(&mut warnings);
+			let refs = convert(&args, csl, row_errors, &mut log)?;
+			log.finish()
+				.into_diagnostic()
+				.map_err(|err| err.wrap_err(format!("writing {}", path.display())))?;
+			refs
 		}
-		None => None,
+		None => convert(&args, csl, row_errors, &mut warnings)?,
+	};
+
+	if args.warnings == WarningsFormat::Json {
+		eprintln!("{}", output::envelope("csl2cff", report.to_json()));
 	}
-}
 
-fn page_start(page: Option<String>) -> Option<u64> {
-	if let Some(page) = page {
-		if let Ok(single) = page.parse::<u64>() {
-			Some(single)
-		} else if let Some(start) = page.split('-').next() {
-			u64::from_str(start).ok()
+	if args.redact {
+		for reference in &mut refs {
+			reference.redact_contact_details();
+		}
+	}
+
+	if args.keep_format {
+		if let Some(target) = args.replace {
+			edit_cff(&target, |yaml| edit::replace_references(yaml, &refs))?;
+		} else if let Some(target) = args.insert {
+			edit_cff(&target, |yaml| edit::insert_references(yaml, &refs))?;
 		} else {
-			None
+			print_references(refs)?;
+		}
+	} else if let Some(target) = args.replace {
+		let mut cff = read_cff(&target)?;
+		cff.references = refs;
+		if args.redact {
+			cff.redact_contact_details();
 		}
+		write_cff(&target, &cff)?;
+	} else if let Some(target) = args.insert {
+		let mut cff = read_cff(&target)?;
+		cff.references.extend(refs);
+		if args.redact {
+			cff.redact_contact_details();
+		}
+		write_cff(&target, &cff)?;
 	} else {
-		None
+		print_references(refs)?;
 	}
+
+	if args.deny_warnings && !report.is_empty() {
+		return Err(miette!(
+			"some items weren't converted exactly, see the warnings"
+		));
+	}
+
+	Ok(())
 }
 
-fn page_end(page: Option<String>) -> Option<u64> {
-	if let Some(page) = page {
-		if let Ok(single) = page.parse::<u64>() {
-			Some(single)
-		} else if let Some(end) = page.split_once('-').map(|x| x.1) {
-			u64::from_str(end).ok()
+/// Convert items to references, sending reports to the sink.
+fn convert(
+	args: &Args,
+	csl: Vec<Item>,
+	row_errors: Vec<RowError>,
+	sink: &mut dyn WarningSink,
+) -> Result<Vec<Reference>> {
+	let mut refs = Vec::with_capacity(csl.len());
+	let options = args.options();
+	for error in row_errors {
+		let mut record = RecordReport::new(format!("row {}", error.row));
+		record.warn(format!("skipped: {}", error.message));
+		sink.report(record);
+	}
+	for item in csl {
+		let id = item.id.clone();
+		let mut reference = if args.lossless {
+			let mut reference = item_to_reference_with(item.clone(), &options, &mut *sink)
+				.map_err(|err| miette!("converting {}: {}", id, err))?;
+			stash(&mut reference, &item);
+			reference
 		} else {
-			None
+			item_to_reference_with(item, &options, &mut *sink)
+				.map_err(|err| miette!("converting {}: {}", id, err))?
+		};
+		if args.normalize_unicode {
+			let mut record = RecordReport::new(id);
+			check_unicode(&mut reference, &mut record);
+			if !record.is_empty() {
+				sink.report(record);
+			}
 		}
-	} else {
-		None
+		refs.push(reference);
 	}
+	Ok(refs)
 }
 
-fn extra_idents(eissn: Option<String>, issnl: Option<String>) -> Vec<Identifier> {
-	let mut idents = Vec::new();
-	if let Some(eissn) = eissn {
-		idents.push(Identifier::Other {
-			value: eissn,
-			description: Some("EISSN".into()),
-		});
-	}
-	if let Some(issnl) = issnl {
-		idents.push(Identifier::Other {
-			value: issnl,
-			description: Some("ISSNL".into()),
+fn check_unicode(reference: &mut Reference, record: &mut RecordReport) {
+	reference.normalize_unicode();
+	for suspect in reference.mojibake() {
+		record.warn(match suspect.suggestion {
+			Some(suggestion) => format!(
+				"{} looks damaged: {:?}, maybe {:?}",
+				suspect.path, suspect.value, suggestion
+			),
+			None => format!("{} looks damaged: {:?}", suspect.path, suspect.value),
 		});
 	}
-	idents
+}
+
+fn read_cff(file: &Path) -> Result<Cff> {
+	let text = read_to_string(file).into_diagnostic()?;
+	from_str_located(&text).map_err(|err| {
+		Report::new(err)
+			.with_source_code(NamedSource::new(file.display().to_string(), text.clone()))
+	})
+}
+
+fn write_cff(target: &Path, cff: &Cff) -> Result<()> {
+	let file = File::create(target).into_diagnostic()?;
+	to_writer(file, cff).into_diagnostic()
+}
+
+fn edit_cff(target: &Path, edit: impl FnOnce(&str) -> citeworks_cff::Result<String>) -> Result<()> {
+	read_cff(target)?;
+	let text = read_to_string(target).into_diagnostic()?;
+	let text = edit(&text).into_diagnostic()?;
+	write(target, text).into_diagnostic()
+}
+
+fn print_references(refs: Vec<Reference>) -> Result<()> {
+	let stdout = std::io::stdout();
+	serde_yaml::to_writer(
+		stdout,
+		&serde_yaml::Value::Sequence(
+			refs.into_iter()
+				.map(|r| serde_yaml::to_value(r).unwrap())
+				.collect(),
+		),
+	)
+	.into_diagnostic()
 }
@@ -0,0 +1,101 @@
+use std::{fs::write, process::Command};
+
+fn doctor(cff: &str, args: &[&str]) -> (bool, String) {
+	doctor_with_config(cff, None, args)
+}
+
+fn doctor_with_config(cff: &str, config: Option<&str>, args: &[&str]) -> (bool, String) {
+	let dir = tempfile::tempdir().unwrap();
+	write(dir.path().join("CITATION.cff"), cff).unwrap();
+	if let Some(config) = config {
+		write(dir.path().join(".citeworks.yml"), config).unwrap();
+	}
+	let output = Command::new(env!("CARGO_BIN_EXE_citeworks"))
+		.arg("doctor")
+		.args(args)
+		.current_dir(dir.path())
+		.output()
+		.unwrap();
+	(
+		output.status.success(),
+		String::from_utf8(output.stdout).unwrap(),
+	)
+}
+
+#[test]
+fn extensions() {
+	let (success, stdout) = doctor(
+		"cff-version: 1.2.0
+message: Please cite this
+title: Thing
+authors:
+  - family-names: Doe
+    given-names: Jane
+    ror: https://ror.org/027m9bs27
+funding:
+  - funder:
+      name: Acme
+",
+		&[],
+	);
+
+	assert!(!success, "{stdout}");
+	assert!(stdout.contains("error: funding: extension key"), "{stdout}");
+	assert!(
+		stdout.contains("error: authors[0].ror: extension key"),
+		"{stdout}"
+	);
+	assert!(!stdout.contains("CFF 1.2.0 schema"), "{stdout}");
+}
+
+#[test]
+fn schema() {
+	let (success, stdout) = doctor(
+		"cff-version: 1.2.0
+message: Please cite this
+title: Thing
+authors:
+  - family-names: Doe
+    given-names: Jane
+keywords: []
+",
+		&[],
+	);
+
+	assert!(!success, "{stdout}");
+	assert!(
+		stdout.contains("error: keywords: doesn't match the CFF 1.2.0 schema (line 7, column 1)"),
+		"{stdout}"
+	);
+}
+
+const COMMIT: &str = "cff-version: 1.2.0
+message: Please cite this
+title: Thing
+authors:
+  - family-names: Doe
+    given-names: Jane
+commit: abc
+";
+
+#[test]
+fn deny_over_config() {
+	let (success, stdout) = doctor_with_config(
+		COMMIT,
+		Some("rules:\n  commit-format: off\n"),
+		&["--deny", "commit-format"],
+	);
+	assert!(!success, "{stdout}");
+	assert!(stdout.contains("[CFF021 commit-format]"), "{stdout}");
+}
+
+#[test]
+fn allow_over_config() {
+	let (success, stdout) = doctor_with_config(
+		COMMIT,
+		Some("rules:\n  commit-format: error\n"),
+		&["--allow", "commit-format"],
+	);
+	assert!(success, "{stdout}");
+	assert!(stdout.contains("looks good"), "{stdout}");
+}
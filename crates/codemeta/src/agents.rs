@@ -0,0 +1,185 @@
+//! Types for people and organisations.
+
+use std::collections::BTreeMap;
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::values::{one_or_many, FromText};
+
+/// A person or organisation, e.g. an author or funder.
+///
+/// This is read from an object with a `@type` of `Person` or `Organization`,
+/// or from a plain name, which is read as a person with only a `name`. Objects
+/// without a `@type` are read as people, and those of other types, like
+/// CodeMeta 3's `Role`, are kept as they are.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Agent {
+	/// A person.
+	Person(Person),
+
+	/// An organisation.
+	Organization(Organization),
+
+	/// Anything else.
+	Other(Value),
+}
+
+impl Agent {
+	/// If this is a person, return it.
+	pub fn as_person(&self) -> Option<&Person> {
+		if let Self::Person(person) = self {
+			Some(person)
+		} else {
+			None
+		}
+	}
+
+	/// If this is an organisation, return it.
+	pub fn as_organization(&self) -> Option<&Organization> {
+		if let Self::Organization(org) = self {
+			Some(org)
+		} else {
+			None
+		}
+	}
+}
+
+impl FromText for Agent {
+	fn from_text(text: String) -> Self {
+		Self::Person(Person {
+			name: Some(text),
+			..Default::default()
+		})
+	}
+}
+
+#[derive(Serialize)]
+#[serde(tag = "@type")]
+enum TaggedAgent<'a> {
+	Person(&'a Person),
+	Organization(&'a Organization),
+}
+
+impl Serialize for Agent {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		match self {
+			Self::Person(person) => TaggedAgent::Person(person).serialize(serializer),
+			Self::Organization(org) => TaggedAgent::Organization(org).serialize(serializer),
+			Self::Other(value) => value.serialize(serializer),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for Agent {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let mut obj = match Value::deserialize(deserializer)? {
+			Value::String(name) => return Ok(Self::from_text(name)),
+			Value::Object(obj) => obj,
+			other => {
+				return Err(D::Error::custom(format!(
+					"expected an agent, found {other}"
+				)))
+			}
+		};
+
+		let agent_type = match obj.get("@type") {
+			None => None,
+			Some(Value::String(agent_type)) => Some(agent_type.as_str()),
+			Some(_) => return Ok(Self::Other(Value::Object(obj))),
+		};
+		match agent_type {
+			None | Some("Person") => {
+				obj.remove("@type");
+				serde_json::from_value(Value::Object(obj))
+					.map(Self::Person)
+					.map_err(D::Error::custom)
+			}
+			Some("Organization") => {
+				obj.remove("@type");
+				serde_json::from_value(Value::Object(obj))
+					.map(Self::Organization)
+					.map_err(D::Error::custom)
+			}
+			Some(_) => Ok(Self::Other(Value::Object(obj))),
+		}
+	}
+}
+
+/// A person.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+	/// Identifier of the person, usually their ORCID URL.
+	#[serde(default, rename = "@id", skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+
+	/// Given names.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub given_name: Option<String>,
+
+	/// Family names, including particles like `van`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub family_name: Option<String>,
+
+	/// Full name, usually only when the given and family names aren't known.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+
+	/// Email address.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub email: Option<String>,
+
+	/// Organisations the person is affiliated with.
+	#[serde(
+		default,
+		deserialize_with = "one_or_many",
+		skip_serializing_if = "Vec::is_empty"
+	)]
+	pub affiliation: Vec<Agent>,
+
+	/// Properties not defined above.
+	#[serde(flatten)]
+	pub extra: BTreeMap<String, Value>,
+}
+
+/// An organisation.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Organization {
+	/// Identifier of the organisation, e.g. its ROR URL.
+	#[serde(default, rename = "@id", skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+
+	/// Name.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+
+	/// Email address.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub email: Option<String>,
+
+	/// Website.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+
+	/// Properties not defined above.
+	#[serde(flatten)]
+	pub extra: BTreeMap<String, Value>,
+}
+
+impl Organization {
+	/// An organisation with only a name.
+	pub fn named(name: impl Into<String>) -> Self {
+		Self {
+			name: Some(name.into()),
+			..Default::default()
+		}
+	}
+}
@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+	agents::Agent,
+	values::{one_or_many, text, FromText},
+};
+
+/// The JSON-LD context for CodeMeta 3.0.
+pub const CONTEXT: &str = "https://w3id.org/codemeta/3.0";
+
+/// A CodeMeta document.
+///
+/// Dates are kept as the strings they're written as, which should be ISO 8601
+/// dates like `2017-12-18`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeMeta {
+	/// The JSON-LD context, usually the [CONTEXT] of a CodeMeta version.
+	#[serde(rename = "@context", default = "default_context")]
+	pub context: Value,
+
+	/// The schema.org type, usually `SoftwareSourceCode`.
+	#[serde(rename = "@type", default = "default_type")]
+	pub code_type: String,
+
+	/// Identifier of the software as a URL, e.g. its DOI URL.
+	#[serde(default, rename = "@id", skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+
+	/// Name of the software.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+
+	/// Description of the software.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub description: Option<String>,
+
+	/// Version of the software.
+	#[serde(
+		default,
+		deserialize_with = "text",
+		skip_serializing_if = "Option::is_none"
+	)]
+	pub version: Option<String>,
+
+	/// Version of the software, for when `version` is the metadata's version.
+	#[serde(
+		default,
+		deserialize_with = "text",
+		skip_serializing_if = "Option::is_none"
+	)]
+	pub software_version: Option<String>,
+
+	/// Identifiers of the software, e.g. a DOI URL.
+	#[serde(
+		default,
+		deserialize_with = "one_or_many",
+		skip_serializing_if = "Vec::is_empty"
+	)]
+	pub identifier: Vec<String>,
+
+	/// Repository of the source code.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub code_repository: Option<String>,
+
+	/// Homepage of the software.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+
+	/// Where to download the software, e.g. a package registry.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub download_url: Option<String>,
+
+	/// Where to report issues.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub issue_tracker: Option<String>,
+
+	/// Licenses, usually as SPDX URLs like `https://spdx.org/licenses/MIT`.
+	#[serde(
+		default,
+		deserialize_with = "one_or_many",
+		skip_serializing_if = "Vec::is_empty"
+	)]
+	pub license: Vec<String>,
+
+	/// When the software was first created.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub date_created: Option<String>,
+
+	/// When the software was last modified.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub date_modified: Option<String>,
+
+	/// When this version of the software was published.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub date_published: Option<String>,
+
+	/// Keywords.
+	///
+	/// These may also be a single comma-separated string.
+	#[serde(
+		default,
+		deserialize_with = "one_or_many",
+		skip_serializing_if = "Vec::is_empty"
+	)]
+	pub keywords: Vec<String>,
+
+	/// Authors, in order.
+	#[serde(
+		default,
+		deserialize_with = "one_or_many",
+		skip_serializing_if = "Vec::is_empty"
+	)]
+	pub author: Vec<Agent>,
+
+	/// Other contributors.
+	#[serde(
+		default,
+		deserialize_with = "one_or_many",
+		skip_serializing_if = "Vec::is_empty"
+	)]
+	pub contributor: Vec<Agent>,
+
+	/// Maintainers, who can be contacted about the software.
+	#[serde(
+		default,
+		deserialize_with = "one_or_many",
+		skip_serializing_if = "Vec::is_empty"
+	)]
+	pub maintainer: Vec<Agent>,
+
+	/// Grants funding the software.
+	#[serde(
+		default,
+		deserialize_with = "one_or_many",
+		skip_serializing_if = "Vec::is_empty"
+	)]
+	pub funding: Vec<Grant>,
+
+	/// Properties not defined above.
+	///
+	/// These are kept so properties this library doesn't know, like
+	/// `programmingLanguage`, aren't lost when reading and writing documents.
+	#[serde(flatten)]
+	pub extra: BTreeMap<String, Value>,
+}
+
+impl Default for CodeMeta {
+	fn default() -> Self {
+		Self {
+			context: default_context(),
+			code_type: default_type(),
+			id: None,
+			name: None,
+			description: None,
+			version: None,
+			software_version: None,
+			identifier: Vec::new(),
+			code_repository: None,
+			url: None,
+			download_url: None,
+			issue_tracker: None,
+			license: Vec::new(),
+			date_created: None,
+			date_modified: None,
+			date_published: None,
+			keywords: Vec::new(),
+			author: Vec::new(),
+			contributor: Vec::new(),
+			maintainer: Vec::new(),
+			funding: Vec::new(),
+			extra: BTreeMap::new(),
+		}
+	}
+}
+
+fn default_context() -> Value {
+	CONTEXT.into()
+}
+
+fn default_type() -> String {
+	"SoftwareSourceCode".into()
+}
+
+/// A grant funding the software.
+///
+/// CodeMeta 2.0 documents often have `funding` as text: that's read as a grant
+/// with only a `name`.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Grant {
+	/// Identifier of the grant, e.g. its URL.
+	#[serde(default, rename = "@id", skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+
+	/// The award number or code given by the funder.
+	#[serde(
+		default,
+		deserialize_with = "text",
+		skip_serializing_if = "Option::is_none"
+	)]
+	pub identifier: Option<String>,
+
+	/// Title of the grant or funded project.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+
+	/// Webpage of the grant.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub url: Option<String>,
+
+	/// Funders of the grant.
+	#[serde(
+		default,
+		deserialize_with = "one_or_many",
+		skip_serializing_if = "Vec::is_empty"
+	)]
+	pub funder: Vec<Agent>,
+
+	/// Properties not defined above.
+	#[serde(flatten)]
+	pub extra: BTreeMap<String, Value>,
+}
+
+impl FromText for Grant {
+	fn from_text(text: String) -> Self {
+		Self {
+			name: Some(text),
+			..Default::default()
+		}
+	}
+}
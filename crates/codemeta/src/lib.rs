@@ -0,0 +1,93 @@
+//! [CodeMeta](https://codemeta.github.io) serde types and implementations.
+//!
+//! CodeMeta files, usually `codemeta.json`, describe software as [schema.org]
+//! `SoftwareSourceCode` in JSON-LD. This reads and writes them as plain JSON,
+//! without JSON-LD processing: the properties are expected to be named as in
+//! the CodeMeta context, which is how they're written in practice.
+//!
+//! Properties which can be a single value or a list are read as lists, and
+//! properties not defined here are kept as they are in [`CodeMeta::extra`].
+//!
+//! ```
+//! let codemeta = citeworks_codemeta::from_str(r#"{
+//!   "@context": "https://w3id.org/codemeta/3.0",
+//!   "@type": "SoftwareSourceCode",
+//!   "name": "My Research Software",
+//!   "version": "2.0.4",
+//!   "license": "https://spdx.org/licenses/MIT",
+//!   "author": {
+//!     "@type": "Person",
+//!     "givenName": "Stephan",
+//!     "familyName": "Druskat"
+//!   },
+//!   "programmingLanguage": "Rust"
+//! }"#).unwrap();
+//!
+//! assert_eq!(codemeta.name.as_deref(), Some("My Research Software"));
+//! assert_eq!(codemeta.license, ["https://spdx.org/licenses/MIT"]);
+//! assert_eq!(
+//!     codemeta.author[0].as_person().and_then(|p| p.family_name.as_deref()),
+//!     Some("Druskat")
+//! );
+//! assert_eq!(codemeta.extra["programmingLanguage"], "Rust");
+//! ```
+//!
+//! [schema.org]: https://schema.org
+#![warn(clippy::unwrap_used, missing_docs)]
+#![deny(rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use std::io::{Read, Write};
+
+pub use serde_json::Result;
+
+#[doc(inline)]
+pub use codemeta::{CodeMeta, Grant, CONTEXT};
+
+pub mod agents;
+mod codemeta;
+mod values;
+
+/// Deserialize CodeMeta from an IO stream of JSON.
+pub fn from_reader<R>(rdr: R) -> Result<CodeMeta>
+where
+	R: Read,
+{
+	serde_json::from_reader(rdr)
+}
+
+/// Deserialize CodeMeta from bytes of JSON text.
+pub fn from_slice(v: &[u8]) -> Result<CodeMeta> {
+	serde_json::from_slice(v)
+}
+
+/// Deserialize CodeMeta from a string of JSON text.
+pub fn from_str(s: &str) -> Result<CodeMeta> {
+	serde_json::from_str(s)
+}
+
+/// Serialize the given CodeMeta as a String of JSON.
+pub fn to_string(value: &CodeMeta) -> Result<String> {
+	serde_json::to_string(value)
+}
+
+/// Serialize the given CodeMeta as a pretty-printed String of JSON.
+pub fn to_string_pretty(value: &CodeMeta) -> Result<String> {
+	serde_json::to_string_pretty(value)
+}
+
+/// Serialize the given CodeMeta as JSON into the IO stream.
+pub fn to_writer<W>(writer: W, value: &CodeMeta) -> Result<()>
+where
+	W: Write,
+{
+	serde_json::to_writer(writer, value)
+}
+
+/// Serialize the given CodeMeta as pretty-printed JSON into the IO stream.
+pub fn to_writer_pretty<W>(writer: W, value: &CodeMeta) -> Result<()>
+where
+	W: Write,
+{
+	serde_json::to_writer_pretty(writer, value)
+}
@@ -0,0 +1,49 @@
+use serde::{de::DeserializeOwned, de::Error, Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Values which can be written as text instead of an object.
+pub(crate) trait FromText {
+	fn from_text(text: String) -> Self;
+}
+
+impl FromText for String {
+	fn from_text(text: String) -> Self {
+		text
+	}
+}
+
+/// Deserialize a single value or a list of values as a list, where each value
+/// can be text.
+pub(crate) fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+	D: Deserializer<'de>,
+	T: DeserializeOwned + FromText,
+{
+	let values = match Value::deserialize(deserializer)? {
+		Value::Null => Vec::new(),
+		Value::Array(values) => values,
+		value => vec![value],
+	};
+
+	values
+		.into_iter()
+		.map(|value| match value {
+			Value::String(text) => Ok(T::from_text(text)),
+			value => serde_json::from_value(value).map_err(D::Error::custom),
+		})
+		.collect()
+}
+
+/// Deserialize text, also accepting numbers, as e.g. versions are sometimes
+/// written as `2`.
+pub(crate) fn text<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	match Option::<Value>::deserialize(deserializer)? {
+		None | Some(Value::Null) => Ok(None),
+		Some(Value::String(text)) => Ok(Some(text)),
+		Some(Value::Number(number)) => Ok(Some(number.to_string())),
+		Some(other) => Err(D::Error::custom(format!("expected text, found {other}"))),
+	}
+}
@@ -0,0 +1,103 @@
+use citeworks_codemeta::{
+	agents::{Agent, Organization, Person},
+	from_str, to_string_pretty, CodeMeta, Grant,
+};
+use serde_json::{json, Value};
+
+use pretty_assertions::assert_eq;
+
+const CODEMETA_2: &str = r#"{
+  "@context": "https://doi.org/10.5063/schema/codemeta-2.0",
+  "@type": "SoftwareSourceCode",
+  "identifier": "https://doi.org/10.5281/zenodo.1003149",
+  "name": "cffconvert",
+  "version": 2,
+  "description": "Command line program to validate and convert CITATION.cff files.",
+  "codeRepository": "https://github.com/citation-file-format/cffconvert",
+  "license": "https://spdx.org/licenses/Apache-2.0",
+  "keywords": ["bibliography", "CFF"],
+  "programmingLanguage": "Python",
+  "funding": "Netherlands eScience Center",
+  "author": [
+    {
+      "@type": "Person",
+      "@id": "https://orcid.org/0000-0002-7064-4069",
+      "givenName": "Jurriaan H.",
+      "familyName": "Spaaks",
+      "affiliation": {
+        "@type": "Organization",
+        "name": "Netherlands eScience Center"
+      }
+    },
+    "Anonymous Coward",
+    {
+      "@type": "Role",
+      "roleName": "Maintainer",
+      "schema:author": "https://orcid.org/0000-0002-7064-4069"
+    }
+  ]
+}"#;
+
+#[test]
+fn codemeta_2() {
+	let codemeta = from_str(CODEMETA_2).unwrap();
+	assert_eq!(codemeta.version.as_deref(), Some("2"));
+	assert_eq!(
+		codemeta.identifier,
+		["https://doi.org/10.5281/zenodo.1003149"]
+	);
+	assert_eq!(codemeta.license, ["https://spdx.org/licenses/Apache-2.0"]);
+	assert_eq!(
+		codemeta.funding,
+		[Grant {
+			name: Some("Netherlands eScience Center".into()),
+			..Default::default()
+		}]
+	);
+	assert_eq!(
+		codemeta.author[0],
+		Agent::Person(Person {
+			id: Some("https://orcid.org/0000-0002-7064-4069".into()),
+			given_name: Some("Jurriaan H.".into()),
+			family_name: Some("Spaaks".into()),
+			affiliation: vec![Agent::Organization(Organization::named(
+				"Netherlands eScience Center"
+			))],
+			..Default::default()
+		})
+	);
+	assert_eq!(
+		codemeta.author[1].as_person().unwrap().name.as_deref(),
+		Some("Anonymous Coward")
+	);
+	assert!(matches!(&codemeta.author[2], Agent::Other(role) if role["roleName"] == "Maintainer"));
+	assert_eq!(codemeta.extra["programmingLanguage"], "Python");
+}
+
+#[test]
+fn roundtrip() {
+	let codemeta = from_str(CODEMETA_2).unwrap();
+	let written: Value = serde_json::from_str(&to_string_pretty(&codemeta).unwrap()).unwrap();
+	assert_eq!(written["@type"], "SoftwareSourceCode");
+	assert_eq!(written["author"][0]["@type"], "Person");
+	assert_eq!(
+		written["author"][0]["affiliation"][0]["@type"],
+		"Organization"
+	);
+	assert_eq!(written["author"][2]["@type"], "Role");
+	assert_eq!(written["programmingLanguage"], "Python");
+	assert_eq!(from_str(&written.to_string()).unwrap(), codemeta);
+}
+
+#[test]
+fn defaults() {
+	let codemeta = from_str("{}").unwrap();
+	assert_eq!(codemeta, CodeMeta::default());
+	assert_eq!(
+		serde_json::to_value(&codemeta).unwrap(),
+		json!({
+			"@context": "https://w3id.org/codemeta/3.0",
+			"@type": "SoftwareSourceCode",
+		})
+	);
+}
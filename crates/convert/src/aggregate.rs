@@ -0,0 +1,116 @@
+//! A single bibliography of how many projects want to be cited.
+//!
+//! Labs and institutions often keep a list of their publications, and their
+//! software and datasets, which are spread over many CFF files. This collects
+//! the `preferred-citation` of each file, or the work itself if it doesn't
+//! have one, as CSL items, and removes duplicates: several projects described
+//! by the same paper are listed once.
+//!
+//! Items are the same if they have the same DOI, compared as in the
+//! [duplicates](crate::duplicates) module, or if they have no DOI but the same
+//! title and year. The first is kept. Keys are generated once duplicates are
+//! removed, so they don't skip letters.
+//!
+//! ```
+//! use citeworks_convert::aggregate::aggregate_citations;
+//!
+//! let paper = r#"
+//! preferred-citation:
+//!   type: article
+//!   title: Our software suite
+//!   doi: 10.21105/joss.01234
+//!   year: 2020
+//!   authors:
+//!     - family-names: Doe
+//!       given-names: Jane
+//! "#;
+//! let cli = citeworks_cff::from_str(&format!(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: The CLI
+//! authors:
+//!   - family-names: Doe
+//!     given-names: Jane
+//! {paper}"#)).unwrap();
+//! let library = citeworks_cff::from_str(&format!(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: The library
+//! authors:
+//!   - family-names: Doe
+//!     given-names: Jane
+//! {paper}"#)).unwrap();
+//!
+//! let items = aggregate_citations(&[cli, library]);
+//! assert_eq!(items.len(), 1);
+//! assert_eq!(items[0].id, "doe2020");
+//! ```
+
+use std::collections::HashSet;
+
+use citeworks_cff::Cff;
+use citeworks_csl::{ordinaries::OrdinaryValue, Item};
+
+use crate::{
+	csl::{convert_reference, convert_work},
+	duplicates::{normalise, normalise_doi},
+	keys::{year, KeyGenerator},
+	options::ConvertOptions,
+	report::{send, IgnoreWarnings, RecordReport, WarningSink},
+};
+
+/// Collect how CFF documents want to be cited into one list of CSL items.
+///
+/// Items are given keys as described in the [csl module](crate::csl).
+pub fn aggregate_citations(cffs: &[Cff]) -> Vec<Item> {
+	aggregate_citations_with(cffs, &mut KeyGenerator::default(), &mut IgnoreWarnings)
+}
+
+/// Collect how CFF documents want to be cited into one list of CSL items, with
+/// keys from the given generator.
+///
+/// Fields of the kept items which can't be converted are reported to the sink.
+pub fn aggregate_citations_with(
+	cffs: &[Cff],
+	keys: &mut KeyGenerator,
+	report: &mut dyn WarningSink,
+) -> Vec<Item> {
+	let options = ConvertOptions::default();
+	let mut seen = HashSet::new();
+	let mut items = Vec::new();
+	for cff in cffs {
+		let mut record = RecordReport::default();
+		let mut item = match &cff.preferred_citation {
+			Some(preferred) => convert_reference(preferred, String::new(), &mut record),
+			None => convert_work(cff, &options, &mut record),
+		};
+
+		if let Some(identity) = identity(&item) {
+			if !seen.insert(identity) {
+				continue;
+			}
+		}
+
+		item.id = keys.generate(&item);
+		record.id = item.id.clone();
+		send(report, record);
+		items.push(item);
+	}
+	items
+}
+
+/// What makes two items the same work, if it can be told.
+fn identity(item: &Item) -> Option<String> {
+	if let Some(doi) = item.doi.as_ref().map(OrdinaryValue::to_string) {
+		return Some(format!("doi:{}", normalise_doi(&doi)));
+	}
+
+	let title = normalise(&item.title.as_ref()?.to_string());
+	if title.is_empty() {
+		return None;
+	}
+	Some(match year(item) {
+		Some(year) => format!("title:{year}:{title}"),
+		None => format!("title::{title}"),
+	})
+}
@@ -0,0 +1,163 @@
+//! README badges derived from CFF metadata.
+//!
+//! Badges are [shields.io] images wrapped in a link, rendered as Markdown or
+//! HTML so READMEs can be generated consistently from the CFF file.
+//!
+//! ```
+//! use citeworks_convert::badge::{doi_badge, BadgeFormat};
+//!
+//! let cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! doi: 10.5281/zenodo.1234
+//! authors:
+//!   - name: The Research Software project
+//! "#).unwrap();
+//!
+//! let badge = doi_badge(&cff).unwrap();
+//! assert_eq!(
+//!     badge.render(BadgeFormat::Markdown),
+//!     "[![DOI](https://img.shields.io/badge/DOI-10.5281%2Fzenodo.1234-blue)](https://doi.org/10.5281/zenodo.1234)"
+//! );
+//! ```
+//!
+//! [shields.io]: https://shields.io
+
+use std::{fmt::Display, str::FromStr};
+
+use citeworks_cff::{
+	identifiers::{Identifier, IdentifierKind},
+	Cff,
+};
+
+use crate::xml::escape;
+
+/// A badge: an image with alt text, linking somewhere.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Badge {
+	/// Alt text of the image.
+	pub alt: String,
+
+	/// URL of the badge image.
+	pub image: String,
+
+	/// URL the badge links to.
+	pub link: String,
+}
+
+/// Markup to render badges as.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum BadgeFormat {
+	/// Markdown, e.g. for a README.md.
+	Markdown,
+
+	/// HTML, e.g. for a website or reStructuredText raw block.
+	Html,
+}
+
+impl Display for BadgeFormat {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Markdown => "markdown",
+				Self::Html => "html",
+			}
+		)
+	}
+}
+
+impl FromStr for BadgeFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"markdown" | "md" => Ok(Self::Markdown),
+			"html" => Ok(Self::Html),
+			_ => Err(format!("unknown badge format: {s:?}")),
+		}
+	}
+}
+
+impl Badge {
+	/// Make a shields.io static badge.
+	pub fn shields(label: &str, message: &str, color: &str, link: impl Into<String>) -> Self {
+		Self {
+			alt: label.into(),
+			image: format!(
+				"https://img.shields.io/badge/{}-{}-{}",
+				shields_escape(label),
+				shields_escape(message),
+				shields_escape(color)
+			),
+			link: link.into(),
+		}
+	}
+
+	/// Render the badge.
+	pub fn render(&self, format: BadgeFormat) -> String {
+		match format {
+			BadgeFormat::Markdown => format!("[![{}]({})]({})", self.alt, self.image, self.link),
+			BadgeFormat::Html => format!(
+				"<a href=\"{}\"><img src=\"{}\" alt=\"{}\"></a>",
+				escape(&self.link),
+				escape(&self.image),
+				escape(&self.alt)
+			),
+		}
+	}
+}
+
+/// Escape text for a shields.io static badge path segment.
+///
+/// Dashes and underscores are doubled (single ones are separators and spaces),
+/// then anything that's not unreserved in URLs is percent-encoded.
+fn shields_escape(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	for byte in s.replace('-', "--").replace('_', "__").bytes() {
+		match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+				out.push(byte as char)
+			}
+			_ => out.push_str(&format!("%{byte:02X}")),
+		}
+	}
+	out
+}
+
+/// The DOI of a work: the `doi` field, or else the first DOI identifier.
+pub fn doi(cff: &Cff) -> Option<&str> {
+	cff.doi.as_deref().or_else(|| {
+		cff.identifier_of_kind(IdentifierKind::Doi)
+			.map(Identifier::value)
+	})
+}
+
+/// A badge showing the DOI of the work, linking to its resolver URL.
+///
+/// Returns `None` if the work doesn't have a DOI (see [doi]).
+pub fn doi_badge(cff: &Cff) -> Option<Badge> {
+	doi(cff).map(|doi| Badge::shields("DOI", doi, "blue", format!("https://doi.org/{doi}")))
+}
+
+/// A "cite this" badge, linking to the citation metadata.
+///
+/// If `link` isn't given, this links to the `CITATION.cff` file at the root of
+/// the default branch of the `repository-code`, which works for GitHub, GitLab,
+/// and similar forges. Returns `None` if there's nowhere to link to.
+pub fn cite_badge(cff: &Cff, link: Option<&str>) -> Option<Badge> {
+	let link = match link {
+		Some(link) => link.to_string(),
+		None => {
+			let repo = cff.repository_code.as_ref()?;
+			format!(
+				"{}/blob/HEAD/CITATION.cff",
+				repo.as_str().trim_end_matches('/')
+			)
+		}
+	};
+
+	Some(Badge::shields("Cite this", "CITATION.cff", "green", link))
+}
@@ -0,0 +1,450 @@
+//! Conversion between BibTeX entries and CSL items or CFF references.
+//!
+//! BibTeX fields are mapped to CSL variables and back, with types mapped to the
+//! closest CSL type, names split into their parts, and TeX values converted to
+//! and from plain text with [tex]. BibTeX fields without a CSL equivalent are
+//! kept as item variables of the same name, and item variables with lowercase
+//! names which aren't otherwise converted are written as BibTeX fields, so
+//! entries survive going through CSL and back.
+//!
+//! CFF references are converted through CSL, see the [csl](crate::csl) and
+//! [cff](crate::cff) modules.
+//!
+//! ```
+//! use citeworks_convert::bibtex::{entry_to_item, item_to_entry};
+//! use citeworks_csl::items::ItemType;
+//!
+//! let entries = citeworks_bibtex::from_str(r#"
+//! @article{druskat2017,
+//!   author = {Druskat, Stephan},
+//!   title = {The {Citation File Format}},
+//!   journal = {Journal of Open Source Software},
+//!   pages = {1--8},
+//!   year = 2017,
+//! }
+//! "#).unwrap();
+//!
+//! let item = entry_to_item(&entries[0]);
+//! assert_eq!(item.item_type, ItemType::ArticleJournal);
+//! assert_eq!(item.title.as_ref().unwrap().to_string(), "The Citation File Format");
+//! assert_eq!(item.page.as_ref().unwrap().to_string(), "1-8");
+//!
+//! let entry = item_to_entry(&item);
+//! assert_eq!(entry.get("pages"), Some("1--8"));
+//! ```
+
+use std::collections::BTreeMap;
+
+use citeworks_bibtex::{
+	entries::MONTHS,
+	names::Name as BibName,
+	tex::{escape, to_text},
+	Entry, EntryType,
+};
+use citeworks_cff::references::Reference;
+use citeworks_csl::{
+	dates::{Date as CslDate, DateMeta, DateParts},
+	items::{ItemType, ItemValue},
+	names::Name as CslName,
+	ordinaries::OrdinaryValue,
+	Item,
+};
+
+use crate::{
+	cff::item_to_reference,
+	csl::{reference_to_item, references_to_items},
+	text::plain_text,
+};
+
+/// BibTeX fields which are converted to CSL variables of a different name.
+const MAPPED_FIELDS: &[&str] = &[
+	"address",
+	"booktitle",
+	"chapter",
+	"date",
+	"institution",
+	"isbn",
+	"journal",
+	"journaltitle",
+	"keywords",
+	"location",
+	"number",
+	"organization",
+	"pages",
+	"school",
+	"series",
+	"type",
+	"urldate",
+];
+
+/// CSL variables which are converted to BibTeX fields of a different name, or
+/// which would be misleading as BibTeX fields.
+const MAPPED_VARIABLES: &[&str] = &[
+	"chapter-number",
+	"collection-title",
+	"genre",
+	"ISBN",
+	"issue",
+	"keyword",
+	"number",
+	"publisher",
+	"publisher-place",
+	"status",
+];
+
+/// Convert a BibTeX entry to a CSL item, with the entry's key as id.
+pub fn entry_to_item(entry: &Entry) -> Item {
+	let text = |field: &str| entry.get(field).map(to_text);
+	let ordinary = |field: &str| text(field).map(OrdinaryValue::String);
+
+	let item_type = item_type(&entry.entry_type);
+	let container = entry
+		.get("journal")
+		.or_else(|| entry.get("journaltitle"))
+		.or_else(|| entry.get("booktitle"))
+		.map(|title| OrdinaryValue::String(to_text(title)));
+	let (issue, number) = match entry.entry_type {
+		EntryType::Article => (ordinary("number").or_else(|| ordinary("issue")), None),
+		_ => (ordinary("issue"), text("number")),
+	};
+
+	let mut item = Item {
+		id: entry.key.clone(),
+		item_type,
+		author: names(&entry.author),
+		editor: names(&entry.editor),
+		title: ordinary("title"),
+		container_title: container,
+		issued: entry
+			.get("date")
+			.and_then(iso_date)
+			.or_else(|| year_month(entry)),
+		accessed: entry.get("urldate").and_then(iso_date),
+		doi: entry.doi.clone().map(OrdinaryValue::String),
+		url: entry.url.clone().map(OrdinaryValue::String),
+		issn: ordinary("issn"),
+		volume: ordinary("volume"),
+		issue,
+		page: entry
+			.get("pages")
+			.map(|pages| OrdinaryValue::String(to_text(&pages.replace("--", "-")))),
+		abstract_text: ordinary("abstract"),
+		language: ordinary("language"),
+		note: ordinary("note"),
+		..Default::default()
+	};
+
+	let fields = &mut item.fields;
+	insert(fields, "collection-title", text("series"));
+	insert(
+		fields,
+		"publisher",
+		text("publisher")
+			.or_else(|| text("school"))
+			.or_else(|| text("institution"))
+			.or_else(|| text("organization")),
+	);
+	insert(
+		fields,
+		"publisher-place",
+		text("address").or_else(|| text("location")),
+	);
+	insert(fields, "number", number);
+	insert(fields, "ISBN", text("isbn"));
+	insert(fields, "keyword", text("keywords"));
+	insert(fields, "chapter-number", text("chapter"));
+	insert(
+		fields,
+		"genre",
+		text("type").or_else(|| match entry.entry_type {
+			EntryType::PhdThesis => Some("PhD thesis".into()),
+			EntryType::MastersThesis => Some("Master's thesis".into()),
+			_ => None,
+		}),
+	);
+
+	for (name, value) in &entry.fields {
+		let converted = MAPPED_FIELDS.contains(&name.as_str())
+			|| ["issn", "volume", "issue", "abstract", "language", "note"].contains(&name.as_str());
+		if !converted && !fields.contains_key(name) {
+			insert(fields, name, Some(to_text(value)));
+		}
+	}
+
+	item
+}
+
+/// Convert a CSL item to a BibTeX entry, with the item's id as key.
+pub fn item_to_entry(item: &Item) -> Entry {
+	let ordinary = |value: &Option<OrdinaryValue>| {
+		value
+			.as_ref()
+			.map(|value| escape(&plain_text(&value.to_string())))
+	};
+	let field = |name: &str| match item.fields.get(name) {
+		Some(ItemValue::Ordinary(value)) => Some(escape(&plain_text(&value.to_string()))),
+		_ => None,
+	};
+	let genre = field("genre");
+
+	let entry_type = match &item.item_type {
+		ItemType::Thesis
+			if genre
+				.as_deref()
+				.map_or(false, |genre| genre.to_lowercase().contains("master")) =>
+		{
+			EntryType::MastersThesis
+		}
+		ItemType::Thesis => EntryType::PhdThesis,
+		other => entry_type(other),
+	};
+
+	let mut entry = Entry {
+		key: item.id.clone(),
+		author: bib_names(&item.author),
+		editor: bib_names(&item.editor),
+		title: ordinary(&item.title),
+		doi: item.doi.as_ref().map(ToString::to_string),
+		url: item.url.as_ref().map(ToString::to_string),
+		..Default::default()
+	};
+
+	match &item.issued {
+		Some(CslDate::Single { date, .. }) | Some(CslDate::Range { start: date, .. }) => {
+			entry.year = Some(date.year.to_string());
+			entry.month = date
+				.month
+				.and_then(|month| MONTHS.get(usize::from(month).checked_sub(1)?))
+				.map(|month| month.to_string());
+			if date.day.is_some() {
+				entry.set("date", date_string(date));
+			}
+		}
+		Some(CslDate::Raw { date, .. }) | Some(CslDate::Edtf { date, .. }) => {
+			entry.year = Some(escape(date));
+		}
+		Some(CslDate::MetaOnly { meta }) => {
+			entry.year = meta.literal.as_deref().map(escape);
+		}
+		None => {}
+	}
+	if let Some(CslDate::Single { date, .. }) = &item.accessed {
+		entry.set("urldate", date_string(date));
+	}
+
+	let mut set = |name: &str, value: Option<String>| {
+		if let Some(value) = value {
+			entry.set(name, value);
+		}
+	};
+	let container = if matches!(
+		entry_type,
+		EntryType::Article | EntryType::Online | EntryType::Misc
+	) {
+		"journal"
+	} else {
+		"booktitle"
+	};
+	set(container, ordinary(&item.container_title));
+	set("series", field("collection-title"));
+	let publisher = match entry_type {
+		EntryType::PhdThesis | EntryType::MastersThesis => "school",
+		EntryType::TechReport => "institution",
+		_ => "publisher",
+	};
+	set(publisher, field("publisher"));
+	set("address", field("publisher-place"));
+	set("volume", ordinary(&item.volume));
+	set("number", ordinary(&item.issue).or_else(|| field("number")));
+	set(
+		"pages",
+		ordinary(&item.page).map(|pages| pages.replace(['-', '–'], "--")),
+	);
+	set("issn", ordinary(&item.issn));
+	set("isbn", field("ISBN"));
+	set("chapter", field("chapter-number"));
+	set("keywords", field("keyword"));
+	set("abstract", ordinary(&item.abstract_text));
+	set("language", ordinary(&item.language));
+	set("note", ordinary(&item.note));
+	if !matches!(
+		(&entry_type, genre.as_deref()),
+		(EntryType::PhdThesis, Some("PhD thesis"))
+			| (EntryType::MastersThesis, Some("Master's thesis"))
+	) {
+		set("type", genre);
+	}
+
+	for (name, value) in &item.fields {
+		let bibtex_name = name.bytes().all(|c| c.is_ascii_lowercase());
+		if bibtex_name && !MAPPED_VARIABLES.contains(&name.as_str()) && entry.get(name).is_none() {
+			if let ItemValue::Ordinary(value) = value {
+				entry.set(name, escape(&value.to_string()));
+			}
+		}
+	}
+
+	entry.entry_type = entry_type;
+	entry
+}
+
+/// Convert a BibTeX entry to a CFF reference, through CSL.
+pub fn entry_to_reference(entry: &Entry) -> Result<Reference, String> {
+	item_to_reference(entry_to_item(entry))
+}
+
+/// Convert a CFF reference to a BibTeX entry with the given key, through CSL.
+pub fn reference_to_entry(reference: &Reference, key: String) -> Entry {
+	item_to_entry(&reference_to_item(reference, key))
+}
+
+/// Convert CFF references to BibTeX entries.
+///
+/// Keys are generated as for [references_to_items].
+pub fn references_to_entries(references: &[Reference]) -> Vec<Entry> {
+	references_to_items(references)
+		.iter()
+		.map(item_to_entry)
+		.collect()
+}
+
+/// The closest CSL type for a BibTeX entry type.
+pub fn item_type(entry_type: &EntryType) -> ItemType {
+	match entry_type {
+		EntryType::Article => ItemType::ArticleJournal,
+		EntryType::Book | EntryType::Manual | EntryType::Proceedings => ItemType::Book,
+		EntryType::Booklet => ItemType::Pamphlet,
+		EntryType::InBook | EntryType::InCollection => ItemType::Chapter,
+		EntryType::InProceedings => ItemType::PaperConference,
+		EntryType::MastersThesis | EntryType::PhdThesis => ItemType::Thesis,
+		EntryType::TechReport => ItemType::Report,
+		EntryType::Unpublished => ItemType::Manuscript,
+		EntryType::Online => ItemType::Webpage,
+		EntryType::Software => ItemType::Software,
+		EntryType::Dataset => ItemType::Dataset,
+		EntryType::Misc | EntryType::Other(_) => ItemType::Document,
+	}
+}
+
+/// The closest BibTeX entry type for a CSL type.
+///
+/// Theses are `phdthesis`; [item_to_entry] also looks at the genre to find
+/// Master's theses.
+pub fn entry_type(item_type: &ItemType) -> EntryType {
+	match item_type {
+		ItemType::Article
+		| ItemType::ArticleJournal
+		| ItemType::ArticleMagazine
+		| ItemType::ArticleNewspaper
+		| ItemType::Review
+		| ItemType::ReviewBook => EntryType::Article,
+		ItemType::Book | ItemType::Classic | ItemType::Periodical => EntryType::Book,
+		ItemType::Chapter | ItemType::EntryDictionary | ItemType::EntryEncyclopedia => {
+			EntryType::InCollection
+		}
+		ItemType::PaperConference => EntryType::InProceedings,
+		ItemType::Pamphlet => EntryType::Booklet,
+		ItemType::Report => EntryType::TechReport,
+		ItemType::Thesis => EntryType::PhdThesis,
+		ItemType::Manuscript => EntryType::Unpublished,
+		ItemType::Webpage | ItemType::Post | ItemType::PostWeblog => EntryType::Online,
+		ItemType::Software => EntryType::Software,
+		ItemType::Dataset => EntryType::Dataset,
+		_ => EntryType::Misc,
+	}
+}
+
+/// Names of people and organisations; the `others` of `and others` is dropped.
+fn names(names: &[BibName]) -> Vec<CslName> {
+	names
+		.iter()
+		.filter(|name| !name.is_others())
+		.map(|name| match name.literal() {
+			Some(literal) => CslName {
+				literal: Some(to_text(literal)),
+				..Default::default()
+			},
+			None => CslName {
+				family: Some(to_text(&name.last)),
+				given: name.first.as_deref().map(to_text),
+				non_dropping_particle: name.von.as_deref().map(to_text),
+				suffix: name.jr.as_deref().map(to_text),
+				..Default::default()
+			},
+		})
+		.collect()
+}
+
+fn bib_names(names: &[CslName]) -> Vec<BibName> {
+	names
+		.iter()
+		.filter_map(|name| match (&name.family, &name.literal) {
+			(Some(family), _) => Some(BibName {
+				first: name
+					.given
+					.iter()
+					.chain(&name.dropping_particle)
+					.map(|part| escape(part))
+					.reduce(|given, particle| format!("{given} {particle}")),
+				von: name.non_dropping_particle.as_deref().map(escape),
+				last: escape(family),
+				jr: name.suffix.as_deref().map(escape),
+			}),
+			(None, Some(literal)) => Some(BibName {
+				last: format!("{{{}}}", escape(literal)),
+				..Default::default()
+			}),
+			(None, None) => Some(BibName {
+				last: escape(name.given.as_deref()?),
+				..Default::default()
+			}),
+		})
+		.collect()
+}
+
+fn year_month(entry: &Entry) -> Option<CslDate> {
+	let meta = DateMeta::default();
+	match entry.year_number() {
+		Some(year) => Some(CslDate::Single {
+			date: DateParts {
+				year,
+				month: entry.month_number(),
+				day: None,
+			},
+			meta,
+		}),
+		None => Some(CslDate::Raw {
+			date: to_text(entry.year.as_deref()?),
+			meta,
+		}),
+	}
+}
+
+/// Dates like biblatex's `date` and `urldate`: `YYYY`, `YYYY-MM`, or `YYYY-MM-DD`.
+fn iso_date(date: &str) -> Option<CslDate> {
+	let mut parts = date.trim().splitn(3, '-');
+	let year = parts.next()?.parse().ok()?;
+	let month = parts.next().map(str::parse).transpose().ok()?;
+	let day = parts.next().map(str::parse).transpose().ok()?;
+	Some(CslDate::Single {
+		date: DateParts { year, month, day },
+		meta: DateMeta::default(),
+	})
+}
+
+fn date_string(date: &DateParts) -> String {
+	match (date.month, date.day) {
+		(Some(month), Some(day)) => format!("{:04}-{month:02}-{day:02}", date.year),
+		(Some(month), None) => format!("{:04}-{month:02}", date.year),
+		_ => format!("{:04}", date.year),
+	}
+}
+
+fn insert(fields: &mut BTreeMap<String, ItemValue>, name: &str, value: Option<String>) {
+	if let Some(value) = value {
+		fields.insert(
+			name.into(),
+			ItemValue::Ordinary(OrdinaryValue::String(value)),
+		);
+	}
+}
@@ -0,0 +1,202 @@
+//! Conversion from Cargo package metadata.
+//!
+//! This reads the `[package]` table of a `Cargo.toml`: the name is the title,
+//! the description the abstract, the repository the code repository (or
+//! another repository field if its host is for artifacts or archives, see
+//! [classify_repository]), and the homepage the URL. Authors are written as `Name <email>` in Cargo, without
+//! saying which part of the name is the family name, so they're kept as names
+//! (CFF entities) with their email.
+//!
+//! Fields inherited from the workspace, like `version.workspace = true`, are
+//! left out, unless they're filled in first with [inherit_workspace].
+//!
+//! ```
+//! use citeworks_cff::Date;
+//! use citeworks_convert::cargo::{cargo_to_cff, update_cff_from_cargo};
+//!
+//! let released = Date { year: 2022, month: 8, day: 20 };
+//! let mut cff = cargo_to_cff(r#"
+//! [package]
+//! name = "citeworks-cff"
+//! version = "0.1.1"
+//! authors = ["Félix Saparelli <felix@passcod.name>"]
+//! license = "Apache-2.0"
+//! "#, released).unwrap();
+//!
+//! assert_eq!(cff.title, "citeworks-cff");
+//! assert_eq!(cff.version.as_deref(), Some("0.1.1"));
+//! assert_eq!(cff.date_released, Some(released));
+//!
+//! let later = Date { year: 2022, month: 9, day: 1 };
+//! let bumped = update_cff_from_cargo(&mut cff, r#"
+//! [package]
+//! name = "citeworks-cff"
+//! version = "0.2.0"
+//! "#, later).unwrap();
+//!
+//! assert!(bumped);
+//! assert_eq!(cff.version.as_deref(), Some("0.2.0"));
+//! assert_eq!(cff.date_released, Some(later));
+//! ```
+
+use std::str::FromStr;
+
+use citeworks_cff::{
+	names::{EntityName, Name, NameMeta},
+	repositories::{classify_repository, RepositoryKind},
+	Cff, Date, License,
+};
+use serde::Deserialize;
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+	package: Option<Package>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Package {
+	name: String,
+	version: Option<Field<String>>,
+	description: Option<Field<String>>,
+	authors: Option<Field<Vec<String>>>,
+	license: Option<Field<String>>,
+	repository: Option<Field<String>>,
+	homepage: Option<Field<String>>,
+	keywords: Option<Field<Vec<String>>>,
+}
+
+/// A field which may be inherited from the workspace.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Field<T> {
+	Value(T),
+	Workspace {
+		#[allow(dead_code)]
+		workspace: bool,
+	},
+}
+
+impl<T> Field<T> {
+	fn value(field: Option<Self>) -> Option<T> {
+		match field {
+			Some(Self::Value(value)) => Some(value),
+			_ => None,
+		}
+	}
+}
+
+fn package(manifest: &str) -> Result<Package, String> {
+	let manifest: Manifest = toml::from_str(manifest).map_err(|err| err.to_string())?;
+	manifest
+		.package
+		.ok_or_else(|| "Cargo.toml has no [package]".into())
+}
+
+/// Make a CFF document from a `Cargo.toml`, released on the given date.
+///
+/// Fails if the manifest can't be parsed or has no `[package]`. The document
+/// may have no authors, which CFF requires, if the package doesn't have any.
+pub fn cargo_to_cff(manifest: &str, released: Date) -> Result<Cff, String> {
+	let package = package(manifest)?;
+
+	let mut cff = Cff {
+		title: package.name,
+		version: Field::value(package.version),
+		date_released: Some(released),
+		abstract_text: Field::value(package.description),
+		authors: Field::value(package.authors)
+			.unwrap_or_default()
+			.iter()
+			.map(|author| author_name(author))
+			.collect(),
+		license: Field::value(package.license).and_then(|license| license_expression(&license)),
+		url: Field::value(package.homepage).and_then(|url| Url::parse(&url).ok()),
+		keywords: Field::value(package.keywords).unwrap_or_default(),
+		..Default::default()
+	};
+
+	if let Some(url) = Field::value(package.repository).and_then(|url| Url::parse(&url).ok()) {
+		let kind = classify_repository(&url).unwrap_or(RepositoryKind::Code);
+		cff.set_repository(kind, url);
+	}
+
+	Ok(cff)
+}
+
+/// Update the version of a CFF document from a `Cargo.toml`.
+///
+/// If the package's version differs from the document's, it replaces it and
+/// the release date is set to the given date; returns whether it did. Other
+/// fields are kept as they are, as they may have been edited since.
+pub fn update_cff_from_cargo(
+	cff: &mut Cff,
+	manifest: &str,
+	released: Date,
+) -> Result<bool, String> {
+	let version = Field::value(package(manifest)?.version);
+	if version.is_none() || version == cff.version {
+		return Ok(false);
+	}
+
+	cff.version = version;
+	cff.date_released = Some(released);
+	Ok(true)
+}
+
+/// An author from its `Name <email>` form.
+fn author_name(author: &str) -> Name {
+	let (name, email) = match author.split_once('<') {
+		Some((name, email)) => (name, Some(email.trim_end().trim_end_matches('>'))),
+		None => (author, None),
+	};
+
+	Name::Entity(EntityName {
+		name: Some(name.trim().into()),
+		meta: NameMeta {
+			email: email.map(String::from),
+			..Default::default()
+		},
+		..Default::default()
+	})
+}
+
+/// A license expression, also reading the deprecated `MIT/Apache-2.0` form.
+fn license_expression(license: &str) -> Option<License> {
+	License::from_str(&license.replace('/', " OR ")).ok()
+}
+
+/// Fill in the fields a package inherits from its workspace.
+///
+/// Fields like `version.workspace = true` in the `[package]` of the manifest are
+/// replaced with their value in the `[workspace.package]` of the workspace's
+/// manifest, and the manifest is returned with them. Fields the workspace
+/// doesn't have are left as they are.
+pub fn inherit_workspace(manifest: &str, workspace_manifest: &str) -> Result<String, String> {
+	let mut manifest: toml::Value = toml::from_str(manifest).map_err(|err| err.to_string())?;
+	let workspace: toml::Value =
+		toml::from_str(workspace_manifest).map_err(|err| err.to_string())?;
+	let inherited = workspace
+		.get("workspace")
+		.and_then(|workspace| workspace.get("package"))
+		.and_then(toml::Value::as_table);
+
+	if let (Some(package), Some(inherited)) = (
+		manifest
+			.get_mut("package")
+			.and_then(toml::Value::as_table_mut),
+		inherited,
+	) {
+		for (key, value) in package.iter_mut() {
+			let inherits = value
+				.get("workspace")
+				.and_then(toml::Value::as_bool)
+				.unwrap_or(false);
+			if let (true, Some(inherited)) = (inherits, inherited.get(key)) {
+				*value = inherited.clone();
+			}
+		}
+	}
+
+	toml::to_string(&manifest).map_err(|err| err.to_string())
+}
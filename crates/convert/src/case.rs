@@ -0,0 +1,242 @@
+//! Sentence case and title case for titles.
+//!
+//! Styles differ on how titles are cased: APA and most CSL styles want
+//! sentence case, while BibTeX databases are usually in title case. These
+//! convert between the two, leaving alone what they can't safely change:
+//!
+//! - protected spans, as CSL-JSON `<span class="nocase">` or BibTeX braces;
+//! - acronyms and words with capitals inside them, like `DNA` or `iPhone`;
+//! - words with digits, like `3D`;
+//! - and, in title case, small words and name particles, like `of` or `van`.
+//!
+//! ```
+//! use citeworks_convert::case::{sentence_case, title_case};
+//!
+//! assert_eq!(
+//!     sentence_case("The Structure of DNA: A {Bayesian} Approach"),
+//!     "The structure of DNA: A {Bayesian} approach"
+//! );
+//! assert_eq!(
+//!     title_case("a history of the <span class=\"nocase\">iPod</span> in self-driving cars"),
+//!     "A History of the <span class=\"nocase\">iPod</span> in Self-Driving Cars"
+//! );
+//! ```
+//!
+//! Titles entirely in capitals are lowercased first, so their acronyms are lost.
+
+/// Words kept lowercase inside titles in title case.
+///
+/// These are the articles, conjunctions, and short prepositions from the CSL
+/// specification's list of stop words, and common name particles.
+const SMALL_WORDS: &[&str] = &[
+	"a", "an", "and", "as", "at", "but", "by", "da", "de", "del", "della", "den", "der", "di",
+	"du", "for", "from", "in", "into", "la", "le", "nor", "of", "on", "onto", "or", "over", "per",
+	"so", "ten", "ter", "than", "the", "to", "up", "upon", "van", "via", "von", "vs", "with",
+	"yet", "zu",
+];
+
+/// Start of a CSL-JSON protected span.
+const NOCASE: &str = "<span class=\"nocase\">";
+
+/// Convert a title to sentence case.
+///
+/// The first word, and words after a colon, question mark, or exclamation
+/// mark, start with a capital; other capitalised words are lowercased.
+pub fn sentence_case(title: &str) -> String {
+	recase(title, Case::Sentence)
+}
+
+/// Convert a title to title case.
+///
+/// Lowercase words are capitalised, including each part of hyphenated words,
+/// except small words and name particles inside the title.
+pub fn title_case(title: &str) -> String {
+	recase(title, Case::Title)
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Case {
+	Sentence,
+	Title,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Token<'s> {
+	Protected(&'s str),
+	Space(&'s str),
+	Word(&'s str),
+}
+
+fn recase(title: &str, case: Case) -> String {
+	let tokens = tokens(title);
+	let shouting = tokens.iter().all(|token| match token {
+		Token::Word(word) => !word.chars().any(char::is_lowercase),
+		_ => true,
+	});
+	let last = tokens
+		.iter()
+		.rposition(|token| !matches!(token, Token::Space(_)));
+
+	let mut out = String::with_capacity(title.len());
+	let mut start = true;
+	let mut previous = None;
+	for (index, token) in tokens.iter().enumerate() {
+		match *token {
+			Token::Protected(text) | Token::Space(text) => out.push_str(text),
+			// Words joined to a protected span are part of it, like `{DNA}-based`.
+			Token::Word(word) if matches!(previous, Some(Token::Protected(_))) => {
+				out.push_str(word)
+			}
+			Token::Word(word) => {
+				let lowered;
+				let word = if shouting {
+					lowered = word.to_lowercase();
+					&lowered
+				} else {
+					word
+				};
+				recase_word(word, case, start, Some(index) == last, &mut out);
+			}
+		}
+
+		if let Token::Protected(text) | Token::Word(text) = token {
+			start = text.ends_with([':', '?', '!']);
+		}
+		previous = Some(*token);
+	}
+	out
+}
+
+fn recase_word(word: &str, case: Case, start: bool, last: bool, out: &mut String) {
+	let parts = word.split_inclusive(['-', '/']).collect::<Vec<_>>();
+	for (index, part) in parts.iter().enumerate() {
+		let first = start && index == 0;
+		let core = part.trim_matches(|c: char| !c.is_alphabetic());
+		let edge = first || (last && index == parts.len() - 1);
+		let small = !edge && SMALL_WORDS.contains(&core.to_lowercase().as_str());
+
+		match (case, shape(part)) {
+			(_, Shape::Other) => out.push_str(part),
+			(Case::Sentence, Shape::Lower) if first => out.push_str(&capitalise(part)),
+			(Case::Sentence, Shape::Capitalised)
+				if !first && core != "I" && !core.starts_with("I'") =>
+			{
+				out.push_str(&decapitalise(part))
+			}
+			(Case::Title, Shape::Lower) if !small => out.push_str(&capitalise(part)),
+			(Case::Title, Shape::Capitalised) if small => out.push_str(&decapitalise(part)),
+			_ => out.push_str(part),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Shape {
+	/// Only lowercase letters, like `word`
+	Lower,
+
+	/// A capital then lowercase letters, like `Word`
+	Capitalised,
+
+	/// Acronyms, words with digits or inner capitals, and anything else
+	Other,
+}
+
+fn shape(part: &str) -> Shape {
+	if part.chars().any(|c| c.is_numeric()) {
+		return Shape::Other;
+	}
+
+	let mut letters = part.chars().filter(|c| c.is_alphabetic());
+	let first = match letters.next() {
+		Some(first) => first,
+		None => return Shape::Other,
+	};
+	if !letters.all(char::is_lowercase) {
+		Shape::Other
+	} else if first.is_lowercase() {
+		Shape::Lower
+	} else if first.is_uppercase() {
+		Shape::Capitalised
+	} else {
+		Shape::Other
+	}
+}
+
+fn capitalise(part: &str) -> String {
+	map_first_letter(part, |c, out| out.extend(c.to_uppercase()))
+}
+
+fn decapitalise(part: &str) -> String {
+	map_first_letter(part, |c, out| out.extend(c.to_lowercase()))
+}
+
+fn map_first_letter(part: &str, f: impl FnOnce(char, &mut String)) -> String {
+	let mut out = String::with_capacity(part.len());
+	let mut f = Some(f);
+	for c in part.chars() {
+		match f.take() {
+			Some(f) if c.is_alphabetic() => f(c, &mut out),
+			other => {
+				f = other;
+				out.push(c);
+			}
+		}
+	}
+	out
+}
+
+fn tokens(s: &str) -> Vec<Token<'_>> {
+	let mut tokens = Vec::new();
+	let mut rest = s;
+	while let Some(c) = rest.chars().next() {
+		let (token, len) = if let Some(len) = protected_len(rest) {
+			(Token::Protected(&rest[..len]), len)
+		} else if c.is_whitespace() {
+			let len = rest
+				.find(|c: char| !c.is_whitespace())
+				.unwrap_or(rest.len());
+			(Token::Space(&rest[..len]), len)
+		} else {
+			let len = rest
+				.char_indices()
+				.skip(1)
+				.find(|(at, c)| c.is_whitespace() || protected_len(&rest[*at..]).is_some())
+				.map_or(rest.len(), |(at, _)| at);
+			(Token::Word(&rest[..len]), len)
+		};
+		tokens.push(token);
+		rest = &rest[len..];
+	}
+	tokens
+}
+
+/// Length of the protected span at the start of `s`, if there's one.
+fn protected_len(s: &str) -> Option<usize> {
+	let (open, close) = if s.starts_with('{') {
+		("{", "}")
+	} else if s.starts_with(NOCASE) {
+		("<span", "</span>")
+	} else {
+		return None;
+	};
+
+	let mut depth = 0_usize;
+	let mut at = 0;
+	while at < s.len() {
+		let rest = &s[at..];
+		if rest.starts_with(open) {
+			depth += 1;
+			at += open.len();
+		} else if rest.starts_with(close) {
+			depth -= 1;
+			at += close.len();
+			if depth == 0 {
+				return Some(at);
+			}
+		} else {
+			at += rest.chars().next().map_or(1, char::len_utf8);
+		}
+	}
+	None
+}
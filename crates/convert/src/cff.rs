@@ -0,0 +1,831 @@
+//! Conversion from CSL-JSON items to CFF references.
+//!
+//! This is the reverse of the [csl](crate::csl) module. Each item becomes one
+//! reference, with CSL types mapped to CFF types, names split into people and
+//! entities, page ranges split into start and end, and so on. How some values
+//! are handled can be changed with [ConvertOptions]; what's dropped or guessed
+//! is reported to a [WarningSink].
+//!
+//! ```
+//! use citeworks_cff::references::RefType;
+//! use citeworks_convert::cff::item_to_reference;
+//! use citeworks_csl::{items::ItemType, ordinaries::OrdinaryValue, Item};
+//!
+//! let item = Item {
+//!     id: "vaidya2018".into(),
+//!     item_type: ItemType::ArticleJournal,
+//!     title: Some(OrdinaryValue::String("3D printed optics".into())),
+//!     page: Some(OrdinaryValue::String("1-8".into())),
+//!     ..Default::default()
+//! };
+//!
+//! let reference = item_to_reference(item).unwrap();
+//! assert_eq!(reference.work_type, RefType::Article);
+//! assert_eq!(reference.title.as_deref(), Some("3D printed optics"));
+//! assert_eq!((reference.start, reference.end), (Some(1), Some(8)));
+//! ```
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use citeworks_cff::{
+	identifiers::Identifier,
+	names::{EntityName, Name as CffName, NameMeta, PersonName},
+	references::{RefType, Reference},
+	Date as CffDate,
+};
+use citeworks_csl::{
+	dates::{Circa, Date as CslDate, DateParts as CslDateParts, Hemisphere},
+	items::{ItemType, ItemValue},
+	names::Name as CslName,
+	ordinaries::OrdinaryValue,
+	Item,
+};
+use url::Url;
+
+use crate::{
+	csl::{bare_doi, parse_status, status as status_name},
+	options::{BadUrls, ConvertOptions},
+	report::{audit, send, unmapped, IgnoreWarnings, Mapping, RecordReport, WarningSink},
+	text::plain_text,
+};
+
+/// Convert a CSL item to a CFF reference, with the default options.
+///
+/// This only fails with [BadUrls::Strict], which isn't the default.
+pub fn item_to_reference(item: Item) -> Result<Reference, String> {
+	item_to_reference_with(item, &ConvertOptions::default(), &mut IgnoreWarnings)
+}
+
+/// Convert a CSL item to a CFF reference.
+///
+/// Variables which can't be converted are reported to the sink, under the id
+/// of the item. Fails if a URL can't be parsed and the options say to fail.
+pub fn item_to_reference_with(
+	item: Item,
+	options: &ConvertOptions,
+	report: &mut dyn WarningSink,
+) -> Result<Reference, String> {
+	let mut record = RecordReport::new(item.id.clone());
+	let source = if report.audits() {
+		serde_json::to_value(&item).ok()
+	} else {
+		None
+	};
+	let reference = convert_item(item, options, &mut record)?;
+	if let (Some(source), Ok(target)) = (source, serde_json::to_value(&reference)) {
+		audit(&mut record, &source, &target, AUDITED);
+	}
+	send(report, record);
+	Ok(reference)
+}
+
+/// Convert CSL items to CFF references.
+///
+/// See [item_to_reference_with]. Fails on the first item that can't be
+/// converted, with its id in the error.
+pub fn items_to_references_with(
+	items: impl IntoIterator<Item = Item>,
+	options: &ConvertOptions,
+	report: &mut dyn WarningSink,
+) -> Result<Vec<Reference>, String> {
+	items
+		.into_iter()
+		.map(|item| {
+			let id = item.id.clone();
+			item_to_reference_with(item, options, report)
+				.map_err(|err| format!("converting {id}: {err}"))
+		})
+		.collect()
+}
+
+/// CSL variables which are converted.
+const MAPPED_KEYS: &[&str] = &[
+	"id",
+	"type",
+	"author",
+	"contributor",
+	"editor",
+	"translator",
+	"recipient",
+	"container-author",
+	"title",
+	"title-short",
+	"abstract",
+	"container-title",
+	"rights",
+	"license",
+	"source",
+	"accessed",
+	"published",
+	"issued",
+	"original-date",
+	"publisher",
+	"publisher-place",
+	"event",
+	"event-title",
+	"event-place",
+	"event-date",
+	"ISBN",
+	"edition",
+	"status",
+	"DOI",
+	"page",
+	"ISSN",
+	"EISSN",
+	"ISSNL",
+	"issue",
+	"journalAbbreviation",
+	"category",
+	"keyword",
+	"language",
+	"note",
+	"URL",
+	"volume",
+];
+
+/// How CSL variables are converted to reference keys, for audits.
+const AUDITED: &[Mapping] = &[
+	(&["type"], "type", "type mapping"),
+	(&["author", "contributor"], "authors", "names"),
+	(&["editor"], "editors", "names"),
+	(&["container-author"], "editors-series", "names"),
+	(&["recipient"], "recipients", "names"),
+	(&["translator"], "translators", "names"),
+	(&["title"], "title", ""),
+	(&["title-short"], "abbreviation", ""),
+	(&["abstract"], "abstract", "markup removed"),
+	(&["container-title"], "collection-title", ""),
+	(&["rights"], "copyright", ""),
+	(&["license"], "copyright", ""),
+	(&["source"], "database", ""),
+	(&["accessed"], "date-accessed", "date"),
+	(&["published"], "date-published", "date"),
+	(&["issued"], "year", "year of the date"),
+	(&["published"], "year", "year of the date"),
+	(&["issued"], "month", "month of the date"),
+	(&["published"], "month", "month of the date"),
+	(&["issued"], "issue-date", "date as text"),
+	(&["original-date"], "year-original", "year of the date"),
+	(&["publisher", "publisher-place"], "publisher", "entity"),
+	(&["publisher", "publisher-place"], "institution", "entity"),
+	(
+		&["event", "event-title", "event-place", "event-date"],
+		"conference",
+		"entity",
+	),
+	(&["ISBN"], "isbn", ""),
+	(&["edition"], "edition", ""),
+	(&["status"], "status", "status mapping"),
+	(&["DOI"], "doi", ""),
+	(&["page"], "start", "first page"),
+	(&["page"], "end", "last page"),
+	(&["ISSN"], "issn", ""),
+	(&["EISSN", "ISSNL"], "identifiers", "identifiers"),
+	(&["issue"], "issue", ""),
+	(&["journalAbbreviation"], "journal", ""),
+	(&["category", "keyword"], "keywords", "split into keywords"),
+	(&["language"], "languages", "language code"),
+	(&["note"], "notes", ""),
+	(&["URL"], "url", ""),
+	(&["volume"], "volume", "number"),
+];
+
+pub(crate) fn convert_item(
+	item: Item,
+	options: &ConvertOptions,
+	record: &mut RecordReport,
+) -> Result<Reference, String> {
+	unmapped(&item, MAPPED_KEYS, "no CFF equivalent", record);
+	if item.rights.is_some() && item.license.is_some() {
+		record.dropped("license", "copyright taken by rights");
+	}
+
+	let (date_published, year, month) = match (item.issued.as_ref(), item.published.as_ref()) {
+		(None, Some(published)) => {
+			convert_partial_date(published, "published", options.hemisphere, record)
+		}
+		(issued, published) => {
+			let (year, month) = issued.map_or((None, None), |issued| {
+				year_month(issued, "issued", options.hemisphere, record)
+			});
+			(convert_date(published, "published", record), year, month)
+		}
+	};
+	let publisher = convert_publisher(&item.fields, record);
+	let (publisher, institution) = if item.item_type == ItemType::Thesis {
+		(None, publisher)
+	} else {
+		(publisher, None)
+	};
+	let work_type = match options.type_mappings.get(&item.item_type) {
+		Some(work_type) => work_type.clone(),
+		None => {
+			if let ItemType::Other(item_type) = &item.item_type {
+				record.guessed(
+					"type",
+					"generic",
+					format!("no CFF type for {item_type:?}, which isn't a CSL type"),
+				);
+			}
+			ref_type(&item.item_type)
+		}
+	};
+	let conference = convert_event(&item.fields, record);
+	let isbn = field_string(&item.fields, "ISBN");
+	let edition = field_string(&item.fields, "edition");
+	let status = field_string(&item.fields, "status").and_then(|status| {
+		parse_status(&status).or_else(|| match options.status_fallback {
+			Some(fallback) => {
+				record.guessed(
+					"status",
+					status_name(fallback),
+					format!("unrecognised status {status:?}, used the fallback"),
+				);
+				Some(fallback)
+			}
+			None => {
+				record.dropped("status", format!("unrecognised status {status:?}"));
+				None
+			}
+		})
+	});
+	let keywords = split_keywords(
+		ov_string(item.category.clone())
+			.into_iter()
+			.chain(field_string(&item.fields, "keyword")),
+		&options.keyword_separators,
+	);
+
+	let mut identifiers = extra_idents(ov_string(item.eissn), ov_string(item.issnl));
+	let url = match ov_string(item.url) {
+		Some(url) => convert_url(url, options.bad_urls, &mut identifiers, record)?,
+		None => None,
+	};
+
+	let year_original = match item.fields.get("original-date") {
+		Some(ItemValue::Date(date)) => {
+			single_date(date, "original-date", record).map(|parts| parts.year)
+		}
+		_ => None,
+	};
+
+	Ok(Reference {
+		work_type,
+		authors: convert_authors(item.author.into_iter().chain(item.contributor), record),
+		editors: convert_names(item.editor, record),
+		editors_series: convert_names(item.container_author, record),
+		recipients: convert_names(item.recipient, record),
+		translators: convert_names(item.translator, record),
+		abbreviation: ov_string(item.title_short),
+		abstract_text: ov_string(item.abstract_text).map(|text| plain_text(&text)),
+		collection_title: ov_string(item.container_title),
+		copyright: ov_string(item.rights).or_else(|| ov_string(item.license)),
+		database: ov_string(item.source),
+		date_accessed: convert_date(item.accessed.as_ref(), "accessed", record),
+		date_published,
+		year,
+		year_original,
+		month,
+		publisher,
+		institution,
+		conference,
+		isbn: isbn.map(Into::into),
+		edition,
+		status,
+		doi: ov_string(item.doi).map(|doi| {
+			if options.normalise_dois {
+				bare_doi(&doi).to_string()
+			} else {
+				doi
+			}
+		}),
+		start: page_start(ov_string(item.page.clone())),
+		end: page_end(ov_string(item.page.clone())),
+		identifiers,
+		issn: ov_string(item.issn).map(Into::into),
+		issue: ov_string(item.issue),
+		issue_date: item.issued.as_ref().map(date_string),
+		journal: ov_string(item.journal_abbrevation),
+		keywords,
+		languages: ov_string(item.language)
+			.and_then(|tag| convert_language(&tag, record))
+			.map_or_else(Vec::new, |c| vec![c]),
+		notes: ov_string(item.note),
+		title: ov_string(item.title),
+		url,
+		volume: ov_string(item.volume).and_then(|v| match u64::from_str(&v) {
+			Ok(vol) => Some(vol),
+			Err(err) => {
+				record.dropped("volume", format!("could not parse {v:?} as number: {err}"));
+				None
+			}
+		}),
+		..Default::default()
+	})
+}
+
+fn convert_url(
+	url: String,
+	handling: BadUrls,
+	identifiers: &mut Vec<Identifier>,
+	record: &mut RecordReport,
+) -> Result<Option<Url>, String> {
+	let err = match Url::parse(&url) {
+		Ok(parsed) => return Ok(Some(parsed)),
+		Err(err) => err,
+	};
+
+	match handling {
+		BadUrls::Drop => {
+			record.dropped("URL", format!("could not parse URL {url:?}: {err}"));
+			Ok(None)
+		}
+		BadUrls::Keep => {
+			record.warn(format!(
+				"could not parse URL {url:?}: {err}, kept it as an identifier"
+			));
+			identifiers.push(Identifier::Other {
+				value: url,
+				description: Some("URL".into()),
+			});
+			Ok(None)
+		}
+		BadUrls::Repair => match repair_url(&url) {
+			Some(repaired) => {
+				record.guessed("url", repaired.as_str(), format!("repaired URL {url:?}"));
+				Ok(Some(repaired))
+			}
+			None => {
+				record.dropped(
+					"URL",
+					format!("could not parse or repair URL {url:?}: {err}"),
+				);
+				Ok(None)
+			}
+		},
+		BadUrls::Strict => Err(format!("could not parse URL {url:?}: {err}")),
+	}
+}
+
+/// Try to fix common problems with URLs: missing schemes and unencoded spaces.
+fn repair_url(url: &str) -> Option<Url> {
+	let url = url.trim().replace(' ', "%20");
+	match Url::parse(&url) {
+		Ok(parsed) => Some(parsed),
+		Err(url::ParseError::RelativeUrlWithoutBase)
+			if url
+				.split('/')
+				.next()
+				.map_or(false, |host| host.contains('.')) =>
+		{
+			Url::parse(&format!("https://{url}")).ok()
+		}
+		Err(_) => None,
+	}
+}
+
+/// The CFF type for a CSL type.
+///
+/// Types without a CFF equivalent are converted to [RefType::Generic].
+pub fn ref_type(item_type: &ItemType) -> RefType {
+	match item_type {
+		ItemType::Article => RefType::Article,
+		ItemType::ArticleJournal => RefType::Article,
+		ItemType::ArticleMagazine => RefType::MagazineArticle,
+		ItemType::ArticleNewspaper => RefType::NewspaperArticle,
+		ItemType::Bill => RefType::Bill,
+		ItemType::Book => RefType::Book,
+		ItemType::Broadcast => RefType::Generic,
+		ItemType::Chapter => RefType::Book,
+		ItemType::Classic => RefType::Generic,
+		ItemType::Collection => RefType::Generic,
+		ItemType::Dataset => RefType::Data,
+		ItemType::Document => RefType::Generic,
+		ItemType::Entry => RefType::Generic,
+		ItemType::EntryDictionary => RefType::Dictionary,
+		ItemType::EntryEncyclopedia => RefType::Encyclopedia,
+		ItemType::Figure => RefType::Generic,
+		ItemType::Graphic => RefType::Generic,
+		ItemType::Hearing => RefType::Hearing,
+		ItemType::Interview => RefType::Generic,
+		ItemType::LegalCase => RefType::LegalCase,
+		ItemType::Legislation => RefType::GovernmentDocument,
+		ItemType::Manuscript => RefType::Generic,
+		ItemType::Map => RefType::Map,
+		ItemType::MotionPicture => RefType::Video,
+		ItemType::MusicalScore => RefType::Music,
+		ItemType::Pamphlet => RefType::Pamphlet,
+		ItemType::PaperConference => RefType::ConferencePaper,
+		ItemType::Patent => RefType::Patent,
+		ItemType::Performance => RefType::Generic,
+		ItemType::Periodical => RefType::Generic,
+		ItemType::PersonalCommunication => RefType::PersonalCommunication,
+		ItemType::Post => RefType::Blog,
+		ItemType::PostWeblog => RefType::Blog,
+		ItemType::Regulation => RefType::Statute,
+		ItemType::Report => RefType::Report,
+		ItemType::Review => RefType::Generic,
+		ItemType::ReviewBook => RefType::Generic,
+		ItemType::Software => RefType::Software,
+		ItemType::Song => RefType::Music,
+		ItemType::Speech => RefType::SoundRecording,
+		ItemType::Standard => RefType::Standard,
+		ItemType::Thesis => RefType::Thesis,
+		ItemType::Treaty => RefType::GovernmentDocument,
+		ItemType::Webpage => RefType::Website,
+		ItemType::Gazette => RefType::Generic,
+		ItemType::Video => RefType::Video,
+		ItemType::LegalCommentary => RefType::Generic,
+		ItemType::Other(_) => RefType::Generic,
+	}
+}
+
+fn convert_authors(csl: impl Iterator<Item = CslName>, record: &mut RecordReport) -> Vec<CffName> {
+	let mut authors = convert_names(csl.collect(), record);
+	if authors.is_empty() {
+		authors.push(CffName::Anonymous);
+	}
+	authors
+}
+
+fn convert_names(csl: Vec<CslName>, record: &mut RecordReport) -> Vec<CffName> {
+	csl.into_iter()
+		.map(|name| convert_name(name, record))
+		.collect()
+}
+
+fn convert_name(csl_name: CslName, record: &mut RecordReport) -> CffName {
+	let meta = NameMeta {
+		orcid: csl_name.orcid().and_then(|orcid| match orcid_url(orcid) {
+			Some(url) => Some(url),
+			None => {
+				record.dropped("ORCID", format!("not an ORCID iD: {orcid:?}"));
+				None
+			}
+		}),
+		..Default::default()
+	};
+
+	if csl_name.family.is_some() || csl_name.given.is_some() {
+		CffName::Person(PersonName {
+			family_names: csl_name.family,
+			given_names: csl_name.given,
+			name_particle: csl_name.non_dropping_particle,
+			name_suffix: csl_name.suffix,
+			meta,
+			..Default::default()
+		})
+	} else if csl_name.literal.is_some() {
+		CffName::Entity(EntityName {
+			name: csl_name.literal,
+			meta,
+			..Default::default()
+		})
+	} else {
+		let name = format!("{:?}", csl_name);
+		record.guessed(
+			"authors",
+			&name,
+			"name has no family, given, or literal parts, using debug repr",
+		);
+		CffName::Entity(EntityName {
+			name: Some(name),
+			meta,
+			..Default::default()
+		})
+	}
+}
+
+/// The `https://orcid.org/` URL of an ORCID iD, given bare or as a URL.
+///
+/// URLs on any other host, including the ORCID sandbox, are refused, as CFF
+/// only allows `https://orcid.org/` URLs.
+fn orcid_url(orcid: &str) -> Option<Url> {
+	let id = orcid.trim();
+	let id = match Url::parse(id) {
+		Ok(url) if url.host_str() == Some("orcid.org") => url.path().trim_matches('/').to_string(),
+		Ok(_) => return None,
+		Err(_) => id.to_string(),
+	};
+
+	let digits: Vec<char> = id.chars().filter(|c| *c != '-').collect();
+	let valid = id.len() == 19
+		&& digits.len() == 16
+		&& digits[..15].iter().all(char::is_ascii_digit)
+		&& (digits[15].is_ascii_digit() || digits[15] == 'X');
+	if valid {
+		Url::parse(&format!("https://orcid.org/{id}")).ok()
+	} else {
+		None
+	}
+}
+
+fn ov_string(ov: Option<OrdinaryValue>) -> Option<String> {
+	ov.map(|v| v.to_string())
+}
+
+/// An ordinary CSL variable which doesn't have its own field on [Item].
+fn field_string(fields: &BTreeMap<String, ItemValue>, key: &str) -> Option<String> {
+	match fields.get(key) {
+		Some(ItemValue::Ordinary(value)) => Some(value.to_string()),
+		_ => None,
+	}
+}
+
+/// Reduce a BCP 47 language tag to the ISO 639 code CFF wants.
+///
+/// Script, region, and other subtags are dropped, and extended language subtags
+/// are used as the language, so `zh-yue-HK` becomes `yue`.
+fn convert_language(tag: &str, record: &mut RecordReport) -> Option<String> {
+	let mut subtags = tag.trim().split(['-', '_']);
+	let primary = subtags.next().unwrap_or_default();
+	let extlang = subtags.next().filter(|subtag| subtag.len() == 3);
+
+	let is_code =
+		|code: &str| (2..=3).contains(&code.len()) && code.chars().all(|c| c.is_ascii_alphabetic());
+	let code = match extlang {
+		Some(extlang) if is_code(primary) && is_code(extlang) => extlang,
+		_ if is_code(primary) => primary,
+		_ => {
+			record.dropped(
+				"language",
+				format!("can't reduce {tag:?} to an ISO 639 language code"),
+			);
+			return None;
+		}
+	};
+
+	Some(code.to_ascii_lowercase())
+}
+
+/// Split keyword lists, trimming and removing empty and repeated keywords.
+fn split_keywords(values: impl Iterator<Item = String>, separators: &str) -> Vec<String> {
+	let mut keywords: Vec<String> = Vec::new();
+	for value in values {
+		for keyword in value.split(|c| separators.contains(c)) {
+			let keyword = keyword.trim();
+			if !keyword.is_empty() && !keywords.iter().any(|k| k == keyword) {
+				keywords.push(keyword.into());
+			}
+		}
+	}
+	keywords
+}
+
+fn convert_publisher(
+	fields: &BTreeMap<String, ItemValue>,
+	record: &mut RecordReport,
+) -> Option<EntityName> {
+	let city = field_string(fields, "publisher-place");
+	let name = match field_string(fields, "publisher") {
+		Some(name) => name,
+		None => {
+			if city.is_some() {
+				record.dropped("publisher-place", "no publisher to attach it to");
+			}
+			return None;
+		}
+	};
+
+	Some(EntityName {
+		name: Some(name),
+		meta: NameMeta {
+			city,
+			..Default::default()
+		},
+		..Default::default()
+	})
+}
+
+/// Convert the `event-*` variables to a conference.
+///
+/// The deprecated `event` is used if there's no `event-title`.
+fn convert_event(
+	fields: &BTreeMap<String, ItemValue>,
+	record: &mut RecordReport,
+) -> Option<EntityName> {
+	let location = field_string(fields, "event-place");
+	let date = match fields.get("event-date") {
+		Some(ItemValue::Date(date)) => Some(date),
+		_ => None,
+	};
+	let name = match field_string(fields, "event-title").or_else(|| field_string(fields, "event")) {
+		Some(name) => name,
+		None => {
+			if location.is_some() {
+				record.dropped("event-place", "no event to attach it to");
+			}
+			if date.is_some() {
+				record.dropped("event-date", "no event to attach it to");
+			}
+			return None;
+		}
+	};
+
+	let (date_start, date_end) = match date {
+		Some(CslDate::Range { start, end, .. }) => (
+			full_date(*start, "event-date", record),
+			full_date(*end, "event-date", record),
+		),
+		date => (convert_date(date, "event-date", record), None),
+	};
+
+	Some(EntityName {
+		name: Some(name),
+		date_start,
+		date_end,
+		meta: NameMeta {
+			location,
+			..Default::default()
+		},
+	})
+}
+
+/// Convert a CSL date to a CFF date, which needs a day.
+///
+/// Partial dates are dropped rather than completed with made-up days; use
+/// [convert_partial_date] where CFF has year and month fields instead.
+fn convert_date(date: Option<&CslDate>, field: &str, record: &mut RecordReport) -> Option<CffDate> {
+	full_date(single_date(date?, field, record)?, field, record)
+}
+
+fn full_date(parts: CslDateParts, field: &str, record: &mut RecordReport) -> Option<CffDate> {
+	match (parts.month, parts.day) {
+		(Some(month), Some(day)) => Some(CffDate {
+			year: parts.year,
+			month,
+			day,
+		}),
+		_ => {
+			record.dropped(
+				field,
+				format!(
+					"CFF dates need a day, but only {} is known",
+					parts_string(parts)
+				),
+			);
+			None
+		}
+	}
+}
+
+/// Convert a CSL date to a full CFF date if it has a day, or else to a year
+/// and maybe a month.
+fn convert_partial_date(
+	date: &CslDate,
+	field: &str,
+	hemisphere: Hemisphere,
+	record: &mut RecordReport,
+) -> (Option<CffDate>, Option<u64>, Option<u8>) {
+	match single_date(date, field, record) {
+		Some(CslDateParts {
+			year,
+			month: Some(month),
+			day: Some(day),
+		}) => (Some(CffDate { year, month, day }), None, None),
+		Some(_) => {
+			let (year, month) = year_month(date, field, hemisphere, record);
+			(None, year, month)
+		}
+		None => (None, None, None),
+	}
+}
+
+/// The year and month of a CSL date, as far as they're known.
+///
+/// Dates with a year and a season have the first month of the season.
+fn year_month(
+	date: &CslDate,
+	field: &str,
+	hemisphere: Hemisphere,
+	record: &mut RecordReport,
+) -> (Option<u64>, Option<u8>) {
+	let parts = match date.to_parts() {
+		Some((start, _)) => start,
+		None => return (None, None),
+	};
+	let parts = match date.season_range(hemisphere) {
+		Some((start, _)) => {
+			record.guessed(
+				"month",
+				start.month.unwrap_or_default().to_string(),
+				format!(
+					"{field} is {}, which starts then in the {hemisphere} hemisphere",
+					date_string(date)
+				),
+			);
+			CslDateParts {
+				month: start.month,
+				..parts
+			}
+		}
+		None => parts,
+	};
+
+	match u64::try_from(parts.year) {
+		Ok(year) => (Some(year), parts.month),
+		Err(_) => {
+			record.dropped(
+				field,
+				format!("CFF years can't be negative: {}", parts.year),
+			);
+			(None, None)
+		}
+	}
+}
+
+/// The date parts of a single date, or of the start of a range.
+///
+/// EDTF dates are parsed, with ranges for unspecified digits like `201X`.
+fn single_date(date: &CslDate, field: &str, record: &mut RecordReport) -> Option<CslDateParts> {
+	match date.to_parts() {
+		Some((start, None)) => Some(start),
+		Some((start, Some(_))) => {
+			record.warn(format!("{field} is a range, using its start"));
+			Some(start)
+		}
+		None => {
+			record.dropped(
+				field,
+				format!("could not convert date {:?}", date_string(date)),
+			);
+			None
+		}
+	}
+}
+
+/// A CSL date as a string, with only as much precision as it has.
+fn date_string(date: &CslDate) -> String {
+	match date {
+		CslDate::Single { date, .. } => parts_string(*date),
+		CslDate::Range { start, end, .. } => {
+			format!("{}/{}", parts_string(*start), parts_string(*end))
+		}
+		CslDate::Raw { date, .. } | CslDate::Edtf { date, .. } => date.clone(),
+		CslDate::MetaOnly { meta } => meta.literal.clone().unwrap_or_else(|| {
+			let circa = meta.circa.as_ref().map(|circa| match circa {
+				Circa::Arbitrary(circa) => format!("circa {circa}"),
+				Circa::Year(year) => format!("circa {year}"),
+				Circa::Bool(_) => "circa".into(),
+			});
+			[meta.season.map(|season| season.to_string()), circa]
+				.into_iter()
+				.flatten()
+				.collect::<Vec<_>>()
+				.join(", ")
+		}),
+	}
+}
+
+fn parts_string(parts: CslDateParts) -> String {
+	match (parts.month, parts.day) {
+		(Some(month), Some(day)) => format!("{:04}-{month:02}-{day:02}", parts.year),
+		(Some(month), None) => format!("{:04}-{month:02}", parts.year),
+		(None, _) => format!("{:04}", parts.year),
+	}
+}
+
+fn page_start(page: Option<String>) -> Option<u64> {
+	if let Some(page) = page {
+		if let Ok(single) = page.parse::<u64>() {
+			Some(single)
+		} else if let Some(start) = page.split('-').next() {
+			u64::from_str(start).ok()
+		} else {
+			None
+		}
+	} else {
+		None
+	}
+}
+
+fn page_end(page: Option<String>) -> Option<u64> {
+	if let Some(page) = page {
+		if let Ok(single) = page.parse::<u64>() {
+			Some(single)
+		} else if let Some(end) = page.split_once('-').map(|x| x.1) {
+			u64::from_str(end).ok()
+		} else {
+			None
+		}
+	} else {
+		None
+	}
+}
+
+fn extra_idents(eissn: Option<String>, issnl: Option<String>) -> Vec<Identifier> {
+	let mut idents = Vec::new();
+	if let Some(eissn) = eissn {
+		idents.push(Identifier::Other {
+			value: eissn,
+			description: Some("EISSN".into()),
+		});
+	}
+	if let Some(issnl) = issnl {
+		idents.push(Identifier::Other {
+			value: issnl,
+			description: Some("ISSNL".into()),
+		});
+	}
+	idents
+}
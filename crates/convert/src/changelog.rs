@@ -0,0 +1,161 @@
+//! Release notes from changelogs.
+//!
+//! This reads Markdown changelogs, like those following [Keep a Changelog],
+//! where each release is a heading with its version and maybe its date, and
+//! gets the notes of a release, to describe it in a deposit, e.g. as the
+//! [release notes](crate::datacite::DataCiteOptions::release_notes) of DataCite
+//! metadata. Archive descriptions then say what changed in each version.
+//!
+//! ```
+//! use citeworks_convert::changelog::{latest_entry, truncate};
+//!
+//! let entry = latest_entry("
+//! ## Changelog
+//!
+//! ### [Unreleased]
+//!
+//! ### [1.1.0] - 2022-09-01
+//!
+//! #### Added
+//! - Export to DataCite.
+//!
+//! ### [1.0.0] - 2022-08-20
+//! - First release.
+//! ").unwrap();
+//!
+//! assert_eq!(entry.version, "1.1.0");
+//! assert_eq!(entry.date.as_deref(), Some("2022-09-01"));
+//! assert_eq!(entry.notes, "### Added\n- Export to DataCite.");
+//! assert_eq!(truncate(&entry.notes, 24), "### Added\n- Export to…");
+//! ```
+//!
+//! [Keep a Changelog]: https://keepachangelog.com
+
+/// The notes of one release in a changelog.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ChangelogEntry {
+	/// Version of the release, without a leading `v`.
+	pub version: String,
+
+	/// Date of the release as written in the heading, if it's `YYYY-MM-DD`.
+	pub date: Option<String>,
+
+	/// What's under the heading, as Markdown, trimmed.
+	pub notes: String,
+}
+
+/// The latest release in a changelog.
+///
+/// That's the first entry with a version, skipping an `Unreleased` entry.
+/// Returns `None` if there are no releases.
+pub fn latest_entry(changelog: &str) -> Option<ChangelogEntry> {
+	entries(changelog).into_iter().next()
+}
+
+/// The release of this version in a changelog.
+///
+/// A leading `v` is ignored in both the version and the changelog.
+pub fn entry_for_version(changelog: &str, version: &str) -> Option<ChangelogEntry> {
+	let version = version.trim().trim_start_matches('v');
+	entries(changelog)
+		.into_iter()
+		.find(|entry| entry.version == version)
+}
+
+/// All releases in a changelog, in the order they're written.
+pub fn entries(changelog: &str) -> Vec<ChangelogEntry> {
+	let mut entries = Vec::new();
+	// The current entry, with the level of its heading and its lines.
+	let mut current: Option<(usize, ChangelogEntry, Vec<&str>)> = None;
+
+	for line in changelog.lines() {
+		if let Some((level, text)) = heading(line) {
+			let ends_current = current
+				.as_ref()
+				.map_or(false, |(current_level, _, _)| level <= *current_level);
+			if ends_current {
+				entries.extend(current.take().map(finish));
+			}
+			if current.is_none() {
+				current = release(text).map(|entry| (level, entry, Vec::new()));
+				continue;
+			}
+		}
+
+		if let Some((_, _, lines)) = &mut current {
+			lines.push(line);
+		}
+	}
+
+	entries.extend(current.map(finish));
+	entries
+}
+
+/// Shorten text to at most this many characters, at a word boundary.
+///
+/// Text that's shortened ends with `…`, which counts towards the length.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+	if text.chars().count() <= max_chars {
+		return text.into();
+	}
+
+	let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+	let cut = match cut.rfind(char::is_whitespace) {
+		Some(end) if end > 0 => &cut[..end],
+		_ => cut.as_str(),
+	};
+	format!("{}…", cut.trim_end())
+}
+
+/// The level and text of a Markdown heading.
+fn heading(line: &str) -> Option<(usize, &str)> {
+	let level = line.chars().take_while(|c| *c == '#').count();
+	let text = line[level..].strip_prefix(' ')?;
+	(level > 0).then(|| (level, text.trim()))
+}
+
+/// The version and date of a release heading, like `[1.0.0] - 2022-08-20`.
+fn release(heading: &str) -> Option<ChangelogEntry> {
+	let mut words = heading.split_whitespace();
+	let version = words
+		.next()?
+		.trim_start_matches('[')
+		.split([']', '('])
+		.next()?
+		.trim_start_matches('v');
+	if !version.starts_with(|c: char| c.is_ascii_digit()) {
+		return None;
+	}
+
+	let date = words
+		.map(|word| word.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '-'))
+		.find(|word| {
+			word.len() == 10
+				&& word.char_indices().all(|(i, c)| match i {
+					4 | 7 => c == '-',
+					_ => c.is_ascii_digit(),
+				})
+		})
+		.map(String::from);
+
+	Some(ChangelogEntry {
+		version: version.into(),
+		date,
+		notes: String::new(),
+	})
+}
+
+fn finish((_, mut entry, lines): (usize, ChangelogEntry, Vec<&str>)) -> ChangelogEntry {
+	// Link definitions for the headings, usually at the end of the file.
+	let lines: Vec<&str> = lines
+		.into_iter()
+		.filter(|line| {
+			!(line.starts_with('[')
+				&& line
+					.split_once("]: ")
+					.map_or(false, |(label, _)| !label.contains(' ')))
+		})
+		.collect();
+	entry.notes = lines.join("\n").trim().into();
+	entry
+}
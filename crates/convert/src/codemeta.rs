@@ -0,0 +1,381 @@
+//! Conversion between CFF documents and [CodeMeta](citeworks_codemeta).
+//!
+//! This follows the [CodeMeta crosswalk] for CFF: the title is the `name`, the
+//! abstract the `description`, authors are `author`s, contacts `maintainer`s,
+//! and so on. DOIs are written as `https://doi.org/` URLs, in `@id` and
+//! `identifier`, and licenses as SPDX URLs.
+//!
+//! As repositories often have both files, the `update_` functions change only
+//! what's converted, keeping everything else in the target: a CFF document's
+//! references, or a CodeMeta document's `programmingLanguage`, for example.
+//!
+//! ```
+//! use citeworks_convert::codemeta::{cff_to_codemeta, codemeta_to_cff};
+//!
+//! let cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! version: 2.0.4
+//! doi: 10.5281/zenodo.1234
+//! license: MIT
+//! authors:
+//!   - family-names: Druskat
+//!     given-names: Stephan
+//! "#).unwrap();
+//!
+//! let codemeta = cff_to_codemeta(&cff);
+//! assert_eq!(codemeta.name.as_deref(), Some("My Research Software"));
+//! assert_eq!(codemeta.identifier, ["https://doi.org/10.5281/zenodo.1234"]);
+//! assert_eq!(codemeta.license, ["https://spdx.org/licenses/MIT"]);
+//!
+//! let back = codemeta_to_cff(&codemeta).unwrap();
+//! assert_eq!(back.doi, cff.doi);
+//! assert_eq!(back.authors, cff.authors);
+//! ```
+//!
+//! [CodeMeta crosswalk]: https://github.com/codemeta/codemeta/blob/master/crosswalks/Citation%20File%20Format%201.2.0.csv
+
+use std::str::FromStr;
+
+use citeworks_cff::{
+	funding::Funding,
+	identifiers::Identifier,
+	names::{EntityName, Name, NameMeta, PersonName},
+	Cff, Date, License,
+};
+use citeworks_codemeta::{
+	agents::{Agent, Organization, Person},
+	CodeMeta, Grant,
+};
+use url::Url;
+
+use crate::csl::bare_doi;
+
+const SPDX_URL: &str = "https://spdx.org/licenses/";
+
+/// Convert a CFF document to CodeMeta.
+pub fn cff_to_codemeta(cff: &Cff) -> CodeMeta {
+	let mut codemeta = CodeMeta::default();
+	update_codemeta(&mut codemeta, cff);
+	codemeta
+}
+
+/// Update a CodeMeta document from a CFF document.
+///
+/// Properties that are converted are replaced, and removed if the CFF document
+/// doesn't have them; others are kept as they are.
+pub fn update_codemeta(codemeta: &mut CodeMeta, cff: &Cff) {
+	let doi = cff.doi.as_deref().map(doi_url);
+	if doi.is_some() {
+		codemeta.id = doi.clone();
+	}
+
+	codemeta.name = Some(cff.title.clone());
+	codemeta.version = cff.version.clone();
+	codemeta.description = cff.abstract_text.clone();
+	codemeta.identifier = doi
+		.into_iter()
+		.chain(cff.identifiers.iter().map(|id| match id {
+			Identifier::Doi { value, .. } => doi_url(value),
+			id => id.value().to_string(),
+		}))
+		.collect();
+	codemeta.code_repository = cff
+		.repository_code
+		.as_ref()
+		.or(cff.repository.as_ref())
+		.map(ToString::to_string);
+	codemeta.url = cff.url.as_ref().map(ToString::to_string);
+	codemeta.download_url = cff.repository_artifact.as_ref().map(ToString::to_string);
+	codemeta.license = match &cff.license {
+		None => Vec::new(),
+		Some(License::Single(exp)) => vec![license_url(&exp.to_string())],
+		Some(License::AnyOf(exps)) => exps.iter().map(|exp| license_url(exp.as_ref())).collect(),
+		Some(license) => license.members(),
+	};
+	codemeta.date_published = cff.date_released.map(|date| date.to_string());
+	codemeta.keywords = cff.keywords.clone();
+	codemeta.author = cff.authors.iter().filter_map(agent).collect();
+	codemeta.maintainer = cff.contact.iter().filter_map(agent).collect();
+	codemeta.funding = cff.funding.iter().map(grant).collect();
+}
+
+/// Convert a CodeMeta document to a CFF document.
+///
+/// Fails if the document has no `name`, as CFF requires a title.
+pub fn codemeta_to_cff(codemeta: &CodeMeta) -> Result<Cff, String> {
+	let mut cff = Cff::default();
+	update_cff(&mut cff, codemeta)?;
+	Ok(cff)
+}
+
+/// Update a CFF document from a CodeMeta document.
+///
+/// Fields that are converted are replaced, and removed if the CodeMeta
+/// document doesn't have them; others are kept as they are. Fails if the
+/// document has no `name`, as CFF requires a title.
+///
+/// Licenses that aren't SPDX expressions or URLs, and URLs which can't be
+/// parsed, are skipped.
+pub fn update_cff(cff: &mut Cff, codemeta: &CodeMeta) -> Result<(), String> {
+	cff.title = codemeta
+		.name
+		.clone()
+		.filter(|name| !name.trim().is_empty())
+		.ok_or("CodeMeta has no name, which CFF requires as title")?;
+
+	let mut identifiers = Vec::new();
+	for id in codemeta.id.iter().chain(codemeta.identifier.iter()) {
+		let identifier = identifier(id);
+		if !identifiers.contains(&identifier) {
+			identifiers.push(identifier);
+		}
+	}
+	cff.doi = None;
+	if let Some(n) = identifiers
+		.iter()
+		.position(|id| matches!(id, Identifier::Doi { .. }))
+	{
+		cff.doi = Some(identifiers.remove(n).value().into());
+	}
+	cff.identifiers = identifiers;
+
+	cff.version = codemeta
+		.version
+		.clone()
+		.or_else(|| codemeta.software_version.clone());
+	cff.abstract_text = codemeta.description.clone();
+	cff.repository_code = url(&codemeta.code_repository);
+	cff.url = url(&codemeta.url);
+	cff.repository_artifact = url(&codemeta.download_url);
+	cff.license = license(&codemeta.license);
+	cff.date_released = codemeta
+		.date_published
+		.as_deref()
+		.and_then(|date| Date::from_str(date.get(..10).unwrap_or(date)).ok());
+	cff.keywords = match codemeta.keywords.as_slice() {
+		[keywords] => keywords
+			.split(',')
+			.map(str::trim)
+			.filter(|keyword| !keyword.is_empty())
+			.map(String::from)
+			.collect(),
+		keywords => keywords.to_vec(),
+	};
+	cff.authors = codemeta.author.iter().filter_map(name).collect();
+	cff.contact = codemeta.maintainer.iter().filter_map(name).collect();
+	cff.funding = codemeta.funding.iter().flat_map(funding).collect();
+	Ok(())
+}
+
+fn agent(name: &Name) -> Option<Agent> {
+	match name {
+		Name::Person(person) => Some(Agent::Person(Person {
+			id: person.meta.orcid.as_ref().map(ToString::to_string),
+			given_name: person.given_names.clone(),
+			family_name: match (&person.name_particle, &person.family_names) {
+				(Some(particle), Some(family)) => Some(format!("{particle} {family}")),
+				(None, family) => family.clone(),
+				(Some(particle), None) => Some(particle.clone()),
+			},
+			email: person.meta.email.clone(),
+			affiliation: person
+				.affiliation
+				.iter()
+				.map(|affiliation| {
+					Agent::Organization(Organization {
+						id: person.meta.ror.as_ref().map(ToString::to_string),
+						..Organization::named(affiliation)
+					})
+				})
+				.collect(),
+			..Default::default()
+		})),
+		Name::Entity(entity) => Some(Agent::Organization(organization(entity))),
+		Name::Anonymous => None,
+	}
+}
+
+fn organization(entity: &EntityName) -> Organization {
+	Organization {
+		id: entity
+			.meta
+			.ror
+			.as_ref()
+			.or(entity.meta.orcid.as_ref())
+			.map(ToString::to_string),
+		name: entity.name.clone(),
+		email: entity.meta.email.clone(),
+		url: entity.meta.website.as_ref().map(ToString::to_string),
+		..Default::default()
+	}
+}
+
+/// People with only a `name` become CFF entities, as CFF can't split it.
+fn name(agent: &Agent) -> Option<Name> {
+	match agent {
+		Agent::Person(person) if person.given_name.is_some() || person.family_name.is_some() => {
+			let ror = person
+				.affiliation
+				.iter()
+				.find_map(Agent::as_organization)
+				.and_then(|org| org.id.as_deref())
+				.and_then(|id| Url::parse(id).ok())
+				.filter(|id| id.host_str() == Some("ror.org"));
+			Some(Name::Person(PersonName {
+				family_names: person.family_name.clone(),
+				given_names: person.given_name.clone(),
+				affiliation: person
+					.affiliation
+					.iter()
+					.find_map(|affiliation| match affiliation {
+						Agent::Organization(Organization { name, .. }) => name.clone(),
+						Agent::Person(Person { name, .. }) => name.clone(),
+						Agent::Other(_) => None,
+					}),
+				meta: NameMeta {
+					orcid: person.id.as_deref().and_then(|id| Url::parse(id).ok()),
+					email: person.email.clone(),
+					ror,
+					..Default::default()
+				},
+				..Default::default()
+			}))
+		}
+		Agent::Person(Person {
+			name: Some(name),
+			email,
+			..
+		}) => Some(Name::Entity(EntityName {
+			name: Some(name.clone()),
+			meta: NameMeta {
+				email: email.clone(),
+				..Default::default()
+			},
+			..Default::default()
+		})),
+		Agent::Organization(org) => Some(Name::Entity(entity(org))),
+		Agent::Person(_) | Agent::Other(_) => None,
+	}
+}
+
+fn entity(org: &Organization) -> EntityName {
+	let id = org.id.as_deref().and_then(|id| Url::parse(id).ok());
+	let is_ror = id.as_ref().and_then(Url::host_str) == Some("ror.org");
+	EntityName {
+		name: org.name.clone(),
+		meta: NameMeta {
+			ror: id.clone().filter(|_| is_ror),
+			orcid: id.filter(|_| !is_ror),
+			email: org.email.clone(),
+			website: url(&org.url),
+			..Default::default()
+		},
+		..Default::default()
+	}
+}
+
+fn grant(funding: &Funding) -> Grant {
+	Grant {
+		identifier: funding.award_number.clone(),
+		name: funding.award_title.clone(),
+		url: funding.award_uri.as_ref().map(ToString::to_string),
+		funder: vec![Agent::Organization(organization(&funding.funder))],
+		extra: [("@type".into(), "Grant".into())].into_iter().collect(),
+		..Default::default()
+	}
+}
+
+/// Grants without a funder, like CodeMeta 2.0's text `funding`, are skipped.
+fn funding(grant: &Grant) -> Option<Funding> {
+	let funder = grant.funder.iter().find_map(|funder| match name(funder)? {
+		Name::Entity(entity) => Some(entity),
+		Name::Person(person) => Some(EntityName {
+			name: Some(
+				[person.given_names, person.family_names]
+					.into_iter()
+					.flatten()
+					.collect::<Vec<_>>()
+					.join(" "),
+			),
+			meta: person.meta,
+			..Default::default()
+		}),
+		Name::Anonymous => None,
+	})?;
+
+	Some(Funding {
+		funder,
+		award_number: grant.identifier.clone(),
+		award_title: grant.name.clone(),
+		award_uri: url(&grant.url),
+	})
+}
+
+fn identifier(id: &str) -> Identifier {
+	let doi = bare_doi(id);
+	if doi != id.trim() {
+		Identifier::Doi {
+			value: doi.into(),
+			description: None,
+		}
+	} else if id.starts_with("swh:") {
+		Identifier::Swh {
+			value: id.into(),
+			description: None,
+		}
+	} else if let Ok(url) = Url::parse(id) {
+		Identifier::Url {
+			value: url,
+			description: None,
+		}
+	} else {
+		Identifier::Other {
+			value: id.into(),
+			description: None,
+		}
+	}
+}
+
+/// Licenses as SPDX URLs, or as expressions if they're compound.
+fn license_url(expression: &str) -> String {
+	if expression.contains(' ') {
+		expression.into()
+	} else {
+		format!("{SPDX_URL}{expression}")
+	}
+}
+
+fn license(licenses: &[String]) -> Option<License> {
+	let mut expressions: Vec<License> = licenses
+		.iter()
+		.filter_map(|license| {
+			let id = ["https://spdx.org/licenses/", "http://spdx.org/licenses/"]
+				.iter()
+				.find_map(|prefix| license.strip_prefix(prefix))
+				.map(|id| id.trim_end_matches(".html").trim_end_matches(".json"))
+				.unwrap_or(license);
+			License::from_str(id).ok()
+		})
+		.collect();
+
+	match expressions.len() {
+		0 => None,
+		1 => expressions.pop(),
+		_ => Some(License::AnyOf(
+			expressions
+				.iter()
+				.filter_map(License::try_to_expression)
+				.collect(),
+		)),
+	}
+}
+
+fn url(url: &Option<String>) -> Option<Url> {
+	url.as_deref().and_then(|url| Url::parse(url).ok())
+}
+
+fn doi_url(doi: &str) -> String {
+	format!("https://doi.org/{}", bare_doi(doi))
+}
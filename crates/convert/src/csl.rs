@@ -0,0 +1,662 @@
+//! Conversion from CFF to CSL-JSON items.
+//!
+//! Each reference becomes one item. A whole CFF document becomes an item for
+//! the work itself, followed by its references. With
+//! [ConvertOptions::preferred_citation], the document's `preferred-citation` comes
+//! first, as the primary item, followed by the work itself as a related item.
+//!
+//! CFF references don't have ids, so items are given keys made of the first
+//! author's family name and the year, e.g. `druskat2017`, with a letter
+//! suffix when that would clash, e.g. `doe2017b`. Other patterns can be used
+//! with a [KeyGenerator], see the [keys](crate::keys) module.
+//!
+//! ```
+//! use citeworks_convert::csl::cff_to_items;
+//! use citeworks_csl::ordinaries::OrdinaryValue;
+//!
+//! let cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! date-released: 2017-12-18
+//! authors:
+//!   - family-names: Druskat
+//!     given-names: Stephan
+//! "#).unwrap();
+//!
+//! let items = cff_to_items(&cff);
+//! assert_eq!(items[0].id, "druskat2017");
+//! assert_eq!(items[0].title, Some(OrdinaryValue::String("My Research Software".into())));
+//! ```
+
+use std::collections::BTreeMap;
+
+use citeworks_cff::{
+	identifiers::IdentifierKind,
+	names::Name as CffName,
+	references::{PublicationStatus, RefType, Reference},
+	Cff, Date as CffDate, License, WorkType,
+};
+use citeworks_csl::{
+	dates::{Date as CslDate, DateMeta, DateParts},
+	items::{ItemType, ItemValue},
+	names::Name as CslName,
+	ordinaries::OrdinaryValue,
+	Item,
+};
+
+use serde::Serialize;
+
+use crate::{
+	keys::KeyGenerator,
+	options::ConvertOptions,
+	report::{audit, send, unmapped, IgnoreWarnings, Mapping, RecordReport, WarningSink},
+};
+
+/// Top-level CFF keys which are converted, or which are metadata about the file.
+const MAPPED_CFF_KEYS: &[&str] = &[
+	"cff-version",
+	"message",
+	"type",
+	"title",
+	"authors",
+	"abstract",
+	"doi",
+	"identifiers",
+	"url",
+	"repository-code",
+	"date-released",
+	"license",
+	"version",
+	"keywords",
+	"references",
+	"preferred-citation",
+];
+
+/// Reference keys which are converted.
+const MAPPED_REFERENCE_KEYS: &[&str] = &[
+	"type",
+	"authors",
+	"title",
+	"abbreviation",
+	"abstract",
+	"journal",
+	"collection-title",
+	"doi",
+	"url",
+	"repository-code",
+	"issn",
+	"volume",
+	"issue",
+	"start",
+	"end",
+	"date-published",
+	"date-released",
+	"year",
+	"month",
+	"date-accessed",
+	"languages",
+	"copyright",
+	"license",
+	"notes",
+	"publisher",
+	"institution",
+	"conference",
+	"edition",
+	"version",
+	"isbn",
+	"pmcid",
+	"thesis-type",
+	"keywords",
+	"editors",
+	"editors-series",
+	"translators",
+	"recipients",
+	"status",
+];
+
+/// How top-level CFF keys are converted to CSL variables, for audits.
+const AUDITED_CFF: &[Mapping] = &[
+	(&["type"], "type", "type mapping"),
+	(&["authors"], "author", "names"),
+	(&["title"], "title", ""),
+	(&["abstract"], "abstract", ""),
+	(&["doi"], "DOI", ""),
+	(&["identifiers"], "DOI", "DOI identifier"),
+	(&["url"], "URL", ""),
+	(&["repository-code"], "URL", ""),
+	(&["date-released"], "issued", "date"),
+	(&["license"], "license", "SPDX expression"),
+	(&["version"], "version", ""),
+	(&["keywords"], "keyword", "joined"),
+];
+
+/// How reference keys are converted to CSL variables, for audits.
+const AUDITED_REFERENCE: &[Mapping] = &[
+	(&["type"], "type", "type mapping"),
+	(&["authors"], "author", "names"),
+	(&["editors"], "editor", "names"),
+	(&["editors-series"], "container-author", "names"),
+	(&["recipients"], "recipient", "names"),
+	(&["translators"], "translator", "names"),
+	(&["title"], "title", ""),
+	(&["abbreviation"], "title-short", ""),
+	(&["abstract"], "abstract", ""),
+	(&["journal"], "container-title", ""),
+	(&["collection-title"], "container-title", ""),
+	(&["doi"], "DOI", ""),
+	(&["url"], "URL", ""),
+	(&["repository-code"], "URL", ""),
+	(&["issn"], "ISSN", ""),
+	(&["volume"], "volume", ""),
+	(&["issue"], "issue", ""),
+	(&["start", "end"], "page", "page range"),
+	(&["date-published"], "issued", "date"),
+	(&["date-released"], "issued", "date"),
+	(&["year", "month"], "issued", "year and month"),
+	(&["date-accessed"], "accessed", "date"),
+	(&["languages"], "language", "first language"),
+	(&["copyright"], "rights", ""),
+	(&["license"], "license", "SPDX expression"),
+	(&["notes"], "note", ""),
+	(&["publisher"], "publisher", "name"),
+	(&["institution"], "publisher", "name"),
+	(&["publisher"], "publisher-place", "city"),
+	(&["conference"], "event-title", "name"),
+	(&["edition"], "edition", ""),
+	(&["status"], "status", "status mapping"),
+	(&["version"], "version", ""),
+	(&["isbn"], "ISBN", ""),
+	(&["pmcid"], "PMCID", ""),
+	(&["thesis-type"], "genre", ""),
+	(&["keywords"], "keyword", "joined"),
+];
+
+fn audit_conversion(
+	record: &mut RecordReport,
+	source: &impl Serialize,
+	item: &Item,
+	mappings: &[Mapping],
+) {
+	if let (Ok(source), Ok(target)) = (serde_json::to_value(source), serde_json::to_value(item)) {
+		audit(record, &source, &target, mappings);
+	}
+}
+
+/// Convert a CFF document to CSL items: the work itself, then its references.
+///
+/// Items are given keys as described in the [module documentation](self).
+pub fn cff_to_items(cff: &Cff) -> Vec<Item> {
+	cff_to_items_with(
+		cff,
+		&ConvertOptions::default(),
+		&mut KeyGenerator::default(),
+		&mut IgnoreWarnings,
+	)
+}
+
+/// Convert a CFF document to CSL items, with keys from the given generator.
+///
+/// Fields which can't be converted are reported to the sink.
+pub fn cff_to_items_with(
+	cff: &Cff,
+	options: &ConvertOptions,
+	keys: &mut KeyGenerator,
+	report: &mut dyn WarningSink,
+) -> Vec<Item> {
+	let mut items = Vec::new();
+	match &cff.preferred_citation {
+		Some(preferred) if options.preferred_citation => {
+			items.extend(references_to_items_with(
+				std::slice::from_ref(preferred),
+				keys,
+				report,
+			));
+		}
+		_ => {}
+	}
+
+	items.push(work_to_item_with(cff, options, keys, report));
+	items.extend(references_to_items_with(&cff.references, keys, report));
+	items
+}
+
+/// Convert the work a CFF document is about to a CSL item, without its
+/// preferred citation or references.
+///
+/// The item is given a key from the generator, and fields which can't be
+/// converted are reported to the sink.
+pub fn work_to_item_with(
+	cff: &Cff,
+	options: &ConvertOptions,
+	keys: &mut KeyGenerator,
+	report: &mut dyn WarningSink,
+) -> Item {
+	let mut record = RecordReport::default();
+	let mut work = convert_work(cff, options, &mut record);
+	work.id = keys.generate(&work);
+	record.id = work.id.clone();
+	if report.audits() {
+		audit_conversion(&mut record, cff, &work, AUDITED_CFF);
+	}
+	send(report, record);
+	work
+}
+
+/// Convert the work a CFF document is about, without its references.
+pub(crate) fn convert_work(cff: &Cff, options: &ConvertOptions, record: &mut RecordReport) -> Item {
+	let mut work = Item {
+		item_type: match cff.work_type {
+			Some(WorkType::Dataset) => ItemType::Dataset,
+			_ => ItemType::Software,
+		},
+		author: names(&cff.authors),
+		title: Some(OrdinaryValue::String(cff.title.clone())),
+		abstract_text: cff.abstract_text.clone().map(OrdinaryValue::String),
+		doi: cff
+			.doi
+			.as_deref()
+			.or_else(|| {
+				cff.identifier_of_kind(IdentifierKind::Doi)
+					.map(|id| id.value())
+			})
+			.map(|doi| OrdinaryValue::String(doi.into())),
+		url: cff
+			.url
+			.as_ref()
+			.or(cff.repository_code.as_ref())
+			.map(|url| OrdinaryValue::String(url.to_string())),
+		issued: cff.date_released.map(date),
+		license: cff.license.as_ref().map(license),
+		..Default::default()
+	};
+	insert(&mut work.fields, "version", cff.version.clone());
+	insert(&mut work.fields, "keyword", keywords(&cff.keywords));
+
+	unmapped(cff, MAPPED_CFF_KEYS, "no CSL equivalent", record);
+	if cff.url.is_some() && cff.repository_code.is_some() {
+		record.dropped("repository-code", "URL taken by url");
+	}
+	if cff
+		.identifiers
+		.iter()
+		.any(|id| id.kind() != IdentifierKind::Doi)
+	{
+		record.dropped("identifiers", "only DOI identifiers are converted");
+	}
+	if cff.preferred_citation.is_some() && !options.preferred_citation {
+		record.dropped("preferred-citation", "not enabled in the options");
+	}
+
+	work
+}
+
+/// Convert CFF references to CSL items.
+///
+/// Items are given keys as described in the [module documentation](self).
+pub fn references_to_items(references: &[Reference]) -> Vec<Item> {
+	references_to_items_with(
+		references,
+		&mut KeyGenerator::default(),
+		&mut IgnoreWarnings,
+	)
+}
+
+/// Convert CFF references to CSL items, with keys from the given generator.
+///
+/// Fields which can't be converted are reported to the sink.
+pub fn references_to_items_with(
+	references: &[Reference],
+	keys: &mut KeyGenerator,
+	report: &mut dyn WarningSink,
+) -> Vec<Item> {
+	references
+		.iter()
+		.map(|reference| {
+			let mut record = RecordReport::default();
+			let mut item = convert_reference(reference, String::new(), &mut record);
+			item.id = keys.generate(&item);
+			record.id = item.id.clone();
+			if report.audits() {
+				audit_conversion(&mut record, reference, &item, AUDITED_REFERENCE);
+			}
+			send(report, record);
+			item
+		})
+		.collect()
+}
+
+/// Convert a CFF reference to a CSL item with the given id.
+pub fn reference_to_item(reference: &Reference, id: String) -> Item {
+	convert_reference(reference, id, &mut RecordReport::default())
+}
+
+/// Convert a CFF reference to a CSL item with the given id.
+///
+/// Fields which can't be converted are reported to the sink.
+pub fn reference_to_item_with(
+	reference: &Reference,
+	id: String,
+	report: &mut dyn WarningSink,
+) -> Item {
+	let mut record = RecordReport::new(id.clone());
+	let item = convert_reference(reference, id, &mut record);
+	if report.audits() {
+		audit_conversion(&mut record, reference, &item, AUDITED_REFERENCE);
+	}
+	send(report, record);
+	item
+}
+
+pub(crate) fn convert_reference(
+	reference: &Reference,
+	id: String,
+	record: &mut RecordReport,
+) -> Item {
+	unmapped(
+		reference,
+		MAPPED_REFERENCE_KEYS,
+		"no CSL equivalent",
+		record,
+	);
+	if reference.journal.is_some() && reference.collection_title.is_some() {
+		record.dropped("collection-title", "container-title taken by journal");
+	}
+	if reference.url.is_some() && reference.repository_code.is_some() {
+		record.dropped("repository-code", "URL taken by url");
+	}
+	if reference.publisher.is_some() && reference.institution.is_some() {
+		record.dropped("institution", "publisher taken by publisher");
+	}
+	if reference.date_published.is_some() && reference.date_released.is_some() {
+		record.dropped("date-released", "issued taken by date-published");
+	}
+	if reference.languages.len() > 1 {
+		record.dropped(
+			"languages",
+			format!(
+				"CSL items have a single language, dropped {}",
+				reference.languages[1..].join(", ")
+			),
+		);
+	}
+
+	let item_type = item_type(&reference.work_type);
+	if item_type == ItemType::Document && reference.work_type != RefType::Generic {
+		record.guessed(
+			"type",
+			"document",
+			format!("no CSL type for {:?}", reference.work_type),
+		);
+	}
+
+	let mut item = Item {
+		id,
+		item_type,
+		author: names(&reference.authors),
+		editor: names(&reference.editors),
+		translator: names(&reference.translators),
+		recipient: names(&reference.recipients),
+		container_author: names(&reference.editors_series),
+		title: reference.title.clone().map(OrdinaryValue::String),
+		title_short: reference.abbreviation.clone().map(OrdinaryValue::String),
+		abstract_text: reference.abstract_text.clone().map(OrdinaryValue::String),
+		container_title: reference
+			.journal
+			.as_ref()
+			.or(reference.collection_title.as_ref())
+			.cloned()
+			.map(OrdinaryValue::String),
+		doi: reference.doi.clone().map(OrdinaryValue::String),
+		url: reference
+			.url
+			.as_ref()
+			.or(reference.repository_code.as_ref())
+			.map(|url| OrdinaryValue::String(url.to_string())),
+		issn: reference
+			.issn
+			.clone()
+			.map(|issn| OrdinaryValue::String(issn.into())),
+		volume: reference
+			.volume
+			.and_then(|v| i64::try_from(v).ok())
+			.map(OrdinaryValue::Integer),
+		issue: reference.issue.clone().map(OrdinaryValue::String),
+		page: match (reference.start, reference.end) {
+			(Some(start), Some(end)) if start != end => {
+				Some(OrdinaryValue::String(format!("{start}-{end}")))
+			}
+			(Some(page), _) | (None, Some(page)) => Some(OrdinaryValue::String(page.to_string())),
+			(None, None) => None,
+		},
+		issued: reference
+			.date_published
+			.or(reference.date_released)
+			.map(date)
+			.or_else(|| {
+				reference.year.map(|year| CslDate::Single {
+					date: DateParts {
+						year: year as i64,
+						month: reference.month,
+						day: None,
+					},
+					meta: DateMeta::default(),
+				})
+			}),
+		accessed: reference.date_accessed.map(date),
+		language: reference
+			.languages
+			.first()
+			.cloned()
+			.map(OrdinaryValue::String),
+		rights: reference.copyright.clone().map(OrdinaryValue::String),
+		license: reference.license.as_ref().map(license),
+		note: reference.notes.clone().map(OrdinaryValue::String),
+		..Default::default()
+	};
+
+	let fields = &mut item.fields;
+	insert(
+		fields,
+		"publisher",
+		reference
+			.publisher
+			.as_ref()
+			.or(reference.institution.as_ref())
+			.and_then(|p| p.name.clone()),
+	);
+	insert(
+		fields,
+		"publisher-place",
+		reference
+			.publisher
+			.as_ref()
+			.and_then(|p| p.meta.city.clone()),
+	);
+	insert(
+		fields,
+		"event-title",
+		reference.conference.as_ref().and_then(|c| c.name.clone()),
+	);
+	insert(fields, "edition", reference.edition.clone());
+	insert(
+		fields,
+		"status",
+		reference.status.map(|s| status(s).to_string()),
+	);
+	insert(fields, "version", reference.version.clone());
+	insert(fields, "ISBN", reference.isbn.clone().map(String::from));
+	insert(fields, "PMCID", reference.pmcid.clone());
+	insert(fields, "genre", reference.thesis_type.clone());
+	insert(fields, "keyword", keywords(&reference.keywords));
+
+	item
+}
+
+/// A DOI without its `https://doi.org/`, `http://dx.doi.org/`, or `doi:` prefix.
+pub fn bare_doi(doi: &str) -> &str {
+	let doi = doi.trim();
+	[
+		"https://doi.org/",
+		"http://doi.org/",
+		"https://dx.doi.org/",
+		"http://dx.doi.org/",
+		"doi:",
+	]
+	.iter()
+	.find_map(|prefix| {
+		doi.get(..prefix.len())
+			.filter(|start| start.eq_ignore_ascii_case(prefix))
+			.map(|_| &doi[prefix.len()..])
+	})
+	.unwrap_or(doi)
+	.trim()
+}
+
+/// The CSL `status` of a CFF publication status.
+pub fn status(status: PublicationStatus) -> &'static str {
+	match status {
+		PublicationStatus::Abstract => "abstract",
+		PublicationStatus::AdvanceOnline => "advance online publication",
+		PublicationStatus::InPreparation => "in preparation",
+		PublicationStatus::InPress => "in press",
+		PublicationStatus::Preprint => "preprint",
+		PublicationStatus::Submitted => "submitted",
+	}
+}
+
+/// Parse a CSL `status` into a CFF publication status.
+///
+/// CSL doesn't define values for `status`, so this recognises common ones
+/// ignoring case and punctuation, e.g. "In press", "in-press", "forthcoming",
+/// "Epub ahead of print", "under review".
+pub fn parse_status(status: &str) -> Option<PublicationStatus> {
+	let status = status
+		.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+		.map(str::to_lowercase)
+		.collect::<Vec<_>>()
+		.join(" ");
+
+	Some(match status.as_str() {
+		"abstract" => PublicationStatus::Abstract,
+		"advance online"
+		| "advance online publication"
+		| "online first"
+		| "ahead of print"
+		| "epub ahead of print" => PublicationStatus::AdvanceOnline,
+		"in preparation" | "in prep" | "draft" => PublicationStatus::InPreparation,
+		"in press" | "inpress" | "forthcoming" | "accepted" => PublicationStatus::InPress,
+		"preprint" | "pre print" => PublicationStatus::Preprint,
+		"submitted" | "under review" | "in review" => PublicationStatus::Submitted,
+		_ => return None,
+	})
+}
+
+/// Map a CFF reference type to the closest CSL item type.
+pub fn item_type(work_type: &RefType) -> ItemType {
+	use RefType::*;
+	match work_type {
+		Art => ItemType::Graphic,
+		Article => ItemType::ArticleJournal,
+		Audiovisual | FilmBroadcast | Video => ItemType::MotionPicture,
+		Bill => ItemType::Bill,
+		Blog => ItemType::PostWeblog,
+		Book | EditedWork | Proceedings | Conference => ItemType::Book,
+		ConferencePaper => ItemType::PaperConference,
+		Data | Database => ItemType::Dataset,
+		Dictionary => ItemType::EntryDictionary,
+		Encyclopedia => ItemType::EntryEncyclopedia,
+		Hearing => ItemType::Hearing,
+		HistoricalWork => ItemType::Classic,
+		LegalCase => ItemType::LegalCase,
+		LegalRule | Statute => ItemType::Legislation,
+		MagazineArticle => ItemType::ArticleMagazine,
+		Map => ItemType::Map,
+		Music => ItemType::MusicalScore,
+		NewspaperArticle => ItemType::ArticleNewspaper,
+		Pamphlet => ItemType::Pamphlet,
+		Patent => ItemType::Patent,
+		PersonalCommunication => ItemType::PersonalCommunication,
+		Report => ItemType::Report,
+		Serial => ItemType::Periodical,
+		Slides => ItemType::Speech,
+		Software
+		| SoftwareCode
+		| SoftwareContainer
+		| SoftwareExecutable
+		| SoftwareVirtualMachine => ItemType::Software,
+		SoundRecording => ItemType::Song,
+		Standard => ItemType::Standard,
+		Thesis => ItemType::Thesis,
+		Unpublished => ItemType::Manuscript,
+		Website => ItemType::Webpage,
+		Catalogue | Generic | GovernmentDocument | Grant | Manual | Multimedia | Other(_) => {
+			ItemType::Document
+		}
+	}
+}
+
+fn names(names: &[CffName]) -> Vec<CslName> {
+	names
+		.iter()
+		.filter_map(|name| {
+			let (mut csl, meta) = match name {
+				CffName::Person(person) => (
+					CslName {
+						family: person.family_names.clone(),
+						given: person.given_names.clone(),
+						non_dropping_particle: person.name_particle.clone(),
+						suffix: person.name_suffix.clone(),
+						..Default::default()
+					},
+					&person.meta,
+				),
+				CffName::Entity(entity) => (
+					CslName {
+						literal: Some(entity.name.clone()?),
+						..Default::default()
+					},
+					&entity.meta,
+				),
+				CffName::Anonymous => return None,
+			};
+			if let Some(orcid) = &meta.orcid {
+				csl.extra.insert("ORCID".into(), orcid.to_string());
+			}
+			Some(csl)
+		})
+		.collect()
+}
+
+fn date(date: CffDate) -> CslDate {
+	CslDate::Single {
+		date: DateParts {
+			year: date.year,
+			month: Some(date.month),
+			day: Some(date.day),
+		},
+		meta: DateMeta::default(),
+	}
+}
+
+fn license(license: &License) -> OrdinaryValue {
+	OrdinaryValue::String(license.to_string())
+}
+
+fn keywords(keywords: &[String]) -> Option<String> {
+	if keywords.is_empty() {
+		None
+	} else {
+		Some(keywords.join(", "))
+	}
+}
+
+fn insert(fields: &mut BTreeMap<String, ItemValue>, name: &str, value: Option<String>) {
+	if let Some(value) = value {
+		fields.insert(
+			name.into(),
+			ItemValue::Ordinary(OrdinaryValue::String(value)),
+		);
+	}
+}
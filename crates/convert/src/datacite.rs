@@ -0,0 +1,631 @@
+//! Export to the [DataCite Metadata Schema][schema] 4.x.
+//!
+//! DataCite registers DOIs for research outputs, including software deposited
+//! in repositories like Zenodo. This produces both the JSON accepted by the
+//! DataCite REST API (the `attributes` of a DOI) and the XML of the schema.
+//!
+//! Creators are the CFF authors, with their ORCID or ROR as name identifiers,
+//! and contacts are contributors of type `ContactPerson`. The references, and
+//! the preferred citation, are related identifiers by their DOI or URL, and
+//! the license is in the rights list with its SPDX identifier.
+//!
+//! CFF has no publisher, which DataCite requires: it can be given in the
+//! [DataCiteOptions], and is [`(:unav)`](UNAVAILABLE) otherwise.
+//!
+//! ```
+//! use citeworks_convert::datacite::{cff_to_datacite_json, DataCiteOptions};
+//!
+//! let cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! license: MIT
+//! authors:
+//!   - family-names: Druskat
+//!     given-names: Stephan
+//!     orcid: https://orcid.org/0000-0003-4925-7248
+//! "#).unwrap();
+//!
+//! let json = cff_to_datacite_json(&cff, &DataCiteOptions::default());
+//! assert_eq!(json["titles"][0]["title"], "My Research Software");
+//! assert_eq!(
+//!     json["creators"][0]["nameIdentifiers"][0]["nameIdentifier"],
+//!     "https://orcid.org/0000-0003-4925-7248"
+//! );
+//! assert_eq!(json["rightsList"][0]["rightsIdentifier"], "MIT");
+//! ```
+//!
+//! [schema]: https://schema.datacite.org/meta/kernel-4/
+
+use citeworks_cff::{
+	identifiers::Identifier,
+	names::{EntityName, Name, PersonName},
+	references::Reference,
+	Cff, License, WorkType,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::xml::XmlWriter;
+
+/// The DataCite kernel namespace.
+pub const NAMESPACE: &str = "http://datacite.org/schema/kernel-4";
+
+/// The DataCite kernel schema location.
+pub const SCHEMA_LOCATION: &str =
+	"http://datacite.org/schema/kernel-4 http://schema.datacite.org/meta/kernel-4/metadata.xsd";
+
+/// DataCite's standard value for information which isn't available.
+pub const UNAVAILABLE: &str = "(:unav)";
+
+/// Options for DataCite export.
+///
+/// These provide properties that CFF has no equivalent for.
+#[derive(Debug, Clone, Default)]
+pub struct DataCiteOptions {
+	/// The publisher of the work, e.g. the repository it's deposited in.
+	pub publisher: Option<String>,
+
+	/// Notes on this release, e.g. its [changelog](crate::changelog) entry.
+	///
+	/// These are a description of type `Other`, after the abstract, which is
+	/// what Zenodo shows as the notes of a deposit.
+	pub release_notes: Option<String>,
+}
+
+/// Export a CFF document as DataCite JSON.
+///
+/// This is the `attributes` object of a DOI in the DataCite REST API.
+pub fn cff_to_datacite_json(cff: &Cff, options: &DataCiteOptions) -> Value {
+	serde_json::to_value(Resource::new(cff, options)).expect("serialising plain data")
+}
+
+/// Export a CFF document as DataCite XML.
+pub fn cff_to_datacite_xml(cff: &Cff, options: &DataCiteOptions) -> String {
+	Resource::new(cff, options).to_xml()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Resource {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	doi: Option<String>,
+	creators: Vec<Creator>,
+	titles: Vec<Title>,
+	publisher: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	publication_year: Option<String>,
+	types: Types,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	subjects: Vec<Subject>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	contributors: Vec<Creator>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	dates: Vec<DateEntry>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	alternate_identifiers: Vec<AlternateIdentifier>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	related_identifiers: Vec<RelatedIdentifier>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	version: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	rights_list: Vec<Rights>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	descriptions: Vec<Description>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	funding_references: Vec<FundingReference>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Creator {
+	name: String,
+	name_type: &'static str,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	given_name: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	family_name: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	name_identifiers: Vec<NameIdentifier>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	affiliation: Vec<Affiliation>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	contributor_type: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NameIdentifier {
+	name_identifier: String,
+	name_identifier_scheme: &'static str,
+	scheme_uri: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Affiliation {
+	name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Title {
+	title: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Types {
+	resource_type_general: &'static str,
+	resource_type: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Subject {
+	subject: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DateEntry {
+	date: String,
+	date_type: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AlternateIdentifier {
+	alternate_identifier: String,
+	alternate_identifier_type: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RelatedIdentifier {
+	related_identifier: String,
+	related_identifier_type: &'static str,
+	relation_type: &'static str,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rights {
+	rights: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	rights_uri: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	rights_identifier: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	rights_identifier_scheme: Option<&'static str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	scheme_uri: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Description {
+	description: String,
+	description_type: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FundingReference {
+	funder_name: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	funder_identifier: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	funder_identifier_type: Option<&'static str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	award_number: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	award_title: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	award_uri: Option<String>,
+}
+
+impl Resource {
+	fn new(cff: &Cff, options: &DataCiteOptions) -> Self {
+		let resource_type = match cff.work_type {
+			Some(WorkType::Dataset) => "Dataset",
+			_ => "Software",
+		};
+
+		let alternate_identifiers = cff
+			.identifiers
+			.iter()
+			.filter_map(|identifier| {
+				let (value, kind) = match identifier {
+					Identifier::Doi { value, .. } if Some(value) == cff.doi.as_ref() => {
+						return None
+					}
+					Identifier::Doi { value, .. } => (value.clone(), "DOI"),
+					Identifier::Url { value, .. } => (value.to_string(), "URL"),
+					Identifier::Swh { value, .. } => (value.clone(), "SWHID"),
+					Identifier::Other { value, .. } => (value.clone(), "Other"),
+				};
+				Some(AlternateIdentifier {
+					alternate_identifier: value,
+					alternate_identifier_type: kind,
+				})
+			})
+			.collect();
+
+		let mut related_identifiers: Vec<_> = cff
+			.preferred_citation
+			.iter()
+			.map(|reference| (reference, "IsDocumentedBy"))
+			.chain(
+				cff.references
+					.iter()
+					.map(|reference| (reference, "References")),
+			)
+			.filter_map(|(reference, relation_type)| {
+				let (related_identifier, related_identifier_type) = related(reference)?;
+				Some(RelatedIdentifier {
+					related_identifier,
+					related_identifier_type,
+					relation_type,
+				})
+			})
+			.collect();
+		if let Some(url) = &cff.repository_code {
+			related_identifiers.push(RelatedIdentifier {
+				related_identifier: url.to_string(),
+				related_identifier_type: "URL",
+				relation_type: "IsSupplementTo",
+			});
+		}
+
+		Self {
+			doi: cff.doi.clone(),
+			creators: cff.authors.iter().map(creator).collect(),
+			titles: vec![Title {
+				title: cff.title.clone(),
+			}],
+			publisher: options
+				.publisher
+				.clone()
+				.unwrap_or_else(|| UNAVAILABLE.into()),
+			publication_year: cff.date_released.map(|date| date.year.to_string()),
+			types: Types {
+				resource_type_general: resource_type,
+				resource_type,
+			},
+			subjects: cff
+				.keywords
+				.iter()
+				.map(|keyword| Subject {
+					subject: keyword.clone(),
+				})
+				.collect(),
+			contributors: cff
+				.contact
+				.iter()
+				.map(|name| Creator {
+					contributor_type: Some("ContactPerson"),
+					..creator(name)
+				})
+				.collect(),
+			dates: cff
+				.date_released
+				.map(|date| DateEntry {
+					date: date.to_string(),
+					date_type: "Issued",
+				})
+				.into_iter()
+				.collect(),
+			alternate_identifiers,
+			related_identifiers,
+			version: cff.version.clone(),
+			rights_list: match (&cff.license, &cff.license_url) {
+				(Some(license), _) => rights(license),
+				(None, Some(url)) => vec![Rights {
+					rights: url.to_string(),
+					rights_uri: Some(url.to_string()),
+					..Default::default()
+				}],
+				(None, None) => Vec::new(),
+			},
+			descriptions: cff
+				.abstract_text
+				.iter()
+				.map(|text| Description {
+					description: text.clone(),
+					description_type: "Abstract",
+				})
+				.chain(options.release_notes.iter().map(|notes| Description {
+					description: notes.clone(),
+					description_type: "Other",
+				}))
+				.collect(),
+			funding_references: cff
+				.funding
+				.iter()
+				.map(|funding| FundingReference {
+					funder_name: funding
+						.funder
+						.name
+						.clone()
+						.unwrap_or_else(|| UNAVAILABLE.into()),
+					funder_identifier: funding.funder.meta.ror.as_ref().map(|u| u.to_string()),
+					funder_identifier_type: funding.funder.meta.ror.as_ref().map(|_| "ROR"),
+					award_number: funding.award_number.clone(),
+					award_title: funding.award_title.clone(),
+					award_uri: funding.award_uri.as_ref().map(|u| u.to_string()),
+				})
+				.collect(),
+		}
+	}
+
+	fn to_xml(&self) -> String {
+		let mut xml = XmlWriter::with_declaration();
+		xml.open(
+			"resource",
+			&[
+				("xmlns", NAMESPACE),
+				("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"),
+				("xsi:schemaLocation", SCHEMA_LOCATION),
+			],
+		);
+
+		xml.leaf_opt(
+			"identifier",
+			&[("identifierType", "DOI")],
+			self.doi.as_deref(),
+		);
+
+		xml.open("creators", &[]);
+		for creator in &self.creators {
+			creator.to_xml(&mut xml, "creator");
+		}
+		xml.close();
+
+		xml.open("titles", &[]);
+		for title in &self.titles {
+			xml.leaf("title", &[], &title.title);
+		}
+		xml.close();
+
+		xml.leaf("publisher", &[], &self.publisher);
+		xml.leaf_opt("publicationYear", &[], self.publication_year.as_deref());
+		xml.leaf(
+			"resourceType",
+			&[("resourceTypeGeneral", self.types.resource_type_general)],
+			self.types.resource_type,
+		);
+
+		if !self.subjects.is_empty() {
+			xml.open("subjects", &[]);
+			for subject in &self.subjects {
+				xml.leaf("subject", &[], &subject.subject);
+			}
+			xml.close();
+		}
+
+		if !self.contributors.is_empty() {
+			xml.open("contributors", &[]);
+			for contributor in &self.contributors {
+				contributor.to_xml(&mut xml, "contributor");
+			}
+			xml.close();
+		}
+
+		if !self.dates.is_empty() {
+			xml.open("dates", &[]);
+			for date in &self.dates {
+				xml.leaf("date", &[("dateType", date.date_type)], &date.date);
+			}
+			xml.close();
+		}
+
+		if !self.alternate_identifiers.is_empty() {
+			xml.open("alternateIdentifiers", &[]);
+			for id in &self.alternate_identifiers {
+				xml.leaf(
+					"alternateIdentifier",
+					&[("alternateIdentifierType", id.alternate_identifier_type)],
+					&id.alternate_identifier,
+				);
+			}
+			xml.close();
+		}
+
+		if !self.related_identifiers.is_empty() {
+			xml.open("relatedIdentifiers", &[]);
+			for id in &self.related_identifiers {
+				xml.leaf(
+					"relatedIdentifier",
+					&[
+						("relatedIdentifierType", id.related_identifier_type),
+						("relationType", id.relation_type),
+					],
+					&id.related_identifier,
+				);
+			}
+			xml.close();
+		}
+
+		xml.leaf_opt("version", &[], self.version.as_deref());
+
+		if !self.rights_list.is_empty() {
+			xml.open("rightsList", &[]);
+			for rights in &self.rights_list {
+				let attrs: Vec<_> = [
+					("rightsURI", rights.rights_uri.as_deref()),
+					("rightsIdentifier", rights.rights_identifier.as_deref()),
+					("rightsIdentifierScheme", rights.rights_identifier_scheme),
+					("schemeURI", rights.scheme_uri),
+				]
+				.into_iter()
+				.filter_map(|(key, value)| Some((key, value?)))
+				.collect();
+				xml.leaf("rights", &attrs, &rights.rights);
+			}
+			xml.close();
+		}
+
+		if !self.descriptions.is_empty() {
+			xml.open("descriptions", &[]);
+			for description in &self.descriptions {
+				xml.leaf(
+					"description",
+					&[("descriptionType", description.description_type)],
+					&description.description,
+				);
+			}
+			xml.close();
+		}
+
+		if !self.funding_references.is_empty() {
+			xml.open("fundingReferences", &[]);
+			for funding in &self.funding_references {
+				xml.open("fundingReference", &[]);
+				xml.leaf("funderName", &[], &funding.funder_name);
+				if let (Some(id), Some(id_type)) =
+					(&funding.funder_identifier, funding.funder_identifier_type)
+				{
+					xml.leaf("funderIdentifier", &[("funderIdentifierType", id_type)], id);
+				}
+				if let Some(number) = &funding.award_number {
+					match &funding.award_uri {
+						Some(uri) => xml.leaf("awardNumber", &[("awardURI", uri)], number),
+						None => xml.leaf("awardNumber", &[], number),
+					}
+				}
+				xml.leaf_opt("awardTitle", &[], funding.award_title.as_deref());
+				xml.close();
+			}
+			xml.close();
+		}
+
+		xml.close();
+		xml.finish()
+	}
+}
+
+impl Creator {
+	fn to_xml(&self, xml: &mut XmlWriter, element: &'static str) {
+		let name_element = if element == "creator" {
+			"creatorName"
+		} else {
+			"contributorName"
+		};
+
+		match self.contributor_type {
+			Some(contributor_type) => xml.open(element, &[("contributorType", contributor_type)]),
+			None => xml.open(element, &[]),
+		}
+		xml.leaf(name_element, &[("nameType", self.name_type)], &self.name);
+		xml.leaf_opt("givenName", &[], self.given_name.as_deref());
+		xml.leaf_opt("familyName", &[], self.family_name.as_deref());
+		for id in &self.name_identifiers {
+			xml.leaf(
+				"nameIdentifier",
+				&[
+					("nameIdentifierScheme", id.name_identifier_scheme),
+					("schemeURI", id.scheme_uri),
+				],
+				&id.name_identifier,
+			);
+		}
+		for affiliation in &self.affiliation {
+			xml.leaf("affiliation", &[], &affiliation.name);
+		}
+		xml.close();
+	}
+}
+
+fn creator(name: &Name) -> Creator {
+	match name {
+		Name::Person(person) => person_creator(person),
+		Name::Entity(entity) => entity_creator(entity),
+		Name::Anonymous => Creator {
+			name: UNAVAILABLE.into(),
+			name_type: "Personal",
+			..Default::default()
+		},
+	}
+}
+
+fn person_creator(person: &PersonName) -> Creator {
+	let family = match (&person.name_particle, &person.family_names) {
+		(Some(particle), Some(family)) => Some(format!("{particle} {family}")),
+		(None, family) => family.clone(),
+		(Some(particle), None) => Some(particle.clone()),
+	};
+	let name = match (&family, &person.given_names) {
+		(Some(family), Some(given)) => format!("{family}, {given}"),
+		(Some(name), None) | (None, Some(name)) => name.clone(),
+		(None, None) => UNAVAILABLE.into(),
+	};
+
+	Creator {
+		name,
+		name_type: "Personal",
+		given_name: person.given_names.clone(),
+		family_name: family,
+		name_identifiers: person
+			.meta
+			.orcid
+			.iter()
+			.map(|orcid| NameIdentifier {
+				name_identifier: orcid.to_string(),
+				name_identifier_scheme: "ORCID",
+				scheme_uri: "https://orcid.org",
+			})
+			.collect(),
+		affiliation: person
+			.affiliation
+			.iter()
+			.map(|name| Affiliation { name: name.clone() })
+			.collect(),
+		contributor_type: None,
+	}
+}
+
+fn entity_creator(entity: &EntityName) -> Creator {
+	Creator {
+		name: entity.name.clone().unwrap_or_else(|| UNAVAILABLE.into()),
+		name_type: "Organizational",
+		name_identifiers: entity
+			.meta
+			.ror
+			.iter()
+			.map(|ror| NameIdentifier {
+				name_identifier: ror.to_string(),
+				name_identifier_scheme: "ROR",
+				scheme_uri: "https://ror.org",
+			})
+			.collect(),
+		..Default::default()
+	}
+}
+
+fn related(reference: &Reference) -> Option<(String, &'static str)> {
+	reference
+		.doi
+		.clone()
+		.map(|doi| (doi, "DOI"))
+		.or_else(|| reference.url.as_ref().map(|url| (url.to_string(), "URL")))
+}
+
+/// Rights for each license, with its SPDX identifier and name when it's a
+/// single license, or as the expression otherwise.
+fn rights(license: &License) -> Vec<Rights> {
+	license
+		.members()
+		.into_iter()
+		.map(|exp| match spdx::license_id(&exp) {
+			Some(id) => Rights {
+				rights: id.full_name.into(),
+				rights_uri: Some(format!("https://spdx.org/licenses/{}", id.name)),
+				rights_identifier: Some(id.name.into()),
+				rights_identifier_scheme: Some("SPDX"),
+				scheme_uri: Some("https://spdx.org/licenses/"),
+			},
+			None => Rights {
+				rights: exp,
+				..Default::default()
+			},
+		})
+		.collect()
+}
@@ -0,0 +1,163 @@
+//! Finding entries that share a DOI.
+//!
+//! A DOI identifies one work, so two entries with the same DOI are either
+//! duplicates, or one of them has a data-entry error: a copy-pasted DOI, or a
+//! mistyped title or year. This groups entries by DOI and reports which fields
+//! they disagree on, so that can be fixed before publishing.
+//!
+//! DOIs are compared case-insensitively, ignoring `doi:` and `https://doi.org/`
+//! prefixes. Titles and author names are compared ignoring case and
+//! punctuation.
+//!
+//! ```
+//! use citeworks_convert::duplicates::{item_duplicates, Field};
+//!
+//! let items = citeworks_csl::from_str(r#"[
+//!     {"id": "a", "type": "book", "DOI": "10.1234/abc", "title": "A book", "issued": {"date-parts": [[2017]]}},
+//!     {"id": "b", "type": "book", "title": "Another book"},
+//!     {"id": "c", "type": "book", "DOI": "https://doi.org/10.1234/ABC", "title": "A Book.", "issued": {"date-parts": [[2018]]}}
+//! ]"#).unwrap();
+//!
+//! let duplicates = item_duplicates(&items);
+//! assert_eq!(duplicates.len(), 1);
+//! assert_eq!(duplicates[0].entries, vec![0, 2]);
+//! assert_eq!(duplicates[0].conflicts, vec![Field::Year]);
+//! ```
+
+use std::collections::BTreeMap;
+
+use citeworks_cff::references::Reference;
+use citeworks_csl::Item;
+
+use crate::{
+	csl::{bare_doi, references_to_items},
+	keys::year,
+};
+
+/// Entries sharing a DOI.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DuplicateDoi {
+	/// The DOI, normalised to lowercase without a prefix.
+	pub doi: String,
+
+	/// Indices of the entries with this DOI, in the order given.
+	pub entries: Vec<usize>,
+
+	/// Fields the entries disagree on.
+	///
+	/// If this is empty, the entries are plain duplicates.
+	pub conflicts: Vec<Field>,
+}
+
+impl DuplicateDoi {
+	/// Whether the entries disagree on some of their metadata.
+	pub fn is_conflicting(&self) -> bool {
+		!self.conflicts.is_empty()
+	}
+}
+
+/// Fields compared between entries with the same DOI.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Field {
+	/// The title.
+	Title,
+
+	/// The year of publication.
+	Year,
+
+	/// The family names of the authors, in order.
+	Authors,
+}
+
+/// Find CSL items sharing a DOI.
+///
+/// Groups are returned in order of their first entry.
+pub fn item_duplicates(items: &[Item]) -> Vec<DuplicateDoi> {
+	let mut by_doi: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+	for (n, item) in items.iter().enumerate() {
+		if let Some(doi) = item.doi.as_ref().map(|doi| normalise_doi(&doi.to_string())) {
+			if !doi.is_empty() {
+				by_doi.entry(doi).or_default().push(n);
+			}
+		}
+	}
+
+	let mut duplicates: Vec<DuplicateDoi> = by_doi
+		.into_iter()
+		.filter(|(_, entries)| entries.len() > 1)
+		.map(|(doi, entries)| {
+			let group: Vec<&Item> = entries.iter().map(|&n| &items[n]).collect();
+			let mut conflicts = Vec::new();
+			if differ(&group, |item| {
+				item.title.as_ref().map(|t| normalise(&t.to_string()))
+			}) {
+				conflicts.push(Field::Title);
+			}
+			if differ(&group, year) {
+				conflicts.push(Field::Year);
+			}
+			if differ(&group, authors) {
+				conflicts.push(Field::Authors);
+			}
+
+			DuplicateDoi {
+				doi,
+				entries,
+				conflicts,
+			}
+		})
+		.collect();
+	duplicates.sort_by_key(|duplicate| duplicate.entries[0]);
+	duplicates
+}
+
+/// Find CFF references sharing a DOI.
+///
+/// Indices are into the given references, e.g. the `references` of a CFF file.
+pub fn reference_duplicates(references: &[Reference]) -> Vec<DuplicateDoi> {
+	item_duplicates(&references_to_items(references))
+}
+
+/// Whether entries have different values for a field.
+///
+/// Entries without the field are ignored: a missing year isn't a conflict.
+fn differ<T: PartialEq>(items: &[&Item], field: impl Fn(&Item) -> Option<T>) -> bool {
+	let mut values = items.iter().filter_map(|item| field(item));
+	match values.next() {
+		Some(first) => values.any(|value| value != first),
+		None => false,
+	}
+}
+
+fn authors(item: &Item) -> Option<Vec<String>> {
+	if item.author.is_empty() {
+		return None;
+	}
+
+	Some(
+		item.author
+			.iter()
+			.map(|name| {
+				normalise(
+					name.family
+						.as_deref()
+						.or(name.literal.as_deref())
+						.unwrap_or_default(),
+				)
+			})
+			.collect(),
+	)
+}
+
+pub(crate) fn normalise_doi(doi: &str) -> String {
+	bare_doi(doi).to_lowercase()
+}
+
+/// Lowercase words without punctuation.
+pub(crate) fn normalise(text: &str) -> String {
+	text.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+		.map(str::to_lowercase)
+		.collect::<Vec<_>>()
+		.join(" ")
+}
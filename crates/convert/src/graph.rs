@@ -0,0 +1,337 @@
+//! Graphs of how a work's citation metadata connects to the literature.
+//!
+//! The graph has a node for the work described by a CFF file, one for its
+//! `preferred-citation`, and one for each of its `references`, with edges from
+//! the work to the others. Nodes are identified by DOI, URL, or other
+//! identifier where there is one, so a reference that's also the preferred
+//! citation, or that's listed twice, is a single node.
+//!
+//! Graphs render to [Graphviz] DOT, or to JSON for other tools.
+//!
+//! ```
+//! use citeworks_convert::graph::{Graph, Relation};
+//!
+//! let cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! doi: 10.5281/zenodo.1234
+//! authors:
+//!   - name: The Research Software project
+//! preferred-citation:
+//!   type: article
+//!   title: My Research Software, the paper
+//!   doi: 10.21105/joss.01234
+//!   authors:
+//!     - name: The Research Software project
+//! references:
+//!   - type: article
+//!     title: The same paper
+//!     doi: https://doi.org/10.21105/JOSS.01234
+//!     authors:
+//!       - name: The Research Software project
+//! "#).unwrap();
+//!
+//! let graph = Graph::from_cff(&cff);
+//! assert_eq!(graph.nodes.len(), 2);
+//! assert_eq!(graph.nodes[1].id, "doi:10.21105/joss.01234");
+//! assert_eq!(graph.edges.len(), 2);
+//! assert_eq!(graph.edges[0].relation, Relation::PreferredCitation);
+//! assert_eq!(graph.edges[1].relation, Relation::Cites);
+//! ```
+//!
+//! [Graphviz]: https://graphviz.org
+
+use std::{fmt, str::FromStr};
+
+use citeworks_cff::{identifiers::Identifier, references::Reference, Cff};
+use serde_json::{json, Value};
+
+use crate::csl::bare_doi;
+
+/// A graph of a work, its preferred citation, and its references.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Graph {
+	/// The nodes, starting with the work itself.
+	pub nodes: Vec<Node>,
+
+	/// The edges between nodes.
+	pub edges: Vec<Edge>,
+}
+
+/// A work in the graph.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Node {
+	/// Identifier of the node.
+	///
+	/// This is `doi:` followed by the lowercase bare DOI, `url:` followed by
+	/// the URL, or `swh:` or `other:` followed by that identifier. Works without
+	/// any of those are identified by where they are in the CFF document, e.g.
+	/// `references[2]`.
+	pub id: String,
+
+	/// The title of the work.
+	pub label: String,
+
+	/// How the work appears in the CFF document.
+	///
+	/// If it appears several times, this is the first.
+	pub kind: NodeKind,
+}
+
+/// How a work appears in the CFF document.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum NodeKind {
+	/// The work the CFF document is about.
+	Work,
+
+	/// The `preferred-citation`.
+	PreferredCitation,
+
+	/// One of the `references`.
+	Reference,
+}
+
+/// A relation between two works.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Edge {
+	/// Identifier of the node the edge starts at.
+	pub from: String,
+
+	/// Identifier of the node the edge ends at.
+	pub to: String,
+
+	/// How the works are related.
+	pub relation: Relation,
+}
+
+/// How two works are related.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Relation {
+	/// The second work is how the first should be cited.
+	PreferredCitation,
+
+	/// The first work cites the second.
+	Cites,
+}
+
+impl NodeKind {
+	fn name(self) -> &'static str {
+		match self {
+			Self::Work => "work",
+			Self::PreferredCitation => "preferred-citation",
+			Self::Reference => "reference",
+		}
+	}
+}
+
+impl Relation {
+	fn name(self) -> &'static str {
+		match self {
+			Self::PreferredCitation => "preferred-citation",
+			Self::Cites => "cites",
+		}
+	}
+}
+
+/// Output formats for graphs.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum GraphFormat {
+	/// Graphviz DOT.
+	Dot,
+
+	/// JSON, with `nodes` and `edges` arrays.
+	Json,
+}
+
+impl fmt::Display for GraphFormat {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Dot => "dot",
+			Self::Json => "json",
+		})
+	}
+}
+
+impl FromStr for GraphFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"dot" | "graphviz" => Ok(Self::Dot),
+			"json" => Ok(Self::Json),
+			_ => Err(format!("unknown graph format: {s:?}")),
+		}
+	}
+}
+
+impl Graph {
+	/// Build the graph of a CFF document.
+	pub fn from_cff(cff: &Cff) -> Self {
+		let mut graph = Self::default();
+
+		let work = graph.add(
+			node_id(
+				cff.doi.as_deref(),
+				&cff.identifiers,
+				&[
+					cff.repository_code.as_ref().map(|url| url.as_str()),
+					cff.url.as_ref().map(|url| url.as_str()),
+				],
+			)
+			.unwrap_or_else(|| "work".into()),
+			cff.title.clone(),
+			NodeKind::Work,
+		);
+
+		if let Some(preferred) = &cff.preferred_citation {
+			let id =
+				graph.add_reference(preferred, "preferred-citation", NodeKind::PreferredCitation);
+			graph.link(&work, id, Relation::PreferredCitation);
+		}
+
+		for (n, reference) in cff.references.iter().enumerate() {
+			let id =
+				graph.add_reference(reference, &format!("references[{n}]"), NodeKind::Reference);
+			graph.link(&work, id, Relation::Cites);
+		}
+
+		graph
+	}
+
+	/// Render the graph in a format.
+	pub fn render(&self, format: GraphFormat) -> String {
+		match format {
+			GraphFormat::Dot => self.to_dot(),
+			GraphFormat::Json => self.to_json().to_string(),
+		}
+	}
+
+	/// The graph in Graphviz DOT.
+	///
+	/// The work is drawn in bold, and edges to the preferred citation dashed.
+	pub fn to_dot(&self) -> String {
+		let mut dot = String::from("digraph citations {\n\trankdir=LR;\n\tnode [shape=box];\n");
+		for node in &self.nodes {
+			let style = match node.kind {
+				NodeKind::Work => ", style=bold",
+				NodeKind::PreferredCitation => ", style=rounded",
+				NodeKind::Reference => "",
+			};
+			dot.push_str(&format!(
+				"\t{} [label={}{style}];\n",
+				quote(&node.id),
+				quote(&node.label)
+			));
+		}
+		for edge in &self.edges {
+			let style = match edge.relation {
+				Relation::PreferredCitation => " [style=dashed, label=\"preferred-citation\"]",
+				Relation::Cites => "",
+			};
+			dot.push_str(&format!(
+				"\t{} -> {}{style};\n",
+				quote(&edge.from),
+				quote(&edge.to)
+			));
+		}
+		dot.push_str("}\n");
+		dot
+	}
+
+	/// The graph as JSON, with `nodes` and `edges` arrays.
+	pub fn to_json(&self) -> Value {
+		json!({
+			"nodes": self.nodes.iter().map(|node| json!({
+				"id": node.id,
+				"label": node.label,
+				"kind": node.kind.name(),
+			})).collect::<Vec<_>>(),
+			"edges": self.edges.iter().map(|edge| json!({
+				"from": edge.from,
+				"to": edge.to,
+				"relation": edge.relation.name(),
+			})).collect::<Vec<_>>(),
+		})
+	}
+
+	fn add_reference(&mut self, reference: &Reference, path: &str, kind: NodeKind) -> String {
+		let id = node_id(
+			reference.doi.as_deref(),
+			&reference.identifiers,
+			&[
+				reference.url.as_ref().map(|url| url.as_str()),
+				reference.repository_code.as_ref().map(|url| url.as_str()),
+			],
+		)
+		.unwrap_or_else(|| path.into());
+		let label = reference
+			.title
+			.clone()
+			.unwrap_or_else(|| format!("untitled ({path})"));
+		self.add(id, label, kind)
+	}
+
+	/// Add a node unless there's already one with that id, and return the id.
+	fn add(&mut self, id: String, label: String, kind: NodeKind) -> String {
+		if !self.nodes.iter().any(|node| node.id == id) {
+			self.nodes.push(Node {
+				id: id.clone(),
+				label,
+				kind,
+			});
+		}
+		id
+	}
+
+	/// Add an edge, unless it's already there or would loop.
+	fn link(&mut self, from: &str, to: String, relation: Relation) {
+		if from == to
+			|| self
+				.edges
+				.iter()
+				.any(|edge| edge.from == from && edge.to == to && edge.relation == relation)
+		{
+			return;
+		}
+		self.edges.push(Edge {
+			from: from.into(),
+			to,
+			relation,
+		});
+	}
+}
+
+/// The identifier of a work, preferring DOIs, then URLs, then other identifiers.
+fn node_id(doi: Option<&str>, identifiers: &[Identifier], urls: &[Option<&str>]) -> Option<String> {
+	let doi = doi.or_else(|| {
+		identifiers.iter().find_map(|id| match id {
+			Identifier::Doi { value, .. } => Some(value.as_str()),
+			_ => None,
+		})
+	});
+	if let Some(doi) = doi {
+		return Some(format!("doi:{}", bare_doi(doi).to_lowercase()));
+	}
+
+	let url = urls.iter().find_map(|url| *url).or_else(|| {
+		identifiers.iter().find_map(|id| match id {
+			Identifier::Url { value, .. } => Some(value.as_str()),
+			_ => None,
+		})
+	});
+	if let Some(url) = url {
+		return Some(format!("url:{url}"));
+	}
+
+	identifiers.iter().find_map(|id| match id {
+		Identifier::Swh { value, .. } => Some(format!("swh:{}", value.trim_start_matches("swh:"))),
+		Identifier::Other { value, .. } => Some(format!("other:{value}")),
+		_ => None,
+	})
+}
+
+/// Quote a string as a DOT identifier.
+fn quote(s: &str) -> String {
+	format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
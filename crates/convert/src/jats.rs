@@ -0,0 +1,503 @@
+//! Export to [JATS] reference lists.
+//!
+//! The Journal Article Tag Suite is the XML format most journal production
+//! pipelines work with. This produces a `<ref-list>` element containing one
+//! `<ref>` with an `<element-citation>` per reference, which can be embedded
+//! in the `<back>` matter of a JATS article.
+//!
+//! Both CFF references and CSL items can be exported.
+//!
+//! ```
+//! use citeworks_cff::references::{RefType, Reference};
+//! use citeworks_convert::jats::references_to_jats;
+//!
+//! let reference = Reference {
+//!     work_type: RefType::Article,
+//!     title: Some("A paper".into()),
+//!     journal: Some("Journal of Things".into()),
+//!     volume: Some(12),
+//!     ..Default::default()
+//! };
+//!
+//! let xml = references_to_jats(&[reference]);
+//! assert!(xml.contains(r#"<element-citation publication-type="journal">"#));
+//! assert!(xml.contains("<article-title>A paper</article-title>"));
+//! assert!(xml.contains("<source>Journal of Things</source>"));
+//! ```
+//!
+//! [JATS]: https://jats.nlm.nih.gov/
+
+use citeworks_cff::{
+	names::Name as CffName,
+	references::{RefType, Reference},
+};
+use citeworks_csl::{
+	dates::{Date as CslDate, DateParts},
+	items::{ItemType, ItemValue},
+	names::Name as CslName,
+	ordinaries::OrdinaryValue,
+	Item,
+};
+
+use crate::{
+	report::{send, unmapped, IgnoreWarnings, RecordReport, WarningSink},
+	xml::XmlWriter,
+};
+
+/// Reference keys which are converted.
+const MAPPED_REFERENCE_KEYS: &[&str] = &[
+	"type",
+	"authors",
+	"editors",
+	"translators",
+	"title",
+	"journal",
+	"collection-title",
+	"date-published",
+	"date-released",
+	"year",
+	"month",
+	"edition",
+	"volume",
+	"issue",
+	"start",
+	"end",
+	"version",
+	"conference",
+	"institution",
+	"publisher",
+	"doi",
+	"pmcid",
+	"isbn",
+	"issn",
+	"url",
+	"repository-code",
+];
+
+/// CSL variables which are converted, or used as the id.
+const MAPPED_VARIABLES: &[&str] = &[
+	"id",
+	"type",
+	"author",
+	"editor",
+	"translator",
+	"title",
+	"container-title",
+	"issued",
+	"edition",
+	"volume",
+	"issue",
+	"page",
+	"version",
+	"event-title",
+	"publisher-place",
+	"publisher",
+	"DOI",
+	"PMID",
+	"PMCID",
+	"ISBN",
+	"ISSN",
+	"URL",
+];
+
+const NO_EQUIVALENT: &str = "no JATS equivalent";
+
+/// Export CFF references as a JATS `<ref-list>`.
+///
+/// References are given the ids `ref1`, `ref2`, etc, in order.
+pub fn references_to_jats(references: &[Reference]) -> String {
+	references_to_jats_with(references, &mut IgnoreWarnings)
+}
+
+/// Export CFF references as a JATS `<ref-list>`.
+///
+/// Fields which can't be converted are reported to the sink, under the ids of
+/// the references.
+pub fn references_to_jats_with(references: &[Reference], report: &mut dyn WarningSink) -> String {
+	ref_list(references.iter().enumerate().map(|(n, reference)| {
+		let id = format!("ref{}", n + 1);
+		let mut record = RecordReport::new(id.clone());
+		unmapped(reference, MAPPED_REFERENCE_KEYS, NO_EQUIVALENT, &mut record);
+		if reference.url.is_some() && reference.repository_code.is_some() {
+			record.dropped("repository-code", "URI taken by url");
+		}
+		send(report, record);
+		(id, reference_citation(reference))
+	}))
+}
+
+/// Export CSL items as a JATS `<ref-list>`.
+///
+/// References are given the ids of the items, with characters that aren't
+/// valid in XML ids replaced.
+pub fn items_to_jats(items: &[Item]) -> String {
+	items_to_jats_with(items, &mut IgnoreWarnings)
+}
+
+/// Export CSL items as a JATS `<ref-list>`.
+///
+/// Variables which can't be converted are reported to the sink, under the ids
+/// of the items.
+pub fn items_to_jats_with(items: &[Item], report: &mut dyn WarningSink) -> String {
+	ref_list(items.iter().map(|item| {
+		let mut record = RecordReport::new(item.id.clone());
+		unmapped(item, MAPPED_VARIABLES, NO_EQUIVALENT, &mut record);
+		send(report, record);
+		(xml_id(&item.id), item_citation(item))
+	}))
+}
+
+/// A name in a person group.
+enum JatsName {
+	Person {
+		surname: String,
+		given_names: Option<String>,
+		suffix: Option<String>,
+	},
+	Collab(String),
+}
+
+/// An element citation, in a format-independent shape.
+#[derive(Default)]
+struct Citation {
+	publication_type: &'static str,
+	groups: Vec<(&'static str, Vec<JatsName>)>,
+	elements: Vec<(&'static str, Attrs, String)>,
+}
+
+type Attrs = &'static [(&'static str, &'static str)];
+
+impl Citation {
+	fn add(&mut self, name: &'static str, text: Option<impl ToString>) {
+		self.add_with(name, &[], text);
+	}
+
+	fn add_with(&mut self, name: &'static str, attrs: Attrs, text: Option<impl ToString>) {
+		if let Some(text) = text {
+			self.elements.push((name, attrs, text.to_string()));
+		}
+	}
+
+	fn group(&mut self, group_type: &'static str, names: Vec<JatsName>) {
+		if !names.is_empty() {
+			self.groups.push((group_type, names));
+		}
+	}
+}
+
+fn ref_list(citations: impl Iterator<Item = (String, Citation)>) -> String {
+	let mut xml = XmlWriter::default();
+	xml.open("ref-list", &[]);
+	for (id, citation) in citations {
+		xml.open("ref", &[("id", &id)]);
+		xml.open(
+			"element-citation",
+			&[("publication-type", citation.publication_type)],
+		);
+
+		for (group_type, names) in &citation.groups {
+			xml.open("person-group", &[("person-group-type", group_type)]);
+			for name in names {
+				match name {
+					JatsName::Person {
+						surname,
+						given_names,
+						suffix,
+					} => {
+						xml.open("name", &[]);
+						xml.leaf("surname", &[], surname);
+						xml.leaf_opt("given-names", &[], given_names.as_deref());
+						xml.leaf_opt("suffix", &[], suffix.as_deref());
+						xml.close();
+					}
+					JatsName::Collab(name) => xml.leaf("collab", &[], name),
+				}
+			}
+			xml.close();
+		}
+
+		for (name, attrs, text) in &citation.elements {
+			xml.leaf(name, attrs, text);
+		}
+
+		xml.close();
+		xml.close();
+	}
+	xml.close();
+	xml.finish()
+}
+
+/// Make a string usable as an XML id (an NCName).
+fn xml_id(id: &str) -> String {
+	let mut out: String = id
+		.chars()
+		.map(|c| {
+			if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+				c
+			} else {
+				'-'
+			}
+		})
+		.collect();
+
+	if !out.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+		out.insert_str(0, "ref-");
+	}
+	out
+}
+
+fn reference_citation(reference: &Reference) -> Citation {
+	let mut citation = Citation {
+		publication_type: reference_type(&reference.work_type),
+		..Default::default()
+	};
+
+	citation.group("author", cff_names(&reference.authors));
+	citation.group("editor", cff_names(&reference.editors));
+	citation.group("translator", cff_names(&reference.translators));
+
+	let container = reference
+		.journal
+		.as_ref()
+		.or(reference.collection_title.as_ref());
+	match container {
+		Some(container) => {
+			citation.add(
+				title_element(&reference.work_type),
+				reference.title.as_ref(),
+			);
+			citation.add("source", Some(container));
+		}
+		None => citation.add("source", reference.title.as_ref()),
+	}
+
+	let date = reference.date_published.or(reference.date_released);
+	citation.add(
+		"year",
+		date.map(|d| d.year)
+			.or_else(|| reference.year.and_then(|y| i64::try_from(y).ok())),
+	);
+	citation.add(
+		"month",
+		date.map(|d| d.month)
+			.or(reference.month)
+			.map(|m| format!("{m:02}")),
+	);
+	citation.add("day", date.map(|d| format!("{:02}", d.day)));
+
+	citation.add("edition", reference.edition.as_ref());
+	citation.add("volume", reference.volume);
+	citation.add("issue", reference.issue.as_ref());
+	citation.add("fpage", reference.start);
+	citation.add("lpage", reference.end);
+	citation.add("version", reference.version.as_ref());
+	citation.add(
+		"conf-name",
+		reference.conference.as_ref().and_then(|c| c.name.as_ref()),
+	);
+	citation.add(
+		"institution",
+		reference.institution.as_ref().and_then(|i| i.name.as_ref()),
+	);
+	citation.add(
+		"publisher-loc",
+		reference
+			.publisher
+			.as_ref()
+			.and_then(|p| p.meta.city.as_ref().or(p.meta.location.as_ref())),
+	);
+	citation.add(
+		"publisher-name",
+		reference.publisher.as_ref().and_then(|p| p.name.as_ref()),
+	);
+	citation.add_with("pub-id", &[("pub-id-type", "doi")], reference.doi.as_ref());
+	citation.add_with(
+		"pub-id",
+		&[("pub-id-type", "pmcid")],
+		reference.pmcid.as_ref(),
+	);
+	citation.add("isbn", reference.isbn.as_ref());
+	citation.add("issn", reference.issn.as_ref());
+	citation.add(
+		"uri",
+		reference
+			.url
+			.as_ref()
+			.or(reference.repository_code.as_ref()),
+	);
+
+	citation
+}
+
+fn reference_type(work_type: &RefType) -> &'static str {
+	use RefType::*;
+	match work_type {
+		Article | MagazineArticle | NewspaperArticle => "journal",
+		Book | EditedWork | Manual => "book",
+		Conference | ConferencePaper | Proceedings => "confproc",
+		Data | Database => "data",
+		GovernmentDocument | Report => "report",
+		Patent => "patent",
+		Software
+		| SoftwareCode
+		| SoftwareContainer
+		| SoftwareExecutable
+		| SoftwareVirtualMachine => "software",
+		Standard => "standard",
+		Thesis => "thesis",
+		Blog | Website => "webpage",
+		_ => "other",
+	}
+}
+
+fn title_element(work_type: &RefType) -> &'static str {
+	match work_type {
+		RefType::Book | RefType::EditedWork => "chapter-title",
+		_ => "article-title",
+	}
+}
+
+fn cff_names(names: &[CffName]) -> Vec<JatsName> {
+	names
+		.iter()
+		.filter_map(|name| match name {
+			CffName::Person(person) => {
+				let surname = match (&person.name_particle, &person.family_names) {
+					(Some(particle), Some(family)) => format!("{particle} {family}"),
+					(None, Some(family)) => family.clone(),
+					(_, None) => person.given_names.clone()?,
+				};
+				Some(JatsName::Person {
+					given_names: person
+						.given_names
+						.clone()
+						.filter(|_| person.family_names.is_some()),
+					suffix: person.name_suffix.clone(),
+					surname,
+				})
+			}
+			CffName::Entity(entity) => entity.name.clone().map(JatsName::Collab),
+			CffName::Anonymous => Some(JatsName::Collab("Anonymous".into())),
+		})
+		.collect()
+}
+
+fn item_citation(item: &Item) -> Citation {
+	let mut citation = Citation {
+		publication_type: item_type(&item.item_type),
+		..Default::default()
+	};
+
+	citation.group("author", csl_names(&item.author));
+	citation.group("editor", csl_names(&item.editor));
+	citation.group("translator", csl_names(&item.translator));
+
+	let title_element = match item.item_type {
+		ItemType::Chapter => "chapter-title",
+		_ => "article-title",
+	};
+	match &item.container_title {
+		Some(container) => {
+			citation.add(title_element, item.title.as_ref());
+			citation.add("source", Some(container));
+		}
+		None => citation.add("source", item.title.as_ref()),
+	}
+
+	if let Some(date) = item.issued.as_ref().and_then(date_parts) {
+		citation.add("year", Some(date.year));
+		citation.add("month", date.month.map(|m| format!("{m:02}")));
+		citation.add("day", date.day.map(|d| format!("{d:02}")));
+	}
+
+	citation.add("edition", ordinary_field(item, "edition"));
+	citation.add("volume", item.volume.as_ref());
+	citation.add("issue", item.issue.as_ref());
+	if let Some(page) = &item.page {
+		let page = page.to_string();
+		match page.split_once(['-', '–']) {
+			Some((first, last)) => {
+				citation.add("fpage", Some(first.trim()));
+				citation.add("lpage", Some(last.trim()));
+			}
+			None => citation.add("fpage", Some(page)),
+		}
+	}
+	citation.add("version", ordinary_field(item, "version"));
+	citation.add("conf-name", ordinary_field(item, "event-title"));
+	citation.add("publisher-loc", ordinary_field(item, "publisher-place"));
+	citation.add("publisher-name", ordinary_field(item, "publisher"));
+	citation.add_with("pub-id", &[("pub-id-type", "doi")], item.doi.as_ref());
+	citation.add_with(
+		"pub-id",
+		&[("pub-id-type", "pmid")],
+		ordinary_field(item, "PMID"),
+	);
+	citation.add_with(
+		"pub-id",
+		&[("pub-id-type", "pmcid")],
+		ordinary_field(item, "PMCID"),
+	);
+	citation.add("isbn", ordinary_field(item, "ISBN"));
+	citation.add("issn", item.issn.as_ref());
+	citation.add("uri", item.url.as_ref());
+
+	citation
+}
+
+fn item_type(item_type: &ItemType) -> &'static str {
+	use ItemType::*;
+	match item_type {
+		Article | ArticleJournal | ArticleMagazine | ArticleNewspaper | Periodical => "journal",
+		Book | Chapter | Classic | Collection => "book",
+		PaperConference => "confproc",
+		Dataset => "data",
+		Report => "report",
+		Patent => "patent",
+		Software => "software",
+		Standard => "standard",
+		Thesis => "thesis",
+		Post | PostWeblog | Webpage => "webpage",
+		_ => "other",
+	}
+}
+
+fn csl_names(names: &[CslName]) -> Vec<JatsName> {
+	names
+		.iter()
+		.filter_map(|name| {
+			let family = match (&name.non_dropping_particle, &name.family) {
+				(Some(particle), Some(family)) => Some(format!("{particle} {family}")),
+				(_, family) => family.clone(),
+			};
+			let given = match (&name.given, &name.dropping_particle) {
+				(Some(given), Some(particle)) => Some(format!("{given} {particle}")),
+				(given, particle) => given.clone().or_else(|| particle.clone()),
+			};
+
+			match (family, &name.literal) {
+				(Some(surname), _) => Some(JatsName::Person {
+					surname,
+					given_names: given,
+					suffix: name.suffix.clone(),
+				}),
+				(None, Some(literal)) => Some(JatsName::Collab(literal.clone())),
+				(None, None) => None,
+			}
+		})
+		.collect()
+}
+
+fn date_parts(date: &CslDate) -> Option<DateParts> {
+	match date {
+		CslDate::Single { date, .. } | CslDate::Range { start: date, .. } => Some(*date),
+		CslDate::Raw { .. } | CslDate::Edtf { .. } | CslDate::MetaOnly { .. } => None,
+	}
+}
+
+fn ordinary_field<'item>(item: &'item Item, field: &str) -> Option<&'item OrdinaryValue> {
+	match item.fields.get(field) {
+		Some(ItemValue::Ordinary(value)) => Some(value),
+		_ => None,
+	}
+}
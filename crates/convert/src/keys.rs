@@ -0,0 +1,289 @@
+//! Citation key generation.
+//!
+//! CFF references don't have keys, and CSL items sometimes come without ids,
+//! but BibTeX and pandoc need one for every entry. Keys are generated from a
+//! pattern in the style of [Better BibTeX], e.g. `[auth:lower][year]` gives
+//! `druskat2017`, and `[authEtAl][year][titleword]` gives `DoeEtAl2017Art`.
+//!
+//! Patterns are made of literal text and fields in square brackets:
+//!
+//! - `[auth]`: the first author's family name (or the name of an entity),
+//!   or `Anon` if there are no authors;
+//! - `[authors]`: the family names of all authors;
+//! - `[authEtAl]`: the family name of a single author, of both of two authors,
+//!   or of the first author followed by `EtAl` for more;
+//! - `[year]`: the year of issue;
+//! - `[shortyear]`: the last two digits of the year;
+//! - `[titleword]` or `[veryshorttitle]`: the first word of the title, skipping
+//!   words like "a", "the", or "of";
+//! - `[shorttitle]`: the first three such words of the title.
+//!
+//! Fields can be followed by `:lower` or `:upper` to change their case, e.g.
+//! `[auth:lower]`. Only letters and digits are kept from names and titles.
+//!
+//! When a key has already been given out, a letter is appended: `doe2017`,
+//! `doe2017b`, `doe2017c`…
+//!
+//! ```
+//! use citeworks_convert::keys::KeyGenerator;
+//!
+//! let items = citeworks_csl::from_str(r#"[{
+//!     "id": "",
+//!     "type": "article-journal",
+//!     "author": [{"family": "Doe"}, {"family": "Roe"}, {"family": "Moe"}],
+//!     "title": "The art of citation",
+//!     "issued": {"date-parts": [[2017]]}
+//! }]"#).unwrap();
+//!
+//! let mut keys = KeyGenerator::new("[authEtAl][year][titleword]".parse().unwrap());
+//! assert_eq!(keys.generate(&items[0]), "DoeEtAl2017Art");
+//! assert_eq!(keys.generate(&items[0]), "DoeEtAl2017Artb");
+//! ```
+//!
+//! [Better BibTeX]: https://retorque.re/zotero-better-bibtex/citing/
+
+use std::{collections::HashSet, fmt, str::FromStr};
+
+use citeworks_csl::{dates::Date, names::Name, Item};
+
+/// The default pattern, `[auth:lower][year]`.
+pub const DEFAULT_PATTERN: &str = "[auth:lower][year]";
+
+/// Words skipped when taking words from a title.
+const SKIP_WORDS: &[&str] = &[
+	"a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "of", "on", "or",
+	"the", "to", "with",
+];
+
+/// A parsed key pattern.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct KeyPattern {
+	parts: Vec<Part>,
+	source: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Part {
+	Literal(String),
+	Field(Field, Vec<Filter>),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Field {
+	Auth,
+	Authors,
+	AuthEtAl,
+	Year,
+	ShortYear,
+	TitleWord,
+	ShortTitle,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Filter {
+	Lower,
+	Upper,
+}
+
+impl Default for KeyPattern {
+	fn default() -> Self {
+		DEFAULT_PATTERN.parse().expect("default pattern is valid")
+	}
+}
+
+impl fmt::Display for KeyPattern {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.source)
+	}
+}
+
+impl FromStr for KeyPattern {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = Vec::new();
+		let mut rest = s;
+		while !rest.is_empty() {
+			let (literal, field) = match rest.split_once('[') {
+				Some((literal, field)) => (literal, Some(field)),
+				None => (rest, None),
+			};
+			if literal.contains(']') {
+				return Err(format!("unopened ']' in key pattern {s:?}"));
+			}
+			if !literal.is_empty() {
+				parts.push(Part::Literal(literal.into()));
+			}
+
+			rest = match field {
+				Some(field) => {
+					let (field, after) = field
+						.split_once(']')
+						.ok_or_else(|| format!("unclosed '[' in key pattern {s:?}"))?;
+					parts.push(parse_field(field)?);
+					after
+				}
+				None => "",
+			};
+		}
+
+		if parts.is_empty() {
+			return Err("empty key pattern".into());
+		}
+
+		Ok(Self {
+			parts,
+			source: s.into(),
+		})
+	}
+}
+
+fn parse_field(field: &str) -> Result<Part, String> {
+	let mut names = field.split(':');
+	let name = names.next().unwrap_or_default();
+	let field = match name {
+		"auth" => Field::Auth,
+		"authors" => Field::Authors,
+		"authEtAl" => Field::AuthEtAl,
+		"year" => Field::Year,
+		"shortyear" => Field::ShortYear,
+		"titleword" | "veryshorttitle" => Field::TitleWord,
+		"shorttitle" => Field::ShortTitle,
+		_ => return Err(format!("unknown key pattern field: {name:?}")),
+	};
+	let filters = names
+		.map(|filter| match filter {
+			"lower" => Ok(Filter::Lower),
+			"upper" => Ok(Filter::Upper),
+			_ => Err(format!("unknown key pattern filter: {filter:?}")),
+		})
+		.collect::<Result<_, _>>()?;
+	Ok(Part::Field(field, filters))
+}
+
+/// Generates unique keys for items from a pattern.
+#[derive(Debug, Clone, Default)]
+pub struct KeyGenerator {
+	pattern: KeyPattern,
+	seen: HashSet<String>,
+}
+
+impl KeyGenerator {
+	/// Create a generator with the given pattern.
+	pub fn new(pattern: KeyPattern) -> Self {
+		Self {
+			pattern,
+			seen: HashSet::new(),
+		}
+	}
+
+	/// Mark a key as taken, e.g. because an item already has it.
+	pub fn reserve(&mut self, key: impl Into<String>) {
+		self.seen.insert(key.into());
+	}
+
+	/// Generate a key for an item, which hasn't been generated or reserved
+	/// before.
+	pub fn generate(&mut self, item: &Item) -> String {
+		let mut base = String::new();
+		for part in &self.pattern.parts {
+			match part {
+				Part::Literal(literal) => base.push_str(literal),
+				Part::Field(field, filters) => {
+					let mut value = field_value(*field, item);
+					for filter in filters {
+						value = match filter {
+							Filter::Lower => value.to_lowercase(),
+							Filter::Upper => value.to_uppercase(),
+						};
+					}
+					base.push_str(&value);
+				}
+			}
+		}
+
+		let mut key = base.clone();
+		let mut n = 0;
+		while !self.seen.insert(key.clone()) {
+			n += 1;
+			key = match char::from_u32(u32::from(b'a') + n) {
+				Some(suffix) if suffix <= 'z' => format!("{base}{suffix}"),
+				_ => format!("{base}-{n}"),
+			};
+		}
+		key
+	}
+}
+
+/// Give keys to the items that don't have one (an empty id).
+///
+/// Existing ids are kept and reserved, so generated keys won't clash with them.
+pub fn fill_missing_keys(items: &mut [Item], keys: &mut KeyGenerator) {
+	for item in items.iter().filter(|item| !item.id.is_empty()) {
+		keys.reserve(item.id.clone());
+	}
+	for item in items.iter_mut().filter(|item| item.id.is_empty()) {
+		item.id = keys.generate(item);
+	}
+}
+
+fn field_value(field: Field, item: &Item) -> String {
+	let names: Vec<String> = item
+		.author
+		.iter()
+		.filter_map(family)
+		.filter(|name| !name.is_empty())
+		.collect();
+
+	match field {
+		Field::Auth => names.first().cloned().unwrap_or_else(|| "Anon".into()),
+		Field::Authors => names.concat(),
+		Field::AuthEtAl => match names.as_slice() {
+			[] => "Anon".into(),
+			[one] => one.clone(),
+			[one, two] => format!("{one}{two}"),
+			[first, ..] => format!("{first}EtAl"),
+		},
+		Field::Year => year(item).map(|y| y.to_string()).unwrap_or_default(),
+		Field::ShortYear => year(item)
+			.map(|y| format!("{:02}", y.rem_euclid(100)))
+			.unwrap_or_default(),
+		Field::TitleWord => title_words(item, 1),
+		Field::ShortTitle => title_words(item, 3),
+	}
+}
+
+fn family(name: &Name) -> Option<String> {
+	let name = name.family.as_ref().or(name.literal.as_ref())?;
+	Some(name.chars().filter(|c| c.is_alphanumeric()).collect())
+}
+
+/// The year the item was issued, if it has a parsed date.
+pub(crate) fn year(item: &Item) -> Option<i64> {
+	match item.issued.as_ref()? {
+		Date::Single { date, .. } | Date::Range { start: date, .. } => Some(date.year),
+		Date::Raw { .. } | Date::Edtf { .. } | Date::MetaOnly { .. } => None,
+	}
+}
+
+/// The first `n` significant words of the title, capitalised and joined.
+fn title_words(item: &Item, n: usize) -> String {
+	let title = match &item.title {
+		Some(title) => title.to_string(),
+		None => return String::new(),
+	};
+
+	title
+		.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+		.filter(|word| !SKIP_WORDS.contains(&word.to_lowercase().as_str()))
+		.take(n)
+		.map(|word| {
+			let mut chars = word.chars();
+			chars
+				.next()
+				.map(|first| first.to_uppercase().chain(chars).collect::<String>())
+				.unwrap_or_default()
+		})
+		.collect()
+}
@@ -0,0 +1,163 @@
+//! Export to a LaTeX `thebibliography` environment.
+//!
+//! This is for documents that can't use BibTeX or biblatex: each item becomes
+//! a `\bibitem` with its entry rendered in the simple author-date style of the
+//! [render](crate::render) module, which works with plain `\cite{key}`.
+//!
+//! ```
+//! use citeworks_convert::latex::{items_to_thebibliography, LabelStyle, LatexOptions};
+//!
+//! let items = citeworks_csl::from_str(r#"[{
+//!     "id": "doe2017",
+//!     "type": "book",
+//!     "author": [{"family": "Doe", "given": "Jane"}],
+//!     "title": "A book",
+//!     "issued": {"date-parts": [[2017]]}
+//! }]"#).unwrap();
+//!
+//! let options = LatexOptions {
+//!     labels: LabelStyle::AuthorYear,
+//!     ..Default::default()
+//! };
+//! assert_eq!(
+//!     items_to_thebibliography(&items, &options),
+//!     "\\begin{thebibliography}{Doe 2017}\n\n\\bibitem[Doe 2017]{doe2017}\nDoe, J. (2017). \\emph{A book}.\n\n\\end{thebibliography}\n"
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use citeworks_cff::references::Reference;
+use citeworks_csl::Item;
+
+use crate::{
+	csl::references_to_items_with,
+	keys::KeyGenerator,
+	render::{author_date, bibliography_order, entry, Segment},
+	report::{IgnoreWarnings, WarningSink},
+};
+
+/// How items are labelled in the bibliography and in citations.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum LabelStyle {
+	/// Numbered in the order given, e.g. `[1]`.
+	Numeric,
+
+	/// By author and year, e.g. `[Doe et al. 2017]`, sorted alphabetically.
+	AuthorYear,
+}
+
+/// Options for LaTeX export.
+#[derive(Debug, Clone)]
+pub struct LatexOptions {
+	/// How items are labelled.
+	pub labels: LabelStyle,
+
+	/// Whether to wrap URLs in `\url{}`, which needs the `url` or `hyperref`
+	/// package. Otherwise they're written as escaped text.
+	pub url_command: bool,
+}
+
+impl Default for LatexOptions {
+	fn default() -> Self {
+		Self {
+			labels: LabelStyle::Numeric,
+			url_command: false,
+		}
+	}
+}
+
+/// Export CSL items as a `thebibliography` environment.
+///
+/// Item ids are used as the citation keys. Entries are rendered like
+/// [entry](crate::render::entry), which only shows some variables, so there
+/// is nothing to report about the others.
+pub fn items_to_thebibliography(items: &[Item], options: &LatexOptions) -> String {
+	let mut items: Vec<&Item> = items.iter().collect();
+	let labels: Vec<Option<String>> = match options.labels {
+		LabelStyle::Numeric => vec![None; items.len()],
+		LabelStyle::AuthorYear => {
+			items.sort_by(|a, b| bibliography_order(a, b));
+			items
+				.iter()
+				.map(|item| Some(escape(&author_date(item))))
+				.collect()
+		}
+	};
+
+	let widest = match options.labels {
+		// The argument is only used for its width, so use as many 9s as the
+		// largest number has digits.
+		LabelStyle::Numeric => "9".repeat(items.len().max(1).to_string().len()),
+		LabelStyle::AuthorYear => labels
+			.iter()
+			.flatten()
+			.max_by_key(|label| label.chars().count())
+			.cloned()
+			.unwrap_or_default(),
+	};
+
+	let mut out = String::new();
+	writeln!(out, "\\begin{{thebibliography}}{{{widest}}}\n").expect("writing to a String");
+	for (item, label) in items.into_iter().zip(labels) {
+		out.push_str("\\bibitem");
+		if let Some(label) = label {
+			write!(out, "[{label}]").expect("writing to a String");
+		}
+		writeln!(out, "{{{}}}", item.id).expect("writing to a String");
+		out.push_str(&segments(&entry(item), options));
+		out.push_str("\n\n");
+	}
+	out.push_str("\\end{thebibliography}\n");
+	out
+}
+
+/// Export CFF references as a `thebibliography` environment.
+///
+/// Keys are generated as for [references_to_items](crate::csl::references_to_items).
+pub fn references_to_thebibliography(references: &[Reference], options: &LatexOptions) -> String {
+	references_to_thebibliography_with(references, options, &mut IgnoreWarnings)
+}
+
+/// Export CFF references as a `thebibliography` environment.
+///
+/// Fields which can't be converted to CSL items are reported to the sink, as
+/// for [references_to_items_with].
+pub fn references_to_thebibliography_with(
+	references: &[Reference],
+	options: &LatexOptions,
+	report: &mut dyn WarningSink,
+) -> String {
+	let items = references_to_items_with(references, &mut KeyGenerator::default(), report);
+	items_to_thebibliography(&items, options)
+}
+
+fn segments(segments: &[Segment], options: &LatexOptions) -> String {
+	segments
+		.iter()
+		.map(|segment| match segment {
+			Segment::Text(text) => escape(text),
+			Segment::Emph(text) => format!("\\emph{{{}}}", escape(text)),
+			Segment::Link(url) if options.url_command => format!("\\url{{{url}}}"),
+			Segment::Link(url) => escape(url),
+		})
+		.collect()
+}
+
+/// Escape text for LaTeX.
+pub fn escape(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	for c in s.chars() {
+		match c {
+			'\\' => out.push_str("\\textbackslash{}"),
+			'~' => out.push_str("\\textasciitilde{}"),
+			'^' => out.push_str("\\textasciicircum{}"),
+			'&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+				out.push('\\');
+				out.push(c);
+			}
+			c => out.push(c),
+		}
+	}
+	out
+}
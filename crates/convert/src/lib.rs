@@ -0,0 +1,50 @@
+//! Conversions between citation metadata formats.
+//!
+//! This crate converts [CFF](citeworks_cff) documents and references to
+//! [CSL](citeworks_csl) items and [back](cff), and both to other formats, such
+//! as [schema.org](schemaorg) JSON-LD, [CodeMeta](codemeta), [DataCite](datacite)
+//! and [OpenAIRE](openaire) records, [JATS](jats) reference lists,
+//! [LaTeX](latex) bibliographies, CSV [tables](table), or [Wikidata](wikidata)
+//! statements, and reads [Zotero](zotero) RDF exports. It also [aggregates](aggregate) citations across projects,
+//! generates citation [keys], finds [duplicate DOIs](duplicates), makes README
+//! [badges](badge), draws citation [graphs](graph), strips markup to
+//! [plain text](text), changes the [case] of titles, reads release notes from
+//! [changelogs](changelog), and resolves citations in pandoc
+//! documents with a [filter](pandoc). CFF
+//! documents can also be started from [Cargo](cargo) and [npm] package
+//! metadata, for each package of a [workspace].
+#![warn(clippy::unwrap_used, missing_docs)]
+#![deny(rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+pub mod aggregate;
+pub mod badge;
+pub mod bibtex;
+pub mod cargo;
+pub mod case;
+pub mod cff;
+pub mod changelog;
+pub mod codemeta;
+pub mod csl;
+pub mod datacite;
+pub mod duplicates;
+pub mod graph;
+pub mod jats;
+pub mod keys;
+pub mod latex;
+pub mod npm;
+pub mod openaire;
+pub mod options;
+pub mod pandoc;
+pub mod render;
+pub mod report;
+pub mod ris;
+pub mod schemaorg;
+pub mod stash;
+pub mod table;
+pub mod text;
+pub mod wikidata;
+pub mod workspace;
+pub mod zotero;
+
+mod xml;
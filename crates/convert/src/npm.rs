@@ -0,0 +1,202 @@
+//! Conversion from npm package metadata.
+//!
+//! This reads a `package.json` like [cargo](crate::cargo) reads a `Cargo.toml`:
+//! the name is the title, the description the abstract, the repository the code
+//! repository (or another repository field, see [classify_repository]), and the
+//! homepage the URL. The author and contributors are kept as names (CFF
+//! entities), with their email and website.
+//!
+//! Repositories can be given in npm's shorthand, like `github:owner/repo` or
+//! `owner/repo`, or as git URLs, which are made into web URLs.
+//!
+//! ```
+//! use citeworks_cff::Date;
+//! use citeworks_convert::npm::npm_to_cff;
+//!
+//! let released = Date { year: 2022, month: 8, day: 20 };
+//! let cff = npm_to_cff(r#"{
+//!   "name": "citeworks-wasm",
+//!   "version": "0.1.0",
+//!   "author": "Félix Saparelli <felix@passcod.name> (https://passcod.name)",
+//!   "license": "Apache-2.0",
+//!   "repository": "github:passcod/citeworks"
+//! }"#, released).unwrap();
+//!
+//! assert_eq!(cff.title, "citeworks-wasm");
+//! assert_eq!(cff.version.as_deref(), Some("0.1.0"));
+//! assert_eq!(
+//!     cff.repository_code.unwrap().as_str(),
+//!     "https://github.com/passcod/citeworks"
+//! );
+//! ```
+
+use std::str::FromStr;
+
+use citeworks_cff::{
+	names::{EntityName, Name, NameMeta},
+	repositories::{classify_repository, RepositoryKind},
+	Cff, Date, License,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+struct Package {
+	name: Option<String>,
+	version: Option<String>,
+	description: Option<String>,
+	author: Option<Person>,
+	#[serde(default)]
+	contributors: Vec<Person>,
+	license: Option<Value>,
+	repository: Option<Repository>,
+	homepage: Option<String>,
+	#[serde(default)]
+	keywords: Vec<String>,
+}
+
+/// A person, as `Name <email> (url)` or an object with those fields.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Person {
+	Text(String),
+	Object {
+		name: String,
+		email: Option<String>,
+		url: Option<String>,
+	},
+}
+
+/// A repository, as a URL or shorthand, or an object with a `url`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Repository {
+	Text(String),
+	Object { url: String },
+}
+
+fn package(package_json: &str) -> Result<Package, String> {
+	serde_json::from_str(package_json).map_err(|err| err.to_string())
+}
+
+/// Make a CFF document from a `package.json`, released on the given date.
+///
+/// Fails if the file can't be parsed or the package has no name. The document
+/// may have no authors, which CFF requires, if the package doesn't have any.
+pub fn npm_to_cff(package_json: &str, released: Date) -> Result<Cff, String> {
+	let package = package(package_json)?;
+	let title = package
+		.name
+		.ok_or_else(|| String::from("package.json has no name"))?;
+
+	let mut cff = Cff {
+		title,
+		version: package.version,
+		date_released: Some(released),
+		abstract_text: package.description,
+		authors: package
+			.author
+			.into_iter()
+			.chain(package.contributors)
+			.map(person_name)
+			.collect(),
+		license: package
+			.license
+			.as_ref()
+			.and_then(Value::as_str)
+			.and_then(|license| License::from_str(license).ok()),
+		url: package.homepage.and_then(|url| Url::parse(&url).ok()),
+		keywords: package.keywords,
+		..Default::default()
+	};
+
+	if let Some(url) = package.repository.and_then(repository_url) {
+		let kind = classify_repository(&url).unwrap_or(RepositoryKind::Code);
+		cff.set_repository(kind, url);
+	}
+
+	Ok(cff)
+}
+
+/// Update the version of a CFF document from a `package.json`.
+///
+/// This behaves as [update_cff_from_cargo](crate::cargo::update_cff_from_cargo).
+pub fn update_cff_from_npm(
+	cff: &mut Cff,
+	package_json: &str,
+	released: Date,
+) -> Result<bool, String> {
+	let version = package(package_json)?.version;
+	if version.is_none() || version == cff.version {
+		return Ok(false);
+	}
+
+	cff.version = version;
+	cff.date_released = Some(released);
+	Ok(true)
+}
+
+fn person_name(person: Person) -> Name {
+	let (name, email, url) = match person {
+		Person::Object { name, email, url } => (name, email, url),
+		Person::Text(text) => {
+			let (rest, url) = match text.trim_end().strip_suffix(')') {
+				Some(rest) => match rest.rsplit_once('(') {
+					Some((rest, url)) => (rest.to_string(), Some(url.trim().to_string())),
+					None => (text.clone(), None),
+				},
+				None => (text.clone(), None),
+			};
+			let (name, email) = match rest.split_once('<') {
+				Some((name, email)) => (
+					name.to_string(),
+					Some(email.trim_end().trim_end_matches('>').to_string()),
+				),
+				None => (rest, None),
+			};
+			(name, email, url)
+		}
+	};
+
+	Name::Entity(EntityName {
+		name: Some(name.trim().into()),
+		meta: NameMeta {
+			email,
+			website: url.and_then(|url| Url::parse(&url).ok()),
+			..Default::default()
+		},
+		..Default::default()
+	})
+}
+
+/// The web URL of a repository given as shorthand or a git URL.
+fn repository_url(repository: Repository) -> Option<Url> {
+	let url = match repository {
+		Repository::Text(url) | Repository::Object { url } => url,
+	};
+	let url = url.trim();
+
+	let url = if let Some(path) = url.strip_prefix("github:") {
+		format!("https://github.com/{path}")
+	} else if let Some(path) = url.strip_prefix("gitlab:") {
+		format!("https://gitlab.com/{path}")
+	} else if let Some(path) = url.strip_prefix("bitbucket:") {
+		format!("https://bitbucket.org/{path}")
+	} else if let Some(id) = url.strip_prefix("gist:") {
+		format!("https://gist.github.com/{id}")
+	} else if !url.contains(':') {
+		format!("https://github.com/{url}")
+	} else {
+		let url = url.trim_start_matches("git+");
+		if let Some(rest) = url.strip_prefix("git://") {
+			format!("https://{rest}")
+		} else if let Some(rest) = url.strip_prefix("ssh://git@") {
+			format!("https://{rest}")
+		} else {
+			url.to_string()
+		}
+	};
+
+	Url::parse(url.trim_end_matches(".git")).ok()
+}
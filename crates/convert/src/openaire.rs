@@ -0,0 +1,413 @@
+//! Export to [OpenAIRE Guidelines for Software Repositories][guidelines].
+//!
+//! These guidelines are an application profile of the DataCite metadata
+//! schema, which EU-funded projects must deposit software metadata with. This
+//! produces `oai_datacite` XML, and lists the properties the guidelines want
+//! but which the CFF document doesn't provide.
+//!
+//! ```
+//! use citeworks_convert::openaire::{cff_to_openaire, Obligation, OpenAireOptions};
+//!
+//! let cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! authors:
+//!   - family-names: Druskat
+//!     given-names: Stephan
+//! "#).unwrap();
+//!
+//! let export = cff_to_openaire(&cff, &OpenAireOptions::default());
+//! assert!(export.xml.contains("<title>My Research Software</title>"));
+//! assert!(export
+//!     .missing
+//!     .iter()
+//!     .any(|m| m.property == "Identifier" && m.obligation == Obligation::Mandatory));
+//! ```
+//!
+//! [guidelines]: https://openaire-guidelines-for-software-repository-managers.readthedocs.io
+
+use citeworks_cff::{
+	names::{EntityName, Name, PersonName},
+	Cff,
+};
+
+use crate::{
+	report::{send, unmapped, IgnoreWarnings, RecordReport, WarningSink},
+	xml::XmlWriter,
+};
+
+/// The DataCite kernel namespace used by OpenAIRE.
+pub const NAMESPACE: &str = "http://datacite.org/schema/kernel-4";
+
+/// The DataCite kernel schema location used by OpenAIRE.
+pub const SCHEMA_LOCATION: &str =
+	"http://datacite.org/schema/kernel-4 http://schema.datacite.org/meta/kernel-4.1/metadata.xsd";
+
+/// Top-level CFF keys which are converted, or which are metadata about the file.
+const MAPPED_CFF_KEYS: &[&str] = &[
+	"cff-version",
+	"message",
+	"doi",
+	"url",
+	"repository-code",
+	"authors",
+	"title",
+	"date-released",
+	"keywords",
+	"references",
+	"version",
+	"license",
+	"license-url",
+	"abstract",
+	"funding",
+];
+
+/// COAR access right URI for open access.
+pub const OPEN_ACCESS: &str = "http://purl.org/coar/access_right/c_abf2";
+
+/// Options for OpenAIRE export.
+///
+/// These provide properties that CFF has no equivalent for.
+#[derive(Debug, Clone)]
+pub struct OpenAireOptions {
+	/// The publisher of the software, e.g. the repository it's deposited in.
+	pub publisher: Option<String>,
+
+	/// The access rights, as a COAR access right URI and label.
+	pub access_rights: (String, String),
+}
+
+impl Default for OpenAireOptions {
+	fn default() -> Self {
+		Self {
+			publisher: None,
+			access_rights: (OPEN_ACCESS.into(), "open access".into()),
+		}
+	}
+}
+
+/// How much the OpenAIRE guidelines want a property.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Obligation {
+	/// The property is recommended.
+	Recommended,
+
+	/// The property is mandatory when applicable.
+	MandatoryIfApplicable,
+
+	/// The property is mandatory.
+	Mandatory,
+}
+
+/// A property that's wanted by the guidelines but couldn't be filled in.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Missing {
+	/// The OpenAIRE property name, e.g. `Publisher`.
+	pub property: &'static str,
+
+	/// How much the guidelines want it.
+	pub obligation: Obligation,
+
+	/// What to add to the CFF (or the options) to provide it.
+	pub hint: &'static str,
+}
+
+/// The result of an OpenAIRE export.
+#[derive(Debug, Clone)]
+pub struct OpenAireExport {
+	/// The `oai_datacite` XML record.
+	pub xml: String,
+
+	/// Properties that are missing from the record, most important first.
+	pub missing: Vec<Missing>,
+}
+
+/// Export a CFF document as an OpenAIRE software record.
+pub fn cff_to_openaire(cff: &Cff, options: &OpenAireOptions) -> OpenAireExport {
+	cff_to_openaire_with(cff, options, &mut IgnoreWarnings)
+}
+
+/// Export a CFF document as an OpenAIRE software record.
+///
+/// Fields which can't be converted are reported to the sink, under the title
+/// of the document. What the guidelines want but the document doesn't have is
+/// listed in the export's `missing` properties instead.
+pub fn cff_to_openaire_with(
+	cff: &Cff,
+	options: &OpenAireOptions,
+	report: &mut dyn WarningSink,
+) -> OpenAireExport {
+	let mut record = RecordReport::new(cff.title.clone());
+	unmapped(cff, MAPPED_CFF_KEYS, "no OpenAIRE equivalent", &mut record);
+	if cff.license.is_some() && cff.license_url.is_some() {
+		record.dropped("license-url", "rights taken by license");
+	}
+	for (n, reference) in cff.references.iter().enumerate() {
+		if reference.doi.is_none() && reference.url.is_none() {
+			record.dropped(format!("references[{n}]"), "no doi or url to relate to");
+		}
+	}
+	send(report, record);
+
+	let mut missing = Vec::new();
+	let mut miss = |property, obligation, hint| {
+		missing.push(Missing {
+			property,
+			obligation,
+			hint,
+		})
+	};
+
+	let mut xml = XmlWriter::with_declaration();
+	xml.open(
+		"resource",
+		&[
+			("xmlns", NAMESPACE),
+			("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"),
+			("xsi:schemaLocation", SCHEMA_LOCATION),
+		],
+	);
+
+	match &cff.doi {
+		Some(doi) => xml.leaf("identifier", &[("identifierType", "DOI")], doi),
+		None => match &cff.repository_code.as_ref().or(cff.url.as_ref()) {
+			Some(url) => xml.leaf("identifier", &[("identifierType", "URL")], url.as_str()),
+			None => miss(
+				"Identifier",
+				Obligation::Mandatory,
+				"add a `doi`, or a `repository-code` or `url`",
+			),
+		},
+	}
+
+	xml.open("creators", &[]);
+	for author in &cff.authors {
+		creator(&mut xml, author);
+	}
+	xml.close();
+
+	xml.open("titles", &[]);
+	xml.leaf("title", &[], &cff.title);
+	xml.close();
+
+	match &options.publisher {
+		Some(publisher) => xml.leaf("publisher", &[], publisher),
+		None => miss(
+			"Publisher",
+			Obligation::Mandatory,
+			"set the publisher (e.g. the repository) in the export options",
+		),
+	}
+
+	match cff.date_released {
+		Some(date) => xml.leaf("publicationYear", &[], &date.year.to_string()),
+		None => miss(
+			"PublicationYear",
+			Obligation::Mandatory,
+			"add a `date-released`",
+		),
+	}
+
+	xml.leaf(
+		"resourceType",
+		&[("resourceTypeGeneral", "Software")],
+		"Software",
+	);
+
+	if cff.keywords.is_empty() {
+		miss("Subject", Obligation::Recommended, "add `keywords`");
+	} else {
+		xml.open("subjects", &[]);
+		for keyword in &cff.keywords {
+			xml.leaf("subject", &[], keyword);
+		}
+		xml.close();
+	}
+
+	if let Some(date) = cff.date_released {
+		xml.open("dates", &[]);
+		xml.leaf("date", &[("dateType", "Issued")], &date.to_string());
+		xml.close();
+	}
+
+	let alternates: Vec<_> = [cff.url.as_ref(), cff.repository_code.as_ref()]
+		.into_iter()
+		.flatten()
+		.filter(|_| cff.doi.is_some())
+		.collect();
+	if !alternates.is_empty() {
+		xml.open("alternateIdentifiers", &[]);
+		for url in alternates {
+			xml.leaf(
+				"alternateIdentifier",
+				&[("alternateIdentifierType", "URL")],
+				url.as_str(),
+			);
+		}
+		xml.close();
+	}
+
+	let related: Vec<_> = cff
+		.references
+		.iter()
+		.filter_map(|r| {
+			r.doi
+				.as_deref()
+				.map(|doi| ("DOI", doi))
+				.or_else(|| r.url.as_ref().map(|url| ("URL", url.as_str())))
+		})
+		.collect();
+	if related.is_empty() && !cff.references.is_empty() {
+		miss(
+			"RelatedIdentifier",
+			Obligation::MandatoryIfApplicable,
+			"add a `doi` or `url` to references",
+		);
+	} else if !related.is_empty() {
+		xml.open("relatedIdentifiers", &[]);
+		for (id_type, id) in related {
+			xml.leaf(
+				"relatedIdentifier",
+				&[
+					("relatedIdentifierType", id_type),
+					("relationType", "References"),
+				],
+				id,
+			);
+		}
+		xml.close();
+	}
+
+	match &cff.version {
+		Some(version) => xml.leaf("version", &[], version),
+		None => miss("Version", Obligation::Recommended, "add a `version`"),
+	}
+
+	xml.open("rightsList", &[]);
+	let (access_uri, access_label) = &options.access_rights;
+	xml.leaf("rights", &[("rightsURI", access_uri)], access_label);
+	match &cff.license {
+		Some(license) => {
+			for id in license.members() {
+				// Compound expressions and licenses which aren't SPDX have no URI.
+				if id.contains(' ') || !license.is_spdx() {
+					xml.leaf("rights", &[], &id);
+				} else {
+					xml.leaf(
+						"rights",
+						&[("rightsURI", &format!("https://spdx.org/licenses/{id}"))],
+						&id,
+					);
+				}
+			}
+		}
+		None => match &cff.license_url {
+			Some(url) => xml.leaf("rights", &[("rightsURI", url.as_str())], url.as_str()),
+			None => miss(
+				"Rights",
+				Obligation::Mandatory,
+				"add a `license` or `license-url`",
+			),
+		},
+	}
+	xml.close();
+
+	match &cff.abstract_text {
+		Some(text) => {
+			xml.open("descriptions", &[]);
+			xml.leaf("description", &[("descriptionType", "Abstract")], text);
+			xml.close();
+		}
+		None => miss(
+			"Description",
+			Obligation::MandatoryIfApplicable,
+			"add an `abstract`",
+		),
+	}
+
+	if !cff.funding.is_empty() {
+		xml.open("fundingReferences", &[]);
+		for funding in &cff.funding {
+			xml.open("fundingReference", &[]);
+			xml.leaf(
+				"funderName",
+				&[],
+				funding.funder.name.as_deref().unwrap_or(":unav"),
+			);
+			xml.leaf_opt(
+				"funderIdentifier",
+				&[("funderIdentifierType", "ROR")],
+				funding.funder.meta.ror.as_ref().map(|u| u.as_str()),
+			);
+			xml.leaf_opt("awardNumber", &[], funding.award_number.as_deref());
+			xml.leaf_opt("awardTitle", &[], funding.award_title.as_deref());
+			xml.close();
+		}
+		xml.close();
+	}
+
+	xml.close();
+
+	missing.sort_by_key(|m| std::cmp::Reverse(m.obligation));
+	OpenAireExport {
+		xml: xml.finish(),
+		missing,
+	}
+}
+
+fn creator(xml: &mut XmlWriter, name: &Name) {
+	xml.open("creator", &[]);
+	match name {
+		Name::Person(person) => person_creator(xml, person),
+		Name::Entity(entity) => entity_creator(xml, entity),
+		Name::Anonymous => xml.leaf("creatorName", &[], ":unav"),
+	}
+	xml.close();
+}
+
+fn person_creator(xml: &mut XmlWriter, person: &PersonName) {
+	let family = match (&person.name_particle, &person.family_names) {
+		(Some(particle), Some(family)) => Some(format!("{particle} {family}")),
+		(None, family) => family.clone(),
+		(Some(particle), None) => Some(particle.clone()),
+	};
+	let full = match (&family, &person.given_names) {
+		(Some(family), Some(given)) => format!("{family}, {given}"),
+		(Some(name), None) | (None, Some(name)) => name.clone(),
+		(None, None) => ":unav".into(),
+	};
+
+	xml.leaf("creatorName", &[("nameType", "Personal")], &full);
+	xml.leaf_opt("givenName", &[], person.given_names.as_deref());
+	xml.leaf_opt("familyName", &[], family.as_deref());
+	if let Some(orcid) = &person.meta.orcid {
+		xml.leaf(
+			"nameIdentifier",
+			&[
+				("nameIdentifierScheme", "ORCID"),
+				("schemeURI", "https://orcid.org"),
+			],
+			orcid.path().trim_matches('/'),
+		);
+	}
+	xml.leaf_opt("affiliation", &[], person.affiliation.as_deref());
+}
+
+fn entity_creator(xml: &mut XmlWriter, entity: &EntityName) {
+	xml.leaf(
+		"creatorName",
+		&[("nameType", "Organizational")],
+		entity.name.as_deref().unwrap_or(":unav"),
+	);
+	if let Some(ror) = &entity.meta.ror {
+		xml.leaf(
+			"nameIdentifier",
+			&[
+				("nameIdentifierScheme", "ROR"),
+				("schemeURI", "https://ror.org"),
+			],
+			ror.as_str(),
+		);
+	}
+}
@@ -0,0 +1,172 @@
+//! Options for conversions between CSL and CFF.
+//!
+//! The defaults do what's safest for arbitrary input: keep values as they are
+//! unless they can't be converted. [Preset]s adjust that for where the data
+//! comes from, and individual options can be changed after.
+//!
+//! ```
+//! use citeworks_convert::options::{BadUrls, ConvertOptions, Preset};
+//!
+//! let mut options = ConvertOptions::preset(Preset::Zotero);
+//! assert!(options.normalise_dois);
+//!
+//! options.bad_urls = BadUrls::Keep;
+//! ```
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use citeworks_cff::references::{PublicationStatus, RefType};
+use citeworks_csl::{dates::Hemisphere, items::ItemType};
+
+/// Options for converting CSL items to CFF references and back.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ConvertOptions {
+	/// CFF types to use for CSL types, instead of the usual mapping.
+	///
+	/// Only used when converting CSL items to CFF references.
+	pub type_mappings: HashMap<ItemType, RefType>,
+
+	/// Characters separating keywords in CSL `category` and `keyword` values.
+	///
+	/// Keywords are trimmed, and empty ones are skipped. If this is empty, each
+	/// value is kept as a single keyword.
+	pub keyword_separators: String,
+
+	/// Publication status for CSL `status` values that aren't recognised.
+	///
+	/// If this is `None`, unrecognised statuses are dropped.
+	pub status_fallback: Option<PublicationStatus>,
+
+	/// What to do with URLs that can't be parsed.
+	pub bad_urls: BadUrls,
+
+	/// Remove `https://doi.org/` and `doi:` prefixes from DOIs.
+	///
+	/// Only used when converting CSL items to CFF references, as CFF wants
+	/// bare DOIs. See [bare_doi](crate::csl::bare_doi).
+	pub normalise_dois: bool,
+
+	/// Make the CFF `preferred-citation` the primary CSL item.
+	///
+	/// See [cff_to_items_with](crate::csl::cff_to_items_with).
+	pub preferred_citation: bool,
+
+	/// Hemisphere of seasons in CSL dates.
+	///
+	/// Only used when converting CSL items to CFF references, where a date
+	/// with a year and a season gets the season's first month.
+	pub hemisphere: Hemisphere,
+}
+
+impl Default for ConvertOptions {
+	fn default() -> Self {
+		Self {
+			type_mappings: HashMap::new(),
+			keyword_separators: ";,".into(),
+			status_fallback: None,
+			bad_urls: BadUrls::Drop,
+			normalise_dois: false,
+			preferred_citation: false,
+			hemisphere: Hemisphere::Northern,
+		}
+	}
+}
+
+impl ConvertOptions {
+	/// The options for a preset.
+	pub fn preset(preset: Preset) -> Self {
+		match preset {
+			Preset::Zotero => Self {
+				type_mappings: HashMap::from([(ItemType::Manuscript, RefType::Unpublished)]),
+				bad_urls: BadUrls::Repair,
+				normalise_dois: true,
+				..Default::default()
+			},
+			Preset::Crossref => Self {
+				bad_urls: BadUrls::Keep,
+				normalise_dois: true,
+				..Default::default()
+			},
+			Preset::StrictSpec => Self {
+				keyword_separators: String::new(),
+				bad_urls: BadUrls::Strict,
+				..Default::default()
+			},
+		}
+	}
+}
+
+/// Presets of options for common sources of data.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Preset {
+	/// For Zotero exports.
+	///
+	/// Zotero uses `manuscript` for unpublished works, which becomes CFF's
+	/// `unpublished`. URLs are repaired, as they're often typed by hand, and
+	/// DOIs are normalised.
+	Zotero,
+
+	/// For Crossref metadata.
+	///
+	/// DOIs are normalised, and URLs that can't be parsed are kept as
+	/// identifiers rather than dropped.
+	Crossref,
+
+	/// Only what the specifications say, failing on URLs that can't be parsed.
+	///
+	/// Keywords aren't split, and DOIs are kept as they are.
+	StrictSpec,
+}
+
+impl FromStr for Preset {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"zotero" => Ok(Self::Zotero),
+			"crossref" => Ok(Self::Crossref),
+			"strict-spec" => Ok(Self::StrictSpec),
+			_ => Err(format!("unknown preset: {s}")),
+		}
+	}
+}
+
+impl fmt::Display for Preset {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Zotero => "zotero",
+			Self::Crossref => "crossref",
+			Self::StrictSpec => "strict-spec",
+		})
+	}
+}
+
+/// How to handle URLs that can't be parsed.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum BadUrls {
+	/// Drop them.
+	Drop,
+
+	/// Keep them as `other` identifiers.
+	Keep,
+
+	/// Add a missing scheme and encode spaces, and drop them if that fails.
+	Repair,
+
+	/// Fail the conversion.
+	Strict,
+}
+
+impl FromStr for BadUrls {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"drop" => Ok(Self::Drop),
+			"keep" => Ok(Self::Keep),
+			"repair" => Ok(Self::Repair),
+			"strict" => Ok(Self::Strict),
+			_ => Err(format!("unknown URL handling: {s}")),
+		}
+	}
+}
@@ -0,0 +1,352 @@
+//! A [pandoc filter] resolving citations against a library of CSL items.
+//!
+//! Pandoc parses `[@key]` citations into `Cite` elements, which normally get
+//! rendered by citeproc. This fills them in instead, using the simple
+//! author-date style of the [render](crate::render) module, and adds a
+//! bibliography of the cited works at the end of the document (or in a `Div`
+//! with the id `refs`, if there is one), as citeproc does.
+//!
+//! It works on pandoc's JSON AST directly, so it's not tied to a particular
+//! pandoc version.
+//!
+//! ```
+//! use citeworks_convert::pandoc::filter;
+//! use serde_json::json;
+//!
+//! let library = citeworks_csl::from_str(r#"[{
+//!     "id": "doe2017",
+//!     "type": "book",
+//!     "author": [{"family": "Doe", "given": "Jane"}],
+//!     "title": "A book",
+//!     "issued": {"date-parts": [[2017]]}
+//! }]"#).unwrap();
+//!
+//! let mut doc = json!({
+//!     "pandoc-api-version": [1, 23],
+//!     "meta": {},
+//!     "blocks": [{"t": "Para", "c": [{"t": "Cite", "c": [
+//!         [{
+//!             "citationId": "doe2017",
+//!             "citationPrefix": [],
+//!             "citationSuffix": [],
+//!             "citationMode": {"t": "NormalCitation"},
+//!             "citationNoteNum": 1,
+//!             "citationHash": 0
+//!         }],
+//!         [{"t": "Str", "c": "[@doe2017]"}]
+//!     ]}]}]
+//! });
+//!
+//! let report = filter(&mut doc, &library);
+//! assert_eq!(report.cited, vec!["doe2017"]);
+//! assert_eq!(
+//!     doc["blocks"][0]["c"][0]["c"][1],
+//!     json!([{"t": "Str", "c": "(Doe"}, {"t": "Space"}, {"t": "Str", "c": "2017)"}])
+//! );
+//! ```
+//!
+//! [pandoc filter]: https://pandoc.org/filters.html
+
+use std::collections::HashMap;
+
+use citeworks_csl::Item;
+use serde_json::{json, Value};
+
+use crate::render::{bibliography_order, entry, short_authors, year, Segment};
+
+/// What happened while filtering.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct FilterReport {
+	/// Keys of the cited items, in order of first citation.
+	pub cited: Vec<String>,
+
+	/// Keys that weren't found in the library, in order of first citation.
+	///
+	/// Citations with unresolved keys are left as they were.
+	pub unresolved: Vec<String>,
+}
+
+/// Resolve the citations of a pandoc JSON document and add a bibliography.
+pub fn filter(doc: &mut Value, library: &[Item]) -> FilterReport {
+	let library: HashMap<&str, &Item> = library
+		.iter()
+		.map(|item| (item.id.as_str(), item))
+		.collect();
+
+	let mut report = FilterReport::default();
+	if let Some(blocks) = doc.get_mut("blocks") {
+		walk(blocks, &library, &mut report);
+	}
+
+	let mut cited: Vec<&Item> = report
+		.cited
+		.iter()
+		.map(|key| library[key.as_str()])
+		.collect();
+	cited.sort_by(|a, b| bibliography_order(a, b));
+	let refs = bibliography(&cited);
+
+	let title = doc
+		.pointer("/meta/reference-section-title")
+		.and_then(meta_inlines);
+	if let Some(blocks) = doc.get_mut("blocks").and_then(Value::as_array_mut) {
+		if !fill_refs_div(blocks, &refs) && !cited.is_empty() {
+			if let Some(title) = title {
+				blocks.push(json!({
+					"t": "Header",
+					"c": [1, ["bibliography", ["unnumbered"], []], title]
+				}));
+			}
+			blocks.push(json!({
+				"t": "Div",
+				"c": [["refs", ["references", "csl-bib-body"], []], refs]
+			}));
+		}
+	}
+
+	report
+}
+
+fn walk(value: &mut Value, library: &HashMap<&str, &Item>, report: &mut FilterReport) {
+	match value {
+		Value::Array(values) => {
+			for value in values {
+				walk(value, library, report);
+			}
+		}
+		Value::Object(object) => {
+			if object.get("t").and_then(Value::as_str) == Some("Cite") {
+				if let Some(content) = object.get_mut("c") {
+					cite(content, library, report);
+				}
+			} else if let Some(content) = object.get_mut("c") {
+				walk(content, library, report);
+			}
+		}
+		_ => {}
+	}
+}
+
+/// Render a `Cite` element's content: `[citations, inlines]`.
+fn cite(content: &mut Value, library: &HashMap<&str, &Item>, report: &mut FilterReport) {
+	let citations = match content.get(0).and_then(Value::as_array) {
+		Some(citations) => citations.clone(),
+		None => return,
+	};
+
+	let mut resolved = Vec::with_capacity(citations.len());
+	for citation in &citations {
+		let key = citation
+			.get("citationId")
+			.and_then(Value::as_str)
+			.unwrap_or_default();
+		match library.get(key) {
+			Some(item) => {
+				if !report.cited.iter().any(|k| k == key) {
+					report.cited.push(key.into());
+				}
+				resolved.push((citation, *item));
+			}
+			None => {
+				if !report.unresolved.iter().any(|k| k == key) {
+					report.unresolved.push(key.into());
+				}
+			}
+		}
+	}
+	if resolved.len() != citations.len() {
+		return;
+	}
+
+	let mut inlines = Vec::new();
+	let in_text = resolved.iter().all(|(citation, _)| {
+		citation.pointer("/citationMode/t").and_then(Value::as_str) == Some("AuthorInText")
+	});
+	if !in_text {
+		push_text(&mut inlines, "(");
+	}
+
+	for (n, (citation, item)) in resolved.into_iter().enumerate() {
+		if n > 0 {
+			push_text(&mut inlines, "; ");
+		}
+
+		if let Some(prefix) = citation.get("citationPrefix").and_then(Value::as_array) {
+			if !prefix.is_empty() {
+				inlines.extend(prefix.iter().cloned());
+				inlines.push(json!({"t": "Space"}));
+			}
+		}
+
+		match citation.pointer("/citationMode/t").and_then(Value::as_str) {
+			Some("AuthorInText") => push_text(
+				&mut inlines,
+				&format!("{} ({}", short_authors(item), year(item)),
+			),
+			Some("SuppressAuthor") => push_text(&mut inlines, &year(item)),
+			_ => push_text(
+				&mut inlines,
+				&format!("{} {}", short_authors(item), year(item)),
+			),
+		}
+
+		if let Some(suffix) = citation.get("citationSuffix").and_then(Value::as_array) {
+			inlines.extend(suffix.iter().cloned());
+		}
+
+		if in_text {
+			push_text(&mut inlines, ")");
+		}
+	}
+
+	if !in_text {
+		push_text(&mut inlines, ")");
+	}
+
+	content[1] = Value::Array(merge_strs(inlines));
+}
+
+/// Push text as pandoc `Str` and `Space` inlines.
+fn push_text(inlines: &mut Vec<Value>, text: &str) {
+	for (n, word) in text.split(' ').enumerate() {
+		if n > 0 {
+			inlines.push(json!({"t": "Space"}));
+		}
+		if !word.is_empty() {
+			inlines.push(json!({"t": "Str", "c": word}));
+		}
+	}
+}
+
+/// Merge adjacent `Str` inlines, as pandoc would have parsed them.
+fn merge_strs(inlines: Vec<Value>) -> Vec<Value> {
+	let mut merged: Vec<Value> = Vec::with_capacity(inlines.len());
+	for inline in inlines {
+		let is_str = inline.get("t").and_then(Value::as_str) == Some("Str");
+		if let (true, Some(last)) = (is_str, merged.last_mut()) {
+			if last.get("t").and_then(Value::as_str) == Some("Str") {
+				let joined = format!(
+					"{}{}",
+					last["c"].as_str().unwrap_or_default(),
+					inline["c"].as_str().unwrap_or_default()
+				);
+				last["c"] = Value::String(joined);
+				continue;
+			}
+		}
+		merged.push(inline);
+	}
+	merged
+}
+
+fn segment_inlines(segments: &[Segment]) -> Vec<Value> {
+	let mut inlines = Vec::new();
+	for segment in segments {
+		match segment {
+			Segment::Text(text) => push_text(&mut inlines, text),
+			Segment::Emph(text) => {
+				let mut inner = Vec::new();
+				push_text(&mut inner, text);
+				inlines.push(json!({"t": "Emph", "c": inner}));
+			}
+			Segment::Link(url) => inlines.push(json!({
+				"t": "Link",
+				"c": [["", [], []], [{"t": "Str", "c": url}], [url, ""]]
+			})),
+		}
+	}
+	merge_strs(inlines)
+}
+
+fn bibliography(items: &[&Item]) -> Vec<Value> {
+	items
+		.iter()
+		.map(|item| {
+			json!({
+				"t": "Div",
+				"c": [
+					[format!("ref-{}", item.id), ["csl-entry"], []],
+					[{"t": "Para", "c": segment_inlines(&entry(item))}]
+				]
+			})
+		})
+		.collect()
+}
+
+/// If there's a `Div` with id `refs`, put the bibliography in it.
+fn fill_refs_div(blocks: &mut [Value], refs: &[Value]) -> bool {
+	for block in blocks {
+		if block.get("t").and_then(Value::as_str) != Some("Div") {
+			continue;
+		}
+
+		if block.pointer("/c/0/0").and_then(Value::as_str) == Some("refs") {
+			block["c"][1] = Value::Array(refs.to_vec());
+			return true;
+		}
+
+		if let Some(inner) = block.pointer_mut("/c/1").and_then(Value::as_array_mut) {
+			if fill_refs_div(inner, refs) {
+				return true;
+			}
+		}
+	}
+	false
+}
+
+/// Get inlines from a metadata value.
+fn meta_inlines(meta: &Value) -> Option<Value> {
+	match meta.get("t").and_then(Value::as_str)? {
+		"MetaInlines" => meta.get("c").cloned(),
+		"MetaString" => {
+			let mut inlines = Vec::new();
+			push_text(&mut inlines, meta.get("c")?.as_str()?);
+			Some(Value::Array(inlines))
+		}
+		_ => None,
+	}
+}
+
+/// Get the paths listed in the `bibliography` metadata field, if any.
+pub fn bibliography_paths(doc: &Value) -> Vec<String> {
+	fn paths(meta: &Value, out: &mut Vec<String>) {
+		match meta.get("t").and_then(Value::as_str) {
+			Some("MetaList") => {
+				for item in meta
+					.get("c")
+					.and_then(Value::as_array)
+					.into_iter()
+					.flatten()
+				{
+					paths(item, out);
+				}
+			}
+			Some("MetaString") => {
+				if let Some(path) = meta.get("c").and_then(Value::as_str) {
+					out.push(path.into());
+				}
+			}
+			Some("MetaInlines") => {
+				let path: String = meta
+					.get("c")
+					.and_then(Value::as_array)
+					.into_iter()
+					.flatten()
+					.map(|inline| match inline.get("t").and_then(Value::as_str) {
+						Some("Str") => inline["c"].as_str().unwrap_or_default(),
+						Some("Space") => " ",
+						_ => "",
+					})
+					.collect();
+				out.push(path);
+			}
+			_ => {}
+		}
+	}
+
+	let mut out = Vec::new();
+	if let Some(meta) = doc.pointer("/meta/bibliography") {
+		paths(meta, &mut out);
+	}
+	out
+}
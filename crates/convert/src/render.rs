@@ -0,0 +1,450 @@
+//! Simple author-date rendering of CSL items.
+//!
+//! This is not a CSL processor: it produces a single, fixed, APA-like style,
+//! which is enough for exports that need human-readable citations and
+//! bibliography entries, like the [pandoc filter](crate::pandoc). Entries are
+//! rendered to [Segment]s so each output format can apply its own markup.
+//!
+//! ```
+//! use citeworks_convert::render::{author_date, entry, to_plain};
+//!
+//! let items = citeworks_csl::from_str(r#"[{
+//!     "id": "doe2017",
+//!     "type": "article-journal",
+//!     "author": [{"family": "Doe", "given": "Jane"}, {"family": "Roe", "given": "Richard"}],
+//!     "title": "A paper",
+//!     "container-title": "Journal of Things",
+//!     "volume": 12,
+//!     "issue": 3,
+//!     "page": "42-45",
+//!     "issued": {"date-parts": [[2017]]}
+//! }]"#).unwrap();
+//!
+//! assert_eq!(author_date(&items[0]), "Doe and Roe 2017");
+//! assert_eq!(
+//!     to_plain(&entry(&items[0])),
+//!     "Doe, J., & Roe, R. (2017). A paper. Journal of Things, 12(3), 42–45."
+//! );
+//! ```
+//!
+//! How lists of authors are shortened with "et al." and joined can be changed
+//! with [RenderOptions], like the et-al options of CSL styles, and the terms
+//! used come from a [Terms] locale:
+//!
+//! ```
+//! use citeworks_convert::render::{author_date_with, And, RenderOptions, Terms};
+//!
+//! let items = citeworks_csl::from_str(r#"[{
+//!     "id": "doe2017",
+//!     "type": "book",
+//!     "author": [{"family": "Doe"}, {"family": "Roe"}, {"family": "Poe"}],
+//!     "issued": {"date-parts": [[2017]]}
+//! }]"#).unwrap();
+//!
+//! let mut options = RenderOptions::default();
+//! assert_eq!(author_date_with(&items[0], &options), "Doe et al. 2017");
+//!
+//! options.citation.et_al_min = Some(4);
+//! options.citation.and = And::Symbol;
+//! assert_eq!(author_date_with(&items[0], &options), "Doe, Roe, & Poe 2017");
+//!
+//! options.citation.et_al_min = Some(3);
+//! options.citation.et_al_use_first = 2;
+//! options.terms = Terms::locale("de").unwrap();
+//! assert_eq!(author_date_with(&items[0], &options), "Doe, Roe u. a. 2017");
+//! ```
+
+use std::{cmp::Ordering, str::FromStr};
+
+use citeworks_csl::{dates::Date, items::ItemValue, names::Name, Item};
+
+/// A piece of rendered text.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Segment {
+	/// Plain text.
+	Text(String),
+
+	/// Emphasised (italic) text, e.g. a journal name.
+	Emph(String),
+
+	/// A URL, to be made clickable where possible.
+	Link(String),
+}
+
+impl Segment {
+	/// The text of the segment, without markup.
+	pub fn text(&self) -> &str {
+		match self {
+			Self::Text(s) | Self::Emph(s) | Self::Link(s) => s,
+		}
+	}
+}
+
+/// How author lists are rendered in citations and bibliography entries.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RenderOptions {
+	/// For author lists of citations, e.g. "Doe et al. 2017".
+	///
+	/// Defaults to "et al." from three authors, after the first, and "and"
+	/// between two.
+	pub citation: NameOptions,
+
+	/// For author lists of bibliography entries.
+	///
+	/// Defaults to all authors, with "&" before the last.
+	pub bibliography: NameOptions,
+
+	/// The words used, e.g. for "and" and "et al.".
+	pub terms: Terms,
+}
+
+impl Default for RenderOptions {
+	fn default() -> Self {
+		Self {
+			citation: NameOptions {
+				et_al_min: Some(3),
+				et_al_use_first: 1,
+				and: And::Text,
+			},
+			bibliography: NameOptions {
+				et_al_min: None,
+				et_al_use_first: 1,
+				and: And::Symbol,
+			},
+			terms: Terms::default(),
+		}
+	}
+}
+
+/// How a list of authors is shortened and joined, like CSL's `et-al-min`,
+/// `et-al-use-first`, and `and` options.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct NameOptions {
+	/// Shorten lists of at least this many authors, or never if `None`.
+	pub et_al_min: Option<usize>,
+
+	/// How many authors shortened lists keep before "et al.", at least one.
+	pub et_al_use_first: usize,
+
+	/// What goes before the last of a list of authors.
+	pub and: And,
+}
+
+/// What goes before the last of a list of authors.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum And {
+	/// The "and" term of the locale, e.g. "Doe and Roe".
+	Text,
+
+	/// An ampersand, e.g. "Doe & Roe".
+	Symbol,
+
+	/// Nothing but the delimiter, e.g. "Doe, Roe".
+	None,
+}
+
+impl FromStr for And {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"text" => Ok(Self::Text),
+			"symbol" => Ok(Self::Symbol),
+			"none" => Ok(Self::None),
+			_ => Err(format!("expected text, symbol, or none, got {s:?}")),
+		}
+	}
+}
+
+/// The words used when rendering, as in CSL locale files.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Terms {
+	/// The "and" term, e.g. "and", "und", "et".
+	pub and: String,
+
+	/// The "et-al" term, e.g. "et al.", "u. a.".
+	pub et_al: String,
+}
+
+impl Default for Terms {
+	fn default() -> Self {
+		Self {
+			and: "and".into(),
+			et_al: "et al.".into(),
+		}
+	}
+}
+
+impl Terms {
+	/// The terms of a locale, by language tag like `de` or `fr-CA`, for the
+	/// few languages known here.
+	pub fn locale(tag: &str) -> Option<Self> {
+		let language = tag.split(['-', '_']).next().unwrap_or_default();
+		let (and, et_al) = match language.to_ascii_lowercase().as_str() {
+			"en" => ("and", "et al."),
+			"de" => ("und", "u. a."),
+			"es" => ("y", "et al."),
+			"fr" => ("et", "et al."),
+			"it" => ("e", "et al."),
+			"nl" => ("en", "e.a."),
+			"pt" => ("e", "et al."),
+			_ => return None,
+		};
+		Some(Self {
+			and: and.into(),
+			et_al: et_al.into(),
+		})
+	}
+}
+
+/// Join a list of names as the options say.
+///
+/// Names with commas in them, like "Doe, J.", are always delimited from the
+/// last name and "et al." too, or the list would be ambiguous.
+fn join_names(names: &[String], options: &NameOptions, terms: &Terms, commas: bool) -> String {
+	let delimiter = if commas { ", " } else { " " };
+	let shorten = options
+		.et_al_min
+		.map_or(false, |min| names.len() >= min.max(2));
+	let use_first = options.et_al_use_first.max(1);
+	if shorten && use_first < names.len() {
+		return format!(
+			"{}{delimiter}{}",
+			names[..use_first].join(", "),
+			terms.et_al
+		);
+	}
+
+	let and = match options.and {
+		And::Text => Some(terms.and.as_str()),
+		And::Symbol => Some("&"),
+		And::None => None,
+	};
+	match (names, and) {
+		([], _) => String::new(),
+		([one], _) => one.clone(),
+		([one, two], Some(and)) => format!("{one}{delimiter}{and} {two}"),
+		([init @ .., last], Some(and)) => format!("{}, {and} {last}", init.join(", ")),
+		(names, None) => names.join(", "),
+	}
+}
+
+/// Render segments to plain text, without markup.
+pub fn to_plain(segments: &[Segment]) -> String {
+	segments.iter().map(Segment::text).collect()
+}
+
+/// The short form of the authors of an item, as used in citations.
+///
+/// This is the family name (or literal name) of one author, both of two
+/// authors joined by "and", or the first author followed by "et al." for more.
+/// If the item has no authors, its title is used instead.
+pub fn short_authors(item: &Item) -> String {
+	short_authors_with(item, &RenderOptions::default())
+}
+
+/// The short form of the authors of an item, as the citation options say.
+///
+/// See [short_authors].
+pub fn short_authors_with(item: &Item, options: &RenderOptions) -> String {
+	let names: Vec<_> = item.author.iter().filter_map(short_name).collect();
+	if names.is_empty() {
+		return item
+			.title_short
+			.as_ref()
+			.or(item.title.as_ref())
+			.map(|title| title.to_string())
+			.unwrap_or_else(|| "Anonymous".into());
+	}
+	join_names(&names, &options.citation, &options.terms, false)
+}
+
+/// The year an item was issued, or "n.d." (no date).
+pub fn year(item: &Item) -> String {
+	match &item.issued {
+		Some(Date::Single { date, .. }) => date.year.to_string(),
+		Some(Date::Range { start, end, .. }) if start.year != end.year => {
+			format!("{}–{}", start.year, end.year)
+		}
+		Some(Date::Range { start, .. }) => start.year.to_string(),
+		Some(Date::Raw { meta, .. } | Date::Edtf { meta, .. } | Date::MetaOnly { meta }) => {
+			meta.literal.clone().unwrap_or_else(|| "n.d.".into())
+		}
+		None => "n.d.".into(),
+	}
+}
+
+/// An author-date citation label, e.g. "Doe et al. 2017".
+pub fn author_date(item: &Item) -> String {
+	author_date_with(item, &RenderOptions::default())
+}
+
+/// An author-date citation label, as the citation options say.
+pub fn author_date_with(item: &Item, options: &RenderOptions) -> String {
+	format!("{} {}", short_authors_with(item, options), year(item))
+}
+
+/// Compare items in bibliography order: by authors, then year, then title.
+pub fn bibliography_order(a: &Item, b: &Item) -> Ordering {
+	let key = |item: &Item| {
+		(
+			item.author
+				.iter()
+				.map(long_name)
+				.collect::<Vec<_>>()
+				.join(" ")
+				.to_lowercase(),
+			year(item),
+			item.title
+				.as_ref()
+				.map(|t| t.to_string().to_lowercase())
+				.unwrap_or_default(),
+		)
+	};
+	key(a).cmp(&key(b))
+}
+
+/// A full bibliography entry for an item.
+pub fn entry(item: &Item) -> Vec<Segment> {
+	entry_with(item, &RenderOptions::default())
+}
+
+/// A full bibliography entry for an item, as the bibliography options say.
+pub fn entry_with(item: &Item, options: &RenderOptions) -> Vec<Segment> {
+	let mut out = Vec::new();
+	let title = item.title.as_ref().map(|t| t.to_string());
+	let container = item.container_title.as_ref().map(|t| t.to_string());
+
+	let authors: Vec<_> = item.author.iter().map(long_name).collect();
+	let lead = if authors.is_empty() {
+		None
+	} else {
+		Some(join_names(
+			&authors,
+			&options.bibliography,
+			&options.terms,
+			true,
+		))
+	};
+
+	match (lead, &title) {
+		(Some(authors), _) => {
+			text(
+				&mut out,
+				format!("{} ({}). ", end_with_period(&authors), year(item)),
+			);
+			if let Some(title) = &title {
+				title_segment(&mut out, title, container.is_none());
+			}
+		}
+		(None, Some(title)) => {
+			title_segment(&mut out, title, container.is_none());
+			text(&mut out, format!("({}). ", year(item)));
+		}
+		(None, None) => text(&mut out, format!("({}). ", year(item))),
+	}
+
+	if let Some(container) = container {
+		out.push(Segment::Emph(container));
+		if let Some(volume) = &item.volume {
+			text(&mut out, ", ");
+			out.push(Segment::Emph(volume.to_string()));
+		}
+		if let Some(issue) = &item.issue {
+			text(&mut out, format!("({issue})"));
+		}
+		if let Some(page) = &item.page {
+			text(
+				&mut out,
+				format!(", {}", page.to_string().replace('-', "–")),
+			);
+		}
+		text(&mut out, ". ");
+	} else if let Some(ItemValue::Ordinary(publisher)) = item.fields.get("publisher") {
+		text(
+			&mut out,
+			format!("{}. ", end_without_period(&publisher.to_string())),
+		);
+	}
+
+	if let Some(doi) = &item.doi {
+		out.push(Segment::Link(format!("https://doi.org/{doi}")));
+	} else if let Some(url) = &item.url {
+		out.push(Segment::Link(url.to_string()));
+	}
+
+	// Trim the trailing space, merging adjacent text while we're at it.
+	let mut merged: Vec<Segment> = Vec::with_capacity(out.len());
+	for segment in out {
+		match (merged.last_mut(), segment) {
+			(Some(Segment::Text(prev)), Segment::Text(next)) => prev.push_str(&next),
+			(_, segment) => merged.push(segment),
+		}
+	}
+	if let Some(Segment::Text(last)) = merged.last_mut() {
+		let trimmed = last.trim_end().len();
+		last.truncate(trimmed);
+	}
+	merged
+}
+
+fn text(out: &mut Vec<Segment>, s: impl Into<String>) {
+	out.push(Segment::Text(s.into()));
+}
+
+/// Standalone works (books, software…) have italic titles; parts of a
+/// container (articles, chapters…) don't.
+fn title_segment(out: &mut Vec<Segment>, title: &str, standalone: bool) {
+	if standalone {
+		out.push(Segment::Emph(end_without_period(title).into()));
+		text(out, ". ");
+	} else {
+		text(out, format!("{}. ", end_without_period(title)));
+	}
+}
+
+fn end_without_period(s: &str) -> &str {
+	s.trim_end_matches('.')
+}
+
+fn end_with_period(s: &str) -> String {
+	format!("{}.", end_without_period(s))
+}
+
+fn short_name(name: &Name) -> Option<String> {
+	match (&name.non_dropping_particle, &name.family, &name.literal) {
+		(Some(particle), Some(family), _) => Some(format!("{particle} {family}")),
+		(None, Some(family), _) => Some(family.clone()),
+		(_, None, Some(literal)) => Some(literal.clone()),
+		(_, None, None) => name.given.clone(),
+	}
+}
+
+/// "Family, G. G., Suffix" or the literal name.
+fn long_name(name: &Name) -> String {
+	let family = match short_name(name) {
+		Some(family) => family,
+		None => return String::new(),
+	};
+	if name.family.is_none() {
+		return family;
+	}
+
+	let mut out = family;
+	if let Some(given) = &name.given {
+		let initials: Vec<_> = given
+			.split_whitespace()
+			.filter_map(|part| part.chars().next())
+			.map(|c| format!("{c}."))
+			.collect();
+		if !initials.is_empty() {
+			out.push_str(", ");
+			out.push_str(&initials.join(" "));
+		}
+	}
+	if let Some(suffix) = &name.suffix {
+		out.push_str(", ");
+		out.push_str(suffix);
+	}
+	out
+}
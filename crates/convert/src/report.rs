@@ -0,0 +1,616 @@
+//! Reports of what conversions drop or guess.
+//!
+//! Conversions between formats are rarely exact: some fields have nowhere to
+//! go, and some values have to be made up or worked out. Rather than printing
+//! warnings, converters record these in a [RecordReport] per converted record,
+//! and send those to a [WarningSink] chosen by the caller: a
+//! [ConversionReport] collects them so tools can present them as they see fit,
+//! or fail on lossy conversions; [PrintWarnings] writes them out as they come;
+//! [IgnoreWarnings] discards them. With the `log-warnings` feature,
+//! `LogWarnings` sends them to the [log](https://docs.rs/log) crate. Closures
+//! taking a [RecordReport] are sinks too.
+//!
+//! For audits, an [AuditLog] also gets every field that _was_ converted, from
+//! which source field to which target field and how, and writes all of it out
+//! as JSON lines, so converted data can be traced back to its source values.
+//! The CSL and CFF converters record these when their sink
+//! [audits](WarningSink::audits).
+//!
+//! ```
+//! use citeworks_convert::report::ConversionReport;
+//!
+//! let mut report = ConversionReport::default();
+//! report.record("doe2017").dropped("commit", "no CSL equivalent");
+//! report
+//!     .record("doe2017")
+//!     .guessed("date-published", "2017-01-01", "no month or day, used the 1st of January");
+//!
+//! assert_eq!(report.to_string(), "\
+//! doe2017: dropped commit: no CSL equivalent
+//! doe2017: guessed date-published = 2017-01-01: no month or day, used the 1st of January
+//! ");
+//! ```
+
+use std::{
+	collections::HashMap,
+	fmt,
+	io::{self, Write},
+};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Where converters send the reports of converted records.
+pub trait WarningSink {
+	/// Receive the report of one record.
+	///
+	/// Converters don't send empty reports.
+	fn report(&mut self, record: RecordReport);
+
+	/// Whether converters should record what they converted, in
+	/// [RecordReport::mapped]. This is only worth it for audits.
+	fn audits(&self) -> bool {
+		false
+	}
+}
+
+/// Send a record's report to a sink, unless it's empty.
+pub(crate) fn send(sink: &mut dyn WarningSink, record: RecordReport) {
+	if !record.is_empty() || !record.mapped.is_empty() {
+		sink.report(record);
+	}
+}
+
+/// Report the top-level keys of a serialised record which aren't in `mapped` as
+/// dropped, for the given reason.
+pub(crate) fn unmapped(
+	source: &impl Serialize,
+	mapped: &[&str],
+	reason: &str,
+	record: &mut RecordReport,
+) {
+	if let Ok(Value::Object(map)) = serde_json::to_value(source) {
+		for key in map.keys() {
+			if !mapped.contains(&key.as_str()) {
+				record.dropped(key.as_str(), reason);
+			}
+		}
+	}
+}
+
+/// A field converted from a source field, for audits.
+///
+/// These are `(sources, target, transform)`: the source fields which are
+/// combined into the target field, and a description of how, or empty if
+/// values are copied. For the same target, later entries are only used when
+/// none of the sources of earlier ones are there, e.g. for fallbacks.
+pub(crate) type Mapping = (&'static [&'static str], &'static str, &'static str);
+
+/// Record the fields mapped from the source to the target, as serialised.
+pub(crate) fn audit(
+	record: &mut RecordReport,
+	source: &Value,
+	target: &Value,
+	mappings: &[Mapping],
+) {
+	let mut mapped_targets = Vec::new();
+	for (sources, to, transform) in mappings {
+		if mapped_targets.contains(to) {
+			continue;
+		}
+		let result = match target.get(to) {
+			Some(result) => result,
+			None => continue,
+		};
+
+		let mut any = false;
+		for from in *sources {
+			if let Some(value) = source.get(from) {
+				any = true;
+				let transform = match *transform {
+					"" if value == result => "copied",
+					"" => "converted",
+					transform => transform,
+				};
+				record.mapped(*from, *to, value.clone(), result.clone(), transform);
+			}
+		}
+		if any {
+			mapped_targets.push(to);
+		}
+	}
+}
+
+impl WarningSink for ConversionReport {
+	fn report(&mut self, record: RecordReport) {
+		self.push(record);
+	}
+}
+
+impl<F: FnMut(RecordReport)> WarningSink for F {
+	fn report(&mut self, record: RecordReport) {
+		self(record);
+	}
+}
+
+/// A sink which discards reports.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IgnoreWarnings;
+
+impl WarningSink for IgnoreWarnings {
+	fn report(&mut self, _record: RecordReport) {}
+}
+
+/// A sink which writes reports out, one line per entry.
+///
+/// Once writing fails, nothing more is written, and the error is returned by
+/// [finish](Self::finish).
+#[derive(Debug)]
+pub struct PrintWarnings<W> {
+	writer: W,
+	prefix: String,
+	error: Option<io::Error>,
+}
+
+impl PrintWarnings<io::Stderr> {
+	/// Print to stderr, with lines prefixed by `WARNING: `.
+	pub fn stderr() -> Self {
+		Self::new(io::stderr()).prefix("WARNING: ")
+	}
+}
+
+impl<W: Write> PrintWarnings<W> {
+	/// Write to the given writer, without a prefix.
+	pub fn new(writer: W) -> Self {
+		Self {
+			writer,
+			prefix: String::new(),
+			error: None,
+		}
+	}
+
+	/// Prefix each line with this.
+	pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+		self.prefix = prefix.into();
+		self
+	}
+
+	/// Flush the writer, and return the first error writing, if any.
+	pub fn finish(&mut self) -> io::Result<()> {
+		match self.error.take() {
+			Some(err) => Err(err),
+			None => self.writer.flush(),
+		}
+	}
+
+	/// Get the writer back.
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+}
+
+impl<W: Write> WarningSink for PrintWarnings<W> {
+	fn report(&mut self, record: RecordReport) {
+		for line in record.to_string().lines() {
+			if self.error.is_some() {
+				return;
+			}
+			self.error = writeln!(self.writer, "{}{line}", self.prefix).err();
+		}
+	}
+}
+
+/// A sink which writes every entry of reports, converted fields included, as
+/// JSON lines for audits.
+///
+/// Each line is an object with the `record` id and an `action`:
+/// - `mapped`, with the source field (`from`), target field (`to`), source
+///   `value`, target `result`, and `transform`: `copied`, `converted`, or a
+///   description like `names`;
+/// - `dropped`, with the source `field` and `reason`;
+/// - `guessed`, with the target `field`, `value`, and `reason`;
+/// - `warning`, with the `message`.
+///
+/// Reports with anything besides converted fields are then passed on to
+/// another sink, if given. Once writing fails, nothing more is written, and
+/// the error is returned by [finish](Self::finish), which should be called
+/// when done.
+///
+/// ```
+/// use citeworks_convert::{csl::references_to_items_with, keys::KeyGenerator, report::AuditLog};
+///
+/// let reference = citeworks_cff::references::Reference {
+///     title: Some("Opaquity".into()),
+///     ..Default::default()
+/// };
+/// let mut log = AuditLog::new(Vec::new());
+/// references_to_items_with(&[reference], &mut KeyGenerator::default(), &mut log);
+///
+/// let log = String::from_utf8(log.into_inner()).unwrap();
+/// let title = log
+///     .lines()
+///     .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+///     .find(|line| line["from"] == "title")
+///     .unwrap();
+/// assert_eq!(title["action"], "mapped");
+/// assert_eq!(title["to"], "title");
+/// assert_eq!(title["transform"], "copied");
+/// ```
+#[derive(Debug)]
+pub struct AuditLog<W, S = IgnoreWarnings> {
+	writer: W,
+	sink: S,
+	error: Option<io::Error>,
+}
+
+impl<W: Write> AuditLog<W> {
+	/// Write to the given writer.
+	pub fn new(writer: W) -> Self {
+		Self {
+			writer,
+			sink: IgnoreWarnings,
+			error: None,
+		}
+	}
+}
+
+impl<W: Write, S: WarningSink> AuditLog<W, S> {
+	/// Pass reports on to another sink, e.g. to print warnings as well.
+	pub fn forward<T: WarningSink>(self, sink: T) -> AuditLog<W, T> {
+		AuditLog {
+			writer: self.writer,
+			sink,
+			error: self.error,
+		}
+	}
+
+	/// Flush the writer, and return the first error writing, if any.
+	pub fn finish(&mut self) -> io::Result<()> {
+		match self.error.take() {
+			Some(err) => Err(err),
+			None => self.writer.flush(),
+		}
+	}
+
+	/// Get the writer back.
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+}
+
+impl<W: Write, S: WarningSink> WarningSink for AuditLog<W, S> {
+	fn report(&mut self, record: RecordReport) {
+		for line in record.audit_lines() {
+			if self.error.is_some() {
+				break;
+			}
+			self.error = writeln!(self.writer, "{line}").err();
+		}
+		if !record.is_empty() {
+			self.sink.report(record);
+		}
+	}
+
+	fn audits(&self) -> bool {
+		true
+	}
+}
+
+/// A sink which logs each entry of reports as a warning.
+#[cfg(feature = "log-warnings")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogWarnings;
+
+#[cfg(feature = "log-warnings")]
+impl WarningSink for LogWarnings {
+	fn report(&mut self, record: RecordReport) {
+		for line in record.to_string().lines() {
+			log::warn!("{line}");
+		}
+	}
+}
+
+/// What happened to the records of a conversion.
+#[derive(Debug, Clone, Default)]
+pub struct ConversionReport {
+	/// The records which had something to report, in conversion order.
+	pub records: Vec<RecordReport>,
+
+	/// Position of the first record of each id, for the first `indexed`
+	/// records, and the id of the last of those, to notice changes.
+	index: HashMap<String, usize>,
+	indexed: usize,
+	last_indexed: Option<String>,
+}
+
+impl PartialEq for ConversionReport {
+	fn eq(&self, other: &Self) -> bool {
+		self.records == other.records
+	}
+}
+
+impl Eq for ConversionReport {}
+
+/// What happened to one record (a reference, item…) during a conversion.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RecordReport {
+	/// The id of the record, e.g. a CSL item id or citation key.
+	pub id: String,
+
+	/// Fields which were not converted.
+	pub dropped: Vec<Dropped>,
+
+	/// Values which were made up or worked out.
+	pub guessed: Vec<Guessed>,
+
+	/// Anything else worth knowing.
+	pub warnings: Vec<String>,
+
+	/// Fields which were converted, if the sink [audits](WarningSink::audits).
+	pub mapped: Vec<Mapped>,
+}
+
+/// A field which was not converted.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Dropped {
+	/// The name of the field in the source format.
+	pub field: String,
+
+	/// Why it was dropped.
+	pub reason: String,
+}
+
+/// A value which was made up or worked out.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Guessed {
+	/// The name of the field in the target format.
+	pub field: String,
+
+	/// The value it was given.
+	pub value: String,
+
+	/// Why it was guessed.
+	pub reason: String,
+}
+
+/// A field which was converted, for audits.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Mapped {
+	/// The name of the field in the source format.
+	pub from: String,
+
+	/// The name of the field in the target format.
+	pub to: String,
+
+	/// The value in the source, as serialised.
+	pub value: Value,
+
+	/// The value in the target, as serialised.
+	pub result: Value,
+
+	/// How the value was transformed: `copied`, `converted`, or a description.
+	pub transform: String,
+}
+
+impl ConversionReport {
+	/// The report for a record, created if there isn't one yet.
+	pub fn record(&mut self, id: &str) -> &mut RecordReport {
+		let index = match self.position(id) {
+			Some(index) => index,
+			None => {
+				self.records.push(RecordReport {
+					id: id.into(),
+					..Default::default()
+				});
+				self.records.len() - 1
+			}
+		};
+		&mut self.records[index]
+	}
+
+	/// Find a record by id, indexing the records added since the last lookup.
+	///
+	/// The records are public, so if they were changed otherwise than by
+	/// adding more, the index is rebuilt.
+	fn position(&mut self, id: &str) -> Option<usize> {
+		let last = self
+			.indexed
+			.checked_sub(1)
+			.and_then(|last| self.records.get(last));
+		let stale = last.map(|record| &record.id) != self.last_indexed.as_ref()
+			|| matches!(self.index.get(id), Some(&index) if self.records[index].id != id);
+		if stale {
+			self.index.clear();
+			self.indexed = 0;
+		}
+		for (index, record) in self.records.iter().enumerate().skip(self.indexed) {
+			self.index.entry(record.id.clone()).or_insert(index);
+		}
+		self.indexed = self.records.len();
+		self.last_indexed = self.records.last().map(|record| record.id.clone());
+		self.index.get(id).copied()
+	}
+
+	/// Add a record's report, unless it's empty.
+	pub fn push(&mut self, record: RecordReport) {
+		if !record.is_empty() {
+			self.records.push(record);
+		}
+	}
+
+	/// Whether nothing was reported.
+	pub fn is_empty(&self) -> bool {
+		self.records.iter().all(RecordReport::is_empty)
+	}
+
+	/// The report as JSON, for tools to process.
+	///
+	/// This is an object with a `records` array, each with the `id` of the
+	/// record, and `dropped`, `guessed`, and `warnings` arrays. Dropped fields
+	/// have a `field` and `reason`, guessed values also have the `value`, and
+	/// warnings are strings.
+	pub fn to_json(&self) -> Value {
+		json!({
+			"records": self.records.iter().map(RecordReport::to_json).collect::<Vec<_>>(),
+		})
+	}
+}
+
+impl RecordReport {
+	/// Create an empty report for a record.
+	pub fn new(id: impl Into<String>) -> Self {
+		Self {
+			id: id.into(),
+			..Default::default()
+		}
+	}
+
+	/// Record a field which was not converted.
+	pub fn dropped(&mut self, field: impl Into<String>, reason: impl Into<String>) -> &mut Self {
+		self.dropped.push(Dropped {
+			field: field.into(),
+			reason: reason.into(),
+		});
+		self
+	}
+
+	/// Record a value which was made up or worked out.
+	pub fn guessed(
+		&mut self,
+		field: impl Into<String>,
+		value: impl Into<String>,
+		reason: impl Into<String>,
+	) -> &mut Self {
+		self.guessed.push(Guessed {
+			field: field.into(),
+			value: value.into(),
+			reason: reason.into(),
+		});
+		self
+	}
+
+	/// Record anything else.
+	pub fn warn(&mut self, warning: impl Into<String>) -> &mut Self {
+		self.warnings.push(warning.into());
+		self
+	}
+
+	/// Record a field which was converted.
+	pub fn mapped(
+		&mut self,
+		from: impl Into<String>,
+		to: impl Into<String>,
+		value: Value,
+		result: Value,
+		transform: impl Into<String>,
+	) -> &mut Self {
+		self.mapped.push(Mapped {
+			from: from.into(),
+			to: to.into(),
+			value,
+			result,
+			transform: transform.into(),
+		});
+		self
+	}
+
+	/// Whether nothing was reported.
+	///
+	/// Converted fields don't count, as they're only for audits.
+	pub fn is_empty(&self) -> bool {
+		self.dropped.is_empty() && self.guessed.is_empty() && self.warnings.is_empty()
+	}
+
+	/// Every entry of the report as JSON, see [AuditLog].
+	pub fn audit_lines(&self) -> Vec<Value> {
+		let id = &self.id;
+		let mapped = self.mapped.iter().map(|mapped| {
+			json!({
+				"record": id,
+				"action": "mapped",
+				"from": mapped.from,
+				"to": mapped.to,
+				"value": mapped.value,
+				"result": mapped.result,
+				"transform": mapped.transform,
+			})
+		});
+		let dropped = self.dropped.iter().map(|dropped| {
+			json!({
+				"record": id,
+				"action": "dropped",
+				"field": dropped.field,
+				"reason": dropped.reason,
+			})
+		});
+		let guessed = self.guessed.iter().map(|guessed| {
+			json!({
+				"record": id,
+				"action": "guessed",
+				"field": guessed.field,
+				"value": guessed.value,
+				"reason": guessed.reason,
+			})
+		});
+		let warnings = self.warnings.iter().map(|warning| {
+			json!({
+				"record": id,
+				"action": "warning",
+				"message": warning,
+			})
+		});
+		mapped
+			.chain(dropped)
+			.chain(guessed)
+			.chain(warnings)
+			.collect()
+	}
+
+	/// The report as JSON, see [ConversionReport::to_json].
+	pub fn to_json(&self) -> Value {
+		json!({
+			"id": self.id,
+			"dropped": self.dropped.iter().map(|dropped| json!({
+				"field": dropped.field,
+				"reason": dropped.reason,
+			})).collect::<Vec<_>>(),
+			"guessed": self.guessed.iter().map(|guessed| json!({
+				"field": guessed.field,
+				"value": guessed.value,
+				"reason": guessed.reason,
+			})).collect::<Vec<_>>(),
+			"warnings": self.warnings,
+		})
+	}
+}
+
+impl fmt::Display for ConversionReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for record in &self.records {
+			write!(f, "{record}")?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for RecordReport {
+	/// One line per entry, prefixed by the record id.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let id = &self.id;
+		for Dropped { field, reason } in &self.dropped {
+			writeln!(f, "{id}: dropped {field}: {reason}")?;
+		}
+		for Guessed {
+			field,
+			value,
+			reason,
+		} in &self.guessed
+		{
+			writeln!(f, "{id}: guessed {field} = {value}: {reason}")?;
+		}
+		for warning in &self.warnings {
+			writeln!(f, "{id}: {warning}")?;
+		}
+		Ok(())
+	}
+}
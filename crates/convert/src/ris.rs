@@ -0,0 +1,536 @@
+//! Conversion between RIS records and CSL items or CFF references.
+//!
+//! RIS tags are mapped to CSL variables and back, using the meanings Zotero,
+//! EndNote, and Mendeley give them, which sometimes differ from the RIS
+//! specification: dates are read from `DA` when it's more precise than `PY`,
+//! container titles from any of `T2`, `JF`, `JO`, and `BT`, and so on. Values of
+//! tags without a CSL equivalent, or repeated beyond what's converted, are kept
+//! as item variables named by their tag, like `AN` or `L1`, with one line per
+//! value, and written back as RIS, so records survive going through CSL and
+//! back.
+//!
+//! CFF references are converted through CSL, see the [csl](crate::csl) and
+//! [cff](crate::cff) modules.
+//!
+//! ```
+//! use citeworks_convert::ris::{item_to_record, record_to_item};
+//! use citeworks_csl::items::ItemType;
+//!
+//! let records = citeworks_ris::from_str("\
+//! TY  - JOUR
+//! AU  - Druskat, Stephan
+//! TI  - Citation File Format
+//! T2  - Journal of Open Source Software
+//! PY  - 2017
+//! DA  - 2017/12/18/
+//! SP  - 1
+//! EP  - 8
+//! AN  - 12345
+//! ER  -
+//! ").unwrap();
+//!
+//! let item = record_to_item(&records[0]);
+//! assert_eq!(item.item_type, ItemType::ArticleJournal);
+//! assert_eq!(item.page.as_ref().unwrap().to_string(), "1-8");
+//!
+//! let record = item_to_record(&item);
+//! assert_eq!(record.get("DA"), Some("2017/12/18/"));
+//! assert_eq!(record.get("AN"), Some("12345"));
+//! ```
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use citeworks_cff::references::Reference;
+use citeworks_csl::{
+	dates::{Date as CslDate, DateMeta, DateParts, Season},
+	items::{ItemType, ItemValue},
+	names::Name as CslName,
+	ordinaries::OrdinaryValue,
+	Item,
+};
+use citeworks_ris::Record;
+
+use crate::{
+	cff::item_to_reference,
+	csl::{bare_doi, reference_to_item, references_to_items},
+	keys::{fill_missing_keys, KeyGenerator},
+	text::plain_text,
+};
+
+/// Convert an RIS record to a CSL item.
+///
+/// The item's id is the record's `ID`, or empty if it doesn't have one; see
+/// [records_to_items] to generate keys for those.
+pub fn record_to_item(record: &Record) -> Item {
+	let mut tags = Tags::new(record);
+
+	let title = tags.take(&["TI", "T1", "CT"]).or_else(|| {
+		if record.record_type == "BOOK" {
+			tags.take(&["BT"])
+		} else {
+			None
+		}
+	});
+	let date = tags.take(&["DA"]).and_then(|da| {
+		let date = ris_date(&da);
+		let precise = matches!(
+			&date,
+			CslDate::Single { date, meta } if date.month.is_some() || meta.season.is_some()
+		);
+		if precise {
+			// PY is then usually only the year of DA, and written back from it.
+			if let Some(py) = tags.take(&["PY"]) {
+				let same_year = matches!(
+					(&date, date_parts(py.trim())),
+					(CslDate::Single { date, .. }, Some((py, None)))
+						if py.month.is_none() && py.year == date.year
+				);
+				if !same_year {
+					tags.untake("PY", py);
+				}
+			}
+			Some(date)
+		} else {
+			tags.untake("DA", da);
+			None
+		}
+	});
+	let page = match (tags.take(&["SP"]), tags.take(&["EP"])) {
+		(Some(start), Some(end)) if !start.contains('-') => Some(format!("{start}-{end}")),
+		(Some(start), end) => {
+			if let Some(end) = end {
+				tags.untake("EP", end);
+			}
+			Some(start)
+		}
+		(None, end) => end,
+	};
+	let (isbn, issn) = match tags.take(&["SN"]) {
+		Some(sn) if is_isbn(&sn) => (Some(sn), None),
+		sn => (None, sn),
+	};
+	let string = |value: Option<String>| value.map(OrdinaryValue::String);
+
+	let mut item = Item {
+		id: tags.take(&["ID"]).unwrap_or_default(),
+		item_type: item_type(&record.record_type),
+		author: names(tags.take_all(&["AU", "A1"])),
+		editor: names(tags.take_all(&["A2", "ED"])),
+		translator: names(tags.take_all(&["A4"])),
+		title: string(title),
+		title_short: string(tags.take(&["ST"])),
+		container_title: string(tags.take(&["T2", "JF", "JO", "BT"])),
+		issued: date.or_else(|| tags.take(&["PY", "Y1"]).map(|py| ris_date(&py))),
+		accessed: tags.take(&["Y2"]).map(|y2| ris_date(&y2)),
+		volume: string(tags.take(&["VL"])),
+		issue: string(tags.take(&["IS", "CP"])),
+		page: string(page),
+		issn: string(issn),
+		doi: string(tags.take(&["DO"]).map(|doi| bare_doi(&doi).to_string())),
+		url: string(tags.take(&["UR"])),
+		abstract_text: string(tags.take(&["AB", "N2"])),
+		note: string(tags.take(&["N1"])),
+		language: string(tags.take(&["LA"])),
+		source: string(tags.take(&["DP"])),
+		..Default::default()
+	};
+
+	let collection_editors = names(tags.take_all(&["A3"]));
+	let keywords = tags.take_all(&["KW"]);
+	let fields = &mut item.fields;
+	if !collection_editors.is_empty() {
+		fields.insert(
+			"collection-editor".into(),
+			ItemValue::Names(collection_editors),
+		);
+	}
+	insert(fields, "container-title-short", tags.take(&["J2", "JA"]));
+	insert(fields, "collection-title", tags.take(&["T3"]));
+	insert(fields, "publisher", tags.take(&["PB"]));
+	insert(fields, "publisher-place", tags.take(&["CY", "PP"]));
+	insert(fields, "edition", tags.take(&["ET"]));
+	insert(fields, "ISBN", isbn);
+	insert(fields, "genre", tags.take(&["M3"]));
+	insert(fields, "call-number", tags.take(&["CN"]));
+	insert(
+		fields,
+		"keyword",
+		(!keywords.is_empty()).then(|| keywords.join(", ")),
+	);
+	for (tag, values) in tags.rest() {
+		insert(fields, &tag, Some(values.join("\n")));
+	}
+
+	item
+}
+
+/// Convert RIS records to CSL items, generating keys for records without `ID`.
+pub fn records_to_items(records: &[Record], keys: &mut KeyGenerator) -> Vec<Item> {
+	let mut items: Vec<Item> = records.iter().map(record_to_item).collect();
+	fill_missing_keys(&mut items, keys);
+	items
+}
+
+/// Convert a CSL item to an RIS record, with the item's id as `ID`.
+pub fn item_to_record(item: &Item) -> Record {
+	let mut record = Record::new(record_type(&item.item_type));
+	let ordinary =
+		|value: &Option<OrdinaryValue>| value.as_ref().map(|value| plain_text(&value.to_string()));
+	let field = |name: &str| match item.fields.get(name) {
+		Some(ItemValue::Ordinary(value)) => Some(plain_text(&value.to_string())),
+		_ => None,
+	};
+	let mut push = |tag: &str, value: Option<String>| {
+		if let Some(value) = value.filter(|value| !value.is_empty()) {
+			record.push(tag, value);
+		}
+	};
+
+	push("ID", Some(item.id.clone()));
+	for (tag, names) in [
+		("AU", &item.author),
+		("A2", &item.editor),
+		("A4", &item.translator),
+	] {
+		for name in names {
+			push(tag, ris_name(name));
+		}
+	}
+	if let Some(ItemValue::Names(names)) = item.fields.get("collection-editor") {
+		for name in names {
+			push("A3", ris_name(name));
+		}
+	}
+	push("TI", ordinary(&item.title));
+	push("ST", ordinary(&item.title_short));
+	push("T2", ordinary(&item.container_title));
+	push("J2", field("container-title-short"));
+	push("T3", field("collection-title"));
+
+	match &item.issued {
+		Some(CslDate::Single { date, meta })
+		| Some(CslDate::Range {
+			start: date, meta, ..
+		}) => {
+			push("PY", Some(date.year.to_string()));
+			if let Some(season) = &meta.season {
+				push("DA", Some(format!("{}{season}", date_string(date))));
+			} else if date.month.is_some() {
+				push("DA", Some(date_string(date)));
+			}
+		}
+		Some(CslDate::Raw { date, .. }) | Some(CslDate::Edtf { date, .. }) => {
+			push("PY", Some(date.clone()));
+		}
+		Some(CslDate::MetaOnly { meta }) => push("PY", meta.literal.clone()),
+		None => {}
+	}
+	if let Some(CslDate::Single { date, .. }) = &item.accessed {
+		push("Y2", Some(date_string(date)));
+	}
+
+	push("VL", ordinary(&item.volume));
+	push("IS", ordinary(&item.issue));
+	if let Some(page) = ordinary(&item.page) {
+		match page.split_once(&['-', '–'][..]) {
+			Some((start, end)) => {
+				push("SP", Some(start.trim().to_string()));
+				push("EP", Some(end.trim().to_string()));
+			}
+			None => push("SP", Some(page)),
+		}
+	}
+	push("PB", field("publisher"));
+	push("CY", field("publisher-place"));
+	push("ET", field("edition"));
+	push("SN", ordinary(&item.issn));
+	push("SN", field("ISBN"));
+	push("DO", ordinary(&item.doi));
+	push("UR", ordinary(&item.url));
+	push("AB", ordinary(&item.abstract_text));
+	push("N1", ordinary(&item.note));
+	for keyword in field("keyword")
+		.iter()
+		.flat_map(|keywords| keywords.split(','))
+	{
+		push("KW", Some(keyword.trim().to_string()));
+	}
+	push("LA", ordinary(&item.language));
+	push("M3", field("genre"));
+	push("DP", ordinary(&item.source));
+	push("CN", field("call-number"));
+
+	for (tag, value) in &item.fields {
+		let is_tag = tag.len() == 2
+			&& tag.starts_with(|c: char| c.is_ascii_uppercase())
+			&& tag.ends_with(|c: char| c.is_ascii_uppercase() || c.is_ascii_digit());
+		if let (true, ItemValue::Ordinary(value)) = (is_tag, value) {
+			for line in value.to_string().lines() {
+				// Values also converted to variables are written only once.
+				if !record.get_all(tag).any(|existing| existing == line) {
+					record.push(tag.clone(), line);
+				}
+			}
+		}
+	}
+
+	record
+}
+
+/// Convert an RIS record to a CFF reference, through CSL.
+pub fn record_to_reference(record: &Record) -> Result<Reference, String> {
+	item_to_reference(record_to_item(record))
+}
+
+/// Convert a CFF reference to an RIS record with the given `ID`, through CSL.
+pub fn reference_to_record(reference: &Reference, id: String) -> Record {
+	item_to_record(&reference_to_item(reference, id))
+}
+
+/// Convert CFF references to RIS records.
+///
+/// Keys are generated as for [references_to_items].
+pub fn references_to_records(references: &[Reference]) -> Vec<Record> {
+	references_to_items(references)
+		.iter()
+		.map(item_to_record)
+		.collect()
+}
+
+/// The closest CSL type for an RIS type.
+pub fn item_type(record_type: &str) -> ItemType {
+	match record_type.trim().to_ascii_uppercase().as_str() {
+		"JOUR" | "JFULL" | "EJOUR" | "INPR" => ItemType::ArticleJournal,
+		"MGZN" => ItemType::ArticleMagazine,
+		"NEWS" => ItemType::ArticleNewspaper,
+		"BOOK" | "EBOOK" | "EDBOOK" | "CLSWK" => ItemType::Book,
+		"CHAP" | "ECHAP" => ItemType::Chapter,
+		"CONF" | "CPAPER" => ItemType::PaperConference,
+		"THES" => ItemType::Thesis,
+		"RPRT" => ItemType::Report,
+		"ELEC" | "WEB" | "ICOMM" => ItemType::Webpage,
+		"BLOG" => ItemType::PostWeblog,
+		"COMP" => ItemType::Software,
+		"DATA" | "DBASE" | "AGGR" => ItemType::Dataset,
+		"MAP" => ItemType::Map,
+		"PAT" => ItemType::Patent,
+		"STAND" => ItemType::Standard,
+		"UNPB" | "MANSCPT" => ItemType::Manuscript,
+		"PAMP" => ItemType::Pamphlet,
+		"CASE" => ItemType::LegalCase,
+		"BILL" => ItemType::Bill,
+		"STAT" => ItemType::Legislation,
+		"HEAR" => ItemType::Hearing,
+		"PCOMM" => ItemType::PersonalCommunication,
+		"SOUND" | "MUSIC" => ItemType::Song,
+		"VIDEO" | "MPCT" => ItemType::MotionPicture,
+		"ART" | "FIGURE" => ItemType::Graphic,
+		"DICT" => ItemType::EntryDictionary,
+		"ENCYC" => ItemType::EntryEncyclopedia,
+		_ => ItemType::Document,
+	}
+}
+
+/// The closest RIS type for a CSL type.
+pub fn record_type(item_type: &ItemType) -> &'static str {
+	match item_type {
+		ItemType::Article | ItemType::ArticleJournal => "JOUR",
+		ItemType::ArticleMagazine => "MGZN",
+		ItemType::ArticleNewspaper => "NEWS",
+		ItemType::Book | ItemType::Classic => "BOOK",
+		ItemType::Chapter => "CHAP",
+		ItemType::PaperConference => "CPAPER",
+		ItemType::Thesis => "THES",
+		ItemType::Report => "RPRT",
+		ItemType::Webpage | ItemType::Post => "ELEC",
+		ItemType::PostWeblog => "BLOG",
+		ItemType::Software => "COMP",
+		ItemType::Dataset => "DATA",
+		ItemType::Map => "MAP",
+		ItemType::Patent => "PAT",
+		ItemType::Standard => "STAND",
+		ItemType::Manuscript => "UNPB",
+		ItemType::Pamphlet => "PAMP",
+		ItemType::LegalCase => "CASE",
+		ItemType::Bill => "BILL",
+		ItemType::Legislation => "STAT",
+		ItemType::Hearing => "HEAR",
+		ItemType::PersonalCommunication => "PCOMM",
+		ItemType::Song => "SOUND",
+		ItemType::MotionPicture => "MPCT",
+		ItemType::Graphic => "ART",
+		ItemType::EntryDictionary => "DICT",
+		ItemType::EntryEncyclopedia => "ENCYC",
+		_ => "GEN",
+	}
+}
+
+/// The values of a record, taken as they're converted.
+struct Tags<'r> {
+	record: &'r Record,
+	taken: Vec<bool>,
+	untaken: BTreeMap<String, Vec<String>>,
+}
+
+impl<'r> Tags<'r> {
+	fn new(record: &'r Record) -> Self {
+		Self {
+			record,
+			taken: vec![false; record.fields.len()],
+			untaken: BTreeMap::new(),
+		}
+	}
+
+	/// The first value of the first of the tags that has one.
+	fn take(&mut self, tags: &[&str]) -> Option<String> {
+		let n = tags.iter().find_map(|tag| {
+			self.record
+				.fields
+				.iter()
+				.enumerate()
+				.position(|(n, field)| {
+					!self.taken[n] && field.tag == *tag && !field.value.trim().is_empty()
+				})
+		})?;
+		self.taken[n] = true;
+		Some(self.record.fields[n].value.trim().to_string())
+	}
+
+	/// All values of the tags, in order.
+	fn take_all(&mut self, tags: &[&str]) -> Vec<String> {
+		let mut values = Vec::new();
+		for (n, field) in self.record.fields.iter().enumerate() {
+			if !self.taken[n] && tags.contains(&field.tag.as_str()) {
+				self.taken[n] = true;
+				values.push(field.value.trim().to_string());
+			}
+		}
+		values.retain(|value| !value.is_empty());
+		values
+	}
+
+	/// Give back a value that was taken but couldn't be converted.
+	fn untake(&mut self, tag: &str, value: String) {
+		self.untaken.entry(tag.into()).or_default().push(value);
+	}
+
+	/// The values that weren't taken, by tag.
+	fn rest(mut self) -> BTreeMap<String, Vec<String>> {
+		for (n, field) in self.record.fields.iter().enumerate() {
+			if !self.taken[n] && !field.value.trim().is_empty() {
+				self.untaken
+					.entry(field.tag.clone())
+					.or_default()
+					.push(field.value.trim().to_string());
+			}
+		}
+		self.untaken
+	}
+}
+
+/// Names as `Last, First, Suffix`; names without a comma are literal.
+fn names(values: Vec<String>) -> Vec<CslName> {
+	values
+		.into_iter()
+		.map(|value| {
+			let mut parts = value.splitn(3, ',').map(str::trim);
+			let family = parts.next().unwrap_or_default();
+			match parts.next() {
+				Some(given) => CslName {
+					family: Some(family.into()),
+					given: Some(given.into()).filter(|given: &String| !given.is_empty()),
+					suffix: parts.next().map(Into::into),
+					..Default::default()
+				},
+				None => CslName {
+					literal: Some(value),
+					..Default::default()
+				},
+			}
+		})
+		.collect()
+}
+
+fn ris_name(name: &CslName) -> Option<String> {
+	if let Some(literal) = &name.literal {
+		return Some(literal.clone());
+	}
+
+	let family = [&name.non_dropping_particle, &name.family]
+		.into_iter()
+		.flatten()
+		.cloned()
+		.collect::<Vec<_>>()
+		.join(" ");
+	let given = [&name.given, &name.dropping_particle]
+		.into_iter()
+		.flatten()
+		.cloned()
+		.collect::<Vec<_>>()
+		.join(" ");
+	match (family.is_empty(), &name.suffix) {
+		(true, _) if given.is_empty() => None,
+		(true, _) => Some(given),
+		(false, Some(suffix)) => Some(format!("{family}, {given}, {suffix}")),
+		(false, None) if given.is_empty() => Some(family),
+		(false, None) => Some(format!("{family}, {given}")),
+	}
+}
+
+/// Dates as `YYYY/MM/DD/other`, where all but the year can be empty and the
+/// other part is often a season; or as `YYYY-MM-DD`. Others are kept raw.
+fn ris_date(date: &str) -> CslDate {
+	match date_parts(date.trim()) {
+		Some((date, season)) => CslDate::Single {
+			date,
+			meta: DateMeta {
+				season,
+				..Default::default()
+			},
+		},
+		None => CslDate::Raw {
+			date: date.trim().into(),
+			meta: DateMeta::default(),
+		},
+	}
+}
+
+fn date_parts(date: &str) -> Option<(DateParts, Option<Season>)> {
+	let separator = if date.contains('/') { '/' } else { '-' };
+	let mut parts = date.splitn(4, separator).map(str::trim);
+	let year = parts.next()?.parse().ok()?;
+	let mut number = || match parts.next() {
+		None | Some("") => Some(None),
+		Some(part) => part.parse::<u8>().ok().map(Some),
+	};
+	let month = number()?;
+	let day = number()?;
+	let season = match parts.next() {
+		None | Some("") => None,
+		Some(other) => Some(Season::from_str(other).ok()?),
+	};
+	Some((DateParts { year, month, day }, season))
+}
+
+fn date_string(date: &DateParts) -> String {
+	let part = |part: Option<u8>| part.map(|n| format!("{n:02}")).unwrap_or_default();
+	format!("{:04}/{}/{}/", date.year, part(date.month), part(date.day))
+}
+
+/// ISBNs have 10 or 13 digits (the last of 10 can be X); ISSNs have 8.
+fn is_isbn(sn: &str) -> bool {
+	let digits = sn
+		.chars()
+		.filter(|c| c.is_ascii_digit() || *c == 'X' || *c == 'x')
+		.count();
+	digits == 10 || digits == 13
+}
+
+fn insert(fields: &mut BTreeMap<String, ItemValue>, name: &str, value: Option<String>) {
+	if let Some(value) = value {
+		fields.insert(
+			name.into(),
+			ItemValue::Ordinary(OrdinaryValue::String(value)),
+		);
+	}
+}
@@ -0,0 +1,501 @@
+//! Conversion to [schema.org] JSON-LD.
+//!
+//! Software is described as [`SoftwareSourceCode`][ssc], datasets as
+//! [`Dataset`][ds], and references with the closest schema.org type, e.g.
+//! [`ScholarlyArticle`][sa] for articles.
+//!
+//! ```
+//! let cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! version: 2.0.4
+//! authors:
+//!   - family-names: Druskat
+//!     given-names: Stephan
+//! "#).unwrap();
+//!
+//! let jsonld = citeworks_convert::schemaorg::cff_to_jsonld(&cff);
+//! assert_eq!(jsonld["@type"], "SoftwareSourceCode");
+//! assert_eq!(jsonld["name"], "My Research Software");
+//! assert_eq!(jsonld["author"][0]["familyName"], "Druskat");
+//! ```
+//!
+//! [schema.org]: https://schema.org
+//! [ssc]: https://schema.org/SoftwareSourceCode
+//! [ds]: https://schema.org/Dataset
+//! [sa]: https://schema.org/ScholarlyArticle
+
+use citeworks_cff::{
+	names::{EntityName, Name, PersonName},
+	references::{RefType, Reference},
+	Cff, License, WorkType,
+};
+use serde_json::{json, Map, Value};
+
+use crate::report::{send, unmapped, IgnoreWarnings, RecordReport, WarningSink};
+
+/// The JSON-LD context for schema.org.
+pub const CONTEXT: &str = "https://schema.org";
+
+/// Top-level CFF keys which are converted, or which are metadata about the file.
+const MAPPED_CFF_KEYS: &[&str] = &[
+	"cff-version",
+	"message",
+	"type",
+	"doi",
+	"title",
+	"version",
+	"abstract",
+	"date-released",
+	"url",
+	"repository-code",
+	"repository",
+	"license",
+	"keywords",
+	"authors",
+	"contact",
+	"funding",
+	"preferred-citation",
+	"references",
+];
+
+/// Reference keys which are converted.
+const MAPPED_REFERENCE_KEYS: &[&str] = &[
+	"type",
+	"doi",
+	"title",
+	"abbreviation",
+	"abstract",
+	"version",
+	"url",
+	"repository-code",
+	"license",
+	"isbn",
+	"edition",
+	"date-published",
+	"date-released",
+	"year",
+	"start",
+	"end",
+	"publisher",
+	"institution",
+	"languages",
+	"keywords",
+	"authors",
+	"editors",
+	"translators",
+	"journal",
+	"collection-title",
+	"issn",
+	"volume",
+	"issue",
+];
+
+const NO_EQUIVALENT: &str = "no schema.org equivalent";
+
+/// Convert a CFF document to schema.org JSON-LD.
+///
+/// The work itself is the top-level object, and its references are listed
+/// under `citation`.
+pub fn cff_to_jsonld(cff: &Cff) -> Value {
+	cff_to_jsonld_with(cff, &mut IgnoreWarnings)
+}
+
+/// Convert a CFF document to schema.org JSON-LD.
+///
+/// Fields which can't be converted are reported to the sink: the document's
+/// under its title, and references' as `preferred-citation` or
+/// `references[0]`, etc.
+pub fn cff_to_jsonld_with(cff: &Cff, report: &mut dyn WarningSink) -> Value {
+	let mut record = RecordReport::new(cff.title.clone());
+	unmapped(cff, MAPPED_CFF_KEYS, NO_EQUIVALENT, &mut record);
+	if cff.repository_code.is_some() && cff.repository.is_some() {
+		record.dropped("repository", "code repository taken by repository-code");
+	}
+	send(report, record);
+
+	let mut obj = Map::new();
+	obj.insert("@context".into(), CONTEXT.into());
+	obj.insert(
+		"@type".into(),
+		match cff.work_type {
+			Some(WorkType::Dataset) => "Dataset",
+			_ => "SoftwareSourceCode",
+		}
+		.into(),
+	);
+
+	if let Some(doi) = &cff.doi {
+		obj.insert("@id".into(), doi_url(doi).into());
+		obj.insert("identifier".into(), doi_url(doi).into());
+	}
+
+	obj.insert("name".into(), cff.title.clone().into());
+	insert_opt(&mut obj, "version", cff.version.clone());
+	insert_opt(&mut obj, "description", cff.abstract_text.clone());
+	insert_opt(
+		&mut obj,
+		"datePublished",
+		cff.date_released.map(|d| d.to_string()),
+	);
+	insert_opt(&mut obj, "url", cff.url.as_ref().map(|u| u.to_string()));
+	insert_opt(
+		&mut obj,
+		"codeRepository",
+		cff.repository_code
+			.as_ref()
+			.or(cff.repository.as_ref())
+			.map(|u| u.to_string()),
+	);
+	insert_opt(&mut obj, "license", license(cff.license.as_ref()));
+	insert_list(
+		&mut obj,
+		"keywords",
+		cff.keywords.iter().cloned().map(Value::from),
+	);
+	insert_list(&mut obj, "author", cff.authors.iter().filter_map(name));
+	insert_list(&mut obj, "maintainer", cff.contact.iter().filter_map(name));
+	insert_list(
+		&mut obj,
+		"funding",
+		cff.funding.iter().map(|funding| {
+			let mut grant = Map::new();
+			grant.insert("@type".into(), "Grant".into());
+			insert_opt(&mut grant, "identifier", funding.award_number.clone());
+			insert_opt(&mut grant, "name", funding.award_title.clone());
+			insert_opt(
+				&mut grant,
+				"url",
+				funding.award_uri.as_ref().map(|u| u.to_string()),
+			);
+			grant.insert("funder".into(), entity(&funding.funder));
+			Value::Object(grant)
+		}),
+	);
+	insert_list(
+		&mut obj,
+		"citation",
+		cff.preferred_citation
+			.iter()
+			.map(|reference| (String::from("preferred-citation"), reference))
+			.chain(
+				cff.references
+					.iter()
+					.enumerate()
+					.map(|(n, reference)| (format!("references[{n}]"), reference)),
+			)
+			.map(|(id, reference)| {
+				send(report, reference_report(reference, id));
+				reference_object(reference)
+			}),
+	);
+
+	Value::Object(obj)
+}
+
+/// Convert a single reference to schema.org JSON-LD.
+pub fn reference_to_jsonld(reference: &Reference) -> Value {
+	reference_to_jsonld_with(reference, &mut IgnoreWarnings)
+}
+
+/// Convert a single reference to schema.org JSON-LD.
+///
+/// Fields which can't be converted are reported to the sink, under the title
+/// of the reference.
+pub fn reference_to_jsonld_with(reference: &Reference, report: &mut dyn WarningSink) -> Value {
+	send(
+		report,
+		reference_report(reference, reference.title.clone().unwrap_or_default()),
+	);
+	let mut value = reference_object(reference);
+	if let Value::Object(obj) = &mut value {
+		obj.insert("@context".into(), CONTEXT.into());
+	}
+	value
+}
+
+/// Wrap JSON-LD in a `<script>` tag suitable for embedding in an HTML page.
+pub fn html_script(jsonld: &Value) -> String {
+	let json = serde_json::to_string_pretty(jsonld)
+		.expect("serialising a Value never fails")
+		// prevent the JSON from closing the script element early
+		.replace("</", "<\\/");
+	format!("<script type=\"application/ld+json\">\n{json}\n</script>")
+}
+
+/// The schema.org type that best corresponds to a reference type.
+pub fn reference_type(ref_type: &RefType) -> &'static str {
+	match ref_type {
+		RefType::Art => "VisualArtwork",
+		RefType::Article | RefType::ConferencePaper => "ScholarlyArticle",
+		RefType::Audiovisual | RefType::Multimedia => "MediaObject",
+		RefType::Blog => "BlogPosting",
+		RefType::Book | RefType::EditedWork => "Book",
+		RefType::Catalogue => "Collection",
+		RefType::Conference => "Event",
+		RefType::Data | RefType::Database => "Dataset",
+		RefType::Encyclopedia | RefType::Dictionary => "Book",
+		RefType::FilmBroadcast => "Movie",
+		RefType::Grant => "Grant",
+		RefType::Bill | RefType::GovernmentDocument | RefType::LegalRule | RefType::Statute => {
+			"Legislation"
+		}
+		RefType::LegalCase => "CreativeWork",
+		RefType::MagazineArticle | RefType::NewspaperArticle => "Article",
+		RefType::Manual => "TechArticle",
+		RefType::Map => "Map",
+		RefType::Music | RefType::SoundRecording => "MusicRecording",
+		RefType::Patent => "CreativeWork",
+		RefType::Proceedings => "Book",
+		RefType::Report => "Report",
+		RefType::Serial => "Periodical",
+		RefType::Slides => "PresentationDigitalDocument",
+		RefType::SoftwareCode | RefType::Software => "SoftwareSourceCode",
+		RefType::SoftwareContainer
+		| RefType::SoftwareExecutable
+		| RefType::SoftwareVirtualMachine => "SoftwareApplication",
+		RefType::Thesis => "Thesis",
+		RefType::Video => "VideoObject",
+		RefType::Website => "WebSite",
+		_ => "CreativeWork",
+	}
+}
+
+/// What's lost converting a reference.
+fn reference_report(reference: &Reference, id: String) -> RecordReport {
+	let mut record = RecordReport::new(id);
+	unmapped(reference, MAPPED_REFERENCE_KEYS, NO_EQUIVALENT, &mut record);
+	if reference.journal.is_some() && reference.collection_title.is_some() {
+		record.dropped("collection-title", "container taken by journal");
+	}
+	if reference.date_published.is_some() && reference.date_released.is_some() {
+		record.dropped("date-released", "date taken by date-published");
+	}
+	record
+}
+
+fn reference_object(reference: &Reference) -> Value {
+	let mut obj = Map::new();
+	obj.insert("@type".into(), reference_type(&reference.work_type).into());
+
+	if let Some(doi) = &reference.doi {
+		obj.insert("@id".into(), doi_url(doi).into());
+		obj.insert("identifier".into(), doi_url(doi).into());
+	}
+
+	insert_opt(&mut obj, "name", reference.title.clone());
+	insert_opt(&mut obj, "alternateName", reference.abbreviation.clone());
+	insert_opt(&mut obj, "abstract", reference.abstract_text.clone());
+	insert_opt(&mut obj, "version", reference.version.clone());
+	insert_opt(
+		&mut obj,
+		"url",
+		reference.url.as_ref().map(|u| u.to_string()),
+	);
+	insert_opt(
+		&mut obj,
+		"codeRepository",
+		reference.repository_code.as_ref().map(|u| u.to_string()),
+	);
+	insert_opt(&mut obj, "license", license(reference.license.as_ref()));
+	insert_opt(&mut obj, "isbn", reference.isbn.clone().map(String::from));
+	insert_opt(&mut obj, "bookEdition", reference.edition.clone());
+	insert_opt(
+		&mut obj,
+		"datePublished",
+		reference
+			.date_published
+			.or(reference.date_released)
+			.map(|d| d.to_string())
+			.or_else(|| reference.year.map(|y| y.to_string())),
+	);
+	insert_opt(&mut obj, "pageStart", reference.start.map(Value::from));
+	insert_opt(&mut obj, "pageEnd", reference.end.map(Value::from));
+	insert_opt(
+		&mut obj,
+		"publisher",
+		reference.publisher.as_ref().map(entity),
+	);
+	insert_opt(
+		&mut obj,
+		"sourceOrganization",
+		reference.institution.as_ref().map(entity),
+	);
+	insert_list(
+		&mut obj,
+		"inLanguage",
+		reference.languages.iter().cloned().map(Value::from),
+	);
+	insert_list(
+		&mut obj,
+		"keywords",
+		reference.keywords.iter().cloned().map(Value::from),
+	);
+	insert_list(
+		&mut obj,
+		"author",
+		reference.authors.iter().filter_map(name),
+	);
+	insert_list(
+		&mut obj,
+		"editor",
+		reference.editors.iter().filter_map(name),
+	);
+	insert_list(
+		&mut obj,
+		"translator",
+		reference.translators.iter().filter_map(name),
+	);
+
+	if let Some(part_of) = is_part_of(reference) {
+		obj.insert("isPartOf".into(), part_of);
+	}
+
+	Value::Object(obj)
+}
+
+/// The periodical or collection a reference was published in, if any.
+fn is_part_of(reference: &Reference) -> Option<Value> {
+	let (part_type, name) = if let Some(journal) = &reference.journal {
+		("Periodical", journal)
+	} else if let Some(collection) = &reference.collection_title {
+		("Collection", collection)
+	} else {
+		return None;
+	};
+
+	let mut periodical = json!({ "@type": part_type, "name": name });
+	if let Some(issn) = &reference.issn {
+		periodical["issn"] = issn.as_str().into();
+	}
+
+	// Each level present is part of the next: issue, then volume, then periodical.
+	let mut part = periodical;
+	if let Some(volume) = reference.volume {
+		part = json!({
+			"@type": "PublicationVolume",
+			"volumeNumber": volume.to_string(),
+			"isPartOf": part,
+		});
+	}
+	if let Some(issue) = &reference.issue {
+		part = json!({
+			"@type": "PublicationIssue",
+			"issueNumber": issue,
+			"isPartOf": part,
+		});
+	}
+	Some(part)
+}
+
+fn name(name: &Name) -> Option<Value> {
+	match name {
+		Name::Person(person) => Some(person_object(person)),
+		Name::Entity(e) => Some(entity(e)),
+		Name::Anonymous => None,
+	}
+}
+
+fn person_object(person: &PersonName) -> Value {
+	let mut obj = Map::new();
+	obj.insert("@type".into(), "Person".into());
+	insert_opt(
+		&mut obj,
+		"@id",
+		person.meta.orcid.as_ref().map(|u| u.to_string()),
+	);
+	insert_opt(&mut obj, "givenName", person.given_names.clone());
+	insert_opt(
+		&mut obj,
+		"familyName",
+		match (&person.name_particle, &person.family_names) {
+			(Some(particle), Some(family)) => Some(format!("{particle} {family}")),
+			(None, family) => family.clone(),
+			(Some(particle), None) => Some(particle.clone()),
+		},
+	);
+	insert_opt(&mut obj, "honorificSuffix", person.name_suffix.clone());
+	insert_opt(&mut obj, "email", person.meta.email.clone());
+	insert_opt(
+		&mut obj,
+		"affiliation",
+		person.affiliation.as_ref().map(|affiliation| {
+			let mut org = Map::new();
+			org.insert("@type".into(), "Organization".into());
+			insert_opt(
+				&mut org,
+				"@id",
+				person.meta.ror.as_ref().map(|u| u.to_string()),
+			);
+			org.insert("name".into(), affiliation.clone().into());
+			Value::Object(org)
+		}),
+	);
+	Value::Object(obj)
+}
+
+fn entity(entity: &EntityName) -> Value {
+	let mut obj = Map::new();
+	obj.insert("@type".into(), "Organization".into());
+	insert_opt(
+		&mut obj,
+		"@id",
+		entity
+			.meta
+			.ror
+			.as_ref()
+			.or(entity.meta.orcid.as_ref())
+			.map(|u| u.to_string()),
+	);
+	insert_opt(&mut obj, "name", entity.name.clone());
+	insert_opt(&mut obj, "email", entity.meta.email.clone());
+	insert_opt(
+		&mut obj,
+		"url",
+		entity.meta.website.as_ref().map(|u| u.to_string()),
+	);
+	Value::Object(obj)
+}
+
+/// SPDX license URL(s), or the expression string for compound expressions, or
+/// licenses which aren't SPDX expressions as written.
+fn license(license: Option<&License>) -> Option<Value> {
+	let expression_value = |exp: &str| -> Value {
+		if exp.contains(' ') {
+			exp.into()
+		} else {
+			format!("https://spdx.org/licenses/{exp}").into()
+		}
+	};
+
+	license.map(|license| match license {
+		License::Single(exp) => expression_value(&exp.to_string()),
+		License::AnyOf(exps) => Value::Array(
+			exps.iter()
+				.map(|exp| expression_value(exp.as_ref()))
+				.collect(),
+		),
+		License::Unparsed(text) => text.as_str().into(),
+		License::UnparsedAnyOf(texts) => {
+			Value::Array(texts.iter().map(|text| text.as_str().into()).collect())
+		}
+	})
+}
+
+fn doi_url(doi: &str) -> String {
+	format!("https://doi.org/{doi}")
+}
+
+fn insert_opt(obj: &mut Map<String, Value>, key: &str, value: Option<impl Into<Value>>) {
+	if let Some(value) = value {
+		obj.insert(key.into(), value.into());
+	}
+}
+
+fn insert_list(obj: &mut Map<String, Value>, key: &str, values: impl Iterator<Item = Value>) {
+	let values: Vec<Value> = values.collect();
+	if !values.is_empty() {
+		obj.insert(key.into(), Value::Array(values));
+	}
+}
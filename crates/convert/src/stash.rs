@@ -0,0 +1,143 @@
+//! Lossless round-trips from CSL items to CFF references and back.
+//!
+//! CFF references can't represent everything CSL items can, so converting an
+//! item to a reference and back loses data: the item's id, variables CFF has no
+//! field for, types that map to the same CFF type, etc. To avoid that, the CSL
+//! variables which wouldn't survive the round-trip can be [stash]ed in the
+//! reference's `notes`, as a line of JSON:
+//!
+//! ```text
+//! citeworks-csl: {"id":"doe2017","publisher-place":"Wellington"}
+//! ```
+//!
+//! and [restore]d on the way back.
+//!
+//! ```
+//! use citeworks_cff::references::Reference;
+//! use citeworks_convert::{csl::reference_to_item, stash::{restore, stash}};
+//!
+//! let items = citeworks_csl::from_str(r#"[{
+//!     "id": "doe2017",
+//!     "type": "chapter",
+//!     "title": "A chapter",
+//!     "publisher-place": "Wellington"
+//! }]"#).unwrap();
+//! let original = &items[0];
+//!
+//! // A lossy conversion, as done by csl2cff
+//! let mut reference = Reference {
+//!     title: Some("A chapter".into()),
+//!     ..Default::default()
+//! };
+//!
+//! stash(&mut reference, original);
+//! let mut item = reference_to_item(&reference, "generated".into());
+//! assert!(restore(&mut item).unwrap());
+//! assert_eq!(&item, original);
+//! ```
+
+use citeworks_cff::references::Reference;
+use citeworks_csl::Item;
+use serde_json::{Map, Value};
+
+use crate::csl::reference_to_item;
+
+/// The prefix of the line of the notes holding stashed variables.
+pub const MARKER: &str = "citeworks-csl: ";
+
+/// Stash the variables of `original` that `reference` doesn't preserve.
+///
+/// The reference should already have been converted from the original item.
+/// The item's id is always stashed, as CFF references don't have ids.
+/// Variables the round-trip would add are stashed as `null`, so they're
+/// removed when restoring.
+///
+/// Any previous stash in the notes is replaced.
+pub fn stash(reference: &mut Reference, original: &Item) {
+	remove_stash(&mut reference.notes);
+
+	let original = to_map(original);
+	let roundtrip = to_map(&reference_to_item(reference, String::new()));
+
+	let mut stashed = Map::new();
+	for (key, value) in &original {
+		if key == "id" || roundtrip.get(key) != Some(value) {
+			stashed.insert(key.clone(), value.clone());
+		}
+	}
+	for key in roundtrip.keys() {
+		if !original.contains_key(key) {
+			stashed.insert(key.clone(), Value::Null);
+		}
+	}
+
+	let line = format!("{MARKER}{}", Value::Object(stashed));
+	reference.notes = Some(match reference.notes.take() {
+		Some(notes) => format!("{notes}\n\n{line}"),
+		None => line,
+	});
+}
+
+/// Restore stashed variables into an item converted from a reference.
+///
+/// The stash is removed from the item's note. Returns whether there was a
+/// stash to restore, or an error if the stashed variables aren't valid for the
+/// item, in which case the item is left as it was.
+pub fn restore(item: &mut Item) -> Result<bool, String> {
+	let mut note = item.note.as_ref().map(ToString::to_string);
+	let stashed = match remove_stash(&mut note) {
+		Some(stashed) => stashed,
+		None => return Ok(false),
+	};
+
+	let mut map = to_map(item);
+	match note {
+		Some(note) => map.insert("note".into(), Value::String(note)),
+		None => map.remove("note"),
+	};
+	for (key, value) in stashed {
+		if value.is_null() {
+			map.remove(&key);
+		} else {
+			map.insert(key, value);
+		}
+	}
+
+	*item = serde_json::from_value(Value::Object(map))
+		.map_err(|err| format!("invalid stashed variables for {}: {err}", item.id))?;
+	Ok(true)
+}
+
+/// Take the stash line out of notes, returning the stashed variables.
+fn remove_stash(notes: &mut Option<String>) -> Option<Map<String, Value>> {
+	let text = notes.as_deref()?;
+	let (before, line) = match text.rfind(MARKER) {
+		Some(0) => ("", text),
+		Some(n) if text[..n].ends_with('\n') => (&text[..n], &text[n..]),
+		_ => return None,
+	};
+	let (json, after) = line[MARKER.len()..]
+		.split_once('\n')
+		.unwrap_or((&line[MARKER.len()..], ""));
+
+	let stashed = match serde_json::from_str(json) {
+		Ok(Value::Object(stashed)) => stashed,
+		_ => return None,
+	};
+
+	let rest = format!("{}\n{}", before.trim_end(), after.trim_start());
+	let rest = rest.trim();
+	*notes = if rest.is_empty() {
+		None
+	} else {
+		Some(rest.to_string())
+	};
+	Some(stashed)
+}
+
+fn to_map(item: &Item) -> Map<String, Value> {
+	match serde_json::to_value(item) {
+		Ok(Value::Object(map)) => map,
+		_ => Map::new(),
+	}
+}
@@ -0,0 +1,661 @@
+//! Export to and import from CSV and TSV tables.
+//!
+//! Reference lists are often wanted as spreadsheets, for grant reports or
+//! supervisors, and are sometimes kept in them. Each item becomes a row, with one column for each CSL variable
+//! asked for, or for its year, its rendered [entry](crate::render::entry), or
+//! its [citation](crate::render::author_date):
+//!
+//! ```
+//! use citeworks_convert::table::{items_to_table, Column, TableOptions};
+//!
+//! let items = citeworks_csl::from_str(r#"[{
+//!     "id": "doe2017",
+//!     "type": "book",
+//!     "author": [{"family": "Doe", "given": "Jane"}, {"family": "Roe", "given": "Jo"}],
+//!     "title": "Things, and more things",
+//!     "issued": {"date-parts": [[2017, 3]]}
+//! }]"#).unwrap();
+//!
+//! let options = TableOptions {
+//!     columns: "id,author,issued,title".split(',').map(|c| c.parse().unwrap()).collect(),
+//!     ..Default::default()
+//! };
+//! assert_eq!(
+//!     items_to_table(&items, &options),
+//!     "id,author,issued,title\ndoe2017,\"Doe, Jane; Roe, Jo\",2017-03,\"Things, and more things\"\n"
+//! );
+//! ```
+//!
+//! Tables are read back with [table_to_items], with headers naming the
+//! columns as above, or mapped to them in the [ImportOptions]. Rows which can't
+//! be read are reported, and the others are kept:
+//!
+//! ```
+//! use citeworks_convert::{
+//!     keys::KeyGenerator,
+//!     table::{table_to_items, ImportOptions},
+//! };
+//!
+//! let mut options = ImportOptions::default();
+//! options.mapping.insert("Authors".into(), "author".parse().unwrap());
+//!
+//! let (items, errors) = table_to_items(
+//!     "Authors,title,type,year\n\
+//!     \"Doe, Jane; Roe, Jo\",Things,book,2017\n\
+//!     Acme,Stuff,book,twenty\n",
+//!     &options,
+//!     &mut KeyGenerator::default(),
+//! ).unwrap();
+//!
+//! assert_eq!(items[0].id, "doe2017");
+//! assert_eq!(items[0].author[1].family.as_deref(), Some("Roe"));
+//! assert_eq!(errors[0].row, 3);
+//! ```
+//!
+//! With the `xlsx` feature, tables can also be written as XLSX workbooks, with
+//! [items_to_xlsx], for spreadsheet programs which don't read CSV files as UTF-8.
+
+use std::{
+	collections::BTreeMap,
+	fmt::{self, Display},
+	str::FromStr,
+};
+
+use citeworks_cff::references::Reference;
+use citeworks_csl::{
+	dates::{Date, DateParts},
+	items::ItemType,
+	names::Name,
+	variables::{variable, VariableKind},
+	Item,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+	csl::references_to_items,
+	keys::{fill_missing_keys, KeyGenerator},
+	render::{author_date, entry, to_plain},
+};
+
+/// A column of a table.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum Column {
+	/// A CSL variable, like `title` or `DOI`, or the `id` or `type` of the item.
+	///
+	/// Names are written as `Family, Given`, separated by semicolons, and
+	/// dates as `YYYY-MM-DD`, with as many parts as are known.
+	Variable(String),
+
+	/// The year the item was issued.
+	Year,
+
+	/// The bibliography entry of the item, as plain text.
+	Entry,
+
+	/// The author-date citation of the item, like `Doe et al. 2017`.
+	Citation,
+}
+
+impl Column {
+	/// The name of the column, as it's parsed and written in the header.
+	pub fn name(&self) -> &str {
+		match self {
+			Self::Variable(name) => name,
+			Self::Year => "year",
+			Self::Entry => "entry",
+			Self::Citation => "citation",
+		}
+	}
+}
+
+impl FromStr for Column {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim() {
+			"" => Err("empty column name".into()),
+			"year" => Ok(Self::Year),
+			"entry" => Ok(Self::Entry),
+			"citation" => Ok(Self::Citation),
+			name @ ("id" | "type") => Ok(Self::Variable(name.into())),
+			name if variable(name).is_some() => Ok(Self::Variable(name.into())),
+			name => Err(format!("unknown column: {name:?}")),
+		}
+	}
+}
+
+/// How cells are separated.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Delimiter {
+	/// Comma-separated values, quoted as needed.
+	Csv,
+
+	/// Tab-separated values, with tabs and newlines in values replaced by spaces.
+	Tsv,
+}
+
+impl FromStr for Delimiter {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"csv" => Ok(Self::Csv),
+			"tsv" => Ok(Self::Tsv),
+			_ => Err(format!("unknown table format: {s:?}")),
+		}
+	}
+}
+
+/// Options for table export.
+#[derive(Debug, Clone)]
+pub struct TableOptions {
+	/// The columns, in order.
+	pub columns: Vec<Column>,
+
+	/// How cells are separated.
+	pub delimiter: Delimiter,
+
+	/// Whether to start with a row of column names.
+	pub header: bool,
+
+	/// Whether to neutralise cells that spreadsheets would run as formulas.
+	///
+	/// See [write_row].
+	pub escape_formulas: bool,
+}
+
+impl Default for TableOptions {
+	fn default() -> Self {
+		Self {
+			columns: [
+				"id",
+				"type",
+				"author",
+				"year",
+				"title",
+				"container-title",
+				"publisher",
+				"DOI",
+				"URL",
+			]
+			.iter()
+			.map(|name| match *name {
+				"year" => Column::Year,
+				name => Column::Variable(name.into()),
+			})
+			.collect(),
+			delimiter: Delimiter::Csv,
+			header: true,
+			escape_formulas: true,
+		}
+	}
+}
+
+/// Export CSL items as a table, one row per item, in the order given.
+pub fn items_to_table(items: &[Item], options: &TableOptions) -> String {
+	let mut out = String::new();
+	if options.header {
+		let names: Vec<&str> = options.columns.iter().map(Column::name).collect();
+		write_row(&mut out, &names, options.delimiter, options.escape_formulas);
+	}
+	for item in items {
+		let json = serde_json::to_value(item).unwrap_or_default();
+		let cells: Vec<String> = options
+			.columns
+			.iter()
+			.map(|column| cell(item, &json, column))
+			.collect();
+		let cells: Vec<&str> = cells.iter().map(String::as_str).collect();
+		write_row(&mut out, &cells, options.delimiter, options.escape_formulas);
+	}
+	out
+}
+
+/// Export CFF references as a table.
+///
+/// Keys are generated as for [references_to_items].
+pub fn references_to_table(references: &[Reference], options: &TableOptions) -> String {
+	items_to_table(&references_to_items(references), options)
+}
+
+fn cell(item: &Item, json: &Value, column: &Column) -> String {
+	match column {
+		Column::Year => item
+			.issued
+			.as_ref()
+			.and_then(Date::to_parts)
+			.map(|(start, _)| start.year.to_string())
+			.unwrap_or_default(),
+		Column::Entry => to_plain(&entry(item)),
+		Column::Citation => author_date(item),
+		Column::Variable(name) => {
+			let value = match json.get(name) {
+				Some(value) => value,
+				None => return String::new(),
+			};
+			match variable(name).map(|variable| variable.kind) {
+				Some(VariableKind::Names) => {
+					let names: Vec<Name> =
+						serde_json::from_value(value.clone()).unwrap_or_default();
+					names.iter().map(full_name).collect::<Vec<_>>().join("; ")
+				}
+				Some(VariableKind::Date) => serde_json::from_value::<Date>(value.clone())
+					.map(|date| date_text(&date))
+					.unwrap_or_default(),
+				_ => match value {
+					Value::String(s) => s.clone(),
+					Value::Null => String::new(),
+					other => other.to_string(),
+				},
+			}
+		}
+	}
+}
+
+/// "Family, Given", with particles, or the literal name.
+fn full_name(name: &Name) -> String {
+	let family = [&name.non_dropping_particle, &name.family]
+		.iter()
+		.filter_map(|part| part.as_deref())
+		.collect::<Vec<_>>()
+		.join(" ");
+	let given = [&name.given, &name.dropping_particle]
+		.iter()
+		.filter_map(|part| part.as_deref())
+		.collect::<Vec<_>>()
+		.join(" ");
+	match (family.is_empty(), given.is_empty()) {
+		(false, false) => format!("{family}, {given}"),
+		(false, true) => family,
+		(true, false) => given,
+		(true, true) => name.literal.clone().unwrap_or_default(),
+	}
+}
+
+fn date_text(date: &Date) -> String {
+	match (date.to_parts(), date) {
+		(Some((start, None)), _) => parts_text(&start),
+		(Some((start, Some(end))), _) => format!("{}/{}", parts_text(&start), parts_text(&end)),
+		(None, Date::Raw { date, .. } | Date::Edtf { date, .. }) => date.clone(),
+		(None, _) => date.meta().literal.clone().unwrap_or_default(),
+	}
+}
+
+fn parts_text(parts: &DateParts) -> String {
+	match (parts.month, parts.day) {
+		(Some(month), Some(day)) => format!("{:04}-{month:02}-{day:02}", parts.year),
+		(Some(month), None) => format!("{:04}-{month:02}", parts.year),
+		_ => format!("{:04}", parts.year),
+	}
+}
+
+/// Write a row of cells to a CSV or TSV table.
+///
+/// With `escape_formulas`, cells starting with `=`, `+`, `-`, `@`, a tab, or a
+/// carriage return are prefixed with a `'`, so spreadsheets show them as text
+/// instead of running them as formulas.
+pub fn write_row(out: &mut String, cells: &[&str], delimiter: Delimiter, escape_formulas: bool) {
+	for (index, cell) in cells.iter().enumerate() {
+		if index > 0 {
+			out.push(match delimiter {
+				Delimiter::Csv => ',',
+				Delimiter::Tsv => '\t',
+			});
+		}
+		let escaped;
+		let cell = if escape_formulas && cell.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+			escaped = format!("'{cell}");
+			&escaped
+		} else {
+			*cell
+		};
+		match delimiter {
+			Delimiter::Csv if cell.contains([',', '"', '\n', '\r']) => {
+				out.push('"');
+				out.push_str(&cell.replace('"', "\"\""));
+				out.push('"');
+			}
+			Delimiter::Csv => out.push_str(cell),
+			Delimiter::Tsv => out.extend(cell.chars().map(|c| match c {
+				'\t' | '\n' | '\r' => ' ',
+				c => c,
+			})),
+		}
+	}
+	out.push('\n');
+}
+
+/// Export CSL items as an XLSX workbook, one row per item, in the order given.
+///
+/// Cells are typed: years and numbers are numbers, full dates are dates, and
+/// DOIs and URLs are links. Spreadsheet programs read this without asking about
+/// the encoding, as they do for CSV files. The delimiter is ignored.
+#[cfg(feature = "xlsx")]
+pub fn items_to_xlsx(items: &[Item], options: &TableOptions) -> Result<Vec<u8>, String> {
+	use rust_xlsxwriter::{Format, Workbook};
+
+	let mut workbook = Workbook::new();
+	let sheet = workbook.add_worksheet();
+	sheet
+		.set_name("References")
+		.map_err(|err| err.to_string())?;
+
+	let mut first = 0;
+	if options.header {
+		let bold = Format::new().set_bold();
+		for (col, column) in options.columns.iter().enumerate() {
+			sheet
+				.write_string_with_format(0, col as u16, column.name(), &bold)
+				.map_err(|err| err.to_string())?;
+		}
+		sheet
+			.set_freeze_panes(1, 0)
+			.map_err(|err| err.to_string())?;
+		first = 1;
+	}
+
+	let date_format = Format::new().set_num_format("yyyy-mm-dd");
+	for (index, item) in items.iter().enumerate() {
+		let json = serde_json::to_value(item).unwrap_or_default();
+		let row = first + index as u32;
+		for (col, column) in options.columns.iter().enumerate() {
+			let text = cell(item, &json, column);
+			if !text.is_empty() {
+				write_xlsx_cell(sheet, (row, col as u16), column, text, &date_format)
+					.map_err(|err| err.to_string())?;
+			}
+		}
+	}
+
+	sheet.autofit();
+	workbook.save_to_buffer().map_err(|err| err.to_string())
+}
+
+/// Export CFF references as an XLSX workbook.
+///
+/// Keys are generated as for [references_to_items].
+#[cfg(feature = "xlsx")]
+pub fn references_to_xlsx(
+	references: &[Reference],
+	options: &TableOptions,
+) -> Result<Vec<u8>, String> {
+	items_to_xlsx(&references_to_items(references), options)
+}
+
+#[cfg(feature = "xlsx")]
+fn write_xlsx_cell(
+	sheet: &mut rust_xlsxwriter::Worksheet,
+	(row, col): (u32, u16),
+	column: &Column,
+	text: String,
+	date_format: &rust_xlsxwriter::Format,
+) -> Result<(), rust_xlsxwriter::XlsxError> {
+	use rust_xlsxwriter::{ExcelDateTime, Url};
+
+	let kind = match column {
+		Column::Year => Some(VariableKind::Number),
+		Column::Variable(name) => variable(name).map(|variable| variable.kind),
+		Column::Entry | Column::Citation => None,
+	};
+	// Links are limited to 2079 characters in Excel.
+	let link = match column.name() {
+		"DOI" => Some(format!("https://doi.org/{text}")),
+		"URL" => Some(text.clone()),
+		_ => None,
+	}
+	.filter(|link| link.len() <= 2079);
+	let number = Some(&text)
+		.filter(|text| plain_number(text))
+		.and_then(|text| text.parse::<f64>().ok());
+	let date = match text.split('-').collect::<Vec<_>>()[..] {
+		[year, month, day] if text.len() == 10 => {
+			match (year.parse(), month.parse(), day.parse()) {
+				(Ok(year), Ok(month), Ok(day)) => ExcelDateTime::from_ymd(year, month, day).ok(),
+				_ => None,
+			}
+		}
+		_ => None,
+	};
+
+	match (kind, link, number, date) {
+		(_, Some(link), ..) => sheet.write_url_with_text(row, col, Url::new(link), text),
+		(Some(VariableKind::Number), _, Some(number), _) => sheet.write_number(row, col, number),
+		(Some(VariableKind::Date), .., Some(date)) => {
+			sheet.write_datetime_with_format(row, col, &date, date_format)
+		}
+		_ => sheet.write_string(row, col, text),
+	}
+	.map(drop)
+}
+
+/// Whether the text is a number as it'd be written in a spreadsheet, so not
+/// ranges, issues with leading zeros like `007`, or exponents.
+#[cfg(feature = "xlsx")]
+fn plain_number(text: &str) -> bool {
+	let (int, frac) = text.split_once('.').unwrap_or((text, "0"));
+	!int.is_empty()
+		&& !frac.is_empty()
+		&& (int == "0" || !int.starts_with('0'))
+		&& int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+}
+
+/// Options for table import.
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+	/// How cells are separated.
+	pub delimiter: Delimiter,
+
+	/// Columns for headers which aren't column names, e.g. `Authors` for the
+	/// `author` variable.
+	///
+	/// Headers which are neither mapped nor column names are ignored, as are
+	/// `entry` and `citation` columns.
+	pub mapping: BTreeMap<String, Column>,
+
+	/// The type of items without one.
+	pub default_type: ItemType,
+}
+
+impl Default for ImportOptions {
+	fn default() -> Self {
+		Self {
+			delimiter: Delimiter::Csv,
+			mapping: BTreeMap::new(),
+			default_type: ItemType::Document,
+		}
+	}
+}
+
+/// A row of a table which couldn't be read.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RowError {
+	/// The number of the row, as in a spreadsheet: the header is row 1.
+	pub row: usize,
+
+	/// What's wrong with it.
+	pub message: String,
+}
+
+impl Display for RowError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "row {}: {}", self.row, self.message)
+	}
+}
+
+/// Import CSL items from a table, with the first row as the header.
+///
+/// Names are read as they're [exported](Column::Variable), `Family, Given`
+/// separated by semicolons, or as literal names without a comma. Dates are
+/// read as `YYYY-MM-DD`, with as many parts as are known, or as ranges of two
+/// such dates separated by a slash, and kept as raw dates otherwise. A `year`
+/// column is the date the item was issued, unless there's an `issued` column
+/// too.
+///
+/// Rows which can't be read are left out, with an error for each; empty rows
+/// are skipped. Items without an `id` are given keys from the generator. Fails
+/// if the table itself can't be read, e.g. if it has an unterminated quote.
+pub fn table_to_items(
+	text: &str,
+	options: &ImportOptions,
+	keys: &mut KeyGenerator,
+) -> Result<(Vec<Item>, Vec<RowError>), String> {
+	let mut rows = parse_rows(text, options.delimiter)?.into_iter();
+	let header = rows.next().ok_or("the table is empty")?;
+	let columns: Vec<Option<Column>> = header
+		.iter()
+		.map(|name| {
+			options
+				.mapping
+				.get(name.trim())
+				.cloned()
+				.or_else(|| name.parse().ok())
+		})
+		.collect();
+
+	let mut items = Vec::new();
+	let mut errors = Vec::new();
+	for (index, cells) in rows.enumerate() {
+		if cells.iter().all(|cell| cell.trim().is_empty()) {
+			continue;
+		}
+		match row_to_item(&columns, &cells, options) {
+			Ok(item) => items.push(item),
+			Err(message) => errors.push(RowError {
+				row: index + 2,
+				message,
+			}),
+		}
+	}
+
+	fill_missing_keys(&mut items, keys);
+	Ok((items, errors))
+}
+
+fn row_to_item(
+	columns: &[Option<Column>],
+	cells: &[String],
+	options: &ImportOptions,
+) -> Result<Item, String> {
+	if let Some(extra) = cells
+		.iter()
+		.skip(columns.len())
+		.find(|cell| !cell.trim().is_empty())
+	{
+		return Err(format!("{extra:?} is past the last column"));
+	}
+
+	let mut object = Map::new();
+	let mut year = None;
+	for (column, cell) in columns.iter().zip(cells) {
+		let cell = cell.trim();
+		if cell.is_empty() {
+			continue;
+		}
+		match column {
+			Some(Column::Variable(name)) => {
+				let value = match variable(name).map(|variable| variable.kind) {
+					Some(VariableKind::Names) => Value::Array(names(cell)),
+					Some(VariableKind::Date) => date(cell),
+					_ => cell.into(),
+				};
+				object.insert(name.clone(), value);
+			}
+			Some(Column::Year) => {
+				year = Some(
+					cell.parse::<i64>()
+						.map_err(|_| format!("year should be a number, got: {cell:?}"))?,
+				);
+			}
+			Some(Column::Entry | Column::Citation) | None => {}
+		}
+	}
+
+	if let Some(year) = year {
+		object
+			.entry("issued")
+			.or_insert_with(|| json!({ "date-parts": [[year]] }));
+	}
+	object.entry("id").or_insert_with(|| "".into());
+	if !object.contains_key("type") {
+		object.insert(
+			"type".into(),
+			serde_json::to_value(&options.default_type).map_err(|err| err.to_string())?,
+		);
+	}
+
+	serde_json::from_value(Value::Object(object)).map_err(|err| err.to_string())
+}
+
+fn names(cell: &str) -> Vec<Value> {
+	cell.split(';')
+		.map(str::trim)
+		.filter(|name| !name.is_empty())
+		.map(|name| match name.split_once(',') {
+			Some((family, given)) => json!({ "family": family.trim(), "given": given.trim() }),
+			None => json!({ "literal": name }),
+		})
+		.collect()
+}
+
+/// A CSL date from `YYYY[-MM[-DD]]`, or a range of two separated by `/`, or
+/// else a raw date.
+pub(crate) fn date(cell: &str) -> Value {
+	let parts = cell
+		.split('/')
+		.map(|part| {
+			let numbers = part
+				.trim()
+				.split('-')
+				.map(|number| number.parse::<i64>().ok().filter(|_| !number.is_empty()))
+				.collect::<Option<Vec<_>>>()?;
+			(1..=3).contains(&numbers.len()).then(|| numbers)
+		})
+		.collect::<Option<Vec<_>>>();
+	match parts {
+		Some(parts) if parts.len() <= 2 => json!({ "date-parts": parts }),
+		_ => json!({ "raw": cell }),
+	}
+}
+
+/// Split a table into rows of cells.
+fn parse_rows(text: &str, delimiter: Delimiter) -> Result<Vec<Vec<String>>, String> {
+	let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+	if delimiter == Delimiter::Tsv {
+		return Ok(text
+			.lines()
+			.map(|line| line.split('\t').map(String::from).collect())
+			.collect());
+	}
+
+	let mut rows = Vec::new();
+	let mut row = Vec::new();
+	let mut cell = String::new();
+	let mut chars = text.chars().peekable();
+	let mut quoted = false;
+	while let Some(c) = chars.next() {
+		match c {
+			'"' if quoted && chars.peek() == Some(&'"') => {
+				chars.next();
+				cell.push('"');
+			}
+			'"' if quoted => quoted = false,
+			'"' if cell.is_empty() => quoted = true,
+			c if quoted => cell.push(c),
+			',' => row.push(std::mem::take(&mut cell)),
+			'\r' if chars.peek() == Some(&'\n') => {}
+			'\n' => {
+				row.push(std::mem::take(&mut cell));
+				rows.push(std::mem::take(&mut row));
+			}
+			c => cell.push(c),
+		}
+	}
+	if quoted {
+		return Err(format!("row {} has an unterminated quote", rows.len() + 1));
+	}
+	if !cell.is_empty() || !row.is_empty() {
+		row.push(cell);
+		rows.push(row);
+	}
+	Ok(rows)
+}
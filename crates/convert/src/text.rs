@@ -0,0 +1,148 @@
+//! Plain text from marked-up metadata.
+//!
+//! Metadata from publishers often has markup in it: Crossref abstracts are
+//! JATS XML fragments, with `<jats:p>` paragraphs and `<jats:italic>` text, and
+//! other sources use HTML. CFF and most other formats expect plain text, so
+//! [plain_text] strips tags, keeping paragraphs apart, and decodes entities.
+//!
+//! ```
+//! use citeworks_convert::text::plain_text;
+//!
+//! assert_eq!(
+//!     plain_text("<jats:title>Abstract</jats:title><jats:p>We study <jats:italic>E. coli</jats:italic> &amp; friends.</jats:p>\n<jats:p>Results.</jats:p>"),
+//!     "We study E. coli & friends.\n\nResults."
+//! );
+//! ```
+
+/// Elements which start or end a paragraph.
+const BLOCKS: &[&str] = &[
+	"p",
+	"div",
+	"sec",
+	"section",
+	"br",
+	"li",
+	"list",
+	"list-item",
+	"title",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"blockquote",
+	"table",
+	"tr",
+];
+
+/// Strip markup from text, keeping paragraphs apart.
+///
+/// Tags are removed, with block elements such as `<p>` separating paragraphs
+/// by a blank line; whitespace within paragraphs is collapsed; and entities are
+/// decoded. A leading "Abstract" heading is removed too, as Crossref abstracts
+/// often start with one. Text without markup is returned with only its
+/// whitespace tidied.
+pub fn plain_text(text: &str) -> String {
+	let mut paragraphs = Vec::new();
+	let mut current = String::new();
+
+	let mut rest = text;
+	while let Some(start) = rest.find('<') {
+		current.push_str(&rest[..start]);
+		let after = &rest[start + 1..];
+		let is_tag = after.chars().next().map_or(false, |c| {
+			c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?')
+		});
+		match after.find('>') {
+			Some(end) if is_tag => {
+				if BLOCKS.contains(&tag_name(&after[..end]).as_str()) {
+					paragraphs.push(std::mem::take(&mut current));
+				}
+				rest = &after[end + 1..];
+			}
+			_ => {
+				current.push('<');
+				rest = after;
+			}
+		}
+	}
+	current.push_str(rest);
+	paragraphs.push(current);
+
+	let mut paragraphs: Vec<String> = paragraphs
+		.iter()
+		.map(|paragraph| {
+			decode_entities(paragraph)
+				.split_whitespace()
+				.collect::<Vec<_>>()
+				.join(" ")
+		})
+		.filter(|paragraph| !paragraph.is_empty())
+		.collect();
+
+	if paragraphs.len() > 1 && paragraphs[0].eq_ignore_ascii_case("abstract") {
+		paragraphs.remove(0);
+	}
+
+	paragraphs.join("\n\n")
+}
+
+/// The lowercase local name of a tag, without namespace prefix.
+fn tag_name(tag: &str) -> String {
+	let name = tag
+		.trim_start_matches('/')
+		.split(|c: char| c.is_whitespace() || c == '/')
+		.next()
+		.unwrap_or_default();
+	name.rsplit(':')
+		.next()
+		.unwrap_or_default()
+		.to_ascii_lowercase()
+}
+
+fn decode_entities(text: &str) -> String {
+	let mut out = String::with_capacity(text.len());
+	let mut rest = text;
+	while let Some(start) = rest.find('&') {
+		out.push_str(&rest[..start]);
+		let after = &rest[start + 1..];
+		let decoded = after
+			.find(';')
+			.filter(|&end| end <= 10)
+			.and_then(|end| Some((entity(&after[..end])?, end)));
+		match decoded {
+			Some((c, end)) => {
+				out.push(c);
+				rest = &after[end + 1..];
+			}
+			None => {
+				out.push('&');
+				rest = after;
+			}
+		}
+	}
+	out.push_str(rest);
+	out
+}
+
+fn entity(name: &str) -> Option<char> {
+	let code = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+		u32::from_str_radix(hex, 16).ok()?
+	} else if let Some(dec) = name.strip_prefix('#') {
+		dec.parse().ok()?
+	} else {
+		return Some(match name {
+			"amp" => '&',
+			"lt" => '<',
+			"gt" => '>',
+			"quot" => '"',
+			"apos" => '\'',
+			"nbsp" => '\u{a0}',
+			"ndash" => '–',
+			"mdash" => '—',
+			_ => return None,
+		});
+	};
+	char::from_u32(code)
+}
@@ -0,0 +1,346 @@
+//! Export to [Wikidata] statements.
+//!
+//! This supports the [WikiCite] workflow: a CFF document is turned into a list
+//! of statements about the software, which can be rendered as [QuickStatements]
+//! commands to create or update its Wikidata item, and then shows up in tools
+//! like [Scholia].
+//!
+//! Wikidata links most values to other items by their QID, which CFF doesn't
+//! carry. Authors are given as name strings (with their ORCID when known), and
+//! licenses are only included when their QIDs are provided in the options. The
+//! `citeworks-fetch` crate can look those up.
+//!
+//! ```
+//! use citeworks_convert::wikidata::{cff_to_quickstatements, WikidataOptions};
+//!
+//! let cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! version: 2.0.4
+//! authors:
+//!   - family-names: Druskat
+//!     given-names: Stephan
+//! "#).unwrap();
+//!
+//! let commands = cff_to_quickstatements(&cff, &WikidataOptions::default());
+//! assert!(commands.starts_with("CREATE\nLAST\tLen\t\"My Research Software\"\n"));
+//! assert!(commands.contains("LAST\tP348\t\"2.0.4\"\n"));
+//! ```
+//!
+//! [QuickStatements]: https://www.wikidata.org/wiki/Help:QuickStatements
+//! [Scholia]: https://scholia.toolforge.org
+//! [WikiCite]: https://meta.wikimedia.org/wiki/WikiCite
+//! [Wikidata]: https://www.wikidata.org
+
+use std::{
+	fmt::{Display, Write},
+	str::FromStr,
+};
+
+use citeworks_cff::{names::Name, Cff, Date, WorkType};
+
+use crate::report::{send, unmapped, IgnoreWarnings, RecordReport, WarningSink};
+
+/// Top-level CFF keys which are converted, or which are metadata about the file.
+const MAPPED_CFF_KEYS: &[&str] = &[
+	"cff-version",
+	"message",
+	"type",
+	"title",
+	"authors",
+	"version",
+	"date-released",
+	"doi",
+	"url",
+	"repository-code",
+	"license",
+];
+
+/// A Wikidata item identifier, e.g. `Q42`.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Qid(pub u64);
+
+impl Qid {
+	/// The concept URI of the item.
+	pub fn uri(self) -> String {
+		format!("http://www.wikidata.org/entity/{self}")
+	}
+}
+
+impl Display for Qid {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Q{}", self.0)
+	}
+}
+
+impl FromStr for Qid {
+	type Err = String;
+
+	/// Parse a QID, either bare (`Q42`) or as a concept or page URL.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let id = s.trim().rsplit('/').next().unwrap_or_default();
+		id.strip_prefix('Q')
+			.and_then(|n| n.parse().ok())
+			.map(Self)
+			.ok_or_else(|| format!("expected a Wikidata QID like Q42, got: {s:?}"))
+	}
+}
+
+/// Wikidata item for software.
+pub const SOFTWARE: Qid = Qid(7397);
+
+/// Wikidata item for datasets.
+pub const DATASET: Qid = Qid(1172284);
+
+/// A value of a Wikidata statement.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Value {
+	/// Another item.
+	Item(Qid),
+
+	/// A plain string, also used for external identifiers and URLs.
+	String(String),
+
+	/// Text in a given language.
+	Monolingual {
+		/// Wikimedia language code.
+		language: String,
+
+		/// The text.
+		text: String,
+	},
+
+	/// A date, with day precision.
+	Time(Date),
+}
+
+impl Display for Value {
+	/// Formats the value in QuickStatements syntax.
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Item(qid) => write!(f, "{qid}"),
+			Self::String(s) => write!(f, "\"{}\"", clean(s)),
+			Self::Monolingual { language, text } => write!(f, "{language}:\"{}\"", clean(text)),
+			Self::Time(date) => write!(f, "+{date}T00:00:00Z/11"),
+		}
+	}
+}
+
+/// QuickStatements is line- and tab-based and has no escapes.
+fn clean(s: &str) -> String {
+	s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A statement about an item: a property, a value, and qualifiers.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Statement {
+	/// The property, e.g. `P348`.
+	pub property: &'static str,
+
+	/// The value.
+	pub value: Value,
+
+	/// Qualifier properties and values.
+	pub qualifiers: Vec<(&'static str, Value)>,
+}
+
+impl Statement {
+	fn new(property: &'static str, value: Value) -> Self {
+		Self {
+			property,
+			value,
+			qualifiers: Vec::new(),
+		}
+	}
+}
+
+/// Options for Wikidata export.
+#[derive(Debug, Clone)]
+pub struct WikidataOptions {
+	/// The existing item to add statements to.
+	///
+	/// If this isn't set, a new item is created.
+	pub item: Option<Qid>,
+
+	/// Wikimedia language code of the title.
+	pub language: String,
+
+	/// QIDs of the licenses of the work.
+	pub licenses: Vec<Qid>,
+}
+
+impl Default for WikidataOptions {
+	fn default() -> Self {
+		Self {
+			item: None,
+			language: "en".into(),
+			licenses: Vec::new(),
+		}
+	}
+}
+
+/// Convert a CFF document to Wikidata statements.
+///
+/// These are:
+/// - P31 (instance of) software or dataset,
+/// - P1476 (title),
+/// - P2093 (author name string), with P1545 (series ordinal) and P496 (ORCID)
+///   qualifiers,
+/// - P348 (software version identifier), with P577 (publication date),
+/// - P577 (publication date),
+/// - P356 (DOI),
+/// - P856 (official website),
+/// - P1324 (source code repository URL),
+/// - P275 (copyright license), from the options.
+pub fn cff_to_statements(cff: &Cff, options: &WikidataOptions) -> Vec<Statement> {
+	cff_to_statements_with(cff, options, &mut IgnoreWarnings)
+}
+
+/// Convert a CFF document to Wikidata statements.
+///
+/// Fields which can't be converted are reported to the sink, under the title
+/// of the document, including the license if its QIDs aren't in the options.
+pub fn cff_to_statements_with(
+	cff: &Cff,
+	options: &WikidataOptions,
+	report: &mut dyn WarningSink,
+) -> Vec<Statement> {
+	let mut record = RecordReport::new(cff.title.clone());
+	unmapped(cff, MAPPED_CFF_KEYS, "no Wikidata equivalent", &mut record);
+	if cff.license.is_some() && options.licenses.is_empty() {
+		record.dropped("license", "no QIDs given for the license");
+	}
+	send(report, record);
+
+	let mut statements = Vec::new();
+
+	let instance = match cff.work_type {
+		Some(WorkType::Dataset) => DATASET,
+		_ => SOFTWARE,
+	};
+	statements.push(Statement::new("P31", Value::Item(instance)));
+
+	statements.push(Statement::new(
+		"P1476",
+		Value::Monolingual {
+			language: options.language.clone(),
+			text: cff.title.clone(),
+		},
+	));
+
+	for (n, author) in cff.authors.iter().enumerate() {
+		let (name, orcid) = match author {
+			Name::Person(person) => {
+				let name = [
+					person.given_names.as_deref(),
+					person.name_particle.as_deref(),
+					person.family_names.as_deref(),
+					person.name_suffix.as_deref(),
+				]
+				.into_iter()
+				.flatten()
+				.collect::<Vec<_>>()
+				.join(" ");
+				(name, person.meta.orcid.as_ref())
+			}
+			Name::Entity(entity) => (entity.name.clone().unwrap_or_default(), None),
+			Name::Anonymous => continue,
+		};
+		if name.is_empty() {
+			continue;
+		}
+
+		let mut statement = Statement::new("P2093", Value::String(name));
+		statement
+			.qualifiers
+			.push(("P1545", Value::String((n + 1).to_string())));
+		if let Some(orcid) = orcid {
+			statement
+				.qualifiers
+				.push(("P496", Value::String(orcid.path().trim_matches('/').into())));
+		}
+		statements.push(statement);
+	}
+
+	if let Some(version) = &cff.version {
+		let mut statement = Statement::new("P348", Value::String(version.clone()));
+		if let Some(date) = cff.date_released {
+			statement.qualifiers.push(("P577", Value::Time(date)));
+		}
+		statements.push(statement);
+	}
+
+	if let Some(date) = cff.date_released {
+		statements.push(Statement::new("P577", Value::Time(date)));
+	}
+
+	if let Some(doi) = &cff.doi {
+		// Wikidata stores DOIs uppercased.
+		statements.push(Statement::new("P356", Value::String(doi.to_uppercase())));
+	}
+
+	if let Some(url) = &cff.url {
+		statements.push(Statement::new("P856", Value::String(url.to_string())));
+	}
+
+	if let Some(url) = &cff.repository_code {
+		statements.push(Statement::new("P1324", Value::String(url.to_string())));
+	}
+
+	for license in &options.licenses {
+		statements.push(Statement::new("P275", Value::Item(*license)));
+	}
+
+	statements
+}
+
+/// Render statements as QuickStatements (V1) commands.
+///
+/// If `item` is `None`, the commands create a new item with the given English
+/// `label`; otherwise the label is ignored and statements are added to `item`.
+pub fn to_quickstatements(item: Option<Qid>, label: &str, statements: &[Statement]) -> String {
+	let mut out = String::new();
+	let subject = match item {
+		Some(qid) => qid.to_string(),
+		None => {
+			out.push_str("CREATE\n");
+			writeln!(out, "LAST\tLen\t\"{}\"", clean(label)).expect("writing to a String");
+			"LAST".into()
+		}
+	};
+
+	for statement in statements {
+		write!(
+			out,
+			"{subject}\t{}\t{}",
+			statement.property, statement.value
+		)
+		.expect("writing to a String");
+		for (property, value) in &statement.qualifiers {
+			write!(out, "\t{property}\t{value}").expect("writing to a String");
+		}
+		out.push('\n');
+	}
+
+	out
+}
+
+/// Convert a CFF document to QuickStatements commands.
+///
+/// See [cff_to_statements] and [to_quickstatements].
+pub fn cff_to_quickstatements(cff: &Cff, options: &WikidataOptions) -> String {
+	cff_to_quickstatements_with(cff, options, &mut IgnoreWarnings)
+}
+
+/// Convert a CFF document to QuickStatements commands.
+///
+/// See [cff_to_statements_with] and [to_quickstatements].
+pub fn cff_to_quickstatements_with(
+	cff: &Cff,
+	options: &WikidataOptions,
+	report: &mut dyn WarningSink,
+) -> String {
+	let statements = cff_to_statements_with(cff, options, report);
+	to_quickstatements(options.item, &cff.title, &statements)
+}
@@ -0,0 +1,240 @@
+//! Citation files for the packages of a workspace.
+//!
+//! Cargo and npm workspaces list their packages as paths, or glob patterns like
+//! `crates/*`. This reads those lists, to find which directories have packages
+//! that should have their own CFF file, and makes the workspace's own CFF file
+//! cite each package as a `software` reference.
+//!
+//! ```
+//! use citeworks_cff::Cff;
+//! use citeworks_convert::workspace::{set_package_references, Workspace};
+//!
+//! let workspace = Workspace::from_cargo(r#"
+//! [workspace]
+//! members = ["crates/*"]
+//! exclude = ["crates/internal"]
+//! "#).unwrap().unwrap();
+//!
+//! assert!(workspace.includes("crates/cff"));
+//! assert!(!workspace.includes("crates/internal"));
+//! assert!(!workspace.includes("docs"));
+//!
+//! let mut root = Cff {
+//!     title: "citeworks".into(),
+//!     ..Default::default()
+//! };
+//! let package = Cff {
+//!     title: "citeworks-cff".into(),
+//!     version: Some("0.1.1".into()),
+//!     ..Default::default()
+//! };
+//! assert!(set_package_references(&mut root, &[package.clone()]));
+//! assert_eq!(root.references[0].title.as_deref(), Some("citeworks-cff"));
+//!
+//! // Already up to date:
+//! assert!(!set_package_references(&mut root, &[package]));
+//! ```
+
+use citeworks_cff::{
+	references::{RefType, Reference},
+	Cff,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Which package manager a workspace is for.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum WorkspaceKind {
+	/// A Cargo workspace, from the `[workspace]` of a `Cargo.toml`.
+	Cargo,
+
+	/// An npm (or Yarn, or pnpm) workspace, from the `workspaces` of a
+	/// `package.json`.
+	Npm,
+}
+
+impl WorkspaceKind {
+	/// File name of the manifest of packages and workspaces of this kind.
+	pub fn manifest(self) -> &'static str {
+		match self {
+			Self::Cargo => "Cargo.toml",
+			Self::Npm => "package.json",
+		}
+	}
+}
+
+/// The packages of a workspace.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Workspace {
+	/// Which package manager it's for.
+	pub kind: WorkspaceKind,
+
+	/// Paths or glob patterns of package directories, relative to the
+	/// workspace, with `/` separators.
+	pub members: Vec<String>,
+
+	/// Paths or glob patterns of directories which aren't packages, even if
+	/// they match a member pattern.
+	pub exclude: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct CargoManifest {
+	workspace: Option<CargoWorkspace>,
+}
+
+#[derive(Deserialize)]
+struct CargoWorkspace {
+	#[serde(default)]
+	members: Vec<String>,
+	#[serde(default)]
+	exclude: Vec<String>,
+}
+
+impl Workspace {
+	/// Read a Cargo workspace from its `Cargo.toml`.
+	///
+	/// Returns `None` if the manifest has no `[workspace]`.
+	pub fn from_cargo(manifest: &str) -> Result<Option<Self>, String> {
+		let manifest: CargoManifest = toml::from_str(manifest).map_err(|err| err.to_string())?;
+		Ok(manifest.workspace.map(|workspace| Self {
+			kind: WorkspaceKind::Cargo,
+			members: workspace.members,
+			exclude: workspace.exclude,
+		}))
+	}
+
+	/// Read an npm workspace from its `package.json`.
+	///
+	/// The `workspaces` may be a list of patterns, or an object with them in
+	/// `packages`, as Yarn has it. Patterns starting with `!` are exclusions.
+	/// Returns `None` if there are no workspaces.
+	pub fn from_npm(package_json: &str) -> Result<Option<Self>, String> {
+		let package: Value = serde_json::from_str(package_json).map_err(|err| err.to_string())?;
+		let patterns = match package.get("workspaces") {
+			Some(Value::Array(patterns)) => patterns,
+			Some(Value::Object(workspaces)) => match workspaces.get("packages") {
+				Some(Value::Array(patterns)) => patterns,
+				_ => return Ok(None),
+			},
+			_ => return Ok(None),
+		};
+
+		let mut workspace = Self {
+			kind: WorkspaceKind::Npm,
+			members: Vec::new(),
+			exclude: Vec::new(),
+		};
+		for pattern in patterns.iter().filter_map(Value::as_str) {
+			match pattern.strip_prefix('!') {
+				Some(excluded) => workspace.exclude.push(excluded.into()),
+				None => workspace.members.push(pattern.into()),
+			}
+		}
+		Ok(Some(workspace))
+	}
+
+	/// Whether a directory, relative to the workspace, is one of its packages.
+	///
+	/// This only looks at the path: the directory should also have a manifest.
+	pub fn includes(&self, path: &str) -> bool {
+		let matches = |pattern: &String| glob_match(pattern, path);
+		self.members.iter().any(matches) && !self.exclude.iter().any(matches)
+	}
+}
+
+/// Whether a path matches a glob pattern, both with `/` separators.
+///
+/// In segments, `*` matches any characters and `?` any one character, and a
+/// `**` segment matches any number of segments. Leading `./` and trailing `/`
+/// are ignored.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+	fn segments(path: &str) -> Vec<&str> {
+		path.split('/')
+			.filter(|segment| !segment.is_empty() && *segment != ".")
+			.collect()
+	}
+
+	fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+		match (pattern.split_first(), path.split_first()) {
+			(None, None) => true,
+			(Some((&"**", rest)), _) => {
+				match_segments(rest, path)
+					|| (!path.is_empty() && match_segments(pattern, &path[1..]))
+			}
+			(Some((first, rest)), Some((segment, path))) => {
+				match_segment(first.as_bytes(), segment.as_bytes()) && match_segments(rest, path)
+			}
+			_ => false,
+		}
+	}
+
+	fn match_segment(pattern: &[u8], name: &[u8]) -> bool {
+		match (pattern.split_first(), name.split_first()) {
+			(None, None) => true,
+			(Some((b'*', rest)), _) => {
+				match_segment(rest, name)
+					|| (!name.is_empty() && match_segment(pattern, &name[1..]))
+			}
+			(Some((b'?', rest)), Some((_, name))) => match_segment(rest, name),
+			(Some((p, rest)), Some((n, name))) => p == n && match_segment(rest, name),
+			_ => false,
+		}
+	}
+
+	match_segments(&segments(pattern), &segments(path))
+}
+
+/// A `software` reference to a package, from its CFF document.
+///
+/// This has the package's title, authors, version, release date, abstract,
+/// keywords, license, DOI, identifiers, URL, and repositories.
+pub fn package_reference(cff: &Cff) -> Reference {
+	Reference {
+		work_type: RefType::Software,
+		title: Some(cff.title.clone()),
+		authors: cff.authors.clone(),
+		version: cff.version.clone(),
+		date_released: cff.date_released,
+		abstract_text: cff.abstract_text.clone(),
+		keywords: cff.keywords.clone(),
+		license: cff.license.clone(),
+		license_url: cff.license_url.clone(),
+		doi: cff.doi.clone(),
+		identifiers: cff.identifiers.clone(),
+		url: cff.url.clone(),
+		repository: cff.repository.clone(),
+		repository_artifact: cff.repository_artifact.clone(),
+		repository_code: cff.repository_code.clone(),
+		..Default::default()
+	}
+}
+
+/// Make a document reference each package, see [package_reference].
+///
+/// A `software` reference with the same title as a package is replaced, and
+/// packages without one are added at the end. Other references, including
+/// ones to packages which aren't in the list anymore, are kept. Returns
+/// whether the references changed.
+pub fn set_package_references(cff: &mut Cff, packages: &[Cff]) -> bool {
+	let mut changed = false;
+	for package in packages {
+		let reference = package_reference(package);
+		let existing = cff.references.iter_mut().find(|existing| {
+			existing.work_type == RefType::Software && existing.title == reference.title
+		});
+
+		match existing {
+			Some(existing) if *existing == reference => {}
+			Some(existing) => {
+				*existing = reference;
+				changed = true;
+			}
+			None => {
+				cff.references.push(reference);
+				changed = true;
+			}
+		}
+	}
+	changed
+}
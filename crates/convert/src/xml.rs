@@ -0,0 +1,437 @@
+//! Minimal XML reading and writing, enough for the metadata formats in this crate.
+
+use std::fmt::Write;
+
+/// Escape text for use in XML content or attribute values.
+pub fn escape(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	for c in s.chars() {
+		match c {
+			'&' => out.push_str("&amp;"),
+			'<' => out.push_str("&lt;"),
+			'>' => out.push_str("&gt;"),
+			'"' => out.push_str("&quot;"),
+			'\'' => out.push_str("&apos;"),
+			c => out.push(c),
+		}
+	}
+	out
+}
+
+/// Writes indented XML elements to a string.
+#[derive(Debug, Default)]
+pub(crate) struct XmlWriter {
+	out: String,
+	stack: Vec<&'static str>,
+}
+
+impl XmlWriter {
+	/// Start with an XML declaration.
+	pub(crate) fn with_declaration() -> Self {
+		Self {
+			out: String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"),
+			stack: Vec::new(),
+		}
+	}
+
+	fn indent(&mut self) {
+		for _ in 0..self.stack.len() {
+			self.out.push_str("  ");
+		}
+	}
+
+	fn tag(&mut self, name: &str, attrs: &[(&str, &str)]) {
+		self.out.push('<');
+		self.out.push_str(name);
+		for (key, value) in attrs {
+			write!(self.out, " {key}=\"{}\"", escape(value)).expect("writing to a String");
+		}
+	}
+
+	/// Open an element, which must later be closed with [XmlWriter::close].
+	pub(crate) fn open(&mut self, name: &'static str, attrs: &[(&str, &str)]) {
+		self.indent();
+		self.tag(name, attrs);
+		self.out.push_str(">\n");
+		self.stack.push(name);
+	}
+
+	/// Close the most recently opened element.
+	pub(crate) fn close(&mut self) {
+		let name = self.stack.pop().expect("closed more elements than opened");
+		self.indent();
+		writeln!(self.out, "</{name}>").expect("writing to a String");
+	}
+
+	/// Write an element containing only text.
+	pub(crate) fn leaf(&mut self, name: &str, attrs: &[(&str, &str)], text: &str) {
+		self.indent();
+		self.tag(name, attrs);
+		writeln!(self.out, ">{}</{name}>", escape(text)).expect("writing to a String");
+	}
+
+	/// Write an element containing only text, if there is text.
+	pub(crate) fn leaf_opt(&mut self, name: &str, attrs: &[(&str, &str)], text: Option<&str>) {
+		if let Some(text) = text {
+			self.leaf(name, attrs, text);
+		}
+	}
+
+	pub(crate) fn finish(self) -> String {
+		assert!(self.stack.is_empty(), "unclosed elements: {:?}", self.stack);
+		self.out
+	}
+}
+
+/// An element of an XML document, with namespaces resolved.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub(crate) struct Element {
+	/// Namespace URI of the element, if it's in one
+	pub(crate) namespace: Option<String>,
+
+	/// Local name of the element, without its prefix
+	pub(crate) name: String,
+
+	/// Attributes, as namespace URI, local name, and value
+	pub(crate) attributes: Vec<(Option<String>, String, String)>,
+
+	pub(crate) children: Vec<Node>,
+}
+
+/// Content of an element.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum Node {
+	Element(Element),
+	Text(String),
+}
+
+impl Element {
+	/// Whether this is the element with that name in that namespace.
+	pub(crate) fn is(&self, namespace: &str, name: &str) -> bool {
+		self.name == name && self.namespace.as_deref() == Some(namespace)
+	}
+
+	/// The value of an attribute in a namespace.
+	pub(crate) fn attribute(&self, namespace: &str, name: &str) -> Option<&str> {
+		self.attributes
+			.iter()
+			.find(|(ns, local, _)| local == name && ns.as_deref() == Some(namespace))
+			.map(|(_, _, value)| value.as_str())
+	}
+
+	/// The child elements.
+	pub(crate) fn elements(&self) -> impl Iterator<Item = &Element> {
+		self.children.iter().filter_map(|child| match child {
+			Node::Element(element) => Some(element),
+			Node::Text(_) => None,
+		})
+	}
+
+	/// The first child element with that name in that namespace.
+	pub(crate) fn child(&self, namespace: &str, name: &str) -> Option<&Element> {
+		self.elements().find(|child| child.is(namespace, name))
+	}
+
+	/// All the text within the element, trimmed.
+	pub(crate) fn text(&self) -> String {
+		fn collect(element: &Element, out: &mut String) {
+			for child in &element.children {
+				match child {
+					Node::Element(element) => collect(element, out),
+					Node::Text(text) => out.push_str(text),
+				}
+			}
+		}
+
+		let mut out = String::new();
+		collect(self, &mut out);
+		out.trim().into()
+	}
+}
+
+/// Namespace of the `xml:` prefix, which is always declared.
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// How deeply elements can be nested, as they're read recursively.
+const MAX_DEPTH: usize = 256;
+
+/// Parse an XML document into its root element.
+///
+/// This reads elements, attributes, text, CDATA sections, and character and
+/// predefined entity references, and skips comments, processing instructions,
+/// and the document type declaration, whose entities aren't supported.
+/// Documents with elements nested more than `MAX_DEPTH` deep are refused.
+pub(crate) fn parse(text: &str) -> Result<Element, String> {
+	let mut reader = Reader {
+		text: text.strip_prefix('\u{feff}').unwrap_or(text),
+		pos: 0,
+		scopes: Vec::new(),
+	};
+	reader.skip_misc()?;
+	if !reader.rest().starts_with('<') {
+		return Err(reader.error("expected an element"));
+	}
+	let root = reader.element()?;
+	reader.skip_misc()?;
+	if !reader.rest().is_empty() {
+		return Err(reader.error("content after the root element"));
+	}
+	Ok(root)
+}
+
+struct Reader<'t> {
+	text: &'t str,
+	pos: usize,
+
+	/// Namespace prefixes declared by the open elements, innermost last
+	scopes: Vec<Vec<(String, String)>>,
+}
+
+impl<'t> Reader<'t> {
+	fn rest(&self) -> &'t str {
+		&self.text[self.pos..]
+	}
+
+	fn error(&self, message: &str) -> String {
+		let line = self.text[..self.pos].matches('\n').count() + 1;
+		format!("{message} at line {line}")
+	}
+
+	/// Move past `end`, returning the text before it.
+	fn until(&mut self, end: &str) -> Result<&'t str, String> {
+		let rest = self.rest();
+		match rest.find(end) {
+			Some(at) => {
+				self.pos += at + end.len();
+				Ok(&rest[..at])
+			}
+			None => Err(self.error(&format!("expected {end:?}"))),
+		}
+	}
+
+	fn skip_whitespace(&mut self) {
+		let rest = self.rest();
+		self.pos += rest.len() - rest.trim_start().len();
+	}
+
+	/// Skip whitespace, comments, processing instructions, and doctypes.
+	fn skip_misc(&mut self) -> Result<(), String> {
+		loop {
+			self.skip_whitespace();
+			let rest = self.rest();
+			if rest.starts_with("<!--") {
+				self.until("-->")?;
+			} else if rest.starts_with("<?") {
+				self.until("?>")?;
+			} else if rest.starts_with("<!DOCTYPE") {
+				self.skip_doctype()?;
+			} else {
+				return Ok(());
+			}
+		}
+	}
+
+	/// Skip a document type declaration, with its internal subset if any.
+	///
+	/// The subset's declarations may contain `>`, in quoted values and
+	/// comments, and end at the `]` before the declaration's own `>`.
+	fn skip_doctype(&mut self) -> Result<(), String> {
+		self.pos += "<!DOCTYPE".len();
+		let mut in_subset = false;
+		loop {
+			let rest = self.rest();
+			match rest.chars().next() {
+				None => return Err(self.error("unclosed document type declaration")),
+				Some('>') if !in_subset => {
+					self.pos += 1;
+					return Ok(());
+				}
+				Some('[') if !in_subset => in_subset = true,
+				Some(']') if in_subset => in_subset = false,
+				Some(quote @ ('"' | '\'')) => {
+					self.pos += 1;
+					self.until(&quote.to_string())?;
+					continue;
+				}
+				Some('<') if rest.starts_with("<!--") => {
+					self.until("-->")?;
+					continue;
+				}
+				Some('<') if rest.starts_with("<?") => {
+					self.until("?>")?;
+					continue;
+				}
+				Some(_) => {}
+			}
+			self.pos += rest.chars().next().map_or(1, char::len_utf8);
+		}
+	}
+
+	fn name(&mut self) -> Result<String, String> {
+		let rest = self.rest();
+		let len = rest
+			.find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+			.unwrap_or(rest.len());
+		if len == 0 {
+			return Err(self.error("expected a name"));
+		}
+		self.pos += len;
+		Ok(rest[..len].into())
+	}
+
+	fn element(&mut self) -> Result<Element, String> {
+		if self.scopes.len() >= MAX_DEPTH {
+			return Err(self.error("elements nested too deeply"));
+		}
+		self.pos += 1;
+		let qname = self.name()?;
+
+		let mut raw_attributes = Vec::new();
+		let empty = loop {
+			self.skip_whitespace();
+			let rest = self.rest();
+			if rest.starts_with("/>") {
+				self.pos += 2;
+				break true;
+			} else if rest.starts_with('>') {
+				self.pos += 1;
+				break false;
+			}
+
+			let name = self.name()?;
+			self.skip_whitespace();
+			if !self.rest().starts_with('=') {
+				return Err(self.error(&format!("expected a value for {name}")));
+			}
+			self.pos += 1;
+			self.skip_whitespace();
+			let quote = match self.rest().chars().next() {
+				Some(quote @ ('"' | '\'')) => quote,
+				_ => return Err(self.error(&format!("expected a quoted value for {name}"))),
+			};
+			self.pos += 1;
+			let value = self.until(&quote.to_string())?.to_string();
+			raw_attributes.push((name, self.unescape(&value)?));
+		};
+
+		self.scopes.push(
+			raw_attributes
+				.iter()
+				.filter_map(|(name, value)| match name.as_str() {
+					"xmlns" => Some((String::new(), value.clone())),
+					name => name
+						.strip_prefix("xmlns:")
+						.map(|prefix| (prefix.to_string(), value.clone())),
+				})
+				.collect(),
+		);
+
+		let (namespace, name) = self.resolve(&qname, true)?;
+		let mut element = Element {
+			namespace,
+			name,
+			..Default::default()
+		};
+		for (qname, value) in raw_attributes {
+			if qname != "xmlns" && !qname.starts_with("xmlns:") {
+				let (namespace, name) = self.resolve(&qname, false)?;
+				element.attributes.push((namespace, name, value));
+			}
+		}
+
+		if !empty {
+			self.content(&qname, &mut element.children)?;
+		}
+		self.scopes.pop();
+		Ok(element)
+	}
+
+	fn content(&mut self, qname: &str, children: &mut Vec<Node>) -> Result<(), String> {
+		loop {
+			let rest = self.rest();
+			if rest.is_empty() {
+				return Err(self.error(&format!("unclosed element {qname}")));
+			} else if let Some(closing) = rest.strip_prefix("</") {
+				let len = closing.find('>').unwrap_or(closing.len());
+				if closing[..len].trim_end() != qname {
+					return Err(self.error(&format!("expected the end of {qname}")));
+				}
+				self.pos += 2 + len + 1;
+				return Ok(());
+			} else if rest.starts_with("<![CDATA[") {
+				self.pos += "<![CDATA[".len();
+				let text = self.until("]]>")?.to_string();
+				children.push(Node::Text(text));
+			} else if rest.starts_with("<!--") {
+				self.until("-->")?;
+			} else if rest.starts_with("<?") {
+				self.until("?>")?;
+			} else if rest.starts_with('<') {
+				let element = self.element()?;
+				children.push(Node::Element(element));
+			} else {
+				let len = rest.find('<').unwrap_or(rest.len());
+				let text = self.unescape(&rest[..len])?;
+				self.pos += len;
+				children.push(Node::Text(text));
+			}
+		}
+	}
+
+	/// Namespace and local name of a qualified name. Unprefixed attributes
+	/// aren't in the default namespace.
+	fn resolve(&self, qname: &str, element: bool) -> Result<(Option<String>, String), String> {
+		let (prefix, local) = match qname.split_once(':') {
+			Some((prefix, local)) => (prefix, local),
+			None if !element => return Ok((None, qname.into())),
+			None => ("", qname),
+		};
+		if prefix == "xml" {
+			return Ok((Some(XML_NAMESPACE.into()), local.into()));
+		}
+
+		let namespace = self
+			.scopes
+			.iter()
+			.rev()
+			.flatten()
+			.find(|(declared, _)| declared == prefix)
+			.map(|(_, namespace)| namespace.clone());
+		match namespace {
+			Some(namespace) if namespace.is_empty() => Ok((None, local.into())),
+			Some(namespace) => Ok((Some(namespace), local.into())),
+			None if prefix.is_empty() => Ok((None, local.into())),
+			None => Err(self.error(&format!("undeclared namespace prefix {prefix:?}"))),
+		}
+	}
+
+	fn unescape(&self, text: &str) -> Result<String, String> {
+		let mut out = String::with_capacity(text.len());
+		let mut rest = text;
+		while let Some(at) = rest.find('&') {
+			out.push_str(&rest[..at]);
+			rest = &rest[at + 1..];
+			let end = rest
+				.find(';')
+				.ok_or_else(|| self.error("unterminated entity reference"))?;
+			let entity = &rest[..end];
+			let c = match entity {
+				"lt" => Some('<'),
+				"gt" => Some('>'),
+				"amp" => Some('&'),
+				"quot" => Some('"'),
+				"apos" => Some('\''),
+				_ => entity
+					.strip_prefix("#x")
+					.map(|hex| u32::from_str_radix(hex, 16))
+					.or_else(|| entity.strip_prefix('#').map(str::parse))
+					.and_then(Result::ok)
+					.and_then(char::from_u32),
+			};
+			out.push(c.ok_or_else(|| self.error(&format!("unknown entity &{entity};")))?);
+			rest = &rest[end + 1..];
+		}
+		out.push_str(rest);
+		Ok(out)
+	}
+}
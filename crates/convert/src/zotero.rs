@@ -0,0 +1,352 @@
+//! Import of Zotero RDF exports as CSL items.
+//!
+//! Zotero can export libraries as RDF/XML, which is sometimes the only backup
+//! left of a library. Items are read with their stock fields: creators, titles,
+//! dates, the journal, book, or series they're part of, the publisher, pages
+//! and numbers, identifiers, tags, and the extra field, as the `note`.
+//! Attachments and notes are skipped.
+//!
+//! ```
+//! use citeworks_convert::{keys::KeyGenerator, zotero::rdf_to_items};
+//! use citeworks_csl::items::ItemType;
+//!
+//! let items = rdf_to_items(r#"<rdf:RDF
+//!     xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+//!     xmlns:z="http://www.zotero.org/namespaces/export#"
+//!     xmlns:dc="http://purl.org/dc/elements/1.1/"
+//!     xmlns:dcterms="http://purl.org/dc/terms/"
+//!     xmlns:bib="http://purl.org/net/biblio#"
+//!     xmlns:foaf="http://xmlns.com/foaf/0.1/"
+//!     xmlns:prism="http://prismstandard.org/namespaces/1.2/basic/">
+//!   <bib:Article rdf:about="https://doi.org/10.21105/joss.00001">
+//!     <z:itemType>journalArticle</z:itemType>
+//!     <dcterms:isPartOf rdf:resource="urn:issn:2475-9066"/>
+//!     <bib:authors><rdf:Seq><rdf:li><foaf:Person>
+//!       <foaf:surname>Druskat</foaf:surname>
+//!       <foaf:givenName>Stephan</foaf:givenName>
+//!     </foaf:Person></rdf:li></rdf:Seq></bib:authors>
+//!     <dc:title>Citation File Format</dc:title>
+//!     <dc:date>2017-12-18</dc:date>
+//!     <dc:identifier>DOI 10.21105/joss.00001</dc:identifier>
+//!   </bib:Article>
+//!   <bib:Journal rdf:about="urn:issn:2475-9066">
+//!     <dc:title>Journal of Open Source Software</dc:title>
+//!     <prism:volume>2</prism:volume>
+//!   </bib:Journal>
+//! </rdf:RDF>"#, &mut KeyGenerator::default()).unwrap();
+//!
+//! assert_eq!(items[0].id, "druskat2017");
+//! assert_eq!(items[0].item_type, ItemType::ArticleJournal);
+//! assert_eq!(items[0].doi.as_ref().unwrap().to_string(), "10.21105/joss.00001");
+//! assert_eq!(items[0].volume.as_ref().unwrap().to_string(), "2");
+//! ```
+
+use std::collections::HashMap;
+
+use citeworks_csl::{items::ItemType, Item};
+use serde_json::{json, Map, Value};
+
+use crate::{
+	csl::bare_doi,
+	keys::{fill_missing_keys, KeyGenerator},
+	table::date,
+	xml::{self, Element},
+};
+
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const ZOTERO: &str = "http://www.zotero.org/namespaces/export#";
+const DC: &str = "http://purl.org/dc/elements/1.1/";
+const DCTERMS: &str = "http://purl.org/dc/terms/";
+const BIB: &str = "http://purl.org/net/biblio#";
+const FOAF: &str = "http://xmlns.com/foaf/0.1/";
+const PRISM: &str = "http://prismstandard.org/namespaces/1.2/basic/";
+const VCARD: &str = "http://nwalsh.com/rdf/vCard#";
+
+/// Zotero item types and their CSL types.
+const TYPES: &[(&str, ItemType)] = &[
+	("artwork", ItemType::Graphic),
+	("audioRecording", ItemType::Song),
+	("bill", ItemType::Bill),
+	("blogPost", ItemType::PostWeblog),
+	("book", ItemType::Book),
+	("bookSection", ItemType::Chapter),
+	("case", ItemType::LegalCase),
+	("computerProgram", ItemType::Software),
+	("conferencePaper", ItemType::PaperConference),
+	("dataset", ItemType::Dataset),
+	("dictionaryEntry", ItemType::EntryDictionary),
+	("document", ItemType::Document),
+	("email", ItemType::PersonalCommunication),
+	("encyclopediaArticle", ItemType::EntryEncyclopedia),
+	("film", ItemType::MotionPicture),
+	("forumPost", ItemType::Post),
+	("hearing", ItemType::Hearing),
+	("instantMessage", ItemType::PersonalCommunication),
+	("interview", ItemType::Interview),
+	("journalArticle", ItemType::ArticleJournal),
+	("letter", ItemType::PersonalCommunication),
+	("magazineArticle", ItemType::ArticleMagazine),
+	("manuscript", ItemType::Manuscript),
+	("map", ItemType::Map),
+	("newspaperArticle", ItemType::ArticleNewspaper),
+	("patent", ItemType::Patent),
+	("podcast", ItemType::Song),
+	("preprint", ItemType::Article),
+	("presentation", ItemType::Speech),
+	("radioBroadcast", ItemType::Broadcast),
+	("report", ItemType::Report),
+	("standard", ItemType::Standard),
+	("statute", ItemType::Legislation),
+	("thesis", ItemType::Thesis),
+	("tvBroadcast", ItemType::Broadcast),
+	("videoRecording", ItemType::MotionPicture),
+	("webpage", ItemType::Webpage),
+];
+
+/// Elements of creators and their CSL variables.
+///
+/// Zotero's primary creator types of most item types, like programmers or
+/// artists, are authors in CSL.
+const CREATORS: &[(&str, &str, &str)] = &[
+	(BIB, "authors", "author"),
+	(BIB, "editors", "editor"),
+	(ZOTERO, "artists", "author"),
+	(ZOTERO, "bookAuthors", "container-author"),
+	(ZOTERO, "cartographers", "author"),
+	(ZOTERO, "castMembers", "performer"),
+	(ZOTERO, "composers", "composer"),
+	(ZOTERO, "contributors", "contributor"),
+	(ZOTERO, "directors", "director"),
+	(ZOTERO, "guests", "guest"),
+	(ZOTERO, "interviewers", "interviewer"),
+	(ZOTERO, "inventors", "author"),
+	(ZOTERO, "performers", "performer"),
+	(ZOTERO, "podcasters", "author"),
+	(ZOTERO, "presenters", "author"),
+	(ZOTERO, "producers", "producer"),
+	(ZOTERO, "programmers", "author"),
+	(ZOTERO, "recipients", "recipient"),
+	(ZOTERO, "reviewedAuthors", "reviewed-author"),
+	(ZOTERO, "seriesEditors", "collection-editor"),
+	(ZOTERO, "sponsors", "author"),
+	(ZOTERO, "translators", "translator"),
+	(ZOTERO, "wordsBy", "author"),
+];
+
+/// Elements with text and their CSL variables, on items and what they're part of.
+const FIELDS: &[(&str, &str, &str)] = &[
+	(BIB, "pages", "page"),
+	(DC, "coverage", "archive_location"),
+	(DC, "description", "note"),
+	(DC, "rights", "license"),
+	(DCTERMS, "abstract", "abstract"),
+	(PRISM, "edition", "edition"),
+	(PRISM, "section", "section"),
+	(PRISM, "volume", "volume"),
+	(ZOTERO, "archive", "archive"),
+	(ZOTERO, "language", "language"),
+	(ZOTERO, "libraryCatalog", "source"),
+	(ZOTERO, "medium", "medium"),
+	(ZOTERO, "numPages", "number-of-pages"),
+	(ZOTERO, "numberOfVolumes", "number-of-volumes"),
+	(ZOTERO, "shortTitle", "title-short"),
+	(ZOTERO, "type", "genre"),
+	(ZOTERO, "version", "version"),
+];
+
+/// What an element is to the item being read.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Part {
+	/// The item itself
+	Item,
+
+	/// The journal, book, or other container it's in
+	Container,
+
+	/// The series it's in
+	Series,
+}
+
+/// Read the items of a Zotero RDF export, generating keys for them.
+///
+/// Errors are for documents which aren't XML, or aren't RDF.
+pub fn rdf_to_items(text: &str, keys: &mut KeyGenerator) -> Result<Vec<Item>, String> {
+	let root = xml::parse(text)?;
+	if !root.is(RDF, "RDF") {
+		return Err(format!(
+			"expected an RDF document, found a {} element",
+			root.name
+		));
+	}
+
+	let resources: HashMap<&str, &Element> = root
+		.elements()
+		.filter_map(|element| Some((element.attribute(RDF, "about")?, element)))
+		.collect();
+
+	let mut items = Vec::new();
+	for element in root.elements() {
+		let zotero_type = match element.child(ZOTERO, "itemType") {
+			Some(item_type) => item_type.text(),
+			None => continue,
+		};
+		if matches!(zotero_type.as_str(), "attachment" | "note") {
+			continue;
+		}
+
+		let mut object = Map::new();
+		object.insert("id".into(), "".into());
+		let item_type = TYPES
+			.iter()
+			.find(|(name, _)| *name == zotero_type)
+			.map_or(ItemType::Document, |(_, item_type)| item_type.clone());
+		object.insert(
+			"type".into(),
+			serde_json::to_value(&item_type).map_err(|err| err.to_string())?,
+		);
+
+		read(element, Part::Item, &mut object);
+		for part_of in element
+			.elements()
+			.filter(|child| child.is(DCTERMS, "isPartOf"))
+		{
+			let container = part_of.elements().next().or_else(|| {
+				part_of
+					.attribute(RDF, "resource")
+					.and_then(|resource| resources.get(resource).copied())
+			});
+			if let Some(container) = container {
+				let part = if container.is(BIB, "Series") {
+					Part::Series
+				} else {
+					Part::Container
+				};
+				read(container, part, &mut object);
+			}
+		}
+
+		items.push(serde_json::from_value(Value::Object(object)).map_err(|err| err.to_string())?);
+	}
+
+	fill_missing_keys(&mut items, keys);
+	Ok(items)
+}
+
+/// Read the fields of an element, keeping those already read.
+fn read(element: &Element, part: Part, object: &mut Map<String, Value>) {
+	let mut set = |name: &str, value: Value| {
+		object.entry(name).or_insert(value);
+	};
+
+	let mut keywords = Vec::new();
+	for child in element.elements() {
+		let text = child.text();
+		let namespace = child.namespace.as_deref().unwrap_or_default();
+		let name = child.name.as_str();
+
+		if let Some((.., variable)) = CREATORS
+			.iter()
+			.find(|(ns, element, _)| *ns == namespace && *element == name)
+		{
+			let names = creators(child);
+			if !names.is_empty() {
+				set(variable, Value::Array(names));
+			}
+			continue;
+		}
+		if let Some((.., variable)) = FIELDS
+			.iter()
+			.find(|(ns, element, _)| *ns == namespace && *element == name)
+		{
+			if !text.is_empty() {
+				set(variable, text.into());
+			}
+			continue;
+		}
+
+		match (namespace, name, part) {
+			(_, _, _) if text.is_empty() && child.elements().next().is_none() => {}
+			(DC, "title", Part::Item) => set("title", text.into()),
+			(DC, "title", Part::Container) => set("container-title", text.into()),
+			(DC, "title", Part::Series) => set("collection-title", text.into()),
+			(DCTERMS, "alternative", Part::Container) => set("container-title-short", text.into()),
+			(DC, "date", Part::Item | Part::Container) => set("issued", date(&text)),
+			(DCTERMS, "dateSubmitted", _) => {
+				// Zotero writes access dates with a time, like `2020-01-31 10:00:00`.
+				let day = text.split_whitespace().next().unwrap_or_default();
+				set("accessed", date(day));
+			}
+			(PRISM, "number", Part::Container) => set("issue", text.into()),
+			(PRISM, "number", Part::Item) => set("number", text.into()),
+			(DC, "identifier", Part::Series) => set("collection-number", text.into()),
+			(DC, "identifier", _) => {
+				if let Some(url) = child.child(DCTERMS, "URI") {
+					set("URL", url.text().into());
+				} else if let Some((scheme, id)) = text.split_once(' ') {
+					match scheme {
+						"DOI" => set("DOI", bare_doi(id).into()),
+						"ISBN" => set("ISBN", id.trim().into()),
+						"ISSN" => set("ISSN", id.trim().into()),
+						_ => {}
+					}
+				}
+			}
+			(DC, "publisher", _) => {
+				let organization = child.elements().next();
+				if let Some(name) = organization.and_then(|org| org.child(FOAF, "name")) {
+					set("publisher", name.text().into());
+				} else if organization.is_none() {
+					set("publisher", text.into());
+				}
+				if let Some(place) = organization
+					.and_then(|org| org.child(VCARD, "adr"))
+					.and_then(|adr| adr.elements().next())
+					.and_then(|address| address.child(VCARD, "locality"))
+				{
+					set("publisher-place", place.text().into());
+				}
+			}
+			(BIB, "presentedAt", _) => {
+				if let Some(title) = child
+					.elements()
+					.next()
+					.and_then(|event| event.child(DC, "title"))
+				{
+					set("event-title", title.text().into());
+				}
+			}
+			(ZOTERO, "meetingName", _) => set("event-title", text.into()),
+			(DC, "subject", _) => match child.elements().next() {
+				Some(call) if call.namespace.as_deref() == Some(DCTERMS) => {
+					set("call-number", call.text().into())
+				}
+				_ => keywords.push(text),
+			},
+			_ => {}
+		}
+	}
+
+	if !keywords.is_empty() {
+		set("keyword", keywords.join(", ").into());
+	}
+}
+
+/// The names in a creators element, from its `rdf:Seq`.
+fn creators(element: &Element) -> Vec<Value> {
+	element
+		.elements()
+		.flat_map(Element::elements)
+		.filter(|li| li.is(RDF, "li"))
+		.filter_map(|li| li.elements().next())
+		.map(|person| {
+			let part = |name: &str| person.child(FOAF, name).map(Element::text);
+			let family = part("surname").or_else(|| part("familyName"));
+			let given = part("givenName").or_else(|| part("givenname"));
+			match (family, given) {
+				(Some(family), Some(given)) => json!({ "family": family, "given": given }),
+				(Some(name), None) | (None, Some(name)) => json!({ "literal": name }),
+				(None, None) => json!({ "literal": person.text() }),
+			}
+		})
+		.collect()
+}
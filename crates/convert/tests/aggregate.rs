@@ -0,0 +1,78 @@
+use citeworks_cff::{from_str, Cff};
+use citeworks_convert::{
+	aggregate::{aggregate_citations, aggregate_citations_with},
+	keys::KeyGenerator,
+	report::ConversionReport,
+};
+use citeworks_csl::ordinaries::OrdinaryValue;
+
+use pretty_assertions::assert_eq;
+
+fn cff(title: &str, rest: &str) -> Cff {
+	from_str(&format!(
+		"cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: {title}
+date-released: 2021-03-04
+authors:
+  - family-names: Doe
+    given-names: Jane
+{rest}"
+	))
+	.unwrap()
+}
+
+#[test]
+fn works_without_preferred_citation() {
+	let items = aggregate_citations(&[cff("One tool", ""), cff("Another tool", "")]);
+	let ids: Vec<_> = items.iter().map(|item| item.id.as_str()).collect();
+	assert_eq!(ids, vec!["doe2021", "doe2021b"]);
+	assert_eq!(
+		items[1].title,
+		Some(OrdinaryValue::String("Another tool".into()))
+	);
+}
+
+#[test]
+fn duplicates_by_title_and_year() {
+	let paper = "preferred-citation:
+  type: article
+  title: A paper about tools.
+  year: 2020
+  authors:
+    - family-names: Doe
+      given-names: Jane
+";
+	let items = aggregate_citations(&[
+		cff("One tool", paper),
+		cff(
+			"Another tool",
+			&paper.replace("A paper about tools.", "A Paper About Tools"),
+		),
+		cff("Another tool", ""),
+	]);
+	let ids: Vec<_> = items.iter().map(|item| item.id.as_str()).collect();
+	assert_eq!(ids, vec!["doe2020", "doe2021"]);
+}
+
+#[test]
+fn duplicates_by_doi() {
+	let mut report = ConversionReport::default();
+	let items = aggregate_citations_with(
+		&[
+			cff("One tool", "doi: 10.5281/zenodo.1\nabstract: One"),
+			cff(
+				"One tool, renamed",
+				"doi: https://doi.org/10.5281/ZENODO.1\nabstract: Two",
+			),
+		],
+		&mut KeyGenerator::default(),
+		&mut report,
+	);
+	assert_eq!(items.len(), 1);
+	assert_eq!(
+		items[0].title,
+		Some(OrdinaryValue::String("One tool".into()))
+	);
+	assert!(report.records.is_empty());
+}
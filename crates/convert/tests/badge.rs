@@ -0,0 +1,53 @@
+use citeworks_cff::identifiers::Identifier;
+use citeworks_convert::badge::{cite_badge, doi_badge, Badge, BadgeFormat};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::cff_file;
+
+#[test]
+fn doi_from_identifiers() {
+	let mut cff = cff_file("minimal");
+	assert_eq!(doi_badge(&cff), None);
+
+	cff.identifiers.push(Identifier::Doi {
+		value: "10.1000/a_b-c".into(),
+		description: None,
+	});
+	assert_eq!(
+		doi_badge(&cff),
+		Some(Badge {
+			alt: "DOI".into(),
+			image: "https://img.shields.io/badge/DOI-10.1000%2Fa__b--c-blue".into(),
+			link: "https://doi.org/10.1000/a_b-c".into(),
+		})
+	);
+}
+
+#[test]
+fn html() {
+	let badge = doi_badge(&cff_file("simple")).unwrap();
+	assert_eq!(
+		badge.render(BadgeFormat::Html),
+		r#"<a href="https://doi.org/10.5281/zenodo.1234"><img src="https://img.shields.io/badge/DOI-10.5281%2Fzenodo.1234-blue" alt="DOI"></a>"#
+	);
+}
+
+#[test]
+fn cite() {
+	let mut cff = cff_file("minimal");
+	assert_eq!(cite_badge(&cff, None), None);
+
+	cff.repository_code = Some("https://github.com/passcod/citeworks/".parse().unwrap());
+	assert_eq!(
+		cite_badge(&cff, None).unwrap().render(BadgeFormat::Markdown),
+		"[![Cite this](https://img.shields.io/badge/Cite%20this-CITATION.cff-green)](https://github.com/passcod/citeworks/blob/HEAD/CITATION.cff)"
+	);
+	assert_eq!(
+		cite_badge(&cff, Some("https://example.com/cite"))
+			.unwrap()
+			.link,
+		"https://example.com/cite"
+	);
+}
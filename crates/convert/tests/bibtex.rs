@@ -0,0 +1,95 @@
+use citeworks_cff::references::RefType;
+use citeworks_convert::bibtex::{
+	entry_to_item, entry_to_reference, item_to_entry, references_to_entries,
+};
+use citeworks_csl::{items::ItemType, names::Name};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::cff_file;
+
+#[test]
+fn through_csl_and_back() {
+	let entries = citeworks_bibtex::from_str(
+		r#"
+@mastersthesis{roe2019,
+  author = {Roe, Jane and {Ka{\'a}napali Labs} and others},
+  title = {On {\"U}ber-fast Lookups},
+  school = {University of Otago},
+  address = {Dunedin},
+  year = 2019,
+  month = mar,
+  eprint = {1234.5678},
+  archiveprefix = {arXiv},
+  doi = {10.1234/thesis},
+}
+"#,
+	)
+	.unwrap();
+
+	let item = entry_to_item(&entries[0]);
+	assert_eq!(item.id, "roe2019");
+	assert_eq!(item.item_type, ItemType::Thesis);
+	assert_eq!(
+		item.author,
+		vec![
+			Name {
+				family: Some("Roe".into()),
+				given: Some("Jane".into()),
+				..Default::default()
+			},
+			Name {
+				literal: Some("Kaánapali Labs".into()),
+				..Default::default()
+			},
+		]
+	);
+	assert_eq!(
+		item.title.as_ref().unwrap().to_string(),
+		"On Über-fast Lookups"
+	);
+	assert_eq!(item.fields["publisher"], ordinary("University of Otago"));
+	assert_eq!(item.fields["genre"], ordinary("Master's thesis"));
+	assert_eq!(item.fields["eprint"], ordinary("1234.5678"));
+
+	assert_eq!(
+		citeworks_bibtex::to_string(&[item_to_entry(&item)]),
+		"@mastersthesis{roe2019,
+  author = {Roe, Jane and {Kaánapali Labs}},
+  title = {On Über-fast Lookups},
+  address = {Dunedin},
+  archiveprefix = {arXiv},
+  eprint = {1234.5678},
+  school = {University of Otago},
+  year = {2019},
+  month = mar,
+  doi = {10.1234/thesis},
+}
+"
+	);
+}
+
+#[test]
+fn references() {
+	let entries = references_to_entries(&cff_file("conference-paper").references);
+	assert_eq!(entries.len(), 1);
+	assert_eq!(
+		entries[0].entry_type,
+		citeworks_bibtex::EntryType::InProceedings
+	);
+	assert_eq!(entries[0].author[0].last, "Doe");
+
+	let reference = entry_to_reference(&entries[0]).unwrap();
+	assert_eq!(reference.work_type, RefType::ConferencePaper);
+	assert_eq!(
+		reference.title,
+		cff_file("conference-paper").references[0].title
+	);
+}
+
+fn ordinary(s: &str) -> citeworks_csl::items::ItemValue {
+	citeworks_csl::items::ItemValue::Ordinary(citeworks_csl::ordinaries::OrdinaryValue::String(
+		s.into(),
+	))
+}
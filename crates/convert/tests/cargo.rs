@@ -0,0 +1,173 @@
+use citeworks_cff::{
+	names::{EntityName, Name, NameMeta},
+	Date,
+};
+use citeworks_convert::cargo::{cargo_to_cff, inherit_workspace, update_cff_from_cargo};
+
+use pretty_assertions::assert_eq;
+
+const RELEASED: Date = Date {
+	year: 2022,
+	month: 8,
+	day: 20,
+};
+
+#[test]
+fn package() {
+	let cff = cargo_to_cff(
+		r#"
+[package]
+name = "citeworks-cli"
+version = "0.1.1"
+description = "citeworks tool for CFF files"
+authors = ["Félix Saparelli <felix@passcod.name>", "The citeworks contributors"]
+license = "MIT/Apache-2.0"
+repository = "https://github.com/passcod/citeworks"
+keywords = ["cff", "csl"]
+edition.workspace = true
+
+[dependencies]
+clap = "3.2.17"
+"#,
+		RELEASED,
+	)
+	.unwrap();
+
+	assert_eq!(cff.title, "citeworks-cli");
+	assert_eq!(
+		cff.abstract_text.as_deref(),
+		Some("citeworks tool for CFF files")
+	);
+	assert_eq!(
+		cff.authors,
+		[
+			Name::Entity(EntityName {
+				name: Some("Félix Saparelli".into()),
+				meta: NameMeta {
+					email: Some("felix@passcod.name".into()),
+					..Default::default()
+				},
+				..Default::default()
+			}),
+			Name::Entity(EntityName {
+				name: Some("The citeworks contributors".into()),
+				..Default::default()
+			}),
+		]
+	);
+	assert_eq!(
+		cff.license.unwrap().to_expression().to_string(),
+		"MIT OR Apache-2.0"
+	);
+	assert_eq!(
+		cff.repository_code.unwrap().as_str(),
+		"https://github.com/passcod/citeworks"
+	);
+	assert_eq!(cff.keywords, ["cff", "csl"]);
+	assert_eq!(cff.version.as_deref(), Some("0.1.1"));
+	assert_eq!(cff.date_released, Some(RELEASED));
+}
+
+#[test]
+fn workspace_fields() {
+	let cff = cargo_to_cff(
+		r#"
+[package]
+name = "member"
+version.workspace = true
+authors = { workspace = true }
+"#,
+		RELEASED,
+	)
+	.unwrap();
+
+	assert_eq!(cff.title, "member");
+	assert_eq!(cff.version, None);
+	assert!(cff.authors.is_empty());
+
+	assert_eq!(
+		cargo_to_cff("[workspace]\nmembers = []\n", RELEASED).unwrap_err(),
+		"Cargo.toml has no [package]"
+	);
+}
+
+#[test]
+fn update() {
+	let mut cff =
+		cargo_to_cff("[package]\nname = \"pkg\"\nversion = \"1.0.0\"\n", RELEASED).unwrap();
+	cff.abstract_text = Some("Edited since".into());
+
+	let later = Date {
+		year: 2023,
+		month: 1,
+		day: 2,
+	};
+	assert!(!update_cff_from_cargo(
+		&mut cff,
+		"[package]\nname = \"pkg\"\nversion = \"1.0.0\"\n",
+		later
+	)
+	.unwrap());
+	assert_eq!(cff.date_released, Some(RELEASED));
+
+	assert!(update_cff_from_cargo(
+		&mut cff,
+		"[package]\nname = \"pkg\"\nversion = \"1.1.0\"\ndescription = \"New\"\n",
+		later
+	)
+	.unwrap());
+	assert_eq!(cff.version.as_deref(), Some("1.1.0"));
+	assert_eq!(cff.date_released, Some(later));
+	assert_eq!(cff.abstract_text.as_deref(), Some("Edited since"));
+}
+
+#[test]
+fn repository_by_host() {
+	let cff = cargo_to_cff(
+		r#"
+[package]
+name = "citeworks-cff"
+repository = "https://zenodo.org/record/1234"
+"#,
+		RELEASED,
+	)
+	.unwrap();
+
+	assert_eq!(cff.repository_code, None);
+	assert_eq!(
+		cff.repository.map(String::from).as_deref(),
+		Some("https://zenodo.org/record/1234")
+	);
+}
+
+#[test]
+fn inherited_from_workspace() {
+	let manifest = inherit_workspace(
+		r#"
+[package]
+name = "citeworks-cff"
+version.workspace = true
+license.workspace = true
+edition.workspace = true
+keywords = ["cff"]
+"#,
+		r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+version = "0.2.0"
+license = "Apache-2.0"
+keywords = ["citeworks"]
+"#,
+	)
+	.unwrap();
+
+	let cff = cargo_to_cff(&manifest, RELEASED).unwrap();
+	assert_eq!(cff.version.as_deref(), Some("0.2.0"));
+	assert_eq!(
+		cff.license.unwrap().to_expression().to_string(),
+		"Apache-2.0"
+	);
+	assert_eq!(cff.keywords, vec!["cff"]);
+}
@@ -0,0 +1,46 @@
+use citeworks_convert::case::{sentence_case, title_case};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn sentence() {
+	assert_eq!(
+		sentence_case("Learning to Rank With LaTeX and RNA-Seq Data"),
+		"Learning to rank with LaTeX and RNA-seq data"
+	);
+	assert_eq!(
+		sentence_case("What Is a 3D Printer? A Review of the Literature"),
+		"What is a 3D printer? A review of the literature"
+	);
+	assert_eq!(
+		sentence_case("the <span class=\"nocase\">Leipzig Glossing Rules</span> Revisited"),
+		"The <span class=\"nocase\">Leipzig Glossing Rules</span> revisited"
+	);
+	assert_eq!(
+		sentence_case("{Einstein's} Theory, as I Understand It"),
+		"{Einstein's} theory, as I understand it"
+	);
+	assert_eq!(sentence_case("A STUDY OF ENTROPY"), "A study of entropy");
+}
+
+#[test]
+fn title() {
+	assert_eq!(
+		title_case("the paintings of vincent van gogh: a catalogue"),
+		"The Paintings of Vincent van Gogh: A Catalogue"
+	);
+	assert_eq!(
+		title_case("a study of the {pH} of well-known rivers and the eBay effect"),
+		"A Study of the {pH} of Well-Known Rivers and the eBay Effect"
+	);
+	assert_eq!(
+		title_case("What Is It For And Where To?"),
+		"What Is It for and Where To?"
+	);
+	assert_eq!(title_case("{DNA}-based computing"), "{DNA}-based Computing");
+}
+
+#[test]
+fn unbalanced_braces() {
+	assert_eq!(title_case("sets {a, b"), "Sets {a, B");
+}
@@ -0,0 +1,304 @@
+use citeworks_cff::{
+	names::Name,
+	references::{RefType, Reference},
+	validation::Rule,
+};
+use citeworks_convert::{
+	cff::{item_to_reference, item_to_reference_with, items_to_references_with, ref_type},
+	options::{BadUrls, ConvertOptions},
+	report::{AuditLog, ConversionReport, IgnoreWarnings},
+};
+use citeworks_csl::{dates::Hemisphere, from_str, items::ItemType, Item};
+
+use pretty_assertions::assert_eq;
+
+fn item(json: &str) -> Item {
+	from_str(&format!("[{json}]")).unwrap().remove(0)
+}
+
+#[test]
+fn article() {
+	let reference = item_to_reference(item(
+		r#"{
+			"id": "vaidya2018",
+			"type": "article-journal",
+			"author": [{"family": "Vaidya", "given": "Nina"}, {"literal": "The Lab"}],
+			"title": "3D printed optics with nanometer scale surface roughness",
+			"DOI": "10.1038/s41378-018-0015-4",
+			"page": "1-8",
+			"volume": "4",
+			"issued": {"date-parts": [[2018, 7]]},
+			"language": "en-GB"
+		}"#,
+	))
+	.unwrap();
+
+	assert_eq!(reference.work_type, RefType::Article);
+	assert!(matches!(reference.authors[0], Name::Person(_)));
+	assert!(matches!(reference.authors[1], Name::Entity(_)));
+	assert_eq!((reference.start, reference.end), (Some(1), Some(8)));
+	assert_eq!(reference.volume, Some(4));
+	assert_eq!((reference.year, reference.month), (Some(2018), Some(7)));
+	assert_eq!(reference.languages, vec!["en".to_string()]);
+}
+
+#[test]
+fn anonymous() {
+	let reference = item_to_reference(item(r#"{"id": "x", "type": "book"}"#)).unwrap();
+	assert_eq!(
+		reference,
+		Reference {
+			work_type: RefType::Book,
+			authors: vec![Name::Anonymous],
+			..Default::default()
+		}
+	);
+}
+
+#[test]
+fn types() {
+	assert_eq!(
+		ref_type(&ItemType::PaperConference),
+		RefType::ConferencePaper
+	);
+	assert_eq!(ref_type(&ItemType::Broadcast), RefType::Generic);
+
+	let mut options = ConvertOptions::default();
+	options
+		.type_mappings
+		.insert(ItemType::Manuscript, RefType::Unpublished);
+	let reference = item_to_reference_with(
+		item(r#"{"id": "x", "type": "manuscript"}"#),
+		&options,
+		&mut IgnoreWarnings,
+	)
+	.unwrap();
+	assert_eq!(reference.work_type, RefType::Unpublished);
+}
+
+#[test]
+fn options_and_report() {
+	let items = vec![
+		item(r#"{"id": "a", "type": "book", "DOI": "https://doi.org/10.1234/a"}"#),
+		item(r#"{"id": "b", "type": "book", "URL": "not a url", "medium": "CD"}"#),
+	];
+
+	let options = ConvertOptions {
+		normalise_dois: true,
+		..Default::default()
+	};
+	let mut report = ConversionReport::default();
+	let references = items_to_references_with(items.clone(), &options, &mut report).unwrap();
+	assert_eq!(references[0].doi.as_deref(), Some("10.1234/a"));
+	assert_eq!(references[1].url, None);
+	assert_eq!(
+		report.to_string(),
+		"\
+b: dropped medium: no CFF equivalent
+b: dropped URL: could not parse URL \"not a url\": relative URL without a base
+"
+	);
+
+	let strict = ConvertOptions {
+		bad_urls: BadUrls::Strict,
+		..Default::default()
+	};
+	assert_eq!(
+		items_to_references_with(items, &strict, &mut IgnoreWarnings),
+		Err("converting b: could not parse URL \"not a url\": relative URL without a base".into())
+	);
+}
+
+#[test]
+fn seasons() {
+	let winter = item(
+		r#"{"id": "a", "type": "book", "issued": {"date-parts": [[2020]], "season": "winter"}}"#,
+	);
+
+	let mut report = ConversionReport::default();
+	let reference =
+		item_to_reference_with(winter.clone(), &ConvertOptions::default(), &mut report).unwrap();
+	assert_eq!((reference.year, reference.month), (Some(2020), Some(12)));
+	assert_eq!(report.records[0].guessed[0].value, "12");
+
+	let southern = ConvertOptions {
+		hemisphere: Hemisphere::Southern,
+		..Default::default()
+	};
+	let reference = item_to_reference_with(winter, &southern, &mut IgnoreWarnings).unwrap();
+	assert_eq!((reference.year, reference.month), (Some(2020), Some(6)));
+}
+
+#[test]
+fn unknown_item_type() {
+	let preprint = item(r#"{"id": "a", "type": "preprint", "title": "Draft"}"#);
+
+	let mut report = ConversionReport::default();
+	let reference =
+		item_to_reference_with(preprint.clone(), &ConvertOptions::default(), &mut report).unwrap();
+	assert_eq!(reference.work_type, RefType::Generic);
+	assert_eq!(report.records[0].guessed[0].field, "type");
+	assert_eq!(report.records[0].guessed[0].value, "generic");
+
+	let mapped = ConvertOptions {
+		type_mappings: [(ItemType::Other("preprint".into()), RefType::Article)].into(),
+		..Default::default()
+	};
+	let mut report = ConversionReport::default();
+	let reference = item_to_reference_with(preprint, &mapped, &mut report).unwrap();
+	assert_eq!(reference.work_type, RefType::Article);
+	assert!(report
+		.records
+		.iter()
+		.all(|record| record.guessed.is_empty()));
+}
+
+#[test]
+fn edtf_dates() {
+	let edtf = |date: &str| {
+		item(&format!(
+			r#"{{"id": "a", "type": "book", "issued": {{"edtf": "{date}"}}, "accessed": {{"edtf": "{date}"}}}}"#
+		))
+	};
+
+	let reference = item_to_reference(edtf("2018-07-12~")).unwrap();
+	assert_eq!((reference.year, reference.month), (Some(2018), Some(7)));
+	assert_eq!(
+		reference.date_accessed.map(|date| date.to_string()),
+		Some("2018-07-12".into())
+	);
+
+	let mut report = ConversionReport::default();
+	let reference =
+		item_to_reference_with(edtf("201X"), &ConvertOptions::default(), &mut report).unwrap();
+	assert_eq!((reference.year, reference.month), (Some(2010), None));
+	assert_eq!(reference.issue_date.as_deref(), Some("201X"));
+
+	let mut report = ConversionReport::default();
+	let reference =
+		item_to_reference_with(edtf("../2018"), &ConvertOptions::default(), &mut report).unwrap();
+	assert_eq!(reference.year, None);
+	assert_eq!(
+		report.records[0].dropped[0].reason,
+		"could not convert date \"../2018\""
+	);
+}
+
+#[test]
+fn audit_log() {
+	let item = item(
+		r#"{
+			"id": "a",
+			"type": "book",
+			"title": "Opaquity",
+			"rights": "CC0",
+			"license": "MIT",
+			"issued": {"date-parts": [[2017, 2]]},
+			"page": "3-9",
+			"medium": "CD"
+		}"#,
+	);
+
+	let mut report = ConversionReport::default();
+	let mut log = AuditLog::new(Vec::new()).forward(|record| report.push(record));
+	item_to_reference_with(item, &ConvertOptions::default(), &mut log).unwrap();
+	let log = String::from_utf8(log.into_inner()).unwrap();
+	let lines = log
+		.lines()
+		.map(|line| {
+			let line: serde_json::Value = serde_json::from_str(line).unwrap();
+			assert_eq!(line["record"], "a");
+			format!(
+				"{} {} {} {}",
+				line["action"].as_str().unwrap(),
+				line["from"].as_str().or(line["field"].as_str()).unwrap(),
+				line["to"].as_str().unwrap_or("-"),
+				line["transform"]
+					.as_str()
+					.or(line["reason"].as_str())
+					.unwrap(),
+			)
+		})
+		.collect::<Vec<_>>();
+
+	assert_eq!(
+		lines,
+		vec![
+			"mapped type type type mapping",
+			"mapped title title copied",
+			"mapped rights copyright copied",
+			"mapped issued year year of the date",
+			"mapped issued month month of the date",
+			"mapped issued issue-date date as text",
+			"mapped page start first page",
+			"mapped page end last page",
+			"dropped medium - no CFF equivalent",
+			"dropped license - copyright taken by rights",
+		]
+	);
+
+	// Only what wasn't converted exactly is passed on.
+	assert_eq!(report.records.len(), 1);
+	assert_eq!(report.records[0].dropped.len(), 2);
+	assert!(!report.records[0].mapped.is_empty());
+}
+
+#[test]
+fn audit_log_write_error() {
+	struct Full;
+	impl std::io::Write for Full {
+		fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+			Err(std::io::Error::new(std::io::ErrorKind::Other, "disk full"))
+		}
+		fn flush(&mut self) -> std::io::Result<()> {
+			Ok(())
+		}
+	}
+
+	let mut log = AuditLog::new(Full);
+	item_to_reference_with(
+		item(r#"{"id": "a", "type": "book", "title": "Opaquity"}"#),
+		&ConvertOptions::default(),
+		&mut log,
+	)
+	.unwrap();
+	assert_eq!(log.finish().unwrap_err().to_string(), "disk full");
+	assert!(log.finish().is_ok());
+}
+
+#[test]
+fn orcid_hosts() {
+	let reference = item_to_reference(item(
+		r#"{
+			"id": "a",
+			"type": "book",
+			"author": [
+				{"family": "Doe", "ORCID": "https://sandbox.orcid.org/0000-0003-4925-7248"},
+				{"family": "Roe", "ORCID": "https://notorcid.org/0000-0003-4925-7248"},
+				{"family": "Poe", "ORCID": "0000-0003-4925-7248"}
+			]
+		}"#,
+	))
+	.unwrap();
+
+	let orcids: Vec<_> = reference
+		.authors
+		.iter()
+		.map(|name| match name {
+			Name::Person(person) => person.meta.orcid.as_ref().map(|url| url.to_string()),
+			_ => panic!("expected a person"),
+		})
+		.collect();
+	assert_eq!(
+		orcids,
+		vec![
+			None,
+			None,
+			Some("https://orcid.org/0000-0003-4925-7248".to_string()),
+		]
+	);
+	assert!(reference
+		.validate()
+		.iter()
+		.all(|issue| issue.rule != Rule::OrcidFormat));
+}
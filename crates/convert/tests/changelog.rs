@@ -0,0 +1,61 @@
+use citeworks_convert::changelog::{entries, entry_for_version, latest_entry, truncate};
+
+use pretty_assertions::assert_eq;
+
+const CHANGELOG: &str = "# Changelog
+
+All notable changes to this project are documented here.
+
+## [Unreleased]
+- Work in progress.
+
+## [v2.0.0](https://github.com/someone/thing/releases/v2.0.0) (2023-01-02)
+
+### Changed
+- Renamed everything.
+
+### Fixed
+- Nothing works.
+
+## 1.0.0
+First release.
+
+[Unreleased]: https://github.com/someone/thing/compare/v2.0.0...HEAD
+[v2.0.0]: https://github.com/someone/thing/compare/v1.0.0...v2.0.0
+";
+
+#[test]
+fn latest() {
+	let entry = latest_entry(CHANGELOG).unwrap();
+	assert_eq!(entry.version, "2.0.0");
+	assert_eq!(entry.date.as_deref(), Some("2023-01-02"));
+	assert_eq!(
+		entry.notes,
+		"### Changed\n- Renamed everything.\n\n### Fixed\n- Nothing works."
+	);
+}
+
+#[test]
+fn by_version() {
+	let entry = entry_for_version(CHANGELOG, "v1.0.0").unwrap();
+	assert_eq!(entry.date, None);
+	assert_eq!(entry.notes, "First release.");
+
+	assert_eq!(entry_for_version(CHANGELOG, "3.0.0"), None);
+	assert_eq!(entries(CHANGELOG).len(), 2);
+}
+
+#[test]
+fn no_releases() {
+	assert_eq!(
+		latest_entry("# Changelog\n\n## Unreleased\n- Soon.\n"),
+		None
+	);
+}
+
+#[test]
+fn truncation() {
+	assert_eq!(truncate("short", 10), "short");
+	assert_eq!(truncate("one two three", 10), "one two…");
+	assert_eq!(truncate("unbreakable", 5), "unbr…");
+}
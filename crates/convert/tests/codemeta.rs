@@ -0,0 +1,94 @@
+use citeworks_codemeta::agents::Agent;
+use citeworks_convert::codemeta::{cff_to_codemeta, codemeta_to_cff, update_cff, update_codemeta};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::cff_file;
+
+#[test]
+fn through_codemeta_and_back() {
+	let cff = cff_file("mardyn");
+	let codemeta = cff_to_codemeta(&cff);
+	assert_eq!(codemeta.name.as_deref(), Some("ls1 mardyn"));
+	assert_eq!(
+		codemeta.code_repository.as_deref(),
+		Some("https://projects.hlrs.de/projects/ls1/")
+	);
+	assert_eq!(codemeta.date_published.as_deref(), Some("2018-09-05"));
+	assert!(matches!(&codemeta.author[0], Agent::Organization(org)
+		if org.name.as_deref() == Some("Boltzmann-Zuse Society for Computational Molecular Engineering")));
+	assert_eq!(
+		codemeta.maintainer[0]
+			.as_person()
+			.and_then(|person| person.family_name.as_deref()),
+		Some("Neumann")
+	);
+
+	let back = codemeta_to_cff(&codemeta).unwrap();
+	assert_eq!(back.title, cff.title);
+	assert_eq!(back.version, cff.version);
+	assert_eq!(back.abstract_text, cff.abstract_text);
+	assert_eq!(back.url, cff.url);
+	assert_eq!(back.repository_code, cff.repository_code);
+	assert_eq!(back.date_released, cff.date_released);
+	assert_eq!(back.contact, cff.contact);
+	assert_eq!(
+		back.authors[0].as_entity().unwrap().name,
+		cff.authors[0].as_entity().unwrap().name
+	);
+}
+
+#[test]
+fn updates_keep_the_rest() {
+	let mut codemeta = citeworks_codemeta::from_str(
+		r#"{
+			"name": "Old Name",
+			"programmingLanguage": "Rust",
+			"license": "https://spdx.org/licenses/GPL-3.0-only",
+			"funding": "Some foundation"
+		}"#,
+	)
+	.unwrap();
+	let simple = cff_file("simple");
+	update_codemeta(&mut codemeta, &simple);
+	assert_eq!(codemeta.name.as_deref(), Some("My Research Software"));
+	assert_eq!(
+		codemeta.id.as_deref(),
+		Some("https://doi.org/10.5281/zenodo.1234")
+	);
+	assert_eq!(codemeta.extra["programmingLanguage"], "Rust");
+	assert!(codemeta.license.is_empty());
+	assert!(codemeta.funding.is_empty());
+
+	let mut mardyn = cff_file("mardyn");
+	update_cff(&mut mardyn, &codemeta).unwrap();
+	assert_eq!(mardyn.title, "My Research Software");
+	assert_eq!(mardyn.doi.as_deref(), Some("10.5281/zenodo.1234"));
+	assert_eq!(mardyn.identifiers, []);
+	assert_eq!(mardyn.authors, simple.authors);
+	assert_eq!(mardyn.references, cff_file("mardyn").references);
+}
+
+#[test]
+fn licenses_and_keywords() {
+	let codemeta = citeworks_codemeta::from_str(
+		r#"{
+			"name": "x",
+			"license": ["https://spdx.org/licenses/MIT.html", "Apache-2.0", "not a license"],
+			"keywords": "citation, metadata,"
+		}"#,
+	)
+	.unwrap();
+	let cff = codemeta_to_cff(&codemeta).unwrap();
+	assert_eq!(
+		cff.license.unwrap().to_expression().to_string(),
+		"(MIT) OR (Apache-2.0)"
+	);
+	assert_eq!(cff.keywords, ["citation", "metadata"]);
+
+	assert_eq!(
+		codemeta_to_cff(&citeworks_codemeta::from_str("{}").unwrap()),
+		Err("CodeMeta has no name, which CFF requires as title".into())
+	);
+}
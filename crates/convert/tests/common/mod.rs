@@ -0,0 +1,9 @@
+use std::fs::File;
+
+use citeworks_cff::{from_reader, Cff};
+
+/// Read one of the example files that the cff crate's tests check as valid.
+pub fn cff_file(name: &str) -> Cff {
+	let file = File::open(format!("../cff/tests/pass/{name}.cff")).unwrap();
+	from_reader(file).unwrap()
+}
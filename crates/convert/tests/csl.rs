@@ -0,0 +1,253 @@
+use citeworks_convert::{
+	csl::{cff_to_items, cff_to_items_with},
+	keys::KeyGenerator,
+	options::ConvertOptions,
+	report::{AuditLog, ConversionReport, PrintWarnings, RecordReport},
+};
+use citeworks_csl::{items::ItemType, ordinaries::OrdinaryValue};
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+mod common;
+use common::cff_file;
+
+#[test]
+fn conference_paper() {
+	let items = cff_to_items(&cff_file("conference-paper"));
+	assert_eq!(items.len(), 2);
+	assert_eq!(items[0].item_type, ItemType::Software);
+	assert_eq!(
+		items[0].doi,
+		Some(OrdinaryValue::String("10.5281/zenodo.1234".into()))
+	);
+
+	assert_eq!(
+		serde_json::to_value(&items[1]).unwrap(),
+		json!({
+			"id": "doe2017",
+			"type": "paper-conference",
+			"author": [{"family": "Doe", "given": "Jane"}],
+			"issued": {"date-parts": [[2017]]},
+			"DOI": "10.5281/zenodo.1234",
+			"title": "Ultimate-accuracy syntax parsing with My Research Tool",
+			"container-title": "Proceedings of the 1st Conference on Wishful Thinking",
+			"page": "42-45",
+			"editor": [{"family": "Kirk", "given": "James T."}],
+			"event-title": "1st Conference on Wishful Thinking",
+		})
+	);
+}
+
+#[test]
+fn unique_keys() {
+	let mut cff = cff_file("conference-paper");
+	let reference = cff.references[0].clone();
+	cff.references.push(reference.clone());
+	cff.references.push(reference);
+	cff.authors.clear();
+
+	let keys: Vec<_> = cff_to_items(&cff).into_iter().map(|item| item.id).collect();
+	assert_eq!(keys, vec!["anon2017", "doe2017", "doe2017b", "doe2017c"]);
+}
+
+#[test]
+fn conversion_report() {
+	let mut report = ConversionReport::default();
+	cff_to_items_with(
+		&cff_file("conference-paper"),
+		&ConvertOptions::default(),
+		&mut KeyGenerator::default(),
+		&mut report,
+	);
+	assert_eq!(
+		report.to_string(),
+		"doe2017: dropped collection-doi: no CSL equivalent\n"
+	);
+
+	let mut report = ConversionReport::default();
+	cff_to_items_with(
+		&cff_file("closed-source"),
+		&ConvertOptions::default(),
+		&mut KeyGenerator::default(),
+		&mut report,
+	);
+	assert_eq!(
+		report.to_string(),
+		"vader2017: dropped contact: no CSL equivalent\n"
+	);
+	assert_eq!(
+		report.to_json(),
+		json!({"records": [{
+			"id": "vader2017",
+			"dropped": [{"field": "contact", "reason": "no CSL equivalent"}],
+			"guessed": [],
+			"warnings": [],
+		}]})
+	);
+}
+
+#[test]
+fn orcid() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My Research Software
+authors:
+  - family-names: Doe
+    given-names: Jane
+    orcid: https://orcid.org/0000-0003-4925-7248
+"#,
+	)
+	.unwrap();
+
+	let items = cff_to_items(&cff);
+	assert_eq!(
+		items[0].author[0].orcid(),
+		Some("https://orcid.org/0000-0003-4925-7248")
+	);
+}
+
+#[test]
+fn status() {
+	use citeworks_cff::references::PublicationStatus;
+	use citeworks_convert::csl::{parse_status, status};
+
+	assert_eq!(parse_status("In Press"), Some(PublicationStatus::InPress));
+	assert_eq!(parse_status("pre-print"), Some(PublicationStatus::Preprint));
+	assert_eq!(
+		parse_status("Epub ahead of print"),
+		Some(PublicationStatus::AdvanceOnline)
+	);
+	assert_eq!(parse_status("retracted"), None);
+
+	for s in [
+		PublicationStatus::Abstract,
+		PublicationStatus::AdvanceOnline,
+		PublicationStatus::InPreparation,
+		PublicationStatus::InPress,
+		PublicationStatus::Preprint,
+		PublicationStatus::Submitted,
+	] {
+		assert_eq!(parse_status(status(s)), Some(s));
+	}
+}
+
+#[test]
+fn warning_sinks() {
+	let mut printed = PrintWarnings::new(Vec::new()).prefix("warning: ");
+	cff_to_items_with(
+		&cff_file("conference-paper"),
+		&ConvertOptions::default(),
+		&mut KeyGenerator::default(),
+		&mut printed,
+	);
+	assert_eq!(
+		String::from_utf8(printed.into_inner()).unwrap(),
+		"warning: doe2017: dropped collection-doi: no CSL equivalent\n"
+	);
+
+	let mut ids = Vec::new();
+	cff_to_items_with(
+		&cff_file("closed-source"),
+		&ConvertOptions::default(),
+		&mut KeyGenerator::default(),
+		&mut |record: RecordReport| ids.push(record.id),
+	);
+	assert_eq!(ids, vec!["vader2017"]);
+}
+
+#[test]
+fn preferred_citation() {
+	let mut cff = cff_file("conference-paper");
+	cff.preferred_citation = Some(cff.references.remove(0));
+
+	let mut report = ConversionReport::default();
+	let items = cff_to_items_with(
+		&cff,
+		&ConvertOptions::default(),
+		&mut KeyGenerator::default(),
+		&mut report,
+	);
+	assert_eq!(items.len(), 1);
+	assert_eq!(items[0].item_type, ItemType::Software);
+	assert_eq!(
+		report.records[0].dropped[0].field,
+		"preferred-citation".to_string()
+	);
+
+	let mut report = ConversionReport::default();
+	let items = cff_to_items_with(
+		&cff,
+		&ConvertOptions {
+			preferred_citation: true,
+			..Default::default()
+		},
+		&mut KeyGenerator::default(),
+		&mut report,
+	);
+	assert_eq!(
+		items
+			.iter()
+			.map(|item| (item.id.as_str(), item.item_type.clone()))
+			.collect::<Vec<_>>(),
+		vec![
+			("doe2017", ItemType::PaperConference),
+			("druskat2017", ItemType::Software),
+		]
+	);
+}
+
+#[test]
+fn audit_fallbacks() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: Opaquity
+authors:
+  - name: Dark Side Software
+references:
+  - type: book
+    title: A book
+    authors: []
+    collection-title: A series
+    year: 2017
+"#,
+	)
+	.unwrap();
+
+	let mut log = AuditLog::new(Vec::new());
+	cff_to_items_with(
+		&cff,
+		&ConvertOptions::default(),
+		&mut KeyGenerator::default(),
+		&mut log,
+	);
+	let log = String::from_utf8(log.into_inner()).unwrap();
+	let mapped = log
+		.lines()
+		.map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+		.filter(|line| line["action"] == "mapped")
+		.map(|line| {
+			format!(
+				"{} -> {}",
+				line["from"].as_str().unwrap(),
+				line["to"].as_str().unwrap()
+			)
+		})
+		.collect::<Vec<_>>();
+
+	assert_eq!(
+		mapped,
+		vec![
+			"authors -> author",
+			"title -> title",
+			"type -> type",
+			"title -> title",
+			"collection-title -> container-title",
+			"year -> issued",
+		]
+	);
+}
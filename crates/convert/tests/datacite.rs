@@ -0,0 +1,184 @@
+use citeworks_convert::datacite::{cff_to_datacite_json, cff_to_datacite_xml, DataCiteOptions};
+use serde_json::json;
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::cff_file;
+
+fn zenodo() -> DataCiteOptions {
+	DataCiteOptions {
+		publisher: Some("Zenodo".into()),
+		..Default::default()
+	}
+}
+
+#[test]
+fn simple_xml() {
+	assert_eq!(
+		cff_to_datacite_xml(&cff_file("simple"), &zenodo()),
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<resource xmlns="http://datacite.org/schema/kernel-4" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://datacite.org/schema/kernel-4 http://schema.datacite.org/meta/kernel-4/metadata.xsd">
+  <identifier identifierType="DOI">10.5281/zenodo.1234</identifier>
+  <creators>
+    <creator>
+      <creatorName nameType="Personal">Druskat, Stephan</creatorName>
+      <givenName>Stephan</givenName>
+      <familyName>Druskat</familyName>
+      <nameIdentifier nameIdentifierScheme="ORCID" schemeURI="https://orcid.org">https://orcid.org/0000-0003-4925-7248</nameIdentifier>
+    </creator>
+  </creators>
+  <titles>
+    <title>My Research Software</title>
+  </titles>
+  <publisher>Zenodo</publisher>
+  <publicationYear>2017</publicationYear>
+  <resourceType resourceTypeGeneral="Software">Software</resourceType>
+  <dates>
+    <date dateType="Issued">2017-12-18</date>
+  </dates>
+  <version>2.0.4</version>
+</resource>
+"#
+	);
+}
+
+#[test]
+fn simple_json() {
+	assert_eq!(
+		cff_to_datacite_json(&cff_file("simple"), &zenodo()),
+		json!({
+			"doi": "10.5281/zenodo.1234",
+			"creators": [{
+				"name": "Druskat, Stephan",
+				"nameType": "Personal",
+				"givenName": "Stephan",
+				"familyName": "Druskat",
+				"nameIdentifiers": [{
+					"nameIdentifier": "https://orcid.org/0000-0003-4925-7248",
+					"nameIdentifierScheme": "ORCID",
+					"schemeUri": "https://orcid.org",
+				}],
+			}],
+			"titles": [{ "title": "My Research Software" }],
+			"publisher": "Zenodo",
+			"publicationYear": "2017",
+			"types": {
+				"resourceTypeGeneral": "Software",
+				"resourceType": "Software",
+			},
+			"dates": [{ "date": "2017-12-18", "dateType": "Issued" }],
+			"version": "2.0.4",
+		})
+	);
+}
+
+#[test]
+fn references_and_rights() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My Research Software
+authors:
+  - name: The Research Software project
+    ror: https://ror.org/02d8dv541
+license:
+  - Apache-2.0
+  - MIT AND BSD-3-Clause
+repository-code: https://github.com/citation-file-format/my-research-software
+references:
+  - type: article
+    title: A paper
+    authors:
+      - family-names: Roe
+    doi: 10.1234/paper
+  - type: website
+    title: A website
+    authors:
+      - family-names: Doe
+    url: https://example.com
+  - type: book
+    title: A book without identifiers
+    authors:
+      - family-names: Poe
+"#,
+	)
+	.unwrap();
+
+	let json = cff_to_datacite_json(&cff, &DataCiteOptions::default());
+	assert_eq!(json["publisher"], "(:unav)");
+	assert_eq!(
+		json["creators"],
+		json!([{
+			"name": "The Research Software project",
+			"nameType": "Organizational",
+			"nameIdentifiers": [{
+				"nameIdentifier": "https://ror.org/02d8dv541",
+				"nameIdentifierScheme": "ROR",
+				"schemeUri": "https://ror.org",
+			}],
+		}])
+	);
+	assert_eq!(
+		json["relatedIdentifiers"],
+		json!([
+			{
+				"relatedIdentifier": "10.1234/paper",
+				"relatedIdentifierType": "DOI",
+				"relationType": "References",
+			},
+			{
+				"relatedIdentifier": "https://example.com/",
+				"relatedIdentifierType": "URL",
+				"relationType": "References",
+			},
+			{
+				"relatedIdentifier": "https://github.com/citation-file-format/my-research-software",
+				"relatedIdentifierType": "URL",
+				"relationType": "IsSupplementTo",
+			},
+		])
+	);
+	assert_eq!(
+		json["rightsList"],
+		json!([
+			{
+				"rights": "Apache License 2.0",
+				"rightsUri": "https://spdx.org/licenses/Apache-2.0",
+				"rightsIdentifier": "Apache-2.0",
+				"rightsIdentifierScheme": "SPDX",
+				"schemeUri": "https://spdx.org/licenses/",
+			},
+			{ "rights": "MIT AND BSD-3-Clause" },
+		])
+	);
+
+	let xml = cff_to_datacite_xml(&cff, &DataCiteOptions::default());
+	assert!(xml.contains(r#"<rights rightsURI="https://spdx.org/licenses/Apache-2.0" rightsIdentifier="Apache-2.0" rightsIdentifierScheme="SPDX" schemeURI="https://spdx.org/licenses/">Apache License 2.0</rights>"#));
+	assert!(xml.contains(r#"<relatedIdentifier relatedIdentifierType="DOI" relationType="References">10.1234/paper</relatedIdentifier>"#));
+}
+
+#[test]
+fn release_notes() {
+	let cff = cff_file("simple");
+	let options = DataCiteOptions {
+		release_notes: Some("### Fixed\n- Dates before 1970.".into()),
+		..zenodo()
+	};
+
+	let json = cff_to_datacite_json(&cff, &options);
+	let descriptions = json["descriptions"].as_array().unwrap();
+	assert_eq!(
+		descriptions.last().unwrap(),
+		&json!({
+			"description": "### Fixed\n- Dates before 1970.",
+			"descriptionType": "Other",
+		})
+	);
+
+	let xml = cff_to_datacite_xml(&cff, &options);
+	assert!(xml.contains(
+		"<description descriptionType=\"Other\">### Fixed\n- Dates before 1970.</description>"
+	));
+}
@@ -0,0 +1,81 @@
+use citeworks_convert::duplicates::{item_duplicates, reference_duplicates, DuplicateDoi, Field};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn references() {
+	let cff = citeworks_cff::from_str(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My Research Software
+authors:
+  - name: The Research Software project
+references:
+  - type: article
+    title: Deep learning
+    doi: 10.1038/nature14539
+    year: 2015
+    authors:
+      - family-names: LeCun
+        given-names: Yann
+      - family-names: Bengio
+        given-names: Yoshua
+  - type: article
+    title: Unrelated
+    doi: 10.1234/unrelated
+    authors:
+      - family-names: Doe
+  - type: article
+    title: "Deep Learning."
+    doi: doi:10.1038/NATURE14539
+    authors:
+      - family-names: LeCun
+        given-names: Y.
+      - family-names: Bengio
+        given-names: Y.
+  - type: article
+    title: Generative adversarial nets
+    doi: 10.1038/nature14539
+    year: 2014
+    authors:
+      - family-names: Goodfellow
+"#,
+	)
+	.unwrap();
+
+	assert_eq!(
+		reference_duplicates(&cff.references),
+		vec![DuplicateDoi {
+			doi: "10.1038/nature14539".into(),
+			entries: vec![0, 2, 3],
+			conflicts: vec![Field::Title, Field::Year, Field::Authors],
+		}]
+	);
+}
+
+#[test]
+fn plain_duplicates() {
+	let items = citeworks_csl::from_str(
+		r#"[
+			{"id": "b", "type": "book", "DOI": "10.1234/b", "title": "B"},
+			{"id": "a", "type": "book", "DOI": "10.1234/a", "title": "A", "author": [{"family": "Doe"}]},
+			{"id": "a2", "type": "book", "DOI": "10.1234/A", "title": "A"},
+			{"id": "b2", "type": "book", "DOI": "10.1234/b", "title": "B"},
+			{"id": "c", "type": "book", "title": "C"}
+		]"#,
+	)
+	.unwrap();
+
+	let duplicates = item_duplicates(&items);
+	assert_eq!(
+		duplicates
+			.iter()
+			.map(|d| (d.doi.as_str(), d.entries.clone(), d.is_conflicting()))
+			.collect::<Vec<_>>(),
+		vec![
+			("10.1234/b", vec![0, 3], false),
+			("10.1234/a", vec![1, 2], false),
+		]
+	);
+}
@@ -0,0 +1,91 @@
+use citeworks_cff::from_str;
+use citeworks_convert::graph::{Edge, Graph, GraphFormat, NodeKind, Relation};
+use serde_json::json;
+
+use pretty_assertions::assert_eq;
+
+const CFF: &str = r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My "Research" Software
+repository-code: https://github.com/example/software
+authors:
+  - name: The Research Software project
+preferred-citation:
+  type: article
+  title: The paper
+  authors:
+    - name: The Research Software project
+references:
+  - type: software
+    title: A dependency
+    identifiers:
+      - type: swh
+        value: swh:1:dir:bc286860f423ea7ced246ba7458eef4b4541cf2d
+    authors:
+      - name: Someone else
+  - type: article
+    title: A dependency, again
+    identifiers:
+      - type: swh
+        value: swh:1:dir:bc286860f423ea7ced246ba7458eef4b4541cf2d
+    authors:
+      - name: Someone else
+"#;
+
+#[test]
+fn identifiers() {
+	let graph = Graph::from_cff(&from_str(CFF).unwrap());
+	let ids: Vec<_> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+	assert_eq!(
+		ids,
+		vec![
+			"url:https://github.com/example/software",
+			"preferred-citation",
+			"swh:1:dir:bc286860f423ea7ced246ba7458eef4b4541cf2d",
+		]
+	);
+	assert_eq!(graph.nodes[2].kind, NodeKind::Reference);
+	assert_eq!(graph.nodes[2].label, "A dependency");
+	assert_eq!(
+		graph.edges[1],
+		Edge {
+			from: "url:https://github.com/example/software".into(),
+			to: "swh:1:dir:bc286860f423ea7ced246ba7458eef4b4541cf2d".into(),
+			relation: Relation::Cites,
+		}
+	);
+	assert_eq!(graph.edges.len(), 2);
+}
+
+#[test]
+fn dot() {
+	let graph = Graph::from_cff(&from_str(CFF).unwrap());
+	assert_eq!(
+		graph.render(GraphFormat::Dot),
+		r#"digraph citations {
+	rankdir=LR;
+	node [shape=box];
+	"url:https://github.com/example/software" [label="My \"Research\" Software", style=bold];
+	"preferred-citation" [label="The paper", style=rounded];
+	"swh:1:dir:bc286860f423ea7ced246ba7458eef4b4541cf2d" [label="A dependency"];
+	"url:https://github.com/example/software" -> "preferred-citation" [style=dashed, label="preferred-citation"];
+	"url:https://github.com/example/software" -> "swh:1:dir:bc286860f423ea7ced246ba7458eef4b4541cf2d";
+}
+"#
+	);
+}
+
+#[test]
+fn json() {
+	let graph = Graph::from_cff(&from_str(CFF).unwrap());
+	assert_eq!(
+		graph.to_json()["edges"][0],
+		json!({
+			"from": "url:https://github.com/example/software",
+			"to": "preferred-citation",
+			"relation": "preferred-citation",
+		})
+	);
+	assert_eq!(graph.to_json()["nodes"][1]["kind"], "preferred-citation");
+}
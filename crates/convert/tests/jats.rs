@@ -0,0 +1,125 @@
+use citeworks_convert::{
+	jats::{items_to_jats, items_to_jats_with, references_to_jats, references_to_jats_with},
+	report::ConversionReport,
+};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::cff_file;
+
+#[test]
+fn conference_paper() {
+	assert_eq!(
+		references_to_jats(&cff_file("conference-paper").references),
+		r#"<ref-list>
+  <ref id="ref1">
+    <element-citation publication-type="confproc">
+      <person-group person-group-type="author">
+        <name>
+          <surname>Doe</surname>
+          <given-names>Jane</given-names>
+        </name>
+      </person-group>
+      <person-group person-group-type="editor">
+        <name>
+          <surname>Kirk</surname>
+          <given-names>James T.</given-names>
+        </name>
+      </person-group>
+      <article-title>Ultimate-accuracy syntax parsing with My Research Tool</article-title>
+      <source>Proceedings of the 1st Conference on Wishful Thinking</source>
+      <year>2017</year>
+      <fpage>42</fpage>
+      <lpage>45</lpage>
+      <conf-name>1st Conference on Wishful Thinking</conf-name>
+      <pub-id pub-id-type="doi">10.5281/zenodo.1234</pub-id>
+    </element-citation>
+  </ref>
+</ref-list>
+"#
+	);
+}
+
+#[test]
+fn csl_items() {
+	let items = citeworks_csl::from_str(
+		r#"[{
+			"id": "http://zotero.org/users/220875/items/VS77S6IV",
+			"type": "article-journal",
+			"author": [
+				{"family": "Beethoven", "non-dropping-particle": "van", "given": "Ludwig"},
+				{"literal": "The <Collective>"}
+			],
+			"title": "On things & stuff",
+			"container-title": "Journal of Stuff",
+			"issued": {"date-parts": [[2022, 8]]},
+			"volume": 3,
+			"page": "10-20",
+			"publisher": "Stuff Press",
+			"DOI": "10.1234/stuff"
+		}, {
+			"id": "2nd",
+			"type": "motion-picture",
+			"title": "A film"
+		}]"#,
+	)
+	.unwrap();
+
+	assert_eq!(
+		items_to_jats(&items),
+		r#"<ref-list>
+  <ref id="http---zotero.org-users-220875-items-VS77S6IV">
+    <element-citation publication-type="journal">
+      <person-group person-group-type="author">
+        <name>
+          <surname>van Beethoven</surname>
+          <given-names>Ludwig</given-names>
+        </name>
+        <collab>The &lt;Collective&gt;</collab>
+      </person-group>
+      <article-title>On things &amp; stuff</article-title>
+      <source>Journal of Stuff</source>
+      <year>2022</year>
+      <month>08</month>
+      <volume>3</volume>
+      <fpage>10</fpage>
+      <lpage>20</lpage>
+      <publisher-name>Stuff Press</publisher-name>
+      <pub-id pub-id-type="doi">10.1234/stuff</pub-id>
+    </element-citation>
+  </ref>
+  <ref id="ref-2nd">
+    <element-citation publication-type="other">
+      <source>A film</source>
+    </element-citation>
+  </ref>
+</ref-list>
+"#
+	);
+}
+
+#[test]
+fn report() {
+	let references = cff_file("conference-paper").references;
+	let mut report = ConversionReport::default();
+	assert_eq!(
+		references_to_jats_with(&references, &mut report),
+		references_to_jats(&references)
+	);
+	assert_eq!(
+		report.to_string(),
+		"ref1: dropped collection-doi: no JATS equivalent\n"
+	);
+
+	let items = citeworks_csl::from_str(
+		r#"[{"id": "doe2017", "type": "book", "title": "Things", "note": "Signed copy"}]"#,
+	)
+	.unwrap();
+	let mut report = ConversionReport::default();
+	items_to_jats_with(&items, &mut report);
+	assert_eq!(
+		report.to_string(),
+		"doe2017: dropped note: no JATS equivalent\n"
+	);
+}
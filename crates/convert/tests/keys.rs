@@ -0,0 +1,84 @@
+use citeworks_convert::keys::{fill_missing_keys, KeyGenerator, KeyPattern};
+use citeworks_csl::{from_str, Item};
+
+use pretty_assertions::assert_eq;
+
+fn items() -> Vec<Item> {
+	from_str(
+		r#"[
+			{"id": "", "type": "book", "author": [{"family": "Van der Berg", "given": "Anna"}], "title": "On the origin of keys", "issued": {"date-parts": [[2009]]}},
+			{"id": "", "type": "book", "author": [{"family": "Doe"}, {"family": "Roe"}], "title": "A study in pairs", "issued": {"date-parts": [[2017]]}},
+			{"id": "", "type": "book", "author": [{"literal": "Acme Corp."}, {"family": "Doe"}, {"family": "Roe"}], "title": "Reports", "issued": {"raw": "sometime"}},
+			{"id": "", "type": "book", "title": "The Anonymous Work"}
+		]"#,
+	)
+	.unwrap()
+}
+
+fn generate(pattern: &str) -> Vec<String> {
+	let mut keys = KeyGenerator::new(pattern.parse().unwrap());
+	items().iter().map(|item| keys.generate(item)).collect()
+}
+
+#[test]
+fn default_pattern() {
+	assert_eq!(
+		generate("[auth:lower][year]"),
+		["vanderberg2009", "doe2017", "acmecorp", "anon"]
+	);
+}
+
+#[test]
+fn better_bibtex_patterns() {
+	assert_eq!(
+		generate("[authEtAl][year][titleword]"),
+		[
+			"VanderBerg2009Origin",
+			"DoeRoe2017Study",
+			"AcmeCorpEtAlReports",
+			"AnonAnonymous"
+		]
+	);
+	assert_eq!(
+		generate("[auth:upper]_[shortyear]_[shorttitle]"),
+		[
+			"VANDERBERG_09_OriginKeys",
+			"DOE_17_StudyPairs",
+			"ACMECORP__Reports",
+			"ANON__AnonymousWork"
+		]
+	);
+	assert_eq!(
+		generate("[authors]"),
+		["VanderBerg", "DoeRoe", "AcmeCorpDoeRoe", ""]
+	);
+}
+
+#[test]
+fn invalid_patterns() {
+	assert!("".parse::<KeyPattern>().is_err());
+	assert!("[auth".parse::<KeyPattern>().is_err());
+	assert!("auth]".parse::<KeyPattern>().is_err());
+	assert!("[editor]".parse::<KeyPattern>().is_err());
+	assert!("[auth:title]".parse::<KeyPattern>().is_err());
+	assert_eq!(
+		"key-[year]".parse::<KeyPattern>().unwrap().to_string(),
+		"key-[year]"
+	);
+}
+
+#[test]
+fn fill_missing() {
+	let mut items = from_str(
+		r#"[
+			{"id": "", "type": "book", "author": [{"family": "Doe"}], "issued": {"date-parts": [[2017]]}},
+			{"id": "doe2017", "type": "book", "author": [{"family": "Doe"}], "issued": {"date-parts": [[2017]]}},
+			{"id": "", "type": "book", "author": [{"family": "Doe"}], "issued": {"date-parts": [[2017]]}}
+		]"#,
+	)
+	.unwrap();
+
+	fill_missing_keys(&mut items, &mut KeyGenerator::default());
+	let ids: Vec<_> = items.iter().map(|item| item.id.as_str()).collect();
+	assert_eq!(ids, ["doe2017b", "doe2017", "doe2017c"]);
+}
@@ -0,0 +1,84 @@
+use citeworks_convert::{
+	latex::{
+		items_to_thebibliography, references_to_thebibliography,
+		references_to_thebibliography_with, LabelStyle, LatexOptions,
+	},
+	report::ConversionReport,
+};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::cff_file;
+
+#[test]
+fn numeric() {
+	assert_eq!(
+		references_to_thebibliography(
+			&cff_file("conference-paper").references,
+			&LatexOptions {
+				url_command: true,
+				..Default::default()
+			}
+		),
+		r"\begin{thebibliography}{9}
+
+\bibitem{doe2017}
+Doe, J. (2017). Ultimate-accuracy syntax parsing with My Research Tool. \emph{Proceedings of the 1st Conference on Wishful Thinking}, 42–45. \url{https://doi.org/10.5281/zenodo.1234}
+
+\end{thebibliography}
+"
+	);
+}
+
+#[test]
+fn author_year_sorted_and_escaped() {
+	let items = citeworks_csl::from_str(
+		r#"[{
+			"id": "roe2020",
+			"type": "webpage",
+			"author": [{"literal": "R&D Team"}],
+			"title": "100% of #things_here",
+			"URL": "https://example.com/~roe"
+		}, {
+			"id": "doe2017",
+			"type": "book",
+			"author": [{"family": "Doe", "given": "Jane"}, {"family": "Roe", "given": "Richard"}, {"family": "Poe", "given": "Edgar"}],
+			"title": "A book",
+			"issued": {"date-parts": [[2017]]}
+		}]"#,
+	)
+	.unwrap();
+
+	assert_eq!(
+		items_to_thebibliography(
+			&items,
+			&LatexOptions {
+				labels: LabelStyle::AuthorYear,
+				..Default::default()
+			}
+		),
+		r"\begin{thebibliography}{Doe et al. 2017}
+
+\bibitem[Doe et al. 2017]{doe2017}
+Doe, J., Roe, R., \& Poe, E. (2017). \emph{A book}.
+
+\bibitem[R\&D Team n.d.]{roe2020}
+R\&D Team. (n.d.). \emph{100\% of \#things\_here}. https://example.com/\textasciitilde{}roe
+
+\end{thebibliography}
+"
+	);
+}
+
+#[test]
+fn report() {
+	let references = cff_file("conference-paper").references;
+	let mut report = ConversionReport::default();
+	assert_eq!(
+		references_to_thebibliography_with(&references, &LatexOptions::default(), &mut report),
+		references_to_thebibliography(&references, &LatexOptions::default())
+	);
+	assert_eq!(report.records[0].id, "doe2017");
+	assert_eq!(report.records[0].dropped[0].field, "collection-doi");
+}
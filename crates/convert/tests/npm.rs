@@ -0,0 +1,118 @@
+use citeworks_cff::{
+	names::{EntityName, Name, NameMeta},
+	Date,
+};
+use citeworks_convert::npm::{npm_to_cff, update_cff_from_npm};
+
+use pretty_assertions::assert_eq;
+
+const RELEASED: Date = Date {
+	year: 2022,
+	month: 8,
+	day: 20,
+};
+
+#[test]
+fn package() {
+	let cff = npm_to_cff(
+		r#"{
+			"name": "@citeworks/wasm",
+			"version": "0.1.0",
+			"description": "citeworks in the browser",
+			"author": "Félix Saparelli <felix@passcod.name> (https://passcod.name)",
+			"contributors": [{"name": "Jane Roe", "email": "jane@example.com"}],
+			"license": "Apache-2.0",
+			"repository": {"type": "git", "url": "git+https://github.com/passcod/citeworks.git"},
+			"homepage": "https://citeworks.example.com",
+			"keywords": ["cff", "csl"]
+		}"#,
+		RELEASED,
+	)
+	.unwrap();
+
+	assert_eq!(cff.title, "@citeworks/wasm");
+	assert_eq!(cff.version.as_deref(), Some("0.1.0"));
+	assert_eq!(cff.date_released, Some(RELEASED));
+	assert_eq!(
+		cff.abstract_text.as_deref(),
+		Some("citeworks in the browser")
+	);
+	assert_eq!(
+		cff.license.unwrap().to_expression().to_string(),
+		"Apache-2.0"
+	);
+	assert_eq!(
+		cff.repository_code.unwrap().as_str(),
+		"https://github.com/passcod/citeworks"
+	);
+	assert_eq!(cff.url.unwrap().as_str(), "https://citeworks.example.com/");
+	assert_eq!(cff.keywords, vec!["cff", "csl"]);
+	assert_eq!(
+		cff.authors,
+		vec![
+			Name::Entity(EntityName {
+				name: Some("Félix Saparelli".into()),
+				meta: NameMeta {
+					email: Some("felix@passcod.name".into()),
+					website: Some("https://passcod.name".parse().unwrap()),
+					..Default::default()
+				},
+				..Default::default()
+			}),
+			Name::Entity(EntityName {
+				name: Some("Jane Roe".into()),
+				meta: NameMeta {
+					email: Some("jane@example.com".into()),
+					..Default::default()
+				},
+				..Default::default()
+			}),
+		]
+	);
+}
+
+#[test]
+fn repository_shorthand() {
+	for (repository, url) in [
+		("passcod/citeworks", "https://github.com/passcod/citeworks"),
+		("gitlab:someone/thing", "https://gitlab.com/someone/thing"),
+		(
+			"git://github.com/passcod/citeworks.git",
+			"https://github.com/passcod/citeworks",
+		),
+	] {
+		let cff = npm_to_cff(
+			&format!(r#"{{"name": "thing", "repository": "{repository}"}}"#),
+			RELEASED,
+		)
+		.unwrap();
+		assert_eq!(cff.repository_code.unwrap().as_str(), url, "{repository}");
+	}
+}
+
+#[test]
+fn no_name() {
+	assert_eq!(
+		npm_to_cff(r#"{"private": true}"#, RELEASED).unwrap_err(),
+		"package.json has no name"
+	);
+}
+
+#[test]
+fn update() {
+	let mut cff = npm_to_cff(r#"{"name": "thing", "version": "1.0.0"}"#, RELEASED).unwrap();
+	let later = Date {
+		year: 2022,
+		month: 9,
+		day: 1,
+	};
+
+	assert!(
+		!update_cff_from_npm(&mut cff, r#"{"name": "thing", "version": "1.0.0"}"#, later).unwrap()
+	);
+	assert!(
+		update_cff_from_npm(&mut cff, r#"{"name": "thing", "version": "1.1.0"}"#, later).unwrap()
+	);
+	assert_eq!(cff.version.as_deref(), Some("1.1.0"));
+	assert_eq!(cff.date_released, Some(later));
+}
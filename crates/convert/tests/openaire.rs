@@ -0,0 +1,113 @@
+use citeworks_cff::License;
+use citeworks_convert::{
+	openaire::{cff_to_openaire, cff_to_openaire_with, Obligation, OpenAireOptions},
+	report::ConversionReport,
+};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::cff_file;
+
+#[test]
+fn simple() {
+	let export = cff_to_openaire(
+		&cff_file("simple"),
+		&OpenAireOptions {
+			publisher: Some("Zenodo".into()),
+			..Default::default()
+		},
+	);
+
+	assert_eq!(
+		export.xml,
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<resource xmlns="http://datacite.org/schema/kernel-4" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://datacite.org/schema/kernel-4 http://schema.datacite.org/meta/kernel-4.1/metadata.xsd">
+  <identifier identifierType="DOI">10.5281/zenodo.1234</identifier>
+  <creators>
+    <creator>
+      <creatorName nameType="Personal">Druskat, Stephan</creatorName>
+      <givenName>Stephan</givenName>
+      <familyName>Druskat</familyName>
+      <nameIdentifier nameIdentifierScheme="ORCID" schemeURI="https://orcid.org">0000-0003-4925-7248</nameIdentifier>
+    </creator>
+  </creators>
+  <titles>
+    <title>My Research Software</title>
+  </titles>
+  <publisher>Zenodo</publisher>
+  <publicationYear>2017</publicationYear>
+  <resourceType resourceTypeGeneral="Software">Software</resourceType>
+  <dates>
+    <date dateType="Issued">2017-12-18</date>
+  </dates>
+  <version>2.0.4</version>
+  <rightsList>
+    <rights rightsURI="http://purl.org/coar/access_right/c_abf2">open access</rights>
+  </rightsList>
+</resource>
+"#
+	);
+
+	let missing: Vec<_> = export
+		.missing
+		.iter()
+		.map(|m| (m.property, m.obligation))
+		.collect();
+	assert_eq!(
+		missing,
+		vec![
+			("Rights", Obligation::Mandatory),
+			("Description", Obligation::MandatoryIfApplicable),
+			("Subject", Obligation::Recommended),
+		]
+	);
+}
+
+#[test]
+fn missing_mandatory() {
+	let export = cff_to_openaire(&cff_file("minimal"), &OpenAireOptions::default());
+	let mandatory: Vec<_> = export
+		.missing
+		.iter()
+		.filter(|m| m.obligation == Obligation::Mandatory)
+		.map(|m| m.property)
+		.collect();
+	assert_eq!(
+		mandatory,
+		vec!["Identifier", "Publisher", "PublicationYear", "Rights"]
+	);
+}
+
+#[test]
+fn escaping_and_license() {
+	let mut cff = cff_file("short");
+	cff.title = "Ruby <CFF> & friends".into();
+	let export = cff_to_openaire(&cff, &OpenAireOptions::default());
+	assert!(export
+		.xml
+		.contains("<title>Ruby &lt;CFF&gt; &amp; friends</title>"));
+	assert!(export.xml.contains(
+		r#"<rights rightsURI="https://spdx.org/licenses/Apache-2.0">Apache-2.0</rights>"#
+	));
+	assert!(export
+		.xml
+		.contains("<affiliation>The University of Manchester, UK</affiliation>"));
+
+	cff.license = Some(License::Unparsed("Proprietary".into()));
+	let export = cff_to_openaire(&cff, &OpenAireOptions::default());
+	assert!(export.xml.contains("<rights>Proprietary</rights>"));
+}
+
+#[test]
+fn report() {
+	let mut cff = cff_file("conference-paper");
+	cff.references[0].doi = None;
+
+	let mut report = ConversionReport::default();
+	cff_to_openaire_with(&cff, &OpenAireOptions::default(), &mut report);
+	assert_eq!(
+		report.to_string(),
+		"My Research Tool: dropped references[0]: no doi or url to relate to\n"
+	);
+}
@@ -0,0 +1,31 @@
+use citeworks_convert::{
+	csl::bare_doi,
+	options::{BadUrls, ConvertOptions, Preset},
+};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn presets() {
+	for name in ["zotero", "crossref", "strict-spec"] {
+		let preset: Preset = name.parse().unwrap();
+		assert_eq!(preset.to_string(), name);
+	}
+	assert!("citeproc".parse::<Preset>().is_err());
+
+	let strict = ConvertOptions::preset(Preset::StrictSpec);
+	assert_eq!(strict.bad_urls, BadUrls::Strict);
+	assert_eq!(strict.keyword_separators, "");
+	assert_eq!(
+		ConvertOptions::preset(Preset::Crossref).bad_urls,
+		BadUrls::Keep
+	);
+}
+
+#[test]
+fn dois() {
+	assert_eq!(bare_doi(" https://doi.org/10.1234/ABC "), "10.1234/ABC");
+	assert_eq!(bare_doi("DOI:10.1234/abc"), "10.1234/abc");
+	assert_eq!(bare_doi("http://dx.doi.org/10.1234/abc"), "10.1234/abc");
+	assert_eq!(bare_doi("10.1234/abc"), "10.1234/abc");
+}
@@ -0,0 +1,138 @@
+use citeworks_convert::pandoc::{bibliography_paths, filter, FilterReport};
+use serde_json::{json, Value};
+
+use pretty_assertions::assert_eq;
+
+fn citation(key: &str, mode: &str, suffix: Value) -> Value {
+	json!({
+		"citationId": key,
+		"citationPrefix": [],
+		"citationSuffix": suffix,
+		"citationMode": {"t": mode},
+		"citationNoteNum": 1,
+		"citationHash": 0
+	})
+}
+
+fn library() -> Vec<citeworks_csl::Item> {
+	citeworks_csl::from_str(
+		r#"[{
+			"id": "roe2020",
+			"type": "article-journal",
+			"author": [{"family": "Roe", "given": "Richard"}, {"family": "Doe", "given": "Jane"}, {"family": "Poe", "given": "Edgar Allan"}],
+			"title": "A paper",
+			"container-title": "Journal of Things",
+			"volume": 12,
+			"page": "42-45",
+			"issued": {"date-parts": [[2020, 3]]},
+			"DOI": "10.1234/things"
+		}, {
+			"id": "doe2017",
+			"type": "book",
+			"author": [{"family": "Doe", "given": "Jane"}],
+			"title": "A book",
+			"publisher": "Books Inc.",
+			"issued": {"date-parts": [[2017]]}
+		}]"#,
+	)
+	.unwrap()
+}
+
+#[test]
+fn citations_and_bibliography() {
+	let mut doc = json!({
+		"pandoc-api-version": [1, 23],
+		"meta": {"reference-section-title": {"t": "MetaString", "c": "References"}},
+		"blocks": [{"t": "Para", "c": [
+			{"t": "Cite", "c": [
+				[
+					citation("roe2020", "NormalCitation", json!([{"t": "Str", "c": ","}, {"t": "Space"}, {"t": "Str", "c": "p. 3"}])),
+					citation("doe2017", "SuppressAuthor", json!([])),
+				],
+				[{"t": "Str", "c": "[@roe2020, p. 3; -@doe2017]"}]
+			]},
+			{"t": "Space"},
+			{"t": "Cite", "c": [
+				[citation("doe2017", "AuthorInText", json!([]))],
+				[{"t": "Str", "c": "@doe2017"}]
+			]},
+			{"t": "Space"},
+			{"t": "Cite", "c": [
+				[citation("nope", "NormalCitation", json!([]))],
+				[{"t": "Str", "c": "[@nope]"}]
+			]}
+		]}]
+	});
+
+	let report = filter(&mut doc, &library());
+	assert_eq!(
+		report,
+		FilterReport {
+			cited: vec!["roe2020".into(), "doe2017".into()],
+			unresolved: vec!["nope".into()],
+		}
+	);
+
+	let para = &doc["blocks"][0]["c"];
+	assert_eq!(
+		para[0]["c"][1],
+		json!([
+			{"t": "Str", "c": "(Roe"}, {"t": "Space"},
+			{"t": "Str", "c": "et"}, {"t": "Space"},
+			{"t": "Str", "c": "al."}, {"t": "Space"},
+			{"t": "Str", "c": "2020,"}, {"t": "Space"},
+			{"t": "Str", "c": "p. 3;"}, {"t": "Space"},
+			{"t": "Str", "c": "2017)"}
+		])
+	);
+	assert_eq!(
+		para[2]["c"][1],
+		json!([{"t": "Str", "c": "Doe"}, {"t": "Space"}, {"t": "Str", "c": "(2017)"}])
+	);
+	assert_eq!(para[4]["c"][1], json!([{"t": "Str", "c": "[@nope]"}]));
+
+	assert_eq!(
+		doc["blocks"][1],
+		json!({"t": "Header", "c": [1, ["bibliography", ["unnumbered"], []], [{"t": "Str", "c": "References"}]]})
+	);
+	let refs = &doc["blocks"][2]["c"];
+	assert_eq!(refs[0], json!(["refs", ["references", "csl-bib-body"], []]));
+	assert_eq!(refs[1][0]["c"][0][0], "ref-doe2017");
+	assert_eq!(refs[1][1]["c"][0][0], "ref-roe2020");
+	assert_eq!(
+		refs[1][0]["c"][1][0]["c"],
+		json!([
+			{"t": "Str", "c": "Doe,"}, {"t": "Space"},
+			{"t": "Str", "c": "J."}, {"t": "Space"},
+			{"t": "Str", "c": "(2017)."}, {"t": "Space"},
+			{"t": "Emph", "c": [{"t": "Str", "c": "A"}, {"t": "Space"}, {"t": "Str", "c": "book"}]},
+			{"t": "Str", "c": "."}, {"t": "Space"},
+			{"t": "Str", "c": "Books"}, {"t": "Space"},
+			{"t": "Str", "c": "Inc."}
+		])
+	);
+}
+
+#[test]
+fn refs_div() {
+	let mut doc = json!({
+		"pandoc-api-version": [1, 23],
+		"meta": {"bibliography": {"t": "MetaList", "c": [
+			{"t": "MetaInlines", "c": [{"t": "Str", "c": "refs.json"}]},
+			{"t": "MetaString", "c": "CITATION.cff"}
+		]}},
+		"blocks": [
+			{"t": "Div", "c": [["refs", [], []], []]},
+			{"t": "Para", "c": [{"t": "Cite", "c": [
+				[citation("doe2017", "NormalCitation", json!([]))],
+				[{"t": "Str", "c": "[@doe2017]"}]
+			]}]}
+		]
+	});
+
+	assert_eq!(bibliography_paths(&doc), vec!["refs.json", "CITATION.cff"]);
+
+	filter(&mut doc, &library());
+	assert_eq!(doc["blocks"].as_array().unwrap().len(), 2);
+	assert_eq!(doc["blocks"][0]["c"][1][0]["c"][0][0], "ref-doe2017");
+}
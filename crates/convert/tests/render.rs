@@ -0,0 +1,94 @@
+use citeworks_convert::render::{
+	author_date, entry, entry_with, short_authors_with, to_plain, And, NameOptions, RenderOptions,
+	Terms,
+};
+use citeworks_csl::{from_str, Item};
+
+use pretty_assertions::assert_eq;
+
+fn authors(count: usize) -> Item {
+	let names = ["Doe", "Roe", "Poe", "Moe"][..count]
+		.iter()
+		.map(|family| format!(r#"{{"family": "{family}", "given": "Jo"}}"#))
+		.collect::<Vec<_>>()
+		.join(", ");
+	from_str(&format!(
+		r#"[{{"id": "x", "type": "book", "title": "Things", "author": [{names}], "issued": {{"date-parts": [[2017]]}}}}]"#
+	))
+	.unwrap()
+	.remove(0)
+}
+
+#[test]
+fn defaults() {
+	assert_eq!(author_date(&authors(1)), "Doe 2017");
+	assert_eq!(author_date(&authors(2)), "Doe and Roe 2017");
+	assert_eq!(author_date(&authors(3)), "Doe et al. 2017");
+	assert_eq!(
+		to_plain(&entry(&authors(2))),
+		"Doe, J., & Roe, J. (2017). Things."
+	);
+	assert_eq!(
+		to_plain(&entry(&authors(4))),
+		"Doe, J., Roe, J., Poe, J., & Moe, J. (2017). Things."
+	);
+}
+
+#[test]
+fn citation_et_al() {
+	let options = RenderOptions {
+		citation: NameOptions {
+			et_al_min: Some(4),
+			et_al_use_first: 2,
+			and: And::None,
+		},
+		..Default::default()
+	};
+	assert_eq!(short_authors_with(&authors(2), &options), "Doe, Roe");
+	assert_eq!(short_authors_with(&authors(3), &options), "Doe, Roe, Poe");
+	assert_eq!(short_authors_with(&authors(4), &options), "Doe, Roe et al.");
+
+	let never = RenderOptions {
+		citation: NameOptions {
+			et_al_min: None,
+			et_al_use_first: 1,
+			and: And::Text,
+		},
+		terms: Terms::locale("fr-CA").unwrap(),
+		..Default::default()
+	};
+	assert_eq!(
+		short_authors_with(&authors(4), &never),
+		"Doe, Roe, Poe, et Moe"
+	);
+}
+
+#[test]
+fn bibliography_et_al() {
+	let options = RenderOptions {
+		bibliography: NameOptions {
+			et_al_min: Some(3),
+			et_al_use_first: 2,
+			and: And::Text,
+		},
+		terms: Terms::locale("de").unwrap(),
+		..Default::default()
+	};
+	assert_eq!(
+		to_plain(&entry_with(&authors(2), &options)),
+		"Doe, J., und Roe, J. (2017). Things."
+	);
+	assert_eq!(
+		to_plain(&entry_with(&authors(3), &options)),
+		"Doe, J., Roe, J., u. a. (2017). Things."
+	);
+}
+
+#[test]
+fn terms() {
+	assert_eq!(Terms::locale("en-GB"), Some(Terms::default()));
+	assert_eq!(Terms::locale("nl").unwrap().et_al, "e.a.");
+	assert_eq!(Terms::locale("tlh"), None);
+	assert_eq!("symbol".parse(), Ok(And::Symbol));
+	assert!("ampersand".parse::<And>().is_err());
+}
@@ -0,0 +1,32 @@
+use citeworks_convert::report::{ConversionReport, RecordReport};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn record_by_id() {
+	let mut report = ConversionReport::default();
+	report.record("a").warn("first");
+	report.push(RecordReport::new("b"));
+	report.record("b").warn("second");
+	report.record("a").warn("third");
+	assert_eq!(report.records.len(), 2);
+	assert_eq!(report.records[0].warnings, vec!["first", "third"]);
+	assert_eq!(report.records[1].warnings, vec!["second"]);
+}
+
+#[test]
+fn record_after_changes() {
+	let mut report = ConversionReport::default();
+	report.record("a").warn("first");
+	report.record("b").warn("second");
+
+	report.records.remove(0);
+	report.record("b").warn("third");
+	assert_eq!(report.records.len(), 1);
+	assert_eq!(report.records[0].warnings, vec!["second", "third"]);
+
+	report.records.push(RecordReport::new("c"));
+	report.record("c").warn("fourth");
+	assert_eq!(report.records.len(), 2);
+	assert_eq!(report.records[1].warnings, vec!["fourth"]);
+}
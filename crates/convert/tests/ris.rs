@@ -0,0 +1,122 @@
+use citeworks_cff::references::RefType;
+use citeworks_convert::ris::{
+	item_to_record, record_to_item, record_to_reference, references_to_records,
+};
+use citeworks_csl::{dates::Date, items::ItemType, names::Name};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::cff_file;
+
+const ZOTERO: &str = "\
+TY  - JOUR
+TI  - 3D printed optics with nanometer scale surface roughness
+AU  - Vaidya, Nina
+AU  - Solgaard, Olav
+T2  - Microsystems & Nanoengineering
+DA  - 2018/07/16/
+PY  - 2018
+DO  - 10.1038/s41378-018-0015-4
+VL  - 4
+IS  - 1
+SP  - 1
+EP  - 8
+SN  - 2055-7434
+UR  - https://www.nature.com/articles/s41378-018-0015-4
+AN  - 30057787
+KW  - optics
+KW  - printing
+L1  - files/12/Vaidya.pdf
+L1  - files/13/Supplement.pdf
+ER  -
+";
+
+#[test]
+fn through_csl_and_back() {
+	let records = citeworks_ris::from_str(ZOTERO).unwrap();
+	let item = record_to_item(&records[0]);
+	assert_eq!(item.item_type, ItemType::ArticleJournal);
+	assert_eq!(
+		item.author[1],
+		Name {
+			family: Some("Solgaard".into()),
+			given: Some("Olav".into()),
+			..Default::default()
+		}
+	);
+	assert!(matches!(
+		item.issued,
+		Some(Date::Single { date, .. }) if date.month == Some(7) && date.day == Some(16)
+	));
+	assert_eq!(item.page.as_ref().unwrap().to_string(), "1-8");
+	assert_eq!(item.issn.as_ref().unwrap().to_string(), "2055-7434");
+	assert_eq!(item.fields["keyword"], ordinary("optics, printing"));
+	assert_eq!(item.fields["AN"], ordinary("30057787"));
+	assert_eq!(
+		item.fields["L1"],
+		ordinary("files/12/Vaidya.pdf\nfiles/13/Supplement.pdf")
+	);
+	assert!(!item.fields.contains_key("PY"));
+
+	assert_eq!(
+		citeworks_ris::to_string(&[item_to_record(&item)]),
+		"\
+TY  - JOUR
+AU  - Vaidya, Nina
+AU  - Solgaard, Olav
+TI  - 3D printed optics with nanometer scale surface roughness
+T2  - Microsystems & Nanoengineering
+PY  - 2018
+DA  - 2018/07/16/
+VL  - 4
+IS  - 1
+SP  - 1
+EP  - 8
+SN  - 2055-7434
+DO  - 10.1038/s41378-018-0015-4
+UR  - https://www.nature.com/articles/s41378-018-0015-4
+KW  - optics
+KW  - printing
+AN  - 30057787
+L1  - files/12/Vaidya.pdf
+L1  - files/13/Supplement.pdf
+ER  - 
+"
+	);
+}
+
+#[test]
+fn isbns_and_literal_names() {
+	let records = citeworks_ris::from_str(
+		"TY  - BOOK\nAU  - Kaánapali Labs\nBT  - A Book\nSN  - 978-3-16-148410-0\nER  -\n",
+	)
+	.unwrap();
+	let item = record_to_item(&records[0]);
+	assert_eq!(item.item_type, ItemType::Book);
+	assert_eq!(item.author[0].literal.as_deref(), Some("Kaánapali Labs"));
+	assert_eq!(item.title.as_ref().unwrap().to_string(), "A Book");
+	assert_eq!(item.issn, None);
+	assert_eq!(item.fields["ISBN"], ordinary("978-3-16-148410-0"));
+}
+
+#[test]
+fn references() {
+	let records = references_to_records(&cff_file("conference-paper").references);
+	assert_eq!(records.len(), 1);
+	assert_eq!(records[0].record_type, "CPAPER");
+	assert_eq!(records[0].get("AU"), Some("Doe, Jane"));
+
+	let reference = record_to_reference(&records[0]).unwrap();
+	assert_eq!(reference.work_type, RefType::ConferencePaper);
+	assert_eq!(
+		reference.title,
+		cff_file("conference-paper").references[0].title
+	);
+}
+
+fn ordinary(s: &str) -> citeworks_csl::items::ItemValue {
+	citeworks_csl::items::ItemValue::Ordinary(citeworks_csl::ordinaries::OrdinaryValue::String(
+		s.into(),
+	))
+}
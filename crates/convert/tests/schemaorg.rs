@@ -0,0 +1,137 @@
+use citeworks_cff::references::{RefType, Reference};
+use citeworks_convert::{
+	report::ConversionReport,
+	schemaorg::{cff_to_jsonld, cff_to_jsonld_with, html_script, reference_to_jsonld},
+};
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+mod common;
+use common::cff_file;
+
+#[test]
+fn simple() {
+	assert_eq!(
+		cff_to_jsonld(&cff_file("simple")),
+		json!({
+			"@context": "https://schema.org",
+			"@type": "SoftwareSourceCode",
+			"@id": "https://doi.org/10.5281/zenodo.1234",
+			"identifier": "https://doi.org/10.5281/zenodo.1234",
+			"name": "My Research Software",
+			"version": "2.0.4",
+			"datePublished": "2017-12-18",
+			"author": [{
+				"@type": "Person",
+				"@id": "https://orcid.org/0000-0003-4925-7248",
+				"givenName": "Stephan",
+				"familyName": "Druskat",
+			}],
+		})
+	);
+}
+
+#[test]
+fn short_license() {
+	let jsonld = cff_to_jsonld(&cff_file("short"));
+	assert_eq!(jsonld["license"], "https://spdx.org/licenses/Apache-2.0");
+	assert_eq!(jsonld["keywords"], json!(["ruby", "credit", "citation"]));
+	assert_eq!(
+		jsonld["author"][0]["affiliation"],
+		json!({ "@type": "Organization", "name": "The University of Manchester, UK" })
+	);
+}
+
+#[test]
+fn conference_paper_citation() {
+	let jsonld = cff_to_jsonld(&cff_file("conference-paper"));
+	let citation = &jsonld["citation"][0];
+	assert_eq!(citation["@type"], "ScholarlyArticle");
+	assert_eq!(
+		citation["name"],
+		"Ultimate-accuracy syntax parsing with My Research Tool"
+	);
+	assert_eq!(citation["datePublished"], "2017");
+	assert_eq!(citation["pageStart"], 42);
+	assert_eq!(citation["editor"][0]["familyName"], "Kirk");
+	assert_eq!(
+		citation["isPartOf"],
+		json!({
+			"@type": "Collection",
+			"name": "Proceedings of the 1st Conference on Wishful Thinking",
+		})
+	);
+}
+
+#[test]
+fn journal_article() {
+	let jsonld = reference_to_jsonld(&Reference {
+		work_type: RefType::Article,
+		title: Some("3D printed optics".into()),
+		journal: Some("Microsyst Nanoeng".into()),
+		issn: Some("2055-7434".into()),
+		volume: Some(4),
+		issue: Some("1".into()),
+		..Default::default()
+	});
+	assert_eq!(jsonld["@context"], "https://schema.org");
+	assert_eq!(
+		jsonld["isPartOf"],
+		json!({
+			"@type": "PublicationIssue",
+			"issueNumber": "1",
+			"isPartOf": {
+				"@type": "PublicationVolume",
+				"volumeNumber": "4",
+				"isPartOf": {
+					"@type": "Periodical",
+					"name": "Microsyst Nanoeng",
+					"issn": "2055-7434",
+				},
+			},
+		})
+	);
+}
+
+#[test]
+fn volume_without_issue() {
+	let jsonld = reference_to_jsonld(&Reference {
+		work_type: RefType::Article,
+		title: Some("3D printed optics".into()),
+		journal: Some("Microsyst Nanoeng".into()),
+		volume: Some(4),
+		..Default::default()
+	});
+	assert_eq!(
+		jsonld["isPartOf"],
+		json!({
+			"@type": "PublicationVolume",
+			"volumeNumber": "4",
+			"isPartOf": { "@type": "Periodical", "name": "Microsyst Nanoeng" },
+		})
+	);
+}
+
+#[test]
+fn script_tag() {
+	let script = html_script(&json!({ "name": "</script><b>" }));
+	assert!(script.starts_with("<script type=\"application/ld+json\">\n"));
+	assert!(script.ends_with("\n</script>"));
+	assert!(script.contains(r#""<\/script><b>""#));
+}
+
+#[test]
+fn report() {
+	let mut cff = cff_file("conference-paper");
+	cff.commit = Some("1ff847d81f29c45a3a1a5ce73d38e45c2f319bba".into());
+
+	let mut report = ConversionReport::default();
+	assert_eq!(cff_to_jsonld_with(&cff, &mut report), cff_to_jsonld(&cff));
+	assert_eq!(
+		report.to_string(),
+		"My Research Tool: dropped commit: no schema.org equivalent\n\
+		references[0]: dropped collection-doi: no schema.org equivalent\n\
+		references[0]: dropped conference: no schema.org equivalent\n"
+	);
+}
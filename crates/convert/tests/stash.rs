@@ -0,0 +1,97 @@
+use citeworks_cff::references::{RefType, Reference};
+use citeworks_convert::{
+	csl::reference_to_item,
+	stash::{restore, stash, MARKER},
+};
+use citeworks_csl::{ordinaries::OrdinaryValue, Item};
+
+use pretty_assertions::assert_eq;
+
+fn original() -> Item {
+	citeworks_csl::from_str(
+		r#"[{
+			"id": "doe2017",
+			"type": "chapter",
+			"title": "A chapter",
+			"note": "Read this first.",
+			"publisher-place": "Wellington",
+			"issued": {"date-parts": [[2017]], "season": "spring"}
+		}]"#,
+	)
+	.unwrap()
+	.remove(0)
+}
+
+/// A lossy conversion, like csl2cff does.
+fn converted() -> Reference {
+	Reference {
+		work_type: RefType::Book,
+		title: Some("A chapter".into()),
+		notes: Some("Read this first.".into()),
+		year: Some(2017),
+		..Default::default()
+	}
+}
+
+#[test]
+fn round_trip() {
+	let mut reference = converted();
+	stash(&mut reference, &original());
+
+	let notes = reference.notes.clone().unwrap();
+	let (before, line) = notes.split_once("\n\n").unwrap();
+	assert_eq!(before, "Read this first.");
+	assert!(line.starts_with(MARKER), "{line}");
+
+	let mut item = reference_to_item(&reference, "generated".into());
+	assert!(restore(&mut item).unwrap());
+	assert_eq!(item, original());
+}
+
+#[test]
+fn removes_added_variables() {
+	let mut reference = converted();
+	reference.doi = Some("10.1234/not-in-the-original".into());
+	stash(&mut reference, &original());
+
+	let mut item = reference_to_item(&reference, "generated".into());
+	assert!(restore(&mut item).unwrap());
+	assert_eq!(item.doi, None);
+}
+
+#[test]
+fn restash_replaces() {
+	let mut reference = converted();
+	stash(&mut reference, &original());
+	stash(&mut reference, &original());
+	assert_eq!(reference.notes.unwrap().matches(MARKER).count(), 1);
+}
+
+#[test]
+fn nothing_stashed() {
+	let mut item = reference_to_item(&converted(), "doe2017".into());
+	let before = item.clone();
+	assert!(!restore(&mut item).unwrap());
+	assert_eq!(item, before);
+	assert_eq!(
+		item.note,
+		Some(OrdinaryValue::String("Read this first.".into()))
+	);
+}
+
+#[test]
+fn invalid_stash() {
+	let mut reference = converted();
+	reference.notes = Some(format!(
+		"Read this first.\n\n{MARKER}{{\"issued\": \"soon\"}}"
+	));
+
+	let mut item = reference_to_item(&reference, "doe2017".into());
+	let before = item.clone();
+	let err = restore(&mut item).unwrap_err();
+	assert!(
+		err.starts_with("invalid stashed variables for doe2017"),
+		"{err}"
+	);
+	assert_eq!(item, before);
+}
@@ -0,0 +1,199 @@
+use citeworks_convert::{
+	keys::KeyGenerator,
+	table::{
+		items_to_table, references_to_table, table_to_items, Column, Delimiter, ImportOptions,
+		TableOptions,
+	},
+};
+use citeworks_csl::from_str;
+
+use pretty_assertions::assert_eq;
+
+fn items() -> Vec<citeworks_csl::Item> {
+	from_str(
+		r#"[
+		{"id": "doe2017", "type": "article-journal", "title": "Say \"hello\"",
+		 "author": [{"family": "Doe", "given": "Jane"}, {"given": "Ludwig", "non-dropping-particle": "van", "family": "Beethoven"}],
+		 "container-title": "Journal", "issued": {"date-parts": [[2017, 3, 9]]}, "DOI": "10.1000/1"},
+		{"id": "acme", "type": "software", "title": "Tabs\tand\nlines",
+		 "author": [{"literal": "Acme"}], "issued": {"raw": "spring 2020"}, "version": "1.0"}
+	]"#,
+	)
+	.unwrap()
+}
+
+#[test]
+fn default_columns() {
+	assert_eq!(
+		items_to_table(&items(), &TableOptions::default()),
+		"id,type,author,year,title,container-title,publisher,DOI,URL\n\
+		doe2017,article-journal,\"Doe, Jane; van Beethoven, Ludwig\",2017,\"Say \"\"hello\"\"\",Journal,,10.1000/1,\n\
+		acme,software,Acme,,\"Tabs\tand\nlines\",,,,\n"
+	);
+}
+
+#[test]
+fn tsv() {
+	let options = TableOptions {
+		columns: vec![
+			Column::Variable("id".into()),
+			Column::Variable("issued".into()),
+			Column::Variable("version".into()),
+			Column::Citation,
+			Column::Variable("title".into()),
+		],
+		delimiter: Delimiter::Tsv,
+		header: false,
+		escape_formulas: true,
+	};
+	assert_eq!(
+		items_to_table(&items(), &options),
+		"doe2017\t2017-03-09\t\tDoe and van Beethoven 2017\tSay \"hello\"\n\
+		acme\tspring 2020\t1.0\tAcme n.d.\tTabs and lines\n"
+	);
+}
+
+#[test]
+fn formulas() {
+	let items = from_str(
+		r#"[{"id": "x", "type": "book", "title": "=HYPERLINK(\"http://example.com\",\"Hi\")", "note": "-2+3"}]"#,
+	)
+	.unwrap();
+	let mut options = TableOptions {
+		columns: vec![
+			Column::Variable("id".into()),
+			Column::Variable("title".into()),
+			Column::Variable("note".into()),
+		],
+		header: false,
+		..Default::default()
+	};
+	assert_eq!(
+		items_to_table(&items, &options),
+		"x,\"'=HYPERLINK(\"\"http://example.com\"\",\"\"Hi\"\")\",'-2+3\n"
+	);
+
+	options.escape_formulas = false;
+	assert_eq!(
+		items_to_table(&items, &options),
+		"x,\"=HYPERLINK(\"\"http://example.com\"\",\"\"Hi\"\")\",-2+3\n"
+	);
+}
+
+#[test]
+fn columns() {
+	assert_eq!("DOI".parse(), Ok(Column::Variable("DOI".into())));
+	assert_eq!("entry".parse(), Ok(Column::Entry));
+	assert!("doi".parse::<Column>().is_err());
+	assert_eq!("tsv".parse(), Ok(Delimiter::Tsv));
+}
+
+#[test]
+fn references() {
+	let cff = citeworks_cff::from_str(
+		"cff-version: 1.2.0
+message: Cite.
+title: Example
+authors:
+  - name: Acme
+references:
+  - type: book
+    title: Things
+    authors:
+      - family-names: Doe
+        given-names: Jane
+    year: 2017
+",
+	)
+	.unwrap();
+	let options = TableOptions {
+		columns: vec![
+			Column::Variable("title".into()),
+			Column::Year,
+			Column::Entry,
+		],
+		..Default::default()
+	};
+	assert_eq!(
+		references_to_table(&cff.references, &options),
+		"title,year,entry\nThings,2017,\"Doe, J. (2017). Things.\"\n"
+	);
+}
+
+#[test]
+fn import() {
+	let mut options = ImportOptions::default();
+	options
+		.mapping
+		.insert("Authors".into(), Column::Variable("author".into()));
+	let (items, errors) = table_to_items(
+		"\u{feff}Authors,title,type,issued,Notes,year\r\n\
+		\"Doe, Jane; Acme\",\"Say \"\"hello\"\"\",book,2017-03,\"multi\nline\",1999\r\n\
+		,,,,,\r\n\
+		Roe,Stuff,book,,,2020\r\n\
+		Poe,Dates,,spring 2020,,\r\n\
+		Moe,Years,,,,twenty\r\n\
+		Zoe,Extra,,,,,surplus\r\n\
+		Loe,Range,report,2019/2020-01,,\r\n",
+		&options,
+		&mut KeyGenerator::default(),
+	)
+	.unwrap();
+
+	assert_eq!(
+		errors.iter().map(|error| error.row).collect::<Vec<_>>(),
+		vec![6, 7]
+	);
+	assert_eq!(
+		errors[0].to_string(),
+		"row 6: year should be a number, got: \"twenty\""
+	);
+
+	assert_eq!(
+		items_to_table(
+			&items,
+			&TableOptions {
+				columns: ["id", "type", "author", "issued", "title"]
+					.iter()
+					.map(|column| column.parse().unwrap())
+					.collect(),
+				..Default::default()
+			}
+		),
+		"id,type,author,issued,title\n\
+		doe2017,book,\"Doe, Jane; Acme\",2017-03,\"Say \"\"hello\"\"\"\n\
+		roe2020,book,Roe,2020,Stuff\n\
+		poe,document,Poe,spring 2020,Dates\n\
+		loe2019,report,Loe,2019/2020-01,Range\n"
+	);
+}
+
+#[test]
+fn round_trip() {
+	let options = TableOptions {
+		delimiter: Delimiter::Tsv,
+		..Default::default()
+	};
+	let table = items_to_table(&items(), &options);
+	let (items, errors) = table_to_items(
+		&table,
+		&ImportOptions {
+			delimiter: Delimiter::Tsv,
+			..Default::default()
+		},
+		&mut KeyGenerator::default(),
+	)
+	.unwrap();
+	assert_eq!(errors, vec![]);
+	assert_eq!(items_to_table(&items, &options), table);
+}
+
+#[test]
+fn unterminated_quote() {
+	assert!(table_to_items(
+		"title\n\"Things\n",
+		&ImportOptions::default(),
+		&mut KeyGenerator::default()
+	)
+	.is_err());
+}
@@ -0,0 +1,27 @@
+use citeworks_convert::text::plain_text;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn crossref_abstract() {
+	assert_eq!(
+		plain_text(
+			"<jats:sec>\n  <jats:title>Abstract</jats:title>\n  <jats:p>Deep learning allows\n  computational models&#x2013;to learn.</jats:p>\n</jats:sec>\n<jats:sec>\n  <jats:title>Methods</jats:title>\n  <jats:p>Using x<jats:sup>2</jats:sup> &lt; 3.</jats:p>\n</jats:sec>"
+		),
+		"Deep learning allows computational models–to learn.\n\nMethods\n\nUsing x2 < 3."
+	);
+}
+
+#[test]
+fn html() {
+	assert_eq!(
+		plain_text("<p>One<br/>Two</p><p>A &amp; B &unknown; C</p>"),
+		"One\n\nTwo\n\nA & B &unknown; C"
+	);
+}
+
+#[test]
+fn not_markup() {
+	assert_eq!(plain_text("  a < b and c > d  "), "a < b and c > d");
+	assert_eq!(plain_text("Abstract"), "Abstract");
+}
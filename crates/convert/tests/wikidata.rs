@@ -0,0 +1,66 @@
+use citeworks_convert::{
+	report::ConversionReport,
+	wikidata::{cff_to_quickstatements, cff_to_statements_with, Qid, WikidataOptions},
+};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::cff_file;
+
+#[test]
+fn create() {
+	assert_eq!(
+		cff_to_quickstatements(&cff_file("simple"), &WikidataOptions::default()),
+		"CREATE
+LAST\tLen\t\"My Research Software\"
+LAST\tP31\tQ7397
+LAST\tP1476\ten:\"My Research Software\"
+LAST\tP2093\t\"Stephan Druskat\"\tP1545\t\"1\"\tP496\t\"0000-0003-4925-7248\"
+LAST\tP348\t\"2.0.4\"\tP577\t+2017-12-18T00:00:00Z/11
+LAST\tP577\t+2017-12-18T00:00:00Z/11
+LAST\tP356\t\"10.5281/ZENODO.1234\"
+"
+	);
+}
+
+#[test]
+fn update_with_license() {
+	let commands = cff_to_quickstatements(
+		&cff_file("short"),
+		&WikidataOptions {
+			item: Some(Qid(42)),
+			licenses: vec![Qid(13785927)],
+			..Default::default()
+		},
+	);
+
+	assert!(!commands.contains("CREATE"));
+	assert!(commands.starts_with("Q42\tP31\tQ7397\n"));
+	assert!(commands.contains("Q42\tP275\tQ13785927\n"));
+	assert!(commands.contains("Q42\tP348\t\"0.4.0\"\tP577\t+2018-07-22T00:00:00Z/11\n"));
+}
+
+#[test]
+fn qid_parsing() {
+	assert_eq!("Q42".parse(), Ok(Qid(42)));
+	assert_eq!("http://www.wikidata.org/entity/Q42".parse(), Ok(Qid(42)));
+	assert_eq!("https://www.wikidata.org/wiki/Q42".parse(), Ok(Qid(42)));
+	assert!("P31".parse::<Qid>().is_err());
+	assert_eq!(Qid(42).uri(), "http://www.wikidata.org/entity/Q42");
+}
+
+#[test]
+fn report() {
+	let mut cff = cff_file("simple");
+	cff.keywords.push("citation".into());
+	cff.license = Some("MIT".parse().unwrap());
+
+	let mut report = ConversionReport::default();
+	cff_to_statements_with(&cff, &WikidataOptions::default(), &mut report);
+	assert_eq!(
+		report.to_string(),
+		"My Research Software: dropped keywords: no Wikidata equivalent\n\
+		My Research Software: dropped license: no QIDs given for the license\n"
+	);
+}
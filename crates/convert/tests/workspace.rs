@@ -0,0 +1,88 @@
+use citeworks_cff::{
+	references::{RefType, Reference},
+	Cff,
+};
+use citeworks_convert::workspace::{
+	glob_match, package_reference, set_package_references, Workspace, WorkspaceKind,
+};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn npm_workspaces() {
+	let workspace = Workspace::from_npm(
+		r#"{
+			"name": "root",
+			"private": true,
+			"workspaces": ["packages/*", "!packages/scratch", "tools/cli"]
+		}"#,
+	)
+	.unwrap()
+	.unwrap();
+
+	assert_eq!(workspace.kind, WorkspaceKind::Npm);
+	assert!(workspace.includes("packages/core"));
+	assert!(workspace.includes("tools/cli"));
+	assert!(!workspace.includes("packages/scratch"));
+	assert!(!workspace.includes("tools"));
+
+	let yarn = Workspace::from_npm(r#"{"workspaces": {"packages": ["packages/*"]}}"#)
+		.unwrap()
+		.unwrap();
+	assert_eq!(yarn.members, vec!["packages/*"]);
+
+	assert_eq!(Workspace::from_npm(r#"{"name": "single"}"#).unwrap(), None);
+}
+
+#[test]
+fn not_a_cargo_workspace() {
+	assert_eq!(
+		Workspace::from_cargo("[package]\nname = \"single\"\n").unwrap(),
+		None
+	);
+}
+
+#[test]
+fn globs() {
+	assert!(glob_match("crates/*", "crates/cff"));
+	assert!(glob_match("./crates/*/", "crates/cff"));
+	assert!(glob_match("crates/cite?orks-*", "crates/citeworks-cff"));
+	assert!(glob_match("**/cli", "tools/bin/cli"));
+	assert!(glob_match("**/cli", "cli"));
+	assert!(!glob_match("crates/*", "crates/cff/tests"));
+	assert!(!glob_match("crates/c*", "crates/bibtex"));
+}
+
+#[test]
+fn references_replaced_by_title() {
+	let package = Cff {
+		title: "citeworks-cff".into(),
+		version: Some("0.2.0".into()),
+		..Default::default()
+	};
+	let paper = Reference {
+		work_type: RefType::Article,
+		title: Some("citeworks-cff".into()),
+		..Default::default()
+	};
+	let mut root = Cff {
+		title: "citeworks".into(),
+		references: vec![
+			paper.clone(),
+			Reference {
+				work_type: RefType::Software,
+				title: Some("citeworks-cff".into()),
+				version: Some("0.1.1".into()),
+				..Default::default()
+			},
+		],
+		..Default::default()
+	};
+
+	assert!(set_package_references(
+		&mut root,
+		std::slice::from_ref(&package)
+	));
+	assert_eq!(root.references, vec![paper, package_reference(&package)]);
+	assert_eq!(root.references[1].version.as_deref(), Some("0.2.0"));
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "xlsx")]
+
+use std::io::{Cursor, Read};
+
+use citeworks_convert::table::{items_to_xlsx, TableOptions};
+use citeworks_csl::from_str;
+
+fn part(xlsx: &[u8], name: &str) -> String {
+	let mut archive = zip::ZipArchive::new(Cursor::new(xlsx)).unwrap();
+	let mut text = String::new();
+	archive
+		.by_name(name)
+		.unwrap()
+		.read_to_string(&mut text)
+		.unwrap();
+	text
+}
+
+fn items() -> Vec<citeworks_csl::Item> {
+	from_str(
+		r#"[
+		{"id": "doe2017", "type": "article-journal", "title": "Ünïcödé",
+		 "author": [{"family": "Doe", "given": "Jane"}], "volume": "12", "issue": "007",
+		 "issued": {"date-parts": [[2017, 3, 9]]}, "accessed": {"date-parts": [[2020, 5]]},
+		 "DOI": "10.1000/1"}
+	]"#,
+	)
+	.unwrap()
+}
+
+#[test]
+fn typed_cells() {
+	let options = TableOptions {
+		columns: "id,title,year,volume,issue,issued,accessed,DOI"
+			.split(',')
+			.map(|c| c.parse().unwrap())
+			.collect(),
+		..Default::default()
+	};
+	let xlsx = items_to_xlsx(&items(), &options).unwrap();
+	assert!(xlsx.starts_with(b"PK"));
+
+	let sheet = part(&xlsx, "xl/worksheets/sheet1.xml");
+	let strings = part(&xlsx, "xl/sharedStrings.xml");
+	let links = part(&xlsx, "xl/worksheets/_rels/sheet1.xml.rels");
+
+	assert!(strings.contains("Ünïcödé"));
+	// year and volume are numbers, the issue with a leading zero is text
+	assert!(sheet.contains(r#"<c r="C2"><v>2017</v></c>"#), "{sheet}");
+	assert!(sheet.contains(r#"<c r="D2"><v>12</v></c>"#), "{sheet}");
+	assert!(sheet.contains(r#"<c r="E2" t="s">"#), "{sheet}");
+	// the full date is a date, the partial one is text
+	assert!(sheet.contains("<v>42803</v>"), "{sheet}");
+	assert!(strings.contains("2020-05"));
+	assert!(
+		links.contains(r#"Target="https://doi.org/10.1000/1""#),
+		"{links}"
+	);
+}
+
+#[test]
+fn no_header() {
+	let options = TableOptions {
+		header: false,
+		..Default::default()
+	};
+	let xlsx = items_to_xlsx(&items(), &options).unwrap();
+	let sheet = part(&xlsx, "xl/worksheets/sheet1.xml");
+	assert!(sheet.contains(r#"<c r="A1" t="s">"#));
+	assert!(!sheet.contains(r#"r="A2""#));
+	assert!(!part(&xlsx, "xl/sharedStrings.xml").contains(">id<"));
+}
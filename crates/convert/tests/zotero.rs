@@ -0,0 +1,159 @@
+use citeworks_convert::{keys::KeyGenerator, zotero::rdf_to_items};
+use citeworks_csl::items::ItemType;
+
+use pretty_assertions::assert_eq;
+
+const EXPORT: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<rdf:RDF
+ xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+ xmlns:z="http://www.zotero.org/namespaces/export#"
+ xmlns:dcterms="http://purl.org/dc/terms/"
+ xmlns:bib="http://purl.org/net/biblio#"
+ xmlns:foaf="http://xmlns.com/foaf/0.1/"
+ xmlns:link="http://purl.org/rss/1.0/modules/link/"
+ xmlns:dc="http://purl.org/dc/elements/1.1/"
+ xmlns:vcard="http://nwalsh.com/rdf/vCard#"
+ xmlns:prism="http://prismstandard.org/namespaces/1.2/basic/">
+    <rdf:Description rdf:about="urn:isbn:978-3-16-148410-0">
+        <z:itemType>bookSection</z:itemType>
+        <dcterms:isPartOf>
+            <bib:Book rdf:about="urn:isbn:978-3-16-148410-0">
+                <dcterms:isPartOf>
+                    <bib:Series><dc:title>Lecture Notes</dc:title></bib:Series>
+                </dcterms:isPartOf>
+                <dc:identifier>ISBN 978-3-16-148410-0</dc:identifier>
+                <dc:title>Tools &amp; Methods</dc:title>
+            </bib:Book>
+        </dcterms:isPartOf>
+        <dcterms:isPartOf>
+            <bib:Series><dc:title>Lecture Notes</dc:title><dc:identifier>42</dc:identifier></bib:Series>
+        </dcterms:isPartOf>
+        <dc:publisher>
+            <foaf:Organization>
+                <vcard:adr><vcard:Address><vcard:locality>Berlin</vcard:locality></vcard:Address></vcard:adr>
+                <foaf:name>Springer</foaf:name>
+            </foaf:Organization>
+        </dc:publisher>
+        <bib:authors>
+            <rdf:Seq>
+                <rdf:li><foaf:Person><foaf:surname>Doe</foaf:surname><foaf:givenName>Jane</foaf:givenName></foaf:Person></rdf:li>
+                <rdf:li><foaf:Person><foaf:surname>Acme Lab</foaf:surname></foaf:Person></rdf:li>
+            </rdf:Seq>
+        </bib:authors>
+        <bib:editors>
+            <rdf:Seq>
+                <rdf:li><foaf:Person><foaf:surname>Roe</foaf:surname><foaf:givenName>Jo</foaf:givenName></foaf:Person></rdf:li>
+            </rdf:Seq>
+        </bib:editors>
+        <link:link rdf:resource="#item_2"/>
+        <dc:subject>citation</dc:subject>
+        <dc:subject><z:AutomaticTag><rdf:value>software</rdf:value></z:AutomaticTag></dc:subject>
+        <dc:subject><dcterms:LCC><rdf:value>QA76</rdf:value></dcterms:LCC></dc:subject>
+        <dc:title>Citing &#8220;software&#8221;</dc:title>
+        <dc:date>2019-05</dc:date>
+        <dcterms:dateSubmitted>2020-01-31 10:00:00</dcterms:dateSubmitted>
+        <dc:identifier>
+            <dcterms:URI><rdf:value>https://example.com/chapter</rdf:value></dcterms:URI>
+        </dc:identifier>
+        <bib:pages>10-20</bib:pages>
+        <prism:edition>2</prism:edition>
+        <dc:description>Extra notes</dc:description>
+    </rdf:Description>
+    <z:Attachment rdf:about="#item_2">
+        <z:itemType>attachment</z:itemType>
+        <dc:title>Full Text PDF</dc:title>
+    </z:Attachment>
+    <bib:Thesis rdf:about="#item_3">
+        <z:itemType>thesis</z:itemType>
+        <dc:publisher><foaf:Organization><foaf:name>University of Auckland</foaf:name></foaf:Organization></dc:publisher>
+        <bib:authors><rdf:Seq><rdf:li><foaf:Person><foaf:surname>Smith</foaf:surname><foaf:givenName>Ann</foaf:givenName></foaf:Person></rdf:li></rdf:Seq></bib:authors>
+        <dc:title><![CDATA[Notes <on> theses]]></dc:title>
+        <z:type>PhD thesis</z:type>
+        <dc:date>2001</dc:date>
+    </bib:Thesis>
+    <bib:Memo rdf:about="#item_4"><rdf:value>A note</rdf:value></bib:Memo>
+</rdf:RDF>
+"##;
+
+#[test]
+fn book_section() {
+	let items = rdf_to_items(EXPORT, &mut KeyGenerator::default()).unwrap();
+	assert_eq!(items.len(), 2);
+
+	let json = serde_json::to_value(&items[0]).unwrap();
+	assert_eq!(
+		json,
+		serde_json::json!({
+			"id": "doe2019",
+			"type": "chapter",
+			"title": "Citing \u{201c}software\u{201d}",
+			"container-title": "Tools & Methods",
+			"collection-title": "Lecture Notes",
+			"collection-number": "42",
+			"publisher": "Springer",
+			"publisher-place": "Berlin",
+			"author": [{"family": "Doe", "given": "Jane"}, {"literal": "Acme Lab"}],
+			"editor": [{"family": "Roe", "given": "Jo"}],
+			"keyword": "citation, software",
+			"call-number": "QA76",
+			"issued": {"date-parts": [[2019, 5]]},
+			"accessed": {"date-parts": [[2020, 1, 31]]},
+			"URL": "https://example.com/chapter",
+			"ISBN": "978-3-16-148410-0",
+			"page": "10-20",
+			"edition": "2",
+			"note": "Extra notes",
+		})
+	);
+}
+
+#[test]
+fn thesis() {
+	let items = rdf_to_items(EXPORT, &mut KeyGenerator::default()).unwrap();
+	let thesis = &items[1];
+	assert_eq!(thesis.id, "smith2001");
+	assert_eq!(thesis.item_type, ItemType::Thesis);
+	assert_eq!(
+		thesis.title.as_ref().unwrap().to_string(),
+		"Notes <on> theses"
+	);
+
+	let json = serde_json::to_value(thesis).unwrap();
+	assert_eq!(json["publisher"], "University of Auckland");
+	assert_eq!(json["genre"], "PhD thesis");
+}
+
+#[test]
+fn not_rdf() {
+	let err = rdf_to_items("<html><body/></html>", &mut KeyGenerator::default()).unwrap_err();
+	assert!(err.contains("expected an RDF document"), "{err}");
+
+	let err = rdf_to_items(
+		"<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n<a>\n</rdf:RDF>",
+		&mut KeyGenerator::default(),
+	)
+	.unwrap_err();
+	assert_eq!(err, "expected the end of a at line 3");
+}
+
+#[test]
+fn doctype_subset() {
+	let text = EXPORT.replacen(
+		"<rdf:RDF",
+		"<!DOCTYPE rdf:RDF [\n<!ENTITY gt \"a>b\">\n<!-- ]> -->\n<!ATTLIST a b CDATA ']'>\n]>\n<rdf:RDF",
+		1,
+	);
+	let items = rdf_to_items(&text, &mut KeyGenerator::default()).unwrap();
+	assert_eq!(items.len(), 2);
+}
+
+#[test]
+fn nested_too_deeply() {
+	let text = format!(
+		"<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">{}{}</rdf:RDF>",
+		"<a>".repeat(100_000),
+		"</a>".repeat(100_000),
+	);
+	let err = rdf_to_items(&text, &mut KeyGenerator::default()).unwrap_err();
+	assert!(err.contains("elements nested too deeply"), "{err}");
+}
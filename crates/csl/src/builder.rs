@@ -0,0 +1,226 @@
+//! Building items programmatically.
+//!
+//! ```
+//! use citeworks_csl::{items::ItemType, names::Name, Item};
+//!
+//! let builder = Item::builder(ItemType::ArticleJournal)
+//!     .id("roe2022")
+//!     .title("A study")
+//!     .author(Name {
+//!         given: Some("Jane".into()),
+//!         family: Some("Roe".into()),
+//!         ..Default::default()
+//!     })
+//!     .volume(12)
+//!     .extra("ISBN", "978-3-16-148410-0");
+//!
+//! assert_eq!(builder.warnings().len(), 1);
+//! assert_eq!(
+//!     builder.warnings()[0].to_string(),
+//!     "ISBN is unusual for an item of type article-journal"
+//! );
+//!
+//! let item = builder.build().unwrap();
+//! assert_eq!(item.id, "roe2022");
+//! assert_eq!(item.fields.len(), 1);
+//! ```
+
+use std::fmt::Display;
+
+use crate::{
+	dates::Date,
+	items::{Item, ItemType, ItemValue},
+	names::Name,
+	ordinaries::OrdinaryValue,
+	variables::variable,
+};
+
+/// Builder for an [Item].
+///
+/// Setters for names fields add a name each time they're called; others
+/// replace the value.
+#[derive(Debug, Clone)]
+pub struct ItemBuilder {
+	item: Item,
+}
+
+macro_rules! names {
+	($($field:ident),* $(,)?) => {
+		$(
+			#[doc = concat!("Add a name to `", stringify!($field), "`.")]
+			pub fn $field(mut self, name: Name) -> Self {
+				self.item.$field.push(name);
+				self
+			}
+		)*
+	};
+}
+
+macro_rules! dates {
+	($($field:ident),* $(,)?) => {
+		$(
+			#[doc = concat!("Set `", stringify!($field), "`.")]
+			pub fn $field(mut self, date: Date) -> Self {
+				self.item.$field = Some(date);
+				self
+			}
+		)*
+	};
+}
+
+macro_rules! ordinaries {
+	($($setter:ident => $field:ident),* $(,)?) => {
+		$(
+			#[doc = concat!("Set `", stringify!($field), "`.")]
+			pub fn $setter(mut self, value: impl Into<OrdinaryValue>) -> Self {
+				self.item.$field = Some(value.into());
+				self
+			}
+		)*
+	};
+}
+
+impl ItemBuilder {
+	/// Start building an item of the given type.
+	pub fn new(item_type: ItemType) -> Self {
+		Self {
+			item: Item {
+				item_type,
+				..Default::default()
+			},
+		}
+	}
+
+	/// Set the item's ID, which is required.
+	pub fn id(mut self, id: impl Into<String>) -> Self {
+		self.item.id = id.into();
+		self
+	}
+
+	names!(
+		author,
+		contributor,
+		editor,
+		translator,
+		recipient,
+		container_author,
+	);
+
+	dates!(issued, updated, published, accessed);
+
+	ordinaries!(
+		category => category,
+		issn => issn,
+		eissn => eissn,
+		issnl => issnl,
+		doi => doi,
+		url => url,
+		title => title,
+		title_short => title_short,
+		summary => summary,
+		abstract_text => abstract_text,
+		container_title => container_title,
+		journal_abbreviation => journal_abbrevation,
+		volume => volume,
+		issue => issue,
+		page => page,
+		language => language,
+		source => source,
+		rights => rights,
+		license => license,
+		note => note,
+	);
+
+	/// Set a field by its CSL name, for fields without a setter.
+	///
+	/// If the field is one of [Item]'s own, like `title`, the value is checked
+	/// and set there when building, replacing what was set with its setter.
+	pub fn extra(mut self, key: impl Into<String>, value: impl Into<ItemValue>) -> Self {
+		self.item.fields.insert(key.into(), value.into());
+		self
+	}
+
+	/// Fields set so far which are unusual for the item's type.
+	///
+	/// These don't prevent building, but may be mistakes, like an `issue` on a
+	/// book, or an `ISBN` on a journal article.
+	pub fn warnings(&self) -> Vec<FieldWarning> {
+		let item = &self.item;
+		let set = [
+			("issue", item.issue.is_some()),
+			("journalAbbreviation", item.journal_abbrevation.is_some()),
+			("ISSN", item.issn.is_some()),
+			("EISSN", item.eissn.is_some()),
+			("ISSNL", item.issnl.is_some()),
+			("recipient", !item.recipient.is_empty()),
+			("container-author", !item.container_author.is_empty()),
+		];
+
+		set.into_iter()
+			.filter(|(_, set)| *set)
+			.map(|(field, _)| field)
+			.chain(item.fields.keys().map(String::as_str))
+			.filter(|field| !suits(field, &item.item_type))
+			.map(|field| FieldWarning {
+				field: field.into(),
+				item_type: item.item_type.clone(),
+			})
+			.collect()
+	}
+
+	/// Build the item.
+	///
+	/// This fails if the ID isn't set, or if a field set with
+	/// [extra](Self::extra) is one of [Item]'s own but of the wrong kind, like
+	/// a date for `title`.
+	pub fn build(self) -> Result<Item, String> {
+		if self.item.id.is_empty() {
+			return Err("item has no id".into());
+		}
+
+		if self.item.fields.is_empty() {
+			return Ok(self.item);
+		}
+
+		// Going through JSON sets extra fields which are known on the item.
+		serde_json::to_value(&self.item)
+			.and_then(serde_json::from_value)
+			.map_err(|err| format!("invalid field: {}", err))
+	}
+}
+
+/// A field which is unusual for the type of the item it's set on.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FieldWarning {
+	/// CSL name of the field.
+	pub field: String,
+
+	/// Type of the item.
+	pub item_type: ItemType,
+}
+
+impl Display for FieldWarning {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let item_type = serde_json::to_value(&self.item_type)
+			.ok()
+			.and_then(|value| value.as_str().map(ToString::to_string))
+			.unwrap_or_default();
+		write!(
+			f,
+			"{} is unusual for an item of type {}",
+			self.field, item_type
+		)
+	}
+}
+
+fn suits(field: &str, item_type: &ItemType) -> bool {
+	// Variables this library has which aren't standard go with standard ones:
+	// journal abbreviations with issues, on serials.
+	let field = match field {
+		"EISSN" | "ISSNL" => "ISSN",
+		"journalAbbreviation" => "issue",
+		field => field,
+	};
+
+	variable(field).map_or(true, |variable| variable.suits(item_type))
+}
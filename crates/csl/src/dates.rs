@@ -8,6 +8,7 @@ use std::{
 };
 
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Number;
 
 use crate::ordinaries::OrdinaryValue;
 
@@ -26,9 +27,12 @@ use crate::ordinaries::OrdinaryValue;
 ///
 /// [EDTF] (Extended Date/Time Format) is a structured string format for dates,
 /// datetimes, and ranges established by the United States of America's Library
-/// of Congress.
+/// of Congress. These are kept as written, and parsed with the `edtf` feature:
+/// see [to_parts](Self::to_parts) and [crate::edtf].
 ///
 /// All forms may also have any of the [metadata or less-precise fields][meta].
+/// Dates can also be _only_ those, e.g. just a `season` and `circa`, or a
+/// `literal`: these are `MetaOnly`.
 ///
 /// [EDTF]: https://www.librarianshipstudies.com/2016/05/extended-date-time-format-edtf.html
 /// [meta]: DateMeta
@@ -72,6 +76,12 @@ pub enum Date {
 		/// Additional date (meta)data
 		meta: DateMeta,
 	},
+
+	/// Only metadata, without a date
+	MetaOnly {
+		/// Date (meta)data
+		meta: DateMeta,
+	},
 }
 
 #[derive(Debug, Default, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -106,11 +116,62 @@ impl Date {
 			Self::Single { meta, .. }
 			| Self::Range { meta, .. }
 			| Self::Raw { meta, .. }
-			| Self::Edtf { meta, .. } => meta,
+			| Self::Edtf { meta, .. }
+			| Self::MetaOnly { meta } => meta,
+		}
+	}
+
+	/// The start of the date, and its end if it's a range.
+	///
+	/// With the `edtf` feature, EDTF dates are parsed: dates with unspecified
+	/// digits, like `201X`, are ranges over them, and intervals that are open
+	/// or unknown at the end have no end. Their qualifiers and seasons are left
+	/// out, as are the dates of intervals with an open or unknown start.
+	///
+	/// Raw dates, EDTF dates that can't be parsed, and dates that are only
+	/// metadata have no parts.
+	pub fn to_parts(&self) -> Option<(DateParts, Option<DateParts>)> {
+		match self {
+			Self::Single { date, .. } => Some((*date, None)),
+			Self::Range { start, end, .. } => Some((*start, Some(*end))),
+			#[cfg(feature = "edtf")]
+			Self::Edtf { date, .. } => edtf_parts(date),
+			_ => None,
+		}
+	}
+
+	/// The range of dates of the season of a single date that has only a year.
+	///
+	/// See [Season::range].
+	pub fn season_range(&self, hemisphere: Hemisphere) -> Option<(DateParts, DateParts)> {
+		match self {
+			Self::Single { date, meta } if date.month.is_none() => {
+				meta.season_range(date.year, hemisphere)
+			}
+			_ => None,
 		}
 	}
 }
 
+#[cfg(feature = "edtf")]
+fn edtf_parts(date: &str) -> Option<(DateParts, Option<DateParts>)> {
+	use crate::edtf::{Edtf, IntervalEnd};
+
+	let range = |start: DateParts, end: DateParts| (start, Some(end).filter(|end| *end != start));
+	match date.parse().ok()? {
+		Edtf::Date(date) => Some(range(date.earliest(), date.latest())),
+		Edtf::Interval {
+			start: IntervalEnd::Date(start),
+			end: IntervalEnd::Date(end),
+		} => Some(range(start.earliest(), end.latest())),
+		Edtf::Interval {
+			start: IntervalEnd::Date(start),
+			..
+		} => Some((start.earliest(), None)),
+		Edtf::Interval { .. } => None,
+	}
+}
+
 impl Serialize for Date {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
 	where
@@ -138,6 +199,7 @@ impl Serialize for Date {
 			Self::Edtf { date, .. } => {
 				internal.edtf = Some(date.clone());
 			}
+			Self::MetaOnly { .. } => {}
 		}
 
 		internal.serialize(serializer)
@@ -173,6 +235,13 @@ impl<'de> Deserialize<'de> for Date {
 				date: date.clone(),
 				meta: DateMeta::from_internal(internal),
 			})
+		} else if internal.season.is_some()
+			|| internal.circa.is_some()
+			|| internal.literal.is_some()
+		{
+			Ok(Self::MetaOnly {
+				meta: DateMeta::from_internal(internal),
+			})
 		} else {
 			Err(D::Error::custom("unknown date format".to_string()))
 		}
@@ -181,7 +250,8 @@ impl<'de> Deserialize<'de> for Date {
 
 /// The core "date-parts" of a date complex type.
 ///
-/// In CSL-JSON this is an array `[year, month, day]`.
+/// In CSL-JSON this is an array `[year, month, day]`, or `[year, month]` or
+/// `[year]` when the day or month aren't known.
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(try_from = "DatePartsInternal", into = "DatePartsInternal")]
 pub struct DateParts {
@@ -195,25 +265,27 @@ pub struct DateParts {
 	pub day: Option<u8>,
 }
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 struct DatePartsInternal(
 	StrumI64,
 	#[serde(default, skip_serializing_if = "Option::is_none")] Option<StrumU8>,
 	#[serde(default, skip_serializing_if = "Option::is_none")] Option<StrumU8>,
 );
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+// Numbers are read as [Number]s, which also read them when serde_json has
+// arbitrary_precision, as the `raw-numbers` feature enables.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 enum StrumI64 {
 	String(String),
-	Num(i64),
+	Num(Number),
 }
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 enum StrumU8 {
 	String(String),
-	Num(u8),
+	Num(Number),
 }
 
 impl TryFrom<StrumI64> for i64 {
@@ -221,44 +293,53 @@ impl TryFrom<StrumI64> for i64 {
 
 	fn try_from(value: StrumI64) -> Result<Self, Self::Error> {
 		match value {
-			StrumI64::String(s) => s.parse(),
-			StrumI64::Num(t) => Ok(t),
+			StrumI64::String(s) => s.trim().parse(),
+			StrumI64::Num(t) => t.to_string().parse(),
 		}
 	}
 }
 
-impl TryFrom<StrumU8> for u8 {
-	type Error = ParseIntError;
-
-	fn try_from(value: StrumU8) -> Result<Self, Self::Error> {
-		match value {
-			StrumU8::String(s) => s.parse(),
-			StrumU8::Num(t) => Ok(t),
-		}
+impl StrumU8 {
+	/// The month or day, where empty strings and zero mean it's not known.
+	fn part(value: Option<Self>) -> Result<Option<u8>, ParseIntError> {
+		let part = match value {
+			Some(Self::String(s)) if s.trim().is_empty() => return Ok(None),
+			Some(Self::String(s)) => s.trim().parse()?,
+			Some(Self::Num(t)) => t.to_string().parse()?,
+			None => return Ok(None),
+		};
+		Ok(Some(part).filter(|part| *part != 0))
 	}
 }
 
 impl TryFrom<DatePartsInternal> for DateParts {
 	type Error = ParseIntError;
 
+	/// Zotero and others write unknown parts as empty strings, or as zero, e.g.
+	/// `["2020", "04", ""]`. These are read as absent, as is a day without a
+	/// month.
 	fn try_from(
 		DatePartsInternal(year, month, day): DatePartsInternal,
 	) -> Result<Self, Self::Error> {
+		let month = StrumU8::part(month)?;
 		Ok(Self {
 			year: year.try_into()?,
-			month: month.map(|m| m.try_into()).transpose()?,
-			day: day.map(|d| d.try_into()).transpose()?,
+			month,
+			day: month.and(StrumU8::part(day)?),
 		})
 	}
 }
 
 impl From<DateParts> for DatePartsInternal {
+	/// Parts are written as 1, 2, or 3 element arrays, as far as they're known.
+	/// A day without a month would be read as the month, so isn't written.
 	fn from(parts: DateParts) -> Self {
-		Self(
-			StrumI64::Num(parts.year),
-			parts.month.map(StrumU8::Num),
-			parts.day.map(StrumU8::Num),
-		)
+		let month = parts.month.map(|month| StrumU8::Num(month.into()));
+		let day = month
+			.as_ref()
+			.and(parts.day)
+			.map(|day| StrumU8::Num(day.into()));
+		Self(StrumI64::Num(parts.year.into()), month, day)
 	}
 }
 
@@ -287,6 +368,17 @@ pub struct DateMeta {
 }
 
 impl DateMeta {
+	/// The range of dates of the season in a year, if there's a season.
+	///
+	/// See [Season::range].
+	pub fn season_range(
+		&self,
+		year: i64,
+		hemisphere: Hemisphere,
+	) -> Option<(DateParts, DateParts)> {
+		self.season.map(|season| season.range(year, hemisphere))
+	}
+
 	fn from_internal(internal: DateInternal) -> Self {
 		Self {
 			season: internal.season,
@@ -304,7 +396,7 @@ impl DateMeta {
 /// - it can be a boolean (generally only `true`) to indicate that the
 ///   containing date is itself approximate.
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
-#[serde(untagged)]
+#[serde(untagged, try_from = "CircaInternal")]
 pub enum Circa {
 	/// Arbitrary string for the circa value.
 	Arbitrary(String),
@@ -316,6 +408,28 @@ pub enum Circa {
 	Bool(bool),
 }
 
+/// Years are read as [Number]s, which also read them when serde_json has
+/// arbitrary_precision, as the `raw-numbers` feature enables.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CircaInternal {
+	Arbitrary(String),
+	Year(Number),
+	Bool(bool),
+}
+
+impl TryFrom<CircaInternal> for Circa {
+	type Error = ParseIntError;
+
+	fn try_from(value: CircaInternal) -> Result<Self, Self::Error> {
+		Ok(match value {
+			CircaInternal::Arbitrary(s) => Self::Arbitrary(s),
+			CircaInternal::Year(n) => Self::Year(n.to_string().parse()?),
+			CircaInternal::Bool(b) => Self::Bool(b),
+		})
+	}
+}
+
 impl Circa {
 	/// If the [Circa] is an arbitrary string, return it.
 	pub fn as_arbitrary(&self) -> Option<&str> {
@@ -348,7 +462,8 @@ impl Circa {
 /// Season value for approximate dates.
 ///
 /// This does not contain information as to where the season is, e.g.
-/// Winter in the north hemisphere could be Summer in the south.
+/// Winter in the north hemisphere could be Summer in the south: methods that
+/// resolve seasons to months take a [Hemisphere].
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 pub enum Season {
 	/// Spring season, or `season-01` in CSL.
@@ -393,6 +508,62 @@ impl FromStr for Season {
 	}
 }
 
+impl Season {
+	/// The first and last months of the season, as meteorological seasons of
+	/// three months each, e.g. December to February for winter in the north.
+	pub fn months(self, hemisphere: Hemisphere) -> (u8, u8) {
+		let (start, end) = match self {
+			Self::Spring => (3, 5),
+			Self::Summer => (6, 8),
+			Self::Autumn => (9, 11),
+			Self::Winter => (12, 2),
+		};
+		match hemisphere {
+			Hemisphere::Northern => (start, end),
+			Hemisphere::Southern => ((start + 5) % 12 + 1, (end + 5) % 12 + 1),
+		}
+	}
+
+	/// The range of dates of the season in a year, from the first day of its
+	/// first month to the last day of its last month.
+	///
+	/// A season crossing the year boundary starts in the year and ends in the
+	/// next, so winter 2020 in the north is from 2020-12-01 to 2021-02-28.
+	///
+	/// ```
+	/// use citeworks_csl::dates::{DateParts, Hemisphere, Season};
+	///
+	/// let (start, end) = Season::Winter.range(2020, Hemisphere::Northern);
+	/// assert_eq!((start.year, start.month, start.day), (2020, Some(12), Some(1)));
+	/// assert_eq!((end.year, end.month, end.day), (2021, Some(2), Some(28)));
+	/// ```
+	pub fn range(self, year: i64, hemisphere: Hemisphere) -> (DateParts, DateParts) {
+		let (start, end) = self.months(hemisphere);
+		let end_year = if end < start { year + 1 } else { year };
+		(
+			DateParts {
+				year,
+				month: Some(start),
+				day: Some(1),
+			},
+			DateParts {
+				year: end_year,
+				month: Some(end),
+				day: Some(days_in_month(end_year, end)),
+			},
+		)
+	}
+}
+
+pub(crate) fn days_in_month(year: i64, month: u8) -> u8 {
+	match month {
+		2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
+
 impl Serialize for Season {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
 	where
@@ -412,3 +583,40 @@ impl<'de> Deserialize<'de> for Season {
 		Season::from_str(&s).map_err(D::Error::custom)
 	}
 }
+
+/// Hemisphere, to tell which months a [Season] is.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Hemisphere {
+	/// Northern hemisphere, where winter is from December to February.
+	Northern,
+
+	/// Southern hemisphere, where winter is from June to August.
+	Southern,
+}
+
+impl Default for Hemisphere {
+	fn default() -> Self {
+		Self::Northern
+	}
+}
+
+impl Display for Hemisphere {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(match self {
+			Self::Northern => "northern",
+			Self::Southern => "southern",
+		})
+	}
+}
+
+impl FromStr for Hemisphere {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"northern" | "north" => Ok(Self::Northern),
+			"southern" | "south" => Ok(Self::Southern),
+			other => Err(format!("unknown hemisphere: {other:?}")),
+		}
+	}
+}
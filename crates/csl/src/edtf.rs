@@ -0,0 +1,289 @@
+//! Parsing of [EDTF] dates, at levels 0 and 1.
+//!
+//! CSL-JSON dates can be given as EDTF strings, which [Date](crate::dates::Date)
+//! keeps as written. This parses them into dates and intervals, with their
+//! seasons, unspecified digits, and uncertain or approximate qualifiers:
+//!
+//! ```
+//! use citeworks_csl::edtf::{Edtf, IntervalEnd};
+//!
+//! let edtf: Edtf = "1984~/2004-06".parse().unwrap();
+//! match edtf {
+//!     Edtf::Interval {
+//!         start: IntervalEnd::Date(start),
+//!         end: IntervalEnd::Date(end),
+//!     } => {
+//!         assert_eq!(start.date.year, 1984);
+//!         assert!(start.approximate);
+//!         assert_eq!((end.date.year, end.date.month), (2004, Some(6)));
+//!     }
+//!     _ => unreachable!(),
+//! }
+//! ```
+//!
+//! Times are read but not kept, as CSL dates don't have them. Level 2 features,
+//! like qualifiers on parts of a date or sets of dates, are errors.
+//!
+//! [EDTF]: https://www.loc.gov/standards/datetime/
+
+use std::str::FromStr;
+
+use crate::dates::{days_in_month, DateParts, Season};
+
+/// An EDTF date or interval.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Edtf {
+	/// A single date, e.g. `2004-06-11` or `2004-21`
+	Date(EdtfDate),
+
+	/// An interval between two dates, e.g. `2004/2006-08`
+	Interval {
+		/// Start of the interval
+		start: IntervalEnd,
+
+		/// End of the interval
+		end: IntervalEnd,
+	},
+}
+
+/// One end of an EDTF interval.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum IntervalEnd {
+	/// A date, e.g. the `2004` of `2004/2006`
+	Date(EdtfDate),
+
+	/// Unknown, as in `/2006` or `2004/`
+	Unknown,
+
+	/// Open, as in `../2006` or `2004/..`
+	Open,
+}
+
+/// A date in EDTF, with its level 1 extensions.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct EdtfDate {
+	/// The date, as far as it's specified.
+	///
+	/// Unspecified digits of the year are zero, e.g. 2010 for `201X`, and
+	/// unspecified months and days are absent, as they are for `2004-21`.
+	pub date: DateParts,
+
+	/// How many of the last digits of the year are unspecified, e.g. 1 for `201X`.
+	pub unspecified_digits: u8,
+
+	/// The season, for dates like `2004-21`.
+	pub season: Option<Season>,
+
+	/// Whether the date is uncertain, with `?` or `%`.
+	pub uncertain: bool,
+
+	/// Whether the date is approximate, with `~` or `%`.
+	pub approximate: bool,
+}
+
+impl EdtfDate {
+	/// The earliest date this can be, e.g. 2010 for `201X`.
+	pub fn earliest(&self) -> DateParts {
+		let span = self.span();
+		DateParts {
+			year: if self.date.year < 0 {
+				self.date.year - span
+			} else {
+				self.date.year
+			},
+			..self.date
+		}
+	}
+
+	/// The latest date this can be, e.g. 2019 for `201X`.
+	pub fn latest(&self) -> DateParts {
+		let span = self.span();
+		DateParts {
+			year: if self.date.year < 0 {
+				self.date.year
+			} else {
+				self.date.year + span
+			},
+			..self.date
+		}
+	}
+
+	fn span(&self) -> i64 {
+		10_i64.pow(self.unspecified_digits.into()) - 1
+	}
+}
+
+impl FromStr for Edtf {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		match s.split_once('/') {
+			Some((start, end)) => {
+				let start = IntervalEnd::parse(start).map_err(|err| invalid(s, err))?;
+				let end = IntervalEnd::parse(end).map_err(|err| invalid(s, err))?;
+				if start == IntervalEnd::Unknown && end == IntervalEnd::Unknown {
+					return Err(invalid(s, "neither end of the interval is known".into()));
+				}
+				Ok(Self::Interval { start, end })
+			}
+			None => EdtfDate::parse(s, true)
+				.map(Self::Date)
+				.map_err(|err| invalid(s, err)),
+		}
+	}
+}
+
+fn invalid(s: &str, err: String) -> String {
+	format!("invalid EDTF date {s:?}: {err}")
+}
+
+impl IntervalEnd {
+	fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"" => Ok(Self::Unknown),
+			".." => Ok(Self::Open),
+			date => EdtfDate::parse(date, false).map(Self::Date),
+		}
+	}
+}
+
+impl FromStr for EdtfDate {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s.trim(), true).map_err(|err| invalid(s, err))
+	}
+}
+
+impl EdtfDate {
+	fn parse(s: &str, with_time: bool) -> Result<Self, String> {
+		let (s, uncertain, approximate) = match s.chars().last() {
+			Some('?') => (&s[..s.len() - 1], true, false),
+			Some('~') => (&s[..s.len() - 1], false, true),
+			Some('%') => (&s[..s.len() - 1], true, true),
+			_ => (s, false, false),
+		};
+
+		let (s, time) = match s.split_once('T') {
+			Some((date, time)) if with_time => (date, Some(time)),
+			Some(_) => return Err("intervals can't have times".into()),
+			None => (s, None),
+		};
+
+		let (negative, s) = match s.strip_prefix('-') {
+			Some(rest) => (true, rest),
+			None => (false, s),
+		};
+		// Long years, like `Y-170000002`, are only years.
+		let (year, month, day) = if s.starts_with('Y') {
+			(s, None, None)
+		} else {
+			let mut parts = s.split('-');
+			let (year, month, day) = (parts.next().unwrap_or_default(), parts.next(), parts.next());
+			if parts.next().is_some() {
+				return Err("too many parts".into());
+			}
+			(year, month, day)
+		};
+
+		let (year, unspecified_digits) = parse_year(year)?;
+		let year = if negative { -year } else { year };
+
+		let (month, season) = match month {
+			None => (None, None),
+			Some("XX") => (None, None),
+			Some(month) => match parse_number(month, 2)? {
+				month @ 1..=12 => (Some(month as u8), None),
+				21 => (None, Some(Season::Spring)),
+				22 => (None, Some(Season::Summer)),
+				23 => (None, Some(Season::Autumn)),
+				24 => (None, Some(Season::Winter)),
+				month => return Err(format!("{month} isn't a month or season")),
+			},
+		};
+
+		let day = match (day, month) {
+			(None, _) | (Some("XX"), _) => None,
+			(Some(_), None) => return Err("a day needs a month".into()),
+			(Some(day), Some(month)) => match parse_number(day, 2)? {
+				day if day >= 1 && day <= days_in_month(year, month).into() => Some(day as u8),
+				day => return Err(format!("{day} isn't a day of month {month}")),
+			},
+		};
+
+		if unspecified_digits > 0 && (month.is_some() || season.is_some()) {
+			return Err("only the year can have unspecified digits when a month is known".into());
+		}
+
+		if let Some(time) = time {
+			if day.is_none() {
+				return Err("a time needs a day".into());
+			}
+			check_time(time)?;
+		}
+
+		Ok(Self {
+			date: DateParts { year, month, day },
+			unspecified_digits,
+			season,
+			uncertain,
+			approximate,
+		})
+	}
+}
+
+/// Years are four digits, of which the last two can be unspecified, or `Y`
+/// followed by more than four digits, e.g. `Y-170000002`.
+fn parse_year(year: &str) -> Result<(i64, u8), String> {
+	if let Some(long) = year.strip_prefix('Y') {
+		let (sign, long) = match long.strip_prefix('-') {
+			Some(long) => (-1, long),
+			None => (1, long),
+		};
+		if long.len() <= 4 {
+			return Err("years after Y need more than four digits".into());
+		}
+		return parse_number(long, long.len()).map(|year| (sign * year, 0));
+	}
+
+	let digits = year.trim_end_matches('X');
+	let unspecified = year.len() - digits.len();
+	if year.len() != 4 || unspecified > 2 {
+		return Err(format!("{year:?} isn't a year"));
+	}
+	let year = parse_number(digits, digits.len())? * 10_i64.pow(unspecified as u32);
+	Ok((year, unspecified as u8))
+}
+
+fn parse_number(s: &str, len: usize) -> Result<i64, String> {
+	if s.len() != len || !s.bytes().all(|b| b.is_ascii_digit()) {
+		return Err(format!("{s:?} isn't a {len}-digit number"));
+	}
+	s.parse().map_err(|err| format!("{s:?}: {err}"))
+}
+
+/// Times are `hh:mm:ss`, then `Z` or an offset like `+05:30`, if any.
+fn check_time(time: &str) -> Result<(), String> {
+	let (time, offset) = match time.find(['Z', '+', '-']) {
+		Some(at) => time.split_at(at),
+		None => (time, ""),
+	};
+
+	let clock = time.split(':').collect::<Vec<_>>();
+	let valid = clock.len() == 3
+		&& clock.iter().all(|part| parse_number(part, 2).is_ok())
+		&& match offset.strip_prefix(['+', '-']) {
+			None => offset.is_empty() || offset == "Z",
+			Some(offset) => {
+				let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "00"));
+				parse_number(hours, 2).is_ok() && parse_number(minutes, 2).is_ok()
+			}
+		};
+
+	if valid {
+		Ok(())
+	} else {
+		Err(format!("{time:?} isn't a time"))
+	}
+}
@@ -2,9 +2,16 @@
 
 use std::collections::BTreeMap;
 
-use serde::{Deserialize, Serialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
-use crate::{dates::Date, names::Name, ordinaries::OrdinaryValue};
+use crate::{
+	builder::ItemBuilder,
+	dates::Date,
+	names::Name,
+	ordinaries::OrdinaryValue,
+	variables::{variable, VariableKind},
+};
 
 /// An item carries the details of a single unique bibliographic resource.
 ///
@@ -30,6 +37,22 @@ pub struct Item {
 	#[serde(default, skip_serializing_if = "Vec::is_empty")]
 	pub contributor: Vec<Name>,
 
+	/// Editor(s).
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub editor: Vec<Name>,
+
+	/// Translator(s).
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub translator: Vec<Name>,
+
+	/// Recipient(s), e.g. of a letter.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub recipient: Vec<Name>,
+
+	/// Author(s) of the issuing publication, e.g. of a book containing a chapter.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub container_author: Vec<Name>,
+
 	/// Date the item was issued on.
 	#[serde(default, skip_serializing_if = "Option::is_none")]
 	pub issued: Option<Date>,
@@ -146,12 +169,25 @@ pub struct Item {
 	pub note: Option<OrdinaryValue>,
 
 	/// Any field that is not directly supported by name.
-	#[serde(flatten)]
+	#[serde(flatten, deserialize_with = "fields")]
 	pub fields: BTreeMap<String, ItemValue>,
 }
 
+impl Item {
+	/// Start building an item of the given type.
+	pub fn builder(item_type: ItemType) -> ItemBuilder {
+		ItemBuilder::new(item_type)
+	}
+}
+
 /// Any of the possible value types of an item's fields.
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+///
+/// In an item's `fields`, standard CSL variables are read as their kind, e.g.
+/// `original-date` as a date; others are read by their shape: strings and
+/// numbers as ordinary values, arrays as names, and objects as dates if they
+/// have date keys like `date-parts` or `raw`, or as a single name if they have
+/// name keys like `family`.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum ItemValue {
 	/// Ordinary fields containing string or numeric values.
@@ -180,9 +216,134 @@ pub enum ItemValue {
 	Names(Vec<Name>),
 }
 
+// Keys which only dates have, and keys which only names have.
+const DATE_KEYS: &[&str] = &["date-parts", "raw", "edtf", "season", "circa"];
+
+const NAME_KEYS: &[&str] = &[
+	"family",
+	"given",
+	"dropping-particle",
+	"non-dropping-particle",
+	"suffix",
+];
+
+impl ItemValue {
+	/// Read a value, as the kind of the CSL variable `key` if it's a standard
+	/// one and the value has the shape of it, and by its shape otherwise.
+	fn from_value(key: Option<&str>, value: Value) -> Result<Self, String> {
+		if let Some(key) = key {
+			let known = match variable(key).map(|variable| variable.kind) {
+				Some(VariableKind::Date) if value.is_object() => {
+					serde_json::from_value(value.clone()).map(Self::Date).ok()
+				}
+				Some(VariableKind::Names) if value.is_array() => {
+					serde_json::from_value(value.clone()).map(Self::Names).ok()
+				}
+				_ => None,
+			};
+
+			if let Some(known) = known {
+				return Ok(known);
+			}
+		}
+
+		let err = |err: serde_json::Error| match key {
+			Some(key) => format!("{key}: {err}"),
+			None => err.to_string(),
+		};
+
+		match value {
+			Value::String(_) | Value::Number(_) => serde_json::from_value(value)
+				.map(Self::Ordinary)
+				.map_err(err),
+			Value::Array(_) => serde_json::from_value(value).map(Self::Names).map_err(err),
+			Value::Object(ref obj) if DATE_KEYS.iter().any(|k| obj.contains_key(*k)) => {
+				serde_json::from_value(value).map(Self::Date).map_err(err)
+			}
+			Value::Object(ref obj) if NAME_KEYS.iter().any(|k| obj.contains_key(*k)) => {
+				serde_json::from_value(value)
+					.map(|name| Self::Names(vec![name]))
+					.map_err(err)
+			}
+			Value::Object(ref obj) if obj.contains_key("literal") => {
+				serde_json::from_value(value).map(Self::Date).map_err(err)
+			}
+			other => Err(format!(
+				"{}expected a string, number, date, or names, found {other}",
+				key.map(|key| format!("{key}: ")).unwrap_or_default()
+			)),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for ItemValue {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		Self::from_value(None, Value::deserialize(deserializer)?).map_err(D::Error::custom)
+	}
+}
+
+fn fields<'de, D>(deserializer: D) -> Result<BTreeMap<String, ItemValue>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	BTreeMap::<String, Value>::deserialize(deserializer)?
+		.into_iter()
+		.map(|(key, value)| {
+			ItemValue::from_value(Some(&key), value)
+				.map(|value| (key, value))
+				.map_err(D::Error::custom)
+		})
+		.collect()
+}
+
+impl From<OrdinaryValue> for ItemValue {
+	fn from(value: OrdinaryValue) -> Self {
+		Self::Ordinary(value)
+	}
+}
+
+impl From<&str> for ItemValue {
+	fn from(value: &str) -> Self {
+		Self::Ordinary(value.into())
+	}
+}
+
+impl From<String> for ItemValue {
+	fn from(value: String) -> Self {
+		Self::Ordinary(value.into())
+	}
+}
+
+impl From<i64> for ItemValue {
+	fn from(value: i64) -> Self {
+		Self::Ordinary(value.into())
+	}
+}
+
+impl From<Date> for ItemValue {
+	fn from(value: Date) -> Self {
+		Self::Date(value)
+	}
+}
+
+impl From<Vec<Name>> for ItemValue {
+	fn from(value: Vec<Name>) -> Self {
+		Self::Names(value)
+	}
+}
+
 /// The type of the bibliographic resource.
-#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
+///
+/// Types outside CSL and CSL-M, like those some Zotero plugins and citeproc
+/// variants write, are kept as written in [ItemType::Other].
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(
+	rename_all = "kebab-case",
+	expecting = "expected an item type, like `article-journal`"
+)]
 #[allow(missing_docs)]
 pub enum ItemType {
 	// CSL
@@ -235,6 +396,63 @@ pub enum ItemType {
 	Gazette,
 	Video,
 	LegalCommentary,
+
+	// Anything else
+	#[serde(untagged)]
+	Other(String),
+}
+
+impl ItemType {
+	/// All known item types, CSL's then CSL-M's.
+	pub const ALL: &'static [Self] = &[
+		Self::Article,
+		Self::ArticleJournal,
+		Self::ArticleMagazine,
+		Self::ArticleNewspaper,
+		Self::Bill,
+		Self::Book,
+		Self::Broadcast,
+		Self::Chapter,
+		Self::Classic,
+		Self::Collection,
+		Self::Dataset,
+		Self::Document,
+		Self::Entry,
+		Self::EntryDictionary,
+		Self::EntryEncyclopedia,
+		Self::Figure,
+		Self::Graphic,
+		Self::Hearing,
+		Self::Interview,
+		Self::LegalCase,
+		Self::Legislation,
+		Self::Manuscript,
+		Self::Map,
+		Self::MotionPicture,
+		Self::MusicalScore,
+		Self::Pamphlet,
+		Self::PaperConference,
+		Self::Patent,
+		Self::Performance,
+		Self::Periodical,
+		Self::PersonalCommunication,
+		Self::Post,
+		Self::PostWeblog,
+		Self::Regulation,
+		Self::Report,
+		Self::Review,
+		Self::ReviewBook,
+		Self::Software,
+		Self::Song,
+		Self::Speech,
+		Self::Standard,
+		Self::Thesis,
+		Self::Treaty,
+		Self::Webpage,
+		Self::Gazette,
+		Self::Video,
+		Self::LegalCommentary,
+	];
 }
 
 impl Default for ItemType {
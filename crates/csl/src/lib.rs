@@ -28,12 +28,21 @@ use std::io::{Read, Write};
 
 pub use serde_json::Result;
 
+pub use builder::ItemBuilder;
 pub use items::Item;
 
+pub mod builder;
 pub mod dates;
+#[cfg(feature = "edtf")]
+pub mod edtf;
 pub mod items;
 pub mod names;
 pub mod ordinaries;
+pub mod search;
+pub mod sort;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+pub mod variables;
 
 /// Deserialize CSL items from an IO stream of JSON.
 pub fn from_reader<R>(rdr: R) -> Result<Vec<Item>>
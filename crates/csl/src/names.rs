@@ -61,3 +61,16 @@ pub struct Name {
 	#[serde(flatten)]
 	pub extra: BTreeMap<String, String>,
 }
+
+impl Name {
+	/// The ORCID iD in the name's extra fields, as given.
+	///
+	/// CSL doesn't define one, but Crossref and others use a non-standard
+	/// `ORCID` field, either as the bare iD or as its URL.
+	pub fn orcid(&self) -> Option<&str> {
+		self.extra
+			.get("ORCID")
+			.or_else(|| self.extra.get("orcid"))
+			.map(String::as_str)
+	}
+}
@@ -1,13 +1,19 @@
 //! Types and utilities for ordinary values.
 
-use std::{fmt::Display, hash::Hash};
+use std::{fmt::Display, hash::Hash, str::FromStr};
 
 use decorum::{cmp::FloatEq, hash::FloatHash};
-use serde::{Deserialize, Serialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Number, Value};
 
 /// An ordinary value can either be numerical or a string.
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(untagged)]
+///
+/// Numbers are read as integers if they can be, and as floats otherwise. Floats
+/// are written back in the shortest form that reads as the same float, so e.g.
+/// `2.10` is written as `2.1`. With the `raw-numbers` feature, floats which
+/// wouldn't be written as they were read are [RawFloat](Self::RawFloat)s
+/// instead, which are written back exactly.
+#[derive(Debug, Clone)]
 pub enum OrdinaryValue {
 	/// Numeric values (floating)
 	Float(f64),
@@ -17,6 +23,19 @@ pub enum OrdinaryValue {
 
 	/// String values
 	String(String),
+
+	/// Numeric values (floating), with the text they were written as
+	///
+	/// This is only read with the `raw-numbers` feature, which enables
+	/// serde_json's `arbitrary_precision` feature, and is only written exactly
+	/// with it too: without, it's written like a [Float](Self::Float).
+	RawFloat {
+		/// The value of the number.
+		value: f64,
+
+		/// The number as it was written, e.g. `2.10` or `1e3`.
+		raw: String,
+	},
 }
 
 impl OrdinaryValue {
@@ -40,10 +59,85 @@ impl OrdinaryValue {
 
 	/// If the [OrdinaryValue] is a float, return it.
 	pub fn as_f64(&self) -> Option<f64> {
-		if let Self::Float(num) = self {
-			Some(*num)
+		match self {
+			Self::Float(num) | Self::RawFloat { value: num, .. } => Some(*num),
+			_ => None,
+		}
+	}
+
+	/// The value as a float, reading strings which are numbers.
+	///
+	/// Integers beyond 2^53 lose precision, as do floats read with their text.
+	pub fn as_number_lossy(&self) -> Option<f64> {
+		match self {
+			Self::Integer(num) => Some(*num as f64),
+			Self::Float(num) | Self::RawFloat { value: num, .. } => Some(*num),
+			Self::String(s) => s.trim().parse().ok().filter(|num: &f64| num.is_finite()),
+		}
+	}
+
+	/// A float from its text, keeping the text if the float would be written
+	/// differently.
+	pub fn from_float_str(raw: &str) -> Result<Self, std::num::ParseFloatError> {
+		let value = f64::from_str(raw)?;
+		let written = Value::from(value).to_string();
+		Ok(if written == raw {
+			Self::Float(value)
 		} else {
-			None
+			Self::RawFloat {
+				value,
+				raw: raw.into(),
+			}
+		})
+	}
+}
+
+impl From<&str> for OrdinaryValue {
+	fn from(s: &str) -> Self {
+		Self::String(s.into())
+	}
+}
+
+impl From<String> for OrdinaryValue {
+	fn from(s: String) -> Self {
+		Self::String(s)
+	}
+}
+
+impl From<i64> for OrdinaryValue {
+	fn from(num: i64) -> Self {
+		Self::Integer(num)
+	}
+}
+
+impl From<f64> for OrdinaryValue {
+	fn from(num: f64) -> Self {
+		Self::Float(num)
+	}
+}
+
+impl TryFrom<OrdinaryValue> for u64 {
+	type Error = String;
+
+	/// Non-negative integers, including strings of them like `"12"`.
+	fn try_from(value: OrdinaryValue) -> Result<Self, Self::Error> {
+		match &value {
+			OrdinaryValue::Integer(num) => u64::try_from(*num).ok(),
+			OrdinaryValue::String(s) => s.trim().parse().ok(),
+			OrdinaryValue::Float(_) | OrdinaryValue::RawFloat { .. } => None,
+		}
+		.ok_or_else(|| format!("expected a non-negative integer, found {value}"))
+	}
+}
+
+impl TryFrom<OrdinaryValue> for String {
+	type Error = String;
+
+	/// Only strings: use [ToString] to also get numbers as text.
+	fn try_from(value: OrdinaryValue) -> Result<Self, Self::Error> {
+		match value {
+			OrdinaryValue::String(s) => Ok(s),
+			other => Err(format!("expected a string, found {other}")),
 		}
 	}
 }
@@ -54,6 +148,7 @@ impl PartialEq for OrdinaryValue {
 			(Self::Integer(l0), Self::Integer(r0)) => l0 == r0,
 			(Self::Float(l0), Self::Float(r0)) => l0.float_eq(r0),
 			(Self::String(l0), Self::String(r0)) => l0 == r0,
+			(Self::RawFloat { raw: l0, .. }, Self::RawFloat { raw: r0, .. }) => l0 == r0,
 			_ => false,
 		}
 	}
@@ -68,6 +163,7 @@ impl Hash for OrdinaryValue {
 			OrdinaryValue::Float(f) => f.float_hash(state),
 			OrdinaryValue::Integer(i) => i.hash(state),
 			OrdinaryValue::String(s) => s.hash(state),
+			OrdinaryValue::RawFloat { raw, .. } => raw.hash(state),
 		}
 	}
 }
@@ -78,6 +174,45 @@ impl Display for OrdinaryValue {
 			Self::Float(n) => write!(f, "{}", n),
 			Self::Integer(i) => write!(f, "{}", i),
 			Self::String(s) => write!(f, "{}", s),
+			Self::RawFloat { raw, .. } => write!(f, "{}", raw),
+		}
+	}
+}
+
+impl Serialize for OrdinaryValue {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		match self {
+			Self::Float(n) => serializer.serialize_f64(*n),
+			Self::Integer(i) => serializer.serialize_i64(*i),
+			Self::String(s) => serializer.serialize_str(s),
+			Self::RawFloat { value, raw } => match Number::from_str(raw) {
+				Ok(number) => number.serialize(serializer),
+				Err(_) => serializer.serialize_f64(*value),
+			},
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for OrdinaryValue {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		// Going through a Value reads numbers with their text when serde_json
+		// has arbitrary_precision, even when buffered by an untagged enum.
+		match Value::deserialize(deserializer)? {
+			Value::String(s) => Ok(Self::String(s)),
+			Value::Number(n) => Ok(match n.as_i64() {
+				Some(i) => Self::Integer(i),
+				None => Self::from_float_str(&n.to_string())
+					.unwrap_or_else(|_| Self::Float(n.as_f64().unwrap_or(f64::NAN))),
+			}),
+			other => Err(D::Error::custom(format!(
+				"expected a string or number, found {other}"
+			))),
 		}
 	}
 }
@@ -0,0 +1,127 @@
+//! Fuzzy search over a library of CSL items.
+//!
+//! This is for "find the reference I mean" in interactive tools: the query is
+//! matched word by word against the titles, author names, and years of the
+//! items, with [Jaro-Winkler] similarity so that typos, missing accents, and
+//! partially typed words still match.
+//!
+//! ```
+//! use citeworks_csl::search::search;
+//!
+//! let items = citeworks_csl::from_str(r#"[
+//!     {"id": "lecun2015", "type": "article-journal", "title": "Deep learning",
+//!      "author": [{"family": "LeCun", "given": "Yann"}], "issued": {"date-parts": [[2015]]}},
+//!     {"id": "goodfellow2014", "type": "paper-conference", "title": "Generative adversarial nets",
+//!      "author": [{"family": "Goodfellow", "given": "Ian"}], "issued": {"date-parts": [[2014]]}}
+//! ]"#).unwrap();
+//!
+//! let matches = search(&items, "lecunn deep lerning");
+//! assert_eq!(matches[0].item.id, "lecun2015");
+//! assert_eq!(matches.len(), 1);
+//! ```
+//!
+//! [Jaro-Winkler]: https://en.wikipedia.org/wiki/Jaro%E2%80%93Winkler_distance
+
+use std::cmp::Ordering;
+
+use strsim::jaro_winkler;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use crate::{dates::Date, Item};
+
+/// The lowest score [search] returns matches for.
+pub const THRESHOLD: f64 = 0.85;
+
+/// An item matching a search query.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchMatch<'a> {
+	/// The matching item.
+	pub item: &'a Item,
+
+	/// How well it matches, from 0 (not at all) to 1 (every word of the query
+	/// is in the item).
+	pub score: f64,
+}
+
+/// Search items by title, author, and year.
+///
+/// Returns the items scoring at least [THRESHOLD], best first. Items with the
+/// same score are kept in library order.
+pub fn search<'a>(items: &'a [Item], query: &str) -> Vec<SearchMatch<'a>> {
+	let query = words(query);
+	if query.is_empty() {
+		return Vec::new();
+	}
+
+	let mut matches: Vec<SearchMatch<'a>> = items
+		.iter()
+		.map(|item| SearchMatch {
+			item,
+			score: score(&query, &haystack(item)),
+		})
+		.filter(|m| m.score >= THRESHOLD)
+		.collect();
+	matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+	matches
+}
+
+/// The average, over the query words, of their best similarity to an item word.
+fn score(query: &[String], haystack: &[String]) -> f64 {
+	let total: f64 = query
+		.iter()
+		.map(|word| {
+			haystack
+				.iter()
+				.map(|candidate| similarity(word, candidate))
+				.fold(0.0, f64::max)
+		})
+		.sum();
+	total / query.len() as f64
+}
+
+fn similarity(word: &str, candidate: &str) -> f64 {
+	// Numbers are years (or volumes…), which are either right or wrong.
+	if word.chars().all(|c| c.is_ascii_digit()) {
+		return if word == candidate { 1.0 } else { 0.0 };
+	}
+
+	jaro_winkler(word, candidate)
+}
+
+fn haystack(item: &Item) -> Vec<String> {
+	let mut text = Vec::new();
+	for title in [&item.title, &item.title_short].into_iter().flatten() {
+		text.push(title.to_string());
+	}
+	for name in &item.author {
+		text.extend(
+			[&name.family, &name.given, &name.literal]
+				.into_iter()
+				.flatten()
+				.cloned(),
+		);
+	}
+	match &item.issued {
+		Some(Date::Single { date, .. }) => text.push(date.year.to_string()),
+		Some(Date::Range { start, end, .. }) => {
+			text.push(start.year.to_string());
+			text.push(end.year.to_string());
+		}
+		Some(Date::Raw { date, .. } | Date::Edtf { date, .. }) => text.push(date.clone()),
+		Some(Date::MetaOnly { meta }) => text.extend(meta.literal.clone()),
+		None => {}
+	}
+
+	words(&text.join(" "))
+}
+
+/// Lowercase words without accents or punctuation.
+fn words(text: &str) -> Vec<String> {
+	let normalised: String = text
+		.nfd()
+		.filter(|c| !is_combining_mark(*c))
+		.flat_map(char::to_lowercase)
+		.map(|c| if c.is_alphanumeric() { c } else { ' ' })
+		.collect();
+	normalised.split_whitespace().map(String::from).collect()
+}
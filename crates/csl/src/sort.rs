@@ -0,0 +1,281 @@
+//! Sorting of CSL items.
+//!
+//! Bibliography styles sort by a list of keys, such as author, then date, then
+//! title. [sort_items] does the same, so bibliographies and exports come out in
+//! a deterministic order: items that compare equal on every key are ordered by
+//! their id.
+//!
+//! Text is compared case-insensitively by default. With the `icu` feature, it
+//! is compared using the [Unicode Collation Algorithm][uca] instead, tailored
+//! to the [SortSpec::locale] if one is given (still ignoring case), so e.g.
+//! "Ångström" sorts after "Zhang" in Swedish but next to "Andersen" in English.
+//!
+//! ```
+//! use citeworks_csl::sort::{sort_items, SortSpec, SortVariable};
+//!
+//! let mut items = citeworks_csl::from_str(r#"[
+//!     {"id": "b", "type": "book", "author": [{"family": "Roe"}], "issued": {"date-parts": [[2019]]}},
+//!     {"id": "a", "type": "book", "author": [{"family": "Doe"}], "issued": {"date-parts": [[2021]]}},
+//!     {"id": "c", "type": "book", "author": [{"family": "Doe"}], "issued": {"date-parts": [[2017]]}}
+//! ]"#).unwrap();
+//!
+//! sort_items(&mut items, &SortSpec::default());
+//! let ids: Vec<_> = items.iter().map(|item| item.id.as_str()).collect();
+//! assert_eq!(ids, ["c", "a", "b"]);
+//!
+//! sort_items(&mut items, &SortSpec::new().descending(SortVariable::Issued));
+//! let ids: Vec<_> = items.iter().map(|item| item.id.as_str()).collect();
+//! assert_eq!(ids, ["a", "b", "c"]);
+//! ```
+//!
+//! [uca]: https://www.unicode.org/reports/tr10/
+
+use std::cmp::Ordering;
+
+use crate::{dates::Date, names::Name, Item};
+
+/// What to sort items by.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SortSpec {
+	/// Keys to sort by, in order of precedence.
+	pub keys: Vec<SortKey>,
+
+	/// BCP 47 language tag to collate text for, e.g. `sv` or `de-AT`.
+	///
+	/// This is only used with the `icu` feature. Without a locale, or if it's
+	/// invalid, the root collation order is used.
+	pub locale: Option<String>,
+}
+
+/// A key to sort items by.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct SortKey {
+	/// The variable to compare.
+	pub variable: SortVariable,
+
+	/// Which way to sort.
+	pub order: SortOrder,
+}
+
+/// Variables items can be sorted by.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum SortVariable {
+	/// Authors, by family name then given names, one author at a time.
+	Author,
+
+	/// Issued date, by year then month then day.
+	Issued,
+
+	/// Title.
+	Title,
+
+	/// Item id (citation key).
+	Id,
+}
+
+/// Sort direction.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum SortOrder {
+	/// Smallest first, e.g. A to Z, oldest to newest.
+	Ascending,
+
+	/// Largest first, e.g. Z to A, newest to oldest.
+	Descending,
+}
+
+impl SortSpec {
+	/// A spec without any keys, which sorts by id only.
+	pub fn new() -> Self {
+		Self {
+			keys: Vec::new(),
+			locale: None,
+		}
+	}
+
+	/// Add a key sorting in ascending order.
+	pub fn ascending(mut self, variable: SortVariable) -> Self {
+		self.keys.push(SortKey {
+			variable,
+			order: SortOrder::Ascending,
+		});
+		self
+	}
+
+	/// Add a key sorting in descending order.
+	pub fn descending(mut self, variable: SortVariable) -> Self {
+		self.keys.push(SortKey {
+			variable,
+			order: SortOrder::Descending,
+		});
+		self
+	}
+
+	/// Set the locale to collate text for.
+	pub fn locale(mut self, locale: impl Into<String>) -> Self {
+		self.locale = Some(locale.into());
+		self
+	}
+}
+
+impl Default for SortSpec {
+	/// The usual bibliography order: author, then issued date, then title.
+	fn default() -> Self {
+		Self::new()
+			.ascending(SortVariable::Author)
+			.ascending(SortVariable::Issued)
+			.ascending(SortVariable::Title)
+	}
+}
+
+/// Sort items according to a spec.
+///
+/// As in CSL, items which don't have a value for a key are sorted after those
+/// which do, whichever the order. The sort is stable.
+pub fn sort_items(items: &mut [Item], spec: &SortSpec) {
+	let collator = Collator::new(spec.locale.as_deref());
+	items.sort_by(|a, b| {
+		spec.keys
+			.iter()
+			.map(|key| compare_key(&collator, key, a, b))
+			.find(|ordering| ordering.is_ne())
+			.unwrap_or_else(|| collator.compare(&a.id, &b.id).then_with(|| a.id.cmp(&b.id)))
+	});
+}
+
+fn compare_key(collator: &Collator, key: &SortKey, a: &Item, b: &Item) -> Ordering {
+	match key.variable {
+		SortVariable::Author => compare_present(
+			Some(&a.author).filter(|names| !names.is_empty()),
+			Some(&b.author).filter(|names| !names.is_empty()),
+			key.order,
+			|a, b| compare_names(collator, a, b),
+		),
+		SortVariable::Issued => compare_present(
+			date_key(a.issued.as_ref()),
+			date_key(b.issued.as_ref()),
+			key.order,
+			|a, b| a.cmp(&b),
+		),
+		SortVariable::Title => compare_present(
+			a.title.as_ref().map(ToString::to_string),
+			b.title.as_ref().map(ToString::to_string),
+			key.order,
+			|a, b| collator.compare(&a, &b),
+		),
+		SortVariable::Id => compare_present(Some(&a.id), Some(&b.id), key.order, |a, b| {
+			collator.compare(a, b)
+		}),
+	}
+}
+
+/// Compare optional values, putting absent ones last regardless of the order.
+fn compare_present<T>(
+	a: Option<T>,
+	b: Option<T>,
+	order: SortOrder,
+	compare: impl FnOnce(T, T) -> Ordering,
+) -> Ordering {
+	match (a, b) {
+		(Some(a), Some(b)) => match order {
+			SortOrder::Ascending => compare(a, b),
+			SortOrder::Descending => compare(a, b).reverse(),
+		},
+		(Some(_), None) => Ordering::Less,
+		(None, Some(_)) => Ordering::Greater,
+		(None, None) => Ordering::Equal,
+	}
+}
+
+fn compare_names(collator: &Collator, a: &[Name], b: &[Name]) -> Ordering {
+	a.iter()
+		.zip(b)
+		.map(|(a, b)| collator.compare(&name_key(a), &name_key(b)))
+		.find(|ordering| ordering.is_ne())
+		.unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+/// "particle family given suffix", or the literal name.
+fn name_key(name: &Name) -> String {
+	if name.family.is_none() {
+		if let Some(literal) = &name.literal {
+			return literal.clone();
+		}
+	}
+
+	[
+		&name.non_dropping_particle,
+		&name.family,
+		&name.given,
+		&name.dropping_particle,
+		&name.suffix,
+	]
+	.into_iter()
+	.flatten()
+	.map(String::as_str)
+	.collect::<Vec<_>>()
+	.join(" ")
+}
+
+/// (year, month, day) of the start of a date, or the year of a raw date or of
+/// a circa year.
+fn date_key(date: Option<&Date>) -> Option<(i64, u8, u8)> {
+	match date? {
+		Date::Single { date: parts, .. } | Date::Range { start: parts, .. } => Some((
+			parts.year,
+			parts.month.unwrap_or_default(),
+			parts.day.unwrap_or_default(),
+		)),
+		Date::Raw { date, .. } | Date::Edtf { date, .. } => {
+			let date = date.trim();
+			let (sign, rest) = match date.strip_prefix('-') {
+				Some(rest) => (-1, rest),
+				None => (1, date),
+			};
+			let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+			digits.parse::<i64>().ok().map(|year| (sign * year, 0, 0))
+		}
+		Date::MetaOnly { meta } => meta.circa.as_ref()?.as_year().map(|year| (year, 0, 0)),
+	}
+}
+
+#[cfg(not(feature = "icu"))]
+struct Collator;
+
+#[cfg(not(feature = "icu"))]
+impl Collator {
+	fn new(_locale: Option<&str>) -> Self {
+		Self
+	}
+
+	fn compare(&self, a: &str, b: &str) -> Ordering {
+		a.to_lowercase().cmp(&b.to_lowercase())
+	}
+}
+
+#[cfg(feature = "icu")]
+struct Collator(icu_collator::Collator);
+
+#[cfg(feature = "icu")]
+impl Collator {
+	fn new(locale: Option<&str>) -> Self {
+		use icu_collator::{Collator, CollatorOptions, Strength};
+		use icu_locid::Locale;
+
+		let locale: Locale = locale
+			.and_then(|locale| locale.parse().ok())
+			.unwrap_or_default();
+
+		// Ignore case, like the fallback collation does, but not accents.
+		let mut options = CollatorOptions::new();
+		options.strength = Some(Strength::Secondary);
+
+		let collator = Collator::try_new(&(&locale).into(), options)
+			.or_else(|_| Collator::try_new(&Default::default(), options))
+			.expect("root collation data is compiled in");
+		Self(collator)
+	}
+
+	fn compare(&self, a: &str, b: &str) -> Ordering {
+		self.0.compare(a, b)
+	}
+}
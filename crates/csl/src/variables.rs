@@ -0,0 +1,252 @@
+//! Tables of the standard CSL variables.
+//!
+//! These are the variables of [CSL 1.0.2][spec], with the kind of value they
+//! have. Number variables are ordinary values which are usually numbers, like
+//! `volume`, or ranges of numbers, like `page`.
+//!
+//! Some variables are also only usual for some [item types](ItemType), e.g.
+//! `issue` for serials like journal articles. This isn't in the specification,
+//! but in conventions of the CSL styles and the tools which produce CSL-JSON.
+//!
+//! ```
+//! use citeworks_csl::{
+//!     items::ItemType,
+//!     variables::{variable, VariableKind},
+//! };
+//!
+//! let issue = variable("issue").unwrap();
+//! assert_eq!(issue.kind, VariableKind::Number);
+//! assert!(issue.suits(&ItemType::ArticleJournal));
+//! assert!(!issue.suits(&ItemType::Book));
+//!
+//! assert_eq!(variable("original-date").unwrap().kind, VariableKind::Date);
+//! assert!(variable("not-a-variable").is_none());
+//! ```
+//!
+//! [spec]: https://docs.citationstyles.org/en/stable/specification.html#appendix-iv-variables
+
+use crate::items::ItemType;
+
+/// The kind of value a variable has.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum VariableKind {
+	/// Ordinary ("standard") variables, with text.
+	Ordinary,
+
+	/// Number variables, with numbers or text like ranges.
+	Number,
+
+	/// Date variables.
+	Date,
+
+	/// Name variables.
+	Names,
+}
+
+/// A standard CSL variable.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Variable {
+	/// Name of the variable, as in CSL-JSON.
+	pub name: &'static str,
+
+	/// The kind of value it has.
+	pub kind: VariableKind,
+
+	/// The item types it's usual for, or `None` if it's usual for all.
+	pub item_types: Option<&'static [ItemType]>,
+}
+
+impl Variable {
+	/// Whether the variable is usual for items of this type.
+	pub fn suits(&self, item_type: &ItemType) -> bool {
+		self.item_types
+			.map_or(true, |item_types| item_types.contains(item_type))
+	}
+}
+
+/// Find a standard variable by name.
+pub fn variable(name: &str) -> Option<&'static Variable> {
+	VARIABLES.iter().find(|variable| variable.name == name)
+}
+
+/// Serials, which have issues and are identified by ISSNs.
+pub const SERIAL_TYPES: &[ItemType] = &[
+	ItemType::Article,
+	ItemType::ArticleJournal,
+	ItemType::ArticleMagazine,
+	ItemType::ArticleNewspaper,
+	ItemType::Gazette,
+	ItemType::PaperConference,
+	ItemType::Periodical,
+	ItemType::Review,
+	ItemType::ReviewBook,
+];
+
+/// Serials, and items which can be in a series identified by an ISSN.
+pub const ISSN_TYPES: &[ItemType] = &[
+	ItemType::Article,
+	ItemType::ArticleJournal,
+	ItemType::ArticleMagazine,
+	ItemType::ArticleNewspaper,
+	ItemType::Gazette,
+	ItemType::PaperConference,
+	ItemType::Periodical,
+	ItemType::Review,
+	ItemType::ReviewBook,
+	ItemType::Book,
+	ItemType::Chapter,
+	ItemType::Report,
+];
+
+/// Parts of a larger work, which has its own authors.
+pub const PART_TYPES: &[ItemType] = &[
+	ItemType::Broadcast,
+	ItemType::Chapter,
+	ItemType::Entry,
+	ItemType::EntryDictionary,
+	ItemType::EntryEncyclopedia,
+	ItemType::PaperConference,
+	ItemType::Review,
+	ItemType::ReviewBook,
+];
+
+/// Books and parts of them, which are identified by ISBNs.
+pub const BOOK_TYPES: &[ItemType] = &[
+	ItemType::Book,
+	ItemType::Chapter,
+	ItemType::Classic,
+	ItemType::Collection,
+	ItemType::EntryDictionary,
+	ItemType::EntryEncyclopedia,
+	ItemType::MusicalScore,
+	ItemType::Pamphlet,
+	ItemType::PaperConference,
+	ItemType::Report,
+	ItemType::Thesis,
+];
+
+/// Reviews, which have a reviewed work.
+pub const REVIEW_TYPES: &[ItemType] = &[ItemType::Review, ItemType::ReviewBook];
+
+/// Correspondence, which has recipients.
+pub const CORRESPONDENCE_TYPES: &[ItemType] =
+	&[ItemType::PersonalCommunication, ItemType::Interview];
+
+macro_rules! variables {
+	($($name:literal: $kind:ident $(in $types:ident)?),* $(,)?) => {
+		/// All standard variables, by kind, in alphabetical order.
+		pub const VARIABLES: &[Variable] = &[$(
+			Variable {
+				name: $name,
+				kind: VariableKind::$kind,
+				item_types: variables!(@types $($types)?),
+			},
+		)*];
+	};
+	(@types) => { None };
+	(@types $types:ident) => { Some($types) };
+}
+
+variables! {
+	"abstract": Ordinary,
+	"annote": Ordinary,
+	"archive": Ordinary,
+	"archive-place": Ordinary,
+	"archive_collection": Ordinary,
+	"archive_location": Ordinary,
+	"authority": Ordinary,
+	"call-number": Ordinary,
+	"citation-key": Ordinary,
+	"citation-label": Ordinary,
+	"collection-title": Ordinary,
+	"container-title": Ordinary,
+	"container-title-short": Ordinary,
+	"dimensions": Ordinary,
+	"division": Ordinary,
+	"DOI": Ordinary,
+	"event": Ordinary,
+	"event-place": Ordinary,
+	"event-title": Ordinary,
+	"genre": Ordinary,
+	"ISBN": Ordinary in BOOK_TYPES,
+	"ISSN": Ordinary in ISSN_TYPES,
+	"jurisdiction": Ordinary,
+	"keyword": Ordinary,
+	"language": Ordinary,
+	"license": Ordinary,
+	"medium": Ordinary,
+	"note": Ordinary,
+	"original-publisher": Ordinary,
+	"original-publisher-place": Ordinary,
+	"original-title": Ordinary,
+	"part-title": Ordinary,
+	"PMCID": Ordinary,
+	"PMID": Ordinary,
+	"publisher": Ordinary,
+	"publisher-place": Ordinary,
+	"references": Ordinary,
+	"reviewed-genre": Ordinary in REVIEW_TYPES,
+	"reviewed-title": Ordinary in REVIEW_TYPES,
+	"scale": Ordinary,
+	"source": Ordinary,
+	"status": Ordinary,
+	"title": Ordinary,
+	"title-short": Ordinary,
+	"URL": Ordinary,
+	"volume-title": Ordinary,
+	"year-suffix": Ordinary,
+
+	"chapter-number": Number,
+	"citation-number": Number,
+	"collection-number": Number,
+	"edition": Number,
+	"first-reference-note-number": Number,
+	"issue": Number in SERIAL_TYPES,
+	"locator": Number,
+	"number": Number,
+	"number-of-pages": Number,
+	"number-of-volumes": Number,
+	"page": Number,
+	"page-first": Number,
+	"part-number": Number,
+	"printing-number": Number,
+	"section": Number,
+	"supplement-number": Number,
+	"version": Number,
+	"volume": Number,
+
+	"accessed": Date,
+	"available-date": Date,
+	"event-date": Date,
+	"issued": Date,
+	"original-date": Date,
+	"submitted": Date,
+
+	"author": Names,
+	"chair": Names,
+	"collection-editor": Names,
+	"compiler": Names,
+	"composer": Names,
+	"container-author": Names in PART_TYPES,
+	"contributor": Names,
+	"curator": Names,
+	"director": Names,
+	"editor": Names,
+	"editor-translator": Names,
+	"editorial-director": Names,
+	"executive-producer": Names,
+	"guest": Names,
+	"host": Names,
+	"illustrator": Names,
+	"interviewer": Names,
+	"narrator": Names,
+	"organizer": Names,
+	"original-author": Names,
+	"performer": Names,
+	"producer": Names,
+	"recipient": Names in CORRESPONDENCE_TYPES,
+	"reviewed-author": Names in REVIEW_TYPES,
+	"script-writer": Names,
+	"series-creator": Names,
+	"translator": Names,
+}
@@ -0,0 +1,66 @@
+use citeworks_csl::{
+	dates::{Date, DateParts},
+	items::{ItemType, ItemValue},
+	ordinaries::OrdinaryValue,
+	Item,
+};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn requires_id() {
+	assert_eq!(
+		Item::builder(ItemType::Book).title("A book").build(),
+		Err("item has no id".into())
+	);
+}
+
+#[test]
+fn known_extra_fields() {
+	let item = Item::builder(ItemType::Book)
+		.id("book")
+		.title("A book")
+		.extra("title", "The book")
+		.extra("ISBN", "978-3-16-148410-0")
+		.build()
+		.unwrap();
+
+	assert_eq!(item.title, Some(OrdinaryValue::from("The book")));
+	assert_eq!(
+		item.fields.into_iter().collect::<Vec<_>>(),
+		[(String::from("ISBN"), ItemValue::from("978-3-16-148410-0"))]
+	);
+
+	assert!(Item::builder(ItemType::Book)
+		.id("book")
+		.extra(
+			"title",
+			Date::Single {
+				date: DateParts {
+					year: 2022,
+					month: None,
+					day: None,
+				},
+				meta: Default::default(),
+			}
+		)
+		.build()
+		.is_err());
+}
+
+#[test]
+fn warnings() {
+	let builder = Item::builder(ItemType::Book)
+		.id("book")
+		.issue(3)
+		.issn("2049-3630")
+		.extra("ISBN", "978-3-16-148410-0");
+	let warnings = builder.warnings();
+
+	assert_eq!(warnings.len(), 1);
+	assert_eq!(warnings[0].field, "issue");
+	assert_eq!(
+		warnings[0].to_string(),
+		"issue is unusual for an item of type book"
+	);
+}
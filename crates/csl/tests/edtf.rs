@@ -0,0 +1,127 @@
+#![cfg(feature = "edtf")]
+
+use citeworks_csl::{
+	dates::{Date, DateParts, Season},
+	edtf::{Edtf, EdtfDate, IntervalEnd},
+};
+use pretty_assertions::assert_eq;
+
+fn parts(year: i64, month: Option<u8>, day: Option<u8>) -> DateParts {
+	DateParts { year, month, day }
+}
+
+fn date(s: &str) -> EdtfDate {
+	match s.parse().unwrap() {
+		Edtf::Date(date) => date,
+		other => panic!("not a date: {other:?}"),
+	}
+}
+
+#[test]
+fn level_0() {
+	assert_eq!(date("1985-04-12").date, parts(1985, Some(4), Some(12)));
+	assert_eq!(date("1985-04").date, parts(1985, Some(4), None));
+	assert_eq!(date("1985").date, parts(1985, None, None));
+	assert_eq!(date("-0044").date, parts(-44, None, None));
+	assert_eq!(
+		date("1985-04-12T23:20:30+05:30").date,
+		parts(1985, Some(4), Some(12))
+	);
+	assert_eq!(
+		"1964/2008".parse::<Edtf>().unwrap(),
+		Edtf::Interval {
+			start: IntervalEnd::Date(date("1964")),
+			end: IntervalEnd::Date(date("2008")),
+		}
+	);
+}
+
+#[test]
+fn level_1() {
+	assert_eq!(date("Y170000002").date.year, 170_000_002);
+	assert_eq!(date("Y-170000002").date.year, -170_000_002);
+	assert_eq!(date("2001-21").season, Some(Season::Spring));
+	assert_eq!(date("2001-24").date, parts(2001, None, None));
+
+	let qualified = date("1984?");
+	assert!(qualified.uncertain && !qualified.approximate);
+	let qualified = date("2004-06~");
+	assert!(!qualified.uncertain && qualified.approximate);
+	let qualified = date("2004-06-11%");
+	assert!(qualified.uncertain && qualified.approximate);
+
+	let unspecified = date("201X");
+	assert_eq!(unspecified.unspecified_digits, 1);
+	assert_eq!(unspecified.earliest(), parts(2010, None, None));
+	assert_eq!(unspecified.latest(), parts(2019, None, None));
+	assert_eq!(date("19XX").latest(), parts(1999, None, None));
+	assert_eq!(date("1985-04-XX").date, parts(1985, Some(4), None));
+	assert_eq!(date("1985-XX-XX").date, parts(1985, None, None));
+
+	assert_eq!(
+		"../1985-04-12".parse::<Edtf>().unwrap(),
+		Edtf::Interval {
+			start: IntervalEnd::Open,
+			end: IntervalEnd::Date(date("1985-04-12")),
+		}
+	);
+	assert_eq!(
+		"1985-04-12/".parse::<Edtf>().unwrap(),
+		Edtf::Interval {
+			start: IntervalEnd::Date(date("1985-04-12")),
+			end: IntervalEnd::Unknown,
+		}
+	);
+}
+
+#[test]
+fn invalid() {
+	for invalid in [
+		"",
+		"/",
+		"85",
+		"1985-13",
+		"1985-02-30",
+		"1985-XX-12",
+		"198X-04",
+		"1XXX",
+		"Y1985",
+		"1985-04-12T25",
+		"1985/2000T12:00:00",
+		"{1667,1668}",
+		"2004?-06-11",
+	] {
+		assert!(invalid.parse::<Edtf>().is_err(), "{invalid:?} parsed");
+	}
+	assert_eq!(
+		"1985-13".parse::<Edtf>().unwrap_err(),
+		r#"invalid EDTF date "1985-13": 13 isn't a month or season"#
+	);
+}
+
+#[test]
+fn to_parts() {
+	let edtf = |date: &str| Date::Edtf {
+		date: date.into(),
+		meta: Default::default(),
+	};
+
+	assert_eq!(
+		edtf("2004-06-11T10:00:00Z").to_parts(),
+		Some((parts(2004, Some(6), Some(11)), None))
+	);
+	assert_eq!(
+		edtf("201X").to_parts(),
+		Some((parts(2010, None, None), Some(parts(2019, None, None))))
+	);
+	assert_eq!(
+		edtf("2004-06~/2006").to_parts(),
+		Some((parts(2004, Some(6), None), Some(parts(2006, None, None))))
+	);
+	assert_eq!(
+		edtf("2004/..").to_parts(),
+		Some((parts(2004, None, None), None))
+	);
+	assert_eq!(edtf("../2004").to_parts(), None);
+	assert_eq!(edtf("sometime").to_parts(), None);
+}
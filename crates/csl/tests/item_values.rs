@@ -0,0 +1,106 @@
+use citeworks_csl::{
+	dates::{Date, DateMeta},
+	items::ItemValue,
+	names::Name,
+	ordinaries::OrdinaryValue,
+	Item,
+};
+
+use pretty_assertions::assert_eq;
+
+fn item(fields: &str) -> serde_json::Result<Item> {
+	serde_json::from_str(&format!(
+		r#"{{ "id": "example-id", "type": "report", {fields} }}"#
+	))
+}
+
+fn field(fields: &str, key: &str) -> ItemValue {
+	item(fields).unwrap().fields.remove(key).unwrap()
+}
+
+#[test]
+fn known_variables() {
+	assert!(matches!(
+		field(
+			r#""original-date": { "date-parts": [[1850]] }"#,
+			"original-date"
+		),
+		ItemValue::Date(Date::Single { .. })
+	));
+	assert_eq!(
+		field(
+			r#""original-date": { "literal": "Spring" }"#,
+			"original-date"
+		),
+		ItemValue::Date(Date::MetaOnly {
+			meta: DateMeta {
+				literal: Some("Spring".into()),
+				..Default::default()
+			}
+		})
+	);
+	assert_eq!(
+		field(
+			r#""reviewed-author": [{ "literal": "ACME" }]"#,
+			"reviewed-author"
+		),
+		ItemValue::Names(vec![Name {
+			literal: Some("ACME".into()),
+			..Default::default()
+		}])
+	);
+
+	// Standard variables with the wrong shape are read by their shape.
+	assert_eq!(
+		field(r#""original-date": "1850""#, "original-date"),
+		ItemValue::Ordinary(OrdinaryValue::String("1850".into()))
+	);
+}
+
+#[test]
+fn shapes() {
+	assert_eq!(
+		field(r#""number-of-pages": 3"#, "number-of-pages"),
+		ItemValue::Ordinary(OrdinaryValue::Integer(3))
+	);
+	assert_eq!(
+		field(
+			r#""custom-person": { "family": "Doe", "literal": "J. Doe" }"#,
+			"custom-person"
+		),
+		ItemValue::Names(vec![Name {
+			family: Some("Doe".into()),
+			literal: Some("J. Doe".into()),
+			..Default::default()
+		}])
+	);
+	assert!(matches!(
+		field(
+			r#""custom-date": { "raw": "1/2/3456", "literal": "then" }"#,
+			"custom-date"
+		),
+		ItemValue::Date(Date::Raw { .. })
+	));
+}
+
+#[test]
+fn adversarial() {
+	// A name-like object under a date variable is read as a name, not an error.
+	assert_eq!(
+		field(r#""event-date": { "family": "Doe" }"#, "event-date"),
+		ItemValue::Names(vec![Name {
+			family: Some("Doe".into()),
+			..Default::default()
+		}])
+	);
+
+	let err = item(r#""custom": { "unknown": true }"#).unwrap_err();
+	assert!(
+		err.to_string()
+			.starts_with("custom: expected a string, number, date, or names"),
+		"{err}"
+	);
+	assert!(item(r#""custom": null"#).is_err());
+	assert!(item(r#""custom": ["a", "b"]"#).is_err());
+	assert!(item(r#""custom": { "date-parts": "nope" }"#).is_err());
+}
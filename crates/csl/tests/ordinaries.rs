@@ -0,0 +1,39 @@
+use citeworks_csl::ordinaries::OrdinaryValue;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn conversions() {
+	assert_eq!(
+		OrdinaryValue::from("12"),
+		OrdinaryValue::String("12".into())
+	);
+	assert_eq!(OrdinaryValue::from(12), OrdinaryValue::Integer(12));
+	assert_eq!(OrdinaryValue::from(1.5), OrdinaryValue::Float(1.5));
+
+	assert_eq!(u64::try_from(OrdinaryValue::from(12)), Ok(12));
+	assert_eq!(u64::try_from(OrdinaryValue::from(" 12 ")), Ok(12));
+	assert_eq!(
+		u64::try_from(OrdinaryValue::from(-1)),
+		Err("expected a non-negative integer, found -1".into())
+	);
+	assert!(u64::try_from(OrdinaryValue::from(1.5)).is_err());
+
+	assert_eq!(
+		String::try_from(OrdinaryValue::from("title")),
+		Ok("title".into())
+	);
+	assert_eq!(
+		String::try_from(OrdinaryValue::from(12)),
+		Err("expected a string, found 12".into())
+	);
+}
+
+#[test]
+fn lossy_numbers() {
+	assert_eq!(OrdinaryValue::from(12).as_number_lossy(), Some(12.0));
+	assert_eq!(OrdinaryValue::from(1.5).as_number_lossy(), Some(1.5));
+	assert_eq!(OrdinaryValue::from(" 2.10").as_number_lossy(), Some(2.1));
+	assert_eq!(OrdinaryValue::from("12a").as_number_lossy(), None);
+	assert_eq!(OrdinaryValue::from("NaN").as_number_lossy(), None);
+}
@@ -1,7 +1,7 @@
 use std::fs::File;
 
 use citeworks_csl::{
-	dates::{Circa, Date, DateMeta, DateParts, Season},
+	dates::{Circa, Date, DateMeta, DateParts, Hemisphere, Season},
 	from_reader,
 	items::{ItemType, ItemValue},
 	names::Name,
@@ -182,3 +182,161 @@ fn extra() {
 		}]
 	);
 }
+
+#[test]
+fn name_roles() {
+	let csl = citeworks_csl::from_str(
+		r#"[{
+			"id": "letter",
+			"type": "chapter",
+			"editor": [{"family": "Roe"}],
+			"translator": [{"literal": "John Doe"}],
+			"recipient": [{"family": "Smith"}],
+			"container-author": [{"family": "Jones"}]
+		}]"#,
+	)
+	.unwrap();
+	let family = |family: &str| Name {
+		family: Some(family.into()),
+		..Default::default()
+	};
+	assert_eq!(
+		csl,
+		vec![Item {
+			id: "letter".into(),
+			item_type: ItemType::Chapter,
+			editor: vec![family("Roe")],
+			translator: vec![Name {
+				literal: Some("John Doe".into()),
+				..Default::default()
+			}],
+			recipient: vec![family("Smith")],
+			container_author: vec![family("Jones")],
+			..Default::default()
+		}]
+	);
+}
+
+#[test]
+fn partial_date_parts() {
+	let parts = |json: &str| serde_json::from_str::<DateParts>(json).unwrap();
+	let year_month = DateParts {
+		year: 2020,
+		month: Some(4),
+		day: None,
+	};
+	assert_eq!(parts(r#"["2020", "04", ""]"#), year_month);
+	assert_eq!(parts(r#"[2020, 4, 0]"#), year_month);
+	assert_eq!(parts(r#"[" 2020", "04 "]"#), year_month);
+	assert_eq!(
+		parts(r#"["2020", "", "12"]"#),
+		DateParts {
+			year: 2020,
+			month: None,
+			day: None,
+		}
+	);
+
+	assert_eq!(
+		parts("[2020]"),
+		DateParts {
+			year: 2020,
+			month: None,
+			day: None,
+		}
+	);
+	assert_eq!(parts("[2020, 4]"), year_month);
+	assert_eq!(
+		parts("[2020, 4, 12]"),
+		DateParts {
+			year: 2020,
+			month: Some(4),
+			day: Some(12),
+		}
+	);
+
+	assert!(serde_json::from_str::<DateParts>("[]").is_err());
+	assert!(serde_json::from_str::<DateParts>("[2020, 4, 12, 1]").is_err());
+	assert!(serde_json::from_str::<DateParts>(r#"["", "04"]"#).is_err());
+	assert!(serde_json::from_str::<DateParts>(r#"["2020", "April"]"#).is_err());
+}
+
+#[test]
+fn meta_only_dates() {
+	let date = |json: &str| serde_json::from_str::<Date>(json).unwrap();
+
+	let season = date(r#"{"season": "season-02", "circa": 1850}"#);
+	assert_eq!(
+		season,
+		Date::MetaOnly {
+			meta: DateMeta {
+				season: Some(Season::Summer),
+				circa: Some(Circa::Year(1850)),
+				..Default::default()
+			}
+		}
+	);
+	assert_eq!(
+		serde_json::to_string(&season).unwrap(),
+		r#"{"season":"summer","circa":1850}"#
+	);
+
+	let circa = date(r#"{"circa": true}"#);
+	assert_eq!(circa.meta().circa, Some(Circa::Bool(true)));
+	assert_eq!(date(&serde_json::to_string(&circa).unwrap()), circa);
+
+	assert_eq!(
+		date(r#"{"literal": "Early Bronze Age"}"#)
+			.meta()
+			.literal
+			.as_deref(),
+		Some("Early Bronze Age")
+	);
+	assert!(serde_json::from_str::<Date>("{}").is_err());
+}
+
+#[test]
+fn season_ranges() {
+	let parts = |year, month, day| DateParts {
+		year,
+		month: Some(month),
+		day: Some(day),
+	};
+
+	assert_eq!(
+		Season::Spring.range(2021, Hemisphere::Northern),
+		(parts(2021, 3, 1), parts(2021, 5, 31))
+	);
+	assert_eq!(
+		Season::Winter.range(2023, Hemisphere::Northern),
+		(parts(2023, 12, 1), parts(2024, 2, 29))
+	);
+	assert_eq!(
+		Season::Summer.range(2023, Hemisphere::Southern),
+		(parts(2023, 12, 1), parts(2024, 2, 29))
+	);
+	assert_eq!(
+		Season::Winter.range(2023, Hemisphere::Southern),
+		(parts(2023, 6, 1), parts(2023, 8, 31))
+	);
+
+	let date: Date =
+		serde_json::from_str(r#"{"date-parts": [[1999]], "season": "autumn"}"#).unwrap();
+	assert_eq!(
+		date.season_range(Hemisphere::Southern),
+		Some((parts(1999, 3, 1), parts(1999, 5, 31)))
+	);
+	let date: Date =
+		serde_json::from_str(r#"{"date-parts": [[1999, 10]], "season": "autumn"}"#).unwrap();
+	assert_eq!(date.season_range(Hemisphere::Northern), None);
+}
+
+#[test]
+fn unknown_item_type() {
+	let text = r#"[{"id": "preprint-1", "type": "preprint", "title": "Draft"}]"#;
+	let csl = citeworks_csl::from_str(text).unwrap();
+	assert_eq!(csl[0].item_type, ItemType::Other("preprint".into()));
+
+	let written = citeworks_csl::to_string(&csl).unwrap();
+	assert!(written.contains(r#""type":"preprint""#), "{written}");
+}
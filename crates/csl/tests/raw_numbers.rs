@@ -0,0 +1,19 @@
+#![cfg(feature = "raw-numbers")]
+
+use citeworks_csl::{dates::Date, items::ItemValue, ordinaries::OrdinaryValue};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn floats_as_written() {
+	let json = r#"[{"id":"example-id","type":"software","issued":{"date-parts":[[2022,8]],"circa":1990},"volume":3,"version":2.10}]"#;
+	let items = citeworks_csl::from_str(json).unwrap();
+
+	assert_eq!(
+		items[0].fields["version"],
+		ItemValue::Ordinary(OrdinaryValue::from_float_str("2.10").unwrap())
+	);
+	assert_eq!(items[0].volume, Some(OrdinaryValue::Integer(3)));
+	assert!(matches!(items[0].issued, Some(Date::Single { .. })));
+	assert_eq!(citeworks_csl::to_string(&items).unwrap(), json);
+}
@@ -0,0 +1,21 @@
+use citeworks_csl::test_util::{
+	arb_date, arb_item, arb_name, assert_json_roundtrip, assert_roundtrip,
+};
+use proptest::{collection::vec, prelude::*};
+
+proptest! {
+	#[test]
+	fn items_json(items in vec(arb_item(), 0..5)) {
+		assert_json_roundtrip(&items);
+	}
+
+	#[test]
+	fn date_json(date in arb_date()) {
+		assert_roundtrip(&date, serde_json::to_string, |s| serde_json::from_str(&s));
+	}
+
+	#[test]
+	fn name_json(name in arb_name()) {
+		assert_roundtrip(&name, serde_json::to_string, |s| serde_json::from_str(&s));
+	}
+}
@@ -0,0 +1,57 @@
+use citeworks_csl::{from_str, search::search, Item};
+
+use pretty_assertions::assert_eq;
+
+fn library() -> Vec<Item> {
+	from_str(
+		r#"[
+			{"id": "druskat2017", "type": "software", "title": "Citation File Format",
+			 "author": [{"family": "Druskat", "given": "Stephan"}], "issued": {"date-parts": [[2017]]}},
+			{"id": "muller2020", "type": "book", "title": "Über die Zitierung von Software",
+			 "author": [{"family": "Müller", "given": "Jürgen"}], "issued": {"date-parts": [[2020]]}},
+			{"id": "smith2016", "type": "article-journal", "title": "Software citation principles",
+			 "author": [{"family": "Smith", "given": "Arfon M."}], "issued": {"date-parts": [[2016]]}},
+			{"id": "smith2019", "type": "article-journal", "title": "Software citation implementation",
+			 "author": [{"family": "Smith", "given": "Arfon M."}], "issued": {"date-parts": [[2019]]}}
+		]"#,
+	)
+	.unwrap()
+}
+
+fn ids(query: &str) -> Vec<String> {
+	search(&library(), query)
+		.into_iter()
+		.map(|m| m.item.id.clone())
+		.collect()
+}
+
+#[test]
+fn typos() {
+	assert_eq!(ids("citaton fle format"), ["druskat2017"]);
+}
+
+#[test]
+fn accents() {
+	assert_eq!(ids("muller uber"), ["muller2020"]);
+	assert_eq!(ids("MÜLLER"), ["muller2020"]);
+}
+
+#[test]
+fn years_match_exactly() {
+	assert_eq!(ids("smith 2019"), ["smith2019"]);
+	assert_eq!(ids("smith 2018"), Vec::<String>::new());
+}
+
+#[test]
+fn best_first() {
+	let library = library();
+	let matches = search(&library, "software citation principles");
+	assert_eq!(matches[0].item.id, "smith2016");
+	assert_eq!(matches[0].score, 1.0);
+	assert!(matches.windows(2).all(|w| w[0].score >= w[1].score));
+}
+
+#[test]
+fn empty_query() {
+	assert_eq!(ids("  ...  "), Vec::<String>::new());
+}
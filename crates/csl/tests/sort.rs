@@ -0,0 +1,100 @@
+use citeworks_csl::{
+	from_str,
+	sort::{sort_items, SortSpec, SortVariable},
+	Item,
+};
+
+use pretty_assertions::assert_eq;
+
+fn ids(items: &[Item]) -> Vec<&str> {
+	items.iter().map(|item| item.id.as_str()).collect()
+}
+
+#[test]
+fn bibliography_order() {
+	let mut items = from_str(
+		r#"[
+			{"id": "roe", "type": "book", "author": [{"family": "Roe", "given": "Richard"}], "issued": {"date-parts": [[2019]]}},
+			{"id": "doe-b", "type": "book", "author": [{"family": "Doe", "given": "Jane"}], "issued": {"date-parts": [[2017, 6]]}, "title": "Beta"},
+			{"id": "doe-a", "type": "book", "author": [{"family": "doe", "given": "Jane"}], "issued": {"date-parts": [[2017, 6]]}, "title": "alpha"},
+			{"id": "doe-early", "type": "book", "author": [{"family": "Doe", "given": "Jane"}], "issued": {"date-parts": [[2017, 2]]}},
+			{"id": "van", "type": "book", "author": [{"family": "Gogh", "non-dropping-particle": "van", "given": "Vincent"}]},
+			{"id": "org", "type": "book", "author": [{"literal": "Acme Corporation"}]},
+			{"id": "doe-coauthor", "type": "book", "author": [{"family": "Doe", "given": "Jane"}, {"family": "Zed"}], "issued": {"date-parts": [[2000]]}}
+		]"#,
+	)
+	.unwrap();
+
+	sort_items(&mut items, &SortSpec::default());
+	assert_eq!(
+		ids(&items),
+		[
+			"org",
+			"doe-early",
+			"doe-a",
+			"doe-b",
+			"doe-coauthor",
+			"roe",
+			"van"
+		]
+	);
+}
+
+#[test]
+fn missing_values_last() {
+	let mut items = from_str(
+		r#"[
+			{"id": "undated", "type": "book"},
+			{"id": "old", "type": "book", "issued": {"date-parts": [[1999]]}},
+			{"id": "raw", "type": "book", "issued": {"raw": "2005 or so"}},
+			{"id": "new", "type": "book", "issued": {"date-parts": [[2021]]}}
+		]"#,
+	)
+	.unwrap();
+
+	sort_items(&mut items, &SortSpec::new().ascending(SortVariable::Issued));
+	assert_eq!(ids(&items), ["old", "raw", "new", "undated"]);
+
+	sort_items(
+		&mut items,
+		&SortSpec::new().descending(SortVariable::Issued),
+	);
+	assert_eq!(ids(&items), ["new", "raw", "old", "undated"]);
+}
+
+#[test]
+fn ties_broken_by_id() {
+	let mut items = from_str(
+		r#"[
+			{"id": "c", "type": "book", "title": "Same"},
+			{"id": "a", "type": "book", "title": "Same"},
+			{"id": "b", "type": "book", "title": "Same"}
+		]"#,
+	)
+	.unwrap();
+
+	sort_items(&mut items, &SortSpec::new().ascending(SortVariable::Title));
+	assert_eq!(ids(&items), ["a", "b", "c"]);
+
+	sort_items(&mut items, &SortSpec::new().descending(SortVariable::Id));
+	assert_eq!(ids(&items), ["c", "b", "a"]);
+}
+
+#[cfg(feature = "icu")]
+#[test]
+fn locale_collation() {
+	let mut items = from_str(
+		r#"[
+			{"id": "zhang", "type": "book", "author": [{"family": "Zhang"}]},
+			{"id": "angstrom", "type": "book", "author": [{"family": "Ångström"}]},
+			{"id": "andersen", "type": "book", "author": [{"family": "Andersen"}]}
+		]"#,
+	)
+	.unwrap();
+
+	sort_items(&mut items, &SortSpec::default().locale("en"));
+	assert_eq!(ids(&items), ["andersen", "angstrom", "zhang"]);
+
+	sort_items(&mut items, &SortSpec::default().locale("sv"));
+	assert_eq!(ids(&items), ["andersen", "zhang", "angstrom"]);
+}
@@ -0,0 +1,38 @@
+use std::collections::BTreeSet;
+
+use citeworks_csl::{
+	items::ItemType,
+	variables::{variable, VariableKind, VARIABLES},
+};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn unique_names() {
+	let names: BTreeSet<_> = VARIABLES.iter().map(|v| v.name).collect();
+	assert_eq!(names.len(), VARIABLES.len());
+}
+
+#[test]
+fn kinds() {
+	let count = |kind| VARIABLES.iter().filter(|v| v.kind == kind).count();
+	assert_eq!(count(VariableKind::Date), 6);
+	assert_eq!(count(VariableKind::Names), 27);
+	assert_eq!(variable("page").unwrap().kind, VariableKind::Number);
+	assert_eq!(variable("DOI").unwrap().kind, VariableKind::Ordinary);
+}
+
+#[test]
+fn item_types() {
+	let all: BTreeSet<_> = ItemType::ALL
+		.iter()
+		.map(|t| serde_json::to_string(t).unwrap())
+		.collect();
+	assert_eq!(all.len(), 47);
+
+	let isbn = variable("ISBN").unwrap();
+	let usual: Vec<_> = ItemType::ALL.iter().filter(|t| isbn.suits(t)).collect();
+	assert!(usual.contains(&&ItemType::Book));
+	assert!(!usual.contains(&&ItemType::ArticleJournal));
+	assert!(variable("title").unwrap().item_types.is_none());
+}
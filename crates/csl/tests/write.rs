@@ -177,3 +177,39 @@ fn extra() {
 		})
 	);
 }
+
+#[test]
+fn numbers() {
+	let items = citeworks_csl::from_str(
+		r#"[{ "id": "example-id", "type": "report", "volume": 3, "version": 2.5 }]"#,
+	)
+	.unwrap();
+	assert_eq!(items[0].volume, Some(OrdinaryValue::Integer(3)));
+	assert_eq!(
+		citeworks_csl::to_string(&items).unwrap(),
+		r#"[{"id":"example-id","type":"report","volume":3,"version":2.5}]"#
+	);
+
+	let raw = OrdinaryValue::from_float_str("2.10").unwrap();
+	assert_eq!(raw.as_f64(), Some(2.1));
+	assert_eq!(raw.to_string(), "2.10");
+	assert_eq!(
+		OrdinaryValue::from_float_str("2.5").unwrap(),
+		OrdinaryValue::Float(2.5)
+	);
+}
+
+#[test]
+fn partial_date_parts() {
+	let parts = |year, month, day| serde_json::to_value(DateParts { year, month, day }).unwrap();
+
+	assert_eq!(parts(2020, None, None), serde_json::json!([2020]));
+	assert_eq!(parts(2020, Some(5), None), serde_json::json!([2020, 5]));
+	assert_eq!(
+		parts(2020, Some(5), Some(4)),
+		serde_json::json!([2020, 5, 4])
+	);
+
+	// A day without a month can't be written, so isn't.
+	assert_eq!(parts(2020, None, Some(4)), serde_json::json!([2020]));
+}
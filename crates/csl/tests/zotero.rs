@@ -1,6 +1,6 @@
 use std::{collections::BTreeMap, fs::File};
 
-use citeworks_csl::from_reader;
+use citeworks_csl::{from_reader, items::ItemType};
 
 use pretty_assertions::assert_eq;
 
@@ -21,3 +21,23 @@ fn parse_own() {
 	let csl = from_reader(&mut file).unwrap();
 	assert_eq!(csl.len(), 10);
 }
+
+#[test]
+fn parse_unknown_types() {
+	let mut file = File::open("tests/csl-json/unknown-types.json").unwrap();
+	let csl = from_reader(&mut file).unwrap();
+
+	let types: Vec<_> = csl.iter().map(|item| item.item_type.clone()).collect();
+	assert_eq!(
+		types,
+		vec![
+			ItemType::Other("data".into()),
+			ItemType::Other("standard-draft".into()),
+			ItemType::Dataset,
+		]
+	);
+
+	let written = citeworks_csl::to_string(&csl).unwrap();
+	assert!(written.contains(r#""type":"standard-draft""#), "{written}");
+	assert_eq!(citeworks_csl::from_str(&written).unwrap(), csl);
+}
@@ -0,0 +1,254 @@
+//! Starter CFF documents from [crates.io] metadata.
+//!
+//! This reads a published crate's description, repository, homepage, license,
+//! keywords, and latest version, and its owners, to make a CFF document for
+//! it. Owners are kept as names (CFF entities), as crates.io doesn't say which
+//! part of a name is the family name.
+//!
+//! ```no_run
+//! use citeworks_fetch::crates_io::CratesIo;
+//!
+//! let cff = CratesIo::new().starter_cff("citeworks-cff")?;
+//! if let Some(cff) = cff {
+//!     print!("{}", citeworks_cff::to_string(&cff).unwrap());
+//! }
+//! # Ok::<(), citeworks_fetch::Error>(())
+//! ```
+//!
+//! To generate files for many crates, see `citeworks init --from-crates-io`.
+//!
+//! [crates.io]: https://crates.io
+
+use std::str::FromStr;
+
+use citeworks_cff::{
+	names::{EntityName, Name, NameMeta},
+	repositories::{classify_repository, RepositoryKind},
+	Cff, Date, License,
+};
+use serde::Deserialize;
+use url::Url;
+
+use crate::{Result, USER_AGENT};
+
+/// The public crates.io API endpoint.
+pub const ENDPOINT: &str = "https://crates.io/api/v1";
+
+/// A client for the crates.io API.
+#[derive(Debug, Clone)]
+pub struct CratesIo {
+	agent: ureq::Agent,
+	endpoint: String,
+}
+
+/// A published crate.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct Crate {
+	/// Name of the crate.
+	pub name: String,
+
+	/// Description from its manifest.
+	#[serde(default)]
+	pub description: Option<String>,
+
+	/// Repository URL from its manifest.
+	#[serde(default)]
+	pub repository: Option<String>,
+
+	/// Homepage URL from its manifest.
+	#[serde(default)]
+	pub homepage: Option<String>,
+
+	/// Keywords from its manifest.
+	#[serde(default)]
+	pub keywords: Vec<String>,
+
+	/// Latest stable version, if it has one.
+	#[serde(default)]
+	pub max_stable_version: Option<String>,
+
+	/// Latest version.
+	pub newest_version: String,
+}
+
+/// A published version of a crate.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct Version {
+	/// The version number.
+	pub num: String,
+
+	/// License expression from its manifest.
+	#[serde(default)]
+	pub license: Option<String>,
+
+	/// When it was published, in RFC 3339 format.
+	pub created_at: String,
+
+	/// Whether it was yanked.
+	#[serde(default)]
+	pub yanked: bool,
+}
+
+/// An owner of a crate.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct Owner {
+	/// Login, e.g. `passcod`, or `github:org:team` for teams.
+	pub login: String,
+
+	/// Display name.
+	#[serde(default)]
+	pub name: Option<String>,
+
+	/// `user` or `team`.
+	pub kind: String,
+
+	/// Profile URL.
+	#[serde(default)]
+	pub url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CrateResponse {
+	#[serde(rename = "crate")]
+	krate: Crate,
+	#[serde(default)]
+	versions: Vec<Version>,
+}
+
+#[derive(Deserialize)]
+struct OwnersResponse {
+	users: Vec<Owner>,
+}
+
+impl Default for CratesIo {
+	fn default() -> Self {
+		Self::with_endpoint(ENDPOINT)
+	}
+}
+
+impl CratesIo {
+	/// Create a client for the public endpoint.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create a client for a different endpoint, e.g. a mirror.
+	pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+		Self {
+			agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+			endpoint: endpoint.into(),
+		}
+	}
+
+	fn get(&self, path: &str) -> Result<Option<ureq::Response>> {
+		let url = format!("{}/{path}", self.endpoint.trim_end_matches('/'));
+		match self.agent.get(&url).call() {
+			Ok(response) => Ok(Some(response)),
+			Err(ureq::Error::Status(404, _)) => Ok(None),
+			Err(err) => Err(err.into()),
+		}
+	}
+
+	/// Look up a crate and its versions, newest first.
+	///
+	/// Returns `None` if there's no such crate.
+	pub fn krate(&self, name: &str) -> Result<Option<(Crate, Vec<Version>)>> {
+		let response = match self.get(&format!("crates/{}", name.trim()))? {
+			Some(response) => response,
+			None => return Ok(None),
+		};
+
+		let response: CrateResponse = serde_json::from_reader(response.into_reader())?;
+		Ok(Some((response.krate, response.versions)))
+	}
+
+	/// Look up the owners of a crate, users and teams.
+	pub fn owners(&self, name: &str) -> Result<Vec<Owner>> {
+		let response = match self.get(&format!("crates/{}/owners", name.trim()))? {
+			Some(response) => response,
+			None => return Ok(Vec::new()),
+		};
+
+		let response: OwnersResponse = serde_json::from_reader(response.into_reader())?;
+		Ok(response.users)
+	}
+
+	/// Make a starter CFF document for a crate.
+	///
+	/// Returns `None` if there's no such crate. See [crate_to_cff].
+	pub fn starter_cff(&self, name: &str) -> Result<Option<Cff>> {
+		let (krate, versions) = match self.krate(name)? {
+			Some(found) => found,
+			None => return Ok(None),
+		};
+		let owners = self.owners(name)?;
+		Ok(Some(crate_to_cff(&krate, &versions, &owners)))
+	}
+}
+
+/// Make a CFF document from crates.io metadata.
+///
+/// The version is the latest stable version, or the latest if there's no
+/// stable one, with its publication date as the release date and its license.
+/// The crate's page is the artifact repository, unless its repository field
+/// points at another artifact repository. Owners are the authors, and the
+/// document has no authors if the crate has no owners, which CFF requires.
+pub fn crate_to_cff(krate: &Crate, versions: &[Version], owners: &[Owner]) -> Cff {
+	let latest = krate
+		.max_stable_version
+		.as_deref()
+		.unwrap_or(&krate.newest_version);
+	let version = versions
+		.iter()
+		.find(|version| version.num == latest)
+		.or_else(|| versions.iter().find(|version| !version.yanked));
+
+	let mut cff = Cff {
+		title: krate.name.clone(),
+		abstract_text: krate
+			.description
+			.as_deref()
+			.map(str::trim)
+			.map(String::from),
+		version: Some(version.map_or(latest, |version| &version.num).into()),
+		date_released: version.and_then(|version| {
+			version
+				.created_at
+				.get(..10)
+				.and_then(|date| Date::from_str(date).ok())
+		}),
+		license: version
+			.and_then(|version| version.license.as_deref())
+			.and_then(|license| License::from_str(&license.replace('/', " OR ")).ok()),
+		url: krate
+			.homepage
+			.as_deref()
+			.and_then(|url| Url::parse(url).ok()),
+		keywords: krate.keywords.clone(),
+		authors: owners.iter().map(owner_name).collect(),
+		repository_artifact: Url::parse(&format!("https://crates.io/crates/{}", krate.name)).ok(),
+		..Default::default()
+	};
+
+	if let Some(url) = krate
+		.repository
+		.as_deref()
+		.and_then(|url| Url::parse(url).ok())
+	{
+		let kind = classify_repository(&url).unwrap_or(RepositoryKind::Code);
+		cff.set_repository(kind, url);
+	}
+
+	cff
+}
+
+fn owner_name(owner: &Owner) -> Name {
+	Name::Entity(EntityName {
+		name: Some(owner.name.clone().unwrap_or_else(|| owner.login.clone())),
+		meta: NameMeta {
+			website: owner.url.as_deref().and_then(|url| Url::parse(url).ok()),
+			..Default::default()
+		},
+		..Default::default()
+	})
+}
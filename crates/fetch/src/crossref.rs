@@ -0,0 +1,160 @@
+//! Retraction checking with [Crossref].
+//!
+//! Crossref records the notices that update a work: corrections, expressions
+//! of concern, retractions… Since 2023 it also includes the [Retraction Watch]
+//! database, which covers retractions publishers never registered. This looks
+//! up works by DOI and reports the notices that say they shouldn't be cited.
+//!
+//! ```no_run
+//! use citeworks_fetch::crossref::Crossref;
+//!
+//! let crossref = Crossref::new().mailto("me@example.com");
+//! if let Some(notice) = crossref.retraction("10.1016/S0140-6736(97)11096-0")? {
+//!     println!("retracted by {} ({})", notice.doi, notice.kind);
+//! }
+//! # Ok::<(), citeworks_fetch::Error>(())
+//! ```
+//!
+//! [Crossref]: https://www.crossref.org
+//! [Retraction Watch]: https://retractionwatch.com
+
+use citeworks_csl::dates::DateParts;
+use serde::Deserialize;
+
+use crate::{Result, USER_AGENT};
+
+/// The public Crossref API endpoint.
+pub const ENDPOINT: &str = "https://api.crossref.org";
+
+/// A client for the Crossref API.
+#[derive(Debug, Clone)]
+pub struct Crossref {
+	agent: ureq::Agent,
+	endpoint: String,
+	mailto: Option<String>,
+}
+
+/// A notice updating a work.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct Update {
+	/// The DOI of the notice.
+	#[serde(rename = "DOI")]
+	pub doi: String,
+
+	/// What kind of update this is, e.g. `retraction` or `correction`.
+	#[serde(rename = "type")]
+	pub kind: String,
+
+	/// Where Crossref got this from, e.g. `publisher` or `retraction-watch`.
+	#[serde(default)]
+	pub source: Option<String>,
+
+	/// When the update was made.
+	#[serde(default, deserialize_with = "date_parts")]
+	pub updated: Option<DateParts>,
+}
+
+impl Update {
+	/// Whether this notice means the work shouldn't be cited.
+	pub fn is_retraction(&self) -> bool {
+		matches!(
+			self.kind.as_str(),
+			"retraction" | "withdrawal" | "removal" | "partial_retraction"
+		)
+	}
+}
+
+#[derive(Deserialize)]
+struct Response {
+	message: Work,
+}
+
+#[derive(Deserialize)]
+struct Work {
+	#[serde(default, rename = "updated-by")]
+	updated_by: Vec<Update>,
+}
+
+impl Default for Crossref {
+	fn default() -> Self {
+		Self::with_endpoint(ENDPOINT)
+	}
+}
+
+impl Crossref {
+	/// Create a client for the public endpoint.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create a client for a different endpoint.
+	pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+		Self {
+			agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+			endpoint: endpoint.into(),
+			mailto: None,
+		}
+	}
+
+	/// Send an email address with requests, to use Crossref's "polite" pool.
+	pub fn mailto(mut self, email: impl Into<String>) -> Self {
+		self.mailto = Some(email.into());
+		self
+	}
+
+	/// Get the notices updating a work by DOI.
+	///
+	/// Returns `None` if the DOI isn't known to Crossref.
+	pub fn updates(&self, doi: &str) -> Result<Option<Vec<Update>>> {
+		let url = crate::doi_url(&self.endpoint, &["works"], doi)?;
+		let mut request = self.agent.request_url("GET", &url);
+		if let Some(mailto) = &self.mailto {
+			request = request.query("mailto", mailto);
+		}
+
+		let response = match request.call() {
+			Ok(response) => response,
+			Err(ureq::Error::Status(404, _)) => return Ok(None),
+			Err(err) => return Err(err.into()),
+		};
+
+		let response: Response = serde_json::from_reader(response.into_reader())?;
+		Ok(Some(response.message.updated_by))
+	}
+
+	/// Check whether a work has been retracted or withdrawn.
+	///
+	/// Returns the retraction notice, if there's one. Unknown DOIs aren't
+	/// considered retracted.
+	pub fn retraction(&self, doi: &str) -> Result<Option<Update>> {
+		Ok(self
+			.updates(doi)?
+			.unwrap_or_default()
+			.into_iter()
+			.find(Update::is_retraction))
+	}
+}
+
+/// Crossref dates are `{"date-parts": [[2020, 1, 31]], …}`.
+fn date_parts<'de, D>(deserializer: D) -> std::result::Result<Option<DateParts>, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	#[derive(Deserialize)]
+	struct CrossrefDate {
+		#[serde(rename = "date-parts")]
+		date_parts: Vec<Vec<Option<i64>>>,
+	}
+
+	let date = Option::<CrossrefDate>::deserialize(deserializer)?;
+	Ok(date
+		.and_then(|date| date.date_parts.into_iter().next())
+		.and_then(|parts| {
+			let mut parts = parts.into_iter();
+			let year = parts.next().flatten()?;
+			let mut small = || parts.next().flatten().and_then(|n| u8::try_from(n).ok());
+			let month = small();
+			let day = month.and(small());
+			Some(DateParts { year, month, day })
+		}))
+}
@@ -0,0 +1,178 @@
+//! Metadata registered for DOIs, and whether a CFF document agrees with it.
+//!
+//! DOI registration agencies serve the metadata of the works they register as
+//! CSL-JSON, with [content negotiation] on `doi.org`. This works for DOIs from
+//! both Crossref and DataCite, the agency Zenodo registers DOIs with.
+//!
+//! A common problem is for a CITATION.cff and the Zenodo record of the same
+//! software to disagree, e.g. when authors are added to one but not the other.
+//! [drift] compares the title, authors, and version.
+//!
+//! ```no_run
+//! use citeworks_fetch::doi::{drift, Resolver};
+//!
+//! let cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! doi: 10.5281/zenodo.1234
+//! authors:
+//!   - name: The Research Software project
+//! "#).unwrap();
+//!
+//! if let Some(registered) = Resolver::new().csl("10.5281/zenodo.1234")? {
+//!     for drift in drift(&cff, &registered) {
+//!         println!("{}: {:?} in CFF, {:?} registered", drift.field, drift.cff, drift.registered);
+//!     }
+//! }
+//! # Ok::<(), citeworks_fetch::Error>(())
+//! ```
+//!
+//! [content negotiation]: https://citation.crosscite.org/docs.html
+
+use citeworks_cff::{names::Name as CffName, Cff};
+use citeworks_csl::{items::ItemValue, names::Name as CslName, Item};
+
+use crate::{Result, USER_AGENT};
+
+/// The public DOI resolver.
+pub const ENDPOINT: &str = "https://doi.org";
+
+/// A client for resolving DOIs to metadata.
+#[derive(Debug, Clone)]
+pub struct Resolver {
+	agent: ureq::Agent,
+	endpoint: String,
+}
+
+/// A difference between a CFF document and the metadata registered for its DOI.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Drift {
+	/// The CFF field that differs: `title`, `authors`, or `version`.
+	pub field: &'static str,
+
+	/// The value in the CFF document.
+	///
+	/// Authors are listed by family name, separated by `; `.
+	pub cff: String,
+
+	/// The value in the registered metadata.
+	pub registered: String,
+}
+
+impl Default for Resolver {
+	fn default() -> Self {
+		Self::with_endpoint(ENDPOINT)
+	}
+}
+
+impl Resolver {
+	/// Create a client for the public resolver.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create a client for a different resolver.
+	pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+		Self {
+			agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+			endpoint: endpoint.into(),
+		}
+	}
+
+	/// Get the metadata registered for a DOI, as a CSL item.
+	///
+	/// Returns `None` if the DOI isn't registered.
+	pub fn csl(&self, doi: &str) -> Result<Option<Item>> {
+		let url = crate::doi_url(&self.endpoint, &[], doi)?;
+		let response = match self
+			.agent
+			.request_url("GET", &url)
+			.set("Accept", "application/vnd.citationstyles.csl+json")
+			.call()
+		{
+			Ok(response) => response,
+			Err(ureq::Error::Status(404, _)) => return Ok(None),
+			Err(err) => return Err(err.into()),
+		};
+
+		Ok(Some(serde_json::from_reader(response.into_reader())?))
+	}
+}
+
+/// Compare a CFF document with the metadata registered for its DOI.
+///
+/// Titles are compared ignoring case and spacing, and authors by family name,
+/// or by name for entities, in order. Versions are only compared if both have
+/// one, as not all DOIs are for a version.
+pub fn drift(cff: &Cff, registered: &Item) -> Vec<Drift> {
+	let mut drifts = Vec::new();
+
+	let title = registered
+		.title
+		.as_ref()
+		.map(ToString::to_string)
+		.unwrap_or_default();
+	if normalise(&cff.title) != normalise(&title) {
+		drifts.push(Drift {
+			field: "title",
+			cff: cff.title.clone(),
+			registered: title,
+		});
+	}
+
+	let cff_authors: Vec<String> = cff.authors.iter().filter_map(cff_name).collect();
+	let registered_authors: Vec<String> = registered.author.iter().filter_map(csl_name).collect();
+	let (cff_authors, registered_authors) = (cff_authors.join("; "), registered_authors.join("; "));
+	if normalise(&cff_authors) != normalise(&registered_authors) {
+		drifts.push(Drift {
+			field: "authors",
+			cff: cff_authors,
+			registered: registered_authors,
+		});
+	}
+
+	let version = match registered.fields.get("version") {
+		Some(ItemValue::Ordinary(version)) => Some(version.to_string()),
+		_ => None,
+	};
+	if let (Some(cff_version), Some(version)) = (&cff.version, version) {
+		if normalise(cff_version.trim_start_matches('v'))
+			!= normalise(version.trim_start_matches('v'))
+		{
+			drifts.push(Drift {
+				field: "version",
+				cff: cff_version.clone(),
+				registered: version,
+			});
+		}
+	}
+
+	drifts
+}
+
+fn cff_name(name: &CffName) -> Option<String> {
+	match name {
+		CffName::Person(person) => person
+			.family_names
+			.clone()
+			.or_else(|| person.given_names.clone()),
+		CffName::Entity(entity) => entity.name.clone(),
+		CffName::Anonymous => None,
+	}
+}
+
+fn csl_name(name: &CslName) -> Option<String> {
+	name.family
+		.clone()
+		.or_else(|| name.literal.clone())
+		.or_else(|| name.given.clone())
+}
+
+/// Lowercase, with runs of whitespace made single spaces.
+fn normalise(s: &str) -> String {
+	s.split_whitespace()
+		.collect::<Vec<_>>()
+		.join(" ")
+		.to_lowercase()
+}
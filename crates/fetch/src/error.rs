@@ -0,0 +1,66 @@
+use std::fmt::Display;
+
+/// Errors that can occur while fetching.
+#[derive(Debug)]
+pub enum Error {
+	/// The request failed, or the service returned an error status.
+	Http(Box<ureq::Error>),
+
+	/// The response couldn't be read.
+	Io(std::io::Error),
+
+	/// The response wasn't in the expected format.
+	Json(serde_json::Error),
+
+	/// The endpoint isn't a valid base URL.
+	Url(url::ParseError),
+}
+
+/// Alias for a `Result` with the error type [Error].
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Http(err) => write!(f, "request failed: {err}"),
+			Self::Io(err) => write!(f, "reading response failed: {err}"),
+			Self::Json(err) => write!(f, "unexpected response: {err}"),
+			Self::Url(err) => write!(f, "invalid endpoint: {err}"),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Http(err) => Some(err),
+			Self::Io(err) => Some(err),
+			Self::Json(err) => Some(err),
+			Self::Url(err) => Some(err),
+		}
+	}
+}
+
+impl From<ureq::Error> for Error {
+	fn from(err: ureq::Error) -> Self {
+		Self::Http(Box::new(err))
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(err: std::io::Error) -> Self {
+		Self::Io(err)
+	}
+}
+
+impl From<serde_json::Error> for Error {
+	fn from(err: serde_json::Error) -> Self {
+		Self::Json(err)
+	}
+}
+
+impl From<url::ParseError> for Error {
+	fn from(err: url::ParseError) -> Self {
+		Self::Url(err)
+	}
+}
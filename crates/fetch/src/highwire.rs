@@ -0,0 +1,218 @@
+//! CSL items from Highwire Press (Google Scholar) meta tags.
+//!
+//! Most publisher landing pages carry `<meta name="citation_title" …>` and
+//! similar tags so Google Scholar can index them. This reads those into an
+//! [Item], which covers publishers and repositories that don't register their
+//! metadata with Crossref.
+//!
+//! This module is only available with the `highwire` feature.
+//!
+//! ```
+//! use citeworks_fetch::highwire::parse_html;
+//!
+//! let item = parse_html(r#"
+//!     <html><head>
+//!     <meta name="citation_title" content="A paper">
+//!     <meta name="citation_author" content="Doe, Jane">
+//!     <meta name="citation_journal_title" content="Journal of Things">
+//!     <meta name="citation_publication_date" content="2017/12/18">
+//!     </head></html>
+//! "#, "https://example.com/paper").unwrap();
+//!
+//! assert_eq!(item.author[0].family.as_deref(), Some("Doe"));
+//! assert_eq!(item.container_title.unwrap().to_string(), "Journal of Things");
+//! ```
+
+use citeworks_csl::{
+	dates::{Date, DateMeta, DateParts},
+	items::{ItemType, ItemValue},
+	names::Name,
+	ordinaries::OrdinaryValue,
+	Item,
+};
+use scraper::{Html, Selector};
+
+use crate::{Result, USER_AGENT};
+
+/// A client for fetching landing pages.
+#[derive(Debug, Clone)]
+pub struct Highwire {
+	agent: ureq::Agent,
+}
+
+impl Default for Highwire {
+	fn default() -> Self {
+		Self {
+			agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+		}
+	}
+}
+
+impl Highwire {
+	/// Create a client.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Fetch a landing page and read its citation meta tags.
+	///
+	/// Returns `None` if the page doesn't have a `citation_title`.
+	pub fn fetch(&self, url: &str) -> Result<Option<Item>> {
+		let response = self.agent.get(url).call()?;
+		let final_url = response.get_url().to_string();
+		let html = response.into_string()?;
+		Ok(parse_html(&html, &final_url))
+	}
+}
+
+/// Read the citation meta tags of an HTML page.
+///
+/// The `url` is used as the item's URL if the page doesn't give one, and as its
+/// id if it has no DOI. Returns `None` if there's no `citation_title`.
+pub fn parse_html(html: &str, url: &str) -> Option<Item> {
+	let document = Html::parse_document(html);
+	let selector = Selector::parse("meta[name^='citation_']").expect("valid selector");
+	let tags: Vec<(String, String)> = document
+		.select(&selector)
+		.filter_map(|element| {
+			let name = element.value().attr("name")?;
+			let content = element.value().attr("content")?.trim();
+			if content.is_empty() {
+				None
+			} else {
+				Some((name.to_ascii_lowercase(), content.to_string()))
+			}
+		})
+		.collect();
+
+	let first = |name: &str| {
+		tags.iter()
+			.find(|(tag, _)| tag == name)
+			.map(|(_, content)| content.clone())
+	};
+	let all = |name: &str| {
+		tags.iter()
+			.filter(|(tag, _)| tag == name)
+			.map(|(_, content)| content.clone())
+			.collect::<Vec<_>>()
+	};
+	let string = |name: &str| first(name).map(OrdinaryValue::String);
+
+	let title = first("citation_title")?;
+	let doi = first("citation_doi").map(|doi| {
+		doi.trim_start_matches("doi:")
+			.trim_start_matches("https://doi.org/")
+			.to_string()
+	});
+	let journal = first("citation_journal_title");
+	let conference = first("citation_conference_title");
+	let thesis_institution = first("citation_dissertation_institution");
+	let report_institution = first("citation_technical_report_institution");
+	let isbn = first("citation_isbn");
+
+	let item_type = if journal.is_some() {
+		ItemType::ArticleJournal
+	} else if conference.is_some() {
+		ItemType::PaperConference
+	} else if thesis_institution.is_some() {
+		ItemType::Thesis
+	} else if report_institution.is_some() {
+		ItemType::Report
+	} else if isbn.is_some() {
+		ItemType::Book
+	} else {
+		ItemType::Article
+	};
+
+	let page = match (first("citation_firstpage"), first("citation_lastpage")) {
+		(Some(first), Some(last)) => Some(format!("{first}-{last}")),
+		(first, None) => first,
+		(None, last) => last,
+	};
+
+	let mut item = Item {
+		id: doi.clone().unwrap_or_else(|| url.to_string()),
+		item_type,
+		author: all("citation_author").iter().map(|a| name(a)).collect(),
+		issued: first("citation_publication_date")
+			.or_else(|| first("citation_date"))
+			.or_else(|| first("citation_online_date"))
+			.and_then(|d| date(&d)),
+		title: Some(OrdinaryValue::String(title)),
+		container_title: journal
+			.or(conference)
+			.or_else(|| first("citation_inbook_title"))
+			.map(OrdinaryValue::String),
+		journal_abbrevation: string("citation_journal_abbrev"),
+		volume: string("citation_volume"),
+		issue: string("citation_issue"),
+		page: page.map(OrdinaryValue::String),
+		doi: doi.map(OrdinaryValue::String),
+		issn: string("citation_issn"),
+		url: first("citation_abstract_html_url")
+			.or_else(|| first("citation_fulltext_html_url"))
+			.or_else(|| Some(url.to_string()))
+			.map(OrdinaryValue::String),
+		language: string("citation_language"),
+		abstract_text: string("citation_abstract"),
+		..Default::default()
+	};
+
+	let mut insert = |field: &str, value: Option<String>| {
+		if let Some(value) = value {
+			item.fields.insert(
+				field.into(),
+				ItemValue::Ordinary(OrdinaryValue::String(value)),
+			);
+		}
+	};
+	insert(
+		"publisher",
+		first("citation_publisher")
+			.or(thesis_institution)
+			.or(report_institution),
+	);
+	insert("number", first("citation_technical_report_number"));
+	insert("ISBN", isbn);
+	insert("PMID", first("citation_pmid"));
+	insert("keyword", {
+		let keywords = all("citation_keywords");
+		if keywords.is_empty() {
+			None
+		} else {
+			Some(keywords.join("; "))
+		}
+	});
+
+	Some(item)
+}
+
+/// Parse an author tag, either "Family, Given" or "Given Family".
+fn name(author: &str) -> Name {
+	let (family, given) = match author.split_once(',') {
+		Some((family, given)) => (family.trim(), Some(given.trim())),
+		None => match author.trim().rsplit_once(' ') {
+			Some((given, family)) => (family, Some(given.trim())),
+			None => (author.trim(), None),
+		},
+	};
+
+	Name {
+		family: Some(family.into()),
+		given: given.filter(|g| !g.is_empty()).map(Into::into),
+		..Default::default()
+	}
+}
+
+/// Parse a date like `2017/12/18`, `2017-12-18`, `2017/12`, or `2017`.
+fn date(date: &str) -> Option<Date> {
+	let mut parts = date.trim().split(['/', '-']);
+	let year = parts.next()?.parse().ok()?;
+	let month = parts.next().and_then(|m| m.parse().ok());
+	let day = month.and(parts.next().and_then(|d| d.parse().ok()));
+
+	Some(Date::Single {
+		date: DateParts { year, month, day },
+		meta: DateMeta::default(),
+	})
+}
@@ -0,0 +1,43 @@
+//! Lookups of citation metadata from online services.
+//!
+//! Everything here makes network requests, with [ureq]. Each service has its
+//! own module and client type, which can be pointed at a different endpoint
+//! (e.g. a mirror, or a mock for testing).
+#![warn(clippy::unwrap_used, missing_docs)]
+#![deny(rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use citeworks_convert::csl::bare_doi;
+use url::Url;
+
+pub use error::{Error, Result};
+
+pub mod crates_io;
+pub mod crossref;
+pub mod doi;
+mod error;
+#[cfg(feature = "highwire")]
+pub mod highwire;
+pub mod unpaywall;
+pub mod wikidata;
+
+/// The User-Agent sent with requests, as many services ask for one.
+pub const USER_AGENT: &str = concat!(
+	"citeworks/",
+	env!("CARGO_PKG_VERSION"),
+	" (https://github.com/passcod/citeworks)"
+);
+
+/// The URL of a DOI under an endpoint, after the given path.
+///
+/// The DOI is stripped of any `https://doi.org/` prefix, and added as a
+/// single path segment, so its slashes and other characters are encoded.
+pub(crate) fn doi_url(endpoint: &str, path: &[&str], doi: &str) -> Result<Url> {
+	let mut url = Url::parse(endpoint)?;
+	url.path_segments_mut()
+		.map_err(|_| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+		.pop_if_empty()
+		.extend(path)
+		.push(bare_doi(doi));
+	Ok(url)
+}
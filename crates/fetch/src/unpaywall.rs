@@ -0,0 +1,206 @@
+//! Open access enrichment with [Unpaywall].
+//!
+//! Unpaywall knows where free, legal copies of scholarly works can be found.
+//! This looks up references and items by DOI and records the best open access
+//! URL in their `url`, so reference lists point at copies readers can access.
+//!
+//! Unpaywall asks for an email address with every request.
+//!
+//! ```no_run
+//! use citeworks_fetch::unpaywall::{EnrichOptions, Unpaywall};
+//!
+//! let mut cff = citeworks_cff::from_str(r#"
+//! cff-version: 1.2.0
+//! message: Please cite this software using these metadata.
+//! title: My Research Software
+//! authors:
+//!   - name: The Research Software project
+//! references:
+//!   - type: article
+//!     title: Deep learning
+//!     doi: 10.1038/nature14539
+//!     authors:
+//!       - family-names: LeCun
+//!         given-names: Yann
+//! "#).unwrap();
+//!
+//! let unpaywall = Unpaywall::new("me@example.com");
+//! let changed = unpaywall.enrich_references(&mut cff.references, &EnrichOptions::default())?;
+//! println!("{changed} references now link to open access copies");
+//! # Ok::<(), citeworks_fetch::Error>(())
+//! ```
+//!
+//! [Unpaywall]: https://unpaywall.org
+
+use citeworks_cff::references::Reference;
+use citeworks_csl::{ordinaries::OrdinaryValue, Item};
+use serde::Deserialize;
+use url::Url;
+
+use crate::{Result, USER_AGENT};
+
+/// The public Unpaywall API endpoint.
+pub const ENDPOINT: &str = "https://api.unpaywall.org/v2";
+
+/// A client for the Unpaywall API.
+#[derive(Debug, Clone)]
+pub struct Unpaywall {
+	agent: ureq::Agent,
+	endpoint: String,
+	email: String,
+}
+
+/// Where an open access copy of a work is.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct OaLocation {
+	/// The best URL: the PDF if there's one, or else the landing page.
+	pub url: String,
+
+	/// URL of the landing page of this copy.
+	#[serde(default)]
+	pub url_for_landing_page: Option<String>,
+
+	/// URL of the PDF of this copy.
+	#[serde(default)]
+	pub url_for_pdf: Option<String>,
+
+	/// `publisher` or `repository`.
+	#[serde(default)]
+	pub host_type: Option<String>,
+
+	/// The license of this copy, e.g. `cc-by`.
+	#[serde(default)]
+	pub license: Option<String>,
+
+	/// Which version this is, e.g. `publishedVersion` or `acceptedVersion`.
+	#[serde(default)]
+	pub version: Option<String>,
+}
+
+/// Options for enrichment.
+#[derive(Debug, Clone, Default)]
+pub struct EnrichOptions {
+	/// Replace URLs that are already present.
+	///
+	/// By default, only references and items without a URL are filled in.
+	pub overwrite: bool,
+
+	/// Use the landing page of the copy rather than its PDF.
+	pub prefer_landing_page: bool,
+}
+
+#[derive(Deserialize)]
+struct Response {
+	best_oa_location: Option<OaLocation>,
+}
+
+impl Unpaywall {
+	/// Create a client for the public endpoint.
+	pub fn new(email: impl Into<String>) -> Self {
+		Self::with_endpoint(ENDPOINT, email)
+	}
+
+	/// Create a client for a different endpoint.
+	pub fn with_endpoint(endpoint: impl Into<String>, email: impl Into<String>) -> Self {
+		Self {
+			agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+			endpoint: endpoint.into(),
+			email: email.into(),
+		}
+	}
+
+	/// Find the best open access copy of a work by DOI.
+	///
+	/// Returns `None` if the DOI isn't known to Unpaywall, or if there's no
+	/// open access copy.
+	pub fn best_location(&self, doi: &str) -> Result<Option<OaLocation>> {
+		let url = crate::doi_url(&self.endpoint, &[], doi)?;
+		let response = match self
+			.agent
+			.request_url("GET", &url)
+			.query("email", &self.email)
+			.call()
+		{
+			Ok(response) => response,
+			Err(ureq::Error::Status(404, _)) => return Ok(None),
+			Err(err) => return Err(err.into()),
+		};
+
+		let response: Response = serde_json::from_reader(response.into_reader())?;
+		Ok(response.best_oa_location)
+	}
+
+	fn best_url(&self, doi: &str, options: &EnrichOptions) -> Result<Option<String>> {
+		Ok(self.best_location(doi)?.map(|location| {
+			if options.prefer_landing_page {
+				location.url_for_landing_page.unwrap_or(location.url)
+			} else {
+				location.url_for_pdf.unwrap_or(location.url)
+			}
+		}))
+	}
+
+	/// Record the best open access URL of a reference in its `url`.
+	///
+	/// Returns whether the reference was changed. References without a DOI are
+	/// left alone, as are those with a URL unless [EnrichOptions::overwrite]
+	/// is set.
+	pub fn enrich_reference(
+		&self,
+		reference: &mut Reference,
+		options: &EnrichOptions,
+	) -> Result<bool> {
+		let doi = match &reference.doi {
+			Some(doi) if options.overwrite || reference.url.is_none() => doi,
+			_ => return Ok(false),
+		};
+
+		let url = match self.best_url(doi, options)? {
+			Some(url) => url,
+			None => return Ok(false),
+		};
+
+		match Url::parse(&url) {
+			Ok(url) if reference.url.as_ref() != Some(&url) => {
+				reference.url = Some(url);
+				Ok(true)
+			}
+			_ => Ok(false),
+		}
+	}
+
+	/// Enrich several references, see [Unpaywall::enrich_reference].
+	///
+	/// Returns how many were changed.
+	pub fn enrich_references(
+		&self,
+		references: &mut [Reference],
+		options: &EnrichOptions,
+	) -> Result<usize> {
+		let mut changed = 0;
+		for reference in references {
+			if self.enrich_reference(reference, options)? {
+				changed += 1;
+			}
+		}
+		Ok(changed)
+	}
+
+	/// Record the best open access URL of an item in its `URL`.
+	///
+	/// This behaves as [Unpaywall::enrich_reference].
+	pub fn enrich_item(&self, item: &mut Item, options: &EnrichOptions) -> Result<bool> {
+		let doi = match &item.doi {
+			Some(doi) if options.overwrite || item.url.is_none() => doi.to_string(),
+			_ => return Ok(false),
+		};
+
+		match self.best_url(&doi, options)? {
+			Some(url) if item.url.as_ref().and_then(|u| u.as_str()) != Some(url.as_str()) => {
+				item.url = Some(OrdinaryValue::String(url));
+				Ok(true)
+			}
+			_ => Ok(false),
+		}
+	}
+}
@@ -0,0 +1,164 @@
+//! Wikidata QID lookups.
+//!
+//! This queries the [Wikidata Query Service][wdqs] to find the items for works
+//! by their DOI or ISBN, for people by their ORCID, and for licenses by their
+//! SPDX identifier. Those QIDs can then be used with
+//! [citeworks_convert::wikidata] to export statements.
+//!
+//! ```no_run
+//! use citeworks_fetch::wikidata::Wikidata;
+//!
+//! let wikidata = Wikidata::new();
+//! let qid = wikidata.qid_for_doi("10.1038/NATURE14539")?;
+//! println!("{qid:?}");
+//! # Ok::<(), citeworks_fetch::Error>(())
+//! ```
+//!
+//! [wdqs]: https://query.wikidata.org
+
+use citeworks_convert::csl::bare_doi;
+use serde::Deserialize;
+
+use crate::{Result, USER_AGENT};
+
+pub use citeworks_convert::wikidata::Qid;
+
+/// The public Wikidata Query Service SPARQL endpoint.
+pub const ENDPOINT: &str = "https://query.wikidata.org/sparql";
+
+/// A client for the Wikidata Query Service.
+#[derive(Debug, Clone)]
+pub struct Wikidata {
+	agent: ureq::Agent,
+	endpoint: String,
+}
+
+impl Default for Wikidata {
+	fn default() -> Self {
+		Self::with_endpoint(ENDPOINT)
+	}
+}
+
+impl Wikidata {
+	/// Create a client for the public endpoint.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create a client for a different SPARQL endpoint.
+	pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+		Self {
+			agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+			endpoint: endpoint.into(),
+		}
+	}
+
+	/// Run a SPARQL query which selects `?item`, and return the QIDs found.
+	pub fn query(&self, sparql: &str) -> Result<Vec<Qid>> {
+		let response: SparqlResponse = serde_json::from_reader(
+			self.agent
+				.get(&self.endpoint)
+				.query("query", sparql)
+				.set("Accept", "application/sparql-results+json")
+				.call()?
+				.into_reader(),
+		)?;
+
+		Ok(response
+			.results
+			.bindings
+			.into_iter()
+			.filter_map(|binding| binding.item.value.parse().ok())
+			.collect())
+	}
+
+	fn first(&self, sparql: &str) -> Result<Option<Qid>> {
+		Ok(self.query(sparql)?.into_iter().min())
+	}
+
+	/// Find the item for a work by its DOI (P356).
+	///
+	/// This accepts either the bare DOI or its URL. If there are several items,
+	/// the oldest (lowest) QID is returned.
+	pub fn qid_for_doi(&self, doi: &str) -> Result<Option<Qid>> {
+		// Wikidata stores DOIs uppercased.
+		self.first(&format!(
+			"SELECT ?item WHERE {{ ?item wdt:P356 {} . }}",
+			literal(&bare_doi(doi).to_uppercase())
+		))
+	}
+
+	/// Find the item for a work by its ISBN-13 (P212) or ISBN-10 (P957).
+	///
+	/// Wikidata stores ISBNs with hyphens, so a hyphenated ISBN is more likely
+	/// to be found; the hyphen-less form is tried as well.
+	pub fn qid_for_isbn(&self, isbn: &str) -> Result<Option<Qid>> {
+		let isbn = isbn.trim();
+		let bare: String = isbn
+			.chars()
+			.filter(|c| c.is_ascii_digit() || *c == 'X' || *c == 'x')
+			.collect();
+		self.first(&format!(
+			"SELECT ?item WHERE {{ VALUES ?isbn {{ {} {} }} {{ ?item wdt:P212 ?isbn }} UNION {{ ?item wdt:P957 ?isbn }} }}",
+			literal(isbn),
+			literal(&bare)
+		))
+	}
+
+	/// Find the item for a person by their ORCID iD (P496).
+	///
+	/// This accepts either the bare iD or its URL.
+	pub fn qid_for_orcid(&self, orcid: &str) -> Result<Option<Qid>> {
+		let id = orcid.trim().trim_end_matches('/');
+		let id = id.rsplit('/').next().unwrap_or(id);
+		self.first(&format!(
+			"SELECT ?item WHERE {{ ?item wdt:P496 {} . }}",
+			literal(id)
+		))
+	}
+
+	/// Find the item for a license by its SPDX identifier (P2479).
+	pub fn qid_for_license(&self, spdx: &str) -> Result<Option<Qid>> {
+		self.first(&format!(
+			"SELECT ?item WHERE {{ ?item wdt:P2479 {} . }}",
+			literal(spdx.trim())
+		))
+	}
+}
+
+/// Quote a string as a SPARQL literal.
+fn literal(s: &str) -> String {
+	let mut quoted = String::with_capacity(s.len() + 2);
+	quoted.push('"');
+	for c in s.chars() {
+		match c {
+			'\\' => quoted.push_str("\\\\"),
+			'"' => quoted.push_str("\\\""),
+			'\n' => quoted.push_str("\\n"),
+			'\r' => quoted.push_str("\\r"),
+			c => quoted.push(c),
+		}
+	}
+	quoted.push('"');
+	quoted
+}
+
+#[derive(Deserialize)]
+struct SparqlResponse {
+	results: SparqlResults,
+}
+
+#[derive(Deserialize)]
+struct SparqlResults {
+	bindings: Vec<SparqlBinding>,
+}
+
+#[derive(Deserialize)]
+struct SparqlBinding {
+	item: SparqlValue,
+}
+
+#[derive(Deserialize)]
+struct SparqlValue {
+	value: String,
+}
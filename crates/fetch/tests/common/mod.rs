@@ -0,0 +1,47 @@
+use std::{
+	io::{BufRead, BufReader, Write},
+	net::TcpListener,
+	sync::mpsc,
+	thread,
+};
+
+/// Serve a single request with the given status and JSON body, and send back
+/// the request line that was received.
+pub fn serve_once(status: u16, body: &'static str) -> (String, mpsc::Receiver<String>) {
+	serve(vec![(status, body)])
+}
+
+/// Serve requests in turn with the given statuses and JSON bodies, one per
+/// connection, and send back the request lines that were received.
+pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<String>) {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let endpoint = format!("http://{}", listener.local_addr().unwrap());
+	let (tx, rx) = mpsc::channel();
+
+	thread::spawn(move || {
+		for (status, body) in responses {
+			let (mut stream, _) = listener.accept().unwrap();
+			let mut reader = BufReader::new(stream.try_clone().unwrap());
+			let mut request_line = String::new();
+			reader.read_line(&mut request_line).unwrap();
+			loop {
+				let mut line = String::new();
+				reader.read_line(&mut line).unwrap();
+				if line.trim().is_empty() {
+					break;
+				}
+			}
+
+			write!(
+				stream,
+				"HTTP/1.1 {status} Whatever\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+				body.len()
+			)
+			.unwrap();
+			// The receiver may have been dropped by tests that don't check requests.
+			tx.send(request_line).ok();
+		}
+	});
+
+	(endpoint, rx)
+}
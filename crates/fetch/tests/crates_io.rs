@@ -0,0 +1,163 @@
+use std::str::FromStr;
+
+use citeworks_cff::{
+	names::{EntityName, Name, NameMeta},
+	Date, License,
+};
+use citeworks_fetch::crates_io::CratesIo;
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::{serve, serve_once};
+
+const CRATE: &str = r#"{
+	"crate": {
+		"name": "citeworks-cff",
+		"description": "Serde types for Citation File Format (CFF).\n",
+		"repository": "https://github.com/passcod/citeworks",
+		"homepage": "https://citeworks.example.com",
+		"keywords": ["cff", "citation"],
+		"max_stable_version": "0.1.1",
+		"newest_version": "0.2.0-beta.1"
+	},
+	"versions": [
+		{
+			"num": "0.2.0-beta.1",
+			"license": "Apache-2.0",
+			"created_at": "2022-09-01T10:00:00.000000+00:00",
+			"yanked": false
+		},
+		{
+			"num": "0.1.1",
+			"license": "MIT/Apache-2.0",
+			"created_at": "2022-08-20T04:52:11.314159+00:00",
+			"yanked": false
+		}
+	]
+}"#;
+
+const OWNERS: &str = r#"{
+	"users": [
+		{
+			"id": 1,
+			"login": "passcod",
+			"kind": "user",
+			"name": "Félix Saparelli",
+			"url": "https://github.com/passcod"
+		},
+		{
+			"id": 2,
+			"login": "github:passcod:citeworks",
+			"kind": "team",
+			"name": null,
+			"url": "https://github.com/passcod"
+		}
+	]
+}"#;
+
+#[test]
+fn starter_cff() {
+	let (endpoint, requests) = serve(vec![(200, CRATE), (200, OWNERS)]);
+	let cff = CratesIo::with_endpoint(endpoint)
+		.starter_cff("citeworks-cff")
+		.unwrap()
+		.unwrap();
+
+	assert!(requests
+		.recv()
+		.unwrap()
+		.starts_with("GET /crates/citeworks-cff HTTP"));
+	assert!(requests
+		.recv()
+		.unwrap()
+		.starts_with("GET /crates/citeworks-cff/owners HTTP"));
+
+	assert_eq!(cff.title, "citeworks-cff");
+	assert_eq!(
+		cff.abstract_text.as_deref(),
+		Some("Serde types for Citation File Format (CFF).")
+	);
+	assert_eq!(cff.version.as_deref(), Some("0.1.1"));
+	assert_eq!(
+		cff.date_released,
+		Some(Date::from_str("2022-08-20").unwrap())
+	);
+	assert_eq!(
+		cff.license,
+		Some(License::from_str("MIT OR Apache-2.0").unwrap())
+	);
+	assert_eq!(
+		cff.repository_code.unwrap().as_str(),
+		"https://github.com/passcod/citeworks"
+	);
+	assert_eq!(
+		cff.repository_artifact.unwrap().as_str(),
+		"https://crates.io/crates/citeworks-cff"
+	);
+	assert_eq!(cff.url.unwrap().as_str(), "https://citeworks.example.com/");
+	assert_eq!(cff.keywords, vec!["cff", "citation"]);
+	assert_eq!(
+		cff.authors,
+		vec![
+			Name::Entity(EntityName {
+				name: Some("Félix Saparelli".into()),
+				meta: NameMeta {
+					website: Some("https://github.com/passcod".parse().unwrap()),
+					..Default::default()
+				},
+				..Default::default()
+			}),
+			Name::Entity(EntityName {
+				name: Some("github:passcod:citeworks".into()),
+				meta: NameMeta {
+					website: Some("https://github.com/passcod".parse().unwrap()),
+					..Default::default()
+				},
+				..Default::default()
+			}),
+		]
+	);
+}
+
+#[test]
+fn artifact_repository() {
+	let (endpoint, _) = serve(vec![
+		(
+			200,
+			r#"{
+				"crate": {
+					"name": "thing",
+					"repository": "https://github.com/someone/thing/releases",
+					"newest_version": "1.0.0"
+				},
+				"versions": [{"num": "1.0.0", "created_at": "2023-01-02T00:00:00Z"}]
+			}"#,
+		),
+		(200, r#"{"users": []}"#),
+	]);
+	let cff = CratesIo::with_endpoint(endpoint)
+		.starter_cff("thing")
+		.unwrap()
+		.unwrap();
+
+	assert_eq!(cff.repository_code, None);
+	assert_eq!(
+		cff.repository_artifact.unwrap().as_str(),
+		"https://github.com/someone/thing/releases"
+	);
+	assert_eq!(cff.version.as_deref(), Some("1.0.0"));
+	assert_eq!(cff.license, None);
+	assert!(cff.authors.is_empty());
+}
+
+#[test]
+fn missing() {
+	let (endpoint, _) = serve_once(404, r#"{"errors": [{"detail": "Not Found"}]}"#);
+	assert_eq!(
+		CratesIo::with_endpoint(endpoint)
+			.starter_cff("no-such-crate")
+			.unwrap(),
+		None
+	);
+}
@@ -0,0 +1,74 @@
+use citeworks_csl::dates::DateParts;
+use citeworks_fetch::crossref::Crossref;
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::serve_once;
+
+#[test]
+fn retracted() {
+	let (endpoint, request) = serve_once(
+		200,
+		r#"{"status": "ok", "message-type": "work", "message": {
+			"DOI": "10.1016/s0140-6736(97)11096-0",
+			"updated-by": [
+				{
+					"DOI": "10.1016/s0140-6736(10)60175-4",
+					"type": "correction",
+					"source": "publisher",
+					"updated": {"date-parts": [[2004, 3, 6]]}
+				},
+				{
+					"DOI": "10.1016/s0140-6736(10)60175-4",
+					"type": "retraction",
+					"source": "retraction-watch",
+					"updated": {"date-parts": [[2010, 2, 6]], "timestamp": 1265414400000}
+				}
+			]
+		}}"#,
+	);
+
+	let notice = Crossref::with_endpoint(endpoint)
+		.mailto("me@example.com")
+		.retraction("https://doi.org/10.1016/S0140-6736(97)11096-0")
+		.unwrap()
+		.unwrap();
+	assert_eq!(notice.kind, "retraction");
+	assert_eq!(notice.source.as_deref(), Some("retraction-watch"));
+	assert_eq!(
+		notice.updated,
+		Some(DateParts {
+			year: 2010,
+			month: Some(2),
+			day: Some(6)
+		})
+	);
+
+	let request = request.recv().unwrap();
+	assert!(
+		request.starts_with("GET /works/10.1016%2FS0140-6736(97)11096-0?mailto=me%40example.com "),
+		"{request}"
+	);
+}
+
+#[test]
+fn not_retracted() {
+	let (endpoint, _) = serve_once(
+		200,
+		r#"{"status": "ok", "message-type": "work", "message": {"DOI": "10.1038/nature14539"}}"#,
+	);
+	let notice = Crossref::with_endpoint(endpoint)
+		.retraction("10.1038/nature14539")
+		.unwrap();
+	assert_eq!(notice, None);
+}
+
+#[test]
+fn unknown_doi() {
+	let (endpoint, _) = serve_once(404, r#"{"status": "error"}"#);
+	let updates = Crossref::with_endpoint(endpoint)
+		.updates("10.1234/nope")
+		.unwrap();
+	assert_eq!(updates, None);
+}
@@ -0,0 +1,90 @@
+use citeworks_fetch::doi::{drift, Drift, Resolver};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::serve_once;
+
+const ZENODO: &str = r#"{
+	"type": "software",
+	"id": "https://doi.org/10.5281/zenodo.1234",
+	"author": [{"family": "Doe", "given": "Jane"}, {"literal": "The Research Software project"}],
+	"title": "My research  software",
+	"version": "v1.2.0",
+	"DOI": "10.5281/ZENODO.1234",
+	"publisher": "Zenodo"
+}"#;
+
+fn cff(authors: &str, version: &str) -> citeworks_cff::Cff {
+	citeworks_cff::from_str(&format!(
+		r#"
+cff-version: 1.2.0
+message: Please cite this software using these metadata.
+title: My Research Software
+doi: 10.5281/zenodo.1234
+version: {version}
+authors:
+{authors}
+"#
+	))
+	.unwrap()
+}
+
+#[test]
+fn no_drift() {
+	let (endpoint, request) = serve_once(200, ZENODO);
+	let registered = Resolver::with_endpoint(endpoint)
+		.csl("https://doi.org/10.5281/zenodo.1234")
+		.unwrap()
+		.unwrap();
+
+	let request = request.recv().unwrap();
+	assert!(
+		request.starts_with("GET /10.5281%2Fzenodo.1234 "),
+		"{request}"
+	);
+
+	let cff = cff(
+		"  - family-names: Doe\n    given-names: J.\n  - name: The Research Software project",
+		"1.2.0",
+	);
+	assert_eq!(drift(&cff, &registered), vec![]);
+}
+
+#[test]
+fn drifted() {
+	let (endpoint, _) = serve_once(200, ZENODO);
+	let registered = Resolver::with_endpoint(endpoint)
+		.csl("10.5281/zenodo.1234")
+		.unwrap()
+		.unwrap();
+
+	let cff = cff(
+		"  - family-names: Doe\n  - family-names: Roe\n  - name: The Research Software project",
+		"1.3.0",
+	);
+	assert_eq!(
+		drift(&cff, &registered),
+		vec![
+			Drift {
+				field: "authors",
+				cff: "Doe; Roe; The Research Software project".into(),
+				registered: "Doe; The Research Software project".into(),
+			},
+			Drift {
+				field: "version",
+				cff: "1.3.0".into(),
+				registered: "v1.2.0".into(),
+			},
+		]
+	);
+}
+
+#[test]
+fn unregistered() {
+	let (endpoint, _) = serve_once(404, r#"{"status": "error"}"#);
+	let registered = Resolver::with_endpoint(endpoint)
+		.csl("10.1234/nope")
+		.unwrap();
+	assert_eq!(registered, None);
+}
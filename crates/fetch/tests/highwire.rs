@@ -0,0 +1,89 @@
+use citeworks_fetch::highwire::parse_html;
+
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[test]
+fn journal_article() {
+	let item = parse_html(
+		r#"<!doctype html>
+		<html><head>
+		<meta name="citation_title" content="Deep learning &amp; things">
+		<meta name="citation_author" content="LeCun, Yann">
+		<meta name="citation_author" content="Yoshua Bengio">
+		<meta name="citation_author" content="Hinton">
+		<meta name="citation_journal_title" content="Nature">
+		<meta name="citation_publisher" content="Nature Publishing Group">
+		<meta name="citation_publication_date" content="2015/05/28">
+		<meta name="citation_volume" content="521">
+		<meta name="citation_issue" content="7553">
+		<meta name="citation_firstpage" content="436">
+		<meta name="citation_lastpage" content="444">
+		<meta name="citation_doi" content="doi:10.1038/nature14539">
+		<meta name="citation_issn" content="1476-4687">
+		<meta name="citation_keywords" content="deep learning">
+		<meta name="citation_keywords" content="neural networks">
+		<meta name="citation_pdf_url" content="">
+		<meta name="description" content="not a citation tag">
+		</head><body></body></html>"#,
+		"https://www.nature.com/articles/nature14539",
+	)
+	.unwrap();
+
+	assert_eq!(
+		serde_json::to_value(&item).unwrap(),
+		json!({
+			"id": "10.1038/nature14539",
+			"type": "article-journal",
+			"author": [
+				{"family": "LeCun", "given": "Yann"},
+				{"family": "Bengio", "given": "Yoshua"},
+				{"family": "Hinton"}
+			],
+			"issued": {"date-parts": [[2015, 5, 28]]},
+			"ISSN": "1476-4687",
+			"DOI": "10.1038/nature14539",
+			"URL": "https://www.nature.com/articles/nature14539",
+			"title": "Deep learning & things",
+			"container-title": "Nature",
+			"volume": "521",
+			"issue": "7553",
+			"page": "436-444",
+			"publisher": "Nature Publishing Group",
+			"keyword": "deep learning; neural networks"
+		})
+	);
+}
+
+#[test]
+fn thesis() {
+	let item = parse_html(
+		r#"<meta name="citation_title" content="On things">
+		<meta name="citation_author" content="Doe, Jane">
+		<meta name="citation_dissertation_institution" content="University of Places">
+		<meta name="citation_date" content="2019">"#,
+		"https://repository.example.edu/123",
+	)
+	.unwrap();
+
+	assert_eq!(item.id, "https://repository.example.edu/123");
+	assert_eq!(
+		serde_json::to_value(&item).unwrap()["type"],
+		json!("thesis")
+	);
+	assert_eq!(
+		serde_json::to_value(&item).unwrap()["publisher"],
+		json!("University of Places")
+	);
+}
+
+#[test]
+fn no_metadata() {
+	assert_eq!(
+		parse_html(
+			"<html><head><title>Hi</title></head></html>",
+			"https://example.com"
+		),
+		None
+	);
+}
@@ -0,0 +1,149 @@
+use citeworks_cff::references::Reference;
+use citeworks_csl::{ordinaries::OrdinaryValue, Item};
+use citeworks_fetch::unpaywall::{EnrichOptions, Unpaywall};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::serve_once;
+
+const OPEN: &str = r#"{
+	"doi": "10.1038/nature14539",
+	"is_oa": true,
+	"best_oa_location": {
+		"url": "https://europepmc.org/articles/pmc123/pdf",
+		"url_for_pdf": "https://europepmc.org/articles/pmc123/pdf",
+		"url_for_landing_page": "https://europepmc.org/articles/pmc123",
+		"host_type": "repository",
+		"license": "cc-by",
+		"version": "acceptedVersion"
+	}
+}"#;
+
+fn reference(url: Option<&str>) -> Reference {
+	Reference {
+		doi: Some("10.1038/nature14539".into()),
+		url: url.map(|url| url.parse().unwrap()),
+		..Default::default()
+	}
+}
+
+#[test]
+fn enrich_reference() {
+	let (endpoint, request) = serve_once(200, OPEN);
+	let mut reference = reference(None);
+
+	let changed = Unpaywall::with_endpoint(endpoint, "me@example.com")
+		.enrich_reference(&mut reference, &EnrichOptions::default())
+		.unwrap();
+	assert!(changed);
+	assert_eq!(
+		reference.url.unwrap().as_str(),
+		"https://europepmc.org/articles/pmc123/pdf"
+	);
+
+	let request = request.recv().unwrap();
+	assert!(
+		request.starts_with("GET /10.1038%2Fnature14539?email=me%40example.com "),
+		"{request}"
+	);
+}
+
+#[test]
+fn enrich_item_landing_page() {
+	let (endpoint, _) = serve_once(200, OPEN);
+	let mut item = Item {
+		id: "lecun2015".into(),
+		doi: Some(OrdinaryValue::String("10.1038/nature14539".into())),
+		..Default::default()
+	};
+
+	let changed = Unpaywall::with_endpoint(endpoint, "me@example.com")
+		.enrich_item(
+			&mut item,
+			&EnrichOptions {
+				prefer_landing_page: true,
+				..Default::default()
+			},
+		)
+		.unwrap();
+	assert!(changed);
+	assert_eq!(
+		item.url,
+		Some(OrdinaryValue::String(
+			"https://europepmc.org/articles/pmc123".into()
+		))
+	);
+}
+
+#[test]
+fn keeps_existing_url() {
+	// No server: this must not make a request at all.
+	let unpaywall = Unpaywall::with_endpoint("http://127.0.0.1:9", "me@example.com");
+	let mut reference = reference(Some("https://example.com/paper"));
+
+	let changed = unpaywall
+		.enrich_reference(&mut reference, &EnrichOptions::default())
+		.unwrap();
+	assert!(!changed);
+	assert_eq!(reference.url.unwrap().as_str(), "https://example.com/paper");
+}
+
+#[test]
+fn overwrite_existing_url() {
+	let (endpoint, _) = serve_once(200, OPEN);
+	let mut reference = reference(Some("https://example.com/paper"));
+
+	let changed = Unpaywall::with_endpoint(endpoint, "me@example.com")
+		.enrich_reference(
+			&mut reference,
+			&EnrichOptions {
+				overwrite: true,
+				..Default::default()
+			},
+		)
+		.unwrap();
+	assert!(changed);
+	assert_eq!(
+		reference.url.unwrap().as_str(),
+		"https://europepmc.org/articles/pmc123/pdf"
+	);
+}
+
+#[test]
+fn closed_access() {
+	let (endpoint, _) = serve_once(
+		200,
+		r#"{"doi": "10.1038/nature14539", "is_oa": false, "best_oa_location": null}"#,
+	);
+	let mut reference = reference(None);
+
+	let changed = Unpaywall::with_endpoint(endpoint, "me@example.com")
+		.enrich_reference(&mut reference, &EnrichOptions::default())
+		.unwrap();
+	assert!(!changed);
+	assert_eq!(reference.url, None);
+}
+
+#[test]
+fn unknown_doi() {
+	let (endpoint, _) = serve_once(404, r#"{"error": true, "message": "not found"}"#);
+	let location = Unpaywall::with_endpoint(endpoint, "me@example.com")
+		.best_location("10.1234/nope")
+		.unwrap();
+	assert_eq!(location, None);
+}
+
+#[test]
+fn encodes_doi() {
+	let (endpoint, request) = serve_once(404, r#"{"error": true, "message": "not found"}"#);
+	Unpaywall::with_endpoint(format!("{endpoint}/v2/"), "me@example.com")
+		.best_location("https://doi.org/10.1002/(SICI)1097-4571 #1?")
+		.unwrap();
+
+	let request = request.recv().unwrap();
+	assert!(
+		request.starts_with("GET /v2/10.1002%2F(SICI)1097-4571%20%231%3F?email="),
+		"{request}"
+	);
+}
@@ -0,0 +1,80 @@
+use citeworks_fetch::wikidata::{Qid, Wikidata};
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::serve_once;
+
+fn sparql(endpoint: String) -> Wikidata {
+	Wikidata::with_endpoint(format!("{endpoint}/sparql"))
+}
+
+#[test]
+fn doi_lookup() {
+	let (endpoint, request) = serve_once(
+		200,
+		r#"{"head":{"vars":["item"]},"results":{"bindings":[
+			{"item":{"type":"uri","value":"http://www.wikidata.org/entity/Q28090716"}},
+			{"item":{"type":"uri","value":"http://www.wikidata.org/entity/Q1234"}}
+		]}}"#,
+	);
+
+	let qid = sparql(endpoint).qid_for_doi("10.1038/nature14539").unwrap();
+	assert_eq!(qid, Some(Qid(1234)));
+
+	let request = request.recv().unwrap();
+	assert!(request.starts_with("GET /sparql?query="), "{request}");
+	assert!(request.contains("P356"), "{request}");
+	assert!(request.contains("10.1038%2FNATURE14539"), "{request}");
+}
+
+#[test]
+fn doi_url_lookup() {
+	let (endpoint, request) = serve_once(
+		200,
+		r#"{"head":{"vars":["item"]},"results":{"bindings":[
+			{"item":{"type":"uri","value":"http://www.wikidata.org/entity/Q28090716"}}
+		]}}"#,
+	);
+
+	let qid = sparql(endpoint)
+		.qid_for_doi("https://doi.org/10.1038/nature14539")
+		.unwrap();
+	assert_eq!(qid, Some(Qid(28090716)));
+
+	let request = request.recv().unwrap();
+	assert!(request.contains("%2210.1038%2FNATURE14539%22"), "{request}");
+}
+
+#[test]
+fn not_found() {
+	let (endpoint, _) = serve_once(
+		200,
+		r#"{"head":{"vars":["item"]},"results":{"bindings":[]}}"#,
+	);
+	let qid = sparql(endpoint).qid_for_license("MIT").unwrap();
+	assert_eq!(qid, None);
+}
+
+#[test]
+fn bad_response() {
+	let (endpoint, _) = serve_once(200, "<html>oops</html>");
+	assert!(sparql(endpoint)
+		.qid_for_orcid("https://orcid.org/0000-0003-4925-7248")
+		.is_err());
+}
+
+#[test]
+fn escapes_literals() {
+	let (endpoint, request) = serve_once(
+		200,
+		r#"{"head":{"vars":["item"]},"results":{"bindings":[]}}"#,
+	);
+	sparql(endpoint).qid_for_license("MIT\r\n\"} \\").unwrap();
+
+	let request = request.recv().unwrap();
+	assert!(
+		request.contains("%22MIT%5Cr%5Cn%5C%22%7D+%5C%5C%22"),
+		"{request}"
+	);
+}
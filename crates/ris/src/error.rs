@@ -0,0 +1,41 @@
+use std::fmt::Display;
+
+/// An error in the syntax of an RIS file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Error {
+	/// What went wrong.
+	pub message: String,
+
+	/// The line where it went wrong, starting at 1.
+	pub line: usize,
+
+	/// The column where it went wrong, in characters, starting at 1.
+	pub column: usize,
+}
+
+/// Alias for a `Result` with the error type [Error].
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+	pub(crate) fn at(source: &str, offset: usize, message: impl Into<String>) -> Self {
+		let before = &source[..offset];
+		let line_start = before.rfind('\n').map_or(0, |n| n + 1);
+		Self {
+			message: message.into(),
+			line: before.matches('\n').count() + 1,
+			column: before[line_start..].chars().count() + 1,
+		}
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{} at line {} column {}",
+			self.message, self.line, self.column
+		)
+	}
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,61 @@
+//! RIS types, parsing, and writing.
+//!
+//! RIS is the tagged format exported by EndNote, Mendeley, Zotero, and most
+//! databases: each record starts with a `TY` (type) line and ends with an `ER`
+//! line, and has one line per value, like `AU  - Doe, Jane`. Tags can repeat,
+//! e.g. one `AU` per author, so records are kept as the list of their fields,
+//! in order.
+//!
+//! Parsing accepts the quirks of common exporters: byte order marks, CRLF line
+//! endings, missing or extra spaces around the `-`, values continued on lines
+//! without a tag, lowercase tags, and a missing `ER` at the end of the file.
+//!
+//! ```
+//! let records = citeworks_ris::from_str("\
+//! TY  - JOUR
+//! AU  - Druskat, Stephan
+//! TI  - Citation File Format
+//! PY  - 2017/12/18/
+//! KW  - citation
+//! KW  - software
+//! ER  -
+//! ").unwrap();
+//!
+//! assert_eq!(records[0].record_type, "JOUR");
+//! assert_eq!(records[0].get("TI"), Some("Citation File Format"));
+//! assert_eq!(records[0].get_all("KW").collect::<Vec<_>>(), ["citation", "software"]);
+//! ```
+#![warn(clippy::unwrap_used, missing_docs)]
+#![deny(rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use std::io::Write;
+
+pub use error::{Error, Result};
+pub use records::{Field, Record};
+
+mod error;
+mod parser;
+pub mod records;
+
+/// Parse the records of an RIS file.
+pub fn from_str(s: &str) -> Result<Vec<Record>> {
+	parser::parse(s)
+}
+
+/// Write records as RIS.
+pub fn to_string(records: &[Record]) -> String {
+	records
+		.iter()
+		.map(ToString::to_string)
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+/// Write records as RIS into the IO stream.
+pub fn to_writer<W>(mut writer: W, records: &[Record]) -> std::io::Result<()>
+where
+	W: Write,
+{
+	writer.write_all(to_string(records).as_bytes())
+}
@@ -0,0 +1,67 @@
+use crate::{Error, Record, Result};
+
+pub(crate) fn parse(source: &str) -> Result<Vec<Record>> {
+	let mut records = Vec::new();
+	let mut current: Option<Record> = None;
+
+	let mut offset = 0;
+	for line in source.split_inclusive('\n') {
+		let start = offset;
+		offset += line.len();
+		let line = line.trim_start_matches('\u{feff}').trim_end();
+
+		match tag_line(line)
+			.as_ref()
+			.map(|(tag, value)| (tag.as_str(), *value))
+		{
+			Some(("TY", value)) => {
+				// Some exporters leave out the ER between records.
+				records.extend(current.replace(Record::new(value.to_ascii_uppercase())));
+			}
+			Some(("ER", _)) => records.extend(current.take()),
+			Some((tag, value)) => match &mut current {
+				Some(record) => record.push(tag, value),
+				None => return Err(Error::at(source, start, "expected a TY line")),
+			},
+			None if line.trim().is_empty() => {}
+			None => match current.as_mut().and_then(|record| record.fields.last_mut()) {
+				Some(field) => {
+					field.value.push('\n');
+					field.value.push_str(line.trim());
+				}
+				None => {
+					return Err(Error::at(
+						source,
+						start,
+						if current.is_some() {
+							"expected a tag"
+						} else {
+							"expected a TY line"
+						},
+					))
+				}
+			},
+		}
+	}
+
+	records.extend(current);
+	Ok(records)
+}
+
+/// Split a line like `AU  - Doe, Jane` into its uppercased tag and value.
+///
+/// The spec says two spaces before the `-` and one after, but exporters vary.
+fn tag_line(line: &str) -> Option<(String, &str)> {
+	let tag = line.get(..2)?;
+	let mut chars = tag.chars();
+	if !(chars.next()?.is_ascii_alphabetic() && chars.next()?.is_ascii_alphanumeric()) {
+		return None;
+	}
+
+	let rest = line[2..].trim_start_matches(' ').strip_prefix('-')?;
+	if !(rest.is_empty() || rest.starts_with(' ')) {
+		return None;
+	}
+
+	Some((tag.to_ascii_uppercase(), rest.trim()))
+}
@@ -0,0 +1,71 @@
+//! Types and utilities for records.
+
+use std::fmt::Display;
+
+/// An RIS record.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Record {
+	/// The type of the record, from its `TY` line, e.g. `JOUR`.
+	pub record_type: String,
+
+	/// The fields of the record, in order, without `TY` and `ER`.
+	pub fields: Vec<Field>,
+}
+
+/// A line of an RIS record.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Field {
+	/// The tag, two uppercase characters, e.g. `AU`.
+	pub tag: String,
+
+	/// The value, which may contain newlines.
+	pub value: String,
+}
+
+impl Record {
+	/// A record of a type, without fields.
+	pub fn new(record_type: impl Into<String>) -> Self {
+		Self {
+			record_type: record_type.into(),
+			fields: Vec::new(),
+		}
+	}
+
+	/// The first value of a tag.
+	pub fn get(&self, tag: &str) -> Option<&str> {
+		self.fields
+			.iter()
+			.find(|field| field.tag == tag && !field.value.is_empty())
+			.map(|field| field.value.as_str())
+	}
+
+	/// All values of a tag, in order.
+	pub fn get_all<'r>(&'r self, tag: &'r str) -> impl Iterator<Item = &'r str> + 'r {
+		self.fields
+			.iter()
+			.filter(move |field| field.tag == tag)
+			.map(|field| field.value.as_str())
+			.filter(|value| !value.is_empty())
+	}
+
+	/// Add a value to the end of the record.
+	pub fn push(&mut self, tag: impl Into<String>, value: impl Into<String>) {
+		self.fields.push(Field {
+			tag: tag.into(),
+			value: value.into(),
+		});
+	}
+}
+
+impl Display for Record {
+	/// Writes the record with `TY` first and `ER` last, and a line per value.
+	///
+	/// Newlines in values are written as continuation lines.
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "TY  - {}", self.record_type)?;
+		for field in &self.fields {
+			writeln!(f, "{}  - {}", field.tag, field.value)?;
+		}
+		writeln!(f, "ER  - ")
+	}
+}
@@ -0,0 +1,56 @@
+use citeworks_ris::{from_str, to_string, Record};
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn exporter_quirks() {
+	let records = from_str(
+		"\u{feff}TY  - JOUR\r
+AU  - Vaidya, Nina\r
+au - Solgaard, Olav\r
+AB  - Complex optical devices were 3D printed\r
+using commercial technology.\r
+ER  -\r
+\r
+TY  - BOOK\r
+TI  -   A book   \r
+TY  - COMP\r
+TI  - Some software\r
+",
+	)
+	.unwrap();
+
+	assert_eq!(records.len(), 3);
+	assert_eq!(
+		records[0].get_all("AU").collect::<Vec<_>>(),
+		["Vaidya, Nina", "Solgaard, Olav"]
+	);
+	assert_eq!(
+		records[0].get("AB"),
+		Some("Complex optical devices were 3D printed\nusing commercial technology.")
+	);
+	assert_eq!(records[1].get("TI"), Some("A book"));
+	assert_eq!(records[2].record_type, "COMP");
+}
+
+#[test]
+fn errors() {
+	let err = from_str("\nAU  - Doe, Jane\n").unwrap_err();
+	assert_eq!((err.line, err.message.as_str()), (2, "expected a TY line"));
+
+	let err = from_str("[{\"id\": \"not-ris\"}]").unwrap_err();
+	assert_eq!(err.line, 1);
+}
+
+#[test]
+fn roundtrip() {
+	let mut record = Record::new("JOUR");
+	record.push("AU", "Doe, Jane");
+	record.push("AB", "Two\nlines");
+	record.push("KW", "one");
+	record.push("KW", "two");
+
+	let written = to_string(&[record.clone(), record.clone()]);
+	assert!(written.starts_with("TY  - JOUR\nAU  - Doe, Jane\nAB  - Two\nlines\n"));
+	assert_eq!(from_str(&written).unwrap(), vec![record.clone(), record]);
+}